@@ -1,5 +1,12 @@
+use reqwest::Method;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::binance_rest::SymbolFilters;
+use crate::http_retry::{self, RetryConfig};
+use crate::rate_limiter::RateLimiter;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BinanceOrderSide {
@@ -7,6 +14,7 @@ pub enum BinanceOrderSide {
     SELL,
 }
 
+#[allow(non_camel_case_types)]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BinanceOrderType {
     LIMIT,
@@ -41,6 +49,7 @@ pub struct BinanceOrderRequest {
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct BinanceOrderResponse {
     pub symbol: String,
     pub order_id: u64,
@@ -53,11 +62,13 @@ pub struct BinanceOrderResponse {
     pub cummulative_quote_qty: String,
     pub status: BinanceOrderStatus,
     pub time_in_force: String,
+    #[serde(rename = "type")]
     pub order_type: String,
     pub side: String,
     pub fills: Option<Vec<BinanceFill>>,
 }
 
+#[allow(non_camel_case_types)]
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 pub enum BinanceOrderStatus {
     NEW,
@@ -69,7 +80,112 @@ pub enum BinanceOrderStatus {
     EXPIRED,
 }
 
+impl BinanceOrderStatus {
+    /// Status text used for order history rows.
+    pub fn label(&self) -> &'static str {
+        match self {
+            BinanceOrderStatus::NEW => "Pending",
+            BinanceOrderStatus::PARTIALLY_FILLED => "Partially Filled",
+            BinanceOrderStatus::FILLED => "Filled",
+            BinanceOrderStatus::CANCELED => "Cancelled",
+            BinanceOrderStatus::PENDING_CANCEL => "Pending Cancel",
+            BinanceOrderStatus::REJECTED => "Rejected",
+            BinanceOrderStatus::EXPIRED => "Expired",
+        }
+    }
+}
+
+/// Identifies an existing order either by exchange id or by the client id it
+/// was submitted with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinanceOrderRef {
+    OrderId(u64),
+    ClientOrderId(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceCancelResponse {
+    pub symbol: String,
+    pub orig_client_order_id: String,
+    pub order_id: u64,
+    pub order_list_id: Option<i64>,
+    pub client_order_id: String,
+    pub price: String,
+    pub orig_qty: String,
+    pub executed_qty: String,
+    pub cummulative_quote_qty: String,
+    pub status: BinanceOrderStatus,
+    pub time_in_force: String,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    pub side: String,
+}
+
+/// An order as returned by the `GET /api/v3/order` and `openOrders` queries.
 #[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceOrderInfo {
+    pub symbol: String,
+    pub order_id: u64,
+    pub order_list_id: Option<i64>,
+    pub client_order_id: String,
+    pub price: String,
+    pub orig_qty: String,
+    pub executed_qty: String,
+    pub cummulative_quote_qty: String,
+    pub status: BinanceOrderStatus,
+    pub time_in_force: String,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    pub side: String,
+    pub stop_price: Option<String>,
+    pub time: u64,
+    pub update_time: u64,
+    pub is_working: bool,
+}
+
+impl From<BinanceOrderInfo> for BinanceOrderResponse {
+    /// The order as found by a status query after a placement whose outcome
+    /// was unknown. Fills are not part of the query response.
+    fn from(info: BinanceOrderInfo) -> Self {
+        Self {
+            symbol: info.symbol,
+            order_id: info.order_id,
+            order_list_id: info.order_list_id,
+            client_order_id: info.client_order_id,
+            transact_time: info.update_time,
+            price: info.price,
+            orig_qty: info.orig_qty,
+            executed_qty: info.executed_qty,
+            cummulative_quote_qty: info.cummulative_quote_qty,
+            status: info.status,
+            time_in_force: info.time_in_force,
+            order_type: info.order_type,
+            side: info.side,
+            fills: None,
+        }
+    }
+}
+
+/// `GET /api/v3/account`, trimmed to the fields the TUI uses.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceAccountInfo {
+    pub can_trade: bool,
+    pub update_time: u64,
+    pub balances: Vec<BinanceBalance>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BinanceBalance {
+    pub asset: String,
+    pub free: String,
+    pub locked: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct BinanceFill {
     pub price: String,
     pub qty: String,
@@ -78,27 +194,305 @@ pub struct BinanceFill {
     pub trade_id: u64,
 }
 
+#[derive(Debug)]
+pub enum BinanceError {
+    Http(reqwest::Error),
+    Api { code: i64, msg: String },
+    /// The order id does not exist (`-2013`) or could not be cancelled because
+    /// Binance does not know it (`-2011 Unknown order sent.`).
+    UnknownOrder { code: i64, msg: String },
+    Status { status: u16, body: String },
+    Parse(String),
+    /// Sending now would exceed the client-side rate limit.
+    RateLimited { retry_after: Duration },
+}
+
+impl BinanceError {
+    fn from_api(code: i64, msg: String) -> Self {
+        if code == -2013 || (code == -2011 && msg.starts_with("Unknown order")) {
+            BinanceError::UnknownOrder { code, msg }
+        } else {
+            BinanceError::Api { code, msg }
+        }
+    }
+
+    /// Whether a failed placement may still have reached the matching engine:
+    /// the connection dropped, the gateway failed, or Binance itself reports
+    /// the execution status as unknown (`-1006`, `-1007`).
+    pub fn outcome_unknown(&self) -> bool {
+        match self {
+            BinanceError::Http(_) => true,
+            BinanceError::Status { status, .. } => *status >= 500,
+            BinanceError::Api { code, .. } => *code == -1006 || *code == -1007,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for BinanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinanceError::Http(e) => write!(f, "HTTP error: {}", e),
+            BinanceError::Api { code, msg } => write!(f, "Binance error {}: {}", code, msg),
+            BinanceError::UnknownOrder { code, msg } => write!(f, "Unknown order ({}): {}", code, msg),
+            BinanceError::Status { status, body } => write!(f, "HTTP {}: {}", status, body),
+            BinanceError::Parse(msg) => write!(f, "Failed to parse response: {}", msg),
+            BinanceError::RateLimited { retry_after } => {
+                write!(f, "Rate limit reached, retry in {:.1}s", retry_after.as_secs_f64())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BinanceError {}
+
+impl From<reqwest::Error> for BinanceError {
+    fn from(error: reqwest::Error) -> Self {
+        BinanceError::Http(error)
+    }
+}
+
+/// The first exchange filter an order breaks, found before it is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterViolation {
+    PriceBelowMin { price: f64, min_price: f64 },
+    PriceAboveMax { price: f64, max_price: f64 },
+    PriceOffTick { price: f64, tick_size: f64 },
+    QuantityBelowMin { quantity: f64, min_qty: f64 },
+    QuantityAboveMax { quantity: f64, max_qty: f64 },
+    QuantityOffStep { quantity: f64, step_size: f64 },
+    BelowMinNotional { notional: f64, min_notional: f64 },
+}
+
+impl fmt::Display for FilterViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterViolation::PriceBelowMin { price, min_price } => write!(f, "price {} is below the minimum {}", price, min_price),
+            FilterViolation::PriceAboveMax { price, max_price } => write!(f, "price {} is above the maximum {}", price, max_price),
+            FilterViolation::PriceOffTick { price, tick_size } => write!(f, "price {} is not a multiple of the tick size {}", price, tick_size),
+            FilterViolation::QuantityBelowMin { quantity, min_qty } => write!(f, "quantity {} is below the minimum {}", quantity, min_qty),
+            FilterViolation::QuantityAboveMax { quantity, max_qty } => write!(f, "quantity {} is above the maximum {}", quantity, max_qty),
+            FilterViolation::QuantityOffStep { quantity, step_size } => write!(f, "quantity {} is not a multiple of the step size {}", quantity, step_size),
+            FilterViolation::BelowMinNotional { notional, min_notional } => write!(f, "notional {} is below the minimum {}", notional, min_notional),
+        }
+    }
+}
+
+impl std::error::Error for FilterViolation {}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListenKeyResponse {
+    listen_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceApiError {
+    code: i64,
+    msg: String,
+}
+
+#[derive(Clone)]
 pub struct BinanceOrderClient {
     api_key: String,
     secret_key: String,
     base_url: String,
     recv_window: u64,
+    http: reqwest::Client,
+    rate_limiter: Arc<RateLimiter>,
+    retry: RetryConfig,
 }
 
 impl BinanceOrderClient {
     pub fn new(api_key: String, secret_key: String, testnet: bool) -> Self {
         let base_url = if testnet {
-            "https://testnet.binancefuture.com".to_string()
+            "https://testnet.binance.vision"
         } else {
-            "https://fapi.binance.com".to_string()
+            "https://api.binance.com"
         };
 
+        Self::with_base_url(api_key, secret_key, base_url)
+    }
+
+    pub fn with_base_url(api_key: String, secret_key: String, base_url: &str) -> Self {
         Self {
             api_key,
             secret_key,
-            base_url,
+            base_url: base_url.trim_end_matches('/').to_string(),
             recv_window: 5000,
+            http: reqwest::Client::new(),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Shares `rate_limiter` with other clients using the same API host.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
+    }
+
+    /// Whether this client talks to the spot testnet rather than production.
+    pub fn is_testnet(&self) -> bool {
+        self.base_url.contains("testnet")
+    }
+
+    /// Signs and submits `order` to `POST /api/v3/order`.
+    ///
+    /// The POST is never replayed blindly. Orders without a client id get one,
+    /// and when a failure leaves the outcome unknown the order is looked up by
+    /// that id: if Binance has it, that order is returned, and only if Binance
+    /// reports it unknown is it submitted again.
+    pub async fn place_order(&self, order: &BinanceOrderRequest) -> Result<BinanceOrderResponse, BinanceError> {
+        let mut order = order.clone();
+        let client_order_id = order.new_client_order_id.get_or_insert_with(new_client_order_id).clone();
+
+        let mut attempt = 1;
+        loop {
+            self.rate_limiter
+                .acquire_order(1)
+                .await
+                .map_err(|retry_after| BinanceError::RateLimited { retry_after })?;
+            let error = match self.dispatch(Method::POST, "/api/v3/order", self.signed_query_string(&order), None).await {
+                Ok(body) => return parse_response(&body),
+                Err(error) => error,
+            };
+            if !error.outcome_unknown() || attempt >= self.retry.max_attempts {
+                return Err(error);
+            }
+
+            tokio::time::sleep(self.retry.backoff(attempt)).await;
+            match self.get_order_by_client_id(&order.symbol, &client_order_id).await {
+                Ok(landed) => return Ok(landed.into()),
+                Err(BinanceError::UnknownOrder { .. }) => {}
+                // Still unknown; resubmitting could place the order twice
+                Err(_) => return Err(error),
+            }
+            order.timestamp = chrono::Utc::now().timestamp_millis() as u64;
+            attempt += 1;
+        }
+    }
+
+    /// Validates `order` against `POST /api/v3/order/test`. Binance checks the
+    /// signature and parameters but never sends the order to the matching engine,
+    /// so this is a safe way to verify API credentials.
+    pub async fn place_order_test(&self, order: &BinanceOrderRequest) -> Result<(), BinanceError> {
+        self.send(Method::POST, "/api/v3/order/test", self.signed_query_string(order), 1).await?;
+        Ok(())
+    }
+
+    pub async fn cancel_order(&self, symbol: &str, order: &BinanceOrderRef) -> Result<BinanceCancelResponse, BinanceError> {
+        let query_string = self.signed_params(vec![("symbol", symbol.to_string()), order_ref_param(order)]);
+        let body = self.send(Method::DELETE, "/api/v3/order", query_string, 1).await?;
+        parse_response(&body)
+    }
+
+    pub async fn cancel_all_open_orders(&self, symbol: &str) -> Result<Vec<BinanceCancelResponse>, BinanceError> {
+        let query_string = self.signed_params(vec![("symbol", symbol.to_string())]);
+        let body = self.send(Method::DELETE, "/api/v3/openOrders", query_string, 1).await?;
+        parse_response(&body)
+    }
+
+    pub async fn get_order(&self, symbol: &str, order_id: u64) -> Result<BinanceOrderInfo, BinanceError> {
+        let query_string = self.signed_params(vec![("symbol", symbol.to_string()), ("orderId", order_id.to_string())]);
+        let body = self.send(Method::GET, "/api/v3/order", query_string, 4).await?;
+        parse_response(&body)
+    }
+
+    pub async fn get_order_by_client_id(&self, symbol: &str, client_order_id: &str) -> Result<BinanceOrderInfo, BinanceError> {
+        let query_string = self.signed_params(vec![
+            ("symbol", symbol.to_string()),
+            ("origClientOrderId", client_order_id.to_string()),
+        ]);
+        let body = self.send(Method::GET, "/api/v3/order", query_string, 4).await?;
+        parse_response(&body)
+    }
+
+    pub async fn get_open_orders(&self, symbol: &str) -> Result<Vec<BinanceOrderInfo>, BinanceError> {
+        let query_string = self.signed_params(vec![("symbol", symbol.to_string())]);
+        let body = self.send(Method::GET, "/api/v3/openOrders", query_string, 6).await?;
+        parse_response(&body)
+    }
+
+    pub async fn get_account(&self) -> Result<BinanceAccountInfo, BinanceError> {
+        let query_string = self.signed_params(Vec::new());
+        let body = self.send(Method::GET, "/api/v3/account", query_string, 20).await?;
+        parse_response(&body)
+    }
+
+    /// Creates a user data stream listenKey. Only the API key is required.
+    pub async fn create_listen_key(&self) -> Result<String, BinanceError> {
+        let body = self.send(Method::POST, "/api/v3/userDataStream", String::new(), 2).await?;
+        let response: ListenKeyResponse = parse_response(&body)?;
+        Ok(response.listen_key)
+    }
+
+    /// Extends the listenKey's validity by 60 minutes; Binance recommends
+    /// calling this every 30 minutes.
+    pub async fn keepalive_listen_key(&self, listen_key: &str) -> Result<(), BinanceError> {
+        self.send(Method::PUT, "/api/v3/userDataStream", format!("listenKey={}", listen_key), 2).await?;
+        Ok(())
+    }
+
+    pub async fn close_listen_key(&self, listen_key: &str) -> Result<(), BinanceError> {
+        self.send(Method::DELETE, "/api/v3/userDataStream", format!("listenKey={}", listen_key), 2).await?;
+        Ok(())
+    }
+
+    /// Waits for `weight` of rate limit budget before every attempt, then
+    /// sends the request.
+    async fn send(&self, method: Method, path: &str, query: String, weight: u32) -> Result<String, BinanceError> {
+        self.dispatch(method, path, query, Some(weight)).await
+    }
+
+    /// Sends one request; idempotent ones are retried on transient failures.
+    /// `weight`, when given, is acquired ahead of each attempt; `place_order`
+    /// passes none because it acquires its order budget itself.
+    async fn dispatch(&self, method: Method, path: &str, query: String, weight: Option<u32>) -> Result<String, BinanceError> {
+        let url = if query.is_empty() {
+            format!("{}{}", self.base_url, path)
+        } else {
+            format!("{}{}?{}", self.base_url, path, query)
+        };
+        let retry = if http_retry::is_idempotent(&method) { self.retry } else { RetryConfig::none() };
+        let acquire = || async move {
+            match weight {
+                Some(weight) => self
+                    .rate_limiter
+                    .acquire(weight)
+                    .await
+                    .map_err(|retry_after| BinanceError::RateLimited { retry_after }),
+                None => Ok(()),
+            }
+        };
+        let response = http_retry::send_with_retry_gated(&retry, acquire, || {
+            self.http
+                .request(method.clone(), &url)
+                .header("X-MBX-APIKEY", &self.api_key)
+        })
+        .await?;
+        self.rate_limiter.sync_from_headers(response.headers(), Instant::now());
+
+        let status = response.status();
+        let body = response.text().await?;
+
+        if let Ok(error) = serde_json::from_str::<BinanceApiError>(&body) {
+            return Err(BinanceError::from_api(error.code, error.msg));
+        }
+        if !status.is_success() {
+            return Err(BinanceError::Status { status: status.as_u16(), body });
         }
+
+        Ok(body)
     }
 
     pub fn create_limit_order(
@@ -177,6 +571,57 @@ impl BinanceOrderClient {
         }
     }
 
+    /// Checks `order` against PRICE_FILTER, LOT_SIZE and MIN_NOTIONAL. A zero
+    /// limit in `filters` means that check is disabled, as on Binance. Market
+    /// orders carry no price, so only the quantity checks apply to them.
+    pub fn validate(order: &BinanceOrderRequest, filters: &SymbolFilters) -> Result<(), FilterViolation> {
+        if let Some(price) = order.price {
+            if filters.min_price > 0.0 && price < filters.min_price {
+                return Err(FilterViolation::PriceBelowMin { price, min_price: filters.min_price });
+            }
+            if filters.max_price > 0.0 && price > filters.max_price {
+                return Err(FilterViolation::PriceAboveMax { price, max_price: filters.max_price });
+            }
+            if filters.tick_size > 0.0 && !is_multiple_of(price - filters.min_price, filters.tick_size) {
+                return Err(FilterViolation::PriceOffTick { price, tick_size: filters.tick_size });
+            }
+        }
+
+        let quantity = order.quantity;
+        if filters.min_qty > 0.0 && quantity < filters.min_qty {
+            return Err(FilterViolation::QuantityBelowMin { quantity, min_qty: filters.min_qty });
+        }
+        if filters.max_qty > 0.0 && quantity > filters.max_qty {
+            return Err(FilterViolation::QuantityAboveMax { quantity, max_qty: filters.max_qty });
+        }
+        if filters.step_size > 0.0 && !is_multiple_of(quantity - filters.min_qty, filters.step_size) {
+            return Err(FilterViolation::QuantityOffStep { quantity, step_size: filters.step_size });
+        }
+
+        if let Some(price) = order.price {
+            let notional = price * quantity;
+            if notional < filters.min_notional {
+                return Err(FilterViolation::BelowMinNotional { notional, min_notional: filters.min_notional });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Snaps the price to the nearest tick and rounds the quantity down to the
+    /// step size, so the result never trades more than was asked for.
+    pub fn round_to_filters(order: &BinanceOrderRequest, filters: &SymbolFilters) -> BinanceOrderRequest {
+        let mut rounded = order.clone();
+        if filters.tick_size > 0.0 {
+            rounded.price = order.price.map(|price| snap_to_step(price, filters.tick_size, f64::round));
+            rounded.stop_price = order.stop_price.map(|price| snap_to_step(price, filters.tick_size, f64::round));
+        }
+        if filters.step_size > 0.0 {
+            rounded.quantity = snap_to_step(order.quantity, filters.step_size, f64::floor);
+        }
+        rounded
+    }
+
     pub fn convert_polymarket_order_type(polymarket_type: &str) -> (BinanceOrderType, Option<BinanceTimeInForce>) {
         match polymarket_type {
             "GTC" => (BinanceOrderType::LIMIT, Some(BinanceTimeInForce::GTC)),
@@ -198,48 +643,104 @@ impl BinanceOrderClient {
         hex::encode(mac.finalize().into_bytes())
     }
 
+    /// Builds the unsigned query string. Parameters are emitted in a fixed
+    /// insertion order so the same order always produces the same bytes.
     pub fn build_query_string(&self, order: &BinanceOrderRequest) -> String {
-        let mut params = HashMap::new();
-        
-        params.insert("symbol", order.symbol.clone());
-        params.insert("side", format!("{:?}", order.side));
-        params.insert("type", format!("{:?}", order.order_type));
-        
+        let mut params: Vec<(&str, String)> = Vec::new();
+
+        params.push(("symbol", order.symbol.clone()));
+        params.push(("side", format!("{:?}", order.side)));
+        params.push(("type", format!("{:?}", order.order_type)));
+
         if let Some(time_in_force) = &order.time_in_force {
-            params.insert("timeInForce", format!("{:?}", time_in_force));
+            params.push(("timeInForce", format!("{:?}", time_in_force)));
         }
-        
-        params.insert("quantity", order.quantity.to_string());
-        
+
+        params.push(("quantity", order.quantity.to_string()));
+
         if let Some(price) = order.price {
-            params.insert("price", price.to_string());
+            params.push(("price", price.to_string()));
         }
-        
+
         if let Some(stop_price) = order.stop_price {
-            params.insert("stopPrice", stop_price.to_string());
+            params.push(("stopPrice", stop_price.to_string()));
         }
-        
+
         if let Some(iceberg_qty) = order.iceberg_qty {
-            params.insert("icebergQty", iceberg_qty.to_string());
+            params.push(("icebergQty", iceberg_qty.to_string()));
         }
-        
+
         if let Some(client_order_id) = &order.new_client_order_id {
-            params.insert("newClientOrderId", client_order_id.clone());
+            params.push(("newClientOrderId", client_order_id.clone()));
         }
-        
+
         if let Some(resp_type) = &order.new_order_resp_type {
-            params.insert("newOrderRespType", resp_type.clone());
+            params.push(("newOrderRespType", resp_type.clone()));
         }
-        
-        params.insert("recvWindow", order.recv_window.unwrap_or(5000).to_string());
-        params.insert("timestamp", order.timestamp.to_string());
-        
-        params
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<_>>()
-            .join("&")
+
+        params.push(("recvWindow", order.recv_window.unwrap_or(5000).to_string()));
+        params.push(("timestamp", order.timestamp.to_string()));
+
+        join_params(&params)
     }
+
+    /// Returns the exact string that is transmitted: the query string with its
+    /// HMAC appended as the final `signature` parameter.
+    pub fn signed_query_string(&self, order: &BinanceOrderRequest) -> String {
+        self.sign(self.build_query_string(order))
+    }
+
+    /// Signed query for non-order endpoints: `params`, then `recvWindow` and
+    /// `timestamp`, then the signature.
+    fn signed_params(&self, mut params: Vec<(&str, String)>) -> String {
+        params.push(("recvWindow", self.recv_window.to_string()));
+        params.push(("timestamp", chrono::Utc::now().timestamp_millis().to_string()));
+        self.sign(join_params(&params))
+    }
+
+    fn sign(&self, query_string: String) -> String {
+        let signature = self.generate_signature(&query_string);
+        format!("{}&signature={}", query_string, signature)
+    }
+}
+
+fn is_multiple_of(value: f64, step: f64) -> bool {
+    let steps = value / step;
+    (steps - steps.round()).abs() <= 1e-9 * steps.abs().max(1.0)
+}
+
+/// Rounds `value` to a multiple of `step` with `round` and trims float noise
+/// to the step's number of decimals.
+fn snap_to_step(value: f64, step: f64, round: fn(f64) -> f64) -> f64 {
+    // Nudge before flooring so 0.3 / 0.1 = 2.9999999999999996 still counts as 3 steps
+    let steps = round(value / step + 1e-9);
+    let decimals = (-step.log10()).ceil().max(0.0) as i32;
+    let scale = 10f64.powi(decimals);
+    (steps * step * scale).round() / scale
+}
+
+fn join_params(params: &[(&str, String)]) -> String {
+    params
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// A fresh `newClientOrderId`, well inside Binance's 36 character limit.
+fn new_client_order_id() -> String {
+    format!("ob-{:016x}", rand::random::<u64>())
+}
+
+fn order_ref_param(order: &BinanceOrderRef) -> (&'static str, String) {
+    match order {
+        BinanceOrderRef::OrderId(order_id) => ("orderId", order_id.to_string()),
+        BinanceOrderRef::ClientOrderId(client_order_id) => ("origClientOrderId", client_order_id.clone()),
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(body: &str) -> Result<T, BinanceError> {
+    serde_json::from_str(body).map_err(|e| BinanceError::Parse(e.to_string()))
 }
 
 pub fn convert_polymarket_to_binance_example() {
@@ -264,7 +765,7 @@ pub fn convert_polymarket_to_binance_example() {
         BinanceTimeInForce::GTC,
     );
 
-    println!("Converted Polymarket order to Binance: {:?}", binance_order);
+    tracing::info!(?binance_order, "converted Polymarket order to Binance");
 }
 
 #[derive(Debug, Clone)]
@@ -284,6 +785,502 @@ pub enum PolymarketOrderSide {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rate_limiter::RateLimitConfig;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    const ORDER_RESPONSE_FIXTURE: &str = r#"{
+        "symbol": "BTCUSDT",
+        "orderId": 28,
+        "orderListId": -1,
+        "clientOrderId": "6gCrw2kRUAF9CvJDGP16IP",
+        "transactTime": 1507725176595,
+        "price": "50000.00000000",
+        "origQty": "1.00000000",
+        "executedQty": "1.00000000",
+        "cummulativeQuoteQty": "50000.00000000",
+        "status": "FILLED",
+        "timeInForce": "GTC",
+        "type": "LIMIT",
+        "side": "BUY",
+        "workingTime": 1507725176595,
+        "selfTradePreventionMode": "NONE",
+        "fills": [
+            {"price": "50000.00000000", "qty": "0.40000000", "commission": "0.00040000", "commissionAsset": "BTC", "tradeId": 56},
+            {"price": "50000.00000000", "qty": "0.60000000", "commission": "0.00060000", "commissionAsset": "BTC", "tradeId": 57}
+        ]
+    }"#;
+
+    fn mock_client(server: &MockServer) -> BinanceOrderClient {
+        BinanceOrderClient::with_base_url("test_key".to_string(), "test_secret".to_string(), &server.uri())
+            .with_retry_config(RetryConfig {
+                max_attempts: 3,
+                base_delay: Duration::from_millis(1),
+                max_delay: Duration::from_secs(1),
+            })
+    }
+
+    fn query_value(request: &Request, key: &str) -> Option<String> {
+        request.url.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.into_owned())
+    }
+
+    /// Checks that the `signature` parameter is the HMAC of everything before it.
+    fn signed_with_test_secret(request: &Request) -> bool {
+        let query = request.url.query().unwrap_or_default();
+        let Some((payload, signature)) = query.rsplit_once("&signature=") else {
+            return false;
+        };
+        let client = BinanceOrderClient::with_base_url(String::new(), "test_secret".to_string(), "");
+        client.generate_signature(payload) == signature
+    }
+
+    #[tokio::test]
+    async fn test_place_order_parses_fills() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .and(header("X-MBX-APIKEY", "test_key"))
+            .and(query_param("symbol", "BTCUSDT"))
+            .and(query_param("type", "LIMIT"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(ORDER_RESPONSE_FIXTURE))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let order = client.create_limit_order("BTCUSDT", BinanceOrderSide::BUY, 1.0, 50000.0, BinanceTimeInForce::GTC);
+        let response = client.place_order(&order).await.unwrap();
+
+        assert_eq!(response.order_id, 28);
+        assert_eq!(response.status, BinanceOrderStatus::FILLED);
+        assert_eq!(response.order_type, "LIMIT");
+        let fills = response.fills.unwrap();
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[1].qty, "0.60000000");
+        assert_eq!(fills[1].commission_asset, "BTC");
+        assert_eq!(fills[1].trade_id, 57);
+    }
+
+    #[tokio::test]
+    async fn test_place_order_maps_api_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(400).set_body_string(
+                r#"{"code":-2010,"msg":"Account has insufficient balance for requested action."}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let order = client.create_market_order("BTCUSDT", BinanceOrderSide::SELL, 5.0);
+
+        match client.place_order(&order).await {
+            Err(BinanceError::Api { code, msg }) => {
+                assert_eq!(code, -2010);
+                assert!(msg.contains("insufficient balance"));
+            }
+            other => panic!("expected API error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_used_weight_header_throttles_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/order"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(ORDER_INFO_FIXTURE)
+                    .insert_header("X-MBX-USED-WEIGHT-1M", "98"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let limiter = Arc::new(RateLimiter::new(RateLimitConfig {
+            weight_per_minute: 100,
+            orders_per_second: 10,
+            max_delay: Duration::ZERO,
+        }));
+        let client = mock_client(&server).with_rate_limiter(limiter.clone());
+
+        assert!(client.get_order("BTCUSDT", 12345).await.is_ok());
+        assert_eq!(limiter.usage(Instant::now()).used_weight, 98);
+        assert!(matches!(
+            client.get_order("BTCUSDT", 12345).await,
+            Err(BinanceError::RateLimited { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retried_requests_are_charged_weight_per_attempt() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(ORDER_INFO_FIXTURE))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let limiter = Arc::new(RateLimiter::default());
+        let client = mock_client(&server).with_rate_limiter(limiter.clone());
+
+        assert!(client.get_order("BTCUSDT", 12345).await.is_ok());
+        assert_eq!(limiter.usage(Instant::now()).used_weight, 2 * 4);
+    }
+
+    const CANCEL_RESPONSE_FIXTURE: &str = r#"{
+        "symbol": "BTCUSDT",
+        "origClientOrderId": "myOrder1",
+        "orderId": 4,
+        "orderListId": -1,
+        "clientOrderId": "cancelMyOrder1",
+        "transactTime": 1684804350068,
+        "price": "50000.00000000",
+        "origQty": "1.00000000",
+        "executedQty": "0.00000000",
+        "cummulativeQuoteQty": "0.00000000",
+        "status": "CANCELED",
+        "timeInForce": "GTC",
+        "type": "LIMIT",
+        "side": "BUY",
+        "selfTradePreventionMode": "NONE"
+    }"#;
+
+    const ORDER_INFO_FIXTURE: &str = r#"{
+        "symbol": "BTCUSDT",
+        "orderId": 4,
+        "orderListId": -1,
+        "clientOrderId": "myOrder1",
+        "price": "50000.00000000",
+        "origQty": "1.00000000",
+        "executedQty": "0.25000000",
+        "cummulativeQuoteQty": "12500.00000000",
+        "status": "PARTIALLY_FILLED",
+        "timeInForce": "GTC",
+        "type": "LIMIT",
+        "side": "BUY",
+        "stopPrice": "0.00000000",
+        "icebergQty": "0.00000000",
+        "time": 1499827319559,
+        "updateTime": 1499827319559,
+        "isWorking": true,
+        "workingTime": 1499827319559,
+        "origQuoteOrderQty": "0.000000",
+        "selfTradePreventionMode": "NONE"
+    }"#;
+
+    #[tokio::test]
+    async fn test_cancel_order_and_unknown_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/v3/order"))
+            .and(query_param("symbol", "BTCUSDT"))
+            .and(query_param("orderId", "4"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(CANCEL_RESPONSE_FIXTURE))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/v3/order"))
+            .and(query_param("origClientOrderId", "missing"))
+            .respond_with(ResponseTemplate::new(400).set_body_string(r#"{"code":-2011,"msg":"Unknown order sent."}"#))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let cancelled = client.cancel_order("BTCUSDT", &BinanceOrderRef::OrderId(4)).await.unwrap();
+        assert_eq!(cancelled.order_id, 4);
+        assert_eq!(cancelled.orig_client_order_id, "myOrder1");
+        assert_eq!(cancelled.status, BinanceOrderStatus::CANCELED);
+
+        match client.cancel_order("BTCUSDT", &BinanceOrderRef::ClientOrderId("missing".to_string())).await {
+            Err(BinanceError::UnknownOrder { code, msg }) => {
+                assert_eq!(code, -2011);
+                assert_eq!(msg, "Unknown order sent.");
+            }
+            other => panic!("expected unknown order error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_open_orders() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/v3/openOrders"))
+            .and(query_param("symbol", "BTCUSDT"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("[{}]", CANCEL_RESPONSE_FIXTURE)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cancelled = mock_client(&server).cancel_all_open_orders("BTCUSDT").await.unwrap();
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].client_order_id, "cancelMyOrder1");
+    }
+
+    #[tokio::test]
+    async fn test_order_status_queries() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/order"))
+            .and(header("X-MBX-APIKEY", "test_key"))
+            .and(query_param("orderId", "4"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(ORDER_INFO_FIXTURE))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/order"))
+            .and(query_param("orderId", "99"))
+            .respond_with(ResponseTemplate::new(400).set_body_string(r#"{"code":-2013,"msg":"Order does not exist."}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/openOrders"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("[{}]", ORDER_INFO_FIXTURE)))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let order = client.get_order("BTCUSDT", 4).await.unwrap();
+        assert_eq!(order.status, BinanceOrderStatus::PARTIALLY_FILLED);
+        assert_eq!(order.executed_qty, "0.25000000");
+        assert!(order.is_working);
+
+        assert!(matches!(
+            client.get_order("BTCUSDT", 99).await,
+            Err(BinanceError::UnknownOrder { code: -2013, .. })
+        ));
+
+        let open_orders = client.get_open_orders("BTCUSDT").await.unwrap();
+        assert_eq!(open_orders.len(), 1);
+        assert_eq!(open_orders[0].order_id, 4);
+    }
+
+    #[tokio::test]
+    async fn test_queries_retry_transient_failures() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/openOrders"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/openOrders"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("[{}]", ORDER_INFO_FIXTURE)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Cancels are not replayed
+        Mock::given(method("DELETE"))
+            .and(path("/api/v3/openOrders"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        assert_eq!(client.get_open_orders("BTCUSDT").await.unwrap().len(), 1);
+        assert!(matches!(
+            client.cancel_all_open_orders("BTCUSDT").await,
+            Err(BinanceError::Status { status: 503, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_place_order_finds_order_that_landed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(504).set_body_string("Gateway Timeout"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/order"))
+            .and(query_param("origClientOrderId", "myOrder1"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(ORDER_INFO_FIXTURE))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let mut order = client.create_limit_order("BTCUSDT", BinanceOrderSide::BUY, 1.0, 50000.0, BinanceTimeInForce::GTC);
+        order.new_client_order_id = Some("myOrder1".to_string());
+
+        let response = client.place_order(&order).await.unwrap();
+        assert_eq!(response.order_id, 4);
+        assert_eq!(response.client_order_id, "myOrder1");
+        assert_eq!(response.status, BinanceOrderStatus::PARTIALLY_FILLED);
+        assert!(response.fills.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_place_order_resubmits_only_unknown_orders() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(ORDER_RESPONSE_FIXTURE))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(400).set_body_string(r#"{"code":-2013,"msg":"Order does not exist."}"#))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let order = client.create_limit_order("BTCUSDT", BinanceOrderSide::BUY, 1.0, 50000.0, BinanceTimeInForce::GTC);
+        assert_eq!(client.place_order(&order).await.unwrap().order_id, 28);
+
+        // Every attempt and lookup used the same generated client order id
+        let requests = server.received_requests().await.unwrap();
+        let methods: Vec<String> = requests.iter().map(|r| r.method.to_string()).collect();
+        assert_eq!(methods, ["POST", "GET", "POST", "GET", "POST"]);
+        let client_order_id = query_value(&requests[0], "newClientOrderId").unwrap();
+        assert!(client_order_id.starts_with("ob-"));
+        for request in &requests {
+            let id = query_value(request, "newClientOrderId").or_else(|| query_value(request, "origClientOrderId"));
+            assert_eq!(id.as_deref(), Some(client_order_id.as_str()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_place_order_gives_up_when_lookup_fails() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let order = client.create_market_order("BTCUSDT", BinanceOrderSide::SELL, 1.0);
+        assert!(matches!(
+            client.place_order(&order).await,
+            Err(BinanceError::Status { status: 503, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_account_balances() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/account"))
+            .and(header("X-MBX-APIKEY", "test_key"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{
+                    "makerCommission": 15, "takerCommission": 15, "canTrade": true, "canWithdraw": true,
+                    "canDeposit": true, "updateTime": 123456789, "accountType": "SPOT",
+                    "balances": [
+                        {"asset": "BTC", "free": "4723846.89208129", "locked": "0.00000000"},
+                        {"asset": "USDT", "free": "4763368.68006011", "locked": "12.50000000"}
+                    ],
+                    "permissions": ["SPOT"]
+                }"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let account = mock_client(&server).get_account().await.unwrap();
+        assert!(account.can_trade);
+        assert_eq!(account.balances.len(), 2);
+        assert_eq!(account.balances[1].asset, "USDT");
+        assert_eq!(account.balances[1].locked, "12.50000000");
+    }
+
+    #[tokio::test]
+    async fn test_listen_key_lifecycle() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/userDataStream"))
+            .and(header("X-MBX-APIKEY", "test_key"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"listenKey":"pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1"}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        for verb in ["PUT", "DELETE"] {
+            Mock::given(method(verb))
+                .and(path("/api/v3/userDataStream"))
+                .and(query_param("listenKey", "pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1"))
+                .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = mock_client(&server);
+        let listen_key = client.create_listen_key().await.unwrap();
+        assert!(listen_key.starts_with("pqia91ma"));
+        client.keepalive_listen_key(&listen_key).await.unwrap();
+        client.close_listen_key(&listen_key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_place_order_test_endpoint() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order/test"))
+            .and(header("X-MBX-APIKEY", "test_key"))
+            .and(signed_with_test_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let order = client.create_limit_order("BTCUSDT", BinanceOrderSide::BUY, 1.0, 50000.0, BinanceTimeInForce::GTC);
+        client.place_order_test(&order).await.unwrap();
+        server.verify().await;
+
+        // Non-JSON failures keep the HTTP status
+        server.reset().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order/test"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("Bad Gateway"))
+            .mount(&server)
+            .await;
+        assert!(matches!(
+            client.place_order_test(&order).await,
+            Err(BinanceError::Status { status: 502, .. })
+        ));
+    }
 
     #[test]
     fn test_order_type_conversion() {
@@ -342,4 +1339,122 @@ mod tests {
         assert!(query_string.contains("quantity=1"));
         assert!(query_string.contains("price=50000"));
     }
+
+    fn btc_filters() -> SymbolFilters {
+        SymbolFilters {
+            symbol: "BTCUSDT".to_string(),
+            tick_size: 0.01,
+            min_price: 0.01,
+            max_price: 1_000_000.0,
+            step_size: 0.00001,
+            min_qty: 0.00001,
+            max_qty: 9000.0,
+            min_notional: 5.0,
+        }
+    }
+
+    fn limit_order(price: f64, quantity: f64) -> BinanceOrderRequest {
+        let client = BinanceOrderClient::new("test_key".to_string(), "test_secret".to_string(), true);
+        client.create_limit_order("BTCUSDT", BinanceOrderSide::BUY, quantity, price, BinanceTimeInForce::GTC)
+    }
+
+    #[test]
+    fn test_validate_price_filter() {
+        let filters = btc_filters();
+        assert!(BinanceOrderClient::validate(&limit_order(50000.01, 0.001), &filters).is_ok());
+        assert!(matches!(
+            BinanceOrderClient::validate(&limit_order(50000.015, 0.001), &filters),
+            Err(FilterViolation::PriceOffTick { .. })
+        ));
+
+        let mut tight = filters.clone();
+        tight.min_notional = 0.0;
+        assert!(BinanceOrderClient::validate(&limit_order(0.01, 1.0), &tight).is_ok());
+        assert!(matches!(
+            BinanceOrderClient::validate(&limit_order(0.0, 1.0), &tight),
+            Err(FilterViolation::PriceBelowMin { .. })
+        ));
+        assert!(BinanceOrderClient::validate(&limit_order(1_000_000.0, 0.001), &tight).is_ok());
+        assert!(matches!(
+            BinanceOrderClient::validate(&limit_order(1_000_000.01, 0.001), &tight),
+            Err(FilterViolation::PriceAboveMax { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_lot_size_and_notional() {
+        let mut filters = btc_filters();
+        filters.min_notional = 0.0;
+
+        assert!(BinanceOrderClient::validate(&limit_order(50000.0, 0.00001), &filters).is_ok());
+        assert!(matches!(
+            BinanceOrderClient::validate(&limit_order(50000.0, 0.0), &filters),
+            Err(FilterViolation::QuantityBelowMin { .. })
+        ));
+        assert!(BinanceOrderClient::validate(&limit_order(1.0, 9000.0), &filters).is_ok());
+        assert!(matches!(
+            BinanceOrderClient::validate(&limit_order(1.0, 9000.00001), &filters),
+            Err(FilterViolation::QuantityAboveMax { .. })
+        ));
+        assert!(matches!(
+            BinanceOrderClient::validate(&limit_order(50000.0, 0.000015), &filters),
+            Err(FilterViolation::QuantityOffStep { .. })
+        ));
+
+        // 0.0001 BTC at 50000 is exactly the 5 USDT minimum; one tick lower is not
+        filters.min_notional = 5.0;
+        assert!(BinanceOrderClient::validate(&limit_order(50000.0, 0.0001), &filters).is_ok());
+        assert!(matches!(
+            BinanceOrderClient::validate(&limit_order(49999.99, 0.0001), &filters),
+            Err(FilterViolation::BelowMinNotional { .. })
+        ));
+    }
+
+    #[test]
+    fn test_round_to_filters() {
+        let filters = btc_filters();
+        let rounded = BinanceOrderClient::round_to_filters(&limit_order(50000.016, 0.123456789), &filters);
+
+        assert_eq!(rounded.price, Some(50000.02));
+        assert_eq!(rounded.quantity, 0.12345);
+        assert!(BinanceOrderClient::validate(&rounded, &filters).is_ok());
+
+        let rounded = BinanceOrderClient::round_to_filters(&limit_order(100.0, 0.3), &filters);
+        assert_eq!(rounded.quantity, 0.3);
+    }
+
+    #[test]
+    fn test_query_string_is_deterministic() {
+        let client = BinanceOrderClient::new(
+            "test_key".to_string(),
+            "test_secret".to_string(),
+            true,
+        );
+
+        let mut order = client.create_limit_order(
+            "BTCUSDT",
+            BinanceOrderSide::BUY,
+            1.5,
+            50000.0,
+            BinanceTimeInForce::GTC,
+        );
+        order.timestamp = 1700000000000;
+
+        let expected = client.build_query_string(&order);
+        assert_eq!(
+            expected,
+            "symbol=BTCUSDT&side=BUY&type=LIMIT&timeInForce=GTC&quantity=1.5&price=50000\
+             &newOrderRespType=RESULT&recvWindow=5000&timestamp=1700000000000"
+        );
+
+        let expected_signed = client.signed_query_string(&order);
+        for _ in 0..100 {
+            assert_eq!(client.build_query_string(&order.clone()), expected);
+            assert_eq!(client.signed_query_string(&order.clone()), expected_signed);
+        }
+        assert_eq!(
+            expected_signed,
+            format!("{}&signature={}", expected, client.generate_signature(&expected))
+        );
+    }
 }
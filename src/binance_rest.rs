@@ -0,0 +1,426 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use serde::Deserialize;
+use serde_json::Value;
+use crate::binance_ws::DepthSnapshot;
+use crate::http_retry::{self, RetryConfig};
+use crate::rate_limiter::RateLimiter;
+use crate::ui::Candlestick;
+
+#[derive(Debug)]
+pub enum BinanceRestError {
+    Http(reqwest::Error),
+    Api { code: i64, msg: String },
+    Status { status: u16, body: String },
+    Parse(String),
+    SymbolNotFound(String),
+    RateLimited { retry_after: Duration },
+}
+
+impl fmt::Display for BinanceRestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinanceRestError::Http(e) => write!(f, "HTTP error: {}", e),
+            BinanceRestError::Api { code, msg } => write!(f, "Binance error {}: {}", code, msg),
+            BinanceRestError::Status { status, body } => write!(f, "HTTP {}: {}", status, body),
+            BinanceRestError::Parse(msg) => write!(f, "Failed to parse response: {}", msg),
+            BinanceRestError::SymbolNotFound(symbol) => write!(f, "Symbol not found: {}", symbol),
+            BinanceRestError::RateLimited { retry_after } => {
+                write!(f, "Rate limit reached, retry in {:.1}s", retry_after.as_secs_f64())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BinanceRestError {}
+
+impl From<reqwest::Error> for BinanceRestError {
+    fn from(error: reqwest::Error) -> Self {
+        BinanceRestError::Http(error)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceApiError {
+    code: i64,
+    msg: String,
+}
+
+/// Trading rules for a symbol, taken from the `filters` array of exchangeInfo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolFilters {
+    pub symbol: String,
+    pub tick_size: f64,
+    pub min_price: f64,
+    pub max_price: f64,
+    pub step_size: f64,
+    pub min_qty: f64,
+    pub max_qty: f64,
+    pub min_notional: f64,
+}
+
+/// Rolling 24 hour statistics for a symbol from `/api/v3/ticker/24hr`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticker24h {
+    pub symbol: String,
+    pub last_price: f64,
+    /// Absolute change over the window, in the quote asset.
+    pub price_change: f64,
+    pub price_change_percent: f64,
+    /// Volume traded over the window, in the quote asset.
+    pub quote_volume: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TickerResponse {
+    symbol: String,
+    last_price: String,
+    price_change: String,
+    price_change_percent: String,
+    quote_volume: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SymbolInfo {
+    symbol: String,
+    filters: Vec<SymbolFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "filterType")]
+enum SymbolFilter {
+    #[serde(rename = "PRICE_FILTER", rename_all = "camelCase")]
+    Price { min_price: String, max_price: String, tick_size: String },
+    #[serde(rename = "LOT_SIZE", rename_all = "camelCase")]
+    LotSize { min_qty: String, max_qty: String, step_size: String },
+    #[serde(rename = "MIN_NOTIONAL", rename_all = "camelCase")]
+    MinNotional { min_notional: String },
+    #[serde(rename = "NOTIONAL", rename_all = "camelCase")]
+    Notional { min_notional: String },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone)]
+pub struct BinanceRestClient {
+    base_url: String,
+    http: reqwest::Client,
+    rate_limiter: Arc<RateLimiter>,
+    retry: RetryConfig,
+}
+
+impl BinanceRestClient {
+    pub fn new() -> Self {
+        Self::with_base_url("https://api.binance.com")
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Shares `rate_limiter` with other clients using the same API host.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub async fn get_klines(&self, symbol: &str, interval: &str, limit: u16) -> Result<Vec<Candlestick>, BinanceRestError> {
+        let limit = limit.to_string();
+        let body = self
+            .get("/api/v3/klines", &[("symbol", symbol), ("interval", interval), ("limit", &limit)], 2)
+            .await?;
+        parse_klines(&body)
+    }
+
+    pub async fn get_exchange_info(&self, symbol: &str) -> Result<SymbolFilters, BinanceRestError> {
+        let body = self.get("/api/v3/exchangeInfo", &[("symbol", symbol)], 20).await?;
+        parse_exchange_info(&body, symbol)
+    }
+
+    pub async fn get_ticker_24hr(&self, symbol: &str) -> Result<Ticker24h, BinanceRestError> {
+        let body = self.get("/api/v3/ticker/24hr", &[("symbol", symbol)], 2).await?;
+        parse_ticker_24hr(&body)
+    }
+
+    /// The top `limit` levels a side, as the snapshot a diff-depth stream
+    /// resyncs from.
+    pub async fn get_depth(&self, symbol: &str, limit: u16) -> Result<DepthSnapshot, BinanceRestError> {
+        // Weight grows with the depth asked for
+        let weight = match limit {
+            0..=100 => 5,
+            101..=500 => 25,
+            501..=1000 => 50,
+            _ => 250,
+        };
+        let limit = limit.to_string();
+        let body = self.get("/api/v3/depth", &[("symbol", symbol), ("limit", &limit)], weight).await?;
+        serde_json::from_str(&body).map_err(|e| BinanceRestError::Parse(e.to_string()))
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, &str)], weight: u32) -> Result<String, BinanceRestError> {
+        let url = format!("{}{}", self.base_url, path);
+        // Every attempt counts against the weight limit, retries included
+        let acquire = || async {
+            self.rate_limiter
+                .acquire(weight)
+                .await
+                .map_err(|retry_after| BinanceRestError::RateLimited { retry_after })
+        };
+        let response = http_retry::send_with_retry_gated(&self.retry, acquire, || self.http.get(&url).query(query)).await?;
+        self.rate_limiter.sync_from_headers(response.headers(), Instant::now());
+
+        let status = response.status();
+        let body = response.text().await?;
+
+        if let Some(error) = parse_api_error(&body) {
+            return Err(error);
+        }
+        if !status.is_success() {
+            return Err(BinanceRestError::Status { status: status.as_u16(), body });
+        }
+
+        Ok(body)
+    }
+}
+
+impl Default for BinanceRestClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recognizes Binance's `{"code":-1121,"msg":"Invalid symbol."}` error payload.
+pub fn parse_api_error(body: &str) -> Option<BinanceRestError> {
+    serde_json::from_str::<BinanceApiError>(body)
+        .ok()
+        .map(|e| BinanceRestError::Api { code: e.code, msg: e.msg })
+}
+
+pub fn parse_klines(body: &str) -> Result<Vec<Candlestick>, BinanceRestError> {
+    if let Some(error) = parse_api_error(body) {
+        return Err(error);
+    }
+
+    let rows: Vec<Vec<Value>> = serde_json::from_str(body)
+        .map_err(|e| BinanceRestError::Parse(e.to_string()))?;
+
+    rows.iter()
+        .map(|row| {
+            let open_time = row.first()
+                .and_then(Value::as_i64)
+                .ok_or_else(|| BinanceRestError::Parse("missing kline open time".to_string()))?;
+            let timestamp = chrono::DateTime::from_timestamp_millis(open_time)
+                .ok_or_else(|| BinanceRestError::Parse(format!("invalid kline open time {}", open_time)))?;
+
+            Ok(Candlestick::new(
+                timestamp,
+                kline_field(row, 1)?,
+                kline_field(row, 2)?,
+                kline_field(row, 3)?,
+                kline_field(row, 4)?,
+                kline_field(row, 5)?,
+            ))
+        })
+        .collect()
+}
+
+fn kline_field(row: &[Value], index: usize) -> Result<f64, BinanceRestError> {
+    row.get(index)
+        .and_then(Value::as_str)
+        .and_then(|v| v.parse::<f64>().ok())
+        .ok_or_else(|| BinanceRestError::Parse(format!("invalid kline field at index {}", index)))
+}
+
+pub fn parse_exchange_info(body: &str, symbol: &str) -> Result<SymbolFilters, BinanceRestError> {
+    if let Some(error) = parse_api_error(body) {
+        return Err(error);
+    }
+
+    let info: ExchangeInfo = serde_json::from_str(body)
+        .map_err(|e| BinanceRestError::Parse(e.to_string()))?;
+    let symbol_info = info.symbols
+        .into_iter()
+        .find(|s| s.symbol.eq_ignore_ascii_case(symbol))
+        .ok_or_else(|| BinanceRestError::SymbolNotFound(symbol.to_string()))?;
+
+    let mut filters = SymbolFilters {
+        symbol: symbol_info.symbol,
+        tick_size: 0.0,
+        min_price: 0.0,
+        max_price: 0.0,
+        step_size: 0.0,
+        min_qty: 0.0,
+        max_qty: 0.0,
+        min_notional: 0.0,
+    };
+
+    for filter in symbol_info.filters {
+        match filter {
+            SymbolFilter::Price { min_price, max_price, tick_size } => {
+                filters.min_price = parse_decimal(&min_price)?;
+                filters.max_price = parse_decimal(&max_price)?;
+                filters.tick_size = parse_decimal(&tick_size)?;
+            }
+            SymbolFilter::LotSize { min_qty, max_qty, step_size } => {
+                filters.min_qty = parse_decimal(&min_qty)?;
+                filters.max_qty = parse_decimal(&max_qty)?;
+                filters.step_size = parse_decimal(&step_size)?;
+            }
+            SymbolFilter::MinNotional { min_notional } | SymbolFilter::Notional { min_notional } => {
+                filters.min_notional = parse_decimal(&min_notional)?;
+            }
+            SymbolFilter::Other => {}
+        }
+    }
+
+    Ok(filters)
+}
+
+pub fn parse_ticker_24hr(body: &str) -> Result<Ticker24h, BinanceRestError> {
+    if let Some(error) = parse_api_error(body) {
+        return Err(error);
+    }
+
+    let ticker: TickerResponse = serde_json::from_str(body)
+        .map_err(|e| BinanceRestError::Parse(e.to_string()))?;
+    Ok(Ticker24h {
+        symbol: ticker.symbol,
+        last_price: parse_decimal(&ticker.last_price)?,
+        price_change: parse_decimal(&ticker.price_change)?,
+        price_change_percent: parse_decimal(&ticker.price_change_percent)?,
+        quote_volume: parse_decimal(&ticker.quote_volume)?,
+    })
+}
+
+fn parse_decimal(value: &str) -> Result<f64, BinanceRestError> {
+    value.parse::<f64>()
+        .map_err(|_| BinanceRestError::Parse(format!("invalid decimal '{}'", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KLINES_FIXTURE: &str = r#"[
+        [1499040000000, "0.01634790", "0.80000000", "0.01575800", "0.01577100", "148976.11427815",
+         1499644799999, "2434.19055334", 308, "1756.87402397", "28.46694368", "0"],
+        [1499644800000, "0.01577100", "0.01600000", "0.01500000", "0.01590000", "1200.50000000",
+         1500249599999, "19.00000000", 12, "600.00000000", "9.50000000", "0"]
+    ]"#;
+
+    const EXCHANGE_INFO_FIXTURE: &str = r#"{
+        "timezone": "UTC",
+        "serverTime": 1565246363776,
+        "symbols": [{
+            "symbol": "BTCUSDT",
+            "status": "TRADING",
+            "baseAsset": "BTC",
+            "quoteAsset": "USDT",
+            "filters": [
+                {"filterType": "PRICE_FILTER", "minPrice": "0.01000000", "maxPrice": "1000000.00000000", "tickSize": "0.01000000"},
+                {"filterType": "LOT_SIZE", "minQty": "0.00001000", "maxQty": "9000.00000000", "stepSize": "0.00001000"},
+                {"filterType": "ICEBERG_PARTS", "limit": 10},
+                {"filterType": "NOTIONAL", "minNotional": "5.00000000", "applyMinToMarket": true,
+                 "maxNotional": "9000000.00000000", "applyMaxToMarket": false, "avgPriceMins": 5}
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn test_parse_klines() {
+        let candles = parse_klines(KLINES_FIXTURE).unwrap();
+        assert_eq!(candles.len(), 2);
+
+        let first = &candles[0];
+        assert_eq!(first.timestamp.timestamp_millis(), 1499040000000);
+        assert_eq!(first.open, 0.0163479);
+        assert_eq!(first.high, 0.8);
+        assert_eq!(first.low, 0.015758);
+        assert_eq!(first.close, 0.015771);
+        assert_eq!(first.volume, 148976.11427815);
+        assert_eq!(candles[1].close, 0.0159);
+    }
+
+    #[test]
+    fn test_parse_exchange_info_filters() {
+        let filters = parse_exchange_info(EXCHANGE_INFO_FIXTURE, "BTCUSDT").unwrap();
+        assert_eq!(filters.symbol, "BTCUSDT");
+        assert_eq!(filters.tick_size, 0.01);
+        assert_eq!(filters.min_price, 0.01);
+        assert_eq!(filters.max_price, 1_000_000.0);
+        assert_eq!(filters.step_size, 0.00001);
+        assert_eq!(filters.min_qty, 0.00001);
+        assert_eq!(filters.max_qty, 9000.0);
+        assert_eq!(filters.min_notional, 5.0);
+
+        assert!(matches!(
+            parse_exchange_info(EXCHANGE_INFO_FIXTURE, "ETHUSDT"),
+            Err(BinanceRestError::SymbolNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_ticker_24hr() {
+        let body = r#"{
+            "symbol": "ADAUSDT", "priceChange": "-0.01230000", "priceChangePercent": "-2.651",
+            "weightedAvgPrice": "0.45800000", "prevClosePrice": "0.46390000", "lastPrice": "0.45160000",
+            "volume": "301245678.30000000", "quoteVolume": "137968521.41000000",
+            "openTime": 1700000000000, "closeTime": 1700086399999, "count": 412345
+        }"#;
+        let ticker = parse_ticker_24hr(body).unwrap();
+        assert_eq!(ticker.symbol, "ADAUSDT");
+        assert_eq!(ticker.last_price, 0.4516);
+        assert_eq!(ticker.price_change, -0.0123);
+        assert_eq!(ticker.price_change_percent, -2.651);
+        assert_eq!(ticker.quote_volume, 137968521.41);
+
+        assert!(matches!(
+            parse_ticker_24hr(r#"{"code":-1121,"msg":"Invalid symbol."}"#),
+            Err(BinanceRestError::Api { code: -1121, .. })
+        ));
+        assert!(matches!(
+            parse_ticker_24hr(r#"{"symbol": "ADAUSDT", "lastPrice": "n/a"}"#),
+            Err(BinanceRestError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_binance_error_payload() {
+        let body = r#"{"code":-1121,"msg":"Invalid symbol."}"#;
+
+        match parse_klines(body) {
+            Err(BinanceRestError::Api { code, msg }) => {
+                assert_eq!(code, -1121);
+                assert_eq!(msg, "Invalid symbol.");
+            }
+            other => panic!("expected API error, got {:?}", other),
+        }
+        assert!(matches!(parse_exchange_info(body, "BTCUSDT"), Err(BinanceRestError::Api { .. })));
+        assert!(matches!(parse_klines("<html>"), Err(BinanceRestError::Parse(_))));
+    }
+}
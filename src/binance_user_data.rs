@@ -0,0 +1,305 @@
+use std::sync::mpsc as std_mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use futures_util::StreamExt;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use crate::binance_orders::{BinanceOrderClient, BinanceOrderSide, BinanceOrderStatus};
+
+/// Binance expires a listenKey after 60 minutes without a keepalive.
+pub const LISTEN_KEY_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+pub fn user_stream_base_url(testnet: bool) -> &'static str {
+    if testnet {
+        "wss://stream.testnet.binance.vision"
+    } else {
+        "wss://stream.binance.com:9443"
+    }
+}
+
+/// An `executionReport` event: a change to one of the account's orders.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderUpdate {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "c")]
+    pub client_order_id: String,
+    #[serde(rename = "S")]
+    pub side: BinanceOrderSide,
+    #[serde(rename = "o")]
+    pub order_type: String,
+    #[serde(rename = "q", deserialize_with = "decimal")]
+    pub quantity: f64,
+    #[serde(rename = "p", deserialize_with = "decimal")]
+    pub price: f64,
+    #[serde(rename = "x")]
+    pub execution_type: String,
+    #[serde(rename = "X")]
+    pub status: BinanceOrderStatus,
+    #[serde(rename = "r")]
+    pub reject_reason: String,
+    #[serde(rename = "i")]
+    pub order_id: u64,
+    #[serde(rename = "l", deserialize_with = "decimal")]
+    pub last_filled_quantity: f64,
+    #[serde(rename = "z", deserialize_with = "decimal")]
+    pub cumulative_filled_quantity: f64,
+    #[serde(rename = "L", deserialize_with = "decimal")]
+    pub last_filled_price: f64,
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
+}
+
+/// An `outboundAccountPosition` event listing the balances that changed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BalanceUpdate {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "u")]
+    pub last_update_time: u64,
+    #[serde(rename = "B")]
+    pub balances: Vec<AssetBalance>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssetBalance {
+    #[serde(rename = "a")]
+    pub asset: String,
+    #[serde(rename = "f", deserialize_with = "decimal")]
+    pub free: f64,
+    #[serde(rename = "l", deserialize_with = "decimal")]
+    pub locked: f64,
+}
+
+#[derive(Debug, Clone)]
+pub enum UserDataEvent {
+    Order(OrderUpdate),
+    Balance(BalanceUpdate),
+    /// The stream task stopped with an error; no further events will arrive.
+    StreamError(String),
+}
+
+fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+/// Parses a user data stream message, routing on its `e` field. Event types
+/// the client does not track yield `Ok(None)`.
+pub fn parse_user_data_event(text: &str) -> Result<Option<UserDataEvent>, serde_json::Error> {
+    let value: Value = serde_json::from_str(text)?;
+    let event = match value.get("e").and_then(Value::as_str) {
+        Some("executionReport") => UserDataEvent::Order(serde_json::from_value(value)?),
+        Some("outboundAccountPosition") => UserDataEvent::Balance(serde_json::from_value(value)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
+/// Tracks when the listenKey next needs a keepalive. Callers pass the current
+/// time in, so the schedule can be driven without a real clock.
+#[derive(Debug, Clone)]
+pub struct KeepaliveSchedule {
+    interval: Duration,
+    last_keepalive: Instant,
+}
+
+impl KeepaliveSchedule {
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self { interval, last_keepalive: now }
+    }
+
+    pub fn next_due(&self) -> Instant {
+        self.last_keepalive + self.interval
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next_due()
+    }
+
+    pub fn mark_sent(&mut self, now: Instant) {
+        self.last_keepalive = now;
+    }
+}
+
+/// Opens a listenKey, forwards order and balance events until `shutdown`
+/// fires or the socket closes, and closes the listenKey on the way out.
+pub async fn run_user_data_stream(
+    client: BinanceOrderClient,
+    stream_base_url: &str,
+    events: mpsc::UnboundedSender<UserDataEvent>,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let listen_key = client.create_listen_key().await?;
+    let (mut socket, _) = connect_async(format!("{}/ws/{}", stream_base_url, listen_key)).await?;
+
+    let mut schedule = KeepaliveSchedule::new(LISTEN_KEY_KEEPALIVE_INTERVAL, Instant::now());
+    let mut keepalive_tick = tokio::time::interval(Duration::from_secs(60));
+
+    let result: Result<(), Box<dyn std::error::Error + Send + Sync>> = loop {
+        tokio::select! {
+            message = socket.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    if let Ok(Some(event)) = parse_user_data_event(&text) {
+                        if events.send(event).is_err() {
+                            break Ok(());
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => break Err(e.into()),
+            },
+            _ = keepalive_tick.tick() => {
+                let now = Instant::now();
+                if schedule.is_due(now) {
+                    if let Err(e) = client.keepalive_listen_key(&listen_key).await {
+                        break Err(e.into());
+                    }
+                    schedule.mark_sent(now);
+                }
+            }
+            _ = shutdown.changed() => break Ok(()),
+        }
+    };
+
+    let _ = socket.close(None).await;
+    client.close_listen_key(&listen_key).await?;
+    result
+}
+
+/// Runs `run_user_data_stream` on its own thread so the synchronous UI loop
+/// can poll for events.
+pub struct UserDataStreamHandle {
+    events: mpsc::UnboundedReceiver<UserDataEvent>,
+    shutdown: watch::Sender<bool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl UserDataStreamHandle {
+    pub fn spawn(client: BinanceOrderClient, stream_base_url: &str) -> Self {
+        let (event_tx, events) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let stream_base_url = stream_base_url.to_string();
+
+        let thread = std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    let _ = event_tx.send(UserDataEvent::StreamError(e.to_string()));
+                    return;
+                }
+            };
+            let result = runtime.block_on(run_user_data_stream(client, &stream_base_url, event_tx.clone(), shutdown_rx));
+            if let Err(e) = result {
+                let _ = event_tx.send(UserDataEvent::StreamError(e.to_string()));
+            }
+        });
+
+        Self { events, shutdown, thread: Some(thread) }
+    }
+
+    /// Returns every event received since the last call without blocking.
+    pub fn drain(&mut self) -> Vec<UserDataEvent> {
+        let mut drained = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            drained.push(event);
+        }
+        drained
+    }
+
+    /// Signals the stream task to close its listenKey and waits up to
+    /// `timeout` for it to finish.
+    pub fn stop(&mut self, timeout: Duration) {
+        let _ = self.shutdown.send(true);
+        if let Some(thread) = self.thread.take() {
+            let (done_tx, done_rx) = std_mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = thread.join();
+                let _ = done_tx.send(());
+            });
+            let _ = done_rx.recv_timeout(timeout);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXECUTION_REPORT_FIXTURE: &str = r#"{
+        "e": "executionReport", "E": 1499405658658, "s": "ETHBTC", "c": "mUvoqJxFIILMdfAW5iGSOW",
+        "S": "BUY", "o": "LIMIT", "f": "GTC", "q": "1.00000000", "p": "0.10264410", "P": "0.00000000",
+        "F": "0.00000000", "g": -1, "C": "", "x": "TRADE", "X": "PARTIALLY_FILLED", "r": "NONE",
+        "i": 4293153, "l": "0.25000000", "z": "0.25000000", "L": "0.10264400", "n": "0.00002500",
+        "N": "ETH", "T": 1499405658657, "t": 77, "I": 8641984, "w": false, "m": true, "M": true,
+        "O": 1499405658657, "Z": "0.02566100", "Y": "0.02566100", "Q": "0.00000000",
+        "W": 1499405658657, "V": "NONE"
+    }"#;
+
+    const ACCOUNT_POSITION_FIXTURE: &str = r#"{
+        "e": "outboundAccountPosition", "E": 1564034571105, "u": 1564034571073,
+        "B": [
+            {"a": "ETH", "f": "10000.000000", "l": "0.000000"},
+            {"a": "BTC", "f": "1.500000", "l": "0.250000"}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_execution_report() {
+        let update = match parse_user_data_event(EXECUTION_REPORT_FIXTURE).unwrap() {
+            Some(UserDataEvent::Order(update)) => update,
+            other => panic!("expected order update, got {:?}", other),
+        };
+
+        assert_eq!(update.symbol, "ETHBTC");
+        assert_eq!(update.order_id, 4293153);
+        assert_eq!(update.client_order_id, "mUvoqJxFIILMdfAW5iGSOW");
+        assert_eq!(update.side, BinanceOrderSide::BUY);
+        assert_eq!(update.status, BinanceOrderStatus::PARTIALLY_FILLED);
+        assert_eq!(update.execution_type, "TRADE");
+        assert_eq!(update.quantity, 1.0);
+        assert_eq!(update.price, 0.1026441);
+        assert_eq!(update.last_filled_quantity, 0.25);
+        assert_eq!(update.cumulative_filled_quantity, 0.25);
+        assert_eq!(update.last_filled_price, 0.102644);
+        assert_eq!(update.transaction_time, 1499405658657);
+    }
+
+    #[test]
+    fn test_parse_account_position_and_unknown_events() {
+        let update = match parse_user_data_event(ACCOUNT_POSITION_FIXTURE).unwrap() {
+            Some(UserDataEvent::Balance(update)) => update,
+            other => panic!("expected balance update, got {:?}", other),
+        };
+        assert_eq!(update.balances.len(), 2);
+        assert_eq!(update.balances[1].asset, "BTC");
+        assert_eq!(update.balances[1].free, 1.5);
+        assert_eq!(update.balances[1].locked, 0.25);
+
+        let expired = r#"{"e":"listenKeyExpired","E":1576653824250,"listenKey":"OfYGbUzi3PraNagEkdKuFwUHn48brFsItTdsuiIXrucEvD0rhRXZ7I6URWfE8YE8"}"#;
+        assert!(parse_user_data_event(expired).unwrap().is_none());
+        assert!(parse_user_data_event("{").is_err());
+    }
+
+    #[test]
+    fn test_keepalive_schedule() {
+        let start = Instant::now();
+        let mut schedule = KeepaliveSchedule::new(LISTEN_KEY_KEEPALIVE_INTERVAL, start);
+
+        assert!(!schedule.is_due(start));
+        assert!(!schedule.is_due(start + Duration::from_secs(29 * 60 + 59)));
+        assert!(schedule.is_due(start + Duration::from_secs(30 * 60)));
+
+        // A late keepalive pushes the next one out from when it was actually sent
+        let sent_at = start + Duration::from_secs(31 * 60);
+        schedule.mark_sent(sent_at);
+        assert_eq!(schedule.next_due(), sent_at + LISTEN_KEY_KEEPALIVE_INTERVAL);
+        assert!(!schedule.is_due(start + Duration::from_secs(60 * 60)));
+        assert!(schedule.is_due(start + Duration::from_secs(61 * 60)));
+    }
+}
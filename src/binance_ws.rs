@@ -1,8 +1,20 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::mpsc as std_mpsc;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use crate::binance_rest::BinanceRestClient;
 use crate::order_book::OrderBook;
 use crate::order::OrderSide;
+use crate::trade::Trade;
+
+/// Levels a side fetched when a diff-depth book resyncs.
+pub const RESYNC_DEPTH_LIMIT: u16 = 1000;
 
 pub struct BinanceWebSocketClient {
     pub symbol: String,
@@ -19,6 +31,237 @@ pub struct BinanceWebSocketClient {
     pub depth_snapshot: Option<DepthSnapshot>,
     pub buffered_events: Vec<DepthUpdateEvent>,
     pub last_update_id: u64,
+    /// Set from a gap in depth update ids until a snapshot reloads the book;
+    /// diffs arriving meanwhile wait in `buffered_events`.
+    pub awaiting_snapshot: bool,
+    /// Where resync snapshots are fetched from.
+    pub rest: BinanceRestClient,
+    pub metrics: StreamMetrics,
+    pub next_request_id: u64,
+}
+
+/// Market streams the client knows how to subscribe to and apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamType {
+    /// Incremental `@depth@100ms` diffs.
+    Depth,
+    /// `@depth20@100ms` partial-book snapshots that replace the local book.
+    Depth20,
+    Trade,
+    BookTicker,
+}
+
+impl StreamType {
+    pub fn stream_name(&self, symbol: &str) -> String {
+        let symbol = symbol.to_lowercase();
+        match self {
+            StreamType::Depth => format!("{}@depth@100ms", symbol),
+            StreamType::Depth20 => format!("{}@depth20@100ms", symbol),
+            StreamType::Trade => format!("{}@trade", symbol),
+            StreamType::BookTicker => format!("{}@bookTicker", symbol),
+        }
+    }
+
+    /// Recognizes a stream name such as `btcusdt@depth20@100ms`.
+    pub fn from_stream_name(stream: &str) -> Option<Self> {
+        let (_, kind) = stream.split_once('@')?;
+        let kind = kind.split('@').next()?;
+        match kind {
+            "depth" => Some(StreamType::Depth),
+            "depth20" => Some(StreamType::Depth20),
+            "trade" => Some(StreamType::Trade),
+            "bookTicker" => Some(StreamType::BookTicker),
+            _ => None,
+        }
+    }
+}
+
+/// Fixed-capacity ring buffer of latency samples; once full, the oldest
+/// sample is evicted so memory stays bounded on long-running streams.
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    samples: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl LatencyHistogram {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn record(&mut self, value: u64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Nearest-rank percentile over the retained samples.
+    pub fn percentile(&self, percentile: f64) -> Option<u64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+
+        let rank = ((percentile / 100.0) * sorted.len() as f64).ceil() as usize;
+        let index = rank.clamp(1, sorted.len()) - 1;
+        Some(sorted[index])
+    }
+
+    pub fn p50(&self) -> Option<u64> {
+        self.percentile(50.0)
+    }
+
+    pub fn p99(&self) -> Option<u64> {
+        self.percentile(99.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StreamRate {
+    pub total_messages: u64,
+    pub messages_per_sec: f64,
+    window_start_ms: u64,
+    window_count: u64,
+}
+
+impl StreamRate {
+    fn record(&mut self, receive_time_ms: u64) {
+        if self.total_messages == 0 {
+            self.window_start_ms = receive_time_ms;
+        }
+
+        self.total_messages += 1;
+        self.window_count += 1;
+
+        let elapsed_ms = receive_time_ms.saturating_sub(self.window_start_ms);
+        if elapsed_ms >= 1000 {
+            self.messages_per_sec = self.window_count as f64 * 1000.0 / elapsed_ms as f64;
+            self.window_start_ms = receive_time_ms;
+            self.window_count = 0;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamMetrics {
+    pub streams: HashMap<String, StreamRate>,
+    pub parse_failures: u64,
+    /// Depth levels skipped because their price or quantity was malformed.
+    pub malformed_levels: u64,
+    /// Times a gap in depth update ids cleared the book for a resync.
+    pub resyncs: u64,
+    /// Event time (`E`) to local receive time, in milliseconds.
+    pub end_to_end_latency_ms: LatencyHistogram,
+    /// Time spent applying a depth update to the local book, in microseconds.
+    pub depth_apply_latency_us: LatencyHistogram,
+}
+
+impl StreamMetrics {
+    pub const HISTOGRAM_CAPACITY: usize = 1024;
+
+    pub fn new() -> Self {
+        Self {
+            streams: HashMap::new(),
+            parse_failures: 0,
+            malformed_levels: 0,
+            resyncs: 0,
+            end_to_end_latency_ms: LatencyHistogram::new(Self::HISTOGRAM_CAPACITY),
+            depth_apply_latency_us: LatencyHistogram::new(Self::HISTOGRAM_CAPACITY),
+        }
+    }
+
+    pub fn record_message(&mut self, stream: &str, event_time_ms: Option<u64>, receive_time_ms: u64) {
+        self.streams
+            .entry(stream.to_string())
+            .or_default()
+            .record(receive_time_ms);
+
+        if let Some(event_time_ms) = event_time_ms {
+            self.end_to_end_latency_ms
+                .record(receive_time_ms.saturating_sub(event_time_ms));
+        }
+    }
+
+    pub fn record_parse_failure(&mut self) {
+        self.parse_failures += 1;
+    }
+
+    pub fn record_malformed_level(&mut self) {
+        self.malformed_levels += 1;
+    }
+
+    pub fn record_resync(&mut self) {
+        self.resyncs += 1;
+    }
+
+    pub fn record_depth_apply(&mut self, elapsed: Duration) {
+        self.depth_apply_latency_us.record(elapsed.as_micros() as u64);
+    }
+
+    pub fn total_messages(&self) -> u64 {
+        self.streams.values().map(|s| s.total_messages).sum()
+    }
+
+    pub fn messages_per_sec(&self) -> f64 {
+        self.streams.values().map(|s| s.messages_per_sec).sum()
+    }
+}
+
+impl Default for StreamMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Why `parse_level` rejected a depth level; holds the offending string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelParseError {
+    /// Not a positive, finite number.
+    Price(String),
+    /// Not a finite number of zero or more.
+    Quantity(String),
+}
+
+impl fmt::Display for LevelParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelParseError::Price(text) => write!(f, "invalid level price '{}'", text),
+            LevelParseError::Quantity(text) => write!(f, "invalid level quantity '{}'", text),
+        }
+    }
+}
+
+impl std::error::Error for LevelParseError {}
+
+/// A depth level's `[price, quantity]` strings as numbers. A zero quantity
+/// is valid: it removes the level.
+pub fn parse_level(entry: &[String; 2]) -> Result<(f64, f64), LevelParseError> {
+    let [price, quantity] = entry;
+    let price_value = price
+        .parse::<f64>()
+        .ok()
+        .filter(|price| price.is_finite() && *price > 0.0)
+        .ok_or_else(|| LevelParseError::Price(price.clone()))?;
+    let quantity_value = quantity
+        .parse::<f64>()
+        .ok()
+        .filter(|quantity| quantity.is_finite() && *quantity >= 0.0)
+        .ok_or_else(|| LevelParseError::Quantity(quantity.clone()))?;
+    Ok((price_value, quantity_value))
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -82,6 +325,46 @@ pub struct BookTickerEvent {
     pub best_ask_qty: String,
 }
 
+/// Envelope used on `/stream?streams=...` connections:
+/// `{"stream":"btcusdt@depth","data":{...}}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CombinedStreamMessage<T> {
+    pub stream: String,
+    pub data: T,
+}
+
+/// A stream payload parsed into the event type its stream name implies.
+#[derive(Debug, Clone)]
+pub enum MarketEvent {
+    DepthUpdate(DepthUpdateEvent),
+    DepthSnapshot(DepthSnapshot),
+    Trade(TradeEvent),
+    BookTicker(BookTickerEvent),
+}
+
+impl MarketEvent {
+    pub fn event_time(&self) -> Option<u64> {
+        match self {
+            MarketEvent::DepthUpdate(event) => Some(event.event_time),
+            MarketEvent::Trade(event) => Some(event.event_time),
+            MarketEvent::DepthSnapshot(_) | MarketEvent::BookTicker(_) => None,
+        }
+    }
+}
+
+/// Parses `data` according to the `stream` it arrived on. Streams the client
+/// does not handle yield `Ok(None)`.
+pub fn parse_stream_event(stream: &str, data: serde_json::Value) -> Result<Option<MarketEvent>, serde_json::Error> {
+    let event = match StreamType::from_stream_name(stream) {
+        Some(StreamType::Depth) => MarketEvent::DepthUpdate(serde_json::from_value(data)?),
+        Some(StreamType::Depth20) => MarketEvent::DepthSnapshot(serde_json::from_value(data)?),
+        Some(StreamType::Trade) => MarketEvent::Trade(serde_json::from_value(data)?),
+        Some(StreamType::BookTicker) => MarketEvent::BookTicker(serde_json::from_value(data)?),
+        None => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
 #[derive(Debug, Serialize)]
 pub struct BinanceSubscribeRequest {
     pub method: String,
@@ -119,16 +402,200 @@ impl BinanceWebSocketClient {
             depth_snapshot: None,
             buffered_events: Vec::new(),
             last_update_id: 0,
+            awaiting_snapshot: false,
+            rest: BinanceRestClient::new(),
+            metrics: StreamMetrics::new(),
+            next_request_id: 1,
+        }
+    }
+
+    /// Builds a `SUBSCRIBE` request for `streams` on this client's symbol.
+    pub fn subscribe(&mut self, streams: Vec<StreamType>) -> BinanceSubscribeRequest {
+        let id = self.next_request_id;
+        self.next_request_id += 1;
+
+        BinanceSubscribeRequest {
+            method: "SUBSCRIBE".to_string(),
+            params: streams.iter().map(|stream| stream.stream_name(&self.symbol)).collect(),
+            id,
+        }
+    }
+
+    pub fn metrics(&self) -> StreamMetrics {
+        self.metrics.clone()
+    }
+
+    /// Parses a raw stream payload, records rate/latency metrics for it and
+    /// applies depth updates to the local order book.
+    pub fn handle_message(&mut self, stream: &str, payload: &str) -> Result<(), serde_json::Error> {
+        let data: serde_json::Value = self.parse_event(payload)?;
+        self.handle_stream_data(stream, data)
+    }
+
+    /// Handles a message from a combined-stream connection, routing on its
+    /// `stream` field.
+    pub fn handle_combined_message(&mut self, text: &str) -> Result<(), serde_json::Error> {
+        let message: CombinedStreamMessage<serde_json::Value> = self.parse_event(text)?;
+        self.handle_stream_data(&message.stream, message.data)
+    }
+
+    fn handle_stream_data(&mut self, stream: &str, data: serde_json::Value) -> Result<(), serde_json::Error> {
+        let _span = tracing::trace_span!("stream_message", stream).entered();
+        let receive_time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        let event = parse_stream_event(stream, data).inspect_err(|_| self.metrics.record_parse_failure())?;
+        self.metrics.record_message(stream, event.as_ref().and_then(MarketEvent::event_time), receive_time_ms);
+
+        match event {
+            Some(MarketEvent::DepthUpdate(event)) => {
+                let started = Instant::now();
+                self.apply_depth_update(&event);
+                self.metrics.record_depth_apply(started.elapsed());
+            }
+            Some(MarketEvent::DepthSnapshot(snapshot)) => {
+                let started = Instant::now();
+                self.apply_depth_snapshot(&snapshot, receive_time_ms);
+                self.metrics.record_depth_apply(started.elapsed());
+            }
+            Some(MarketEvent::BookTicker(event)) => self.apply_book_ticker(&event, receive_time_ms),
+            Some(MarketEvent::Trade(event)) => self.apply_trade(&event),
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn parse_event<T: DeserializeOwned>(&mut self, payload: &str) -> Result<T, serde_json::Error> {
+        serde_json::from_str(payload).inspect_err(|_| self.metrics.record_parse_failure())
+    }
+
+    /// Records a print from the trade stream in the local book's trade
+    /// history. Binance does not say which orders traded, so both ids are 0.
+    pub fn apply_trade(&mut self, event: &TradeEvent) {
+        let (Ok(price), Ok(quantity)) = (event.price.parse::<f64>(), event.quantity.parse::<f64>()) else {
+            return;
+        };
+        // The buyer resting on the book means the seller crossed the spread
+        let aggressor_side = if event.is_buyer_maker { OrderSide::Ask } else { OrderSide::Bid };
+        self.order_book.record_trades(&[Trade {
+            bid_order_id: 0,
+            ask_order_id: 0,
+            price,
+            quantity,
+            timestamp: event.trade_time,
+            aggressor_side,
+            maker_fee: 0.0,
+            taker_fee: 0.0,
+        }]);
+    }
+
+    /// Applies a diff from the `@depth` stream. Diffs the book already holds
+    /// are skipped; one that does not follow on from the last update id means
+    /// updates were lost, so the book is cleared and diffs are buffered until
+    /// `apply_depth_snapshot` reloads it.
+    pub fn apply_depth_update(&mut self, event: &DepthUpdateEvent) {
+        if self.awaiting_snapshot {
+            self.buffered_events.push(event.clone());
+            return;
+        }
+        if event.final_update_id <= self.last_update_id {
+            return;
+        }
+        if self.last_update_id != 0 && event.first_update_id > self.last_update_id + 1 {
+            tracing::warn!(
+                expected = self.last_update_id + 1,
+                received = event.first_update_id,
+                "gap in depth updates; resyncing from a snapshot"
+            );
+            self.metrics.record_resync();
+            self.order_book.clear();
+            self.awaiting_snapshot = true;
+            self.buffered_events.push(event.clone());
+            return;
+        }
+
+        for (levels, side) in [(&event.bids, OrderSide::Bid), (&event.asks, OrderSide::Ask)] {
+            self.apply_levels(side, levels, event.event_time);
+        }
+        self.last_update_id = event.final_update_id;
+    }
+
+    /// Fetches a REST snapshot for a book waiting on a resync and applies it.
+    /// On failure the book keeps waiting and the next diff tries again.
+    pub async fn resync_from_rest(&mut self) {
+        match self.rest.get_depth(&self.symbol, RESYNC_DEPTH_LIMIT).await {
+            Ok(snapshot) => {
+                let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_millis() as u64);
+                self.apply_depth_snapshot(&snapshot, now_ms);
+                tracing::info!(symbol = %self.symbol, last_update_id = self.last_update_id, "depth resynced");
+            }
+            Err(error) => tracing::warn!(%error, "depth resync failed; retrying on the next update"),
+        }
+    }
+
+    /// Sets each level on `side`. Malformed levels are skipped and counted
+    /// in the metrics; the rest of the message still applies.
+    fn apply_levels(&mut self, side: OrderSide, levels: &[[String; 2]], timestamp: u64) {
+        for entry in levels {
+            match parse_level(entry) {
+                Ok((price, quantity)) => self.order_book.set_level_quantity(side, price, quantity, timestamp),
+                Err(error) => {
+                    self.metrics.record_malformed_level();
+                    tracing::warn!(?side, %error, "skipping malformed depth level");
+                }
+            }
+        }
+    }
+
+    /// Replaces the local book with a partial-depth snapshot, then replays
+    /// any diffs buffered while a resync waited for it.
+    pub fn apply_depth_snapshot(&mut self, snapshot: &DepthSnapshot, timestamp: u64) {
+        self.order_book.clear();
+        for (levels, side) in [(&snapshot.bids, OrderSide::Bid), (&snapshot.asks, OrderSide::Ask)] {
+            self.apply_levels(side, levels, timestamp);
+        }
+        self.last_update_id = snapshot.lastUpdateId;
+        self.awaiting_snapshot = false;
+        for event in std::mem::take(&mut self.buffered_events) {
+            self.apply_depth_update(&event);
+        }
+    }
+
+    /// Makes the ticker's prices the top of book, dropping any local levels
+    /// that would sit better than them.
+    pub fn apply_book_ticker(&mut self, event: &BookTickerEvent, timestamp: u64) {
+        let quotes = [
+            (OrderSide::Bid, &event.best_bid_price, &event.best_bid_qty),
+            (OrderSide::Ask, &event.best_ask_price, &event.best_ask_qty),
+        ];
+
+        for (side, price, quantity) in quotes {
+            let (Ok(price), Ok(quantity)) = (price.parse::<f64>(), quantity.parse::<f64>()) else {
+                continue;
+            };
+
+            loop {
+                let stale_level = match side {
+                    OrderSide::Bid => self.order_book.get_best_bid().filter(|best| *best > price),
+                    OrderSide::Ask => self.order_book.get_best_ask().filter(|best| *best < price),
+                };
+                match stale_level {
+                    Some(level) => self.order_book.set_level_quantity(side, level, 0.0, timestamp),
+                    None => break,
+                }
+            }
+            self.order_book.set_level_quantity(side, price, quantity, timestamp);
         }
     }
 
     pub fn simulate_binance_connection(&self) {
-        println!("🔌 Simulating Binance WebSocket connection...");
-        println!("📡 Would connect to: {}/ws/{}@depth@100ms", 
-            self.base_url, self.symbol.to_lowercase());
-        println!("📊 Would subscribe to: depth updates, trades, book ticker");
-        println!("🏓 Would handle ping/pong every 20 seconds");
-        println!("🔄 Would reconnect automatically on disconnection");
+        tracing::info!(
+            url = %format!("{}/ws/{}@depth@100ms", self.base_url, self.symbol.to_lowercase()),
+            "simulating Binance WebSocket connection; would subscribe to depth, trades and book ticker"
+        );
     }
 
     pub fn display_order_book(&self) {
@@ -155,15 +622,152 @@ impl BinanceWebSocketClient {
     }
 }
 
+/// Combined-stream URL for `streams` on `symbol`, e.g.
+/// `wss://stream.binance.com:9443/stream?streams=btcusdt@depth20@100ms/btcusdt@trade`.
+pub fn combined_stream_url(base_url: &str, symbol: &str, streams: &[StreamType]) -> String {
+    let names: Vec<String> = streams.iter().map(|stream| stream.stream_name(symbol)).collect();
+    format!("{}/stream?streams={}", base_url, names.join("/"))
+}
+
+/// What `MarketStreamHandle` reports back to the UI thread.
+#[derive(Debug, Clone)]
+pub enum MarketStreamEvent {
+    Connected,
+    /// The client's metrics so far, sent about once a second.
+    Metrics(StreamMetrics),
+    /// The socket closed; carries the error if it failed.
+    Closed(Option<String>),
+}
+
+/// Connects `client` to the combined stream for `streams` and applies every
+/// message to its order book until `shutdown` fires or the socket closes.
+pub async fn run_market_stream(
+    mut client: BinanceWebSocketClient,
+    streams: Vec<StreamType>,
+    events: mpsc::UnboundedSender<MarketStreamEvent>,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let url = combined_stream_url(&client.base_url, &client.symbol, &streams);
+    let (mut socket, _) = connect_async(url).await?;
+    client.is_connected = true;
+    tracing::info!(symbol = %client.symbol, "connected to Binance market stream");
+    let _ = events.send(MarketStreamEvent::Connected);
+
+    let mut metrics_tick = tokio::time::interval(Duration::from_secs(1));
+    let result: Result<(), Box<dyn std::error::Error + Send + Sync>> = loop {
+        tokio::select! {
+            message = socket.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    // Malformed payloads are counted in the metrics and skipped
+                    if let Err(error) = client.handle_combined_message(&text) {
+                        tracing::warn!(%error, "skipping malformed stream message");
+                    }
+                    if client.awaiting_snapshot {
+                        client.resync_from_rest().await;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => break Err(e.into()),
+            },
+            _ = metrics_tick.tick() => {
+                if events.send(MarketStreamEvent::Metrics(client.metrics())).is_err() {
+                    break Ok(());
+                }
+            }
+            _ = shutdown.changed() => break Ok(()),
+        }
+    };
+
+    client.is_connected = false;
+    let _ = events.send(MarketStreamEvent::Metrics(client.metrics()));
+    let _ = socket.close(None).await;
+    result
+}
+
+type StreamTask = std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>;
+
+/// Runs `run_market_stream` in the background, either on its own thread or
+/// as a task on a runtime the caller already drives. The book it maintains is
+/// shared, so the UI can read it between frames.
+pub struct MarketStreamHandle {
+    order_book: Arc<OrderBook>,
+    events: mpsc::UnboundedReceiver<MarketStreamEvent>,
+    shutdown: watch::Sender<bool>,
+    finished: std_mpsc::Receiver<()>,
+}
+
+impl MarketStreamHandle {
+    /// Runs the stream on a new thread with its own runtime.
+    pub fn spawn(client: BinanceWebSocketClient, streams: Vec<StreamType>) -> Self {
+        Self::start(client, streams, |task, event_tx| {
+            std::thread::spawn(move || match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(task),
+                Err(e) => {
+                    let _ = event_tx.send(MarketStreamEvent::Closed(Some(e.to_string())));
+                }
+            });
+        })
+    }
+
+    /// Runs the stream as a task on `runtime`.
+    pub fn spawn_on(runtime: &tokio::runtime::Handle, client: BinanceWebSocketClient, streams: Vec<StreamType>) -> Self {
+        Self::start(client, streams, |task, _| {
+            runtime.spawn(task);
+        })
+    }
+
+    fn start<F>(client: BinanceWebSocketClient, streams: Vec<StreamType>, launch: F) -> Self
+    where
+        F: FnOnce(StreamTask, mpsc::UnboundedSender<MarketStreamEvent>),
+    {
+        let order_book = client.order_book.clone();
+        let (event_tx, events) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let (finished_tx, finished) = std_mpsc::channel();
+
+        let task_tx = event_tx.clone();
+        let task: StreamTask = Box::pin(async move {
+            let result = run_market_stream(client, streams, task_tx.clone(), shutdown_rx).await;
+            if let Err(error) = &result {
+                tracing::error!(%error, "Binance market stream failed");
+            }
+            let _ = task_tx.send(MarketStreamEvent::Closed(result.err().map(|e| e.to_string())));
+            let _ = finished_tx.send(());
+        });
+        launch(task, event_tx);
+
+        Self { order_book, events, shutdown, finished }
+    }
+
+    pub fn order_book(&self) -> &Arc<OrderBook> {
+        &self.order_book
+    }
+
+    /// Returns every event received since the last call without blocking.
+    pub fn drain(&mut self) -> Vec<MarketStreamEvent> {
+        let mut drained = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            drained.push(event);
+        }
+        drained
+    }
+
+    /// Signals the stream to close and waits up to `timeout` for it.
+    pub fn stop(&mut self, timeout: Duration) {
+        let _ = self.shutdown.send(true);
+        let _ = self.finished.recv_timeout(timeout);
+    }
+}
+
 pub async fn run_binance_client(symbol: String) -> Result<(), Box<dyn std::error::Error>> {
     let client = BinanceWebSocketClient::new(symbol.clone());
     
-    println!("🚀 Starting Binance WebSocket client for {}", symbol);
-    println!("🔌 Base URL: {}", client.base_url);
-    println!("📡 Streams: depth@100ms, trade, bookTicker");
-    println!("🏓 Ping/Pong: Every 20 seconds");
-    println!("🔄 Auto-reconnect: Enabled");
-    println!("⚠️  Note: This is a simulated client for demonstration");
+    tracing::info!(
+        %symbol,
+        base_url = %client.base_url,
+        "starting simulated Binance WebSocket client on depth@100ms, trade and bookTicker"
+    );
     
     client.simulate_binance_connection();
     
@@ -173,6 +777,9 @@ pub async fn run_binance_client(symbol: String) -> Result<(), Box<dyn std::error
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+    use futures_util::SinkExt;
 
     #[test]
     fn test_binance_client_creation() {
@@ -182,6 +789,48 @@ mod tests {
         assert_eq!(client.order_book.get_total_price_levels(), (0, 0));
     }
 
+    #[test]
+    fn test_combined_stream_url() {
+        assert_eq!(
+            combined_stream_url("wss://stream.binance.com:9443", "BTCUSDT", &[StreamType::Depth20, StreamType::Trade]),
+            "wss://stream.binance.com:9443/stream?streams=btcusdt@depth20@100ms/btcusdt@trade"
+        );
+    }
+
+    #[test]
+    fn test_market_stream_handle_applies_snapshots() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        runtime.spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            let snapshot = r#"{"stream":"btcusdt@depth20@100ms","data":{"lastUpdateId":7,"bids":[["100.5","2.0"]],"asks":[["101.0","1.5"]]}}"#;
+            socket.send(Message::Text(snapshot.to_string())).await.unwrap();
+            let _ = socket.next().await;
+        });
+
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+        client.base_url = url;
+        let mut handle = MarketStreamHandle::spawn(client, vec![StreamType::Depth20]);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while handle.order_book().get_best_ask().is_none() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(handle.order_book().get_best_bid(), Some(100.5));
+        assert_eq!(handle.order_book().get_best_ask(), Some(101.0));
+        assert!(matches!(handle.drain().first(), Some(MarketStreamEvent::Connected)));
+
+        handle.stop(Duration::from_secs(2));
+        let events = handle.drain();
+        assert!(matches!(events.last(), Some(MarketStreamEvent::Closed(None))));
+        match &events[events.len() - 2] {
+            MarketStreamEvent::Metrics(metrics) => assert_eq!(metrics.total_messages(), 1),
+            other => panic!("expected final metrics, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_subscribe_request_serialization() {
         let request = BinanceSubscribeRequest {
@@ -195,6 +844,119 @@ mod tests {
         assert!(json.contains("btcusdt@depth20@100ms"));
     }
 
+    #[test]
+    fn test_subscribe_builds_stream_params() {
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+
+        let request = client.subscribe(vec![StreamType::Depth20, StreamType::BookTicker]);
+        assert_eq!(request.method, "SUBSCRIBE");
+        assert_eq!(request.params, vec!["btcusdt@depth20@100ms", "btcusdt@bookTicker"]);
+        assert_eq!(request.id, 1);
+
+        let request = client.subscribe(vec![StreamType::Depth, StreamType::Trade]);
+        assert_eq!(request.params, vec!["btcusdt@depth@100ms", "btcusdt@trade"]);
+        assert_eq!(request.id, 2);
+
+        for stream in [StreamType::Depth, StreamType::Depth20, StreamType::Trade, StreamType::BookTicker] {
+            assert_eq!(StreamType::from_stream_name(&stream.stream_name("ETHUSDT")), Some(stream));
+        }
+        assert_eq!(StreamType::from_stream_name("btcusdt@kline_1m"), None);
+    }
+
+    #[test]
+    fn test_apply_depth20_snapshot_resets_book() {
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+        client.order_book.add_order(OrderSide::Bid, 40000.0, 1.0, 1);
+        client.order_book.add_order(OrderSide::Ask, 60000.0, 1.0, 2);
+
+        let snapshot = r#"{"lastUpdateId":160,
+            "bids":[["50000.00","1.5"],["49999.00","2.0"]],
+            "asks":[["50001.00","1.0"],["50002.00","2.5"]]}"#;
+        client.handle_message("btcusdt@depth20@100ms", snapshot).unwrap();
+
+        assert_eq!(client.last_update_id, 160);
+        assert_eq!(client.order_book.get_total_price_levels(), (2, 2));
+        assert_eq!(client.order_book.get_best_bid(), Some(50000.0));
+        assert_eq!(client.order_book.get_best_ask(), Some(50001.0));
+        let (bids, _) = client.order_book.get_market_depth(2);
+        assert_eq!(bids[1], (49999.0, 2.0));
+    }
+
+    #[test]
+    fn test_book_ticker_sets_top_of_book() {
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+        client.order_book.set_level_quantity(OrderSide::Bid, 50000.0, 1.0, 1);
+        client.order_book.set_level_quantity(OrderSide::Bid, 49990.0, 1.0, 1);
+        client.order_book.set_level_quantity(OrderSide::Ask, 50010.0, 1.0, 1);
+
+        let ticker = r#"{"u":400900217,"s":"BTCUSDT","b":"49995.00","B":"3.0","a":"50005.00","A":"0.5"}"#;
+        client.handle_message("btcusdt@bookTicker", ticker).unwrap();
+
+        assert_eq!(client.order_book.get_best_bid(), Some(49995.0));
+        assert_eq!(client.order_book.get_best_ask(), Some(50005.0));
+        assert_eq!(client.order_book.get_total_price_levels(), (2, 2));
+    }
+
+    #[test]
+    fn test_combined_stream_deserialization() {
+        let depth: CombinedStreamMessage<DepthUpdateEvent> = serde_json::from_str(
+            r#"{"stream":"btcusdt@depth","data":{"e":"depthUpdate","E":1,"s":"BTCUSDT","U":1,"u":2,
+                "b":[["50000.00","1.5"]],"a":[]}}"#,
+        ).unwrap();
+        assert_eq!(depth.stream, "btcusdt@depth");
+        assert_eq!(depth.data.final_update_id, 2);
+
+        let trade: CombinedStreamMessage<TradeEvent> = serde_json::from_str(
+            r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":2,"s":"BTCUSDT","t":12345,
+                "p":"50000.50","q":"0.25","T":2,"m":true}}"#,
+        ).unwrap();
+        assert_eq!(trade.data.trade_id, 12345);
+        assert!(trade.data.is_buyer_maker);
+
+        let ticker: CombinedStreamMessage<BookTickerEvent> = serde_json::from_str(
+            r#"{"stream":"btcusdt@bookTicker","data":{"u":7,"s":"BTCUSDT","b":"49999.00","B":"1.0","a":"50001.00","A":"2.0"}}"#,
+        ).unwrap();
+        assert_eq!(ticker.data.best_ask_qty, "2.0");
+    }
+
+    #[test]
+    fn test_combined_stream_routing() {
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+
+        client.handle_combined_message(
+            r#"{"stream":"btcusdt@depth","data":{"e":"depthUpdate","E":1,"s":"BTCUSDT","U":1,"u":2,
+                "b":[["50000.00","1.5"]],"a":[["50010.00","1.0"]]}}"#,
+        ).unwrap();
+        assert_eq!(client.last_update_id, 2);
+        assert_eq!(client.order_book.get_best_bid(), Some(50000.0));
+
+        client.handle_combined_message(
+            r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":2,"s":"BTCUSDT","t":1,
+                "p":"50005.00","q":"0.1","T":2,"m":false}}"#,
+        ).unwrap();
+        let trades = client.order_book.recent_trades(10);
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].price, trades[0].quantity, trades[0].timestamp), (50005.0, 0.1, 2));
+        assert_eq!(trades[0].aggressor_side, OrderSide::Bid);
+        client.handle_combined_message(
+            r#"{"stream":"btcusdt@bookTicker","data":{"u":3,"s":"BTCUSDT","b":"50001.00","B":"2.0","a":"50002.00","A":"1.0"}}"#,
+        ).unwrap();
+        assert_eq!(client.order_book.get_best_bid(), Some(50001.0));
+        assert_eq!(client.order_book.get_best_ask(), Some(50002.0));
+
+        // A trade payload on a depth stream is a parse failure, not a misrouted event
+        assert!(client.handle_combined_message(
+            r#"{"stream":"btcusdt@depth","data":{"e":"trade","E":2,"s":"BTCUSDT","t":1}}"#,
+        ).is_err());
+
+        let metrics = client.metrics();
+        assert_eq!(metrics.total_messages(), 3);
+        assert_eq!(metrics.parse_failures, 1);
+        for stream in ["btcusdt@depth", "btcusdt@trade", "btcusdt@bookTicker"] {
+            assert_eq!(metrics.streams[stream].total_messages, 1);
+        }
+    }
+
     #[test]
     fn test_depth_snapshot_deserialization() {
         let json = r#"{
@@ -208,4 +970,144 @@ mod tests {
         assert_eq!(snapshot.bids.len(), 2);
         assert_eq!(snapshot.asks.len(), 2);
     }
+
+    #[test]
+    fn test_latency_histogram_percentiles() {
+        let mut histogram = LatencyHistogram::new(100);
+        assert_eq!(histogram.p50(), None);
+
+        for value in 1..=100 {
+            histogram.record(value);
+        }
+        assert_eq!(histogram.p50(), Some(50));
+        assert_eq!(histogram.p99(), Some(99));
+        assert_eq!(histogram.percentile(100.0), Some(100));
+
+        // Ring buffer evicts the oldest samples once full
+        for value in 101..=200 {
+            histogram.record(value);
+        }
+        assert_eq!(histogram.len(), 100);
+        assert_eq!(histogram.p50(), Some(150));
+        assert_eq!(histogram.p99(), Some(199));
+    }
+
+    #[test]
+    fn test_stream_metrics_from_messages() {
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+        let depth = r#"{"e":"depthUpdate","E":1,"s":"BTCUSDT","U":1,"u":2,
+            "b":[["50000.00","1.5"]],"a":[["50001.00","2.0"]]}"#;
+
+        client.handle_message("btcusdt@depth@100ms", depth).unwrap();
+        assert!(client.handle_message("btcusdt@trade", "not json").is_err());
+
+        let metrics = client.metrics();
+        assert_eq!(metrics.total_messages(), 1);
+        assert_eq!(metrics.parse_failures, 1);
+        assert_eq!(metrics.end_to_end_latency_ms.len(), 1);
+        assert_eq!(metrics.depth_apply_latency_us.len(), 1);
+        assert_eq!(client.order_book.get_best_bid(), Some(50000.0));
+        assert_eq!(client.order_book.get_best_ask(), Some(50001.0));
+        assert_eq!(client.last_update_id, 2);
+    }
+
+    #[test]
+    fn test_malformed_depth_levels_are_skipped_and_counted() {
+        let level = |price: &str, quantity: &str| [price.to_string(), quantity.to_string()];
+        assert_eq!(parse_level(&level("50000.10", "0")), Ok((50000.1, 0.0)));
+        assert_eq!(parse_level(&level("abc", "1.0")), Err(LevelParseError::Price("abc".to_string())));
+        assert_eq!(parse_level(&level("-5", "1.0")), Err(LevelParseError::Price("-5".to_string())));
+        assert_eq!(parse_level(&level("100", "NaN")), Err(LevelParseError::Quantity("NaN".to_string())));
+        assert_eq!(parse_level(&level("100", "-1")).unwrap_err().to_string(), "invalid level quantity '-1'");
+
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+        let depth = r#"{"e":"depthUpdate","E":1,"s":"BTCUSDT","U":1,"u":2,
+            "b":[["abc","1.5"],["49999.00","2.0"]],"a":[["50001.00","lots"],["50002.00","1.0"]]}"#;
+        client.handle_message("btcusdt@depth@100ms", depth).unwrap();
+        assert_eq!(client.metrics.malformed_levels, 2);
+        assert_eq!(client.metrics.parse_failures, 0);
+        assert_eq!(client.order_book.get_market_depth(5), (vec![(49999.0, 2.0)], vec![(50002.0, 1.0)]));
+
+        client.apply_depth_snapshot(
+            &DepthSnapshot {
+                lastUpdateId: 3,
+                bids: vec![level("", "1.0"), level("49998.00", "1.0")],
+                asks: vec![],
+            },
+            2,
+        );
+        assert_eq!(client.metrics.malformed_levels, 3);
+        assert_eq!(client.order_book.get_best_bid(), Some(49998.0));
+    }
+
+    fn depth_update(first: u64, last: u64, bids: &[(&str, &str)]) -> DepthUpdateEvent {
+        DepthUpdateEvent {
+            event_type: "depthUpdate".to_string(),
+            event_time: 1,
+            symbol: "BTCUSDT".to_string(),
+            first_update_id: first,
+            final_update_id: last,
+            bids: bids.iter().map(|(price, quantity)| [price.to_string(), quantity.to_string()]).collect(),
+            asks: vec![],
+        }
+    }
+
+    #[test]
+    fn test_depth_update_gap_clears_the_book_until_a_snapshot() {
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+        let snapshot = |id: u64, bids: &[(&str, &str)]| DepthSnapshot {
+            lastUpdateId: id,
+            bids: bids.iter().map(|(price, quantity)| [price.to_string(), quantity.to_string()]).collect(),
+            asks: vec![],
+        };
+        client.apply_depth_snapshot(&snapshot(10, &[("100.0", "1.0")]), 1);
+        client.apply_depth_update(&depth_update(11, 12, &[("99.0", "2.0")]));
+        // Already applied: ignored
+        client.apply_depth_update(&depth_update(9, 12, &[("99.0", "5.0")]));
+        assert_eq!(client.order_book.get_market_depth(5).0, vec![(100.0, 1.0), (99.0, 2.0)]);
+
+        // 13 and 14 never arrived
+        client.apply_depth_update(&depth_update(15, 16, &[("98.0", "1.0")]));
+        client.apply_depth_update(&depth_update(17, 17, &[("100.0", "0")]));
+        assert!(client.awaiting_snapshot);
+        assert_eq!(client.metrics.resyncs, 1);
+        assert_eq!(client.order_book.get_total_orders(), 0);
+        assert_eq!(client.buffered_events.len(), 2);
+
+        // The snapshot covers up to 15; the buffered diffs carry on from it
+        client.apply_depth_snapshot(&snapshot(15, &[("100.0", "3.0"), ("97.0", "1.0")]), 2);
+        assert!(!client.awaiting_snapshot);
+        assert!(client.buffered_events.is_empty());
+        assert_eq!(client.last_update_id, 17);
+        assert_eq!(client.order_book.get_market_depth(5).0, vec![(98.0, 1.0), (97.0, 1.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_resync_reloads_the_book_from_a_rest_snapshot() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/depth"))
+            .and(query_param("symbol", "BTCUSDT"))
+            .and(query_param("limit", "1000"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"lastUpdateId":20,"bids":[["100.0","4.0"]],"asks":[["101.0","2.0"]]}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+        client.rest = BinanceRestClient::with_base_url(&server.uri());
+        client.apply_depth_update(&depth_update(1, 5, &[("100.0", "1.0")]));
+        client.apply_depth_update(&depth_update(9, 21, &[("99.0", "1.0")]));
+        assert!(client.awaiting_snapshot);
+
+        client.resync_from_rest().await;
+        assert!(!client.awaiting_snapshot);
+        assert_eq!(client.last_update_id, 21);
+        assert_eq!(client.order_book.get_market_depth(5), (vec![(100.0, 4.0), (99.0, 1.0)], vec![(101.0, 2.0)]));
+    }
 }
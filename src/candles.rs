@@ -0,0 +1,226 @@
+use crate::trade::Trade;
+use crate::ui::{Candlestick, ChartTimeframe};
+use chrono::{DateTime, TimeZone, Utc};
+use std::time::Duration;
+
+/// Minute candles kept as the base series for every chart timeframe: 30 days.
+pub const MINUTE_CANDLE_CAPACITY: usize = 30 * 24 * 60;
+
+/// Start of the `timeframe` bucket holding `timestamp`. Buckets are aligned to
+/// the Unix epoch, so hourly candles open on the hour and daily ones at 00:00 UTC.
+pub fn bucket_start(timestamp: DateTime<Utc>, timeframe: &ChartTimeframe) -> DateTime<Utc> {
+    let width = timeframe.duration().num_seconds();
+    let start = timestamp.timestamp().div_euclid(width) * width;
+    Utc.timestamp_opt(start, 0).single().unwrap_or(timestamp)
+}
+
+/// Aggregates `candles` (oldest first) into `timeframe` candles: the first
+/// open, highest high, lowest low, last close and summed volume of each
+/// bucket, stamped with the bucket's start. Buckets with no candles are left
+/// out rather than filled in.
+pub fn resample(candles: &[Candlestick], timeframe: &ChartTimeframe) -> Vec<Candlestick> {
+    let mut resampled: Vec<Candlestick> = Vec::new();
+    for candle in candles {
+        let start = bucket_start(candle.timestamp, timeframe);
+        match resampled.last_mut() {
+            Some(bucket) if bucket.timestamp == start => {
+                bucket.high = bucket.high.max(candle.high);
+                bucket.low = bucket.low.min(candle.low);
+                bucket.close = candle.close;
+                bucket.volume += candle.volume;
+            }
+            _ => resampled.push(Candlestick::new(
+                start,
+                candle.open,
+                candle.high,
+                candle.low,
+                candle.close,
+                candle.volume,
+            )),
+        }
+    }
+    resampled
+}
+
+/// Buckets `trades`, stamped in Unix milliseconds as the Binance feed's are,
+/// into `interval` candles aligned to the epoch: the first trade's price
+/// opens, the last closes and the quantities sum to the volume. Trades are
+/// taken in time order whatever order they are given in, and buckets with no
+/// trades are left out.
+pub fn trades_to_candles(trades: &[Trade], interval: Duration) -> Vec<Candlestick> {
+    let width = (interval.as_millis() as u64).max(1);
+    let mut ordered: Vec<&Trade> = trades.iter().collect();
+    ordered.sort_by_key(|trade| trade.timestamp);
+
+    let mut candles: Vec<Candlestick> = Vec::new();
+    for trade in ordered {
+        let start_millis = trade.timestamp / width * width;
+        let start = Utc.timestamp_millis_opt(start_millis as i64).single().unwrap_or_default();
+        match candles.last_mut() {
+            Some(candle) if candle.timestamp == start => {
+                candle.high = candle.high.max(trade.price);
+                candle.low = candle.low.min(trade.price);
+                candle.close = trade.price;
+                candle.volume += trade.quantity;
+            }
+            _ => candles.push(Candlestick::new(start, trade.price, trade.price, trade.price, trade.price, trade.quantity)),
+        }
+    }
+    candles
+}
+
+/// Folds a trade or price tick into the minute candle it falls in, opening a
+/// new candle when `timestamp` is past the last one. Ticks older than the last
+/// candle are dropped, and at most `MINUTE_CANDLE_CAPACITY` candles are kept.
+pub fn record_tick(candles: &mut Vec<Candlestick>, timestamp: DateTime<Utc>, price: f64, volume: f64) {
+    let start = bucket_start(timestamp, &ChartTimeframe::OneMinute);
+    match candles.last_mut() {
+        Some(last) if last.timestamp == start => {
+            last.high = last.high.max(price);
+            last.low = last.low.min(price);
+            last.close = price;
+            last.volume += volume;
+        }
+        Some(last) if last.timestamp > start => {}
+        _ => candles.push(Candlestick::new(start, price, price, price, price, volume)),
+    }
+
+    let excess = candles.len().saturating_sub(MINUTE_CANDLE_CAPACITY);
+    candles.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, hour, minute, 0).unwrap()
+    }
+
+    fn ohlcv(candle: &Candlestick) -> [f64; 5] {
+        [candle.open, candle.high, candle.low, candle.close, candle.volume]
+    }
+
+    /// Ten minutes from 09:58 to 10:07, each opening at the last close.
+    fn minute_series() -> Vec<Candlestick> {
+        let closes = [100.0, 102.0, 101.0, 105.0, 103.0, 104.0, 99.0, 98.0, 100.0, 101.0];
+        let mut open = 99.0;
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                let timestamp = at(9, 58) + chrono::Duration::minutes(i as i64);
+                let candle = Candlestick::new(timestamp, open, open.max(close) + 1.0, open.min(close) - 1.0, close, i as f64 + 1.0);
+                open = close;
+                candle
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_trades_to_candles() {
+        let trade = |seconds: u64, price: f64, quantity: f64| Trade {
+            bid_order_id: 1,
+            ask_order_id: 2,
+            price,
+            quantity,
+            timestamp: at(10, 0).timestamp_millis() as u64 + seconds * 1000,
+            aggressor_side: crate::order::OrderSide::Bid,
+            maker_fee: 0.0,
+            taker_fee: 0.0,
+        };
+        // Out of order on purpose: the 10:00:50 trade closes the first minute
+        let trades = [
+            trade(5, 100.0, 1.0),
+            trade(50, 101.0, 0.5),
+            trade(20, 103.0, 2.0),
+            trade(30, 99.0, 1.5),
+            trade(61, 102.0, 3.0),
+            trade(119, 104.0, 0.25),
+            trade(75, 101.5, 1.0),
+        ];
+        let candles = trades_to_candles(&trades, Duration::from_secs(60));
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].timestamp, at(10, 0));
+        assert_eq!(ohlcv(&candles[0]), [100.0, 103.0, 99.0, 101.0, 5.0]);
+        assert_eq!(candles[1].timestamp, at(10, 1));
+        assert_eq!(ohlcv(&candles[1]), [102.0, 104.0, 101.5, 104.0, 4.25]);
+
+        // A wider interval folds both minutes into one candle; quiet buckets are skipped
+        let five = trades_to_candles(&trades, Duration::from_secs(300));
+        assert_eq!(five.len(), 1);
+        assert_eq!(ohlcv(&five[0]), [100.0, 104.0, 99.0, 104.0, 9.25]);
+        let gap = trades_to_candles(&[trade(0, 1.0, 1.0), trade(600, 2.0, 1.0)], Duration::from_secs(60));
+        assert_eq!(gap.iter().map(|candle| candle.timestamp).collect::<Vec<_>>(), vec![at(10, 0), at(10, 10)]);
+        assert!(trades_to_candles(&[], Duration::from_secs(60)).is_empty());
+    }
+
+    #[test]
+    fn test_resample_aggregates_buckets() {
+        let minutes = minute_series();
+
+        let five = resample(&minutes, &ChartTimeframe::FiveMinutes);
+        let starts: Vec<DateTime<Utc>> = five.iter().map(|candle| candle.timestamp).collect();
+        assert_eq!(starts, vec![at(9, 55), at(10, 0), at(10, 5)]);
+        // 09:58-09:59, 10:00-10:04 and 10:05-10:07
+        assert_eq!(ohlcv(&five[0]), [99.0, 103.0, 98.0, 102.0, 3.0]);
+        assert_eq!(ohlcv(&five[1]), [102.0, 106.0, 98.0, 99.0, 25.0]);
+        assert_eq!(ohlcv(&five[2]), [99.0, 102.0, 97.0, 101.0, 27.0]);
+
+        let hours = resample(&minutes, &ChartTimeframe::OneHour);
+        assert_eq!(hours.len(), 2);
+        assert_eq!(hours[1].timestamp, at(10, 0));
+        assert_eq!(ohlcv(&hours[1]), [102.0, 106.0, 97.0, 101.0, 52.0]);
+
+        let day = resample(&minutes, &ChartTimeframe::OneDay);
+        assert_eq!(day.len(), 1);
+        assert_eq!(day[0].timestamp, Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+        assert_eq!(ohlcv(&day[0]), [99.0, 106.0, 97.0, 101.0, 55.0]);
+
+        // Resampling is idempotent and 1m leaves minute candles alone
+        let again = resample(&five, &ChartTimeframe::FiveMinutes);
+        assert_eq!(again.iter().map(ohlcv).collect::<Vec<_>>(), five.iter().map(ohlcv).collect::<Vec<_>>());
+        let same = resample(&minutes, &ChartTimeframe::OneMinute);
+        assert_eq!(same.iter().map(ohlcv).collect::<Vec<_>>(), minutes.iter().map(ohlcv).collect::<Vec<_>>());
+        assert!(resample(&[], &ChartTimeframe::OneHour).is_empty());
+    }
+
+    #[test]
+    fn test_resample_skips_gaps() {
+        let mut minutes = minute_series();
+        // Nothing traded from 10:00 to 10:04
+        minutes.retain(|candle| candle.timestamp < at(10, 0) || candle.timestamp >= at(10, 5));
+        let five = resample(&minutes, &ChartTimeframe::FiveMinutes);
+        let starts: Vec<DateTime<Utc>> = five.iter().map(|candle| candle.timestamp).collect();
+        assert_eq!(starts, vec![at(9, 55), at(10, 5)]);
+        assert_eq!(five[1].open, 99.0);
+    }
+
+    #[test]
+    fn test_record_tick_builds_minute_candles() {
+        let mut candles = Vec::new();
+        let seconds = |minute, second| at(10, minute) + chrono::Duration::seconds(second);
+        record_tick(&mut candles, seconds(0, 5), 100.0, 1.0);
+        record_tick(&mut candles, seconds(0, 30), 103.0, 2.0);
+        record_tick(&mut candles, seconds(0, 59), 99.0, 0.5);
+        record_tick(&mut candles, seconds(1, 0), 101.0, 1.0);
+        // Late ticks for a closed minute are ignored
+        record_tick(&mut candles, seconds(0, 40), 90.0, 4.0);
+
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].timestamp, at(10, 0));
+        assert_eq!(ohlcv(&candles[0]), [100.0, 103.0, 99.0, 99.0, 3.5]);
+        assert_eq!(candles[1].timestamp, at(10, 1));
+        assert_eq!(ohlcv(&candles[1]), [101.0, 101.0, 101.0, 101.0, 1.0]);
+
+        let mut full: Vec<Candlestick> = (0..MINUTE_CANDLE_CAPACITY)
+            .map(|i| Candlestick::new(at(0, 0) - chrono::Duration::minutes(i as i64 + 1), 1.0, 1.0, 1.0, 1.0, 1.0))
+            .rev()
+            .collect();
+        let oldest = full[1].timestamp;
+        record_tick(&mut full, at(0, 0), 2.0, 1.0);
+        assert_eq!(full.len(), MINUTE_CANDLE_CAPACITY);
+        assert_eq!(full[0].timestamp, oldest);
+        assert_eq!(full.last().unwrap().close, 2.0);
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::VecDeque;
+
+/// Commands kept in the command bar's history, oldest dropped first.
+pub const COMMAND_HISTORY_CAPACITY: usize = 100;
+
+/// The command bar's input: the line being edited with its cursor, and the
+/// history of submitted commands that Up and Down step through.
+#[derive(Debug, Clone, Default)]
+pub struct CommandLine {
+    line: Vec<char>,
+    /// Insertion point, in chars from the start of the line.
+    cursor: usize,
+    /// Submitted commands, oldest first.
+    history: VecDeque<String>,
+    /// Index into `history` of the recalled command while browsing it.
+    recalled: Option<usize>,
+    /// What was typed before browsing started, given back by stepping past
+    /// the newest command.
+    draft: String,
+}
+
+impl CommandLine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> String {
+        self.line.iter().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.line.is_empty()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The line up to the cursor, e.g. to measure where the cursor is drawn.
+    pub fn before_cursor(&self) -> String {
+        self.line[..self.cursor].iter().collect()
+    }
+
+    /// Replaces the line with `text`, cursor at the end.
+    pub fn set(&mut self, text: &str) {
+        self.line = text.chars().collect();
+        self.cursor = self.line.len();
+        self.recalled = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.set("");
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.line.insert(self.cursor, c);
+        self.cursor += 1;
+        self.recalled = None;
+    }
+
+    /// Deletes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.line.remove(self.cursor);
+            self.recalled = None;
+        }
+    }
+
+    /// Deletes the character under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.line.len() {
+            self.line.remove(self.cursor);
+            self.recalled = None;
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.line.len());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.line.len();
+    }
+
+    /// Takes the line for running, adding it to the history unless it is
+    /// blank or repeats the newest entry.
+    pub fn submit(&mut self) -> String {
+        let command = self.text();
+        self.clear();
+        self.draft.clear();
+        let trimmed = command.trim();
+        if !trimmed.is_empty() && self.history.back().map(String::as_str) != Some(trimmed) {
+            self.history.push_back(trimmed.to_string());
+            if self.history.len() > COMMAND_HISTORY_CAPACITY {
+                self.history.pop_front();
+            }
+        }
+        command
+    }
+
+    /// Submitted commands, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &str> {
+        self.history.iter().map(String::as_str)
+    }
+
+    /// Replaces the history with `commands` (oldest first), keeping the
+    /// newest `COMMAND_HISTORY_CAPACITY` non-blank ones.
+    pub fn load_history<I: IntoIterator<Item = String>>(&mut self, commands: I) {
+        self.history = commands.into_iter().filter(|command| !command.trim().is_empty()).collect();
+        let excess = self.history.len().saturating_sub(COMMAND_HISTORY_CAPACITY);
+        self.history.drain(..excess);
+        self.recalled = None;
+    }
+
+    /// Whether Up or Down has put a history entry on the line that has not
+    /// been edited since.
+    pub fn is_recalling(&self) -> bool {
+        self.recalled.is_some()
+    }
+
+    /// Puts the command before the recalled one (or the newest) on the line.
+    /// Returns false with the line untouched when there is nothing older.
+    pub fn history_previous(&mut self) -> bool {
+        let index = match self.recalled {
+            Some(0) => return false,
+            Some(index) => index - 1,
+            None if self.history.is_empty() => return false,
+            None => {
+                self.draft = self.text();
+                self.history.len() - 1
+            }
+        };
+        self.recall(index);
+        true
+    }
+
+    /// Puts the command after the recalled one on the line, or the draft
+    /// typed before browsing once past the newest. Returns false when no
+    /// command is recalled.
+    pub fn history_next(&mut self) -> bool {
+        let Some(index) = self.recalled else {
+            return false;
+        };
+        if index + 1 < self.history.len() {
+            self.recall(index + 1);
+        } else {
+            let draft = std::mem::take(&mut self.draft);
+            self.set(&draft);
+        }
+        true
+    }
+
+    fn recall(&mut self, index: usize) {
+        let command = self.history[index].clone();
+        self.set(&command);
+        self.recalled = Some(index);
+    }
+
+    /// The start (in chars) of the word the cursor is in or just after, and
+    /// the complete words before it.
+    fn word_at_cursor(&self) -> (usize, Vec<String>) {
+        let start = self.line[..self.cursor]
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .map_or(0, |space| space + 1);
+        let before: String = self.line[..start].iter().collect();
+        (start, before.split_whitespace().map(str::to_string).collect())
+    }
+
+    /// Words that could finish the one under the cursor, sorted. While the
+    /// words before it are the start of one of `names` (which may be several
+    /// words long, like `alert above`), those are the names' next words; once
+    /// they spell out a whole name they come from `arguments` for that name.
+    /// Matching ignores case.
+    pub fn completions(&self, names: &[&str], arguments: impl Fn(&str) -> Vec<String>) -> Vec<String> {
+        let (start, words) = self.word_at_cursor();
+        let partial: String = self.line[start..self.cursor].iter().collect::<String>().to_lowercase();
+
+        let mut candidates: Vec<String> = Vec::new();
+        for name in names {
+            let name_words: Vec<&str> = name.split_whitespace().collect();
+            if words.len() < name_words.len() {
+                if name_words[..words.len()] == words[..] {
+                    candidates.push(name_words[words.len()].to_string());
+                }
+            } else if name_words[..] == words[..name_words.len()] {
+                candidates.extend(arguments(name));
+            }
+        }
+        candidates.retain(|candidate| candidate.to_lowercase().starts_with(&partial));
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+
+    /// Completes the word under the cursor from `completions`: a single
+    /// candidate replaces it and is followed by a space, several extend it as
+    /// far as they agree. Returns the candidates.
+    pub fn complete(&mut self, names: &[&str], arguments: impl Fn(&str) -> Vec<String>) -> Vec<String> {
+        let candidates = self.completions(names, arguments);
+        let replacement = match candidates.as_slice() {
+            [] => return candidates,
+            [only] => format!("{} ", only),
+            [first, rest @ ..] => rest.iter().fold(first.clone(), |prefix, candidate| {
+                prefix
+                    .chars()
+                    .zip(candidate.chars())
+                    .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                    .map(|(a, _)| a)
+                    .collect()
+            }),
+        };
+
+        let (start, _) = self.word_at_cursor();
+        // A shared prefix shorter than what was typed (only possible when
+        // case differs) would delete input, so leave the word alone then
+        if replacement.chars().count() >= self.cursor - start {
+            let tail = self.line.split_off(self.cursor);
+            self.line.truncate(start);
+            self.line.extend(replacement.chars());
+            // Don't double the space when completing before one
+            if tail.first().is_some_and(|c| c.is_whitespace()) && self.line.last() == Some(&' ') {
+                self.line.pop();
+                self.cursor = self.line.len() + 1;
+            } else {
+                self.cursor = self.line.len();
+            }
+            self.line.extend(tail);
+            self.recalled = None;
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: &[&str] = &["help", "add_orders", "alert above", "alert below", "alert list", "coin"];
+
+    fn coin_symbols(name: &str) -> Vec<String> {
+        if name == "coin" {
+            ["BTC", "ETH", "SOL"].map(str::to_string).to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn typed(text: &str) -> CommandLine {
+        let mut line = CommandLine::new();
+        text.chars().for_each(|c| line.insert(c));
+        line
+    }
+
+    #[test]
+    fn test_editing_at_the_cursor() {
+        let mut line = typed("alrt above");
+        line.move_home();
+        line.move_right();
+        line.move_right();
+        line.insert('e');
+        assert_eq!(line.text(), "alert above");
+        assert_eq!(line.cursor(), 3);
+        assert_eq!(line.before_cursor(), "ale");
+
+        line.delete();
+        line.backspace();
+        assert_eq!(line.text(), "alt above");
+        line.move_end();
+        line.move_right();
+        assert_eq!(line.cursor(), 9);
+        line.backspace();
+        line.insert('é');
+        assert_eq!(line.text(), "alt abové");
+
+        line.move_home();
+        line.backspace();
+        line.move_left();
+        assert_eq!((line.cursor(), line.text().as_str()), (0, "alt abové"));
+        line.move_end();
+        line.delete();
+        assert_eq!(line.text(), "alt abové");
+
+        line.clear();
+        assert!(line.is_empty());
+        assert_eq!(line.cursor(), 0);
+    }
+
+    #[test]
+    fn test_history_navigation() {
+        let mut line = CommandLine::new();
+        assert!(!line.history_previous());
+        for command in ["help", "bars", "bars", "  ", "refresh 5"] {
+            line.set(command);
+            assert_eq!(line.submit(), command);
+        }
+        // Blank lines and repeats of the newest entry are not kept
+        assert_eq!(line.history().collect::<Vec<_>>(), vec!["help", "bars", "refresh 5"]);
+
+        for c in "al".chars() {
+            line.insert(c);
+        }
+        assert!(line.history_previous());
+        assert_eq!(line.text(), "refresh 5");
+        assert!(line.is_recalling());
+        line.history_previous();
+        line.history_previous();
+        assert_eq!(line.text(), "help");
+        assert!(!line.history_previous());
+        assert_eq!(line.text(), "help");
+
+        line.history_next();
+        assert_eq!(line.text(), "bars");
+        line.history_next();
+        line.history_next();
+        // Past the newest entry the draft comes back
+        assert_eq!(line.text(), "al");
+        assert_eq!(line.cursor(), 2);
+        assert!(!line.history_next());
+
+        // Editing a recalled command leaves the history alone
+        line.history_previous();
+        line.insert('0');
+        assert!(!line.is_recalling());
+        assert_eq!(line.submit(), "refresh 50");
+        assert_eq!(line.history().last(), Some("refresh 50"));
+        assert_eq!(line.history().count(), 4);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut line = CommandLine::new();
+        for i in 0..COMMAND_HISTORY_CAPACITY + 5 {
+            line.set(&format!("refresh {}", i));
+            line.submit();
+        }
+        assert_eq!(line.history().count(), COMMAND_HISTORY_CAPACITY);
+        assert_eq!(line.history().next(), Some("refresh 5"));
+
+        line.load_history((0..COMMAND_HISTORY_CAPACITY + 2).map(|i| format!("bars {}", i)).chain([String::new()]));
+        assert_eq!(line.history().count(), COMMAND_HISTORY_CAPACITY);
+        assert_eq!(line.history().next(), Some("bars 2"));
+        line.history_previous();
+        assert_eq!(line.text(), format!("bars {}", COMMAND_HISTORY_CAPACITY + 1));
+    }
+
+    #[test]
+    fn test_completion_candidates() {
+        let candidates = |text: &str| typed(text).completions(NAMES, coin_symbols);
+        assert_eq!(candidates("a"), vec!["add_orders", "alert"]);
+        assert_eq!(candidates(""), vec!["add_orders", "alert", "coin", "help"]);
+        assert_eq!(candidates("alert "), vec!["above", "below", "list"]);
+        assert_eq!(candidates("alert b"), vec!["below"]);
+        assert_eq!(candidates("coin e"), vec!["ETH"]);
+        assert!(candidates("coin BTC ").contains(&"SOL".to_string()));
+        assert!(candidates("alert above 1").is_empty());
+        assert!(candidates("zz").is_empty());
+
+        // The word under the cursor is the one completed
+        let mut line = typed("coin s help");
+        for _ in 0..5 {
+            line.move_left();
+        }
+        assert_eq!(line.completions(NAMES, coin_symbols), vec!["SOL"]);
+    }
+
+    #[test]
+    fn test_complete_fills_in_the_word() {
+        let mut line = typed("al");
+        assert_eq!(line.complete(NAMES, coin_symbols), vec!["alert"]);
+        assert_eq!(line.text(), "alert ");
+        line.complete(NAMES, coin_symbols);
+        // Ambiguous: nothing shared beyond what is typed
+        assert_eq!(line.text(), "alert ");
+        line.insert('l');
+        line.complete(NAMES, coin_symbols);
+        assert_eq!(line.text(), "alert list ");
+
+        let mut line = typed("coin b");
+        line.complete(NAMES, coin_symbols);
+        assert_eq!(line.text(), "coin BTC ");
+
+        // Several candidates extend the word to their common prefix
+        let mut line = typed("ex");
+        line.complete(&["export trades", "export alerts", "expire"], |_| Vec::new());
+        assert_eq!(line.text(), "exp");
+        assert_eq!(line.cursor(), 3);
+
+        // Completing mid-line keeps the rest and a single space
+        let mut line = typed("alert ab 100");
+        for _ in 0..4 {
+            line.move_left();
+        }
+        line.complete(NAMES, coin_symbols);
+        assert_eq!(line.text(), "alert above 100");
+        assert_eq!(line.cursor(), 12);
+
+        let mut line = typed("zz");
+        assert!(line.complete(NAMES, coin_symbols).is_empty());
+        assert_eq!(line.text(), "zz");
+    }
+}
@@ -0,0 +1,284 @@
+use std::fmt;
+
+/// One positional argument in a command's spec.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arg {
+    Required(&'static str),
+    Optional(&'static str),
+    /// Any number of trailing words, e.g. an alert message.
+    Rest(&'static str),
+}
+
+impl fmt::Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arg::Required(name) => write!(f, "<{}>", name),
+            Arg::Optional(name) => write!(f, "[{}]", name),
+            Arg::Rest(name) => write!(f, "[{}...]", name),
+        }
+    }
+}
+
+pub type CommandHandler<C> = fn(&mut C, &[&str]);
+
+/// A command bar command. `name` may be several words (`pm book`); the
+/// arguments are whatever follows it, split by `split_words`.
+pub struct Command<C> {
+    pub name: &'static str,
+    pub args: &'static [Arg],
+    pub description: &'static str,
+    pub handler: CommandHandler<C>,
+}
+
+impl<C> Clone for Command<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for Command<C> {}
+
+impl<C> Command<C> {
+    pub fn new(name: &'static str, args: &'static [Arg], description: &'static str, handler: CommandHandler<C>) -> Self {
+        Self { name, args, description, handler }
+    }
+
+    /// `name` followed by the argument spec, e.g. `refresh <seconds>`.
+    pub fn usage(&self) -> String {
+        let mut usage = self.name.to_string();
+        for arg in self.args {
+            usage.push_str(&format!(" {}", arg));
+        }
+        usage
+    }
+
+    pub fn min_args(&self) -> usize {
+        self.args.iter().filter(|arg| matches!(arg, Arg::Required(_))).count()
+    }
+
+    /// `None` when a `Rest` argument takes any number of trailing words.
+    pub fn max_args(&self) -> Option<usize> {
+        if self.args.iter().any(|arg| matches!(arg, Arg::Rest(_))) {
+            None
+        } else {
+            Some(self.args.len())
+        }
+    }
+
+    fn accepts(&self, count: usize) -> bool {
+        count >= self.min_args() && self.max_args().is_none_or(|max| count <= max)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    Unknown(String),
+    /// The command exists but was given the wrong number of arguments; holds
+    /// its usage line.
+    Usage(String),
+    /// A command group such as `alert` was given no subcommand, or one it
+    /// does not have.
+    Subcommand { group: String, choices: Vec<&'static str> },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Unknown(input) => write!(f, "Unknown command: {} (type help for a list)", input),
+            CommandError::Usage(usage) => write!(f, "Usage: {}", usage),
+            CommandError::Subcommand { group, choices } => write!(f, "Usage: {} <{}> ...", group, choices.join("|")),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Splits `input` on whitespace, except that a word opening with `"` or `'`
+/// runs to the matching quote, so `export orders "my orders.csv"` has three
+/// words. The quotes are dropped; an unclosed one runs to the end of `input`.
+/// Quotes inside a word, as in `don't`, are ordinary characters.
+pub fn split_words(input: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut rest = input.trim_start();
+    while let Some(first) = rest.chars().next() {
+        let (word, after) = if first == '"' || first == '\'' {
+            let quoted = &rest[1..];
+            match quoted.find(first) {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            }
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        };
+        words.push(word);
+        rest = after.trim_start();
+    }
+    words
+}
+
+/// Maps command names to handlers over a context `C` (the `App`), checking
+/// arity before a handler runs.
+pub struct CommandRegistry<C> {
+    commands: Vec<Command<C>>,
+}
+
+impl<C> Clone for CommandRegistry<C> {
+    fn clone(&self) -> Self {
+        Self { commands: self.commands.clone() }
+    }
+}
+
+impl<C> Default for CommandRegistry<C> {
+    fn default() -> Self {
+        Self { commands: Vec::new() }
+    }
+}
+
+impl<C> CommandRegistry<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `command`, replacing any earlier one with the same name.
+    pub fn register(&mut self, command: Command<C>) {
+        match self.commands.iter_mut().find(|existing| existing.name == command.name) {
+            Some(existing) => *existing = command,
+            None => self.commands.push(command),
+        }
+    }
+
+    /// Commands in registration order.
+    pub fn commands(&self) -> &[Command<C>] {
+        &self.commands
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Command<C>> {
+        self.commands.iter().find(|command| command.name == name)
+    }
+
+    /// Finds the command `input` names, preferring the longest matching name,
+    /// and returns it with its arguments once their count has been checked.
+    pub fn resolve<'a>(&self, input: &'a str) -> Result<(Command<C>, Vec<&'a str>), CommandError> {
+        let words = split_words(input);
+        let matched = self
+            .commands
+            .iter()
+            .filter(|command| {
+                let name: Vec<&str> = command.name.split_whitespace().collect();
+                words.starts_with(&name)
+            })
+            .max_by_key(|command| command.name.split_whitespace().count());
+
+        let Some(command) = matched else {
+            let group = words.first().copied().unwrap_or_default();
+            let choices: Vec<&'static str> = self
+                .commands
+                .iter()
+                .filter_map(|command| command.name.strip_prefix(group)?.strip_prefix(' '))
+                .collect();
+            return Err(if choices.is_empty() {
+                CommandError::Unknown(input.trim().to_string())
+            } else {
+                CommandError::Subcommand { group: group.to_string(), choices }
+            });
+        };
+
+        let args = words[command.name.split_whitespace().count()..].to_vec();
+        if !command.accepts(args.len()) {
+            return Err(CommandError::Usage(command.usage()));
+        }
+        Ok((*command, args))
+    }
+
+    /// Runs the command `input` names against `context`. Blank input is a no-op.
+    pub fn execute(&self, context: &mut C, input: &str) -> Result<(), CommandError> {
+        if input.trim().is_empty() {
+            return Ok(());
+        }
+        let (command, args) = self.resolve(input)?;
+        (command.handler)(context, &args);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(log: &mut Vec<String>, args: &[&str]) {
+        log.push(args.join(","));
+    }
+
+    #[test]
+    fn test_resolve_prefers_longest_name_and_checks_arity() {
+        let mut registry: CommandRegistry<Vec<String>> = CommandRegistry::new();
+        registry.register(Command::new("pm book", &[Arg::Optional("token_id")], "", record));
+        registry.register(Command::new("pm stream", &[], "", |log, _| log.push("stream".to_string())));
+        registry.register(Command::new(
+            "alert above",
+            &[Arg::Required("price"), Arg::Rest("message")],
+            "",
+            record,
+        ));
+
+        let mut log = Vec::new();
+        registry.execute(&mut log, "  pm   book 123 ").unwrap();
+        registry.execute(&mut log, "pm book").unwrap();
+        registry.execute(&mut log, "pm stream").unwrap();
+        registry.execute(&mut log, "alert above 100 to the moon").unwrap();
+        registry.execute(&mut log, "   ").unwrap();
+        assert_eq!(log, vec!["123", "", "stream", "100,to,the,moon"]);
+
+        assert_eq!(
+            registry.execute(&mut log, "pm book 1 2"),
+            Err(CommandError::Usage("pm book [token_id]".to_string()))
+        );
+        assert_eq!(
+            registry.execute(&mut log, "alert above").unwrap_err().to_string(),
+            "Usage: alert above <price> [message...]"
+        );
+        assert_eq!(
+            registry.execute(&mut log, "pm").unwrap_err().to_string(),
+            "Usage: pm <book|stream> ..."
+        );
+        assert_eq!(
+            registry.execute(&mut log, "alert sideways 5"),
+            Err(CommandError::Subcommand { group: "alert".to_string(), choices: vec!["above"] })
+        );
+        assert_eq!(registry.execute(&mut log, "dance"), Err(CommandError::Unknown("dance".to_string())));
+        assert_eq!(log.len(), 4);
+    }
+
+    #[test]
+    fn test_split_words_respects_quotes() {
+        assert_eq!(split_words("  export  orders ~/a.csv "), vec!["export", "orders", "~/a.csv"]);
+        assert_eq!(
+            split_words(r#"export trades "/tmp/my trades.csv" --force"#),
+            vec!["export", "trades", "/tmp/my trades.csv", "--force"]
+        );
+        assert_eq!(split_words("alert above 5 'big move' don't"), vec!["alert", "above", "5", "big move", "don't"]);
+        assert_eq!(split_words(r#"a "" b"#), vec!["a", "", "b"]);
+        assert_eq!(split_words(r#"a "unclosed path"#), vec!["a", "unclosed path"]);
+        assert!(split_words("   ").is_empty());
+
+        let mut registry: CommandRegistry<Vec<String>> = CommandRegistry::new();
+        registry.register(Command::new("export", &[Arg::Required("what"), Arg::Required("path")], "", record));
+        let mut log = Vec::new();
+        registry.execute(&mut log, r#"export depth "a b.csv""#).unwrap();
+        assert_eq!(log, vec!["depth,a b.csv"]);
+    }
+
+    #[test]
+    fn test_register_replaces_same_name() {
+        let mut registry: CommandRegistry<Vec<String>> = CommandRegistry::new();
+        registry.register(Command::new("bars", &[], "old", record));
+        registry.register(Command::new("help", &[], "", record));
+        registry.register(Command::new("bars", &[], "new", record));
+
+        let names: Vec<&str> = registry.commands().iter().map(|command| command.name).collect();
+        assert_eq!(names, vec!["bars", "help"]);
+        assert_eq!(registry.get("bars").unwrap().description, "new");
+        assert_eq!(registry.get("bars").unwrap().max_args(), Some(0));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::simulator::SimulatorParams;
+use crate::theme::CustomTheme;
+use crate::ui::DEFAULT_ALERT_HISTORY_LIMIT;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// `order-book` under `$XDG_CONFIG_HOME`, `%APPDATA%` or `~/.config`,
+/// whichever is set first. The settings, alerts and command history live here.
+pub fn config_dir() -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    let config = var("XDG_CONFIG_HOME")
+        .or_else(|| var("APPDATA"))
+        .or_else(|| var("HOME").map(|home| home.join(".config")))?;
+    Some(config.join("order-book"))
+}
+
+/// `config.toml` in the config directory.
+pub fn default_config_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+/// Settings applied at startup and saved when changed on the Settings tab.
+/// Fields missing from the file keep their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub auto_refresh: bool,
+    /// Seconds between market data refreshes.
+    pub refresh_interval: f64,
+    pub alert_sound_enabled: bool,
+    /// Most alert firings kept in the Alerts tab's history.
+    pub alert_history_limit: usize,
+    pub use_real_data: bool,
+    /// Symbol of the coin selected at startup, e.g. "BTC".
+    pub default_coin: String,
+    /// Name of a preset ("dark", "light", "high-contrast") or custom theme.
+    pub theme: String,
+    /// Seeds the market simulator so every run simulates the same market;
+    /// `--seed` on the command line takes precedence.
+    pub seed: Option<u64>,
+    /// Least severe level written to the log file: "error", "warn", "info",
+    /// "debug" or "trace"; `--log-level` takes precedence.
+    pub log_level: String,
+    /// Log file; `order-book.log` in the config directory when unset.
+    pub log_file: Option<PathBuf>,
+    /// The `[simulator]` table, after the plain values.
+    pub simulator: SimulatorParams,
+    /// `[[themes]]` tables. Tables must follow every plain value for the TOML
+    /// to encode, so these stay last and are left out when empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub themes: Vec<CustomTheme>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            auto_refresh: true,
+            refresh_interval: 2.0,
+            alert_sound_enabled: true,
+            alert_history_limit: DEFAULT_ALERT_HISTORY_LIMIT,
+            use_real_data: false,
+            default_coin: "BTC".to_string(),
+            theme: "dark".to_string(),
+            seed: None,
+            log_level: "info".to_string(),
+            log_file: None,
+            simulator: SimulatorParams::default(),
+            themes: Vec::new(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Reads the settings at `path`; a missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("Could not read {}: {}", path.display(), e)),
+        };
+        toml::from_str(&text).map_err(|e| format!("Invalid settings file {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string_pretty(self).map_err(|e| format!("Could not encode settings: {}", e))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+        }
+        let partial = path.with_extension("toml.tmp");
+        std::fs::write(&partial, text)
+            .and_then(|_| std::fs::rename(&partial, path))
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_config_file(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("order-book-config-test-{}", std::process::id()))
+            .join(format!("{}.toml", name))
+    }
+
+    #[test]
+    fn test_missing_fields_take_defaults() {
+        let config: AppConfig = toml::from_str("refresh_interval = 5.0\ndefault_coin = \"SOL\"\n").unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                refresh_interval: 5.0,
+                default_coin: "SOL".to_string(),
+                ..AppConfig::default()
+            }
+        );
+        assert_eq!(toml::from_str::<AppConfig>("").unwrap(), AppConfig::default());
+
+        assert_eq!(AppConfig::load(&temp_config_file("missing")).unwrap(), AppConfig::default());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = temp_config_file("round-trip");
+        let config = AppConfig {
+            auto_refresh: false,
+            refresh_interval: 0.5,
+            alert_sound_enabled: false,
+            alert_history_limit: 20,
+            use_real_data: true,
+            default_coin: "ETH".to_string(),
+            theme: "mine".to_string(),
+            seed: Some(42),
+            log_level: "debug".to_string(),
+            log_file: Some(PathBuf::from("/tmp/order-book.log")),
+            simulator: SimulatorParams { volatility: 2.5, ..SimulatorParams::default() },
+            themes: vec![CustomTheme {
+                name: "mine".to_string(),
+                bid: Some("#00ff00".to_string()),
+                chart: vec!["blue".to_string()],
+                ..CustomTheme::default()
+            }],
+        };
+        config.save(&path).unwrap();
+        assert_eq!(AppConfig::load(&path).unwrap(), config);
+
+        std::fs::write(&path, "auto_refresh = \"sometimes\"").unwrap();
+        let error = AppConfig::load(&path).unwrap_err();
+        assert!(error.starts_with("Invalid settings file"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
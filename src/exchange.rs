@@ -0,0 +1,743 @@
+use async_trait::async_trait;
+use std::fmt;
+use tokio::sync::{mpsc, watch};
+use crate::binance_orders::{
+    BinanceError, BinanceOrderClient, BinanceOrderInfo, BinanceOrderRef, BinanceOrderSide, BinanceOrderStatus,
+    BinanceTimeInForce,
+};
+use crate::binance_user_data::{self, user_stream_base_url, UserDataEvent};
+use crate::order::OrderSide;
+use crate::polymarket_orders::{
+    PolymarketClobClient, PolymarketError, PolymarketOpenOrder, PolymarketOrderSide, PolymarketOrderStatus,
+    PolymarketOrderType,
+};
+use crate::polymarket_user::{self, PolymarketOrderUpdate, PolymarketUserEvent, UserChannelConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeInForce {
+    GoodTilCancelled,
+    ImmediateOrCancel,
+    FillOrKill,
+    /// Good until the given unix timestamp in seconds.
+    GoodTilDate(i64),
+    /// Rests as a maker order, or is rejected if it would trade on arrival.
+    PostOnly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Pending,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Rejected,
+    Expired,
+}
+
+impl OrderStatus {
+    /// Status text used for order history rows.
+    pub fn label(&self) -> &'static str {
+        match self {
+            OrderStatus::Pending => "Pending",
+            OrderStatus::PartiallyFilled => "Partially Filled",
+            OrderStatus::Filled => "Filled",
+            OrderStatus::Cancelled => "Cancelled",
+            OrderStatus::Rejected => "Rejected",
+            OrderStatus::Expired => "Expired",
+        }
+    }
+}
+
+impl From<&BinanceOrderStatus> for OrderStatus {
+    fn from(status: &BinanceOrderStatus) -> Self {
+        match status {
+            BinanceOrderStatus::NEW | BinanceOrderStatus::PENDING_CANCEL => OrderStatus::Pending,
+            BinanceOrderStatus::PARTIALLY_FILLED => OrderStatus::PartiallyFilled,
+            BinanceOrderStatus::FILLED => OrderStatus::Filled,
+            BinanceOrderStatus::CANCELED => OrderStatus::Cancelled,
+            BinanceOrderStatus::REJECTED => OrderStatus::Rejected,
+            BinanceOrderStatus::EXPIRED => OrderStatus::Expired,
+        }
+    }
+}
+
+/// What a venue returned for an accepted order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderAck {
+    pub order_id: String,
+    pub status: OrderStatus,
+    /// Limit price after any rounding the venue client applied.
+    pub price: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenOrder {
+    pub order_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: f64,
+    pub quantity: f64,
+    pub filled_quantity: f64,
+    pub status: OrderStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balance {
+    pub asset: String,
+    pub free: f64,
+    pub locked: f64,
+}
+
+/// A change to one of the account's orders, whichever venue it is on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderUpdate {
+    pub order_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: f64,
+    pub quantity: f64,
+    pub filled_quantity: f64,
+    pub status: OrderStatus,
+    /// When the venue reported the change, in milliseconds since the epoch.
+    pub timestamp: u64,
+}
+
+impl From<&binance_user_data::OrderUpdate> for OrderUpdate {
+    fn from(update: &binance_user_data::OrderUpdate) -> Self {
+        Self {
+            order_id: update.order_id.to_string(),
+            symbol: update.symbol.clone(),
+            side: order_side(&update.side),
+            price: update.price,
+            quantity: update.quantity,
+            filled_quantity: update.cumulative_filled_quantity,
+            status: OrderStatus::from(&update.status),
+            timestamp: update.transaction_time,
+        }
+    }
+}
+
+impl From<&PolymarketOrderUpdate> for OrderUpdate {
+    fn from(update: &PolymarketOrderUpdate) -> Self {
+        let status = match update.status {
+            PolymarketOrderStatus::Matched => OrderStatus::Filled,
+            PolymarketOrderStatus::Cancelled => OrderStatus::Cancelled,
+            _ if update.size_matched > 0.0 => OrderStatus::PartiallyFilled,
+            _ => OrderStatus::Pending,
+        };
+        Self {
+            order_id: update.order_id.clone(),
+            symbol: update.asset_id.clone(),
+            side: match update.side {
+                PolymarketOrderSide::BUY => OrderSide::Bid,
+                PolymarketOrderSide::SELL => OrderSide::Ask,
+            },
+            price: update.price,
+            quantity: update.original_size.unwrap_or(update.size_matched),
+            filled_quantity: update.size_matched,
+            status,
+            // The user channel reports seconds
+            timestamp: update.timestamp.saturating_mul(1000),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExchangeError {
+    Binance(BinanceError),
+    Polymarket(PolymarketError),
+    /// The venue has no open order with this id.
+    UnknownOrder(String),
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExchangeError::Binance(e) => write!(f, "{}", e),
+            ExchangeError::Polymarket(e) => write!(f, "{}", e),
+            ExchangeError::UnknownOrder(order_id) => write!(f, "Unknown order {}", order_id),
+            ExchangeError::Unsupported(what) => write!(f, "{} is not supported on this venue", what),
+        }
+    }
+}
+
+impl std::error::Error for ExchangeError {}
+
+impl From<PolymarketError> for ExchangeError {
+    fn from(error: PolymarketError) -> Self {
+        ExchangeError::Polymarket(error)
+    }
+}
+
+impl From<BinanceError> for ExchangeError {
+    fn from(error: BinanceError) -> Self {
+        ExchangeError::Binance(error)
+    }
+}
+
+/// Order routing that looks the same for every venue. `symbol` is whatever
+/// the venue trades: a pair like "BTCUSDT" on Binance, a token id on Polymarket.
+#[async_trait]
+pub trait ExchangeClient: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn place_limit(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        price: f64,
+        quantity: f64,
+        time_in_force: TimeInForce,
+    ) -> Result<OrderAck, ExchangeError>;
+
+    async fn place_market(&self, symbol: &str, side: OrderSide, quantity: f64) -> Result<OrderAck, ExchangeError>;
+
+    async fn cancel(&self, symbol: &str, order_id: &str) -> Result<(), ExchangeError>;
+
+    async fn open_orders(&self, symbol: &str) -> Result<Vec<OpenOrder>, ExchangeError>;
+
+    async fn balances(&self) -> Result<Vec<Balance>, ExchangeError>;
+
+    /// Starts streaming order updates; the stream stops once the receiver is
+    /// dropped. Must be called from within a tokio runtime.
+    async fn order_updates(&self) -> Result<mpsc::UnboundedReceiver<OrderUpdate>, ExchangeError>;
+}
+
+#[async_trait]
+impl ExchangeClient for BinanceOrderClient {
+    fn name(&self) -> &'static str {
+        "Binance"
+    }
+
+    async fn place_limit(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        price: f64,
+        quantity: f64,
+        time_in_force: TimeInForce,
+    ) -> Result<OrderAck, ExchangeError> {
+        let time_in_force = match time_in_force {
+            TimeInForce::GoodTilCancelled => BinanceTimeInForce::GTC,
+            TimeInForce::ImmediateOrCancel => BinanceTimeInForce::IOC,
+            TimeInForce::FillOrKill => BinanceTimeInForce::FOK,
+            TimeInForce::PostOnly => BinanceTimeInForce::GTX,
+            TimeInForce::GoodTilDate(_) => return Err(ExchangeError::Unsupported("good-til-date")),
+        };
+        let request = self.create_limit_order(symbol, binance_side(side), quantity, price, time_in_force);
+        let response = self.place_order(&request).await?;
+        Ok(OrderAck {
+            order_id: response.order_id.to_string(),
+            status: OrderStatus::from(&response.status),
+            price: Some(price),
+        })
+    }
+
+    async fn place_market(&self, symbol: &str, side: OrderSide, quantity: f64) -> Result<OrderAck, ExchangeError> {
+        let request = self.create_market_order(symbol, binance_side(side), quantity);
+        let response = self.place_order(&request).await?;
+        Ok(OrderAck {
+            order_id: response.order_id.to_string(),
+            status: OrderStatus::from(&response.status),
+            price: None,
+        })
+    }
+
+    async fn cancel(&self, symbol: &str, order_id: &str) -> Result<(), ExchangeError> {
+        let order = match order_id.parse::<u64>() {
+            Ok(id) => BinanceOrderRef::OrderId(id),
+            Err(_) => BinanceOrderRef::ClientOrderId(order_id.to_string()),
+        };
+        match self.cancel_order(symbol, &order).await {
+            Ok(_) => Ok(()),
+            Err(BinanceError::UnknownOrder { .. }) => Err(ExchangeError::UnknownOrder(order_id.to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn open_orders(&self, symbol: &str) -> Result<Vec<OpenOrder>, ExchangeError> {
+        self.get_open_orders(symbol).await?.iter().map(open_order).collect()
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>, ExchangeError> {
+        self.get_account()
+            .await?
+            .balances
+            .iter()
+            .map(|balance| {
+                Ok(Balance {
+                    asset: balance.asset.clone(),
+                    free: decimal(&balance.free)?,
+                    locked: decimal(&balance.locked)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn order_updates(&self) -> Result<mpsc::UnboundedReceiver<OrderUpdate>, ExchangeError> {
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+        let (update_tx, updates) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let stream_base_url = user_stream_base_url(self.is_testnet());
+
+        tokio::spawn(binance_user_data::run_user_data_stream(self.clone(), stream_base_url, event_tx, shutdown_rx));
+        tokio::spawn(async move {
+            // Dropping `shutdown` when the consumer goes away stops the stream
+            let _shutdown = shutdown;
+            while let Some(event) = events.recv().await {
+                if let UserDataEvent::Order(update) = event {
+                    if update_tx.send(OrderUpdate::from(&update)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(updates)
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for PolymarketClobClient {
+    fn name(&self) -> &'static str {
+        "Polymarket"
+    }
+
+    async fn place_limit(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        price: f64,
+        quantity: f64,
+        time_in_force: TimeInForce,
+    ) -> Result<OrderAck, ExchangeError> {
+        let (order_type, expiration) = match time_in_force {
+            TimeInForce::GoodTilCancelled => (PolymarketOrderType::GTC, None),
+            TimeInForce::FillOrKill => (PolymarketOrderType::FOK, None),
+            TimeInForce::GoodTilDate(expiration) => (PolymarketOrderType::GTD, Some(expiration)),
+            TimeInForce::ImmediateOrCancel => return Err(ExchangeError::Unsupported("immediate-or-cancel")),
+            TimeInForce::PostOnly => return Err(ExchangeError::Unsupported("post-only")),
+        };
+
+        let order_args = self
+            .create_order_args(price, quantity, polymarket_side(side), symbol.to_string())
+            .with_order_type(order_type, expiration);
+        let price = order_args.price;
+        self.check_order_args(&order_args)?;
+        let order = self.create_order(order_args);
+        self.validate_order(&order, order_type)?;
+
+        let response = self.post_order(order.clone(), order_type).await?;
+
+        let status = match response.status {
+            Some(PolymarketOrderStatus::Matched) => OrderStatus::Filled,
+            _ => OrderStatus::Pending,
+        };
+        Ok(OrderAck {
+            order_id: response.order_id.unwrap_or_else(|| order.salt.to_string()),
+            status,
+            price: Some(price),
+        })
+    }
+
+    /// The CLOB has no market orders; a FOK at the most aggressive price on
+    /// the tick grid fills against whatever liquidity is resting.
+    async fn place_market(&self, symbol: &str, side: OrderSide, quantity: f64) -> Result<OrderAck, ExchangeError> {
+        let tick_size = self.market_params(symbol).tick_size;
+        let price = match side {
+            OrderSide::Bid => 1.0 - tick_size,
+            OrderSide::Ask => tick_size,
+        };
+        self.place_limit(symbol, side, price, quantity, TimeInForce::FillOrKill).await
+    }
+
+    async fn cancel(&self, _symbol: &str, order_id: &str) -> Result<(), ExchangeError> {
+        let response = self.cancel_order(order_id).await?;
+        if response.canceled.iter().any(|id| id == order_id) {
+            Ok(())
+        } else {
+            Err(ExchangeError::UnknownOrder(order_id.to_string()))
+        }
+    }
+
+    /// `symbol` is the outcome token id.
+    async fn open_orders(&self, symbol: &str) -> Result<Vec<OpenOrder>, ExchangeError> {
+        self.get_open_orders(None)
+            .await?
+            .iter()
+            .filter(|order| order.asset_id == symbol)
+            .map(polymarket_open_order)
+            .collect()
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>, ExchangeError> {
+        Err(ExchangeError::Unsupported("querying balances"))
+    }
+
+    async fn order_updates(&self) -> Result<mpsc::UnboundedReceiver<OrderUpdate>, ExchangeError> {
+        let credentials = self.api_credentials().cloned().ok_or(PolymarketError::MissingCredentials)?;
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+        let (update_tx, updates) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = watch::channel(false);
+
+        tokio::spawn(polymarket_user::run_user_channel(
+            UserChannelConfig::default(),
+            credentials,
+            event_tx,
+            shutdown_rx,
+        ));
+        tokio::spawn(async move {
+            // Dropping `shutdown` when the consumer goes away stops the channel
+            let _shutdown = shutdown;
+            while let Some(event) = events.recv().await {
+                if let PolymarketUserEvent::Order(update) = event {
+                    if update_tx.send(OrderUpdate::from(&update)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(updates)
+    }
+}
+
+fn binance_side(side: OrderSide) -> BinanceOrderSide {
+    match side {
+        OrderSide::Bid => BinanceOrderSide::BUY,
+        OrderSide::Ask => BinanceOrderSide::SELL,
+    }
+}
+
+fn polymarket_side(side: OrderSide) -> PolymarketOrderSide {
+    match side {
+        OrderSide::Bid => PolymarketOrderSide::BUY,
+        OrderSide::Ask => PolymarketOrderSide::SELL,
+    }
+}
+
+fn order_side(side: &BinanceOrderSide) -> OrderSide {
+    match side {
+        BinanceOrderSide::BUY => OrderSide::Bid,
+        BinanceOrderSide::SELL => OrderSide::Ask,
+    }
+}
+
+fn open_order(info: &BinanceOrderInfo) -> Result<OpenOrder, ExchangeError> {
+    Ok(OpenOrder {
+        order_id: info.order_id.to_string(),
+        symbol: info.symbol.clone(),
+        side: if info.side == "BUY" { OrderSide::Bid } else { OrderSide::Ask },
+        price: decimal(&info.price)?,
+        quantity: decimal(&info.orig_qty)?,
+        filled_quantity: decimal(&info.executed_qty)?,
+        status: OrderStatus::from(&info.status),
+    })
+}
+
+fn polymarket_open_order(order: &PolymarketOpenOrder) -> Result<OpenOrder, ExchangeError> {
+    let parse = |value: &str| {
+        value
+            .parse::<f64>()
+            .map_err(|_| PolymarketError::Parse(format!("invalid decimal '{}'", value)))
+    };
+    let quantity = parse(&order.original_size)?;
+    let filled_quantity = parse(&order.size_matched)?;
+    Ok(OpenOrder {
+        order_id: order.id.clone(),
+        symbol: order.asset_id.clone(),
+        side: if order.side == "BUY" { OrderSide::Bid } else { OrderSide::Ask },
+        price: parse(&order.price)?,
+        quantity,
+        filled_quantity,
+        status: if filled_quantity > 0.0 { OrderStatus::PartiallyFilled } else { OrderStatus::Pending },
+    })
+}
+
+fn decimal(value: &str) -> Result<f64, ExchangeError> {
+    value
+        .parse::<f64>()
+        .map_err(|_| ExchangeError::Binance(BinanceError::Parse(format!("invalid decimal '{}'", value))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// An in-memory venue: limit orders rest until cancelled, market orders
+    /// fill immediately, and every change is published as an update.
+    #[derive(Default)]
+    struct MockExchange {
+        orders: Mutex<Vec<OpenOrder>>,
+        next_id: Mutex<u64>,
+        updates: Mutex<Vec<mpsc::UnboundedSender<OrderUpdate>>>,
+    }
+
+    impl MockExchange {
+        fn publish(&self, order: &OpenOrder) {
+            let update = OrderUpdate {
+                order_id: order.order_id.clone(),
+                symbol: order.symbol.clone(),
+                side: order.side,
+                price: order.price,
+                quantity: order.quantity,
+                filled_quantity: order.filled_quantity,
+                status: order.status,
+                timestamp: 0,
+            };
+            self.updates.lock().retain(|tx| tx.send(update.clone()).is_ok());
+        }
+
+        fn next_order_id(&self) -> String {
+            let mut next_id = self.next_id.lock();
+            *next_id += 1;
+            next_id.to_string()
+        }
+    }
+
+    #[async_trait]
+    impl ExchangeClient for MockExchange {
+        fn name(&self) -> &'static str {
+            "Mock"
+        }
+
+        async fn place_limit(
+            &self,
+            symbol: &str,
+            side: OrderSide,
+            price: f64,
+            quantity: f64,
+            time_in_force: TimeInForce,
+        ) -> Result<OrderAck, ExchangeError> {
+            if time_in_force != TimeInForce::GoodTilCancelled {
+                return Err(ExchangeError::Unsupported("non-GTC limit orders"));
+            }
+            let order = OpenOrder {
+                order_id: self.next_order_id(),
+                symbol: symbol.to_string(),
+                side,
+                price,
+                quantity,
+                filled_quantity: 0.0,
+                status: OrderStatus::Pending,
+            };
+            self.publish(&order);
+            self.orders.lock().push(order.clone());
+            Ok(OrderAck { order_id: order.order_id, status: order.status, price: Some(price) })
+        }
+
+        async fn place_market(&self, symbol: &str, side: OrderSide, quantity: f64) -> Result<OrderAck, ExchangeError> {
+            let order = OpenOrder {
+                order_id: self.next_order_id(),
+                symbol: symbol.to_string(),
+                side,
+                price: 0.0,
+                quantity,
+                filled_quantity: quantity,
+                status: OrderStatus::Filled,
+            };
+            self.publish(&order);
+            Ok(OrderAck { order_id: order.order_id, status: order.status, price: None })
+        }
+
+        async fn cancel(&self, _symbol: &str, order_id: &str) -> Result<(), ExchangeError> {
+            let mut orders = self.orders.lock();
+            let index = orders
+                .iter()
+                .position(|o| o.order_id == order_id)
+                .ok_or_else(|| ExchangeError::UnknownOrder(order_id.to_string()))?;
+            let mut order = orders.remove(index);
+            order.status = OrderStatus::Cancelled;
+            drop(orders);
+            self.publish(&order);
+            Ok(())
+        }
+
+        async fn open_orders(&self, symbol: &str) -> Result<Vec<OpenOrder>, ExchangeError> {
+            Ok(self.orders.lock().iter().filter(|o| o.symbol == symbol).cloned().collect())
+        }
+
+        async fn balances(&self) -> Result<Vec<Balance>, ExchangeError> {
+            Ok(vec![Balance { asset: "USDT".to_string(), free: 1000.0, locked: 0.0 }])
+        }
+
+        async fn order_updates(&self) -> Result<mpsc::UnboundedReceiver<OrderUpdate>, ExchangeError> {
+            let (tx, rx) = mpsc::unbounded_channel();
+            self.updates.lock().push(tx);
+            Ok(rx)
+        }
+    }
+
+    /// Drives any venue through a place, list, cancel round trip.
+    async fn exercise(exchange: &dyn ExchangeClient) -> Vec<OrderUpdate> {
+        let mut updates = exchange.order_updates().await.unwrap();
+
+        let ack = exchange
+            .place_limit("BTCUSDT", OrderSide::Bid, 50000.0, 0.5, TimeInForce::GoodTilCancelled)
+            .await
+            .unwrap();
+        assert_eq!(ack.status, OrderStatus::Pending);
+
+        let open = exchange.open_orders("BTCUSDT").await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].order_id, ack.order_id);
+        assert!(exchange.open_orders("ETHUSDT").await.unwrap().is_empty());
+
+        exchange.cancel("BTCUSDT", &ack.order_id).await.unwrap();
+        assert!(exchange.open_orders("BTCUSDT").await.unwrap().is_empty());
+        assert!(matches!(
+            exchange.cancel("BTCUSDT", &ack.order_id).await,
+            Err(ExchangeError::UnknownOrder(_))
+        ));
+
+        let fill = exchange.place_market("BTCUSDT", OrderSide::Ask, 0.25).await.unwrap();
+        assert_eq!(fill.status, OrderStatus::Filled);
+
+        let mut received = Vec::new();
+        while let Ok(update) = updates.try_recv() {
+            received.push(update);
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_exchange_contract_with_mock() {
+        let exchange = MockExchange::default();
+        let updates = exercise(&exchange).await;
+
+        let statuses: Vec<OrderStatus> = updates.iter().map(|u| u.status).collect();
+        assert_eq!(statuses, vec![OrderStatus::Pending, OrderStatus::Cancelled, OrderStatus::Filled]);
+        assert_eq!(updates[2].side, OrderSide::Ask);
+        assert_eq!(updates[2].filled_quantity, 0.25);
+
+        let balances = exchange.balances().await.unwrap();
+        assert_eq!(balances[0].asset, "USDT");
+        assert!(matches!(
+            exchange.place_limit("BTCUSDT", OrderSide::Bid, 1.0, 1.0, TimeInForce::FillOrKill).await,
+            Err(ExchangeError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_polymarket_client_maps_order_types() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"success":true,"errorMsg":"","orderID":"0x3a7b5f2d1c","status":"live"}"#,
+            ))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut client = PolymarketClobClient::new(
+            server.uri(),
+            "test_key".to_string(),
+            137,
+            crate::polymarket_orders::PolymarketSignatureType::EMAIL_MAGIC,
+            Some("0xTestAddress".to_string()),
+        );
+        client.set_api_credentials(client.create_or_derive_api_credentials());
+        let exchange: &dyn ExchangeClient = &client;
+
+        let ack = exchange
+            .place_limit("token", OrderSide::Bid, 0.506, 10.0, TimeInForce::GoodTilCancelled)
+            .await
+            .unwrap();
+        assert_eq!(ack.order_id, "0x3a7b5f2d1c");
+        assert_eq!(ack.price, Some(0.51));
+        assert_eq!(ack.status, OrderStatus::Pending);
+
+        let expiration = chrono::Utc::now().timestamp() + 600;
+        assert!(exchange.place_limit("token", OrderSide::Ask, 0.5, 10.0, TimeInForce::GoodTilDate(expiration)).await.is_ok());
+
+        match exchange.place_limit("token", OrderSide::Bid, 0.5, 1.0, TimeInForce::GoodTilCancelled).await {
+            Err(ExchangeError::Polymarket(PolymarketError::InvalidOrderMinSize)) => {}
+            other => panic!("expected a CLOB error, got {:?}", other),
+        }
+        assert!(matches!(
+            exchange.place_limit("token", OrderSide::Bid, 0.5, 10.0, TimeInForce::ImmediateOrCancel).await,
+            Err(ExchangeError::Unsupported(_))
+        ));
+        assert!(matches!(exchange.balances().await, Err(ExchangeError::Unsupported(_))));
+    }
+
+    #[tokio::test]
+    async fn test_polymarket_cancel_and_open_orders() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"[{"id":"0x1","status":"LIVE","market":"0xcond","asset_id":"token","side":"SELL","original_size":"10","size_matched":"4","price":"0.62"},
+                    {"id":"0x2","status":"LIVE","market":"0xcond","asset_id":"other","side":"BUY","original_size":"5","size_matched":"0","price":"0.38"}]"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"canceled":[],"not_canceled":{"0x9":"order can't be found - already canceled or matched"}}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let mut client = PolymarketClobClient::new(
+            server.uri(),
+            "test_key".to_string(),
+            137,
+            crate::polymarket_orders::PolymarketSignatureType::EMAIL_MAGIC,
+            None,
+        );
+        client.set_api_credentials(client.create_or_derive_api_credentials());
+        let exchange: &dyn ExchangeClient = &client;
+
+        let open = exchange.open_orders("token").await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, OrderSide::Ask);
+        assert_eq!(open[0].filled_quantity, 4.0);
+        assert_eq!(open[0].status, OrderStatus::PartiallyFilled);
+
+        assert!(matches!(exchange.cancel("token", "0x9").await, Err(ExchangeError::UnknownOrder(_))));
+    }
+
+    #[test]
+    fn test_polymarket_updates_normalize_status() {
+        let mut update = PolymarketOrderUpdate {
+            order_id: "0x1".to_string(),
+            asset_id: "token".to_string(),
+            side: PolymarketOrderSide::SELL,
+            price: 0.57,
+            original_size: Some(10.0),
+            size_matched: 0.0,
+            status: PolymarketOrderStatus::Live,
+            timestamp: 1672290687,
+        };
+        let statuses: Vec<OrderStatus> = [
+            (PolymarketOrderStatus::Live, 0.0),
+            (PolymarketOrderStatus::Live, 4.0),
+            (PolymarketOrderStatus::Matched, 10.0),
+            (PolymarketOrderStatus::Cancelled, 4.0),
+        ]
+        .into_iter()
+        .map(|(status, size_matched)| {
+            update.status = status;
+            update.size_matched = size_matched;
+            OrderUpdate::from(&update).status
+        })
+        .collect();
+        assert_eq!(
+            statuses,
+            vec![OrderStatus::Pending, OrderStatus::PartiallyFilled, OrderStatus::Filled, OrderStatus::Cancelled]
+        );
+
+        let normalized = OrderUpdate::from(&update);
+        assert_eq!(normalized.side, OrderSide::Ask);
+        assert_eq!(normalized.symbol, "token");
+        assert_eq!(normalized.quantity, 10.0);
+        assert_eq!(normalized.timestamp, 1_672_290_687_000);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::order::OrderSide;
+use crate::order_book::DepthLevels;
+use crate::trade::Trade;
+use crate::ui::{AlertBehavior, AlertType, OrderRecord, PriceAlert};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// What `export` can write.
+pub const EXPORT_KINDS: [&str; 4] = ["orders", "trades", "alerts", "depth"];
+
+/// `path` with a leading `~` replaced by the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    let home = || std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from);
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match home() {
+            Some(home) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(path),
+        },
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Writes `contents` to `path`. An existing file is only replaced when
+/// `overwrite` is set.
+pub fn write_export(path: &Path, contents: &str, overwrite: bool) -> Result<(), String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    let mut file = options.open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => format!("{} already exists; add --force to overwrite it", path.display()),
+        _ => format!("Could not write {}: {}", path.display(), e),
+    })?;
+    file.write_all(contents.as_bytes())
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+/// One CSV field, quoted when it holds a comma, quote or line break.
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(header: &[&str], rows: impl IntoIterator<Item = Vec<String>>) -> String {
+    let mut csv = header.join(",");
+    csv.push('\n');
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|value| field(value)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn side_name(side: OrderSide) -> String {
+    match side {
+        OrderSide::Bid => "buy",
+        OrderSide::Ask => "sell",
+    }
+    .to_string()
+}
+
+/// Order history, in the order given.
+pub fn orders_csv<'a>(records: impl IntoIterator<Item = &'a OrderRecord>) -> String {
+    let header = ["timestamp", "venue", "symbol", "side", "price", "quantity", "status", "order_id", "reason"];
+    to_csv(
+        &header,
+        records.into_iter().map(|record| {
+            vec![
+                record.timestamp.to_rfc3339(),
+                record.venue.as_str().to_string(),
+                record.symbol.clone(),
+                side_name(record.side),
+                record.price.to_string(),
+                record.quantity.to_string(),
+                record.status.clone(),
+                record.order_id.clone(),
+                record.reason.clone().unwrap_or_default(),
+            ]
+        }),
+    )
+}
+
+pub fn trades_csv(trades: &[Trade]) -> String {
+    let header = [
+        "timestamp",
+        "aggressor_side",
+        "price",
+        "quantity",
+        "bid_order_id",
+        "ask_order_id",
+        "maker_fee",
+        "taker_fee",
+    ];
+    to_csv(
+        &header,
+        trades.iter().map(|trade| {
+            vec![
+                trade.timestamp.to_string(),
+                side_name(trade.aggressor_side),
+                trade.price.to_string(),
+                trade.quantity.to_string(),
+                trade.bid_order_id.to_string(),
+                trade.ask_order_id.to_string(),
+                trade.maker_fee.to_string(),
+                trade.taker_fee.to_string(),
+            ]
+        }),
+    )
+}
+
+/// Both sides of the book, best price first, with the running total of
+/// quantity from the best price out.
+pub fn depth_csv(bids: &DepthLevels, asks: &DepthLevels) -> String {
+    let side_rows = |side: OrderSide, levels: &DepthLevels| {
+        let mut total = 0.0;
+        levels
+            .iter()
+            .map(|&(price, quantity)| {
+                total += quantity;
+                vec![side_name(side), price.to_string(), quantity.to_string(), total.to_string()]
+            })
+            .collect::<Vec<_>>()
+    };
+    let rows = side_rows(OrderSide::Bid, bids).into_iter().chain(side_rows(OrderSide::Ask, asks));
+    to_csv(&["side", "price", "quantity", "cumulative_quantity"], rows)
+}
+
+pub fn alerts_csv(alerts: &[PriceAlert]) -> String {
+    let header = [
+        "id",
+        "symbol",
+        "type",
+        "value",
+        "message",
+        "active",
+        "behavior",
+        "created_at",
+        "triggered_at",
+        "triggered_count",
+    ];
+    to_csv(
+        &header,
+        alerts.iter().map(|alert| {
+            let (kind, value) = match alert.alert_type {
+                AlertType::PriceAbove(value) => ("price_above", value.to_string()),
+                AlertType::PriceBelow(value) => ("price_below", value.to_string()),
+                AlertType::PercentageChange(value) => ("percentage_change", value.to_string()),
+                AlertType::VolumeSpike(value) => ("volume_spike", value.to_string()),
+                AlertType::PriceCross(value) => ("price_cross", value.to_string()),
+                AlertType::SpreadAbove(value) => ("spread_above", value.to_string()),
+                AlertType::MidPriceCross(value) => ("mid_price_cross", value.to_string()),
+                // Side, level count and quantity, as `alert liq` takes them
+                AlertType::LiquidityBelow { side, levels, qty } => {
+                    let side = if side == OrderSide::Bid { "bid" } else { "ask" };
+                    ("liquidity_below", format!("{} {} {}", side, levels, qty))
+                }
+                // Notional and window seconds, as `alert whale` takes them
+                AlertType::WhaleTrade { notional, window_secs } => ("whale_trade", format!("{} {}", notional, window_secs)),
+            };
+            let behavior = match alert.behavior {
+                AlertBehavior::OneShot => "one_shot".to_string(),
+                AlertBehavior::Repeating { cooldown } => format!("repeat_{}s", cooldown.as_secs_f64()),
+                AlertBehavior::ReArmOnCross => "rearm".to_string(),
+            };
+            vec![
+                alert.id.to_string(),
+                alert.symbol.clone(),
+                kind.to_string(),
+                value,
+                alert.message.clone(),
+                alert.is_active.to_string(),
+                behavior,
+                alert.created_at.to_rfc3339(),
+                alert.triggered_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+                alert.triggered_count.to_string(),
+            ]
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::OrderVenue;
+    use std::time::Duration;
+
+    fn temp_export_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("order-book-export-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_fields_are_quoted_when_needed() {
+        assert_eq!(field("plain"), "plain");
+        assert_eq!(field("a,b"), "\"a,b\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(to_csv(&["a", "b"], vec![vec!["1".to_string(), "x,y".to_string()]]), "a,b\n1,\"x,y\"\n");
+    }
+
+    #[test]
+    fn test_csv_writers() {
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2024-03-01T10:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        let orders = orders_csv(&[OrderRecord {
+            timestamp,
+            venue: OrderVenue::Binance,
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Ask,
+            price: 27000.5,
+            quantity: 0.25,
+            status: "Rejected".to_string(),
+            order_id: "42".to_string(),
+            reason: Some("Filter failure: PRICE_FILTER, tick 0.01".to_string()),
+        }]);
+        assert_eq!(
+            orders,
+            "timestamp,venue,symbol,side,price,quantity,status,order_id,reason\n\
+             2024-03-01T10:00:00+00:00,Binance,BTCUSDT,sell,27000.5,0.25,Rejected,42,\"Filter failure: PRICE_FILTER, tick 0.01\"\n"
+        );
+
+        let trade = Trade {
+            bid_order_id: 1,
+            ask_order_id: 2,
+            price: 100.5,
+            quantity: 3.0,
+            timestamp: 7,
+            aggressor_side: OrderSide::Bid,
+            maker_fee: 0.0,
+            taker_fee: 0.3,
+        };
+        assert_eq!(
+            trades_csv(&[trade]).lines().nth(1),
+            Some("7,buy,100.5,3,1,2,0,0.3")
+        );
+
+        let depth = depth_csv(&vec![(99.0, 1.0), (98.0, 2.5)], &vec![(101.0, 4.0)]);
+        assert_eq!(
+            depth,
+            "side,price,quantity,cumulative_quantity\nbuy,99,1,1\nbuy,98,2.5,3.5\nsell,101,4,4\n"
+        );
+
+        let mut alert = PriceAlert::new(3, "ETHUSDT".to_string(), AlertType::PriceCross(2000.0), "cross, then".to_string());
+        alert.behavior = AlertBehavior::Repeating { cooldown: Duration::from_secs(60) };
+        alert.created_at = timestamp;
+        let alerts = alerts_csv(&[alert]);
+        assert_eq!(
+            alerts.lines().nth(1),
+            Some("3,ETHUSDT,price_cross,2000,\"cross, then\",true,repeat_60s,2024-03-01T10:00:00+00:00,,0")
+        );
+    }
+
+    #[test]
+    fn test_write_export_refuses_to_overwrite_without_force() {
+        let path = temp_export_file("orders.csv");
+        write_export(&path, "first\n", false).unwrap();
+        let error = write_export(&path, "second\n", false).unwrap_err();
+        assert!(error.ends_with("already exists; add --force to overwrite it"), "{}", error);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\n");
+
+        write_export(&path, "third\n", true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third\n");
+        std::fs::remove_file(&path).unwrap();
+
+        let missing_dir = path.with_file_name("no-such-dir").join("x.csv");
+        assert!(write_export(&missing_dir, "", false).unwrap_err().starts_with("Could not write"));
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = PathBuf::from(std::env::var_os("HOME").unwrap());
+        assert_eq!(expand_home("~/orders.csv"), home.join("orders.csv"));
+        assert_eq!(expand_home("~"), home);
+        assert_eq!(expand_home("/tmp/~x.csv"), PathBuf::from("/tmp/~x.csv"));
+        assert_eq!(expand_home("~other/x.csv"), PathBuf::from("~other/x.csv"));
+    }
+}
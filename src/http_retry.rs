@@ -0,0 +1,261 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use std::future::Future;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryConfig {
+    /// Total tries, including the first; 1 disables retries.
+    pub max_attempts: u32,
+    /// Backoff before the second attempt; doubles for each one after it.
+    pub base_delay: Duration,
+    /// Cap on a single wait. A `Retry-After` longer than this is not waited
+    /// out and the failing response is returned instead.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// A single attempt.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Exponential backoff after failed attempt number `attempt` (1-based),
+    /// with "equal jitter": somewhere between half and all of the capped delay.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = self.base_delay.saturating_mul(1u32 << exponent).min(self.max_delay);
+        delay / 2 + delay.mul_f64(rand::random::<f64>() / 2.0)
+    }
+}
+
+/// Methods that can be replayed without changing the outcome. DELETE is left
+/// out: a replayed cancel reports the order as unknown once the first landed.
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT)
+}
+
+/// Throttling and server-side failures. Binance's 418 (IP ban) is not
+/// included; retrying only extends the ban.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// The `Retry-After` header, given either as delay seconds or as an HTTP date.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Sends the request built by `build` until it succeeds, fails in a way that
+/// retrying cannot fix, or `config.max_attempts` is used up. The last response
+/// or error is returned as-is, so callers map failures exactly as before.
+///
+/// Only use this for requests that are safe to replay; see `is_idempotent`.
+pub async fn send_with_retry<F>(config: &RetryConfig, build: F) -> Result<Response, reqwest::Error>
+where
+    F: FnMut() -> RequestBuilder,
+{
+    send_with_retry_gated(config, || async { Ok(()) }, build).await
+}
+
+/// Like `send_with_retry`, but awaits `before_attempt` ahead of every attempt,
+/// so a rate limiter is charged for the retries too. An error from it stops
+/// the retries and is returned in place of the last response.
+pub async fn send_with_retry_gated<F, G, Fut, E>(
+    config: &RetryConfig,
+    mut before_attempt: G,
+    mut build: F,
+) -> Result<Response, E>
+where
+    F: FnMut() -> RequestBuilder,
+    G: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+    E: From<reqwest::Error>,
+{
+    let mut attempt = 1;
+    loop {
+        before_attempt().await?;
+        let result = build().send().await;
+        let delay = match &result {
+            Ok(response) if is_retryable_status(response.status()) => {
+                retry_after(response.headers()).unwrap_or_else(|| config.backoff(attempt))
+            }
+            Err(error) if error.is_connect() || error.is_timeout() => config.backoff(attempt),
+            _ => return Ok(result?),
+        };
+        if attempt >= config.max_attempts || delay > config.max_delay {
+            return Ok(result?);
+        }
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn fast_retries() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_with_jitter_and_cap() {
+        let config = RetryConfig {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+        };
+        for _ in 0..50 {
+            let first = config.backoff(1);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+            let third = config.backoff(3);
+            assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+            let capped = config.backoff(9);
+            assert!(capped >= Duration::from_millis(500) && capped <= Duration::from_millis(1000));
+        }
+        assert!(config.backoff(u32::MAX) <= Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(RETRY_AFTER, "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(120)).to_rfc2822();
+        headers.insert(RETRY_AFTER, later.parse().unwrap());
+        let wait = retry_after(&headers).unwrap();
+        assert!(wait > Duration::from_secs(100) && wait <= Duration::from_secs(120));
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_idempotent_methods_and_retryable_statuses() {
+        assert!(is_idempotent(&Method::GET));
+        assert!(is_idempotent(&Method::PUT));
+        assert!(!is_idempotent(&Method::POST));
+        assert!(!is_idempotent(&Method::DELETE));
+
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::REQUEST_TIMEOUT));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::IM_A_TEAPOT));
+    }
+
+    #[tokio::test]
+    async fn test_fails_twice_then_succeeds() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/flaky"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/flaky"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let http = reqwest::Client::new();
+        let url = format!("{}/flaky", server.uri());
+        let response = send_with_retry(&fast_retries(), || http.get(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts_or_long_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/down"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(3)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/throttled"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "60"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let http = reqwest::Client::new();
+        let config = fast_retries();
+        for (route, status) in [("/down", 500), ("/throttled", 429), ("/missing", 404)] {
+            let url = format!("{}{}", server.uri(), route);
+            let response = send_with_retry(&config, || http.get(&url)).await.unwrap();
+            assert_eq!(response.status().as_u16(), status);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_honours_short_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let http = reqwest::Client::new();
+        let config = RetryConfig {
+            max_attempts: 2,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(60),
+        };
+        // Retry-After: 0 wins over the 30s backoff, so this returns promptly
+        let started = std::time::Instant::now();
+        let response = send_with_retry(&config, || http.get(server.uri())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::ui::Candlestick;
+
+/// Bollinger bands: a simple moving average of the closes with bands `k`
+/// population standard deviations above and below it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BollingerBands {
+    pub middle: Vec<Option<f64>>,
+    pub upper: Vec<Option<f64>>,
+    pub lower: Vec<Option<f64>>,
+}
+
+/// MACD line (fast EMA minus slow EMA), its signal line (an EMA of the MACD
+/// line) and the histogram between them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Macd {
+    pub macd: Vec<Option<f64>>,
+    pub signal: Vec<Option<f64>>,
+    pub histogram: Vec<Option<f64>>,
+}
+
+// Every series below has one entry per candle; entries before the indicator
+// has enough history are `None`, so a series shorter than the period is all
+// `None` and a period of 0 yields nothing.
+
+fn closes(candles: &[Candlestick]) -> Vec<Option<f64>> {
+    candles.iter().map(|candle| Some(candle.close)).collect()
+}
+
+/// Exponential moving average of `values` from their first `period`
+/// consecutive values, seeded with the simple average of those.
+fn ema_of(values: &[Option<f64>], period: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; values.len()];
+    if period == 0 {
+        return out;
+    }
+    let Some(first) = values.iter().position(Option::is_some) else {
+        return out;
+    };
+    let seed_end = first + period - 1;
+    if seed_end >= values.len() || values[first..=seed_end].iter().any(Option::is_none) {
+        return out;
+    }
+
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let mut ema = values[first..=seed_end].iter().flatten().sum::<f64>() / period as f64;
+    out[seed_end] = Some(ema);
+    for (index, value) in values.iter().enumerate().skip(seed_end + 1) {
+        let Some(value) = value else { break };
+        ema += (value - ema) * alpha;
+        out[index] = Some(ema);
+    }
+    out
+}
+
+/// Exponential moving average of the closes.
+pub fn ema(candles: &[Candlestick], period: usize) -> Vec<Option<f64>> {
+    ema_of(&closes(candles), period)
+}
+
+/// Wilder's relative strength index of the closes, from 0 to 100. The first
+/// value needs `period` price changes, so it lands on candle `period`.
+pub fn rsi(candles: &[Candlestick], period: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; candles.len()];
+    if period == 0 || candles.len() <= period {
+        return out;
+    }
+
+    let changes: Vec<f64> = candles.windows(2).map(|pair| pair[1].close - pair[0].close).collect();
+    let mut average_gain = changes[..period].iter().map(|change| change.max(0.0)).sum::<f64>() / period as f64;
+    let mut average_loss = changes[..period].iter().map(|change| (-change).max(0.0)).sum::<f64>() / period as f64;
+
+    let strength = |gain: f64, loss: f64| match (gain, loss) {
+        (gain, loss) if loss > 0.0 => 100.0 - 100.0 / (1.0 + gain / loss),
+        (gain, _) if gain > 0.0 => 100.0,
+        // A flat stretch is neither overbought nor oversold
+        _ => 50.0,
+    };
+    out[period] = Some(strength(average_gain, average_loss));
+    for (index, change) in changes.iter().enumerate().skip(period) {
+        average_gain = (average_gain * (period - 1) as f64 + change.max(0.0)) / period as f64;
+        average_loss = (average_loss * (period - 1) as f64 + (-change).max(0.0)) / period as f64;
+        out[index + 1] = Some(strength(average_gain, average_loss));
+    }
+    out
+}
+
+/// Bollinger bands over `period` closes, `k` standard deviations wide.
+pub fn bollinger(candles: &[Candlestick], period: usize, k: f64) -> BollingerBands {
+    let mut bands = BollingerBands {
+        middle: vec![None; candles.len()],
+        upper: vec![None; candles.len()],
+        lower: vec![None; candles.len()],
+    };
+    if period == 0 {
+        return bands;
+    }
+
+    for (offset, window) in candles.windows(period).enumerate() {
+        let index = offset + period - 1;
+        let mean = window.iter().map(|candle| candle.close).sum::<f64>() / period as f64;
+        let variance = window.iter().map(|candle| (candle.close - mean).powi(2)).sum::<f64>() / period as f64;
+        let width = k * variance.sqrt();
+        bands.middle[index] = Some(mean);
+        bands.upper[index] = Some(mean + width);
+        bands.lower[index] = Some(mean - width);
+    }
+    bands
+}
+
+/// MACD with `fast` and `slow` EMAs of the closes and a `signal` EMA of the
+/// MACD line, the usual parameters being 12, 26 and 9.
+pub fn macd(candles: &[Candlestick], fast: usize, slow: usize, signal: usize) -> Macd {
+    let fast = ema(candles, fast);
+    let slow = ema(candles, slow);
+    let macd: Vec<Option<f64>> = fast
+        .iter()
+        .zip(&slow)
+        .map(|(fast, slow)| Some((*fast)? - (*slow)?))
+        .collect();
+    let signal = ema_of(&macd, signal);
+    let histogram = macd
+        .iter()
+        .zip(&signal)
+        .map(|(macd, signal)| Some((*macd)? - (*signal)?))
+        .collect();
+    Macd { macd, signal, histogram }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candles(closes: &[f64]) -> Vec<Candlestick> {
+        let start = chrono::Utc::now();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let timestamp = start + chrono::Duration::minutes(i as i64);
+                Candlestick::new(timestamp, *close, *close, *close, *close, 1.0)
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: &[Option<f64>], expected: &[f64], tolerance: f64) {
+        assert_eq!(actual.len(), expected.len());
+        for (i, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+            let actual = actual.unwrap();
+            assert!((actual - expected).abs() <= tolerance, "#{}: {} vs {}", i, actual, expected);
+        }
+    }
+
+    // StockCharts' 10-day EMA worked example
+    const EMA_CLOSES: [f64; 30] = [
+        22.27, 22.19, 22.08, 22.17, 22.18, 22.13, 22.23, 22.43, 22.24, 22.29, 22.15, 22.39, 22.38, 22.61, 23.36,
+        24.05, 23.75, 23.83, 23.95, 23.63, 23.82, 23.87, 23.65, 23.19, 23.10, 23.33, 22.68, 23.10, 22.40, 22.17,
+    ];
+
+    #[test]
+    fn test_ema_matches_reference() {
+        let ema = ema(&candles(&EMA_CLOSES), 10);
+        assert!(ema[..9].iter().all(Option::is_none));
+        assert_close(
+            &ema[9..],
+            &[
+                22.22, 22.21, 22.24, 22.27, 22.33, 22.52, 22.80, 22.97, 23.13, 23.28, 23.34, 23.43, 23.51, 23.54,
+                23.47, 23.40, 23.39, 23.26, 23.23, 23.08, 22.92,
+            ],
+            0.011,
+        );
+    }
+
+    #[test]
+    fn test_rsi_matches_wilder_example() {
+        let closes = [
+            44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61, 46.28, 46.28,
+            46.00, 46.03, 46.41, 46.22, 45.64, 46.21, 46.25, 45.71, 46.45, 45.78, 45.35, 44.03, 44.18, 44.22, 44.57,
+            43.42, 42.66, 43.13,
+        ];
+        let rsi = rsi(&candles(&closes), 14);
+        assert!(rsi[..14].iter().all(Option::is_none));
+        // The published table rounds its running averages to two places,
+        // which moves each RSI by up to ~0.07
+        assert_close(
+            &rsi[14..],
+            &[
+                70.53, 66.32, 66.55, 69.41, 66.36, 57.97, 62.93, 63.26, 56.06, 62.38, 54.71, 50.42, 39.99, 41.46,
+                41.87, 45.46, 37.30, 33.08, 37.77,
+            ],
+            0.1,
+        );
+
+        assert_eq!(super::rsi(&candles(&[1.0, 2.0, 3.0]), 2)[2], Some(100.0));
+        assert_eq!(super::rsi(&candles(&[3.0, 2.0, 1.0]), 2)[2], Some(0.0));
+        assert_eq!(super::rsi(&candles(&[5.0, 5.0, 5.0]), 2)[2], Some(50.0));
+    }
+
+    #[test]
+    fn test_bollinger_bands() {
+        let bands = bollinger(&candles(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]), 5, 2.0);
+        assert!(bands.middle[..4].iter().all(Option::is_none));
+        // Population standard deviation of 1..=5 is sqrt(2)
+        assert_close(&bands.middle[4..], &[3.0, 4.0], 1e-12);
+        assert_close(&bands.upper[4..], &[3.0 + 2.0 * 2f64.sqrt(), 4.0 + 2.0 * 2f64.sqrt()], 1e-12);
+        assert_close(&bands.lower[4..], &[3.0 - 2.0 * 2f64.sqrt(), 4.0 - 2.0 * 2f64.sqrt()], 1e-12);
+
+        let bands = bollinger(&candles(&EMA_CLOSES), 20, 2.0);
+        assert_close(&bands.middle[19..20], &[22.7155], 1e-4);
+        assert_close(&bands.upper[19..20], &[24.1261], 1e-4);
+        assert_close(&bands.lower[19..20], &[21.3049], 1e-4);
+    }
+
+    #[test]
+    fn test_macd_lines_align() {
+        let series = candles(&EMA_CLOSES);
+        let macd = macd(&series, 3, 6, 4);
+        let fast = ema(&series, 3);
+        let slow = ema(&series, 6);
+
+        // The MACD line starts with the slow EMA, the signal `signal - 1` later
+        assert!(macd.macd[..5].iter().all(Option::is_none));
+        assert!(macd.signal[..8].iter().all(Option::is_none));
+        assert!(macd.signal[8..].iter().all(Option::is_some));
+        for i in 5..series.len() {
+            assert!((macd.macd[i].unwrap() - (fast[i].unwrap() - slow[i].unwrap())).abs() < 1e-12);
+        }
+        let seed = macd.macd[5..9].iter().flatten().sum::<f64>() / 4.0;
+        assert!((macd.signal[8].unwrap() - seed).abs() < 1e-12);
+        assert_eq!(macd.histogram[8], Some(macd.macd[8].unwrap() - macd.signal[8].unwrap()));
+
+        let flat = super::macd(&candles(&[10.0; 40]), 12, 26, 9);
+        assert!(flat.histogram[33..].iter().all(|value| value.unwrap().abs() < 1e-12));
+        assert!(flat.histogram[..33].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_short_series_and_zero_periods() {
+        let short = candles(&[1.0, 2.0, 3.0]);
+        assert_eq!(ema(&short, 5), vec![None; 3]);
+        assert_eq!(rsi(&short, 3), vec![None; 3]);
+        assert_eq!(bollinger(&short, 4, 2.0).upper, vec![None; 3]);
+        assert_eq!(macd(&short, 12, 26, 9).signal, vec![None; 3]);
+
+        assert_eq!(ema(&short, 0), vec![None; 3]);
+        assert_eq!(rsi(&short, 0), vec![None; 3]);
+        assert_eq!(bollinger(&short, 0, 2.0).middle, vec![None; 3]);
+        assert!(ema(&[], 3).is_empty());
+    }
+}
@@ -3,16 +3,44 @@ pub mod order_book;
 pub mod price;
 pub mod trade;
 pub mod binance_ws;
+pub mod binance_rest;
+pub mod binance_orders;
+pub mod binance_user_data;
+pub mod candles;
+pub mod command_line;
+pub mod commands;
+pub mod config;
+pub mod exchange;
+pub mod export;
+pub mod http_retry;
+pub mod indicators;
+pub mod logging;
 pub mod polymarket_orders;
+pub mod polymarket_user;
+pub mod rate_limiter;
+pub mod simulator;
+pub mod theme;
+pub mod trade_flow;
 pub mod ui;
+pub mod watchlist;
 
 pub use order::{Order, OrderSide};
-pub use order_book::OrderBook;
+pub use order_book::{
+    BookDiff, LevelChange, MatchResult, OrderBook, OrderBookSnapshot, PostOnlyRejected, PriceRule, QueuePosition,
+    SideDiff, TakeProfitOrder, DEFAULT_MAX_MATCH_ITERATIONS, DISPLAY_DEPTH_LEVELS, TRADE_HISTORY_CAPACITY,
+};
 pub use price::Price;
-pub use trade::Trade;
+pub use trade::{FeeSchedule, Trade};
 pub use binance_ws::run_binance_client;
-pub use polymarket_orders::{PolymarketClobClient, PolymarketOrderSide, PolymarketOrderType, PolymarketSignatureType, PolymarketOrder, PolymarketOrderArgs};
+pub use binance_orders::{BinanceError, BinanceOrderClient, FilterViolation};
+pub use exchange::{ExchangeClient, ExchangeError};
+pub use binance_rest::{BinanceRestClient, BinanceRestError, SymbolFilters};
+pub use http_retry::RetryConfig;
+pub use rate_limiter::{RateLimitConfig, RateLimiter, RateLimitUsage};
+pub use polymarket_orders::{AssetType, BalanceShortfall, BookSnapshot, CancelResponse, MarketParams, PolymarketClobClient, PolymarketError, PolymarketOrderSide, PolymarketOrderStatus, PolymarketOrderType, PolymarketSignatureType, PolymarketOrder, PolymarketOrderArgs};
+pub use polymarket_user::{PolymarketUserEvent, UserChannelConfig};
 pub use ui::App;
+pub use rust_decimal::Decimal;
 
 #[cfg(test)]
 mod tests {
@@ -46,12 +74,194 @@ mod tests {
         order_book.add_order(OrderSide::Bid, 100.0, 10.0, 1);
         order_book.add_order(OrderSide::Ask, 99.0, 15.0, 2);
         
-        let trades = order_book.match_orders();
+        let trades = order_book.match_orders().trades;
         assert_eq!(trades.len(), 1);
         
         let trade = &trades[0];
         assert_eq!(trade.price, 100.0); // Price-time priority: bid came first
         assert_eq!(trade.quantity, 10.0);
+        assert_eq!(trade.aggressor_side, OrderSide::Ask);
+    }
+
+    #[test]
+    fn test_limit_order_reports_only_its_own_fills() {
+        // Added without matching, the book is already crossed at 100 / 99
+        let order_book = OrderBook::new();
+        let crossed_bid = order_book.add_order(OrderSide::Bid, 100.0, 5.0, 1);
+        let crossed_ask = order_book.add_order(OrderSide::Ask, 99.0, 5.0, 2);
+        let resting_ask = order_book.add_order(OrderSide::Ask, 101.0, 2.0, 3);
+
+        let (order_id, trades) = order_book.add_limit_order(OrderSide::Bid, 99.5, 1.0, 4);
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].bid_order_id, trades[0].ask_order_id), (order_id, crossed_ask));
+        assert_eq!((trades[0].price, trades[0].quantity, trades[0].aggressor_side), (99.0, 1.0, OrderSide::Bid));
+        // The crossed bid did not trade and the new bid did not rest
+        assert_eq!(order_book.get_order(crossed_bid).map(|order| order.quantity), Some(5.0));
+        assert!(order_book.get_order(order_id).is_none());
+        assert_eq!(order_book.get_order(crossed_ask).map(|order| order.quantity), Some(4.0));
+
+        // The rest of an order the opposite side cannot fill rests at its price
+        let (order_id, trades) = order_book.add_limit_order(OrderSide::Ask, 100.5, 3.0, 5);
+        assert!(trades.is_empty());
+        assert_eq!(order_book.get_order(order_id).map(|order| order.quantity), Some(3.0));
+        let ask_id = order_id;
+        let (order_id, trades) = order_book.add_limit_order(OrderSide::Bid, 101.0, 10.0, 6);
+        let filled: Vec<(u64, f64)> = trades.iter().map(|trade| (trade.ask_order_id, trade.quantity)).collect();
+        assert_eq!(filled, [(crossed_ask, 4.0), (ask_id, 3.0), (resting_ask, 2.0)]);
+        assert_eq!(order_book.get_order(order_id).map(|order| order.quantity), Some(1.0));
+        assert_eq!(order_book.get_best_bid(), Some(101.0));
+        assert_eq!(order_book.recent_trades(10).len(), 4);
+    }
+
+    #[test]
+    fn test_trade_fees_by_role() {
+        let order_book = OrderBook::new();
+        assert_eq!(order_book.fee_schedule(), FeeSchedule::default());
+        order_book.set_fee_schedule(FeeSchedule::new(0.0, 2.0));
+
+        // The resting bid is the maker, the later ask the taker
+        order_book.add_order(OrderSide::Bid, 100.0, 10.0, 1);
+        let (_, trades) = order_book.add_limit_order(OrderSide::Ask, 99.0, 4.0, 2);
+        assert_eq!(trades.len(), 1);
+        let trade = &trades[0];
+        assert_eq!(trade.aggressor_side, OrderSide::Ask);
+        assert_eq!(trade.notional(), 400.0);
+        assert_eq!(trade.maker_fee, 0.0);
+        assert!((trade.taker_fee - 0.08).abs() < 1e-12);
+
+        let trades = order_book.add_market_order(OrderSide::Ask, 1.0, 3);
+        assert!((trades[0].taker_fee - 0.02).abs() < 1e-12);
+
+        // Zero fees by default
+        let free = OrderBook::new();
+        free.add_order(OrderSide::Bid, 100.0, 1.0, 1);
+        let (_, trades) = free.add_limit_order(OrderSide::Ask, 100.0, 1.0, 2);
+        assert_eq!((trades[0].maker_fee, trades[0].taker_fee), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_price_rules() {
+        // A resting bid at 101 crossed by a later ask limited to 99
+        let cross = |rule| {
+            let order_book = OrderBook::new();
+            order_book.set_price_rule(rule);
+            order_book.add_order(OrderSide::Bid, 101.0, 2.0, 1);
+            let (_, trades) = order_book.add_limit_order(OrderSide::Ask, 99.0, 1.0, 2);
+            assert_eq!(trades.len(), 1);
+            assert_eq!(trades[0].aggressor_side, OrderSide::Ask);
+            trades[0].price
+        };
+        assert_eq!(OrderBook::new().price_rule(), PriceRule::MakerPrice);
+        assert_eq!(cross(PriceRule::MakerPrice), 101.0);
+        assert_eq!(cross(PriceRule::TakerPrice), 99.0);
+        assert_eq!(cross(PriceRule::Midpoint), 100.0);
+
+        // The maker is whichever order came first, whatever its side
+        let order_book = OrderBook::new();
+        order_book.set_price_rule(PriceRule::TakerPrice);
+        order_book.add_order(OrderSide::Ask, 99.0, 1.0, 1);
+        let (_, trades) = order_book.add_limit_order(OrderSide::Bid, 101.0, 1.0, 2);
+        assert_eq!((trades[0].price, trades[0].aggressor_side), (101.0, OrderSide::Bid));
+
+        // Market orders name no price, so they take the maker's under any rule
+        order_book.set_price_rule(PriceRule::Midpoint);
+        order_book.add_order(OrderSide::Ask, 105.0, 1.0, 3);
+        let trades = order_book.add_market_order(OrderSide::Bid, 1.0, 4);
+        assert_eq!(trades[0].price, 105.0);
+
+        let resting = order_book::OrderQueue::new();
+        resting.add_order(Order::new(1, OrderSide::Ask, 99.0, 1.0, 1));
+        let incoming = order_book::OrderQueue::new();
+        incoming.add_order(Order::new(2, OrderSide::Bid, 101.0, 1.0, 2));
+        assert_eq!(incoming.match_orders_with(&resting, OrderSide::Bid, PriceRule::MakerPrice)[0].price, 99.0);
+        assert_eq!(incoming.match_orders_with(&resting, OrderSide::Bid, PriceRule::Midpoint)[0].price, 100.0);
+    }
+
+    #[test]
+    fn test_post_only_orders() {
+        let order_book = OrderBook::new();
+        order_book.add_order(OrderSide::Bid, 100.0, 10.0, 1);
+        order_book.add_order(OrderSide::Ask, 101.0, 10.0, 2);
+
+        // Touching or crossing the opposite best is rejected without resting
+        let rejected = order_book.add_post_only_order(OrderSide::Bid, 101.0, 1.0, 3).unwrap_err();
+        assert_eq!(rejected, PostOnlyRejected { side: OrderSide::Bid, price: 101.0, opposite_best: 101.0 });
+        assert!(order_book.add_post_only_order(OrderSide::Ask, 99.5, 1.0, 4).is_err());
+        assert_eq!(order_book.get_total_orders(), 2);
+        assert!(order_book.match_orders().trades.is_empty());
+
+        // Inside the spread it rests like any limit order
+        let bid = order_book.add_post_only_order(OrderSide::Bid, 100.5, 2.0, 5).unwrap();
+        let ask = order_book.add_post_only_order(OrderSide::Ask, 100.75, 3.0, 6).unwrap();
+        assert_eq!(order_book.get_best_bid(), Some(100.5));
+        assert_eq!(order_book.get_best_ask(), Some(100.75));
+        assert!(order_book.get_order(bid).is_some());
+        assert!(order_book.get_order(ask).is_some());
+
+        // An empty opposite side never rejects
+        let empty = OrderBook::new();
+        assert!(empty.add_post_only_order(OrderSide::Ask, 1.0, 1.0, 1).is_ok());
+    }
+
+    #[test]
+    fn test_take_profit_triggers_when_price_crosses_target() {
+        let order_book = OrderBook::new();
+        order_book.add_order(OrderSide::Bid, 109.0, 5.0, 1);
+        order_book.add_order(OrderSide::Ask, 111.0, 5.0, 2);
+
+        // Long 2 from 100: sell once the last trade reaches 110
+        let long_exit = order_book.add_take_profit_order(OrderSide::Ask, 110.0, 2.0, 3);
+        // Short 1: buy back once price falls to 95
+        let short_exit = order_book.add_take_profit_order(OrderSide::Bid, 95.0, 1.0, 4);
+        assert_eq!(order_book.get_total_orders(), 2);
+        assert_eq!(order_book.take_profit_orders().len(), 2);
+
+        assert!(order_book.trigger_take_profits(105.0).is_empty());
+        assert!(order_book.trigger_take_profits(109.99).is_empty());
+
+        let trades = order_book.trigger_take_profits(110.5);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].price, 109.0);
+        assert_eq!(trades[0].quantity, 2.0);
+        assert_eq!(trades[0].aggressor_side, OrderSide::Ask);
+        let remaining: Vec<u64> = order_book.take_profit_orders().iter().map(|order| order.id).collect();
+        assert_eq!(remaining, vec![short_exit]);
+
+        // Fired orders are gone, so the same price does not fire them again
+        assert!(order_book.trigger_take_profits(110.5).is_empty());
+        assert!(order_book.cancel_take_profit(long_exit).is_none());
+        assert!(order_book.cancel_take_profit(short_exit).is_some());
+        assert!(order_book.trigger_take_profits(90.0).is_empty());
+    }
+
+    #[test]
+    fn test_checksum_is_stable_across_insertion_order() {
+        let orders = [
+            (OrderSide::Bid, 100.0, 1.5),
+            (OrderSide::Bid, 100.0, 0.5),
+            (OrderSide::Bid, 99.5, 3.0),
+            (OrderSide::Bid, 99.0, 1.0),
+            (OrderSide::Ask, 101.0, 1.5),
+            (OrderSide::Ask, 101.5, 4.0),
+        ];
+        let forward = OrderBook::new();
+        for (i, (side, price, quantity)) in orders.iter().enumerate() {
+            forward.add_order(*side, *price, *quantity, i as u64);
+        }
+        let reversed = OrderBook::new();
+        for (i, (side, price, quantity)) in orders.iter().rev().enumerate() {
+            reversed.add_order(*side, *price, *quantity, i as u64);
+        }
+
+        // CRC32 of "100:2:101:1.5:99.5:3:101.5:4:99:1"
+        assert_eq!(forward.checksum(3), 3601828351);
+        assert_eq!(reversed.checksum(3), forward.checksum(3));
+        assert_ne!(forward.checksum(1), forward.checksum(3));
+        // Every level, the way depth queries spell it
+        assert_eq!(forward.checksum(usize::MAX), forward.checksum(3));
+
+        reversed.add_order(OrderSide::Ask, 102.0, 1.0, 10);
+        assert_ne!(reversed.checksum(3), forward.checksum(3));
     }
 
     #[test]
@@ -98,6 +308,51 @@ mod tests {
         assert_eq!(order_book.get_total_price_levels(), (10, 10));
     }
 
+    #[test]
+    fn test_concurrent_post_only_orders_never_cross() {
+        use std::sync::Arc;
+        use std::thread;
+
+        // Bids and asks race for the same prices; whichever rests first
+        // makes the other side reject, so the book never ends up crossed
+        let order_book = Arc::new(OrderBook::new());
+        let handles: Vec<_> = [OrderSide::Bid, OrderSide::Ask]
+            .into_iter()
+            .map(|side| {
+                let order_book = Arc::clone(&order_book);
+                thread::spawn(move || {
+                    for i in 0..500 {
+                        let _ = order_book.add_post_only_order(side, 100.0 + (i % 5) as f64, 1.0, i);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        if let (Some(bid), Some(ask)) = (order_book.get_best_bid(), order_book.get_best_ask()) {
+            assert!(bid < ask, "crossed book: bid {} ask {}", bid, ask);
+        }
+        assert!(order_book.match_orders().trades.is_empty());
+    }
+
+    #[test]
+    fn test_total_notional() {
+        let order_book = OrderBook::new();
+        assert_eq!(order_book.total_notional(), (0.0, 0.0));
+
+        order_book.add_order(OrderSide::Bid, 100.0, 2.0, 1);
+        order_book.add_order(OrderSide::Bid, 100.0, 0.5, 2);
+        let deeper_bid = order_book.add_order(OrderSide::Bid, 99.5, 4.0, 3);
+        let ask = order_book.add_order(OrderSide::Ask, 101.0, 3.0, 4);
+        assert_eq!(order_book.total_notional(), (250.0 + 398.0, 303.0));
+
+        order_book.update_order(ask, 1.0);
+        order_book.remove_order(deeper_bid);
+        assert_eq!(order_book.total_notional(), (250.0, 101.0));
+    }
+
     #[test]
     fn test_order_removal() {
         let order_book = OrderBook::new();
@@ -110,6 +365,88 @@ mod tests {
         assert_eq!(order_book.get_total_orders(), 0);
     }
 
+    #[test]
+    fn test_cancel_all_by_owner() {
+        let order_book = OrderBook::new();
+        let alice_bid = order_book.add_order_for_owner(OrderSide::Bid, 100.0, 1.0, 1, "alice");
+        let bob_bid = order_book.add_order_for_owner(OrderSide::Bid, 100.0, 2.0, 2, "bob");
+        let alice_top = order_book.add_order_for_owner(OrderSide::Bid, 101.0, 1.0, 3, "alice");
+        let alice_ask = order_book.add_order_for_owner(OrderSide::Ask, 105.0, 3.0, 4, "alice");
+        let anonymous_ask = order_book.add_order(OrderSide::Ask, 106.0, 1.0, 5);
+
+        let cancelled = order_book.cancel_all_by_owner("alice");
+        let mut ids: Vec<u64> = cancelled.iter().map(|order| order.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec![alice_bid, alice_top, alice_ask]);
+        assert!(cancelled.iter().all(|order| order.owner.as_deref() == Some("alice")));
+
+        assert_eq!(order_book.get_total_orders(), 2);
+        assert!(order_book.get_order(bob_bid).is_some());
+        assert!(order_book.get_order(anonymous_ask).is_some());
+        // The emptied 101 bid and 105 ask levels are gone
+        assert_eq!(order_book.get_total_price_levels(), (1, 1));
+        assert_eq!(order_book.get_best_bid(), Some(100.0));
+        assert_eq!(order_book.get_best_ask(), Some(106.0));
+        assert_eq!(order_book.get_stats().total_orders_cancelled, 3);
+        assert!(order_book.validate_consistency());
+
+        assert!(order_book.cancel_all_by_owner("alice").is_empty());
+        assert_eq!(order_book.get_stats().total_orders_cancelled, 3);
+    }
+
+    #[test]
+    fn test_queue_position() {
+        let order_book = OrderBook::new();
+        let first = order_book.add_order(OrderSide::Ask, 100.0, 1.5, 1);
+        let middle = order_book.add_order(OrderSide::Ask, 100.0, 2.0, 2);
+        let last = order_book.add_order(OrderSide::Ask, 100.0, 3.0, 3);
+        // A better price doesn't count: only the order's own level does
+        order_book.add_order(OrderSide::Ask, 99.0, 10.0, 4);
+
+        let position = order_book.queue_position(middle).unwrap();
+        assert_eq!(position, QueuePosition { side: OrderSide::Ask, price: 100.0, orders_ahead: 1, quantity_ahead: 1.5 });
+        assert_eq!(order_book.queue_position(first).unwrap().orders_ahead, 0);
+        assert_eq!(order_book.queue_position(last).unwrap().quantity_ahead, 3.5);
+
+        // Orders ahead leaving move it up the queue
+        order_book.remove_order(first);
+        let position = order_book.queue_position(last).unwrap();
+        assert_eq!((position.orders_ahead, position.quantity_ahead), (1, 2.0));
+
+        let bid = order_book.add_order(OrderSide::Bid, 90.0, 1.0, 5);
+        assert_eq!(order_book.queue_position(bid).map(|p| p.side), Some(OrderSide::Bid));
+        assert_eq!(order_book.queue_position(999), None);
+    }
+
+    #[test]
+    fn test_fork_leaves_the_original_untouched() {
+        let order_book = OrderBook::new();
+        order_book.set_price_rule(PriceRule::Midpoint);
+        order_book.add_order(OrderSide::Ask, 100.0, 1.0, 1);
+        order_book.add_order(OrderSide::Ask, 101.0, 2.0, 2);
+        order_book.add_order(OrderSide::Ask, 102.0, 3.0, 3);
+        let resting_bid = order_book.add_order(OrderSide::Bid, 95.0, 4.0, 4);
+        let depth = order_book.get_market_depth(10);
+
+        let fork = order_book.fork();
+        assert_eq!(fork.get_market_depth(10), depth);
+        assert_eq!(fork.price_rule(), PriceRule::Midpoint);
+
+        let (order_id, trades) = fork.add_limit_order(OrderSide::Bid, 105.0, 10.0, 5);
+        assert_eq!(trades.len(), 3);
+        assert_eq!(fork.get_market_depth(10), (vec![(105.0, 4.0), (95.0, 4.0)], vec![]));
+        fork.update_order(resting_bid, 1.0);
+        assert!(order_book.get_order(order_id).is_none());
+
+        assert_eq!(order_book.get_market_depth(10), depth);
+        assert_eq!(order_book.get_total_orders(), 4);
+        assert_eq!(order_book.get_order(resting_bid).map(|order| order.quantity), Some(4.0));
+        assert!(order_book.recent_trades(10).is_empty());
+        assert_eq!(order_book.get_stats().total_volume_traded, 0.0);
+        // Ids carry on from the original's in both books
+        assert_eq!(order_book.add_order(OrderSide::Bid, 90.0, 1.0, 6), order_id);
+    }
+
     #[test]
     fn test_order_update() {
         let order_book = OrderBook::new();
@@ -138,5 +475,498 @@ mod tests {
         assert_eq!(stats.best_ask, Some(101.0));
         assert_eq!(stats.spread, Some(1.0));
         assert_eq!(stats.mid_price, Some(100.5));
+        assert_eq!(stats.imbalance, Some(-0.2));
+
+        order_book.add_order(OrderSide::Bid, 99.0, 15.0, 3);
+        assert_eq!(order_book.get_stats().imbalance, Some(0.25));
+        order_book.clear();
+        assert_eq!(order_book.get_stats().imbalance, None);
+    }
+
+    #[test]
+    fn test_incremental_stats_match_full_recompute() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let order_book = OrderBook::new();
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut ids = Vec::new();
+
+        for step in 0..2000u64 {
+            let side = if rng.gen::<bool>() { OrderSide::Bid } else { OrderSide::Ask };
+            let price = 95.0 + rng.gen_range(0..100) as f64 * 0.1;
+            match rng.gen_range(0..10) {
+                0..=3 => ids.push(order_book.add_order(side, price, rng.gen_range(1.0..5.0), step)),
+                4 | 5 if !ids.is_empty() => {
+                    let id = ids.swap_remove(rng.gen_range(0..ids.len()));
+                    order_book.remove_order(id);
+                }
+                6 if !ids.is_empty() => {
+                    order_book.update_order(ids[rng.gen_range(0..ids.len())], rng.gen_range(1.0..5.0));
+                }
+                7 => order_book.set_level_quantity(side, price, rng.gen_range(0.0..3.0_f64).floor(), step),
+                8 => {
+                    order_book.match_orders();
+                }
+                _ => {
+                    order_book.add_market_order(side, rng.gen_range(0.5..3.0), step);
+                }
+            }
+
+            // What a full recompute from the book would give
+            let best_bid = order_book.get_best_bid();
+            let best_ask = order_book.get_best_ask();
+            let stats = order_book.get_stats();
+            assert_eq!(stats.best_bid, best_bid, "best bid after step {}", step);
+            assert_eq!(stats.best_ask, best_ask, "best ask after step {}", step);
+            match (best_bid, best_ask) {
+                (Some(bid), Some(ask)) => {
+                    assert_eq!(stats.spread, Some(ask - bid));
+                    assert_eq!(stats.mid_price, Some((bid + ask) / 2.0));
+                }
+                _ => assert_eq!((stats.spread, stats.mid_price), (None, None)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_stats_json_round_trip() {
+        use order_book::OrderBookStats;
+
+        let order_book = OrderBook::new();
+        order_book.add_order(OrderSide::Bid, 100.0, 10.0, 1);
+        order_book.add_order(OrderSide::Bid, 99.5, 5.0, 2);
+        order_book.add_order(OrderSide::Ask, 101.0, 15.0, 3);
+
+        let json = order_book.stats_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["total_orders"], 3);
+        assert_eq!(value["bid_levels"], 2);
+        assert_eq!(value["ask_levels"], 1);
+
+        let decoded: OrderBookStats = serde_json::from_str(&json).unwrap();
+        let stats = order_book.get_stats();
+        assert_eq!(decoded.total_orders_created, stats.total_orders_created);
+        assert_eq!(decoded.best_bid, Some(100.0));
+        assert_eq!(decoded.best_ask, stats.best_ask);
+        assert_eq!(decoded.spread, stats.spread);
+        assert_eq!(decoded.mid_price, stats.mid_price);
+        assert_eq!(decoded.imbalance, stats.imbalance);
+        assert_eq!(decoded.last_match_time, None);
+
+        // An empty book serializes its missing prices as null
+        let empty: serde_json::Value = serde_json::from_str(&OrderBook::new().stats_json()).unwrap();
+        assert!(empty["best_bid"].is_null());
+        assert_eq!(empty["total_orders"], 0);
+    }
+
+    #[test]
+    fn test_get_level_orders_fifo() {
+        let order_book = OrderBook::new();
+
+        let first = order_book.add_order(OrderSide::Bid, 100.0, 1.0, 3);
+        let second = order_book.add_order(OrderSide::Bid, 100.0, 2.0, 1);
+        let third = order_book.add_order(OrderSide::Bid, 100.0, 3.0, 2);
+        order_book.add_order(OrderSide::Bid, 99.0, 4.0, 4);
+
+        let ids: Vec<u64> = order_book.get_level_orders(OrderSide::Bid, 100.0).iter().map(|o| o.id).collect();
+        assert_eq!(ids, vec![first, second, third]);
+
+        order_book.remove_order(second);
+        let ids: Vec<u64> = order_book.get_level_orders(OrderSide::Bid, 100.0).iter().map(|o| o.id).collect();
+        assert_eq!(ids, vec![first, third]);
+
+        assert!(order_book.get_level_orders(OrderSide::Ask, 100.0).is_empty());
+        assert!(order_book.get_level_orders(OrderSide::Bid, 98.0).is_empty());
+    }
+
+    #[test]
+    fn test_l3_depth_lists_orders_per_level() {
+        let order_book = OrderBook::new();
+
+        let bid_a = order_book.add_order(OrderSide::Bid, 100.0, 1.0, 10);
+        let bid_b = order_book.add_order(OrderSide::Bid, 100.0, 2.5, 11);
+        let bid_c = order_book.add_order(OrderSide::Bid, 99.0, 4.0, 12);
+        let ask_a = order_book.add_order(OrderSide::Ask, 102.0, 3.0, 13);
+        let ask_b = order_book.add_order(OrderSide::Ask, 101.0, 0.5, 14);
+        let ask_c = order_book.add_order(OrderSide::Ask, 101.0, 1.5, 15);
+
+        let (bids, asks) = order_book.get_l3_depth(5);
+        assert_eq!(bids, vec![
+            (100.0, vec![(bid_a, 1.0, 10), (bid_b, 2.5, 11)]),
+            (99.0, vec![(bid_c, 4.0, 12)]),
+        ]);
+        assert_eq!(asks, vec![
+            (101.0, vec![(ask_b, 0.5, 14), (ask_c, 1.5, 15)]),
+            (102.0, vec![(ask_a, 3.0, 13)]),
+        ]);
+
+        // Quantities per level agree with the L2 view
+        let (l2_bids, _) = order_book.get_market_depth(1);
+        let (l3_bids, l3_asks) = order_book.get_l3_depth(1);
+        assert_eq!(l3_bids.len(), 1);
+        assert_eq!(l3_asks.len(), 1);
+        assert_eq!(l3_bids[0].1.iter().map(|(_, qty, _)| qty).sum::<f64>(), l2_bids[0].1);
+    }
+
+    #[test]
+    fn test_match_iteration_cap_is_reported_and_resumable() {
+        let crossed_book = || {
+            let order_book = OrderBook::new();
+            for timestamp in 0..1500 {
+                order_book.add_order(OrderSide::Bid, 100.0, 1.0, timestamp);
+            }
+            order_book.add_order(OrderSide::Ask, 100.0, 1500.0, 1500);
+            order_book
+        };
+
+        let order_book = crossed_book();
+        order_book.set_max_match_iterations(1000);
+        let first = order_book.match_orders();
+        assert_eq!(first.trades.len(), 1000);
+        assert!(first.hit_iteration_cap);
+        assert_eq!(order_book.get_best_bid(), Some(100.0));
+        assert_eq!(order_book.get_best_ask(), Some(100.0));
+
+        let rest = order_book.match_orders();
+        assert_eq!(rest.trades.len(), 500);
+        assert!(!rest.hit_iteration_cap);
+        assert_eq!(order_book.get_total_orders(), 0);
+        assert_eq!(order_book.get_stats().total_orders_matched, 1500);
+        // Reaching the cap exactly on the last crossing trade is not a truncation
+        let done = order_book.match_orders();
+        assert!(done.trades.is_empty() && !done.hit_iteration_cap);
+
+        // The default cap is well above that
+        let order_book = crossed_book();
+        assert_eq!(order_book.max_match_iterations(), DEFAULT_MAX_MATCH_ITERATIONS);
+        let all = order_book.match_orders();
+        assert_eq!((all.trades.len(), all.hit_iteration_cap), (1500, false));
+
+        // A limit order stops once it is filled, so the cap never truncates
+        // it; the crossed bids it leaves are still matched under the cap
+        let order_book = crossed_book();
+        order_book.set_max_match_iterations(0);
+        assert_eq!(order_book.max_match_iterations(), 1);
+        assert!(order_book.match_orders().hit_iteration_cap);
+        let (order_id, trades) = order_book.add_limit_order(OrderSide::Ask, 99.0, 10.0, 1501);
+        assert_eq!(trades.len(), 10);
+        assert!(trades.iter().all(|trade| trade.ask_order_id == order_id));
+        assert!(order_book.get_order(order_id).is_none());
+        assert_eq!(order_book.get_total_orders(), 1490);
+        let next = order_book.match_orders();
+        assert_eq!((next.trades.len(), next.hit_iteration_cap), (1, true));
+
+        // Or rests once the opposite side is past its price
+        let order_book = crossed_book();
+        order_book.set_max_match_iterations(1);
+        let (order_id, trades) = order_book.add_limit_order(OrderSide::Bid, 99.0, 5.0, 1501);
+        assert!(trades.is_empty());
+        assert_eq!(order_book.get_order(order_id).map(|order| order.quantity), Some(5.0));
+    }
+
+    #[test]
+    fn test_snapshot_diff_categorizes_level_changes() {
+        let before = OrderBookSnapshot {
+            bids: vec![(100.0, 1.0), (99.5, 2.0), (99.0, 3.0)],
+            asks: vec![(100.5, 1.5), (101.0, 2.5)],
+        };
+        let after = OrderBookSnapshot {
+            bids: vec![(100.2, 0.5), (100.0, 1.0), (99.5, 4.0), (98.5, 1.0)],
+            asks: vec![(101.0, 2.0), (101.5, 3.0)],
+        };
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.bids,
+            SideDiff {
+                added: vec![(100.2, 0.5), (98.5, 1.0)],
+                removed: vec![(99.0, 3.0)],
+                changed: vec![LevelChange { price: 99.5, old_quantity: 2.0, new_quantity: 4.0 }],
+            }
+        );
+        assert_eq!(
+            diff.asks,
+            SideDiff {
+                added: vec![(101.5, 3.0)],
+                removed: vec![(100.5, 1.5)],
+                changed: vec![LevelChange { price: 101.0, old_quantity: 2.5, new_quantity: 2.0 }],
+            }
+        );
+        assert_eq!(
+            diff.to_string(),
+            "+ bid 100.2 x 0.5\n+ bid 98.5 x 1\n- bid 99 x 3\n~ bid 99.5 x 2 -> 4\n\
+             + ask 101.5 x 3\n- ask 100.5 x 1.5\n~ ask 101 x 2.5 -> 2\n"
+        );
+
+        // Diffing the other way swaps added and removed
+        let back = after.diff(&before);
+        assert_eq!(back.bids.added, diff.bids.removed);
+        assert_eq!(back.asks.removed, diff.asks.added);
+        assert!(before.diff(&before).is_empty());
+        assert_eq!(before.diff(&before).to_string(), "no changes");
+
+        // A depth update applied to a book shows up as exactly that delta
+        let order_book = OrderBook::new();
+        for &(price, quantity) in &before.bids {
+            order_book.set_level_quantity(OrderSide::Bid, price, quantity, 1);
+        }
+        for &(price, quantity) in &before.asks {
+            order_book.set_level_quantity(OrderSide::Ask, price, quantity, 1);
+        }
+        assert_eq!(order_book.snapshot(10), before);
+        order_book.set_level_quantity(OrderSide::Ask, 100.5, 0.0, 2);
+        let delta = before.diff(&order_book.snapshot(10));
+        assert_eq!(delta.asks.removed, vec![(100.5, 1.5)]);
+        assert!(delta.bids.is_empty() && delta.asks.added.is_empty() && delta.asks.changed.is_empty());
+    }
+
+    #[test]
+    fn test_price_formats_at_any_precision() {
+        let price = Price(3245.678912);
+        assert_eq!(price.format_with(2), "3245.68");
+        assert_eq!(price.format_with(4), "3245.6789");
+        assert_eq!(price.format_with(6), "3245.678912");
+        assert_eq!(Price(0.5234).format_with(0), "1");
+
+        assert_eq!(price.to_string(), "3245.68");
+        assert_eq!(format!("{:.4}", price), "3245.6789");
+        assert_eq!(format!("{:.6}", Price(0.523)), "0.523000");
+    }
+
+    #[test]
+    fn test_decimal_prices_round_trip() {
+        for input in ["0.015", "0.0001"] {
+            let price: Price = input.parse().unwrap();
+            assert_eq!(price.to_decimal().unwrap().to_string(), input);
+            assert_eq!(price.format_scaled(input.len() as u32 - 2), input);
+        }
+        assert!("0.01x".parse::<Price>().is_err());
+
+        let order_book = OrderBook::new();
+        order_book.add_order_str(OrderSide::Bid, "0.015", 10.0, 1).unwrap();
+        order_book.add_order_str(OrderSide::Ask, "0.0151", 10.0, 2).unwrap();
+        assert_eq!(order_book.get_best_bid(), Some(0.015));
+        assert_eq!(order_book.get_best_ask(), Some(0.0151));
+        assert_eq!(order_book.format_price(order_book.get_best_bid().unwrap()), "0.0150");
+
+        // Snapped in decimal arithmetic: 0.07 / 0.01 * 0.01 stays 0.07
+        let ticked = OrderBook::new();
+        ticked.set_tick_size(Some(0.01));
+        ticked.add_order_str(OrderSide::Bid, "0.0704", 1.0, 1).unwrap();
+        ticked.add_order_decimal(OrderSide::Ask, Decimal::new(75, 3), 1.0, 2);
+        assert_eq!(ticked.get_best_bid(), Some(0.07));
+        assert_eq!(ticked.get_best_ask(), Some(0.08));
+        assert_eq!(ticked.format_price(0.07), "0.07");
+        assert_eq!(ticked.round_to_tick_decimal(Decimal::new(5, 3)).to_string(), "0.01");
+        assert!(ticked.add_order_str(OrderSide::Bid, "", 1.0, 3).is_err());
+    }
+
+    #[test]
+    fn test_depth_by_notional_on_sample_book() {
+        let app = App::new();
+        let book = &app.order_book;
+        let cap = 250_000.0;
+
+        for side in [OrderSide::Bid, OrderSide::Ask] {
+            let depth = book.depth_by_notional(side, cap);
+            let notional: f64 = depth.iter().map(|(price, qty)| price * qty).sum();
+            assert!(notional >= cap - 1e-6 && notional < cap + 1e-6);
+
+            // Best first, full levels, then a partial one
+            let (full, last) = depth.split_at(depth.len() - 1);
+            let (book_bids, book_asks) = book.get_market_depth(depth.len());
+            let book_levels = if side == OrderSide::Bid { book_bids } else { book_asks };
+            assert_eq!(full, &book_levels[..full.len()]);
+            assert_eq!(last[0].0, book_levels[full.len()].0);
+            assert!(last[0].1 < book_levels[full.len()].1);
+        }
+
+        assert!(book.depth_by_notional(OrderSide::Bid, 0.0).is_empty());
+        let everything = book.depth_by_notional(OrderSide::Ask, f64::INFINITY);
+        assert_eq!(everything, book.get_market_depth(usize::MAX).1);
+    }
+
+    #[test]
+    fn test_liquidity_up_to_and_quantity_at_price() {
+        let order_book = OrderBook::new();
+        order_book.add_order(OrderSide::Bid, 99.0, 2.0, 1);
+        order_book.add_order(OrderSide::Bid, 98.0, 3.0, 2);
+        order_book.add_order(OrderSide::Bid, 98.0, 1.0, 3);
+        order_book.add_order(OrderSide::Ask, 101.0, 1.5, 4);
+        order_book.add_order(OrderSide::Ask, 102.0, 2.5, 5);
+        order_book.add_order(OrderSide::Ask, 105.0, 4.0, 6);
+
+        assert_eq!(order_book.quantity_at_price(OrderSide::Bid, 98.0), 4.0);
+        assert_eq!(order_book.quantity_at_price(OrderSide::Ask, 102.0), 2.5);
+        assert_eq!(order_book.quantity_at_price(OrderSide::Ask, 103.0), 0.0);
+        assert_eq!(order_book.quantity_at_price(OrderSide::Bid, 101.0), 0.0);
+
+        // Buying takes asks up to the limit, inclusive
+        assert_eq!(order_book.liquidity_up_to(OrderSide::Bid, 102.0), 4.0);
+        assert_eq!(order_book.liquidity_up_to(OrderSide::Bid, 104.99), 4.0);
+        assert_eq!(order_book.liquidity_up_to(OrderSide::Bid, 1000.0), 8.0);
+        // Selling takes bids down to the limit
+        assert_eq!(order_book.liquidity_up_to(OrderSide::Ask, 98.0), 6.0);
+        assert_eq!(order_book.liquidity_up_to(OrderSide::Ask, 98.5), 2.0);
+
+        // Limits the other side never reaches
+        assert_eq!(order_book.liquidity_up_to(OrderSide::Bid, 100.0), 0.0);
+        assert_eq!(order_book.liquidity_up_to(OrderSide::Ask, 99.5), 0.0);
+
+        // Read-only
+        assert_eq!(order_book.get_total_orders(), 6);
+        assert_eq!(order_book.get_best_ask(), Some(101.0));
+    }
+
+    #[test]
+    fn test_trade_history_is_bounded_and_newest_first() {
+        let order_book = OrderBook::new();
+        for i in 0..TRADE_HISTORY_CAPACITY + 5 {
+            order_book.add_order(OrderSide::Ask, 100.0, 1.0, i as u64);
+            order_book.add_market_order(OrderSide::Bid, 1.0, i as u64);
+        }
+
+        let trades = order_book.recent_trades(usize::MAX);
+        assert_eq!(trades.len(), TRADE_HISTORY_CAPACITY);
+        assert_eq!(trades[0].timestamp, (TRADE_HISTORY_CAPACITY + 4) as u64);
+        assert_eq!(trades.last().unwrap().timestamp, 5);
+        assert_eq!(order_book.recent_trades(3).len(), 3);
+
+        // Clearing the levels keeps the prints
+        order_book.clear();
+        assert_eq!(order_book.recent_trades(1)[0].timestamp, (TRADE_HISTORY_CAPACITY + 4) as u64);
+    }
+
+    #[test]
+    fn test_trades_carry_their_execution_time() {
+        // Makers from long ago, in whatever unit their source used
+        let order_book = OrderBook::new();
+        order_book.add_order(OrderSide::Ask, 100.0, 1.0, 7);
+        order_book.add_order(OrderSide::Ask, 101.0, 1.0, 8);
+
+        // An incoming order's fills carry its own arrival time
+        let arrived = 1_700_000_000_000;
+        let (_, trades) = order_book.add_limit_order(OrderSide::Bid, 100.0, 1.0, arrived);
+        assert_eq!(trades[0].timestamp, arrived);
+        assert_eq!(order_book.add_market_order(OrderSide::Bid, 0.5, arrived + 5)[0].timestamp, arrived + 5);
+
+        // A sweep of orders crossed on the book stamps the time it ran
+        let before = chrono::Utc::now().timestamp_millis() as u64;
+        order_book.add_order(OrderSide::Bid, 101.0, 0.5, 9);
+        let swept = order_book.match_orders().trades;
+        assert_eq!(swept.len(), 1);
+        assert!(swept[0].timestamp >= before, "{} < {}", swept[0].timestamp, before);
+        assert_eq!(order_book.get_stats().last_match_time, Some(swept[0].timestamp));
+    }
+
+    #[test]
+    fn test_stats_track_last_and_session_traded_prices() {
+        let order_book = OrderBook::new();
+        let stats = order_book.get_stats();
+        assert_eq!((stats.last_trade_price, stats.session_high, stats.session_low), (None, None, None));
+
+        // A market buy sweeping two levels, then crossing limits at lower prices
+        order_book.add_order(OrderSide::Ask, 101.0, 1.0, 1);
+        order_book.add_order(OrderSide::Ask, 103.0, 1.0, 2);
+        order_book.add_market_order(OrderSide::Bid, 2.0, 3);
+        let stats = order_book.get_stats();
+        assert_eq!(stats.last_trade_price, Some(103.0));
+        assert_eq!((stats.session_high, stats.session_low), (Some(103.0), Some(101.0)));
+
+        order_book.add_order(OrderSide::Bid, 98.5, 1.0, 4);
+        order_book.add_order(OrderSide::Ask, 98.0, 1.0, 5);
+        assert_eq!(order_book.match_orders().trades.len(), 1);
+        order_book.add_limit_order(OrderSide::Ask, 102.0, 1.0, 6);
+        order_book.add_limit_order(OrderSide::Bid, 102.5, 1.0, 7);
+        let stats = order_book.get_stats();
+        assert_eq!(stats.last_trade_price, Some(102.0));
+        assert_eq!((stats.session_high, stats.session_low), (Some(103.0), Some(98.5)));
+        assert!(order_book.to_string().contains("Last Trade: 102.0000 (High: 103.0000, Low: 98.5000)"));
+
+        // Clearing starts a new session
+        order_book.clear();
+        assert_eq!(order_book.get_stats().session_high, None);
+    }
+
+    #[test]
+    fn test_depth_with_and_without_hidden_reserve() {
+        let order_book = OrderBook::new();
+        order_book.set_level_quantities(OrderSide::Bid, 99.0, 2.0, 8.0, 1);
+        order_book.set_level_quantity(OrderSide::Bid, 98.0, 1.0, 2);
+        order_book.set_level_quantities(OrderSide::Ask, 101.0, 1.5, 0.5, 3);
+        order_book.set_level_quantities(OrderSide::Ask, 102.0, 0.0, 4.0, 4);
+
+        assert_eq!(order_book.get_market_depth(5), (vec![(99.0, 2.0), (98.0, 1.0)], vec![(101.0, 1.5)]));
+        assert_eq!(
+            order_book.get_market_depth_with_hidden(5, true),
+            (vec![(99.0, 10.0), (98.0, 1.0)], vec![(101.0, 2.0)])
+        );
+        assert_eq!(order_book.get_market_depth_with_hidden(5, false), order_book.get_market_depth(5));
+
+        // Reserve behind resting orders, which a fork keeps
+        order_book.add_order(OrderSide::Bid, 97.0, 3.0, 5);
+        assert!(order_book.set_hidden_quantity(OrderSide::Bid, 97.0, 6.0));
+        assert!(!order_book.set_hidden_quantity(OrderSide::Ask, 97.0, 6.0));
+        let fork = order_book.fork();
+        assert_eq!(fork.get_market_depth_with_hidden(3, true).0[2], (97.0, 9.0));
+
+        // Hidden quantity is never matched against
+        order_book.add_market_order(OrderSide::Ask, 2.0, 6);
+        assert_eq!(order_book.get_market_depth_with_hidden(1, true).0, vec![(98.0, 1.0)]);
+
+        // Replacing the level replaces its reserve
+        order_book.set_level_quantity(OrderSide::Ask, 101.0, 1.5, 7);
+        assert_eq!(order_book.get_market_depth_with_hidden(1, true).1, vec![(101.0, 1.5)]);
+    }
+
+    #[test]
+    fn test_format_book_lists_each_side_best_first() {
+        let order_book = OrderBook::new();
+        for (i, price) in [103.0, 101.0, 105.0, 102.0].into_iter().enumerate() {
+            order_book.add_order(OrderSide::Ask, price, 1.0 + i as f64, i as u64);
+        }
+        for (i, price) in [97.5, 99.0, 98.0].into_iter().enumerate() {
+            order_book.add_order(OrderSide::Bid, price, 10.0, 10 + i as u64);
+        }
+        order_book.add_order(OrderSide::Bid, 99.0, 2.5, 20);
+
+        let lines = |text: &str, label: &str| -> Vec<String> {
+            text.lines().filter(|line| line.starts_with(label)).map(str::to_string).collect()
+        };
+        let prices = |rows: &[String]| -> Vec<f64> {
+            rows.iter().map(|row| row[4..].split('|').next().unwrap().trim().parse().unwrap()).collect()
+        };
+
+        let text = order_book.format_book(3);
+        let (asks, bids) = (lines(&text, "ASK:"), lines(&text, "BID:"));
+        assert_eq!(prices(&asks), vec![101.0, 102.0, 103.0]);
+        assert_eq!(prices(&bids), vec![99.0, 98.0, 97.5]);
+        assert_eq!(bids[0], "BID:  99.0000 | 12.5000 | 2 orders");
+        assert_eq!(asks[0], "ASK: 101.0000 |  2.0000 | 1 orders");
+        // Every row lines its columns up
+        assert!(asks.iter().chain(&bids).all(|row| row.len() == bids[0].len()));
+        assert!(text.find("ASK:").unwrap() < text.find("BID:").unwrap());
+
+        // Display is the same at the default depth
+        let shown = order_book.to_string();
+        assert_eq!(shown, order_book.format_book(DISPLAY_DEPTH_LEVELS));
+        assert_eq!(prices(&lines(&shown, "ASK:")), vec![101.0, 102.0, 103.0, 105.0]);
+        assert_eq!(order_book.format_book(0).matches("orders").count(), 0);
+    }
+
+    #[test]
+    fn test_tick_size_rounding() {
+        let order_book = OrderBook::new();
+        order_book.add_order(OrderSide::Bid, 100.123, 1.0, 1);
+        assert_eq!(order_book.get_best_bid(), Some(100.123));
+
+        order_book.set_tick_size(Some(0.05));
+        order_book.add_order(OrderSide::Ask, 101.034, 1.0, 2);
+        order_book.add_order(OrderSide::Ask, 101.076, 1.0, 3);
+
+        let (_, asks) = order_book.get_market_depth(2);
+        assert_eq!(asks[0].0, 101.05);
+        assert_eq!(asks[1].0, 101.1);
     }
 }
@@ -0,0 +1,237 @@
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Most lines the Logs tab keeps; older ones are dropped.
+pub const LOG_BUFFER_LINES: usize = 500;
+
+/// The least severe level the Logs tab is sent. Everything at the configured
+/// level still goes to the log file.
+pub const LOG_PANEL_LEVEL: Level = Level::WARN;
+
+/// `order-book.log` in the config directory.
+pub fn default_log_path() -> Option<PathBuf> {
+    Some(crate::config::config_dir()?.join("order-book.log"))
+}
+
+/// A level name as the settings and `--log-level` take it, ignoring case.
+pub fn parse_level(text: &str) -> Result<Level, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("Unknown log level '{}'; use error, warn, info, debug or trace", text.trim()))
+}
+
+/// One captured event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub level: Level,
+    /// Module the event came from, e.g. `order_book::binance_ws`.
+    pub target: String,
+    /// The message followed by any other fields as `name=value`.
+    pub message: String,
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:>5} {}: {}", self.at.format("%H:%M:%S"), self.level, self.target, self.message)
+    }
+}
+
+/// Captured lines, oldest first, shared between the capture layer and the app.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<LogLine>>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self { lines: Arc::new(Mutex::new(VecDeque::new())), capacity: capacity.max(1) }
+    }
+
+    /// Adds `line`, dropping the oldest past the capacity.
+    pub fn push(&self, line: LogLine) {
+        let mut lines = self.lines.lock();
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// The lines at `min_level` or more severe, oldest first.
+    pub fn lines(&self, min_level: Level) -> Vec<LogLine> {
+        self.lines.lock().iter().filter(|line| line.level <= min_level).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.lock().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.lines.lock().clear();
+    }
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(LOG_BUFFER_LINES)
+    }
+}
+
+/// Collects an event's `message` and its other fields.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl MessageVisitor {
+    fn into_text(self) -> String {
+        let mut text = self.message;
+        for field in self.fields {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&field);
+        }
+        text
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={}", field.name(), value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+/// Copies events at `min_level` or more severe into a `LogBuffer`.
+pub struct CaptureLayer {
+    buffer: LogBuffer,
+    min_level: Level,
+}
+
+impl CaptureLayer {
+    pub fn new(buffer: LogBuffer, min_level: Level) -> Self {
+        Self { buffer, min_level }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if *metadata.level() > self.min_level {
+            return;
+        }
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        self.buffer.push(LogLine {
+            at: chrono::Utc::now(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.into_text(),
+        });
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::from_level(self.min_level))
+    }
+}
+
+/// Appends events at `level` or more severe to the file at `path`, and
+/// captures warnings and errors into the returned buffer for the Logs tab.
+/// The file is written from a background thread until the guard is dropped.
+pub fn init(path: &Path, level: Level) -> Result<(LogBuffer, WorkerGuard), String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Log file {} has no file name", path.display()))?;
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+    let (writer, guard) = tracing_appender::non_blocking(tracing_appender::rolling::never(dir, file_name));
+
+    let buffer = LogBuffer::default();
+    let file_layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_filter(LevelFilter::from_level(level));
+    tracing_subscriber::registry()
+        .with(file_layer)
+        .with(CaptureLayer::new(buffer.clone(), LOG_PANEL_LEVEL))
+        .try_init()
+        .map_err(|e| format!("Could not start logging: {}", e))?;
+    Ok((buffer, guard))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_layer_keeps_warnings_and_errors() {
+        let buffer = LogBuffer::new(3);
+        let subscriber = tracing_subscriber::registry().with(CaptureLayer::new(buffer.clone(), Level::WARN));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("connected");
+            tracing::debug!(levels = 20, "applied snapshot");
+            tracing::warn!(stream = "btcusdt@depth", "skipping malformed level");
+            let _span = tracing::info_span!("submit_order").entered();
+            tracing::error!(code = -2010, "order rejected");
+        });
+
+        let lines = buffer.lines(Level::WARN);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].level, Level::WARN);
+        assert_eq!(lines[0].message, "skipping malformed level stream=btcusdt@depth");
+        assert_eq!(lines[0].target, module_path!());
+        assert_eq!(lines[1].message, "order rejected code=-2010");
+
+        let errors = buffer.lines(Level::ERROR);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().ends_with("ERROR order_book::logging::tests: order rejected code=-2010"));
+
+        // Past the capacity the oldest lines go first
+        tracing::subscriber::with_default(
+            tracing_subscriber::registry().with(CaptureLayer::new(buffer.clone(), Level::WARN)),
+            || {
+                for n in 0..3 {
+                    tracing::warn!("warning {}", n);
+                }
+            },
+        );
+        let messages: Vec<String> = buffer.lines(Level::TRACE).into_iter().map(|line| line.message).collect();
+        assert_eq!(messages, ["warning 0", "warning 1", "warning 2"]);
+        buffer.clear();
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("warn"), Ok(Level::WARN));
+        assert_eq!(parse_level(" DEBUG "), Ok(Level::DEBUG));
+        assert!(parse_level("loud").unwrap_err().starts_with("Unknown log level 'loud'"));
+    }
+}
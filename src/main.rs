@@ -1,32 +1,194 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    cursor::Show,
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use order_book::App;
+use clap::{Parser, Subcommand};
+use order_book::binance_ws::{BinanceWebSocketClient, LatencyHistogram};
+use order_book::config::{default_config_path, AppConfig};
+use order_book::logging::{self, LogBuffer};
+use order_book::price::{Price, DEFAULT_PRICE_DECIMALS};
+use order_book::simulator::MarketSimulator;
+use order_book::ui::{BOOK_DEPTH_LEVELS, ORDER_HISTORY_ROWS};
+use order_book::{App, OrderBook};
 use ratatui::backend::CrosstermBackend;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 use std::{error::Error, io, time::Duration};
 
+/// What the main loop should do after an input event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoopControl {
+    Continue,
+    /// The terminal changed size; repaint everything.
+    Redraw,
+    Quit,
+}
+
+/// Puts the terminal back into cooked mode on the main screen when dropped,
+/// including while unwinding from a panic.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        // Dropped if the screen switch fails, taking raw mode back off
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+
+    /// Safe to call any number of times, set up or not: the panic hook and
+    /// the guard's drop both run when a panic unwinds past the guard.
+    fn restore() {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Show);
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        Self::restore();
+    }
+}
+
+/// Terminal order book over a simulated market or the live Binance feed.
+#[derive(Debug, Parser)]
+#[command(name = "order-book", version)]
+struct Cli {
+    /// Coin to select at startup, e.g. BTC or BTCUSDT
+    #[arg(long, global = true)]
+    symbol: Option<String>,
+    /// Settings file to use instead of the default one
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Seeds the market simulator; takes precedence over the settings' seed
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    /// Start on the live Binance feed
+    #[arg(long, global = true)]
+    real_data: bool,
+    /// Least severe level written to the log file (error, warn, info, debug
+    /// or trace); takes precedence over the settings' log_level
+    #[arg(long, global = true, value_parser = logging::parse_level)]
+    log_level: Option<tracing::Level>,
+    #[command(subcommand)]
+    mode: Option<Mode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Subcommand)]
+enum Mode {
+    /// The terminal UI (the default)
+    Tui,
+    /// Run the feed and book without the terminal UI, printing the top of
+    /// book every refresh
+    Headless,
+    /// Play back recorded Binance combined-stream messages, one per line
+    Replay {
+        file: PathBuf,
+        /// Playback speed relative to the recorded event times
+        #[arg(long, default_value_t = 1.0, value_parser = parse_speed)]
+        speed: f64,
+    },
+    /// Measure order book throughput and latency on random limit orders
+    Bench {
+        #[arg(long, default_value_t = 100000)]
+        orders: usize,
+    },
+}
+
+fn parse_speed(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(speed) if speed > 0.0 && speed.is_finite() => Ok(speed),
+        _ => Err(format!("speed must be a positive number, got {}", text)),
+    }
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = ratatui::Terminal::new(backend)?;
+    let cli = Cli::parse();
+    // Held until exit: dropping the guard flushes the log file
+    let (log_buffer, _log_guard) = match start_logging(&cli) {
+        Ok((buffer, guard)) => (buffer, Some(guard)),
+        Err(reason) => {
+            eprintln!("{}; continuing without a log file", reason);
+            (LogBuffer::default(), None)
+        }
+    };
+    match cli.mode.clone().unwrap_or(Mode::Tui) {
+        Mode::Tui => run_tui(&cli, log_buffer),
+        Mode::Headless => run_headless(&cli),
+        Mode::Replay { file, speed } => run_replay(&cli, &file, speed),
+        Mode::Bench { orders } => {
+            run_bench(orders);
+            Ok(())
+        }
+    }
+}
 
-    let mut app = App::new();
+/// Logs to the settings' log file at `--log-level`, or else the settings'
+/// level.
+fn start_logging(cli: &Cli) -> Result<(LogBuffer, tracing_appender::non_blocking::WorkerGuard), String> {
+    // A settings file that fails to load is reported by the app itself
+    let config = cli
+        .config
+        .clone()
+        .or_else(default_config_path)
+        .and_then(|path| AppConfig::load(&path).ok())
+        .unwrap_or_default();
+    let level = match cli.log_level {
+        Some(level) => level,
+        None => logging::parse_level(&config.log_level)?,
+    };
+    let path = config
+        .log_file
+        .or_else(logging::default_log_path)
+        .ok_or("No log file; set log_file in the settings")?;
+    logging::init(&path, level)
+}
+
+/// The app the options describe, with its feeds running on `runtime`.
+fn build_app(cli: &Cli, runtime: &tokio::runtime::Runtime) -> Result<App, String> {
+    let mut app = App::with_config(cli.config.clone().or_else(default_config_path), cli.seed);
+    app.persist_to_config_dir();
+    app.runtime = Some(runtime.handle().clone());
     app.add_sample_orders();
+    if let Some(symbol) = &cli.symbol {
+        if !app.select_coin_by_symbol(symbol) {
+            return Err(format!("Unknown symbol: {}", symbol));
+        }
+    }
+    if cli.real_data && !app.use_real_data {
+        app.toggle_real_data();
+    }
+    Ok(app)
+}
 
-    let res = run_app(&mut terminal, app);
+fn run_tui(cli: &Cli, log_buffer: LogBuffer) -> Result<(), Box<dyn Error>> {
+    // Restore first so the panic message lands on the normal screen
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        TerminalGuard::restore();
+        default_hook(info);
+    }));
+
+    // The UI loop stays on this thread and polls the feeds' channels between
+    // frames; the feeds themselves run as tasks on the runtime's workers
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut app = build_app(cli, &runtime)?;
+    app.log_buffer = log_buffer;
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    let guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = ratatui::Terminal::new(backend)?;
+
+    let res = run_app(&mut terminal, app);
+    drop(guard);
 
     if let Err(err) = res {
         println!("{}", err);
@@ -35,6 +197,134 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Prints a `book_line` every refresh interval until Ctrl-C.
+fn run_headless(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut app = build_app(cli, &runtime)?;
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = interrupted.clone();
+    runtime.spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            flag.store(true, Ordering::Relaxed);
+        }
+    });
+
+    while !interrupted.load(Ordering::Relaxed) {
+        app.drain_market_stream_events();
+        app.drain_requests();
+        app.simulation_tick();
+        let decimals = app.current_price_decimals();
+        println!("{}", book_line(&app.current_market, &app.order_book, decimals, chrono::Utc::now()));
+        std::thread::sleep(app.refresh_interval);
+    }
+    app.shutdown();
+    Ok(())
+}
+
+/// Applies each recorded message to a fresh book, waiting out the gaps
+/// between event times divided by `speed`, and prints a `book_line` for
+/// every second of recorded time.
+fn run_replay(cli: &Cli, file: &Path, speed: f64) -> Result<(), Box<dyn Error>> {
+    let text = std::fs::read_to_string(file).map_err(|e| format!("Could not read {}: {}", file.display(), e))?;
+    let symbol = cli.symbol.clone().unwrap_or_else(|| "BTCUSDT".to_string());
+    let mut client = BinanceWebSocketClient::new(symbol);
+
+    let (mut applied, mut skipped) = (0, 0);
+    let mut previous_time: Option<u64> = None;
+    let mut printed_second = None;
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let event_time = recorded_event_time(line);
+        if let (Some(previous), Some(time)) = (previous_time, event_time) {
+            std::thread::sleep(Duration::from_millis(time.saturating_sub(previous)).div_f64(speed));
+        }
+        previous_time = event_time.or(previous_time);
+
+        if client.handle_combined_message(line).is_err() {
+            skipped += 1;
+            continue;
+        }
+        applied += 1;
+
+        let Some(time) = previous_time.and_then(|time| chrono::DateTime::from_timestamp_millis(time as i64)) else {
+            continue;
+        };
+        if printed_second != Some(time.timestamp()) {
+            printed_second = Some(time.timestamp());
+            println!("{}", book_line(&client.symbol, &client.order_book, DEFAULT_PRICE_DECIMALS, time));
+        }
+    }
+    println!("Replayed {} messages, skipped {} malformed", applied, skipped);
+    Ok(())
+}
+
+/// The `E` event time of a combined-stream message, in milliseconds.
+fn recorded_event_time(line: &str) -> Option<u64> {
+    let message: serde_json::Value = serde_json::from_str(line).ok()?;
+    message["data"]["E"].as_u64()
+}
+
+/// Adds `orders` simulated limit orders within a dollar of 100, so many
+/// cross, and prints the throughput and per-order latency percentiles.
+fn run_bench(orders: usize) {
+    let book = OrderBook::new();
+    let mut simulator = MarketSimulator::new(Some(BENCH_SEED));
+    let mut latencies = LatencyHistogram::new(orders);
+    let mut trades = 0;
+
+    let started = Instant::now();
+    for timestamp in 0..orders as u64 {
+        let Some((side, price, quantity)) = simulator.next_order(100.0, 1.0, 1.0) else {
+            continue;
+        };
+        let order_started = Instant::now();
+        trades += book.add_limit_order(side, price, quantity, timestamp).1.len();
+        latencies.record(order_started.elapsed().as_nanos() as u64);
+    }
+    let elapsed = started.elapsed();
+
+    let micros = |percentile: f64| latencies.percentile(percentile).unwrap_or(0) as f64 / 1000.0;
+    println!(
+        "{} orders in {:.3}s: {:.0} ops/sec, {} trades, {} resting",
+        orders,
+        elapsed.as_secs_f64(),
+        orders as f64 / elapsed.as_secs_f64(),
+        trades,
+        book.get_total_orders()
+    );
+    println!(
+        "latency p50 {:.2}µs  p90 {:.2}µs  p99 {:.2}µs  p99.9 {:.2}µs  max {:.2}µs",
+        micros(50.0),
+        micros(90.0),
+        micros(99.0),
+        micros(99.9),
+        micros(100.0)
+    );
+}
+
+/// Seed the benchmark's orders come from, so runs are comparable.
+const BENCH_SEED: u64 = 42;
+
+/// One line of headless output: the time, best bid and ask with their
+/// quantities, spread, mid and last trade, e.g.
+/// `12:00:01 BTCUSDT bid 99.50 x 2 | ask 100.50 x 1.25 | spread 1.00 mid 100.00 | last 100.10`.
+fn book_line(symbol: &str, book: &OrderBook, decimals: usize, at: chrono::DateTime<chrono::Utc>) -> String {
+    let (bids, asks) = book.get_market_depth(1);
+    let price = |price: f64| Price(price).format_with(decimals);
+    let level = |levels: &[(f64, f64)]| match levels.first() {
+        Some(&(best, quantity)) => format!("{} x {}", price(best), quantity),
+        None => "-".to_string(),
+    };
+    let mut line = format!("{} {} bid {} | ask {}", at.format("%H:%M:%S"), symbol, level(&bids), level(&asks));
+    if let (Some(spread), Some(mid)) = (book.get_spread(), book.get_mid_price()) {
+        line.push_str(&format!(" | spread {} mid {}", price(spread), price(mid)));
+    }
+    if let Some(last) = book.get_stats().last_trade_price {
+        line.push_str(&format!(" | last {}", price(last)));
+    }
+    line
+}
+
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut ratatui::Terminal<B>,
     mut app: App,
@@ -44,269 +334,956 @@ fn run_app<B: ratatui::backend::Backend>(
     loop {
         terminal.draw(|f| order_book::ui::draw_ui(f, &mut app))?;
 
-        // Auto-update market data every 2 seconds
-        if last_update.elapsed() >= Duration::from_secs(2) {
-            app.update_market_data();
-            app.simulate_real_time_updates();
+        app.drain_user_data_events();
+        app.drain_market_stream_events();
+        app.drain_requests();
+
+        // Auto-update market data every `refresh_interval`
+        if last_update.elapsed() >= app.refresh_interval {
+            app.simulation_tick();
+            app.sample_watchlist_mids();
             last_update = std::time::Instant::now();
         }
 
         // Non-blocking event reading
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    // === QUIT ===
-                    KeyCode::Char('q') | KeyCode::Char('Q') => {
-                        return Ok(());
-                    }
-                    
-                    // === TAB NAVIGATION ===
-                    KeyCode::Tab => {
-                        if key.modifiers.contains(KeyModifiers::SHIFT) {
-                            app.previous_tab();
-                        } else {
-                            app.next_tab();
-                        }
-                    }
-                    KeyCode::Right => {
-                        app.next_tab();
-                    }
-                    KeyCode::Left => {
-                        app.previous_tab();
-                    }
-                    
-                    // === QUICK TAB ACCESS ===
-                    KeyCode::Char('1') => {
-                        if app.user_command.is_empty() {
-                            app.select_coin_by_index(0);
-                        } else {
-                            app.selected_tab = 0;
-                        }
-                    }
-                    KeyCode::Char('2') => {
-                        if app.user_command.is_empty() {
-                            app.select_coin_by_index(1);
-                        } else {
-                            app.selected_tab = 1;
-                        }
-                    }
-                    KeyCode::Char('3') => {
-                        if app.user_command.is_empty() {
-                            app.select_coin_by_index(2);
-                        } else {
-                            app.selected_tab = 2;
-                        }
-                    }
-                    KeyCode::Char('4') => {
-                        app.selected_tab = 3;
-                    }
-                    KeyCode::Char('5') => {
-                        app.selected_tab = 4;
-                    }
-                    KeyCode::Char('6') => {
-                        app.selected_tab = 5;
-                    }
-                    KeyCode::Char('7') => {
-                        app.selected_tab = 6;
-                    }
-                    
-                    // === HELP & UTILITIES ===
-                    KeyCode::Char('?') | KeyCode::F(1) => {
-                        if app.user_command.is_empty() {
-                            app.help_mode = !app.help_mode;
-                        }
-                    }
-                    KeyCode::Char('h') | KeyCode::Char('H') => {
-                        if app.user_command.is_empty() {
-                            app.help_mode = !app.help_mode;
-                        }
-                    }
-                    
-                    // === COMMAND MANAGEMENT ===
-                    KeyCode::Char('c') | KeyCode::Char('C') => {
-                        if app.user_command.is_empty() {
-                            app.clear_user_command();
-                        }
-                    }
-                    KeyCode::Esc => {
-                        app.clear_user_command();
-                    }
-                    KeyCode::Delete => {
-                        app.clear_user_command();
-                    }
-                    KeyCode::Backspace => {
-                        app.remove_user_command();
-                    }
-                    KeyCode::Enter => {
-                        if !app.user_command.is_empty() {
-                            app.execute_user_command();
-                        } else if app.order_input.active {
-                            app.submit_polymarket_order();
-                        }
-                    }
-                    
-                    // === MARKET DATA & ORDERS ===
-                    KeyCode::Char('a') | KeyCode::Char('A') => {
-                        if app.user_command.is_empty() {
-                            app.add_sample_orders();
-                            app.real_time_data.push_back("Sample orders added".to_string());
-                        }
-                    }
-                    KeyCode::Char('m') | KeyCode::Char('M') => {
-                        if app.user_command.is_empty() {
-                            app.update_market_data();
-                            app.real_time_data.push_back("Market data updated".to_string());
-                        }
-                    }
-                    KeyCode::Char('r') | KeyCode::Char('R') => {
-                        if app.user_command.is_empty() {
-                            app.refresh_order_book();
-                        }
-                    }
-                    
-                    // === ORDER INPUT MODE ===
-                    KeyCode::Char('p') | KeyCode::Char('P') => {
-                        if app.user_command.is_empty() {
-                            app.toggle_order_input();
-                        }
-                    }
-                    KeyCode::Char('i') | KeyCode::Char('I') => {
-                        if app.user_command.is_empty() {
-                            app.toggle_order_input();
-                        }
-                    }
-                    
-                    // === ORDER SIDE SELECTION ===
-                    KeyCode::Char('b') | KeyCode::Char('B') => {
-                        if app.user_command.is_empty() {
-                            app.simulate_binance_connection();
-                        } else if app.order_input.active {
-                            app.order_input.side = order_book::polymarket_orders::PolymarketOrderSide::BUY;
-                        }
-                    }
-                    KeyCode::Char('s') | KeyCode::Char('S') => {
-                        if app.order_input.active {
-                            app.order_input.side = order_book::polymarket_orders::PolymarketOrderSide::SELL;
-                        }
-                    }
-                    
-                    // === ORDER TYPE SELECTION ===
-                    KeyCode::Char('g') | KeyCode::Char('G') => {
-                        if app.order_input.active {
-                            app.order_input.order_type = order_book::polymarket_orders::PolymarketOrderType::GTC;
-                        }
-                    }
-                    KeyCode::Char('f') | KeyCode::Char('F') => {
-                        if app.order_input.active {
-                            app.order_input.order_type = order_book::polymarket_orders::PolymarketOrderType::FOK;
-                        }
-                    }
-                    KeyCode::Char('d') | KeyCode::Char('D') => {
-                        if app.order_input.active {
-                            app.order_input.order_type = order_book::polymarket_orders::PolymarketOrderType::GTD;
-                        }
-                    }
-                    
-                    // === TRADING MODE ===
-                    KeyCode::Char('t') | KeyCode::Char('T') => {
-                        if app.user_command.is_empty() {
-                            app.toggle_trading_mode();
-                        }
-                    }
-                    
-                    // === COIN SWITCHING ===
-                    KeyCode::Char('n') | KeyCode::Char('N') => {
-                        if app.user_command.is_empty() {
-                            app.next_coin();
-                        }
-                    }
-                    KeyCode::Char('v') | KeyCode::Char('V') => {
-                        if app.user_command.is_empty() {
-                            app.previous_coin();
-                        }
-                    }
-                    
-                    // === TIMEFRAME NAVIGATION ===
-                    KeyCode::Char('<') | KeyCode::Char(',') => {
-                        if app.user_command.is_empty() {
-                            app.previous_timeframe();
-                        }
-                    }
-                    KeyCode::Char('>') | KeyCode::Char('.') => {
-                        if app.user_command.is_empty() {
-                            app.next_timeframe();
-                        }
-                    }
-                    
-                    // === AUTO-REFRESH ===
-                    KeyCode::Char('l') | KeyCode::Char('L') => {
-                        if app.user_command.is_empty() {
-                            app.auto_refresh = !app.auto_refresh;
-                            app.real_time_data.push_back(format!(
-                                "Auto-refresh {}", if app.auto_refresh { "enabled" } else { "disabled" }
-                            ));
-                        }
-                    }
-                    
-                    // === REAL DATA TOGGLE ===
-                    KeyCode::Char('w') | KeyCode::Char('W') => {
-                        if app.user_command.is_empty() {
-                            app.toggle_real_data();
-                        }
-                    }
-                    
-                    // === ORDER FIELD NAVIGATION ===
-                    KeyCode::Up => {
-                        if app.order_input.active {
-                            app.cycle_order_field_up();
-                        }
-                    }
-                    KeyCode::Down => {
-                        if app.order_input.active {
-                            app.cycle_order_field_down();
-                        }
-                    }
-                    
-                    // === FUNCTION KEYS ===
-                    KeyCode::F(2) => {
-                        app.selected_tab = 0; // Order Book
-                    }
-                    KeyCode::F(3) => {
-                        app.selected_tab = 1; // Trading
-                    }
-                    KeyCode::F(4) => {
-                        app.selected_tab = 2; // Market Data
-                    }
-                    KeyCode::F(5) => {
-                        app.selected_tab = 3; // Orders
-                    }
-                    KeyCode::F(6) => {
-                        app.selected_tab = 4; // Charts
-                    }
-                    KeyCode::F(7) => {
-                        app.selected_tab = 5; // Alerts
-                    }
-                    KeyCode::F(8) => {
-                        app.selected_tab = 6; // Settings
-                    }
-                    
-                    // === SPACE BAR ===
-                    KeyCode::Char(' ') => {
-                        if app.user_command.is_empty() {
-                            app.toggle_order_input();
-                        }
-                    }
-                    
-                    // === CHARACTER INPUT ===
-                    KeyCode::Char(c) => {
-                        if c.is_ascii() && !c.is_control() {
-                            app.add_user_command(c);
-                        }
-                    }
-                    
-                    _ => {}
+            match handle_event(&mut app, event::read()?) {
+                LoopControl::Continue => {}
+                LoopControl::Redraw => {
+                    terminal.autoresize()?;
+                    terminal.clear()?;
                 }
+                LoopControl::Quit => {
+                    app.shutdown();
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+fn handle_event(app: &mut App, event: Event) -> LoopControl {
+    match event {
+        Event::Key(key) => handle_key(app, key),
+        Event::Mouse(mouse) => handle_mouse(app, mouse),
+        Event::Resize(_, _) => LoopControl::Redraw,
+        _ => LoopControl::Continue,
+    }
+}
+
+/// Clicks and the wheel act on whatever the last frame drew at the pointer.
+fn handle_mouse(app: &mut App, mouse: MouseEvent) -> LoopControl {
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => app.click_at(mouse.column, mouse.row),
+        MouseEventKind::ScrollUp => app.scroll_at(mouse.column, mouse.row, -1),
+        MouseEventKind::ScrollDown => app.scroll_at(mouse.column, mouse.row, 1),
+        _ => {}
+    }
+    LoopControl::Continue
+}
+
+fn handle_key(app: &mut App, key: KeyEvent) -> LoopControl {
+    // Raw mode swallows SIGINT, so Ctrl-C arrives as a key press
+    if key.modifiers.contains(KeyModifiers::CONTROL) && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('C')) {
+        return LoopControl::Quit;
+    }
+
+    match key.code {
+        // === QUIT ===
+        KeyCode::Char('q') | KeyCode::Char('Q') => return LoopControl::Quit,
+
+        // === HELP OVERLAY ===
+        // While help is open the arrows and page keys scroll it
+        KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown if app.help_mode => {
+            let page = app.help_page() as isize;
+            app.scroll_help(match key.code {
+                KeyCode::Up => -1,
+                KeyCode::Down => 1,
+                KeyCode::PageUp => -page,
+                _ => page,
+            });
+        }
+        
+        // === COMMAND LINE EDITING ===
+        // While a command is being typed, Tab completes it and the arrows move
+        // its cursor or step through the history
+        KeyCode::Tab if !app.user_command.is_empty() && !app.order_input.active => app.complete_user_command(),
+        KeyCode::Left if !app.user_command.is_empty() && !app.order_input.active => app.user_command.move_left(),
+        KeyCode::Right if !app.user_command.is_empty() && !app.order_input.active => app.user_command.move_right(),
+        KeyCode::Home if !app.order_input.active => app.user_command.move_home(),
+        KeyCode::End if !app.order_input.active => app.user_command.move_end(),
+        KeyCode::Up if !app.order_input.active && (!app.user_command.is_empty() || app.user_command.is_recalling()) => {
+            app.user_command.history_previous();
+        }
+        KeyCode::Down if !app.order_input.active && app.user_command.is_recalling() => {
+            app.user_command.history_next();
+        }
+
+        // === TAB NAVIGATION ===
+        KeyCode::Tab => {
+            if key.modifiers.contains(KeyModifiers::SHIFT) {
+                app.previous_tab();
+            } else {
+                app.next_tab();
+            }
+        }
+        KeyCode::Right => {
+            app.next_tab();
+        }
+        KeyCode::Left => {
+            app.previous_tab();
+        }
+        
+        // === SETTINGS TAB ===
+        // '+' and '-' step the selected setting forwards and back
+        KeyCode::Char(c @ ('+' | '-'))
+            if app.selected_tab == 6 && app.user_command.is_empty() && !app.order_input.active =>
+        {
+            app.change_selected_setting(c == '+');
+        }
+
+        // === LOGS TAB ===
+        // '+' shows errors only, '-' warnings as well
+        KeyCode::Char(c @ ('+' | '-'))
+            if app.selected_tab == 8 && app.user_command.is_empty() && !app.order_input.active =>
+        {
+            app.set_log_filter(c == '+');
+        }
+
+        // === ORDER FORM INPUT ===
+        // Digits and '.' would otherwise switch coins, tabs and timeframes
+        KeyCode::Char(c) if app.order_input.active && (c.is_ascii_digit() || c == '.') => {
+            app.handle_order_input(c);
+        }
+        
+        // === QUICK TAB ACCESS ===
+        KeyCode::Char('1') => {
+            if app.user_command.is_empty() {
+                app.select_coin_by_index(0);
+            } else {
+                app.selected_tab = 0;
+            }
+        }
+        KeyCode::Char('2') => {
+            if app.user_command.is_empty() {
+                app.select_coin_by_index(1);
+            } else {
+                app.selected_tab = 1;
+            }
+        }
+        KeyCode::Char('3') => {
+            if app.user_command.is_empty() {
+                app.select_coin_by_index(2);
+            } else {
+                app.selected_tab = 2;
+            }
+        }
+        // Digits past 3 pick an added coin when there is one, a tab otherwise
+        KeyCode::Char(c @ '4'..='9')
+            if app.user_command.is_empty() && (c as usize - '1' as usize) < app.available_coins.len() =>
+        {
+            app.select_coin_by_index(c as usize - '1' as usize);
+        }
+        KeyCode::Char('4') => {
+            app.selected_tab = 3;
+        }
+        KeyCode::Char('5') => {
+            app.selected_tab = 4;
+        }
+        KeyCode::Char('6') => {
+            app.selected_tab = 5;
+        }
+        KeyCode::Char('7') => {
+            app.selected_tab = 6;
+        }
+        KeyCode::Char('8') => {
+            app.selected_tab = 7;
+        }
+        KeyCode::Char('9') => {
+            app.selected_tab = 8;
+        }
+        
+        // === HELP & UTILITIES ===
+        KeyCode::Char('?') | KeyCode::F(1) => {
+            if app.user_command.is_empty() {
+                app.toggle_help();
+            }
+        }
+        KeyCode::Char('h') | KeyCode::Char('H') => {
+            if app.user_command.is_empty() {
+                app.toggle_help();
+            }
+        }
+        
+        // === COMMAND MANAGEMENT ===
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            if app.user_command.is_empty() {
+                app.clear_user_command();
+            }
+        }
+        KeyCode::Esc => {
+            app.clear_user_command();
+        }
+        KeyCode::Delete if !app.order_input.active => app.user_command.delete(),
+        KeyCode::Delete => {
+            app.clear_user_command();
+        }
+        KeyCode::Backspace => {
+            app.remove_user_command();
+        }
+        KeyCode::Enter => {
+            if !app.user_command.is_empty() {
+                app.execute_user_command();
+            } else if app.order_input.active {
+                app.submit_order();
+            } else if app.selected_tab == 6 {
+                app.change_selected_setting(true);
+            } else if app.selected_tab == 7 {
+                app.open_watchlist_selection();
+            }
+        }
+        
+        // === MARKET DATA & ORDERS ===
+        KeyCode::Char('a') | KeyCode::Char('A') => {
+            if app.user_command.is_empty() {
+                app.add_sample_orders();
+                app.log("Sample orders added".to_string());
+            }
+        }
+        KeyCode::Char('m') | KeyCode::Char('M') if app.order_input.active => app.toggle_market_order(),
+        KeyCode::Char('m') | KeyCode::Char('M') => {
+            if app.user_command.is_empty() {
+                app.update_market_data();
+                app.log("Market data updated".to_string());
+            }
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') => {
+            if app.user_command.is_empty() {
+                app.refresh_order_book();
+            }
+        }
+        
+        // === ORDER INPUT MODE ===
+        KeyCode::Char('p') | KeyCode::Char('P') => {
+            if app.user_command.is_empty() {
+                app.toggle_order_input();
+            }
+        }
+        KeyCode::Char('i') | KeyCode::Char('I') => {
+            if app.user_command.is_empty() {
+                app.toggle_order_input();
+            }
+        }
+        
+        // === ORDER SIDE SELECTION ===
+        KeyCode::Char('b') | KeyCode::Char('B') => {
+            if app.user_command.is_empty() {
+                app.simulate_binance_connection();
+            } else if app.order_input.active {
+                app.order_input.side = order_book::polymarket_orders::PolymarketOrderSide::BUY;
+            }
+        }
+        KeyCode::Char('s') | KeyCode::Char('S') => {
+            if app.order_input.active {
+                app.order_input.side = order_book::polymarket_orders::PolymarketOrderSide::SELL;
+            }
+        }
+        
+        // === ORDER TYPE SELECTION ===
+        KeyCode::Char('g') | KeyCode::Char('G') => {
+            if app.order_input.active {
+                app.select_order_type(order_book::polymarket_orders::PolymarketOrderType::GTC);
+            }
+        }
+        KeyCode::Char('f') | KeyCode::Char('F') => {
+            if app.order_input.active {
+                app.select_order_type(order_book::polymarket_orders::PolymarketOrderType::FOK);
+            }
+        }
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            if app.order_input.active {
+                app.select_order_type(order_book::polymarket_orders::PolymarketOrderType::GTD);
+            }
+        }
+        
+        // === TRADING MODE ===
+        KeyCode::Char('t') | KeyCode::Char('T') => {
+            if app.user_command.is_empty() {
+                app.toggle_trading_mode();
+            }
+        }
+        
+        // === COIN SWITCHING ===
+        KeyCode::Char('n') | KeyCode::Char('N') => {
+            if app.user_command.is_empty() {
+                app.next_coin();
+            }
+        }
+        KeyCode::Char('v') | KeyCode::Char('V') => {
+            if app.user_command.is_empty() {
+                app.previous_coin();
+            }
+        }
+        
+        // === TIMEFRAME NAVIGATION ===
+        KeyCode::Char('<') | KeyCode::Char(',') => {
+            if app.user_command.is_empty() {
+                app.previous_timeframe();
+            }
+        }
+        KeyCode::Char('>') | KeyCode::Char('.') => {
+            if app.user_command.is_empty() {
+                app.next_timeframe();
+            }
+        }
+        
+        // === AUTO-REFRESH ===
+        KeyCode::Char('l') | KeyCode::Char('L') => {
+            if app.user_command.is_empty() {
+                app.auto_refresh = !app.auto_refresh;
+                app.log(format!(
+                    "Auto-refresh {}", if app.auto_refresh { "enabled" } else { "disabled" }
+                ));
+            }
+        }
+        
+        // === REAL DATA TOGGLE ===
+        KeyCode::Char('w') | KeyCode::Char('W') => {
+            if app.user_command.is_empty() {
+                app.toggle_real_data();
+            }
+        }
+        
+        // === ORDER FIELD NAVIGATION ===
+        KeyCode::Up => {
+            if app.order_input.active {
+                app.cycle_order_field_up();
+            } else if app.selected_tab == 0 {
+                app.move_book_cursor_up();
+            } else if app.selected_tab == 3 {
+                app.move_orders_cursor_up();
+            } else if app.selected_tab == 5 {
+                app.move_alert_selection(-1);
+            } else if app.selected_tab == 6 {
+                app.move_settings_selection(-1);
+            } else if app.selected_tab == 7 {
+                app.move_watchlist_selection(-1);
+            } else {
+                app.user_command.history_previous();
+            }
+        }
+        KeyCode::Down => {
+            if app.order_input.active {
+                app.cycle_order_field_down();
+            } else if app.selected_tab == 0 {
+                app.move_book_cursor_down();
+            } else if app.selected_tab == 3 {
+                app.move_orders_cursor_down();
+            } else if app.selected_tab == 5 {
+                app.move_alert_selection(1);
+            } else if app.selected_tab == 6 {
+                app.move_settings_selection(1);
+            } else if app.selected_tab == 7 {
+                app.move_watchlist_selection(1);
             }
         }
+        
+        // === SCROLLING ===
+        KeyCode::PageUp | KeyCode::PageDown | KeyCode::Char('j') | KeyCode::Char('k')
+            if app.user_command.is_empty() && !app.order_input.active && matches!(app.selected_tab, 0 | 3) =>
+        {
+            let page = if app.selected_tab == 0 { BOOK_DEPTH_LEVELS } else { ORDER_HISTORY_ROWS } as isize;
+            let delta = match key.code {
+                KeyCode::PageUp => -page,
+                KeyCode::PageDown => page,
+                KeyCode::Char('k') => -1,
+                _ => 1,
+            };
+            if app.selected_tab == 0 {
+                app.scroll_book(delta);
+            } else {
+                app.move_orders_selection(delta);
+            }
+        }
+
+        // === BOOK VIEW ===
+        KeyCode::Char('e') | KeyCode::Char('E')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 0 =>
+        {
+            app.toggle_book_view();
+        }
+
+        // === TRADES TAPE ===
+        KeyCode::Char('y') | KeyCode::Char('Y')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 0 =>
+        {
+            app.toggle_tape_pause();
+        }
+
+        // === CHART INDICATORS ===
+        KeyCode::Char('e') | KeyCode::Char('E')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 4 =>
+        {
+            app.cycle_chart_overlay();
+        }
+        KeyCode::Char('u') | KeyCode::Char('U')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 4 =>
+        {
+            app.cycle_indicator_pane();
+        }
+
+        // === ALERTS ===
+        KeyCode::Char('e') | KeyCode::Char('E')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 5 =>
+        {
+            app.toggle_selected_alert();
+        }
+        KeyCode::Char('x') | KeyCode::Char('X')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 5 =>
+        {
+            app.remove_selected_alert();
+        }
+
+        // === WATCHLIST ===
+        KeyCode::Char('o') | KeyCode::Char('O')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 7 =>
+        {
+            app.cycle_watchlist_sort();
+        }
+
+        // === ORDER CANCEL ===
+        KeyCode::Char('x') | KeyCode::Char('X')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 0 =>
+        {
+            app.cancel_selected_order();
+        }
+        KeyCode::Char('x') | KeyCode::Char('X')
+            if app.user_command.is_empty() && !app.order_input.active && app.selected_tab == 3 =>
+        {
+            app.cancel_selected_history_order();
+        }
+        
+        // === FUNCTION KEYS ===
+        KeyCode::F(2) => {
+            app.selected_tab = 0; // Order Book
+        }
+        KeyCode::F(3) => {
+            app.selected_tab = 1; // Trading
+        }
+        KeyCode::F(4) => {
+            app.selected_tab = 2; // Market Data
+        }
+        KeyCode::F(5) => {
+            app.selected_tab = 3; // Orders
+        }
+        KeyCode::F(6) => {
+            app.selected_tab = 4; // Charts
+        }
+        KeyCode::F(7) => {
+            app.selected_tab = 5; // Alerts
+        }
+        KeyCode::F(8) => {
+            app.selected_tab = 6; // Settings
+        }
+        KeyCode::F(9) => {
+            app.toggle_simulation_pause();
+        }
+        
+        // === SPACE BAR ===
+        KeyCode::Char(' ') => {
+            if app.user_command.is_empty() {
+                app.toggle_order_input();
+            }
+        }
+        
+        // === CHARACTER INPUT ===
+        KeyCode::Char(c) => {
+            if c.is_ascii() && !c.is_control() {
+                app.add_user_command(c);
+            }
+        }
+        
+        _ => {}
+    }
+    LoopControl::Continue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use order_book::ui::{AlertType, OrderVenue, PolymarketBookView, Setting, HELP_ALERTS_SECTION};
+    use order_book::{MarketParams, OrderBook, OrderSide};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn test_handle_event_redraws_on_resize_and_quits_on_ctrl_c() {
+        let mut app = App::new();
+
+        assert_eq!(handle_event(&mut app, Event::Resize(120, 40)), LoopControl::Redraw);
+        assert_eq!(handle_event(&mut app, Event::FocusGained), LoopControl::Continue);
+
+        // A plain 'c' is the clear shortcut; with Ctrl it quits
+        app.user_command.set("abc");
+        assert_eq!(handle_event(&mut app, key(KeyCode::Char('c'), KeyModifiers::NONE)), LoopControl::Continue);
+        assert_eq!(app.user_command.text(), "abc");
+        assert_eq!(handle_event(&mut app, key(KeyCode::Char('c'), KeyModifiers::CONTROL)), LoopControl::Quit);
+        assert_eq!(app.user_command.text(), "abc");
+
+        assert_eq!(handle_event(&mut app, key(KeyCode::Char('q'), KeyModifiers::NONE)), LoopControl::Quit);
+        // Right only switches tabs once the command line is empty
+        app.user_command.clear();
+        assert_eq!(handle_event(&mut app, key(KeyCode::Right, KeyModifiers::NONE)), LoopControl::Continue);
+        assert_eq!(app.selected_tab, 1);
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> LoopControl {
+        handle_key(app, KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_command_line_arguments() {
+        let parse = |args: &[&str]| Cli::try_parse_from(std::iter::once("order-book").chain(args.iter().copied()));
+        let cli = parse(&[]).unwrap();
+        assert_eq!((cli.seed, cli.real_data, cli.mode), (None, false, None));
+
+        let cli = parse(&["--seed", "42", "--symbol", "ETHUSDT", "--real-data", "--config", "/tmp/settings.toml"]).unwrap();
+        assert_eq!(cli.seed, Some(42));
+        assert_eq!(cli.symbol.as_deref(), Some("ETHUSDT"));
+        assert!(cli.real_data);
+        assert_eq!(cli.config, Some(PathBuf::from("/tmp/settings.toml")));
+        assert_eq!(parse(&["--seed=7"]).unwrap().seed, Some(7));
+        assert_eq!(parse(&["--log-level", "Debug"]).unwrap().log_level, Some(tracing::Level::DEBUG));
+        assert!(parse(&["--log-level", "loud"]).is_err());
+
+        // Options may follow the subcommand too
+        let cli = parse(&["headless", "--seed", "3"]).unwrap();
+        assert_eq!((cli.mode, cli.seed), (Some(Mode::Headless), Some(3)));
+        assert_eq!(
+            parse(&["replay", "feed.jsonl", "--speed", "10"]).unwrap().mode,
+            Some(Mode::Replay { file: PathBuf::from("feed.jsonl"), speed: 10.0 })
+        );
+        assert_eq!(
+            parse(&["replay", "feed.jsonl"]).unwrap().mode,
+            Some(Mode::Replay { file: PathBuf::from("feed.jsonl"), speed: 1.0 })
+        );
+        assert_eq!(parse(&["bench"]).unwrap().mode, Some(Mode::Bench { orders: 100000 }));
+        assert_eq!(parse(&["tui"]).unwrap().mode, Some(Mode::Tui));
+
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--speed"]).is_err());
+        assert!(parse(&["replay"]).is_err());
+        assert!(parse(&["replay", "feed.jsonl", "--speed", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_book_line() {
+        let at = chrono::DateTime::parse_from_rfc3339("2024-03-01T12:00:01Z").unwrap().with_timezone(&chrono::Utc);
+        let book = OrderBook::new();
+        assert_eq!(book_line("BTCUSDT", &book, 2, at), "12:00:01 BTCUSDT bid - | ask -");
+
+        book.add_order(OrderSide::Bid, 99.5, 2.0, 1);
+        assert_eq!(book_line("BTCUSDT", &book, 2, at), "12:00:01 BTCUSDT bid 99.50 x 2 | ask -");
+        book.add_order(OrderSide::Ask, 100.5, 1.25, 2);
+        book.add_order(OrderSide::Ask, 101.0, 3.0, 3);
+        assert_eq!(
+            book_line("BTCUSDT", &book, 2, at),
+            "12:00:01 BTCUSDT bid 99.50 x 2 | ask 100.50 x 1.25 | spread 1.00 mid 100.00"
+        );
+        book.add_market_order(OrderSide::Bid, 0.25, 4);
+        assert_eq!(
+            book_line("ETHUSDT", &book, 1, at),
+            "12:00:01 ETHUSDT bid 99.5 x 2 | ask 100.5 x 1 | spread 1.0 mid 100.0 | last 100.5"
+        );
+    }
+
+    #[test]
+    fn test_options_select_the_coin() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let cli = Cli::try_parse_from(["order-book", "--symbol", "ethusdt", "--seed", "5"]).unwrap();
+        let app = build_app(&cli, &runtime).unwrap();
+        assert_eq!(app.available_coins[app.selected_coin_index].symbol, "ETH");
+        assert_eq!(app.simulator.seed(), Some(5));
+
+        let cli = Cli::try_parse_from(["order-book", "--symbol", "NOPE"]).unwrap();
+        assert_eq!(build_app(&cli, &runtime).err(), Some("Unknown symbol: NOPE".to_string()));
+    }
+
+    #[test]
+    fn test_terminal_restore_is_idempotent() {
+        TerminalGuard::restore();
+        TerminalGuard::restore();
+        drop(TerminalGuard);
+        assert!(!crossterm::terminal::is_raw_mode_enabled().unwrap_or(false));
+    }
+
+    #[test]
+    fn test_f9_pauses_the_simulation() {
+        let mut app = App::with_seed(3);
+        press(&mut app, KeyCode::F(9));
+        assert!(app.simulator.is_paused());
+        let price = app.market_data.current_price;
+        app.simulation_tick();
+        assert_eq!(app.market_data.current_price, price);
+        press(&mut app, KeyCode::F(9));
+        assert!(!app.simulator.is_paused());
+    }
+
+    #[test]
+    fn test_quit_keys() {
+        let mut app = App::new();
+        assert_eq!(press(&mut app, KeyCode::Char('q')), LoopControl::Quit);
+        assert_eq!(press(&mut app, KeyCode::Char('Q')), LoopControl::Quit);
+        assert_eq!(press(&mut app, KeyCode::Esc), LoopControl::Continue);
+    }
+
+    #[test]
+    fn test_watchlist_keys() {
+        let mut app = App::new();
+        app.selected_tab = 7;
+        press(&mut app, KeyCode::Char('o'));
+        press(&mut app, KeyCode::Char('o'));
+        assert_eq!(app.watchlist_sort, order_book::watchlist::WatchlistSort::LastPrice);
+        // By price ETH is the second row
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.current_market, "ETH");
+        assert_eq!(app.selected_tab, 0);
+    }
+
+    #[test]
+    fn test_digits_select_coins_or_tabs() {
+        let mut app = App::new();
+        app.selected_tab = 2;
+
+        // With an empty command line 1-3 pick a coin and leave the tab alone
+        assert_eq!(press(&mut app, KeyCode::Char('2')), LoopControl::Continue);
+        assert_eq!(app.selected_coin_index, 1);
+        assert_eq!(app.selected_tab, 2);
+
+        // While a command is typed they jump to a tab instead
+        app.user_command.set("x");
+        press(&mut app, KeyCode::Char('1'));
+        assert_eq!(app.selected_tab, 0);
+        assert_eq!(app.selected_coin_index, 1);
+
+        // 4-9 and F2-F8 select tabs while there are only three coins
+        press(&mut app, KeyCode::Char('7'));
+        assert_eq!(app.selected_tab, 6);
+        press(&mut app, KeyCode::Char('8'));
+        assert_eq!(app.selected_tab, 7);
+        press(&mut app, KeyCode::Char('9'));
+        assert_eq!(app.selected_tab, 8);
+        press(&mut app, KeyCode::F(5));
+        assert_eq!(app.selected_tab, 3);
+
+        // Once a fourth coin is added, 4 picks it instead
+        app.add_coin("ada").unwrap();
+        app.user_command.clear();
+        press(&mut app, KeyCode::Char('4'));
+        assert_eq!(app.selected_coin_index, 3);
+        assert_eq!(app.selected_tab, 3);
+        press(&mut app, KeyCode::Char('5'));
+        assert_eq!(app.selected_tab, 4);
+        assert_eq!(app.selected_coin_index, 3);
+    }
+
+    #[test]
+    fn test_command_line_editing() {
+        let mut app = App::new();
+
+        // Keys without a binding are typed into the command line
+        for c in "zeu".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert_eq!(app.user_command.text(), "zeu");
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(app.user_command.text(), "ze");
+        press(&mut app, KeyCode::Esc);
+        assert!(app.user_command.is_empty());
+
+        app.user_command.set("bars");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.user_command.is_empty());
+        assert_eq!(app.depth_bar_mode, order_book::ui::DepthBarMode::PerLevel);
+    }
+
+    #[test]
+    fn test_command_line_history_cursor_and_completion_keys() {
+        let mut app = App::new();
+        app.user_command.load_history(Vec::new());
+        app.selected_tab = 1;
+        for command in ["bars", "coin ETH"] {
+            app.user_command.set(command);
+            press(&mut app, KeyCode::Enter);
+        }
+        assert_eq!(app.selected_coin_index, 1);
+
+        // Up and Down step through the history, back to an empty line
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.user_command.text(), "coin ETH");
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.user_command.text(), "bars");
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert!(app.user_command.is_empty());
+
+        // With a command typed, Left/Right/Home/End move its cursor, not the tab
+        app.user_command.set("zeu");
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Char('z'));
+        assert_eq!(app.user_command.text(), "zzeu");
+        press(&mut app, KeyCode::Home);
+        press(&mut app, KeyCode::Delete);
+        press(&mut app, KeyCode::End);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(app.user_command.text(), "ze");
+        assert_eq!(app.selected_tab, 1);
+
+        // Tab completes instead of switching tabs
+        app.user_command.set("coin s");
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.user_command.text(), "coin SOL ");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.selected_coin_index, 2);
+        assert_eq!(app.selected_tab, 1);
+    }
+
+    #[test]
+    fn test_shortcuts_only_fire_with_empty_command() {
+        let mut app = App::new();
+        let help = app.help_mode;
+
+        press(&mut app, KeyCode::Char('?'));
+        assert_eq!(app.help_mode, !help);
+        app.user_command.set("x");
+        press(&mut app, KeyCode::Char('h'));
+        assert_eq!(app.help_mode, !help);
+
+        app.user_command.clear();
+        press(&mut app, KeyCode::Char('p'));
+        assert!(app.order_input.active);
+        press(&mut app, KeyCode::Char('s'));
+        assert_eq!(app.order_input.side, order_book::PolymarketOrderSide::SELL);
+        press(&mut app, KeyCode::Char('d'));
+        assert_eq!(app.order_input.order_type, order_book::PolymarketOrderType::GTD);
+    }
+
+    #[test]
+    fn test_order_form_accepts_typed_numbers() {
+        let mut app = App::new();
+        let coin = app.selected_coin_index;
+        let timeframe = app.selected_timeframe.as_str();
+        press(&mut app, KeyCode::Char('p'));
+        app.order_input.price.clear();
+        app.order_input.quantity.clear();
+
+        for c in "26436.5".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Down);
+        for c in "0.25".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(app.order_input.price, "26436.5");
+        assert_eq!(app.order_input.quantity, "0.2");
+        // The digits did not switch coins and '.' did not change the timeframe
+        assert_eq!(app.selected_coin_index, coin);
+        assert_eq!(app.selected_timeframe.as_str(), timeframe);
+
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.order_input.current_field, order_book::ui::OrderField::Price);
+    }
+
+    #[test]
+    fn test_tab_and_cursor_navigation() {
+        let mut app = App::new();
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.selected_tab, 1);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Tab, KeyModifiers::SHIFT));
+        assert_eq!(app.selected_tab, 0);
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.selected_tab, app.tabs.len() - 1);
+
+        // On the Order Book tab Up/Down move the level cursor, but cycle
+        // the form's fields while an order is being entered
+        app.selected_tab = 0;
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.book_cursor, Some(1));
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.book_cursor, Some(0));
+
+        app.order_input.active = true;
+        let field = app.order_input.current_field;
+        press(&mut app, KeyCode::Down);
+        assert_ne!(app.order_input.current_field, field);
+        assert_eq!(app.book_cursor, Some(0));
+    }
+
+    #[test]
+    fn test_cancel_key_leaves_local_book_alone_while_polymarket_book_is_shown() {
+        let mut app = App::new();
+        app.selected_tab = 0;
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.book_cursor, Some(0));
+
+        app.polymarket_book = Some(PolymarketBookView {
+            token_id: "123".to_string(),
+            book: OrderBook::new(),
+            params: MarketParams::default(),
+        });
+        let (bids, asks) = app.order_book.get_market_depth(usize::MAX);
+        press(&mut app, KeyCode::Char('x'));
+        assert_eq!(app.order_book.get_market_depth(usize::MAX), (bids, asks));
+        assert!(app.order_history.iter().all(|record| record.status != "Cancelled"));
+
+        // The cursor has no rows to move over on the Polymarket book
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.book_cursor, None);
+    }
+
+    #[test]
+    fn test_alerts_tab_keys_select_toggle_and_remove() {
+        let mut app = App::new();
+        app.price_alerts.clear();
+        for target in [100.0, 200.0] {
+            app.add_price_alert("BTCUSDT".to_string(), AlertType::PriceAbove(target), String::new());
+        }
+        app.selected_tab = 5;
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char('e'));
+        assert!(app.price_alerts[0].is_active);
+        assert!(!app.price_alerts[1].is_active);
+
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Char('x'));
+        assert_eq!(app.price_alerts.len(), 1);
+        assert_eq!(app.price_alerts[0].alert_type, AlertType::PriceAbove(200.0));
+        assert!(app.user_command.is_empty());
+    }
+
+    #[test]
+    fn test_scroll_keys_move_the_focused_panel() {
+        let mut app = App::new();
+        app.order_input.venue = OrderVenue::Local;
+        for _ in 0..3 {
+            app.order_input.price = "1".to_string();
+            app.order_input.quantity = "0.1".to_string();
+            app.submit_order();
+        }
+        app.selected_tab = 3;
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.selected_order_row(), Some(1));
+        press(&mut app, KeyCode::PageUp);
+        assert_eq!(app.selected_order_row(), Some(0));
+        assert!(app.user_command.is_empty());
+
+        app.selected_tab = 0;
+        app.order_book = OrderBook::new();
+        for level in 0..50 {
+            app.order_book.add_order(OrderSide::Bid, 1000.0 - level as f64, 1.0, 1);
+        }
+        press(&mut app, KeyCode::PageDown);
+        press(&mut app, KeyCode::PageDown);
+        assert_eq!(app.book_scroll, 30);
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.book_scroll, 29);
+
+        // While typing a command, j and k are just letters
+        app.user_command.set("a");
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.user_command.text(), "ak");
+        assert_eq!(app.book_scroll, 29);
+    }
+
+    #[test]
+    fn test_mouse_clicks_tabs_after_a_frame() {
+        let mut app = App::new();
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 50)).unwrap();
+        terminal.draw(|f| order_book::ui::draw_ui(f, &mut app)).unwrap();
+
+        let mouse = |kind, column, row| Event::Mouse(MouseEvent { kind, column, row, modifiers: KeyModifiers::NONE });
+        // "Charts" starts at column 1 + 13 + 10 + 14 + 9
+        let control = handle_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 49, 1));
+        assert_eq!(control, LoopControl::Continue);
+        assert_eq!(app.selected_tab, 4);
+        handle_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Right), 3, 1));
+        assert_eq!(app.selected_tab, 4);
+
+        app.selected_tab = 0;
+        app.help_mode = true;
+        terminal.draw(|f| order_book::ui::draw_ui(f, &mut app)).unwrap();
+        handle_event(&mut app, mouse(MouseEventKind::ScrollDown, 20, 20));
+        assert!(app.help_scroll > 0);
+        handle_event(&mut app, mouse(MouseEventKind::ScrollUp, 20, 20));
+        assert_eq!(app.help_scroll, 0);
+    }
+
+    #[test]
+    fn test_keys_scroll_the_open_help() {
+        let mut app = App::new();
+        app.selected_tab = 5;
+        press(&mut app, KeyCode::Char('?'));
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+        terminal.draw(|f| order_book::ui::draw_ui(f, &mut app)).unwrap();
+        // Opened at the alert commands
+        let section = app.help_section_offset(HELP_ALERTS_SECTION).unwrap();
+        assert_eq!(app.help_scroll, section);
+
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.help_scroll, section + 1);
+        press(&mut app, KeyCode::PageUp);
+        assert_eq!(app.help_scroll, section + 1 - app.help_page());
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.help_scroll, section - app.help_page());
+        for _ in 0..10 {
+            press(&mut app, KeyCode::PageDown);
+        }
+        // Stopping once the last line is at the bottom
+        let bottom = app.help_max_scroll();
+        assert!(bottom > section);
+        assert_eq!(app.help_scroll, bottom);
+    }
+
+    #[test]
+    fn test_settings_tab_keys_select_and_change() {
+        let mut app = App::new();
+        app.auto_refresh = true;
+        app.alert_sound_enabled = true;
+        app.selected_tab = 6;
+
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.auto_refresh);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.selected_setting(), Setting::AlertSound);
+        press(&mut app, KeyCode::Char('-'));
+        assert!(!app.alert_sound_enabled);
+        press(&mut app, KeyCode::Up);
+        app.refresh_interval = Duration::from_secs(2);
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.refresh_interval, Duration::from_secs(5));
+        assert!(app.user_command.is_empty());
+
+        // On the Logs tab they narrow and widen the level filter
+        app.selected_tab = 8;
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.log_filter, tracing::Level::ERROR);
+        press(&mut app, KeyCode::Char('-'));
+        assert_eq!(app.log_filter, tracing::Level::WARN);
+        assert!(app.user_command.is_empty());
+
+        // Elsewhere '+' and '-' are typed as usual
+        app.selected_tab = 1;
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.user_command.text(), "+");
     }
 }
@@ -1,4 +1,5 @@
 use crate::price::Price;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Order {
@@ -7,14 +8,25 @@ pub struct Order {
     pub price: Price,
     pub quantity: f64,
     pub timestamp: u64,
+    /// Account the order belongs to, if it was placed for one.
+    pub owner: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderSide {
     Bid,
     Ask,
 }
 
+impl OrderSide {
+    pub fn opposite(self) -> Self {
+        match self {
+            OrderSide::Bid => OrderSide::Ask,
+            OrderSide::Ask => OrderSide::Bid,
+        }
+    }
+}
+
 impl Order {
     pub fn new(id: u64, side: OrderSide, price: f64, quantity: f64, timestamp: u64) -> Self {
         Self {
@@ -23,7 +35,13 @@ impl Order {
             price: Price(price),
             quantity,
             timestamp,
+            owner: None,
         }
     }
+
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
 }
 
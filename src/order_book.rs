@@ -1,13 +1,15 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use crossbeam::queue::SegQueue;
 use dashmap::DashMap;
-use parking_lot::RwLock;
+use parking_lot::{RwLock, RwLockWriteGuard};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
 use crate::order::{Order, OrderSide};
 use crate::price::Price;
-use crate::trade::Trade;
+use crate::trade::{FeeSchedule, Trade};
 
 #[derive(Debug)]
 pub struct OrderQueue {
@@ -101,8 +103,28 @@ impl OrderQueue {
         self.orders.iter().map(|entry| entry.value().clone()).collect()
     }
 
-    pub fn match_orders_with(&self, other_queue: &OrderQueue, side: OrderSide) -> Vec<Trade> {
+    /// Returns the live orders in time priority, dropping ids of orders that
+    /// have already been removed from the queue.
+    pub fn get_orders_fifo(&self) -> Vec<Order> {
+        let mut order_ids = Vec::new();
+        while let Some(order_id) = self.order_queue.pop() {
+            order_ids.push(order_id);
+        }
+
+        let mut orders = Vec::new();
+        for order_id in order_ids {
+            if let Some(order) = self.orders.get(&order_id) {
+                orders.push(order.clone());
+                self.order_queue.push(order_id);
+            }
+        }
+
+        orders
+    }
+
+    pub fn match_orders_with(&self, other_queue: &OrderQueue, side: OrderSide, price_rule: PriceRule) -> Vec<Trade> {
         let mut trades = Vec::new();
+        let now = now_millis();
         
         let self_orders = self.get_all_orders();
         let other_orders = other_queue.get_all_orders();
@@ -151,18 +173,23 @@ impl OrderQueue {
             
             if can_match {
                 let trade_quantity = self_order.quantity.min(other_order.quantity);
-                let trade_price = if self_order.timestamp <= other_order.timestamp {
-                    self_order.price.as_f64()
+                // The earlier order was resting, so it is the maker
+                let (maker, taker) = if self_order.timestamp <= other_order.timestamp {
+                    (self_order, other_order)
                 } else {
-                    other_order.price.as_f64()
+                    (other_order, self_order)
                 };
+                let trade_price = price_rule.trade_price(maker.price.as_f64(), Some(taker.price.as_f64()));
                 
                 trades.push(Trade {
                     bid_order_id: if side == OrderSide::Bid { self_order.id } else { other_order.id },
                     ask_order_id: if side == OrderSide::Bid { other_order.id } else { self_order.id },
                     price: trade_price,
                     quantity: trade_quantity,
-                    timestamp: std::cmp::min(self_order.timestamp, other_order.timestamp),
+                    timestamp: now,
+                    aggressor_side: if self_order.timestamp <= other_order.timestamp { side.opposite() } else { side },
+                    maker_fee: 0.0,
+                    taker_fee: 0.0,
                 });
                 
                 if self_order.quantity <= other_order.quantity {
@@ -183,6 +210,9 @@ impl OrderQueue {
 pub struct PriceLevel {
     pub price: Price,
     pub orders: Arc<OrderQueue>,
+    /// Reserve some feeds report behind the visible orders. Nothing matches
+    /// against it; only depth that asks for it shows it.
+    hidden_quantity: Arc<RwLock<f64>>,
 }
 
 impl PriceLevel {
@@ -190,6 +220,7 @@ impl PriceLevel {
         Self {
             price: Price(price),
             orders: Arc::new(OrderQueue::new()),
+            hidden_quantity: Arc::new(RwLock::new(0.0)),
         }
     }
 
@@ -209,6 +240,20 @@ impl PriceLevel {
         self.orders.get_total_quantity()
     }
 
+    pub fn get_hidden_quantity(&self) -> f64 {
+        *self.hidden_quantity.read()
+    }
+
+    pub fn set_hidden_quantity(&self, quantity: f64) {
+        *self.hidden_quantity.write() = quantity.max(0.0);
+    }
+
+    /// The visible quantity, plus the hidden reserve when `include_hidden`.
+    pub fn depth_quantity(&self, include_hidden: bool) -> f64 {
+        let hidden = if include_hidden { self.get_hidden_quantity() } else { 0.0 };
+        self.get_total_quantity() + hidden
+    }
+
     pub fn is_empty(&self) -> bool {
         self.orders.is_empty()
     }
@@ -224,6 +269,20 @@ impl PriceLevel {
     pub fn remove_first_order(&self) -> Option<Order> {
         self.orders.remove_first_order()
     }
+
+    pub fn get_orders_fifo(&self) -> Vec<Order> {
+        self.orders.get_orders_fifo()
+    }
+
+    /// A copy with its own queue, unlike `clone`, which shares the orders.
+    pub fn fork(&self) -> Self {
+        let level = Self::new(self.price.as_f64());
+        for order in self.get_orders_fifo() {
+            level.add_order(order);
+        }
+        level.set_hidden_quantity(self.get_hidden_quantity());
+        level
+    }
 }
 
 #[derive(Debug)]
@@ -233,9 +292,39 @@ pub struct OrderBook {
     next_order_id: AtomicU64,
     stats: Arc<RwLock<OrderBookStats>>,
     matching_lock: parking_lot::Mutex<()>,
+    tick_size: RwLock<Option<f64>>,
+    /// Most decimal places seen on a price given as a decimal, for `format_price`.
+    price_scale: RwLock<Option<u32>>,
+    fee_schedule: RwLock<FeeSchedule>,
+    price_rule: RwLock<PriceRule>,
+    take_profits: RwLock<Vec<TakeProfitOrder>>,
+    /// Latest executions, oldest first, at most `TRADE_HISTORY_CAPACITY`.
+    /// `clear` keeps them: a snapshot-fed book is cleared on every snapshot.
+    trades: RwLock<VecDeque<Trade>>,
+    /// Most trades one `match_orders` call makes; see `MatchResult`.
+    max_match_iterations: AtomicUsize,
 }
 
-#[derive(Debug, Clone)]
+/// `(price, quantity)` levels, best price first.
+pub type DepthLevels = Vec<(f64, f64)>;
+
+/// A price level with its orders as `(id, quantity, timestamp)`, oldest first.
+pub type L3Level = (f64, Vec<(u64, f64, u64)>);
+
+/// Most trades one `OrderBook::match_orders` call makes until changed with
+/// `set_max_match_iterations`.
+pub const DEFAULT_MAX_MATCH_ITERATIONS: usize = 100_000;
+
+/// Most trades `OrderBook::recent_trades` can return.
+pub const TRADE_HISTORY_CAPACITY: usize = 500;
+
+/// Price levels per side the `Display` summary lists.
+pub const DISPLAY_DEPTH_LEVELS: usize = 10;
+
+/// Price levels per side that feed `OrderBookStats::imbalance`.
+pub const IMBALANCE_DEPTH_LEVELS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBookStats {
     pub total_orders_created: u64,
     pub total_orders_matched: u64,
@@ -245,7 +334,19 @@ pub struct OrderBookStats {
     pub best_ask: Option<f64>,
     pub spread: Option<f64>,
     pub mid_price: Option<f64>,
+    /// `(bid_qty - ask_qty) / (bid_qty + ask_qty)` over the top
+    /// `IMBALANCE_DEPTH_LEVELS` levels: +1 is all bids, -1 all asks.
+    pub imbalance: Option<f64>,
     pub last_match_time: Option<u64>,
+    /// Price of the latest trade, and the highest and lowest traded since the
+    /// book was created or last cleared.
+    pub last_trade_price: Option<f64>,
+    pub session_high: Option<f64>,
+    pub session_low: Option<f64>,
+    /// Trades added to the trade history so far, including any it has
+    /// since dropped.
+    #[serde(default)]
+    pub trades_recorded: u64,
 }
 
 impl OrderBookStats {
@@ -259,8 +360,24 @@ impl OrderBookStats {
             best_ask: None,
             spread: None,
             mid_price: None,
+            imbalance: None,
             last_match_time: None,
+            last_trade_price: None,
+            session_high: None,
+            session_low: None,
+            trades_recorded: 0,
+        }
+    }
+
+    /// Moves the last, high and low traded prices over `trades`, oldest first,
+    /// and counts them.
+    pub fn update_trade_prices(&mut self, trades: &[Trade]) {
+        for trade in trades {
+            self.last_trade_price = Some(trade.price);
+            self.session_high = Some(self.session_high.map_or(trade.price, |high| high.max(trade.price)));
+            self.session_low = Some(self.session_low.map_or(trade.price, |low| low.min(trade.price)));
         }
+        self.trades_recorded += trades.len() as u64;
     }
 
     pub fn update_market_data(&mut self, best_bid: Option<f64>, best_ask: Option<f64>) {
@@ -275,6 +392,237 @@ impl OrderBookStats {
             self.mid_price = None;
         }
     }
+
+    /// Records a new best price for one side, reusing the cached best of the
+    /// other side, which a single-side mutation cannot have changed.
+    pub fn update_best(&mut self, side: OrderSide, best: Option<f64>) {
+        match side {
+            OrderSide::Bid => self.update_market_data(best, self.best_ask),
+            OrderSide::Ask => self.update_market_data(self.best_bid, best),
+        }
+    }
+
+    pub fn update_imbalance(&mut self, bid_quantity: f64, ask_quantity: f64) {
+        let total = bid_quantity + ask_quantity;
+        self.imbalance = if total > 0.0 {
+            Some((bid_quantity - ask_quantity) / total)
+        } else {
+            None
+        };
+    }
+}
+
+/// A post-only order that would have taken liquidity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PostOnlyRejected {
+    pub side: OrderSide,
+    pub price: f64,
+    /// The resting price the order would have crossed.
+    pub opposite_best: f64,
+}
+
+impl fmt::Display for PostOnlyRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "post-only {:?} at {} would cross the best {} at {}",
+            self.side,
+            self.price,
+            if self.side == OrderSide::Bid { "ask" } else { "bid" },
+            self.opposite_best
+        )
+    }
+}
+
+impl std::error::Error for PostOnlyRejected {}
+
+/// Both sides' aggregated levels at one moment, best price first, as
+/// returned by `OrderBook::snapshot`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderBookSnapshot {
+    pub bids: DepthLevels,
+    pub asks: DepthLevels,
+}
+
+/// A level whose quantity differs between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelChange {
+    pub price: f64,
+    pub old_quantity: f64,
+    pub new_quantity: f64,
+}
+
+/// How one side of the book moved between two snapshots, best price first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SideDiff {
+    pub added: DepthLevels,
+    /// Levels gone from the later snapshot, with the quantity they had.
+    pub removed: DepthLevels,
+    pub changed: Vec<LevelChange>,
+}
+
+impl SideDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// What changed from one `OrderBookSnapshot` to another.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookDiff {
+    pub bids: SideDiff,
+    pub asks: SideDiff,
+}
+
+impl BookDiff {
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
+
+impl OrderBookSnapshot {
+    /// The changes that turn `self` into `other`. Levels are matched on exact
+    /// price and a quantity counts as changed when it differs at all.
+    pub fn diff(&self, other: &Self) -> BookDiff {
+        BookDiff {
+            bids: diff_levels(&self.bids, &other.bids, OrderSide::Bid),
+            asks: diff_levels(&self.asks, &other.asks, OrderSide::Ask),
+        }
+    }
+}
+
+fn diff_levels(before: &[(f64, f64)], after: &[(f64, f64)], side: OrderSide) -> SideDiff {
+    let index = |levels: &[(f64, f64)]| -> BTreeMap<Price, f64> {
+        levels.iter().map(|&(price, quantity)| (Price(price), quantity)).collect()
+    };
+    let (before, after) = (index(before), index(after));
+
+    let mut diff = SideDiff::default();
+    for (price, &new_quantity) in &after {
+        match before.get(price) {
+            None => diff.added.push((price.as_f64(), new_quantity)),
+            Some(&old_quantity) if old_quantity != new_quantity => diff.changed.push(LevelChange {
+                price: price.as_f64(),
+                old_quantity,
+                new_quantity,
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.removed = before
+        .iter()
+        .filter(|(price, _)| !after.contains_key(*price))
+        .map(|(price, &quantity)| (price.as_f64(), quantity))
+        .collect();
+
+    // The maps ascend, which is best first only for asks
+    if side == OrderSide::Bid {
+        diff.added.reverse();
+        diff.removed.reverse();
+        diff.changed.reverse();
+    }
+    diff
+}
+
+impl fmt::Display for BookDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        for (name, side) in [("bid", &self.bids), ("ask", &self.asks)] {
+            for (price, quantity) in &side.added {
+                writeln!(f, "+ {} {} x {}", name, price, quantity)?;
+            }
+            for (price, quantity) in &side.removed {
+                writeln!(f, "- {} {} x {}", name, price, quantity)?;
+            }
+            for change in &side.changed {
+                writeln!(f, "~ {} {} x {} -> {}", name, change.price, change.old_quantity, change.new_quantity)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Trades from one `OrderBook::match_orders` call. When `hit_iteration_cap`
+/// is set the book may still be crossed; calling again carries on matching.
+#[derive(Debug, Clone, Default)]
+pub struct MatchResult {
+    pub trades: Vec<Trade>,
+    pub hit_iteration_cap: bool,
+}
+
+/// The price two crossing orders trade at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriceRule {
+    /// The resting order's price, as most exchanges do.
+    #[default]
+    MakerPrice,
+    /// The incoming order's limit price.
+    TakerPrice,
+    /// Halfway between the two limit prices, which may fall between ticks.
+    Midpoint,
+}
+
+impl PriceRule {
+    /// The trade price for a maker at `maker` and a taker limited to `taker`.
+    /// Market orders have no limit, so they always trade at the maker's price.
+    pub fn trade_price(self, maker: f64, taker: Option<f64>) -> f64 {
+        match (self, taker) {
+            (PriceRule::MakerPrice, _) | (_, None) => maker,
+            (PriceRule::TakerPrice, Some(taker)) => taker,
+            (PriceRule::Midpoint, Some(taker)) => (maker + taker) / 2.0,
+        }
+    }
+}
+
+/// Where a resting order stands in its price level's time priority.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueuePosition {
+    pub side: OrderSide,
+    pub price: f64,
+    /// Orders at the level that fill before this one.
+    pub orders_ahead: usize,
+    /// Their combined remaining quantity.
+    pub quantity_ahead: f64,
+}
+
+/// A take-profit (Binance's `TAKE_PROFIT`) parked off the book until the last
+/// trade price reaches `trigger_price`, then sent as a market order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TakeProfitOrder {
+    pub id: u64,
+    pub side: OrderSide,
+    pub trigger_price: f64,
+    pub quantity: f64,
+    pub timestamp: u64,
+}
+
+impl TakeProfitOrder {
+    /// An ask closes a long, so it triggers once price rises to the target;
+    /// a bid closes a short and triggers once price falls to it.
+    pub fn is_triggered_by(&self, last_price: f64) -> bool {
+        match self.side {
+            OrderSide::Ask => last_price >= self.trigger_price,
+            OrderSide::Bid => last_price <= self.trigger_price,
+        }
+    }
+}
+
+/// Milliseconds since the epoch, the time a matching sweep stamps its trades with.
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// Best price of one side's levels: the highest bid or the lowest ask.
+fn best_price(side: OrderSide, levels: &BTreeMap<Price, PriceLevel>) -> Option<f64> {
+    let best = match side {
+        OrderSide::Bid => levels.keys().next_back(),
+        OrderSide::Ask => levels.keys().next(),
+    };
+    best.map(|p| p.as_f64())
 }
 
 impl OrderBook {
@@ -285,165 +633,384 @@ impl OrderBook {
             next_order_id: AtomicU64::new(1),
             stats: Arc::new(RwLock::new(OrderBookStats::new())),
             matching_lock: parking_lot::Mutex::new(()),
+            tick_size: RwLock::new(None),
+            price_scale: RwLock::new(None),
+            fee_schedule: RwLock::new(FeeSchedule::default()),
+            price_rule: RwLock::new(PriceRule::default()),
+            take_profits: RwLock::new(Vec::new()),
+            trades: RwLock::new(VecDeque::new()),
+            max_match_iterations: AtomicUsize::new(DEFAULT_MAX_MATCH_ITERATIONS),
         }
     }
 
-    pub fn add_order(&self, side: OrderSide, price: f64, quantity: f64, timestamp: u64) -> u64 {
-        let order_id = self.next_order_id.fetch_add(1, Ordering::Relaxed);
-        let order = Order::new(order_id, side.clone(), price, quantity, timestamp);
+    /// An independent copy of the book: every level, order, setting, stat and
+    /// trade, with nothing shared, so orders sent to the fork leave this book
+    /// untouched.
+    pub fn fork(&self) -> OrderBook {
+        let _guard = self.matching_lock.lock();
+        let fork_side = |side: &RwLock<BTreeMap<Price, PriceLevel>>| {
+            side.read().iter().map(|(price, level)| (price.clone(), level.fork())).collect::<BTreeMap<_, _>>()
+        };
+        OrderBook {
+            bids: RwLock::new(fork_side(&self.bids)),
+            asks: RwLock::new(fork_side(&self.asks)),
+            next_order_id: AtomicU64::new(self.next_order_id.load(Ordering::SeqCst)),
+            stats: Arc::new(RwLock::new(self.stats.read().clone())),
+            matching_lock: parking_lot::Mutex::new(()),
+            tick_size: RwLock::new(*self.tick_size.read()),
+            price_scale: RwLock::new(*self.price_scale.read()),
+            fee_schedule: RwLock::new(self.fee_schedule()),
+            price_rule: RwLock::new(self.price_rule()),
+            take_profits: RwLock::new(self.take_profits.read().clone()),
+            trades: RwLock::new(self.trades.read().clone()),
+            max_match_iterations: AtomicUsize::new(self.max_match_iterations()),
+        }
+    }
 
-        match side {
-            OrderSide::Bid => {
-                let mut bids = self.bids.write();
-                bids.entry(Price(price))
-                    .or_insert_with(|| PriceLevel::new(price))
-                    .add_order(order);
+    /// Caps the trades one `match_orders` call makes, bounding how long it
+    /// holds the matching lock. At least one trade is always allowed. Limit
+    /// and market orders are not capped: they fill only the incoming order,
+    /// so its quantity bounds them.
+    pub fn set_max_match_iterations(&self, max_iterations: usize) {
+        self.max_match_iterations.store(max_iterations.max(1), Ordering::Relaxed);
+    }
+
+    pub fn max_match_iterations(&self) -> usize {
+        self.max_match_iterations.load(Ordering::Relaxed)
+    }
+
+    /// Sets the fees charged on trades matched from now on; the default
+    /// schedule charges nothing.
+    pub fn set_fee_schedule(&self, fee_schedule: FeeSchedule) {
+        *self.fee_schedule.write() = fee_schedule;
+    }
+
+    pub fn fee_schedule(&self) -> FeeSchedule {
+        *self.fee_schedule.read()
+    }
+
+    /// Sets the price crossing orders trade at from now on; see `PriceRule`.
+    pub fn set_price_rule(&self, price_rule: PriceRule) {
+        *self.price_rule.write() = price_rule;
+    }
+
+    pub fn price_rule(&self) -> PriceRule {
+        *self.price_rule.read()
+    }
+
+    fn apply_fees(&self, trades: &mut [Trade]) {
+        let fee_schedule = self.fee_schedule();
+        for trade in trades {
+            fee_schedule.apply(trade);
+        }
+    }
+
+    /// Appends executions to the trade history, dropping the oldest past
+    /// `TRADE_HISTORY_CAPACITY`, and moves the traded-price stats with them.
+    /// Matching records its own trades; a live feed mirrored into the book
+    /// records its prints here.
+    pub fn record_trades(&self, trades: &[Trade]) {
+        {
+            let mut history = self.trades.write();
+            history.extend(trades.iter().cloned());
+            let excess = history.len().saturating_sub(TRADE_HISTORY_CAPACITY);
+            history.drain(..excess);
+        }
+        self.stats.write().update_trade_prices(trades);
+    }
+
+    /// Up to `count` of the latest trades, newest first.
+    pub fn recent_trades(&self, count: usize) -> Vec<Trade> {
+        self.trades.read().iter().rev().take(count).cloned().collect()
+    }
+
+    /// Sets the price increment new orders are snapped to; `None` disables rounding.
+    pub fn set_tick_size(&self, tick_size: Option<f64>) {
+        *self.tick_size.write() = tick_size.filter(|t| *t > 0.0);
+    }
+
+    pub fn get_tick_size(&self) -> Option<f64> {
+        *self.tick_size.read()
+    }
+
+    /// Rounds `price` to the nearest multiple of the configured tick size.
+    pub fn round_to_tick(&self, price: f64) -> f64 {
+        match self.get_tick_size() {
+            Some(tick) => {
+                let decimals = (-tick.log10()).ceil().max(0.0) as i32;
+                let scale = 10f64.powi(decimals);
+                (((price / tick).round() * tick) * scale).round() / scale
             }
-            OrderSide::Ask => {
-                let mut asks = self.asks.write();
-                asks.entry(Price(price))
-                    .or_insert_with(|| PriceLevel::new(price))
-                    .add_order(order);
+            None => price,
+        }
+    }
+
+    /// `round_to_tick` in decimal arithmetic, so the result is an exact
+    /// multiple of the tick (ties round away from zero, like `f64::round`).
+    pub fn round_to_tick_decimal(&self, price: Decimal) -> Decimal {
+        match self.get_tick_size().and_then(|tick| Price(tick).to_decimal()) {
+            Some(tick) if !tick.is_zero() => {
+                (price / tick).round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero) * tick
             }
+            _ => price,
         }
+    }
+
+    /// Formats `price` with the decimal places of the decimal prices this
+    /// book has been given, or two places if it has only seen `f64` prices.
+    pub fn format_price(&self, price: f64) -> String {
+        match *self.price_scale.read() {
+            Some(scale) => Price(price).format_scaled(scale),
+            None => Price(price).to_string(),
+        }
+    }
 
+    pub fn add_order(&self, side: OrderSide, price: f64, quantity: f64, timestamp: u64) -> u64 {
+        self.insert_order(side, self.round_to_tick(price), quantity, timestamp, None)
+    }
+
+    /// `add_order` for an order belonging to `owner`, so it can later be
+    /// cancelled with the rest of that owner's orders.
+    pub fn add_order_for_owner(&self, side: OrderSide, price: f64, quantity: f64, timestamp: u64, owner: &str) -> u64 {
+        self.insert_order(side, self.round_to_tick(price), quantity, timestamp, Some(owner))
+    }
+
+    /// `add_order` for a price given as a decimal. Tick rounding happens on
+    /// the decimal, and the f64 stored is the one nearest to the result.
+    pub fn add_order_decimal(&self, side: OrderSide, price: Decimal, quantity: f64, timestamp: u64) -> u64 {
+        let price = self.round_to_tick_decimal(price);
         {
-            let mut stats = self.stats.write();
-            stats.total_orders_created += 1;
-            self.update_stats_internal(&mut stats);
+            let mut scale = self.price_scale.write();
+            *scale = Some(scale.unwrap_or(0).max(price.scale()));
         }
+        self.insert_order(side, Price::from(price).as_f64(), quantity, timestamp, None)
+    }
 
+    /// `add_order_decimal` for a price string such as "0.015".
+    pub fn add_order_str(
+        &self,
+        side: OrderSide,
+        price: &str,
+        quantity: f64,
+        timestamp: u64,
+    ) -> Result<u64, rust_decimal::Error> {
+        let price = Decimal::from_str_exact(price.trim())?;
+        Ok(self.add_order_decimal(side, price, quantity, timestamp))
+    }
+
+    fn insert_order(&self, side: OrderSide, price: f64, quantity: f64, timestamp: u64, owner: Option<&str>) -> u64 {
+        let order_id = self.next_order_id.fetch_add(1, Ordering::Relaxed);
+        let mut order = Order::new(order_id, side, price, quantity, timestamp);
+        order.owner = owner.map(str::to_string);
+        self.rest_order(order);
         order_id
     }
 
+    /// Puts `order` on its side of the book at its price.
+    fn rest_order(&self, order: Order) {
+        let mut levels = self.side_levels(order.side);
+        self.rest_order_in(order, &mut levels);
+    }
+
+    /// `rest_order` for a caller already holding the side's write lock.
+    fn rest_order_in(&self, order: Order, levels: &mut BTreeMap<Price, PriceLevel>) {
+        let (side, price) = (order.side, order.price.as_f64());
+        levels.entry(Price(price))
+            .or_insert_with(|| PriceLevel::new(price))
+            .add_order(order);
+
+        // Stats are updated before the side lock is released so a concurrent
+        // mutation cannot overwrite them with an older best price
+        let mut stats = self.stats.write();
+        stats.total_orders_created += 1;
+        stats.update_best(side, best_price(side, levels));
+    }
+
+    /// Adds a maker-only limit order (Binance's GTX). If it would cross the
+    /// opposite best price it is rejected instead of resting or trading.
+    /// Both sides stay locked from the check to the insert, so no order can
+    /// slip in between and leave the two crossed.
+    pub fn add_post_only_order(
+        &self,
+        side: OrderSide,
+        price: f64,
+        quantity: f64,
+        timestamp: u64,
+    ) -> Result<u64, PostOnlyRejected> {
+        let price = self.round_to_tick(price);
+        // Bids before asks, the order every two-sided lock takes
+        let mut bids = self.bids.write();
+        let mut asks = self.asks.write();
+        let (levels, opposite_best) = match side {
+            OrderSide::Bid => (&mut *bids, best_price(OrderSide::Ask, &asks).filter(|ask| price >= *ask)),
+            OrderSide::Ask => (&mut *asks, best_price(OrderSide::Bid, &bids).filter(|bid| price <= *bid)),
+        };
+        if let Some(opposite_best) = opposite_best {
+            return Err(PostOnlyRejected { side, price, opposite_best });
+        }
+        let order_id = self.next_order_id.fetch_add(1, Ordering::Relaxed);
+        self.rest_order_in(Order::new(order_id, side, price, quantity, timestamp), levels);
+        Ok(order_id)
+    }
+
+    /// Adds a limit order, first filling it against the opposite side up to
+    /// its price, and returns its id with those fills. Only the new order
+    /// trades: orders already crossed on the book are left for `match_orders`.
+    /// Whatever is not filled rests under the returned id. `timestamp` is
+    /// when the order arrived, in milliseconds, and its fills carry it.
+    pub fn add_limit_order(&self, side: OrderSide, price: f64, quantity: f64, timestamp: u64) -> (u64, Vec<Trade>) {
+        let _lock = self.matching_lock.lock();
+        let order_id = self.next_order_id.fetch_add(1, Ordering::Relaxed);
+        let order = Order::new(order_id, side, self.round_to_tick(price), quantity, timestamp);
+
+        let (mut trades, remaining_quantity) = self.match_incoming(&order, Some(order.price.as_f64()));
+        self.apply_fees(&mut trades);
+        self.record_trades(&trades);
+        if remaining_quantity > 0.0 {
+            self.rest_order(Order { quantity: remaining_quantity, ..order });
+        } else {
+            self.stats.write().total_orders_created += 1;
+        }
+
+        if !trades.is_empty() {
+            let (best_bid, best_ask) = (self.get_best_bid(), self.get_best_ask());
+            let mut stats = self.stats.write();
+            stats.total_orders_matched += trades.len() as u64;
+            stats.total_volume_traded += trades.iter().map(|t| t.price * t.quantity).sum::<f64>();
+            stats.last_match_time = Some(timestamp);
+            stats.update_market_data(best_bid, best_ask);
+        }
+        (order_id, trades)
+    }
+
+    /// Parks a take-profit until `trigger_take_profits` sees a last price
+    /// that reaches `trigger_price`. It does not rest on the book meanwhile.
+    pub fn add_take_profit_order(&self, side: OrderSide, trigger_price: f64, quantity: f64, timestamp: u64) -> u64 {
+        let id = self.next_order_id.fetch_add(1, Ordering::Relaxed);
+        self.take_profits.write().push(TakeProfitOrder {
+            id,
+            side,
+            trigger_price: self.round_to_tick(trigger_price),
+            quantity,
+            timestamp,
+        });
+        id
+    }
+
+    pub fn cancel_take_profit(&self, id: u64) -> Option<TakeProfitOrder> {
+        let mut take_profits = self.take_profits.write();
+        let index = take_profits.iter().position(|order| order.id == id)?;
+        Some(take_profits.remove(index))
+    }
+
+    /// Parked take-profits, oldest first.
+    pub fn take_profit_orders(&self) -> Vec<TakeProfitOrder> {
+        self.take_profits.read().clone()
+    }
+
+    /// Fires every take-profit `last_price` has reached, each as a market
+    /// order placed now, in the order they were parked, and returns the
+    /// resulting trades.
+    pub fn trigger_take_profits(&self, last_price: f64) -> Vec<Trade> {
+        let triggered: Vec<TakeProfitOrder> = {
+            let mut take_profits = self.take_profits.write();
+            let (triggered, parked) = take_profits.drain(..).partition(|order| order.is_triggered_by(last_price));
+            *take_profits = parked;
+            triggered
+        };
+
+        triggered
+            .into_iter()
+            .flat_map(|order| self.add_market_order(order.side, order.quantity, now_millis()))
+            .collect()
+    }
+
+    /// Fills `quantity` against the opposite side at any price; the fills
+    /// carry `timestamp`, in milliseconds, as their execution time.
     pub fn add_market_order(&self, side: OrderSide, quantity: f64, timestamp: u64) -> Vec<Trade> {
+        let _span = tracing::debug_span!("market_order", ?side, quantity).entered();
         let _lock = self.matching_lock.lock();
         
         let order_id = self.next_order_id.fetch_add(1, Ordering::Relaxed);
         let order = Order::new(order_id, side.clone(), 0.0, quantity, timestamp);
         
-        let trades = match side {
-            OrderSide::Bid => {
-                self.match_market_order(order, true)
-            }
-            OrderSide::Ask => {
-                self.match_market_order(order, false)
-            }
-        };
+        let (mut trades, _) = self.match_incoming(&order, None);
+        self.apply_fees(&mut trades);
+        self.record_trades(&trades);
         
         if !trades.is_empty() {
+            let (best_bid, best_ask) = (self.get_best_bid(), self.get_best_ask());
             let mut stats = self.stats.write();
             stats.total_orders_created += 1;
             stats.total_orders_matched += trades.len() as u64;
             stats.total_volume_traded += trades.iter().map(|t| t.price * t.quantity).sum::<f64>();
             stats.last_match_time = Some(timestamp);
-            self.update_stats_internal(&mut stats);
+            stats.update_market_data(best_bid, best_ask);
         }
+        tracing::debug!(trades = trades.len(), "market order swept the book");
         
         trades
     }
 
-    fn match_market_order(&self, order: Order, is_buy: bool) -> Vec<Trade> {
+    /// Fills `order` against the opposite side, best price first, while the
+    /// price is within `limit`; a market order has none. The trades carry the
+    /// incoming order's timestamp as their execution time. Returns them and
+    /// the quantity left unfilled.
+    fn match_incoming(&self, order: &Order, limit: Option<f64>) -> (Vec<Trade>, f64) {
         let mut trades = Vec::new();
         let mut remaining_quantity = order.quantity;
-        
-        if is_buy {
-            loop {
-                let ask_price = {
-                    let asks = self.asks.read();
-                    asks.keys().next().cloned()
-                };
-                
-                if let Some(ask_price) = ask_price {
-                    if remaining_quantity <= 0.0 {
-                        break;
-                    }
-                    
-                    let mut asks = self.asks.write();
-                    if let Some(ask_level) = asks.get_mut(&ask_price) {
-                        if let Some(ask_order) = ask_level.get_first_order() {
-                            let trade_quantity = remaining_quantity.min(ask_order.quantity);
-                            let trade_price = ask_order.price.as_f64();
-                            
-                            trades.push(Trade {
-                                bid_order_id: order.id,
-                                ask_order_id: ask_order.id,
-                                price: trade_price,
-                                quantity: trade_quantity,
-                                timestamp: std::cmp::min(order.timestamp, ask_order.timestamp),
-                            });
-                            
-                            remaining_quantity -= trade_quantity;
-                            
-                            if ask_order.quantity <= trade_quantity {
-                                ask_level.remove_first_order();
-                            } else {
-                                ask_level.update_order(ask_order.id, ask_order.quantity - trade_quantity);
-                            }
-                            
-                            if ask_level.is_empty() {
-                                asks.remove(&ask_price);
-                            }
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                } else {
-                    break;
-                }
+        let price_rule = self.price_rule();
+        let maker_side = order.side.opposite();
+        let mut levels = self.side_levels(maker_side);
+
+        while remaining_quantity > 0.0 {
+            let Some(maker_price) = best_price(maker_side, &levels) else {
+                break;
+            };
+            let crosses = match (order.side, limit) {
+                (_, None) => true,
+                (OrderSide::Bid, Some(limit)) => maker_price <= limit,
+                (OrderSide::Ask, Some(limit)) => maker_price >= limit,
+            };
+            if !crosses {
+                break;
             }
-        } else {
-            loop {
-                let bid_price = {
-                    let bids = self.bids.read();
-                    bids.keys().next_back().cloned()
-                };
-                
-                if let Some(bid_price) = bid_price {
-                    if remaining_quantity <= 0.0 {
-                        break;
-                    }
-                    
-                    let mut bids = self.bids.write();
-                    if let Some(bid_level) = bids.get_mut(&bid_price) {
-                        if let Some(bid_order) = bid_level.get_first_order() {
-                            let trade_quantity = remaining_quantity.min(bid_order.quantity);
-                            let trade_price = bid_order.price.as_f64();
-                            
-                            trades.push(Trade {
-                                bid_order_id: bid_order.id,
-                                ask_order_id: order.id,
-                                price: trade_price,
-                                quantity: trade_quantity,
-                                timestamp: std::cmp::min(order.timestamp, bid_order.timestamp),
-                            });
-                            
-                            remaining_quantity -= trade_quantity;
-                            
-                            if bid_order.quantity <= trade_quantity {
-                                bid_level.remove_first_order();
-                            } else {
-                                bid_level.update_order(bid_order.id, bid_order.quantity - trade_quantity);
-                            }
-                            
-                            if bid_level.is_empty() {
-                                bids.remove(&bid_price);
-                            }
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                } else {
-                    break;
-                }
+            let Some(level) = levels.get_mut(&Price(maker_price)) else {
+                break;
+            };
+            let Some(maker) = level.get_first_order() else {
+                levels.remove(&Price(maker_price));
+                continue;
+            };
+
+            let trade_quantity = remaining_quantity.min(maker.quantity);
+            let (bid_order_id, ask_order_id) = match order.side {
+                OrderSide::Bid => (order.id, maker.id),
+                OrderSide::Ask => (maker.id, order.id),
+            };
+            trades.push(Trade {
+                bid_order_id,
+                ask_order_id,
+                price: price_rule.trade_price(maker.price.as_f64(), limit),
+                quantity: trade_quantity,
+                timestamp: order.timestamp,
+                aggressor_side: order.side,
+                maker_fee: 0.0,
+                taker_fee: 0.0,
+            });
+            remaining_quantity -= trade_quantity;
+
+            if maker.quantity <= trade_quantity {
+                level.remove_first_order();
+            } else {
+                level.update_order(maker.id, maker.quantity - trade_quantity);
+            }
+            if level.is_empty() {
+                levels.remove(&Price(maker_price));
             }
         }
-        
-        trades
+
+        (trades, remaining_quantity)
     }
 
     pub fn remove_order(&self, order_id: u64) -> Option<Order> {
@@ -466,34 +1033,65 @@ impl OrderBook {
             if let Some(price) = bid_price_to_remove {
                 bids.remove(&price);
             }
+
+            if removed_order.is_some() {
+                let mut stats = self.stats.write();
+                stats.total_orders_cancelled += 1;
+                stats.update_best(OrderSide::Bid, best_price(OrderSide::Bid, &bids));
+                return removed_order;
+            }
         }
 
-        if removed_order.is_none() {
-            let mut asks = self.asks.write();
-            for (price, price_level) in asks.iter_mut() {
-                if let Some(order) = price_level.remove_order(order_id) {
-                    removed_order = Some(order);
-                    if price_level.is_empty() {
-                        ask_price_to_remove = Some(price.clone());
-                    }
-                    break;
+        let mut asks = self.asks.write();
+        for (price, price_level) in asks.iter_mut() {
+            if let Some(order) = price_level.remove_order(order_id) {
+                removed_order = Some(order);
+                if price_level.is_empty() {
+                    ask_price_to_remove = Some(price.clone());
                 }
+                break;
             }
-            
-            if let Some(price) = ask_price_to_remove {
-                asks.remove(&price);
-            }
+        }
+
+        if let Some(price) = ask_price_to_remove {
+            asks.remove(&price);
         }
 
         if removed_order.is_some() {
             let mut stats = self.stats.write();
             stats.total_orders_cancelled += 1;
-            self.update_stats_internal(&mut stats);
+            stats.update_best(OrderSide::Ask, best_price(OrderSide::Ask, &asks));
         }
 
         removed_order
     }
 
+    /// Removes every resting order belonging to `owner` from both sides,
+    /// e.g. when that account disconnects, and returns them bids first.
+    pub fn cancel_all_by_owner(&self, owner: &str) -> Vec<Order> {
+        let mut cancelled = Vec::new();
+        for side in [OrderSide::Bid, OrderSide::Ask] {
+            let mut levels = self.side_levels(side);
+            let before = cancelled.len();
+            for price_level in levels.values() {
+                for order in price_level.get_orders_fifo() {
+                    if order.owner.as_deref() == Some(owner) {
+                        cancelled.extend(price_level.remove_order(order.id));
+                    }
+                }
+            }
+            if cancelled.len() == before {
+                continue;
+            }
+            levels.retain(|_, price_level| !price_level.is_empty());
+
+            let mut stats = self.stats.write();
+            stats.total_orders_cancelled += (cancelled.len() - before) as u64;
+            stats.update_best(side, best_price(side, &levels));
+        }
+        cancelled
+    }
+
     pub fn update_order(&self, order_id: u64, new_quantity: f64) -> bool {
         let mut updated = false;
 
@@ -517,12 +1115,56 @@ impl OrderBook {
             }
         }
 
-        if updated {
-            let mut stats = self.stats.write();
-            self.update_stats_internal(&mut stats);
+        // Only a quantity changed, so the best prices in stats still hold
+        updated
+    }
+
+    /// Replaces everything resting at `price` with a single aggregated order of
+    /// `quantity`, removing the level when `quantity` is zero. This mirrors L2
+    /// depth feeds, which publish absolute level sizes rather than orders.
+    pub fn set_level_quantity(&self, side: OrderSide, price: f64, quantity: f64, timestamp: u64) {
+        self.set_level_quantities(side, price, quantity, 0.0, timestamp);
+    }
+
+    /// `set_level_quantity` for feeds that also report a hidden reserve at
+    /// the level. The reserve sits behind the visible quantity, so a level
+    /// with none visible is removed along with its reserve.
+    pub fn set_level_quantities(&self, side: OrderSide, price: f64, visible: f64, hidden: f64, timestamp: u64) {
+        let mut levels = self.side_levels(side);
+
+        levels.remove(&Price(price));
+        if visible > 0.0 {
+            let order_id = self.next_order_id.fetch_add(1, Ordering::Relaxed);
+            let level = PriceLevel::new(price);
+            level.add_order(Order::new(order_id, side, price, visible, timestamp));
+            level.set_hidden_quantity(hidden);
+            levels.insert(Price(price), level);
         }
 
-        updated
+        self.stats.write().update_best(side, best_price(side, &levels));
+    }
+
+    /// Sets the hidden reserve behind what rests at `price`; false when no
+    /// level rests there.
+    pub fn set_hidden_quantity(&self, side: OrderSide, price: f64, hidden: f64) -> bool {
+        let levels = match side {
+            OrderSide::Bid => self.bids.read(),
+            OrderSide::Ask => self.asks.read(),
+        };
+        match levels.get(&Price(price)) {
+            Some(level) => {
+                level.set_hidden_quantity(hidden);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn side_levels(&self, side: OrderSide) -> RwLockWriteGuard<'_, BTreeMap<Price, PriceLevel>> {
+        match side {
+            OrderSide::Bid => self.bids.write(),
+            OrderSide::Ask => self.asks.write(),
+        }
     }
 
     pub fn get_best_bid(&self) -> Option<f64> {
@@ -545,13 +1187,21 @@ impl OrderBook {
         stats.mid_price
     }
 
+    /// Visible quantity of the best `levels` levels a side; see
+    /// `get_market_depth_with_hidden` for the hidden reserves too.
     pub fn get_market_depth(&self, levels: usize) -> (Vec<(f64, f64)>, Vec<(f64, f64)>) {
+        self.get_market_depth_with_hidden(levels, false)
+    }
+
+    /// The best `levels` levels a side, counting each level's hidden reserve
+    /// in its quantity when `include_hidden` is set.
+    pub fn get_market_depth_with_hidden(&self, levels: usize, include_hidden: bool) -> (DepthLevels, DepthLevels) {
         let bids: Vec<(f64, f64)> = {
             let bids = self.bids.read();
             bids.iter()
                 .rev()
                 .take(levels)
-                .map(|(price, level)| (price.as_f64(), level.get_total_quantity()))
+                .map(|(price, level)| (price.as_f64(), level.depth_quantity(include_hidden)))
                 .collect()
         };
 
@@ -559,27 +1209,146 @@ impl OrderBook {
             let asks = self.asks.read();
             asks.iter()
                 .take(levels)
-                .map(|(price, level)| (price.as_f64(), level.get_total_quantity()))
+                .map(|(price, level)| (price.as_f64(), level.depth_quantity(include_hidden)))
                 .collect()
         };
 
         (bids, asks)
     }
 
-    pub fn match_orders(&self) -> Vec<Trade> {
+    /// The top `levels` price levels of each side, for comparing with
+    /// `OrderBookSnapshot::diff`.
+    pub fn snapshot(&self, levels: usize) -> OrderBookSnapshot {
+        let (bids, asks) = self.get_market_depth(levels);
+        OrderBookSnapshot { bids, asks }
+    }
+
+    /// `get_market_depth` with each level's quantity replaced by the total from
+    /// the best price out to and including that level.
+    pub fn cumulative_depth(&self, levels: usize) -> (DepthLevels, DepthLevels) {
+        let accumulate = |levels: DepthLevels| -> DepthLevels {
+            let mut total = 0.0;
+            levels
+                .into_iter()
+                .map(|(price, quantity)| {
+                    total += quantity;
+                    (price, total)
+                })
+                .collect()
+        };
+        let (bids, asks) = self.get_market_depth(levels);
+        (accumulate(bids), accumulate(asks))
+    }
+
+    /// Levels on `side` from the best price outwards until their cumulative
+    /// price * quantity reaches `max_notional`. The last level is cut down to
+    /// the quantity that lands exactly on the cap.
+    pub fn depth_by_notional(&self, side: OrderSide, max_notional: f64) -> Vec<(f64, f64)> {
+        let book = match side {
+            OrderSide::Bid => self.bids.read(),
+            OrderSide::Ask => self.asks.read(),
+        };
+        let levels: Box<dyn Iterator<Item = (&Price, &PriceLevel)>> = match side {
+            OrderSide::Bid => Box::new(book.iter().rev()),
+            OrderSide::Ask => Box::new(book.iter()),
+        };
+
+        let mut depth = Vec::new();
+        let mut notional = 0.0;
+        for (price, level) in levels {
+            let remaining = max_notional - notional;
+            if remaining <= 0.0 {
+                break;
+            }
+            let price = price.as_f64();
+            let quantity = level.get_total_quantity();
+            if price * quantity >= remaining {
+                depth.push((price, remaining / price));
+                break;
+            }
+            notional += price * quantity;
+            depth.push((price, quantity));
+        }
+        depth
+    }
+
+    /// Quantity resting at exactly `price` on `side`, 0 for an empty level.
+    pub fn quantity_at_price(&self, side: OrderSide, price: f64) -> f64 {
+        let levels = match side {
+            OrderSide::Bid => self.bids.read(),
+            OrderSide::Ask => self.asks.read(),
+        };
+        levels.get(&Price(price)).map_or(0.0, |level| level.get_total_quantity())
+    }
+
+    /// How much an order on `side` could fill without trading past
+    /// `limit_price`: the opposite side's quantity at or better than it, i.e.
+    /// asks at or below the limit for a bid and bids at or above it for an ask.
+    pub fn liquidity_up_to(&self, side: OrderSide, limit_price: f64) -> f64 {
+        let limit = Price(limit_price);
+        match side {
+            OrderSide::Bid => self.asks.read().range(..=limit).map(|(_, level)| level.get_total_quantity()).sum(),
+            OrderSide::Ask => self.bids.read().range(limit..).map(|(_, level)| level.get_total_quantity()).sum(),
+        }
+    }
+
+    /// CRC32 of the top `levels` levels, OKX style: `price:quantity` pairs
+    /// taken alternately from the bid and ask side, best first, joined with
+    /// `:`, for comparing against a feed that publishes the same checksum.
+    /// Binance publishes none; its diff-depth book resyncs on gaps in update
+    /// ids instead (`BinanceWebSocketClient::apply_depth_update`).
+    pub fn checksum(&self, levels: usize) -> u32 {
+        let (bids, asks) = self.get_market_depth(levels);
+        let mut fields = Vec::with_capacity(bids.len() + asks.len());
+        for index in 0..bids.len().max(asks.len()) {
+            for (price, quantity) in [bids.get(index), asks.get(index)].into_iter().flatten() {
+                fields.push(format!("{}:{}", price, quantity));
+            }
+        }
+        crc32fast::hash(fields.join(":").as_bytes())
+    }
+
+    /// Like `get_market_depth`, but each level lists its resting orders as
+    /// `(id, quantity, timestamp)` in queue (FIFO) order instead of a total.
+    pub fn get_l3_depth(&self, levels: usize) -> (Vec<L3Level>, Vec<L3Level>) {
+        let breakdown = |price: &Price, level: &PriceLevel| {
+            let orders = level
+                .get_orders_fifo()
+                .into_iter()
+                .map(|order| (order.id, order.quantity, order.timestamp))
+                .collect();
+            (price.as_f64(), orders)
+        };
+
+        let bids: Vec<L3Level> = {
+            let bids = self.bids.read();
+            bids.iter().rev().take(levels).map(|(price, level)| breakdown(price, level)).collect()
+        };
+
+        let asks: Vec<L3Level> = {
+            let asks = self.asks.read();
+            asks.iter().take(levels).map(|(price, level)| breakdown(price, level)).collect()
+        };
+
+        (bids, asks)
+    }
+
+    /// Trades crossing orders, best prices first, until the book is uncrossed
+    /// or `max_match_iterations` trades have been made, in which case
+    /// `hit_iteration_cap` is set and calling again resumes.
+    pub fn match_orders(&self) -> MatchResult {
+        let _span = tracing::debug_span!("match_orders").entered();
         let _lock = self.matching_lock.lock();
+        let now = now_millis();
         
         let mut trades = Vec::new();
         let mut total_matched = 0;
         let mut iteration_count = 0;
-        const MAX_ITERATIONS: usize = 1000;
+        let max_iterations = self.max_match_iterations();
+        let mut hit_iteration_cap = false;
+        let price_rule = self.price_rule();
 
         loop {
-            iteration_count += 1;
-            if iteration_count > MAX_ITERATIONS {
-                break;
-            }
-
             let (best_bid, best_ask) = {
                 let best_bid = self.get_best_bid();
                 let best_ask = self.get_best_ask();
@@ -590,6 +1359,12 @@ impl OrderBook {
                 if bid < ask {
                     break;
                 }
+                // Still crossed, so stopping here leaves work for the next call
+                if iteration_count == max_iterations {
+                    hit_iteration_cap = true;
+                    break;
+                }
+                iteration_count += 1;
 
                 let bid_price = Price(bid);
                 let ask_price = Price(ask);
@@ -607,10 +1382,11 @@ impl OrderBook {
                 if let (Some(bid_level), Some(ask_level)) = (bid_level, ask_level) {
                     if let (Some(bid_order), Some(ask_order)) = (bid_level.get_first_order(), ask_level.get_first_order()) {
                         let trade_quantity = bid_order.quantity.min(ask_order.quantity);
+                        // The earlier order was resting, so it is the maker
                         let trade_price = if bid_order.timestamp <= ask_order.timestamp {
-                            bid
+                            price_rule.trade_price(bid, Some(ask))
                         } else {
-                            ask
+                            price_rule.trade_price(ask, Some(bid))
                         };
 
                         trades.push(Trade {
@@ -618,7 +1394,14 @@ impl OrderBook {
                             ask_order_id: ask_order.id,
                             price: trade_price,
                             quantity: trade_quantity,
-                            timestamp: std::cmp::min(bid_order.timestamp, ask_order.timestamp),
+                            timestamp: now,
+                            aggressor_side: if bid_order.timestamp <= ask_order.timestamp {
+                                OrderSide::Ask
+                            } else {
+                                OrderSide::Bid
+                            },
+                            maker_fee: 0.0,
+                            taker_fee: 0.0,
                         });
 
                         total_matched += 1;
@@ -654,22 +1437,54 @@ impl OrderBook {
             }
         }
 
+        self.apply_fees(&mut trades);
+        self.record_trades(&trades);
+
         if total_matched > 0 {
+            // Matching can empty levels on both sides, so both are recomputed
+            let (best_bid, best_ask) = (self.get_best_bid(), self.get_best_ask());
             let mut stats = self.stats.write();
             stats.total_orders_matched += total_matched;
             stats.total_volume_traded += trades.iter().map(|t| t.price * t.quantity).sum::<f64>();
-            stats.last_match_time = Some(std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64);
-            self.update_stats_internal(&mut stats);
+            stats.last_match_time = Some(now);
+            stats.update_market_data(best_bid, best_ask);
+            tracing::debug!(trades = total_matched, hit_iteration_cap, "matching sweep done");
         }
 
-        trades
+        MatchResult { trades, hit_iteration_cap }
+    }
+
+    /// `get_stats` plus order and level counts as a JSON object, for tools
+    /// that read the book rather than a terminal.
+    pub fn stats_json(&self) -> String {
+        #[derive(Serialize)]
+        struct StatsSnapshot {
+            #[serde(flatten)]
+            stats: OrderBookStats,
+            total_orders: usize,
+            bid_levels: usize,
+            ask_levels: usize,
+        }
+
+        let (bid_levels, ask_levels) = self.get_total_price_levels();
+        let snapshot = StatsSnapshot {
+            stats: self.get_stats(),
+            total_orders: self.get_total_orders(),
+            bid_levels,
+            ask_levels,
+        };
+        serde_json::to_string(&snapshot).expect("stats serialize to JSON")
     }
 
+    /// Counters and best prices as of the last mutation, with the depth
+    /// imbalance computed from the current book.
     pub fn get_stats(&self) -> OrderBookStats {
-        self.stats.read().clone()
+        let mut stats = self.stats.read().clone();
+        let (bids, asks) = self.get_market_depth(IMBALANCE_DEPTH_LEVELS);
+        let bid_quantity: f64 = bids.iter().map(|(_, qty)| qty).sum();
+        let ask_quantity: f64 = asks.iter().map(|(_, qty)| qty).sum();
+        stats.update_imbalance(bid_quantity, ask_quantity);
+        stats
     }
 
     pub fn get_total_orders(&self) -> usize {
@@ -682,6 +1497,19 @@ impl OrderBook {
         bid_count + ask_count
     }
 
+    /// `(bids, asks)`: each side's resting price times quantity, summed over
+    /// every order.
+    pub fn total_notional(&self) -> (f64, f64) {
+        let notional = |side: &RwLock<BTreeMap<Price, PriceLevel>>| -> f64 {
+            side.read()
+                .values()
+                .flat_map(|level| level.orders.get_all_orders())
+                .map(|order| order.price.as_f64() * order.quantity)
+                .sum()
+        };
+        (notional(&self.bids), notional(&self.asks))
+    }
+
     pub fn get_total_price_levels(&self) -> (usize, usize) {
         let bids = self.bids.read();
         let asks = self.asks.read();
@@ -689,17 +1517,12 @@ impl OrderBook {
         (bids.len(), asks.len())
     }
 
-    fn update_stats_internal(&self, stats: &mut OrderBookStats) {
-        let best_bid = self.get_best_bid();
-        let best_ask = self.get_best_ask();
-        stats.update_market_data(best_bid, best_ask);
-    }
-
     pub fn clear(&self) {
         let mut bids = self.bids.write();
         let mut asks = self.asks.write();
         bids.clear();
         asks.clear();
+        self.take_profits.write().clear();
         
         let mut stats = self.stats.write();
         *stats = OrderBookStats::new();
@@ -727,6 +1550,39 @@ impl OrderBook {
         None
     }
 
+    /// How many orders, and how much quantity, are ahead of `order_id` at its
+    /// price. `None` if the order isn't resting in the book.
+    pub fn queue_position(&self, order_id: u64) -> Option<QueuePosition> {
+        for (side, levels) in [(OrderSide::Bid, &self.bids), (OrderSide::Ask, &self.asks)] {
+            let levels = levels.read();
+            let Some(level) = levels.values().find(|level| level.orders.orders.contains_key(&order_id)) else {
+                continue;
+            };
+            let mut position = QueuePosition { side, price: level.price.as_f64(), orders_ahead: 0, quantity_ahead: 0.0 };
+            for order in level.get_orders_fifo() {
+                if order.id == order_id {
+                    return Some(position);
+                }
+                position.orders_ahead += 1;
+                position.quantity_ahead += order.quantity;
+            }
+        }
+        None
+    }
+
+    /// Returns the orders resting at `price` on `side`, oldest first.
+    pub fn get_level_orders(&self, side: OrderSide, price: f64) -> Vec<Order> {
+        let levels = match side {
+            OrderSide::Bid => self.bids.read(),
+            OrderSide::Ask => self.asks.read(),
+        };
+
+        levels
+            .get(&Price(price))
+            .map(|level| level.get_orders_fifo())
+            .unwrap_or_default()
+    }
+
     pub fn validate_consistency(&self) -> bool {
         let bids = self.bids.read();
         let asks = self.asks.read();
@@ -761,57 +1617,73 @@ impl OrderBook {
 
 impl fmt::Display for OrderBook {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "=== HIGH-PERFORMANCE LOCK-FREE ORDER BOOK ===")?;
-        
+        f.write_str(&self.format_book(DISPLAY_DEPTH_LEVELS))
+    }
+}
+
+impl OrderBook {
+    /// The `Display` summary with the best `depth` levels of each side: asks
+    /// from the best (lowest) price out, then bids from the best (highest),
+    /// with price, quantity and order count in aligned columns.
+    pub fn format_book(&self, depth: usize) -> String {
+        use fmt::Write;
+
+        let mut out = String::new();
         let stats = self.get_stats();
+        let _ = writeln!(out, "=== HIGH-PERFORMANCE LOCK-FREE ORDER BOOK ===");
         if let Some(spread) = stats.spread {
-            writeln!(f, "Spread: {:.4}", spread)?;
+            let _ = writeln!(out, "Spread: {:.4}", spread);
         }
         if let Some(mid_price) = stats.mid_price {
-            writeln!(f, "Mid Price: {:.4}", mid_price)?;
+            let _ = writeln!(out, "Mid Price: {:.4}", mid_price);
         }
-        
-        writeln!(f, "Total Orders: {}", self.get_total_orders())?;
+
+        let _ = writeln!(out, "Total Orders: {}", self.get_total_orders());
         let (bid_levels, ask_levels) = self.get_total_price_levels();
-        writeln!(f, "Price Levels - Bids: {}, Asks: {}", bid_levels, ask_levels)?;
-        
+        let _ = writeln!(out, "Price Levels - Bids: {}, Asks: {}", bid_levels, ask_levels);
+
         if let Some(last_match) = stats.last_match_time {
-            writeln!(f, "Last Match: {}", last_match)?;
+            let _ = writeln!(out, "Last Match: {}", last_match);
         }
-        
-        writeln!(f, "----------------")?;
-        
-        {
-            let asks = self.asks.read();
-            let ask_data: Vec<_> = asks.iter().rev().take(10)
-                .map(|(price, level)| (price.as_f64(), level.get_total_quantity(), level.len()))
-                .collect();
-            
-            for (price, quantity, order_count) in ask_data {
-                writeln!(f, "ASK: {:.4} | {:.4} | {} orders", price, quantity, order_count)?;
-            }
+        if let (Some(last), Some(high), Some(low)) = (stats.last_trade_price, stats.session_high, stats.session_low) {
+            let _ = writeln!(out, "Last Trade: {:.4} (High: {:.4}, Low: {:.4})", last, high, low);
         }
-        
-        writeln!(f, "----------------")?;
-        
-        {
-            let bids = self.bids.read();
-            let bid_data: Vec<_> = bids.iter().rev().take(10)
-                .map(|(price, level)| (price.as_f64(), level.get_total_quantity(), level.len()))
-                .collect();
-            
-            for (price, quantity, order_count) in bid_data {
-                writeln!(f, "BID: {:.4} | {:.4} | {} orders", price, quantity, order_count)?;
+
+        let row = |(price, level): (&Price, &PriceLevel)| {
+            (format!("{:.4}", price.as_f64()), format!("{:.4}", level.get_total_quantity()), level.len().to_string())
+        };
+        let asks: Vec<_> = self.asks.read().iter().take(depth).map(row).collect();
+        let bids: Vec<_> = self.bids.read().iter().rev().take(depth).map(row).collect();
+        let width = |column: fn(&(String, String, String)) -> &String| {
+            asks.iter().chain(&bids).map(|row| column(row).len()).max().unwrap_or(0)
+        };
+        let (price_width, quantity_width, count_width) = (width(|row| &row.0), width(|row| &row.1), width(|row| &row.2));
+
+        for (label, rows) in [("ASK", &asks), ("BID", &bids)] {
+            let _ = writeln!(out, "----------------");
+            for (price, quantity, count) in rows {
+                let _ = writeln!(
+                    out,
+                    "{}: {:>pw$} | {:>qw$} | {:>cw$} orders",
+                    label,
+                    price,
+                    quantity,
+                    count,
+                    pw = price_width,
+                    qw = quantity_width,
+                    cw = count_width
+                );
             }
         }
-        
-        writeln!(f, "----------------")?;
-        writeln!(f, "Stats: Created: {}, Matched: {}, Cancelled: {}", 
-            stats.total_orders_created, stats.total_orders_matched, stats.total_orders_cancelled)?;
-        
-        writeln!(f, "Consistency: {}", if self.validate_consistency() { "✅" } else { "❌" })?;
-        
-        Ok(())
+
+        let _ = writeln!(out, "----------------");
+        let _ = writeln!(
+            out,
+            "Stats: Created: {}, Matched: {}, Cancelled: {}",
+            stats.total_orders_created, stats.total_orders_matched, stats.total_orders_cancelled
+        );
+        let _ = writeln!(out, "Consistency: {}", if self.validate_consistency() { "✅" } else { "❌" });
+        out
     }
 }
 
@@ -1,5 +1,15 @@
-use serde::{Deserialize, Serialize};
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use chrono::Utc;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::http_retry::{self, RetryConfig};
+use crate::order::OrderSide;
+use crate::order_book::OrderBook;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum PolymarketOrderSide {
@@ -7,7 +17,7 @@ pub enum PolymarketOrderSide {
     SELL = 1,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum PolymarketOrderType {
     FOK,
     GTC,
@@ -21,7 +31,10 @@ pub enum PolymarketSignatureType {
     EOA_DIRECT = 3,
 }
 
+/// A signed order in the CLOB's wire format: camelCase keys, with `side` sent
+/// as "BUY"/"SELL" rather than the 0/1 used when signing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PolymarketOrder {
     pub salt: u64,
     pub maker: String,
@@ -33,12 +46,36 @@ pub struct PolymarketOrder {
     pub expiration: String,
     pub nonce: String,
     pub fee_rate_bps: String,
+    #[serde(serialize_with = "serialize_side", deserialize_with = "deserialize_side")]
     pub side: u8,
     pub signature_type: u8,
     pub signature: String,
 }
 
+impl PolymarketOrder {
+    pub fn to_clob_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+fn serialize_side<S: Serializer>(side: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+    if *side == PolymarketOrderSide::BUY as u8 {
+        serializer.serialize_str("BUY")
+    } else {
+        serializer.serialize_str("SELL")
+    }
+}
+
+fn deserialize_side<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+        "BUY" => Ok(PolymarketOrderSide::BUY as u8),
+        "SELL" => Ok(PolymarketOrderSide::SELL as u8),
+        other => Err(serde::de::Error::unknown_variant(other, &["BUY", "SELL"])),
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PolymarketOrderRequest {
     pub order: PolymarketOrder,
     pub owner: String,
@@ -46,13 +83,554 @@ pub struct PolymarketOrderRequest {
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PolymarketOrderResponse {
     pub success: bool,
     pub error_msg: Option<String>,
+    #[serde(rename = "orderID", alias = "orderId")]
     pub order_id: Option<String>,
+    #[serde(alias = "transactionsHashes")]
     pub order_hashes: Option<Vec<String>>,
+    pub status: Option<PolymarketOrderStatus>,
 }
 
+/// Where an order stands: the statuses `POST /order` returns, plus
+/// `cancelled`, which only the user channel reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolymarketOrderStatus {
+    Live,
+    Matched,
+    Delayed,
+    Unmatched,
+    Cancelled,
+    Unknown(String),
+}
+
+impl PolymarketOrderStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            PolymarketOrderStatus::Live => "live",
+            PolymarketOrderStatus::Matched => "matched",
+            PolymarketOrderStatus::Delayed => "delayed",
+            PolymarketOrderStatus::Unmatched => "unmatched",
+            PolymarketOrderStatus::Cancelled => "cancelled",
+            PolymarketOrderStatus::Unknown(status) => status,
+        }
+    }
+
+    /// Status text used for order history rows.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PolymarketOrderStatus::Live => "Live",
+            PolymarketOrderStatus::Matched => "Matched",
+            PolymarketOrderStatus::Delayed => "Delayed",
+            PolymarketOrderStatus::Unmatched => "Unmatched",
+            PolymarketOrderStatus::Cancelled => "Cancelled",
+            PolymarketOrderStatus::Unknown(_) => "Unknown",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            PolymarketOrderStatus::Live => "Order placed and resting on the book",
+            PolymarketOrderStatus::Matched => "Order placed and matched with existing resting order",
+            PolymarketOrderStatus::Delayed => "Order marketable, but subject to matching delay",
+            PolymarketOrderStatus::Unmatched => "Order marketable, but failure delaying, placement successful",
+            PolymarketOrderStatus::Cancelled => "Order cancelled and removed from the book",
+            PolymarketOrderStatus::Unknown(_) => "Unknown status",
+        }
+    }
+}
+
+impl FromStr for PolymarketOrderStatus {
+    type Err = Infallible;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        Ok(match status {
+            "live" => PolymarketOrderStatus::Live,
+            "matched" => PolymarketOrderStatus::Matched,
+            "delayed" => PolymarketOrderStatus::Delayed,
+            "unmatched" => PolymarketOrderStatus::Unmatched,
+            "cancelled" => PolymarketOrderStatus::Cancelled,
+            other => PolymarketOrderStatus::Unknown(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for PolymarketOrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+impl Serialize for PolymarketOrderStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PolymarketOrderStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let status = String::deserialize(deserializer)?;
+        Ok(status.parse().unwrap_or_else(|never| match never {}))
+    }
+}
+
+impl PolymarketOrderResponse {
+    /// A 200 response can still carry a rejection in `errorMsg`.
+    fn into_result(self) -> Result<Self, PolymarketError> {
+        match self.error_msg.as_deref() {
+            Some(msg) if !self.success || !msg.is_empty() => Err(PolymarketError::from_error_msg(msg)),
+            None if !self.success => Err(PolymarketError::Unknown("order not accepted".to_string())),
+            _ => Ok(self),
+        }
+    }
+}
+
+/// Most orders `POST /orders` accepts in one request.
+pub const MAX_BATCH_ORDERS: usize = 15;
+
+/// Order amounts are integers in millionths of a share or of a USDC.
+const AMOUNT_SCALE: u128 = 1_000_000;
+
+/// 2^256 - 1, the largest ERC-1155 position id.
+const MAX_TOKEN_ID: &str = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+/// Checks that `token_id` looks like a Polymarket outcome token: a uint256
+/// written in decimal, such as the 77-digit ids the CLOB lists. Binance-style
+/// symbols like "BTCUSDT" are rejected.
+pub fn validate_token_id(token_id: &str) -> Result<(), String> {
+    if token_id.is_empty() {
+        return Err("Enter a Polymarket token id first".to_string());
+    }
+    if !token_id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Invalid token id '{}': Polymarket token ids are decimal numbers", token_id));
+    }
+    if token_id.len() > 1 && token_id.starts_with('0') {
+        return Err(format!("Invalid token id '{}': leading zero", token_id));
+    }
+    // Same-length decimal strings compare like the numbers they spell
+    if token_id.len() > MAX_TOKEN_ID.len() || (token_id.len() == MAX_TOKEN_ID.len() && token_id > MAX_TOKEN_ID) {
+        return Err(format!("Invalid token id '{}': larger than a uint256", token_id));
+    }
+    Ok(())
+}
+
+/// Snaps `price` to the nearest multiple of `tick_size`, e.g. 0.01 or 0.001,
+/// without leaving float noise past the tick's decimals.
+pub fn round_price_to_tick(price: f64, tick_size: f64) -> f64 {
+    let ticks = (price / tick_size).round();
+    let decimals = (-tick_size.log10()).ceil().max(0.0) as i32;
+    let scale = 10f64.powi(decimals);
+    (ticks * tick_size * scale).round() / scale
+}
+
+/// `value` in millionths, rounded to the nearest unit.
+fn to_micro_units(value: f64) -> u128 {
+    (value * AMOUNT_SCALE as f64).round() as u128
+}
+
+/// `(maker_amount, taker_amount)` for an order. Price and size are converted
+/// to micro-units first so the notional is an exact integer product, divided
+/// back down rounding half up; a BUY pays the notional for the shares, a SELL
+/// the reverse.
+fn order_amounts(side: PolymarketOrderSide, price: f64, size: f64) -> (u64, u64) {
+    let price_units = to_micro_units(price);
+    let size_units = to_micro_units(size);
+    let notional_units = (price_units * size_units + AMOUNT_SCALE / 2) / AMOUNT_SCALE;
+    match side {
+        PolymarketOrderSide::BUY => (notional_units as u64, size_units as u64),
+        PolymarketOrderSide::SELL => (size_units as u64, notional_units as u64),
+    }
+}
+
+/// Per-token trading rules from the market metadata.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketParams {
+    pub tick_size: f64,
+    pub min_size: f64,
+    pub max_size: Option<f64>,
+}
+
+impl Default for MarketParams {
+    fn default() -> Self {
+        Self {
+            tick_size: 0.01,
+            min_size: 5.0,
+            max_size: None,
+        }
+    }
+}
+
+impl MarketParams {
+    pub fn round_price(&self, price: f64) -> f64 {
+        round_price_to_tick(price, self.tick_size)
+    }
+
+    pub fn is_on_tick(&self, price: f64) -> bool {
+        let ticks = price / self.tick_size;
+        (ticks - ticks.round()).abs() <= 1e-6
+    }
+
+    /// Decimals needed to show a price on this tick, kept within 2–4.
+    pub fn price_decimals(&self) -> usize {
+        ((-self.tick_size.log10()).ceil() as i64).clamp(2, 4) as usize
+    }
+}
+
+/// Rejections from the CLOB, with the documented `errorMsg` codes split out.
+#[derive(Debug)]
+pub enum PolymarketError {
+    InvalidOrderMinTickSize,
+    InvalidOrderMinSize,
+    InvalidOrderDuplicated,
+    InvalidOrderNotEnoughBalance,
+    InvalidOrderExpiration,
+    InvalidOrderError,
+    ExecutionError,
+    OrderDelayed,
+    DelayingOrderError,
+    FokOrderNotFilled,
+    MarketNotReady,
+    /// Any other `errorMsg` the CLOB returned.
+    Unknown(String),
+    /// The L2 headers were rejected (HTTP 401) or could not be built.
+    Unauthorized(String),
+    MissingCredentials,
+    /// Caught by `preflight_order` before the order was signed.
+    BalanceShortfall(BalanceShortfall),
+    Http(reqwest::Error),
+    Status { status: u16, body: String },
+    Parse(String),
+}
+
+impl PolymarketError {
+    /// Maps an `errorMsg` such as `INVALID_ORDER_MIN_SIZE` (optionally followed
+    /// by details) to its variant.
+    pub fn from_error_msg(msg: &str) -> Self {
+        let code = msg.split(|c: char| c == ':' || c.is_whitespace()).next().unwrap_or_default();
+        match code {
+            "INVALID_ORDER_MIN_TICK_SIZE" => PolymarketError::InvalidOrderMinTickSize,
+            "INVALID_ORDER_MIN_SIZE" => PolymarketError::InvalidOrderMinSize,
+            "INVALID_ORDER_DUPLICATED" => PolymarketError::InvalidOrderDuplicated,
+            "INVALID_ORDER_NOT_ENOUGH_BALANCE" => PolymarketError::InvalidOrderNotEnoughBalance,
+            "INVALID_ORDER_EXPIRATION" => PolymarketError::InvalidOrderExpiration,
+            "INVALID_ORDER_ERROR" => PolymarketError::InvalidOrderError,
+            "EXECUTION_ERROR" => PolymarketError::ExecutionError,
+            "ORDER_DELAYED" => PolymarketError::OrderDelayed,
+            "DELAYING_ORDER_ERROR" => PolymarketError::DelayingOrderError,
+            "FOK_ORDER_NOT_FILLED_ERROR" => PolymarketError::FokOrderNotFilled,
+            "MARKET_NOT_READY" => PolymarketError::MarketNotReady,
+            _ => PolymarketError::Unknown(msg.to_string()),
+        }
+    }
+
+    /// What a documented error code means; `None` for anything else.
+    pub fn description(&self) -> Option<&'static str> {
+        Some(match self {
+            PolymarketError::InvalidOrderMinTickSize => "Order price breaks minimum tick size rules",
+            PolymarketError::InvalidOrderMinSize => "Order size lower than minimum threshold",
+            PolymarketError::InvalidOrderDuplicated => "Same order already placed, cannot place again",
+            PolymarketError::InvalidOrderNotEnoughBalance => "Insufficient balance or allowance",
+            PolymarketError::InvalidOrderExpiration => "Invalid expiration time",
+            PolymarketError::InvalidOrderError => "System error while inserting order",
+            PolymarketError::ExecutionError => "System error while attempting to execute trade",
+            PolymarketError::OrderDelayed => "Order placement delayed due to market conditions",
+            PolymarketError::DelayingOrderError => "Error delaying the order",
+            PolymarketError::FokOrderNotFilled => "FOK order not fully filled, cannot be placed",
+            PolymarketError::MarketNotReady => "Market not ready to process new orders",
+            _ => return None,
+        })
+    }
+
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            PolymarketError::InvalidOrderMinTickSize => Some("INVALID_ORDER_MIN_TICK_SIZE"),
+            PolymarketError::InvalidOrderMinSize => Some("INVALID_ORDER_MIN_SIZE"),
+            PolymarketError::InvalidOrderDuplicated => Some("INVALID_ORDER_DUPLICATED"),
+            PolymarketError::InvalidOrderNotEnoughBalance => Some("INVALID_ORDER_NOT_ENOUGH_BALANCE"),
+            PolymarketError::InvalidOrderExpiration => Some("INVALID_ORDER_EXPIRATION"),
+            PolymarketError::InvalidOrderError => Some("INVALID_ORDER_ERROR"),
+            PolymarketError::ExecutionError => Some("EXECUTION_ERROR"),
+            PolymarketError::OrderDelayed => Some("ORDER_DELAYED"),
+            PolymarketError::DelayingOrderError => Some("DELAYING_ORDER_ERROR"),
+            PolymarketError::FokOrderNotFilled => Some("FOK_ORDER_NOT_FILLED_ERROR"),
+            PolymarketError::MarketNotReady => Some("MARKET_NOT_READY"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PolymarketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolymarketError::Unknown(msg) => write!(f, "CLOB error: {}", msg),
+            PolymarketError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            PolymarketError::MissingCredentials => write!(f, "API credentials not set"),
+            PolymarketError::BalanceShortfall(shortfall) => write!(f, "{}", shortfall),
+            PolymarketError::Http(e) => write!(f, "HTTP error: {}", e),
+            PolymarketError::Status { status, body } => write!(f, "HTTP {}: {}", status, body),
+            PolymarketError::Parse(msg) => write!(f, "Failed to parse response: {}", msg),
+            coded => write!(
+                f,
+                "{} ({})",
+                coded.code().unwrap_or_default(),
+                coded.description().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+impl FromStr for PolymarketError {
+    type Err = Infallible;
+
+    fn from_str(msg: &str) -> Result<Self, Self::Err> {
+        Ok(PolymarketError::from_error_msg(msg))
+    }
+}
+
+/// Serialized as the CLOB code where there is one, else as the message.
+impl Serialize for PolymarketError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (self.code(), self) {
+            (Some(code), _) => serializer.serialize_str(code),
+            (None, PolymarketError::Unknown(msg)) => serializer.serialize_str(msg),
+            (None, other) => serializer.serialize_str(&other.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PolymarketError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let msg = String::deserialize(deserializer)?;
+        Ok(PolymarketError::from_error_msg(&msg))
+    }
+}
+
+impl std::error::Error for PolymarketError {}
+
+impl PolymarketError {
+    /// A copy for each order of a batch that failed as a whole; transport
+    /// errors are kept as their message.
+    fn for_each_order(&self) -> Self {
+        match self {
+            PolymarketError::Unknown(msg) => PolymarketError::Unknown(msg.clone()),
+            PolymarketError::Unauthorized(msg) => PolymarketError::Unauthorized(msg.clone()),
+            PolymarketError::MissingCredentials => PolymarketError::MissingCredentials,
+            PolymarketError::BalanceShortfall(shortfall) => PolymarketError::BalanceShortfall(*shortfall),
+            PolymarketError::Http(e) => PolymarketError::Unknown(e.to_string()),
+            PolymarketError::Status { status, body } => PolymarketError::Status { status: *status, body: body.clone() },
+            PolymarketError::Parse(msg) => PolymarketError::Parse(msg.clone()),
+            coded => PolymarketError::from_error_msg(coded.code().unwrap_or_default()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for PolymarketError {
+    fn from(error: reqwest::Error) -> Self {
+        PolymarketError::Http(error)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct BookLevel {
+    pub price: String,
+    pub size: String,
+}
+
+/// `GET /book` response: prices are probabilities in 0–1, sizes are shares.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BookSnapshot {
+    #[serde(default)]
+    pub market: String,
+    pub asset_id: String,
+    pub timestamp: Option<String>,
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub bids: Vec<BookLevel>,
+    #[serde(default)]
+    pub asks: Vec<BookLevel>,
+    pub tick_size: Option<String>,
+    pub min_order_size: Option<String>,
+}
+
+impl BookSnapshot {
+    /// Bid levels as `(price, size)`, skipping entries that do not parse.
+    pub fn bid_levels(&self) -> Vec<(f64, f64)> {
+        parse_levels(&self.bids)
+    }
+
+    pub fn ask_levels(&self) -> Vec<(f64, f64)> {
+        parse_levels(&self.asks)
+    }
+
+    /// Tick and minimum size reported with the book, defaulting when absent.
+    pub fn market_params(&self) -> MarketParams {
+        let defaults = MarketParams::default();
+        let parse = |value: &Option<String>| value.as_deref().and_then(|v| v.parse::<f64>().ok());
+        MarketParams {
+            tick_size: parse(&self.tick_size).filter(|t| *t > 0.0).unwrap_or(defaults.tick_size),
+            min_size: parse(&self.min_order_size).unwrap_or(defaults.min_size),
+            max_size: defaults.max_size,
+        }
+    }
+
+    /// Replaces the contents of `order_book` with this snapshot, one
+    /// aggregated order per level.
+    pub fn apply_to(&self, order_book: &OrderBook, timestamp: u64) {
+        order_book.clear();
+        for (levels, side) in [(self.bid_levels(), OrderSide::Bid), (self.ask_levels(), OrderSide::Ask)] {
+            for (price, size) in levels {
+                order_book.set_level_quantity(side, price, size, timestamp);
+            }
+        }
+    }
+
+    pub fn to_order_book(&self) -> OrderBook {
+        let order_book = OrderBook::new();
+        let timestamp = self.timestamp.as_deref().and_then(|t| t.parse().ok()).unwrap_or(0);
+        self.apply_to(&order_book, timestamp);
+        order_book
+    }
+}
+
+fn parse_levels(levels: &[BookLevel]) -> Vec<(f64, f64)> {
+    levels
+        .iter()
+        .filter_map(|level| Some((level.price.parse::<f64>().ok()?, level.size.parse::<f64>().ok()?)))
+        .collect()
+}
+
+/// Result of a cancel request: ids that were cancelled, and the reason for
+/// each one that was not.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct CancelResponse {
+    #[serde(default)]
+    pub canceled: Vec<String>,
+    #[serde(default)]
+    pub not_canceled: HashMap<String, String>,
+}
+
+impl CancelResponse {
+    pub fn is_complete(&self) -> bool {
+        self.not_canceled.is_empty()
+    }
+}
+
+/// What `GET /balance-allowance` reports on: USDC or an outcome token.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AssetType {
+    COLLATERAL,
+    CONDITIONAL,
+}
+
+impl AssetType {
+    fn unit(&self) -> &'static str {
+        match self {
+            AssetType::COLLATERAL => "USDC",
+            AssetType::CONDITIONAL => "shares",
+        }
+    }
+}
+
+/// Balance and exchange allowance in micro-units (six decimals), the same
+/// scale as an order's maker amount.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+pub struct BalanceAllowance {
+    #[serde(deserialize_with = "micro_units")]
+    pub balance: u64,
+    #[serde(deserialize_with = "micro_units")]
+    pub allowance: u64,
+}
+
+impl BalanceAllowance {
+    /// The most an order can spend: it needs both the funds and the approval.
+    pub fn available(&self) -> u64 {
+        self.balance.min(self.allowance)
+    }
+}
+
+fn micro_units<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+/// An order needs more collateral than the account can spend.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BalanceShortfall {
+    pub asset_type: AssetType,
+    /// Micro-units the order would lock: its maker amount.
+    pub required: u64,
+    pub balance: u64,
+    pub allowance: u64,
+}
+
+impl BalanceShortfall {
+    pub fn missing(&self) -> u64 {
+        self.required.saturating_sub(self.balance.min(self.allowance))
+    }
+}
+
+impl fmt::Display for BalanceShortfall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let amount = |units: u64| units as f64 / AMOUNT_SCALE as f64;
+        write!(
+            f,
+            "Not enough {}: order needs {:.6}, balance {:.6}, allowance {:.6} ({:.6} short)",
+            self.asset_type.unit(),
+            amount(self.required),
+            amount(self.balance),
+            amount(self.allowance),
+            amount(self.missing())
+        )
+    }
+}
+
+/// An order returned by `GET /data/orders`; sizes are in shares.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct PolymarketOpenOrder {
+    pub id: String,
+    pub status: String,
+    #[serde(default)]
+    pub market: String,
+    pub asset_id: String,
+    pub side: String,
+    pub original_size: String,
+    pub size_matched: String,
+    pub price: String,
+    #[serde(default)]
+    pub outcome: String,
+    pub order_type: Option<String>,
+    pub expiration: Option<String>,
+    pub created_at: Option<i64>,
+}
+
+/// Cursor the CLOB returns on the last page of a paginated response.
+const END_CURSOR: &str = "LTE=";
+
+/// `GET /data/orders` answers with either a bare list or a page of results.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OpenOrdersPage {
+    List(Vec<PolymarketOpenOrder>),
+    Paginated {
+        data: Vec<PolymarketOpenOrder>,
+        next_cursor: Option<String>,
+    },
+}
+
+/// A GTD order has to expire at least this many seconds from now; the CLOB
+/// rejects anything sooner.
+pub const GTD_SECURITY_BUFFER_SECS: i64 = 60;
+
+/// Error body of a non-2xx CLOB response.
+#[derive(Debug, Deserialize)]
+struct ClobErrorBody {
+    #[serde(alias = "errorMsg")]
+    error: String,
+}
+
+#[derive(Clone)]
 pub struct PolymarketClobClient {
     host: String,
     private_key: String,
@@ -60,12 +638,17 @@ pub struct PolymarketClobClient {
     signature_type: PolymarketSignatureType,
     funder_address: Option<String>,
     api_credentials: Option<PolymarketApiCredentials>,
+    market_params: HashMap<String, MarketParams>,
+    http: reqwest::Client,
+    retry: RetryConfig,
 }
 
+/// L2 credentials; `api_secret` is base64url encoded, as the CLOB issues it.
 #[derive(Debug, Clone)]
 pub struct PolymarketApiCredentials {
     pub api_key: String,
     pub api_secret: String,
+    pub api_passphrase: String,
 }
 
 impl PolymarketClobClient {
@@ -83,17 +666,39 @@ impl PolymarketClobClient {
             signature_type,
             funder_address,
             api_credentials: None,
+            market_params: HashMap::new(),
+            http: reqwest::Client::new(),
+            retry: RetryConfig::default(),
         }
     }
 
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn set_market_params(&mut self, token_id: &str, params: MarketParams) {
+        self.market_params.insert(token_id.to_string(), params);
+    }
+
+    /// Rules for `token_id`, falling back to the defaults when none were set.
+    pub fn market_params(&self, token_id: &str) -> MarketParams {
+        self.market_params.get(token_id).copied().unwrap_or_default()
+    }
+
     pub fn set_api_credentials(&mut self, credentials: PolymarketApiCredentials) {
         self.api_credentials = Some(credentials);
     }
 
+    pub fn api_credentials(&self) -> Option<&PolymarketApiCredentials> {
+        self.api_credentials.as_ref()
+    }
+
     pub fn create_or_derive_api_credentials(&self) -> PolymarketApiCredentials {
         PolymarketApiCredentials {
             api_key: format!("derived_key_{}", self.private_key[..8].to_string()),
-            api_secret: format!("derived_secret_{}", self.private_key[..8].to_string()),
+            api_secret: URL_SAFE.encode(format!("derived_secret_{}", &self.private_key[..8])),
+            api_passphrase: format!("derived_passphrase_{}", &self.private_key[..8]),
         }
     }
 
@@ -104,32 +709,38 @@ impl PolymarketClobClient {
         side: PolymarketOrderSide,
         token_id: String,
     ) -> PolymarketOrderArgs {
+        let params = self.market_params(&token_id);
         PolymarketOrderArgs {
-            price,
+            price: params.round_price(price),
             size,
             side,
             token_id,
+            order_type: PolymarketOrderType::GTC,
+            expiration: None,
         }
     }
 
-    pub fn create_order(&self, order_args: PolymarketOrderArgs) -> PolymarketOrder {
-        let current_time = Utc::now();
-        let expiration = current_time.timestamp() + 3600;
-        
-        let salt = rand::random::<u64>();
-        
-        let maker_amount = if order_args.side == PolymarketOrderSide::BUY {
-            (order_args.price * order_args.size * 1000000.0) as u64
-        } else {
-            (order_args.size * 1000000.0) as u64
-        };
-        
-        let taker_amount = if order_args.side == PolymarketOrderSide::BUY {
-            (order_args.size * 1000000.0) as u64
+    /// Rejects arguments whose price is off the market's tick, so they are
+    /// caught before an order is built and signed.
+    pub fn check_order_args(&self, order_args: &PolymarketOrderArgs) -> Result<(), PolymarketError> {
+        if self.market_params(&order_args.token_id).is_on_tick(order_args.price) {
+            Ok(())
         } else {
-            (order_args.price * order_args.size * 1000000.0) as u64
+            Err(PolymarketError::InvalidOrderMinTickSize)
+        }
+    }
+
+    pub fn create_order(&self, order_args: PolymarketOrderArgs) -> PolymarketOrder {
+        // The API expects 0 for orders that do not expire; a GTD order without
+        // an expiration is left at 0 so validation rejects it
+        let expiration = match order_args.order_type {
+            PolymarketOrderType::GTD => order_args.expiration.unwrap_or(0),
+            PolymarketOrderType::GTC | PolymarketOrderType::FOK => 0,
         };
 
+        let salt = rand::random::<u64>();
+        let (maker_amount, taker_amount) = order_amounts(order_args.side, order_args.price, order_args.size);
+
         PolymarketOrder {
             salt,
             maker: self.funder_address.clone().unwrap_or_else(|| "0x0".to_string()),
@@ -147,77 +758,285 @@ impl PolymarketClobClient {
         }
     }
 
+    /// Posts a signed order to `{host}/order` with L2 authentication headers.
     pub async fn post_order(
         &self,
         order: PolymarketOrder,
         order_type: PolymarketOrderType,
-    ) -> Result<PolymarketOrderResponse, Box<dyn std::error::Error>> {
-        let _order_request = PolymarketOrderRequest {
+    ) -> Result<PolymarketOrderResponse, PolymarketError> {
+        let credentials = self.api_credentials.as_ref().ok_or(PolymarketError::MissingCredentials)?;
+        let request = PolymarketOrderRequest {
             order,
-            owner: self.api_credentials.as_ref()
-                .ok_or("API credentials not set")?
-                .api_key.clone(),
+            owner: credentials.api_key.clone(),
             order_type: format!("{:?}", order_type),
         };
+        let body = serde_json::to_string(&request).map_err(|e| PolymarketError::Parse(e.to_string()))?;
+
+        let response: PolymarketOrderResponse =
+            self.send_authenticated(reqwest::Method::POST, "/order", &[], Some(body)).await?;
+        response.into_result()
+    }
+
+    /// Posts several orders through `{host}/orders`, at most
+    /// `MAX_BATCH_ORDERS` per request. Results come back in input order; a
+    /// request that fails as a whole fails every order it carried.
+    pub async fn post_orders(
+        &self,
+        orders: Vec<(PolymarketOrder, PolymarketOrderType)>,
+    ) -> Vec<Result<PolymarketOrderResponse, PolymarketError>> {
+        let mut results = Vec::with_capacity(orders.len());
+        for chunk in orders.chunks(MAX_BATCH_ORDERS) {
+            match self.post_batch(chunk).await {
+                Ok(responses) => {
+                    let mut responses = responses.into_iter();
+                    for _ in chunk {
+                        results.push(match responses.next() {
+                            Some(response) => response.into_result(),
+                            None => Err(PolymarketError::Parse("batch response is missing an order".to_string())),
+                        });
+                    }
+                }
+                Err(e) => results.extend(chunk.iter().map(|_| Err(e.for_each_order()))),
+            }
+        }
+        results
+    }
+
+    async fn post_batch(
+        &self,
+        orders: &[(PolymarketOrder, PolymarketOrderType)],
+    ) -> Result<Vec<PolymarketOrderResponse>, PolymarketError> {
+        let credentials = self.api_credentials.as_ref().ok_or(PolymarketError::MissingCredentials)?;
+        let requests: Vec<PolymarketOrderRequest> = orders
+            .iter()
+            .map(|(order, order_type)| PolymarketOrderRequest {
+                order: order.clone(),
+                owner: credentials.api_key.clone(),
+                order_type: format!("{:?}", order_type),
+            })
+            .collect();
+        let body = serde_json::to_string(&requests).map_err(|e| PolymarketError::Parse(e.to_string()))?;
+        self.send_authenticated(reqwest::Method::POST, "/orders", &[], Some(body)).await
+    }
+
+    /// Balance and allowance of collateral, or of the outcome token `token_id`.
+    pub async fn get_balance_allowance(
+        &self,
+        asset_type: AssetType,
+        token_id: Option<&str>,
+    ) -> Result<BalanceAllowance, PolymarketError> {
+        let mut query = vec![
+            ("asset_type", format!("{:?}", asset_type)),
+            ("signature_type", (self.signature_type as u8).to_string()),
+        ];
+        if let Some(token_id) = token_id {
+            query.push(("token_id", token_id.to_string()));
+        }
+        self.send_authenticated(reqwest::Method::GET, "/balance-allowance", &query, None).await
+    }
+
+    /// Checks that the account can fund an order before it is signed: a BUY
+    /// spends price × size USDC, a SELL spends size outcome tokens. The
+    /// requirement is the exact maker amount the signed order would carry.
+    pub async fn preflight_order(&self, order_args: &PolymarketOrderArgs) -> Result<(), PolymarketError> {
+        let (asset_type, token_id) = match order_args.side {
+            PolymarketOrderSide::BUY => (AssetType::COLLATERAL, None),
+            PolymarketOrderSide::SELL => (AssetType::CONDITIONAL, Some(order_args.token_id.as_str())),
+        };
+        let funds = self.get_balance_allowance(asset_type, token_id).await?;
+
+        let (required, _) = order_amounts(order_args.side, order_args.price, order_args.size);
+        if required > funds.available() {
+            return Err(PolymarketError::BalanceShortfall(BalanceShortfall {
+                asset_type,
+                required,
+                balance: funds.balance,
+                allowance: funds.allowance,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Cancels one order. A cancel the CLOB refuses comes back in
+    /// `not_canceled` rather than as an error.
+    pub async fn cancel_order(&self, order_id: &str) -> Result<CancelResponse, PolymarketError> {
+        let body = serde_json::json!({ "orderID": order_id }).to_string();
+        self.send_authenticated(reqwest::Method::DELETE, "/order", &[], Some(body)).await
+    }
 
-        Ok(PolymarketOrderResponse {
-            success: true,
-            error_msg: None,
-            order_id: Some("order_12345".to_string()),
-            order_hashes: Some(vec!["0xhash123".to_string()]),
+    pub async fn cancel_orders(&self, order_ids: &[String]) -> Result<CancelResponse, PolymarketError> {
+        let body = serde_json::to_string(order_ids).map_err(|e| PolymarketError::Parse(e.to_string()))?;
+        self.send_authenticated(reqwest::Method::DELETE, "/orders", &[], Some(body)).await
+    }
+
+    /// Cancels every open order owned by the API key.
+    pub async fn cancel_all(&self) -> Result<CancelResponse, PolymarketError> {
+        self.send_authenticated(reqwest::Method::DELETE, "/cancel-all", &[], None).await
+    }
+
+    /// Open orders for the API key, optionally limited to one market
+    /// (condition id), following `next_cursor` until the last page.
+    pub async fn get_open_orders(&self, market: Option<&str>) -> Result<Vec<PolymarketOpenOrder>, PolymarketError> {
+        let mut orders = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let mut query = Vec::new();
+            if let Some(market) = market {
+                query.push(("market", market.to_string()));
+            }
+            if !cursor.is_empty() {
+                query.push(("next_cursor", cursor.clone()));
+            }
+
+            match self.send_authenticated(reqwest::Method::GET, "/data/orders", &query, None).await? {
+                OpenOrdersPage::List(page) => {
+                    orders.extend(page);
+                    return Ok(orders);
+                }
+                OpenOrdersPage::Paginated { data, next_cursor } => {
+                    orders.extend(data);
+                    match next_cursor {
+                        Some(next) if next != END_CURSOR && !next.is_empty() && next != cursor => cursor = next,
+                        _ => return Ok(orders),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends an L2-authenticated request and parses a 2xx body as `T`. The
+    /// signature covers the path without its query string. Only GETs are
+    /// retried: the CLOB has no client order id to look a lost POST up by.
+    async fn send_authenticated<T: serde::de::DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<String>,
+    ) -> Result<T, PolymarketError> {
+        let body = body.unwrap_or_default();
+        let headers = self.l2_headers(method.as_str(), path, &body, Utc::now().timestamp())?;
+
+        let url = format!("{}{}", self.host.trim_end_matches('/'), path);
+        let retry = if http_retry::is_idempotent(&method) { self.retry } else { RetryConfig::none() };
+        let response = http_retry::send_with_retry(&retry, || {
+            let mut http_request = self.http.request(method.clone(), &url).query(query);
+            if !body.is_empty() {
+                http_request = http_request.header("Content-Type", "application/json").body(body.clone());
+            }
+            for (name, value) in &headers {
+                http_request = http_request.header(*name, value.as_str());
+            }
+            http_request
         })
+        .await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            let msg = serde_json::from_str::<ClobErrorBody>(&body).map_or(body, |e| e.error);
+            return Err(PolymarketError::Unauthorized(msg));
+        }
+        if !status.is_success() {
+            return Err(match serde_json::from_str::<ClobErrorBody>(&body) {
+                Ok(error) => PolymarketError::from_error_msg(&error.error),
+                Err(_) => PolymarketError::Status { status: status.as_u16(), body },
+            });
+        }
+        serde_json::from_str(&body).map_err(|e| PolymarketError::Parse(e.to_string()))
     }
 
-    pub fn get_order_status_description(status: &str) -> &'static str {
-        match status {
-            "matched" => "Order placed and matched with existing resting order",
-            "live" => "Order placed and resting on the book",
-            "delayed" => "Order marketable, but subject to matching delay",
-            "unmatched" => "Order marketable, but failure delaying, placement successful",
-            _ => "Unknown status",
+    /// Fetches the public order book for `token_id` from `{host}/book`.
+    pub async fn get_order_book(&self, token_id: &str) -> Result<BookSnapshot, PolymarketError> {
+        let url = format!("{}/book", self.host.trim_end_matches('/'));
+        let response = http_retry::send_with_retry(&self.retry, || self.http.get(&url).query(&[("token_id", token_id)])).await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(match serde_json::from_str::<ClobErrorBody>(&body) {
+                Ok(error) => PolymarketError::from_error_msg(&error.error),
+                Err(_) => PolymarketError::Status { status: status.as_u16(), body },
+            });
         }
+        serde_json::from_str(&body).map_err(|e| PolymarketError::Parse(e.to_string()))
+    }
+
+    /// POLY_* headers for a request; the signature is the base64url HMAC-SHA256
+    /// of `timestamp + method + path + body` under the decoded API secret.
+    pub fn l2_headers(
+        &self,
+        method: &str,
+        path: &str,
+        body: &str,
+        timestamp: i64,
+    ) -> Result<Vec<(&'static str, String)>, PolymarketError> {
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+
+        let credentials = self.api_credentials.as_ref().ok_or(PolymarketError::MissingCredentials)?;
+        let secret = URL_SAFE
+            .decode(&credentials.api_secret)
+            .map_err(|e| PolymarketError::Unauthorized(format!("API secret is not base64: {}", e)))?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(&secret).expect("HMAC can take key of any size");
+        mac.update(format!("{}{}{}{}", timestamp, method, path, body).as_bytes());
+        let signature = URL_SAFE.encode(mac.finalize().into_bytes());
+
+        Ok(vec![
+            ("POLY_ADDRESS", self.funder_address.clone().unwrap_or_else(|| "0x0".to_string())),
+            ("POLY_SIGNATURE", signature),
+            ("POLY_TIMESTAMP", timestamp.to_string()),
+            ("POLY_API_KEY", credentials.api_key.clone()),
+            ("POLY_PASSPHRASE", credentials.api_passphrase.clone()),
+        ])
+    }
+
+    pub fn get_order_status_description(status: &str) -> &'static str {
+        status.parse::<PolymarketOrderStatus>().unwrap_or_else(|never| match never {}).description()
     }
 
     pub fn get_error_description(error: &str) -> &'static str {
-        match error {
-            "INVALID_ORDER_MIN_TICK_SIZE" => "Order price breaks minimum tick size rules",
-            "INVALID_ORDER_MIN_SIZE" => "Order size lower than minimum threshold",
-            "INVALID_ORDER_DUPLICATED" => "Same order already placed, cannot place again",
-            "INVALID_ORDER_NOT_ENOUGH_BALANCE" => "Insufficient balance or allowance",
-            "INVALID_ORDER_EXPIRATION" => "Invalid expiration time",
-            "INVALID_ORDER_ERROR" => "System error while inserting order",
-            "EXECUTION_ERROR" => "System error while attempting to execute trade",
-            "ORDER_DELAYED" => "Order placement delayed due to market conditions",
-            "DELAYING_ORDER_ERROR" => "Error delaying the order",
-            "FOK_ORDER_NOT_FILLED_ERROR" => "FOK order not fully filled, cannot be placed",
-            "MARKET_NOT_READY" => "Market not ready to process new orders",
-            _ => "Unknown error",
-        }
-    }
-
-    pub fn validate_order(&self, order: &PolymarketOrder) -> Result<(), String> {
-        let expiration: i64 = order.expiration.parse().map_err(|_| "Invalid expiration format")?;
-        let current_time = Utc::now().timestamp();
-        
-        if expiration <= current_time {
-            return Err("Order expiration must be in the future".to_string());
-        }
-
-        let maker_amount: f64 = order.maker_amount.parse().map_err(|_| "Invalid maker amount")?;
-        let taker_amount: f64 = order.taker_amount.parse().map_err(|_| "Invalid taker amount")?;
-        
-        if maker_amount < 0.01 || taker_amount < 0.01 {
-            return Err("Order amounts must meet minimum tick size requirements".to_string());
-        }
-
-        let size = if order.side == 0 {
-            taker_amount
+        PolymarketError::from_error_msg(error).description().unwrap_or("Unknown error")
+    }
+
+    /// Checks an order against the CLOB's rules for `order_type`: GTD orders
+    /// need an expiration past the security buffer, GTC and FOK orders must
+    /// carry an expiration of 0.
+    pub fn validate_order(&self, order: &PolymarketOrder, order_type: PolymarketOrderType) -> Result<(), PolymarketError> {
+        let parse_error = |field: &str| PolymarketError::Parse(format!("invalid {}", field));
+        let expiration: i64 = order.expiration.parse().map_err(|_| parse_error("expiration"))?;
+        let expiration_ok = match order_type {
+            PolymarketOrderType::GTD => expiration > Utc::now().timestamp() + GTD_SECURITY_BUFFER_SECS,
+            PolymarketOrderType::GTC | PolymarketOrderType::FOK => expiration == 0,
+        };
+        if !expiration_ok {
+            return Err(PolymarketError::InvalidOrderExpiration);
+        }
+
+        let maker_amount: f64 = order.maker_amount.parse().map_err(|_| parse_error("maker amount"))?;
+        let taker_amount: f64 = order.taker_amount.parse().map_err(|_| parse_error("taker amount"))?;
+
+        // Amounts are in 1e-6 units; the share side of the order carries the size
+        let (size_amount, notional_amount) = if order.side == PolymarketOrderSide::BUY as u8 {
+            (taker_amount, maker_amount)
         } else {
-            maker_amount
+            (maker_amount, taker_amount)
         };
-        
-        if size < 1.0 {
-            return Err("Order size must meet minimum size threshold".to_string());
+        if size_amount <= 0.0 {
+            return Err(PolymarketError::InvalidOrderMinSize);
+        }
+
+        let params = self.market_params(&order.token_id);
+        let size = size_amount / 1000000.0;
+        let price = notional_amount / size_amount;
+
+        if !params.is_on_tick(price) || price < params.tick_size || price > 1.0 - params.tick_size {
+            return Err(PolymarketError::InvalidOrderMinTickSize);
+        }
+        if size < params.min_size || params.max_size.is_some_and(|max| size > max) {
+            return Err(PolymarketError::InvalidOrderMinSize);
         }
 
         Ok(())
@@ -230,6 +1049,40 @@ pub struct PolymarketOrderArgs {
     pub size: f64,
     pub side: PolymarketOrderSide,
     pub token_id: String,
+    pub order_type: PolymarketOrderType,
+    /// Unix seconds; only GTD orders expire, so it is ignored for GTC and FOK.
+    pub expiration: Option<i64>,
+}
+
+impl PolymarketOrderArgs {
+    pub fn with_order_type(mut self, order_type: PolymarketOrderType, expiration: Option<i64>) -> Self {
+        self.order_type = order_type;
+        self.expiration = expiration;
+        self
+    }
+}
+
+/// Finest tick any Polymarket market trades on.
+const MIN_TICK_SIZE: f64 = 0.0001;
+
+/// The order on the other outcome of a YES/NO pair with the same exposure as
+/// `args`: a YES and a NO share always settle to 1 USDC between them, so
+/// buying YES at p is selling NO at 1 - p, and selling YES at p is buying NO
+/// at 1 - p. `complement_token_id` is the other outcome's token; size, order
+/// type and expiration carry over.
+pub fn complementary_order_args(args: &PolymarketOrderArgs, complement_token_id: &str) -> PolymarketOrderArgs {
+    let side = match args.side {
+        PolymarketOrderSide::BUY => PolymarketOrderSide::SELL,
+        PolymarketOrderSide::SELL => PolymarketOrderSide::BUY,
+    };
+    PolymarketOrderArgs {
+        price: round_price_to_tick(1.0 - args.price, MIN_TICK_SIZE),
+        size: args.size,
+        side,
+        token_id: complement_token_id.to_string(),
+        order_type: args.order_type,
+        expiration: args.expiration,
+    }
 }
 
 pub fn polymarket_clob_example() {
@@ -267,7 +1120,7 @@ pub fn polymarket_clob_example() {
     println!("   Taker amount: {}", signed_order.taker_amount);
 
     println!("\n✅ Order validation:");
-    match client.validate_order(&signed_order) {
+    match client.validate_order(&signed_order, PolymarketOrderType::GTC) {
         Ok(()) => println!("   Order validation passed"),
         Err(e) => println!("   Order validation failed: {}", e),
     }
@@ -288,6 +1141,74 @@ pub fn polymarket_clob_example() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    fn client_with_credentials(host: String) -> PolymarketClobClient {
+        let mut client = PolymarketClobClient::new(
+            host,
+            "test_key".to_string(),
+            137,
+            PolymarketSignatureType::EMAIL_MAGIC,
+            Some("0xTestAddress".to_string()),
+        );
+        client.set_api_credentials(client.create_or_derive_api_credentials());
+        client
+    }
+
+    fn mock_client(server: &MockServer) -> PolymarketClobClient {
+        client_with_credentials(server.uri())
+    }
+
+    /// Recomputes POLY_SIGNATURE from the request's own timestamp and body.
+    fn signed_with_derived_secret(request: &Request) -> bool {
+        let header = |name: &str| {
+            request
+                .headers
+                .iter()
+                .find(|(key, _)| key.as_str().eq_ignore_ascii_case(name))
+                .map(|(_, values)| values.last().as_str().to_string())
+        };
+        let (Some(signature), Some(timestamp)) = (header("POLY_SIGNATURE"), header("POLY_TIMESTAMP")) else {
+            return false;
+        };
+        let Ok(timestamp) = timestamp.parse::<i64>() else {
+            return false;
+        };
+        let body = String::from_utf8_lossy(&request.body);
+        let client = client_with_credentials(String::new());
+        client
+            .l2_headers(request.method.as_ref(), request.url.path(), &body, timestamp)
+            .map(|headers| headers.iter().any(|(name, value)| *name == "POLY_SIGNATURE" && *value == signature))
+            .unwrap_or(false)
+    }
+
+    fn test_order(client: &PolymarketClobClient) -> PolymarketOrder {
+        let args = client.create_order_args(0.5, 10.0, PolymarketOrderSide::BUY, "test_token".to_string());
+        client.create_order(args)
+    }
+
+    #[test]
+    fn test_validate_token_id() {
+        let listed = "52114319501245915516055106046884209969926127482827954674443846427813813222426";
+        for accepted in ["0", "7", "123", listed, MAX_TOKEN_ID] {
+            assert_eq!(validate_token_id(accepted), Ok(()), "{}", accepted);
+        }
+
+        assert_eq!(validate_token_id(""), Err("Enter a Polymarket token id first".to_string()));
+        assert_eq!(
+            validate_token_id("BTCUSDT"),
+            Err("Invalid token id 'BTCUSDT': Polymarket token ids are decimal numbers".to_string())
+        );
+        for rejected in ["0x3a7b", "-1", "12.5", " 123", "0123"] {
+            assert!(validate_token_id(rejected).is_err(), "{}", rejected);
+        }
+        let too_large = MAX_TOKEN_ID.replacen("115", "116", 1);
+        assert!(validate_token_id(&too_large).unwrap_err().contains("larger than a uint256"));
+        assert!(validate_token_id(&"1".repeat(78)).is_ok());
+        assert!(validate_token_id(&format!("{}0", listed)).is_err());
+        assert!(validate_token_id(&"1".repeat(79)).is_err());
+    }
 
     #[test]
     fn test_order_creation() {
@@ -312,6 +1233,36 @@ mod tests {
         assert_eq!(order_args.token_id, "test_token");
     }
 
+    #[test]
+    fn test_complementary_order_args() {
+        let client = PolymarketClobClient::new(
+            "https://test.polymarket.com".to_string(),
+            "test_key".to_string(),
+            137,
+            PolymarketSignatureType::EMAIL_MAGIC,
+            None,
+        );
+        let buy_yes = client.create_order_args(0.62, 25.0, PolymarketOrderSide::BUY, "111".to_string());
+        let sell_no = complementary_order_args(&buy_yes, "222");
+        assert_eq!(sell_no.side, PolymarketOrderSide::SELL);
+        assert_eq!(sell_no.price, 0.38);
+        assert_eq!(sell_no.size, 25.0);
+        assert_eq!(sell_no.token_id, "222");
+        assert!(client.check_order_args(&sell_no).is_ok());
+
+        // Pairing twice gets back the original order
+        let back = complementary_order_args(&sell_no, "111");
+        assert_eq!(back.side, PolymarketOrderSide::BUY);
+        assert_eq!(back.price, 0.62);
+        assert_eq!(back.token_id, "111");
+
+        let gtd = buy_yes.with_order_type(PolymarketOrderType::GTD, Some(1_900_000_000));
+        let paired = complementary_order_args(&PolymarketOrderArgs { price: 0.071, ..gtd }, "222");
+        assert_eq!(paired.price, 0.929);
+        assert_eq!(paired.order_type, PolymarketOrderType::GTD);
+        assert_eq!(paired.expiration, Some(1_900_000_000));
+    }
+
     #[test]
     fn test_order_validation() {
         let client = PolymarketClobClient::new(
@@ -330,11 +1281,592 @@ mod tests {
         ));
 
         // Valid order should pass
-        assert!(client.validate_order(&order).is_ok());
+        assert_eq!(order.expiration, "0");
+        assert!(client.validate_order(&order, PolymarketOrderType::GTC).is_ok());
+
+        // GTC orders must not carry an expiration
+        order.expiration = (Utc::now().timestamp() + 3600).to_string();
+        assert!(client.validate_order(&order, PolymarketOrderType::GTC).is_err());
+    }
+
+    #[test]
+    fn test_expiration_per_order_type() {
+        let client = client_with_credentials(String::new());
+        let now = Utc::now().timestamp();
+        let args = || client.create_order_args(0.50, 10.0, PolymarketOrderSide::BUY, "test_token".to_string());
+
+        // GTC and FOK ignore any expiration and are sent with 0
+        for order_type in [PolymarketOrderType::GTC, PolymarketOrderType::FOK] {
+            let order = client.create_order(args().with_order_type(order_type, Some(now + 3600)));
+            assert_eq!(order.expiration, "0");
+            assert!(client.validate_order(&order, order_type).is_ok());
+        }
+
+        // GTD keeps its expiration, which has to clear the security buffer
+        let gtd = client.create_order(args().with_order_type(PolymarketOrderType::GTD, Some(now + 600)));
+        assert_eq!(gtd.expiration, (now + 600).to_string());
+        assert!(client.validate_order(&gtd, PolymarketOrderType::GTD).is_ok());
+
+        let inside_buffer = client.create_order(
+            args().with_order_type(PolymarketOrderType::GTD, Some(now + GTD_SECURITY_BUFFER_SECS - 5)),
+        );
+        assert_eq!(
+            client.validate_order(&inside_buffer, PolymarketOrderType::GTD).unwrap_err().code(),
+            Some("INVALID_ORDER_EXPIRATION")
+        );
+        let missing = client.create_order(args().with_order_type(PolymarketOrderType::GTD, None));
+        assert_eq!(missing.expiration, "0");
+        assert!(client.validate_order(&missing, PolymarketOrderType::GTD).is_err());
+    }
+
+    #[test]
+    fn test_order_validation_against_market_params() {
+        let mut client = PolymarketClobClient::new(
+            "https://test.polymarket.com".to_string(),
+            "test_key".to_string(),
+            137,
+            PolymarketSignatureType::EMAIL_MAGIC,
+            Some("0xTestAddress".to_string()),
+        );
+        client.set_market_params("fine_token", MarketParams { tick_size: 0.001, min_size: 5.0, max_size: Some(100.0) });
+
+        // Off the default 0.01 grid: rejected as given, snapped by create_order_args
+        let off_tick = PolymarketOrderArgs {
+            price: 0.505,
+            size: 10.0,
+            side: PolymarketOrderSide::BUY,
+            token_id: "test_token".to_string(),
+            order_type: PolymarketOrderType::GTC,
+            expiration: None,
+        };
+        assert_eq!(client.validate_order(&client.create_order(off_tick), PolymarketOrderType::GTC).unwrap_err().code(), Some("INVALID_ORDER_MIN_TICK_SIZE"));
+        let snapped = client.create_order_args(0.506, 10.0, PolymarketOrderSide::BUY, "test_token".to_string());
+        assert_eq!(snapped.price, 0.51);
+        assert!(client.validate_order(&client.create_order(snapped), PolymarketOrderType::GTC).is_ok());
+
+        // The same price is on the grid of a finer-tick market
+        let fine = client.create_order_args(0.505, 10.0, PolymarketOrderSide::SELL, "fine_token".to_string());
+        assert_eq!(fine.price, 0.505);
+        assert!(client.validate_order(&client.create_order(fine), PolymarketOrderType::GTC).is_ok());
+
+        let too_small = client.create_order_args(0.50, 4.99, PolymarketOrderSide::BUY, "fine_token".to_string());
+        assert_eq!(client.validate_order(&client.create_order(too_small), PolymarketOrderType::GTC).unwrap_err().code(), Some("INVALID_ORDER_MIN_SIZE"));
+        let at_min = client.create_order_args(0.50, 5.0, PolymarketOrderSide::SELL, "fine_token".to_string());
+        assert!(client.validate_order(&client.create_order(at_min), PolymarketOrderType::GTC).is_ok());
+        let too_large = client.create_order_args(0.50, 100.01, PolymarketOrderSide::SELL, "fine_token".to_string());
+        assert_eq!(client.validate_order(&client.create_order(too_large), PolymarketOrderType::GTC).unwrap_err().code(), Some("INVALID_ORDER_MIN_SIZE"));
+    }
+
+    #[test]
+    fn test_order_amounts_are_exact() {
+        // 0.57 * 3.3 * 1e6 is 1880999.9999999998 in floating point
+        assert_eq!(order_amounts(PolymarketOrderSide::BUY, 0.57, 3.3), (1881000, 3300000));
+        assert_eq!(order_amounts(PolymarketOrderSide::SELL, 0.57, 3.3), (3300000, 1881000));
+        assert_eq!(order_amounts(PolymarketOrderSide::BUY, 0.29, 100.0), (29000000, 100000000));
+        // Sub-micro notionals round half up
+        assert_eq!(order_amounts(PolymarketOrderSide::BUY, 0.5, 0.000001), (1, 1));
+
+        let mut client = client_with_credentials(String::new());
+        client.set_market_params("t", MarketParams { min_size: 1.0, ..MarketParams::default() });
+        let order = client.create_order(client.create_order_args(0.57, 3.3, PolymarketOrderSide::BUY, "t".to_string()));
+        assert_eq!(order.maker_amount, "1881000");
+        assert_eq!(order.taker_amount, "3300000");
+        assert!(client.validate_order(&order, PolymarketOrderType::GTC).is_ok());
+    }
+
+    #[test]
+    fn test_thousandth_tick_market() {
+        assert_eq!(round_price_to_tick(0.5674, 0.001), 0.567);
+        assert_eq!(round_price_to_tick(0.5675, 0.01), 0.57);
+
+        let mut client = client_with_credentials(String::new());
+        client.set_market_params("fine", MarketParams { tick_size: 0.001, ..MarketParams::default() });
+
+        let args = client.create_order_args(0.5674, 10.0, PolymarketOrderSide::SELL, "fine".to_string());
+        assert_eq!(args.price, 0.567);
+        assert!(client.check_order_args(&args).is_ok());
+        let order = client.create_order(args);
+        assert_eq!((order.maker_amount.as_str(), order.taker_amount.as_str()), ("10000000", "5670000"));
+        assert!(client.validate_order(&order, PolymarketOrderType::GTC).is_ok());
+
+        // 0.567 is off the default 0.01 grid and is refused before signing
+        let mut coarse = client.create_order_args(0.5, 10.0, PolymarketOrderSide::SELL, "coarse".to_string());
+        coarse.price = 0.567;
+        assert_eq!(client.check_order_args(&coarse).unwrap_err().code(), Some("INVALID_ORDER_MIN_TICK_SIZE"));
+    }
 
-        // Invalid expiration should fail
-        order.expiration = "0".to_string();
-        assert!(client.validate_order(&order).is_err());
+    #[test]
+    fn test_clob_json_round_trip() {
+        let client = PolymarketClobClient::new(
+            "https://test.polymarket.com".to_string(),
+            "test_key".to_string(),
+            137,
+            PolymarketSignatureType::EMAIL_MAGIC,
+            Some("0xTestAddress".to_string()),
+        );
+        let order = client.create_order(client.create_order_args(
+            0.50,
+            10.0,
+            PolymarketOrderSide::SELL,
+            "test_token".to_string(),
+        ));
+
+        let json = order.to_clob_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec![
+            "expiration", "feeRateBps", "maker", "makerAmount", "nonce", "salt",
+            "side", "signature", "signatureType", "signer", "taker", "takerAmount", "tokenId",
+        ]);
+        assert_eq!(value["side"], "SELL");
+        assert_eq!(value["tokenId"], "test_token");
+        assert_eq!(value["makerAmount"], "10000000");
+
+        let decoded: PolymarketOrder = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.side, PolymarketOrderSide::SELL as u8);
+        assert_eq!(decoded.salt, order.salt);
+        assert_eq!(decoded.taker_amount, order.taker_amount);
+
+        let request = PolymarketOrderRequest { order, owner: "api_key".to_string(), order_type: "GTC".to_string() };
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["orderType"], "GTC");
+        assert_eq!(value["order"]["feeRateBps"], "0");
+    }
+
+    #[test]
+    fn test_clob_response_deserializes() {
+        let body = r#"{
+            "success": true,
+            "errorMsg": "",
+            "orderID": "0x3a7b5f2d1c",
+            "orderHashes": ["0xabc123"],
+            "status": "live"
+        }"#;
+        let response: PolymarketOrderResponse = serde_json::from_str(body).unwrap();
+        assert!(response.success);
+        assert_eq!(response.error_msg.as_deref(), Some(""));
+        assert_eq!(response.order_id.as_deref(), Some("0x3a7b5f2d1c"));
+        assert_eq!(response.order_hashes, Some(vec!["0xabc123".to_string()]));
+        assert_eq!(response.status, Some(PolymarketOrderStatus::Live));
+
+        let rejected: PolymarketOrderResponse =
+            serde_json::from_str(r#"{"success": false, "errorMsg": "INVALID_ORDER_MIN_SIZE"}"#).unwrap();
+        assert!(!rejected.success);
+        assert_eq!(rejected.order_id, None);
+    }
+
+    #[test]
+    fn test_l2_headers_require_credentials() {
+        let client = PolymarketClobClient::new(
+            "https://test.polymarket.com".to_string(),
+            "test_key".to_string(),
+            137,
+            PolymarketSignatureType::EMAIL_MAGIC,
+            None,
+        );
+        assert!(matches!(
+            client.l2_headers("POST", "/order", "{}", 1700000000),
+            Err(PolymarketError::MissingCredentials)
+        ));
+
+        let signing = client_with_credentials(String::new());
+        let headers = signing.l2_headers("POST", "/order", "{}", 1700000000).unwrap();
+        let names: Vec<&str> = headers.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["POLY_ADDRESS", "POLY_SIGNATURE", "POLY_TIMESTAMP", "POLY_API_KEY", "POLY_PASSPHRASE"]);
+        assert_eq!(headers[2].1, "1700000000");
+        // Same input, same signature; a different body changes it
+        let again = signing.l2_headers("POST", "/order", "{}", 1700000000).unwrap();
+        let other = signing.l2_headers("POST", "/order", "{\"a\":1}", 1700000000).unwrap();
+        assert_eq!(headers[1].1, again[1].1);
+        assert_ne!(headers[1].1, other[1].1);
+    }
+
+    #[tokio::test]
+    async fn test_post_order_sends_signed_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .and(header("POLY_ADDRESS", "0xTestAddress"))
+            .and(header("POLY_API_KEY", "derived_key_test_key"))
+            .and(header("POLY_PASSPHRASE", "derived_passphrase_test_key"))
+            .and(signed_with_derived_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"success":true,"errorMsg":"","orderID":"0x3a7b5f2d1c","orderHashes":[],"status":"live"}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let response = client.post_order(test_order(&client), PolymarketOrderType::GTC).await.unwrap();
+        assert_eq!(response.order_id.as_deref(), Some("0x3a7b5f2d1c"));
+        assert_eq!(response.status, Some(PolymarketOrderStatus::Live));
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["owner"], "derived_key_test_key");
+        assert_eq!(body["orderType"], "GTC");
+        assert_eq!(body["order"]["tokenId"], "test_token");
+    }
+
+    #[tokio::test]
+    async fn test_post_order_maps_clob_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_string(r#"{"errorMsg":"INVALID_ORDER_NOT_ENOUGH_BALANCE"}"#),
+            )
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let error = client.post_order(test_order(&client), PolymarketOrderType::GTC).await.unwrap_err();
+        assert!(matches!(error, PolymarketError::InvalidOrderNotEnoughBalance));
+        assert_eq!(error.code(), Some("INVALID_ORDER_NOT_ENOUGH_BALANCE"));
+    }
+
+    #[tokio::test]
+    async fn test_post_order_maps_unauthorized() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(401).set_body_string(r#"{"error":"Unauthorized/Invalid api key"}"#))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        match client.post_order(test_order(&client), PolymarketOrderType::GTC).await {
+            Err(PolymarketError::Unauthorized(msg)) => assert_eq!(msg, "Unauthorized/Invalid api key"),
+            other => panic!("expected Unauthorized, got {:?}", other),
+        }
+    }
+
+    const BOOK_FIXTURE: &str = r#"{
+        "market": "0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1",
+        "asset_id": "21742633143463906290569050155826241533067272736897614950488156847949938836455",
+        "timestamp": "1729013216512",
+        "hash": "0x2f8a5c1e9b7d3f6a4e0c8b2d1f7a9e3c5b6d4f20",
+        "bids": [
+            {"price": "0.01", "size": "2004.5"},
+            {"price": "0.47", "size": "120"},
+            {"price": "0.48", "size": "33.3"}
+        ],
+        "asks": [
+            {"price": "0.99", "size": "500"},
+            {"price": "0.52", "size": "60"},
+            {"price": "0.51", "size": "45.12"}
+        ],
+        "min_order_size": "5",
+        "tick_size": "0.001",
+        "neg_risk": false
+    }"#;
+
+    #[test]
+    fn test_book_snapshot_parses_fixture() {
+        let snapshot: BookSnapshot = serde_json::from_str(BOOK_FIXTURE).unwrap();
+        assert!(snapshot.asset_id.starts_with("2174263314"));
+        assert_eq!(snapshot.bid_levels(), vec![(0.01, 2004.5), (0.47, 120.0), (0.48, 33.3)]);
+        assert_eq!(snapshot.ask_levels()[2], (0.51, 45.12));
+
+        let params = snapshot.market_params();
+        assert_eq!(params.tick_size, 0.001);
+        assert_eq!(params.min_size, 5.0);
+        assert_eq!(params.price_decimals(), 3);
+
+        let book = snapshot.to_order_book();
+        assert_eq!(book.get_best_bid(), Some(0.48));
+        assert_eq!(book.get_best_ask(), Some(0.51));
+        let (bids, asks) = book.get_market_depth(10);
+        assert_eq!(bids.len(), 3);
+        assert_eq!(asks[2], (0.99, 500.0));
+    }
+
+    #[test]
+    fn test_book_snapshot_with_empty_side() {
+        let snapshot: BookSnapshot = serde_json::from_str(
+            r#"{"market": "0xabc", "asset_id": "123", "bids": [], "asks": [{"price": "0.9", "size": "10"}]}"#,
+        )
+        .unwrap();
+        assert!(snapshot.bid_levels().is_empty());
+        assert_eq!(snapshot.market_params(), MarketParams::default());
+
+        let book = snapshot.to_order_book();
+        assert_eq!(book.get_best_bid(), None);
+        assert_eq!(book.get_best_ask(), Some(0.9));
+        assert_eq!(book.get_stats().spread, None);
+
+        // A missing side is treated like an empty one
+        let one_sided: BookSnapshot = serde_json::from_str(r#"{"asset_id": "123", "bids": []}"#).unwrap();
+        assert_eq!(one_sided.to_order_book().get_total_orders(), 0);
+    }
+
+    #[test]
+    fn test_price_decimals_follow_tick() {
+        let with_tick = |tick_size| MarketParams { tick_size, ..MarketParams::default() };
+        assert_eq!(with_tick(0.1).price_decimals(), 2);
+        assert_eq!(with_tick(0.01).price_decimals(), 2);
+        assert_eq!(with_tick(0.001).price_decimals(), 3);
+        assert_eq!(with_tick(0.0001).price_decimals(), 4);
+        assert_eq!(with_tick(0.00001).price_decimals(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_get_order_book_queries_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .and(query_param("token_id", "123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(BOOK_FIXTURE))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .and(query_param("token_id", "missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string(r#"{"error":"No orderbook exists for the requested token id"}"#))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let snapshot = client.get_order_book("123").await.unwrap();
+        assert_eq!(snapshot.asks.len(), 3);
+
+        match client.get_order_book("missing").await {
+            Err(PolymarketError::Unknown(msg)) => assert!(msg.contains("No orderbook")),
+            other => panic!("expected a CLOB error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preflight_compares_maker_amount_with_funds() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/balance-allowance"))
+            .and(query_param("asset_type", "COLLATERAL"))
+            .and(query_param("signature_type", "1"))
+            .and(signed_with_derived_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"balance":"1881000","allowance":"99000000"}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/balance-allowance"))
+            .and(query_param("asset_type", "CONDITIONAL"))
+            .and(query_param("token_id", "t"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"balance":"10000000","allowance":"3300000"}"#))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let funds = client.get_balance_allowance(AssetType::COLLATERAL, None).await.unwrap();
+        assert_eq!(funds, BalanceAllowance { balance: 1881000, allowance: 99000000 });
+        assert_eq!(funds.available(), 1881000);
+
+        // 0.57 × 3.3 needs exactly the 1.881 USDC held
+        let buy = client.create_order_args(0.57, 3.3, PolymarketOrderSide::BUY, "t".to_string());
+        assert!(client.preflight_order(&buy).await.is_ok());
+
+        // One millionth of a share more rounds the notional up by one unit
+        let buy = client.create_order_args(0.57, 3.300001, PolymarketOrderSide::BUY, "t".to_string());
+        match client.preflight_order(&buy).await {
+            Err(PolymarketError::BalanceShortfall(shortfall)) => {
+                assert_eq!(shortfall.asset_type, AssetType::COLLATERAL);
+                assert_eq!(shortfall.required, 1881001);
+                assert_eq!(shortfall.missing(), 1);
+            }
+            other => panic!("expected a shortfall, got {:?}", other),
+        }
+
+        // Selling is limited by the allowance, not the larger balance
+        let sell = client.create_order_args(0.57, 3.3, PolymarketOrderSide::SELL, "t".to_string());
+        assert!(client.preflight_order(&sell).await.is_ok());
+        let sell = client.create_order_args(0.57, 3.31, PolymarketOrderSide::SELL, "t".to_string());
+        let error = client.preflight_order(&sell).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Not enough shares: order needs 3.310000, balance 10.000000, allowance 3.300000 (0.010000 short)"
+        );
+    }
+
+    /// Answers a batch with one response per order, keyed by salt; orders for
+    /// token "bad" are rejected.
+    fn batch_responder(request: &Request) -> ResponseTemplate {
+        let orders: Vec<serde_json::Value> = serde_json::from_slice(&request.body).unwrap();
+        let responses: Vec<serde_json::Value> = orders
+            .iter()
+            .map(|request| {
+                let order = &request["order"];
+                if order["tokenId"] == "bad" {
+                    serde_json::json!({"success": false, "errorMsg": "INVALID_ORDER_MIN_SIZE"})
+                } else {
+                    serde_json::json!({"success": true, "errorMsg": "", "orderID": order["salt"].to_string(), "status": "live"})
+                }
+            })
+            .collect();
+        ResponseTemplate::new(200).set_body_json(responses)
+    }
+
+    #[tokio::test]
+    async fn test_post_orders_chunks_at_batch_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/orders"))
+            .and(signed_with_derived_secret)
+            .respond_with(batch_responder)
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let orders: Vec<_> = (0..2 * MAX_BATCH_ORDERS + 1)
+            .map(|_| (test_order(&client), PolymarketOrderType::GTC))
+            .collect();
+        let salts: Vec<String> = orders.iter().map(|(order, _)| order.salt.to_string()).collect();
+
+        let results = client.post_orders(orders).await;
+        let order_ids: Vec<String> = results.into_iter().map(|r| r.unwrap().order_id.unwrap()).collect();
+        assert_eq!(order_ids, salts);
+
+        let batch_sizes: Vec<usize> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| serde_json::from_slice::<Vec<serde_json::Value>>(&request.body).unwrap().len())
+            .collect();
+        assert_eq!(batch_sizes, vec![MAX_BATCH_ORDERS, MAX_BATCH_ORDERS, 1]);
+
+        assert!(client.post_orders(Vec::new()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_post_orders_maps_errors_per_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/orders"))
+            .respond_with(batch_responder)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let mut bad = test_order(&client);
+        bad.token_id = "bad".to_string();
+        let good = test_order(&client);
+        let results = client
+            .post_orders(vec![
+                (good.clone(), PolymarketOrderType::GTC),
+                (bad, PolymarketOrderType::FOK),
+                (good.clone(), PolymarketOrderType::GTC),
+            ])
+            .await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().order_id, Some(good.salt.to_string()));
+        assert!(matches!(results[1], Err(PolymarketError::InvalidOrderMinSize)));
+        assert!(results[2].is_ok());
+
+        // A request rejected as a whole fails each order in it
+        let unauthorized = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/orders"))
+            .respond_with(ResponseTemplate::new(401).set_body_string(r#"{"error":"Unauthorized/Invalid api key"}"#))
+            .mount(&unauthorized)
+            .await;
+        let results = mock_client(&unauthorized)
+            .post_orders(vec![(good.clone(), PolymarketOrderType::GTC), (good, PolymarketOrderType::GTC)])
+            .await;
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|r| matches!(r, Err(PolymarketError::Unauthorized(msg)) if msg == "Unauthorized/Invalid api key")));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_orders_reports_partial_failure() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/orders"))
+            .and(header("POLY_API_KEY", "derived_key_test_key"))
+            .and(signed_with_derived_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"canceled":["0xaaa"],"not_canceled":{"0xbbb":"order can't be found - already canceled or matched"}}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/order"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"canceled":["0xaaa"],"not_canceled":{}}"#))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let response = client.cancel_orders(&["0xaaa".to_string(), "0xbbb".to_string()]).await.unwrap();
+        assert_eq!(response.canceled, vec!["0xaaa".to_string()]);
+        assert!(!response.is_complete());
+        assert!(response.not_canceled["0xbbb"].contains("already canceled"));
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Vec<String> = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body, vec!["0xaaa", "0xbbb"]);
+
+        let single = client.cancel_order("0xaaa").await.unwrap();
+        assert!(single.is_complete());
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(body["orderID"], "0xaaa");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_with_nothing_open() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/cancel-all"))
+            .and(signed_with_derived_secret)
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"canceled":[],"not_canceled":{}}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let response = mock_client(&server).cancel_all().await.unwrap();
+        assert_eq!(response, CancelResponse::default());
+    }
+
+    #[tokio::test]
+    async fn test_get_open_orders_follows_cursor() {
+        let order = |id: &str| {
+            format!(
+                r#"{{"id":"{}","status":"LIVE","market":"0xcond","asset_id":"123","side":"BUY","original_size":"10","size_matched":"2.5","price":"0.45","outcome":"Yes","order_type":"GTC","expiration":"0","created_at":1729013216}}"#,
+                id
+            )
+        };
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .and(query_param("market", "0xcond"))
+            .and(query_param("next_cursor", "MTAw"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"data":[{}],"next_cursor":"LTE="}}"#,
+                order("0x2")
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .and(query_param("market", "0xcond"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"data":[{}],"next_cursor":"MTAw"}}"#,
+                order("0x1")
+            )))
+            .mount(&server)
+            .await;
+
+        let orders = mock_client(&server).get_open_orders(Some("0xcond")).await.unwrap();
+        let ids: Vec<&str> = orders.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, vec!["0x1", "0x2"]);
+        assert_eq!(orders[0].size_matched, "2.5");
+        assert_eq!(orders[0].created_at, Some(1729013216));
     }
 
     #[test]
@@ -349,4 +1881,62 @@ mod tests {
             "Order placed and matched with existing resting order"
         );
     }
+
+    #[test]
+    fn test_error_codes_round_trip() {
+        let codes = [
+            "INVALID_ORDER_MIN_TICK_SIZE",
+            "INVALID_ORDER_MIN_SIZE",
+            "INVALID_ORDER_DUPLICATED",
+            "INVALID_ORDER_NOT_ENOUGH_BALANCE",
+            "INVALID_ORDER_EXPIRATION",
+            "INVALID_ORDER_ERROR",
+            "EXECUTION_ERROR",
+            "ORDER_DELAYED",
+            "DELAYING_ORDER_ERROR",
+            "FOK_ORDER_NOT_FILLED_ERROR",
+            "MARKET_NOT_READY",
+        ];
+        for code in codes {
+            let error: PolymarketError = code.parse().unwrap();
+            assert_eq!(error.code(), Some(code));
+            assert!(error.description().is_some(), "{} has no description", code);
+            assert_eq!(error.to_string(), format!("{} ({})", code, error.description().unwrap()));
+
+            let json = serde_json::to_string(&error).unwrap();
+            assert_eq!(json, format!("\"{}\"", code));
+            let parsed: PolymarketError = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed.code(), Some(code));
+        }
+
+        // Details after the code are dropped; anything else is kept verbatim
+        let detailed: PolymarketError = "FOK_ORDER_NOT_FILLED_ERROR: order 0x1 not filled".parse().unwrap();
+        assert!(matches!(detailed, PolymarketError::FokOrderNotFilled));
+        let unknown: PolymarketError = serde_json::from_str("\"NEW_CODE\"").unwrap();
+        assert!(matches!(&unknown, PolymarketError::Unknown(msg) if msg == "NEW_CODE"));
+        assert_eq!(serde_json::to_string(&unknown).unwrap(), "\"NEW_CODE\"");
+        assert_eq!(unknown.code(), None);
+        assert_eq!(PolymarketClobClient::get_error_description("NEW_CODE"), "Unknown error");
+    }
+
+    #[test]
+    fn test_order_statuses_round_trip() {
+        let statuses = [
+            PolymarketOrderStatus::Live,
+            PolymarketOrderStatus::Matched,
+            PolymarketOrderStatus::Delayed,
+            PolymarketOrderStatus::Unmatched,
+            PolymarketOrderStatus::Cancelled,
+            PolymarketOrderStatus::Unknown("paused".to_string()),
+        ];
+        for status in statuses {
+            assert_eq!(status.as_str().parse::<PolymarketOrderStatus>().unwrap(), status);
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(json, format!("\"{}\"", status.as_str()));
+            assert_eq!(serde_json::from_str::<PolymarketOrderStatus>(&json).unwrap(), status);
+            assert_eq!(status.to_string(), status.description());
+            assert_eq!(PolymarketClobClient::get_order_status_description(status.as_str()), status.description());
+        }
+        assert_eq!(PolymarketOrderStatus::Unknown("paused".to_string()).description(), "Unknown status");
+    }
 }
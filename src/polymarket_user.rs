@@ -0,0 +1,594 @@
+use std::sync::mpsc as std_mpsc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use crate::polymarket_orders::{PolymarketApiCredentials, PolymarketOrderSide, PolymarketOrderStatus};
+
+pub const USER_CHANNEL_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/user";
+
+/// The channel drops connections that stay silent for longer than this.
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserChannelConfig {
+    pub url: String,
+    /// Condition ids to subscribe to; empty means every market.
+    pub markets: Vec<String>,
+    /// First wait before reconnecting; doubles per failed attempt.
+    pub reconnect_delay: Duration,
+    pub max_reconnect_delay: Duration,
+}
+
+impl Default for UserChannelConfig {
+    fn default() -> Self {
+        Self {
+            url: USER_CHANNEL_URL.to_string(),
+            markets: Vec::new(),
+            reconnect_delay: Duration::from_secs(1),
+            max_reconnect_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+/// An `order` event: one of the account's orders was placed, partly matched
+/// or cancelled.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderEvent {
+    pub id: String,
+    pub market: String,
+    pub asset_id: String,
+    pub side: PolymarketOrderSide,
+    #[serde(deserialize_with = "decimal")]
+    pub price: f64,
+    #[serde(deserialize_with = "decimal")]
+    pub original_size: f64,
+    #[serde(deserialize_with = "decimal")]
+    pub size_matched: f64,
+    /// PLACEMENT, UPDATE or CANCELLATION.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(deserialize_with = "timestamp")]
+    pub timestamp: u64,
+}
+
+/// A `trade` event: a match involving at least one of the account's orders,
+/// reported again as it moves through MATCHED, MINED and CONFIRMED.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TradeEvent {
+    pub id: String,
+    pub market: String,
+    pub asset_id: String,
+    pub side: PolymarketOrderSide,
+    #[serde(deserialize_with = "decimal")]
+    pub price: f64,
+    #[serde(deserialize_with = "decimal")]
+    pub size: f64,
+    pub status: String,
+    pub taker_order_id: String,
+    pub maker_orders: Vec<MakerOrder>,
+    /// API key the event was delivered to.
+    pub owner: String,
+    /// API key of the taker.
+    pub trade_owner: String,
+    #[serde(deserialize_with = "timestamp")]
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MakerOrder {
+    pub order_id: String,
+    pub asset_id: String,
+    pub owner: String,
+    #[serde(deserialize_with = "decimal")]
+    pub price: f64,
+    #[serde(deserialize_with = "decimal")]
+    pub matched_amount: f64,
+    /// Missing from older payloads, where the maker took the other side.
+    #[serde(default)]
+    pub side: Option<PolymarketOrderSide>,
+}
+
+/// A change to one of the account's orders, from either event type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolymarketOrderUpdate {
+    pub order_id: String,
+    pub asset_id: String,
+    pub side: PolymarketOrderSide,
+    pub price: f64,
+    /// Only `order` events carry the original size.
+    pub original_size: Option<f64>,
+    /// Cumulative for `order` events; the size of this match for `trade` events.
+    pub size_matched: f64,
+    pub status: PolymarketOrderStatus,
+    pub timestamp: u64,
+}
+
+impl OrderEvent {
+    pub fn status(&self) -> PolymarketOrderStatus {
+        match self.kind.as_str() {
+            "CANCELLATION" => PolymarketOrderStatus::Cancelled,
+            _ if self.size_matched >= self.original_size => PolymarketOrderStatus::Matched,
+            _ => PolymarketOrderStatus::Live,
+        }
+    }
+
+    pub fn to_update(&self) -> PolymarketOrderUpdate {
+        PolymarketOrderUpdate {
+            order_id: self.id.clone(),
+            asset_id: self.asset_id.clone(),
+            side: self.side,
+            price: self.price,
+            original_size: Some(self.original_size),
+            size_matched: self.size_matched,
+            status: self.status(),
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+impl TradeEvent {
+    /// MATCHED, MINED and CONFIRMED all mean the orders traded; RETRYING is
+    /// still settling and FAILED means the match was reverted on chain.
+    pub fn status(&self) -> PolymarketOrderStatus {
+        match self.status.as_str() {
+            "RETRYING" => PolymarketOrderStatus::Delayed,
+            "FAILED" => PolymarketOrderStatus::Unmatched,
+            _ => PolymarketOrderStatus::Matched,
+        }
+    }
+
+    /// Updates for the orders in this trade that belong to `owner`; the
+    /// event also names the counterparties' orders.
+    pub fn to_updates(&self) -> Vec<PolymarketOrderUpdate> {
+        let status = self.status();
+        let mut updates = Vec::new();
+        if self.trade_owner == self.owner {
+            updates.push(PolymarketOrderUpdate {
+                order_id: self.taker_order_id.clone(),
+                asset_id: self.asset_id.clone(),
+                side: self.side,
+                price: self.price,
+                original_size: None,
+                size_matched: self.size,
+                status: status.clone(),
+                timestamp: self.timestamp,
+            });
+        }
+        let opposite = match self.side {
+            PolymarketOrderSide::BUY => PolymarketOrderSide::SELL,
+            PolymarketOrderSide::SELL => PolymarketOrderSide::BUY,
+        };
+        updates.extend(self.maker_orders.iter().filter(|maker| maker.owner == self.owner).map(|maker| {
+            PolymarketOrderUpdate {
+                order_id: maker.order_id.clone(),
+                asset_id: maker.asset_id.clone(),
+                side: maker.side.unwrap_or(opposite),
+                price: maker.price,
+                original_size: None,
+                size_matched: maker.matched_amount,
+                status: status.clone(),
+                timestamp: self.timestamp,
+            }
+        }));
+        updates
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolymarketUserEvent {
+    Order(PolymarketOrderUpdate),
+    /// The socket dropped and the channel is subscribed again.
+    Reconnected,
+    /// The channel rejected the API credentials; the stream has stopped.
+    AuthFailed(String),
+    /// The stream task stopped with an error; no further events will arrive.
+    StreamError(String),
+}
+
+#[derive(Debug)]
+pub enum UserMessageError {
+    AuthFailed(String),
+    Parse(serde_json::Error),
+}
+
+fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+/// Timestamps arrive as strings of unix seconds or milliseconds.
+fn timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(value) => value.parse().map_err(serde::de::Error::custom),
+        Value::Number(value) => value.as_u64().ok_or_else(|| serde::de::Error::custom("invalid timestamp")),
+        other => Err(serde::de::Error::custom(format!("invalid timestamp {}", other))),
+    }
+}
+
+/// The message that authenticates and subscribes a fresh connection.
+pub fn subscribe_message(credentials: &PolymarketApiCredentials, markets: &[String]) -> String {
+    json!({
+        "auth": {
+            "apiKey": credentials.api_key,
+            "secret": credentials.api_secret,
+            "passphrase": credentials.api_passphrase,
+        },
+        "markets": markets,
+        "type": "user",
+    })
+    .to_string()
+}
+
+/// Parses a user channel message, which holds one event or an array of
+/// them, into order updates. Event types the client does not track are
+/// skipped; a plain-text rejection of the credentials is an auth failure.
+pub fn parse_user_message(text: &str) -> Result<Vec<PolymarketOrderUpdate>, UserMessageError> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(_) if text.to_ascii_lowercase().contains("auth") => {
+            return Err(UserMessageError::AuthFailed(text.trim().to_string()))
+        }
+        Err(e) => return Err(UserMessageError::Parse(e)),
+    };
+    let events = match value {
+        Value::Array(events) => events,
+        event => vec![event],
+    };
+
+    let mut updates = Vec::new();
+    for event in events {
+        match event.get("event_type").and_then(Value::as_str) {
+            Some("order") => {
+                let event: OrderEvent = serde_json::from_value(event).map_err(UserMessageError::Parse)?;
+                updates.push(event.to_update());
+            }
+            Some("trade") => {
+                let event: TradeEvent = serde_json::from_value(event).map_err(UserMessageError::Parse)?;
+                updates.extend(event.to_updates());
+            }
+            _ => {}
+        }
+    }
+    Ok(updates)
+}
+
+enum SessionEnd {
+    Shutdown,
+    /// The socket closed; `subscribed` is whether it got past authentication.
+    Disconnected { subscribed: bool },
+    AuthFailed(String),
+}
+
+/// One connection: authenticate, then forward updates until the socket
+/// closes or `shutdown` fires.
+async fn run_session(
+    config: &UserChannelConfig,
+    credentials: &PolymarketApiCredentials,
+    events: &mpsc::UnboundedSender<PolymarketUserEvent>,
+    shutdown: &mut watch::Receiver<bool>,
+    reconnected: bool,
+) -> Result<SessionEnd, Box<dyn std::error::Error + Send + Sync>> {
+    let (mut socket, _) = connect_async(config.url.as_str()).await?;
+    socket.send(Message::Text(subscribe_message(credentials, &config.markets))).await?;
+    if reconnected && events.send(PolymarketUserEvent::Reconnected).is_err() {
+        return Ok(SessionEnd::Shutdown);
+    }
+
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    let mut subscribed = false;
+    let end = loop {
+        tokio::select! {
+            message = socket.next() => match message {
+                Some(Ok(Message::Text(text))) if text == "PONG" => {}
+                Some(Ok(Message::Text(text))) => match parse_user_message(&text) {
+                    Ok(updates) => {
+                        subscribed = true;
+                        if updates.into_iter().any(|update| events.send(PolymarketUserEvent::Order(update)).is_err()) {
+                            break SessionEnd::Shutdown;
+                        }
+                    }
+                    Err(UserMessageError::AuthFailed(reason)) => break SessionEnd::AuthFailed(reason),
+                    Err(UserMessageError::Parse(_)) => {}
+                },
+                Some(Ok(Message::Close(Some(frame)))) if frame.code == CloseCode::Policy => {
+                    break SessionEnd::AuthFailed(frame.reason.to_string());
+                }
+                Some(Ok(Message::Close(_))) | None => break SessionEnd::Disconnected { subscribed },
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+            },
+            _ = ping.tick() => {
+                socket.send(Message::Text("PING".to_string())).await?;
+            }
+            _ = shutdown.changed() => break SessionEnd::Shutdown,
+        }
+    };
+
+    let _ = socket.close(None).await;
+    Ok(end)
+}
+
+/// Keeps the user channel subscribed, reconnecting and re-authenticating
+/// with a capped exponential backoff whenever the socket drops. Stops on
+/// `shutdown` or when the credentials are rejected.
+pub async fn run_user_channel(
+    config: UserChannelConfig,
+    credentials: PolymarketApiCredentials,
+    events: mpsc::UnboundedSender<PolymarketUserEvent>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut delay = config.reconnect_delay;
+    let mut reconnected = false;
+    loop {
+        match run_session(&config, &credentials, &events, &mut shutdown, reconnected).await {
+            Ok(SessionEnd::Shutdown) => return,
+            Ok(SessionEnd::AuthFailed(reason)) => {
+                let _ = events.send(PolymarketUserEvent::AuthFailed(reason));
+                return;
+            }
+            Ok(SessionEnd::Disconnected { subscribed: true }) => delay = config.reconnect_delay,
+            Ok(SessionEnd::Disconnected { subscribed: false }) => {}
+            Err(e) => {
+                if events.send(PolymarketUserEvent::StreamError(e.to_string())).is_err() {
+                    return;
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.changed() => return,
+        }
+        delay = (delay * 2).min(config.max_reconnect_delay);
+        reconnected = true;
+    }
+}
+
+/// Runs `run_user_channel` on its own thread so the synchronous UI loop can
+/// poll for events.
+pub struct UserChannelHandle {
+    events: mpsc::UnboundedReceiver<PolymarketUserEvent>,
+    shutdown: watch::Sender<bool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl UserChannelHandle {
+    pub fn spawn(config: UserChannelConfig, credentials: PolymarketApiCredentials) -> Self {
+        let (event_tx, events) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = watch::channel(false);
+
+        let thread = std::thread::spawn(move || {
+            match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(run_user_channel(config, credentials, event_tx, shutdown_rx)),
+                Err(e) => {
+                    let _ = event_tx.send(PolymarketUserEvent::StreamError(e.to_string()));
+                }
+            }
+        });
+
+        Self { events, shutdown, thread: Some(thread) }
+    }
+
+    /// Returns every event received since the last call without blocking.
+    pub fn drain(&mut self) -> Vec<PolymarketUserEvent> {
+        let mut drained = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            drained.push(event);
+        }
+        drained
+    }
+
+    /// Signals the stream task to close the socket and waits up to `timeout`
+    /// for it to finish.
+    pub fn stop(&mut self, timeout: Duration) {
+        let _ = self.shutdown.send(true);
+        if let Some(thread) = self.thread.take() {
+            let (done_tx, done_rx) = std_mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = thread.join();
+                let _ = done_tx.send(());
+            });
+            let _ = done_rx.recv_timeout(timeout);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+
+    const PLACEMENT_FIXTURE: &str = r#"{
+        "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+        "associate_trades": null, "event_type": "order",
+        "id": "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b",
+        "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+        "order_owner": "9180014b-33c8-9240-a14b-bdca11c0a465", "original_size": "10", "outcome": "YES",
+        "owner": "9180014b-33c8-9240-a14b-bdca11c0a465", "price": "0.57", "side": "SELL",
+        "size_matched": "0", "timestamp": "1672290687", "type": "PLACEMENT"
+    }"#;
+
+    const TRADE_FIXTURE: &str = r#"{
+        "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+        "event_type": "trade", "id": "28c4d2eb-bbea-40e7-a9f0-b2fdb56b2c2e", "last_update": "1672290701",
+        "maker_orders": [
+            {"asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+             "matched_amount": "10", "order_id": "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b",
+             "outcome": "YES", "owner": "9180014b-33c8-9240-a14b-bdca11c0a465", "price": "0.57"},
+            {"asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+             "matched_amount": "5", "order_id": "0x0c9d1b2a", "outcome": "YES",
+             "owner": "8d1c2b3a-0000-0000-0000-000000000000", "price": "0.57"}
+        ],
+        "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+        "matchtime": "1672290701", "outcome": "YES", "owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+        "price": "0.57", "side": "BUY", "size": "15", "status": "MATCHED",
+        "taker_order_id": "0x06bc63e346ed4ceddce9efd6b3af37c8f8f440c92fe7da6b2d0f9e4ccbc50c42",
+        "timestamp": "1672290701", "trade_owner": "7c2a1b3d-0000-0000-0000-000000000000", "type": "TRADE"
+    }"#;
+
+    fn order_event(kind: &str, size_matched: &str) -> String {
+        PLACEMENT_FIXTURE
+            .replace(r#""type": "PLACEMENT""#, &format!(r#""type": "{}""#, kind))
+            .replace(r#""size_matched": "0""#, &format!(r#""size_matched": "{}""#, size_matched))
+    }
+
+    fn credentials() -> PolymarketApiCredentials {
+        PolymarketApiCredentials {
+            api_key: "key".to_string(),
+            api_secret: "c2VjcmV0".to_string(),
+            api_passphrase: "pass".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_order_events_map_to_statuses() {
+        let updates = parse_user_message(PLACEMENT_FIXTURE).unwrap();
+        assert_eq!(updates.len(), 1);
+        let update = &updates[0];
+        assert_eq!(update.order_id, "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b");
+        assert_eq!(update.side, PolymarketOrderSide::SELL);
+        assert_eq!(update.price, 0.57);
+        assert_eq!(update.original_size, Some(10.0));
+        assert_eq!(update.timestamp, 1672290687);
+        assert_eq!(update.status, PolymarketOrderStatus::Live);
+        assert_eq!(update.status.description(), "Order placed and resting on the book");
+
+        let statuses: Vec<_> = [("UPDATE", "4"), ("UPDATE", "10"), ("CANCELLATION", "4")]
+            .iter()
+            .map(|(kind, matched)| parse_user_message(&order_event(kind, matched)).unwrap()[0].status.clone())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![PolymarketOrderStatus::Live, PolymarketOrderStatus::Matched, PolymarketOrderStatus::Cancelled]
+        );
+        assert_eq!(
+            PolymarketOrderStatus::Matched.description(),
+            "Order placed and matched with existing resting order"
+        );
+    }
+
+    #[test]
+    fn test_trade_events_only_report_own_orders() {
+        let updates = parse_user_message(TRADE_FIXTURE).unwrap();
+
+        // The taker and the second maker belong to other accounts
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].order_id, "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b");
+        assert_eq!(updates[0].side, PolymarketOrderSide::SELL);
+        assert_eq!(updates[0].size_matched, 10.0);
+        assert_eq!(updates[0].status, PolymarketOrderStatus::Matched);
+
+        let own_taker = TRADE_FIXTURE.replace("7c2a1b3d-0000-0000-0000-000000000000", "9180014b-33c8-9240-a14b-bdca11c0a465");
+        let updates = parse_user_message(&own_taker).unwrap();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].order_id, "0x06bc63e346ed4ceddce9efd6b3af37c8f8f440c92fe7da6b2d0f9e4ccbc50c42");
+        assert_eq!(updates[0].side, PolymarketOrderSide::BUY);
+        assert_eq!(updates[0].size_matched, 15.0);
+
+        for (status, expected) in [
+            ("MINED", PolymarketOrderStatus::Matched),
+            ("CONFIRMED", PolymarketOrderStatus::Matched),
+            ("RETRYING", PolymarketOrderStatus::Delayed),
+            ("FAILED", PolymarketOrderStatus::Unmatched),
+        ] {
+            let event = TRADE_FIXTURE.replace(r#""status": "MATCHED""#, &format!(r#""status": "{}""#, status));
+            assert_eq!(parse_user_message(&event).unwrap()[0].status, expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_batches_and_auth_failures() {
+        let batch = format!("[{}, {}, {{\"event_type\": \"price_change\"}}]", PLACEMENT_FIXTURE, TRADE_FIXTURE);
+        assert_eq!(parse_user_message(&batch).unwrap().len(), 2);
+        assert!(parse_user_message("[]").unwrap().is_empty());
+
+        assert!(matches!(parse_user_message("INVALID AUTH"), Err(UserMessageError::AuthFailed(reason)) if reason == "INVALID AUTH"));
+        assert!(matches!(parse_user_message("{"), Err(UserMessageError::Parse(_))));
+    }
+
+    #[test]
+    fn test_subscribe_message_carries_credentials() {
+        let message: Value = serde_json::from_str(&subscribe_message(&credentials(), &["0xabc".to_string()])).unwrap();
+        assert_eq!(message["type"], "user");
+        assert_eq!(message["auth"]["apiKey"], "key");
+        assert_eq!(message["auth"]["secret"], "c2VjcmV0");
+        assert_eq!(message["auth"]["passphrase"], "pass");
+        assert_eq!(message["markets"][0], "0xabc");
+    }
+
+    fn test_config(url: String) -> UserChannelConfig {
+        UserChannelConfig {
+            url,
+            markets: Vec::new(),
+            reconnect_delay: Duration::from_millis(10),
+            max_reconnect_delay: Duration::from_millis(50),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_authenticates_again() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut subscriptions = Vec::new();
+            // The first connection drops right after subscribing
+            for reply in [None, Some(PLACEMENT_FIXTURE)] {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut socket = accept_async(stream).await.unwrap();
+                if let Some(Ok(Message::Text(text))) = socket.next().await {
+                    subscriptions.push(text);
+                }
+                match reply {
+                    Some(reply) => {
+                        socket.send(Message::Text(reply.to_string())).await.unwrap();
+                        let _ = socket.next().await;
+                    }
+                    None => socket.close(None).await.unwrap(),
+                }
+            }
+            subscriptions
+        });
+
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let channel = tokio::spawn(run_user_channel(test_config(url), credentials(), event_tx, shutdown_rx));
+
+        assert_eq!(events.recv().await, Some(PolymarketUserEvent::Reconnected));
+        match events.recv().await {
+            Some(PolymarketUserEvent::Order(update)) => assert_eq!(update.status, PolymarketOrderStatus::Live),
+            other => panic!("expected order update, got {:?}", other),
+        }
+
+        shutdown.send(true).unwrap();
+        channel.await.unwrap();
+        let subscriptions = server.await.unwrap();
+        assert_eq!(subscriptions, vec![subscribe_message(&credentials(), &[]); 2]);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_credentials_stop_the_channel() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            let _ = socket.next().await;
+            let frame = CloseFrame { code: CloseCode::Policy, reason: "invalid api key".into() };
+            let _ = socket.close(Some(frame)).await;
+        });
+
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+        let (_shutdown, shutdown_rx) = watch::channel(false);
+        run_user_channel(test_config(url), credentials(), event_tx, shutdown_rx).await;
+
+        assert_eq!(events.recv().await, Some(PolymarketUserEvent::AuthFailed("invalid api key".to_string())));
+        assert_eq!(events.recv().await, None);
+    }
+}
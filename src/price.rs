@@ -1,5 +1,11 @@
 use std::fmt;
 use std::cmp::Ordering;
+use std::str::FromStr;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+/// Decimals a `Price` is shown with when nothing else is asked for.
+pub const DEFAULT_PRICE_DECIMALS: usize = 2;
 
 #[derive(Debug, Clone)]
 pub struct Price(pub f64);
@@ -8,6 +14,47 @@ impl Price {
     pub fn as_f64(&self) -> f64 {
         self.0
     }
+
+    /// The shortest decimal that converts back to this price, so a price
+    /// parsed from "0.015" comes back as exactly 0.015.
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        Decimal::from_f64(self.0).map(|d| d.normalize())
+    }
+
+    /// Formats with `decimals` places, rounding the nearest float as `{:.N}`
+    /// does. Same as `format!("{:.N}", price)`.
+    pub fn format_with(&self, decimals: usize) -> String {
+        format!("{:.*}", decimals, self.0)
+    }
+
+    /// Formats with exactly `scale` decimal places, e.g. the scale of the
+    /// string the price was parsed from. Falls back to `Display` for NaN.
+    pub fn format_scaled(&self, scale: u32) -> String {
+        match self.to_decimal() {
+            Some(mut decimal) => {
+                decimal = decimal.round_dp(scale);
+                decimal.rescale(scale);
+                decimal.to_string()
+            }
+            None => self.to_string(),
+        }
+    }
+}
+
+impl From<Decimal> for Price {
+    fn from(decimal: Decimal) -> Self {
+        Price(decimal.to_f64().unwrap_or(f64::NAN))
+    }
+}
+
+/// Parses a plain decimal string such as "0.0001"; input with more digits
+/// than a `Decimal` holds is rejected rather than rounded.
+impl FromStr for Price {
+    type Err = rust_decimal::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Decimal::from_str_exact(s.trim()).map(Price::from)
+    }
 }
 
 impl PartialEq for Price {
@@ -42,9 +89,12 @@ impl Ord for Price {
     }
 }
 
+/// Uses the format's precision when given (`{:.4}`), otherwise
+/// `DEFAULT_PRICE_DECIMALS`.
 impl fmt::Display for Price {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:.2}", self.0)
+        let decimals = f.precision().unwrap_or(DEFAULT_PRICE_DECIMALS);
+        write!(f, "{:.*}", decimals, self.0)
     }
 }
 
@@ -0,0 +1,268 @@
+use parking_lot::Mutex;
+use reqwest::header::HeaderMap;
+use std::time::{Duration, Instant};
+
+/// Weight Binance has counted against this IP in the current minute.
+pub const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+
+const WEIGHT_WINDOW: Duration = Duration::from_secs(60);
+const ORDER_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitConfig {
+    pub weight_per_minute: u32,
+    pub orders_per_second: u32,
+    /// Longest a request waits for budget before it is rejected instead.
+    pub max_delay: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            weight_per_minute: 6000,
+            orders_per_second: 10,
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitUsage {
+    pub used_weight: u32,
+    pub weight_limit: u32,
+    pub orders_this_second: u32,
+    pub order_limit: u32,
+}
+
+/// A fixed window that starts with the first request and then advances in
+/// whole multiples of its length, like Binance's own counters.
+#[derive(Debug)]
+struct Window {
+    length: Duration,
+    start: Option<Instant>,
+    used: u32,
+}
+
+impl Window {
+    fn new(length: Duration) -> Self {
+        Self { length, start: None, used: 0 }
+    }
+
+    fn roll(&mut self, now: Instant) {
+        match self.start {
+            None => self.start = Some(now),
+            Some(start) if now >= start + self.length => {
+                let elapsed = (now - start).as_nanos() / self.length.as_nanos();
+                self.start = Some(start + self.length * elapsed as u32);
+                self.used = 0;
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// How long until `amount` more fits under `limit`, or `None` if it fits now.
+    fn wait_for(&self, amount: u32, limit: u32, now: Instant) -> Option<Duration> {
+        if self.used + amount <= limit {
+            return None;
+        }
+        let start = self.start.unwrap_or(now);
+        Some((start + self.length).saturating_duration_since(now))
+    }
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    weight: Window,
+    orders: Window,
+}
+
+/// Client-side accounting of Binance's REQUEST_WEIGHT and ORDERS limits. Share
+/// one limiter (behind an `Arc`) between every client that talks to the same
+/// API host, since Binance counts weight per IP.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(LimiterState {
+                weight: Window::new(WEIGHT_WINDOW),
+                orders: Window::new(ORDER_WINDOW),
+            }),
+        }
+    }
+
+    pub fn config(&self) -> RateLimitConfig {
+        self.config
+    }
+
+    /// Records a request of `weight` if it fits in the current minute,
+    /// otherwise returns how long to wait before retrying. A weight above the
+    /// whole limit is admitted once the window is empty.
+    pub fn try_acquire(&self, weight: u32, now: Instant) -> Result<(), Duration> {
+        let mut state = self.state.lock();
+        state.weight.roll(now);
+
+        let weight = weight.min(self.config.weight_per_minute);
+        if let Some(wait) = state.weight.wait_for(weight, self.config.weight_per_minute, now) {
+            return Err(wait);
+        }
+        state.weight.used += weight;
+        Ok(())
+    }
+
+    /// Like `try_acquire`, but the request also has to fit under the
+    /// orders-per-second limit.
+    pub fn try_acquire_order(&self, weight: u32, now: Instant) -> Result<(), Duration> {
+        let mut state = self.state.lock();
+        state.weight.roll(now);
+        state.orders.roll(now);
+
+        let weight = weight.min(self.config.weight_per_minute);
+        let weight_wait = state.weight.wait_for(weight, self.config.weight_per_minute, now);
+        let order_wait = state.orders.wait_for(1, self.config.orders_per_second, now);
+        if let Some(wait) = weight_wait.max(order_wait) {
+            return Err(wait);
+        }
+        state.weight.used += weight;
+        state.orders.used += 1;
+        Ok(())
+    }
+
+    /// Waits for budget, sleeping at most `max_delay`. Returns the remaining
+    /// wait when the request has to be rejected instead.
+    pub async fn acquire(&self, weight: u32) -> Result<(), Duration> {
+        loop {
+            match self.try_acquire(weight, Instant::now()) {
+                Ok(()) => return Ok(()),
+                Err(wait) if wait > self.config.max_delay => return Err(wait),
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    pub async fn acquire_order(&self, weight: u32) -> Result<(), Duration> {
+        loop {
+            match self.try_acquire_order(weight, Instant::now()) {
+                Ok(()) => return Ok(()),
+                Err(wait) if wait > self.config.max_delay => return Err(wait),
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    /// Replaces the local weight count with the one Binance reports; requests
+    /// from other processes on the same IP are only visible this way.
+    pub fn sync_used_weight(&self, used_weight: u32, now: Instant) {
+        let mut state = self.state.lock();
+        state.weight.roll(now);
+        state.weight.used = used_weight;
+    }
+
+    pub fn sync_from_headers(&self, headers: &HeaderMap, now: Instant) {
+        let used_weight = headers
+            .get(USED_WEIGHT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u32>().ok());
+        if let Some(used_weight) = used_weight {
+            self.sync_used_weight(used_weight, now);
+        }
+    }
+
+    pub fn usage(&self, now: Instant) -> RateLimitUsage {
+        let mut state = self.state.lock();
+        state.weight.roll(now);
+        state.orders.roll(now);
+        RateLimitUsage {
+            used_weight: state.weight.used,
+            weight_limit: self.config.weight_per_minute,
+            orders_this_second: state.orders.used,
+            order_limit: self.config.orders_per_second,
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimitConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(weight_per_minute: u32, orders_per_second: u32) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            weight_per_minute,
+            orders_per_second,
+            max_delay: Duration::from_secs(5),
+        })
+    }
+
+    #[test]
+    fn test_weight_budget_delays_until_next_minute() {
+        let limiter = limiter(100, 10);
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire(60, start).is_ok());
+        assert!(limiter.try_acquire(40, start + Duration::from_secs(10)).is_ok());
+        assert_eq!(limiter.try_acquire(1, start + Duration::from_secs(15)), Err(Duration::from_secs(45)));
+        assert_eq!(limiter.usage(start + Duration::from_secs(15)).used_weight, 100);
+
+        // The window resets on the minute, not 60s after the last request
+        assert!(limiter.try_acquire(100, start + Duration::from_secs(60)).is_ok());
+        assert_eq!(limiter.try_acquire(1, start + Duration::from_secs(119)), Err(Duration::from_secs(1)));
+        assert!(limiter.try_acquire(1, start + Duration::from_secs(185)).is_ok());
+        assert_eq!(limiter.usage(start + Duration::from_secs(185)).used_weight, 1);
+    }
+
+    #[test]
+    fn test_order_rate_is_limited_per_second() {
+        let limiter = limiter(100, 2);
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire_order(1, start).is_ok());
+        assert!(limiter.try_acquire_order(1, start + Duration::from_millis(100)).is_ok());
+        assert_eq!(
+            limiter.try_acquire_order(1, start + Duration::from_millis(400)),
+            Err(Duration::from_millis(600))
+        );
+        // Non-order requests still fit in the weight budget
+        assert!(limiter.try_acquire(1, start + Duration::from_millis(400)).is_ok());
+        assert!(limiter.try_acquire_order(1, start + Duration::from_secs(1)).is_ok());
+
+        let usage = limiter.usage(start + Duration::from_secs(1));
+        assert_eq!(usage.orders_this_second, 1);
+        assert_eq!(usage.used_weight, 4);
+    }
+
+    #[test]
+    fn test_used_weight_header_resyncs_count() {
+        let limiter = limiter(100, 10);
+        let start = Instant::now();
+        assert!(limiter.try_acquire(10, start).is_ok());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(USED_WEIGHT_HEADER, "95".parse().unwrap());
+        limiter.sync_from_headers(&headers, start + Duration::from_secs(1));
+
+        assert_eq!(limiter.usage(start + Duration::from_secs(1)).used_weight, 95);
+        assert!(limiter.try_acquire(5, start + Duration::from_secs(2)).is_ok());
+        assert_eq!(limiter.try_acquire(1, start + Duration::from_secs(2)), Err(Duration::from_secs(58)));
+    }
+
+    #[test]
+    fn test_oversized_weight_waits_for_empty_window() {
+        let limiter = limiter(100, 10);
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire(1, start).is_ok());
+        assert_eq!(limiter.try_acquire(500, start), Err(Duration::from_secs(60)));
+        assert!(limiter.try_acquire(500, start + Duration::from_secs(60)).is_ok());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::order::OrderSide;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// How the simulated market moves; the `[simulator]` table of the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulatorParams {
+    /// Largest price move per tick, either way, in the quote currency.
+    pub volatility: f64,
+    /// Scales the trading mode's chance of a new order on each update.
+    pub order_arrival_rate: f64,
+    /// New orders' sizes are spread evenly between these two.
+    pub min_order_size: f64,
+    pub max_order_size: f64,
+}
+
+impl Default for SimulatorParams {
+    fn default() -> Self {
+        Self {
+            volatility: 100.0,
+            order_arrival_rate: 1.0,
+            min_order_size: 0.1,
+            max_order_size: 10.1,
+        }
+    }
+}
+
+impl SimulatorParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.volatility.is_finite() && self.volatility >= 0.0) {
+            return Err(format!("volatility must be zero or more, got {}", self.volatility));
+        }
+        if !(self.order_arrival_rate.is_finite() && self.order_arrival_rate >= 0.0) {
+            return Err(format!("order arrival rate must be zero or more, got {}", self.order_arrival_rate));
+        }
+        if !(self.min_order_size > 0.0 && self.min_order_size <= self.max_order_size && self.max_order_size.is_finite()) {
+            return Err(format!(
+                "order sizes need 0 < min <= max, got {} to {}",
+                self.min_order_size, self.max_order_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A simulated order: side, price and quantity.
+pub type SimulatedOrder = (OrderSide, f64, f64);
+
+/// Source of every simulated price, order and candle. Two simulators built
+/// with the same seed and params produce the same market.
+#[derive(Debug, Clone)]
+pub struct MarketSimulator {
+    rng: StdRng,
+    seed: Option<u64>,
+    pub params: SimulatorParams,
+    paused: bool,
+}
+
+impl MarketSimulator {
+    /// Seeded from `seed`, or from the OS when there is none.
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self { rng, seed, params: SimulatorParams::default(), paused: false }
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Stops the periodic updates; explicit requests still go through.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// The generator itself, for draws the params don't cover.
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// A price move of up to `volatility` either way.
+    pub fn price_move(&mut self) -> f64 {
+        (self.rng.gen::<f64>() - 0.5) * 2.0 * self.params.volatility
+    }
+
+    /// With probability `chance` scaled by the arrival rate, an order on a
+    /// random side within `band` of `price`.
+    pub fn next_order(&mut self, price: f64, chance: f64, band: f64) -> Option<SimulatedOrder> {
+        if self.rng.gen::<f64>() >= chance * self.params.order_arrival_rate {
+            return None;
+        }
+        let side = if self.rng.gen::<bool>() { OrderSide::Bid } else { OrderSide::Ask };
+        let offset = (self.rng.gen::<f64>() - 0.5) * 2.0 * band;
+        let SimulatorParams { min_order_size, max_order_size, .. } = self.params;
+        let quantity = min_order_size + self.rng.gen::<f64>() * (max_order_size - min_order_size);
+        Some((side, price + offset, quantity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orders(simulator: &mut MarketSimulator, count: usize) -> Vec<Option<SimulatedOrder>> {
+        (0..count).map(|_| simulator.next_order(100.0, 0.5, 10.0)).collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_orders() {
+        let mut first = MarketSimulator::new(Some(42));
+        let mut second = MarketSimulator::new(Some(42));
+        let sequence = orders(&mut first, 200);
+        assert_eq!(sequence, orders(&mut second, 200));
+        assert_eq!(first.price_move(), second.price_move());
+        assert_eq!(first.seed(), Some(42));
+
+        let placed: Vec<SimulatedOrder> = sequence.into_iter().flatten().collect();
+        assert!(placed.len() > 50 && placed.len() < 150, "{}", placed.len());
+        assert!(placed.iter().any(|order| order.0 == OrderSide::Bid));
+        assert!(placed.iter().any(|order| order.0 == OrderSide::Ask));
+        for (_, price, quantity) in &placed {
+            assert!((90.0..=110.0).contains(price), "{}", price);
+            assert!((0.1..=10.1).contains(quantity), "{}", quantity);
+        }
+
+        let mut other = MarketSimulator::new(Some(43));
+        assert_ne!(orders(&mut other, 200), orders(&mut MarketSimulator::new(Some(42)), 200));
+    }
+
+    #[test]
+    fn test_params_shape_the_orders() {
+        let mut simulator = MarketSimulator::new(Some(7));
+        simulator.params = SimulatorParams {
+            volatility: 0.5,
+            order_arrival_rate: 0.0,
+            min_order_size: 2.0,
+            max_order_size: 2.0,
+        };
+        assert!(orders(&mut simulator, 100).iter().all(Option::is_none));
+        assert!((0..100).map(|_| simulator.price_move()).all(|step| step.abs() <= 0.5));
+
+        simulator.params.order_arrival_rate = 2.0;
+        let placed = orders(&mut simulator, 100);
+        assert!(placed.iter().all(|order| matches!(order, Some((_, _, quantity)) if *quantity == 2.0)));
+
+        assert!(SimulatorParams::default().validate().is_ok());
+        assert!(SimulatorParams { volatility: -1.0, ..SimulatorParams::default() }.validate().is_err());
+        assert!(SimulatorParams { min_order_size: 5.0, max_order_size: 1.0, ..SimulatorParams::default() }
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let mut simulator = MarketSimulator::new(None);
+        assert!(!simulator.is_paused());
+        simulator.pause();
+        assert!(simulator.is_paused());
+        simulator.resume();
+        assert!(!simulator.is_paused());
+        assert_eq!(simulator.seed(), None);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::order::OrderSide;
+use ratatui::style::{Color, Style};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Share of top-of-book depth one side needs before the separator changes color.
+pub const IMBALANCE_THRESHOLD: f64 = 0.2;
+
+/// The colors every panel draws with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    /// Bids, buys, gains and rising candles.
+    pub bid: Color,
+    /// Asks, sells, losses and falling candles.
+    pub ask: Color,
+    /// Panel titles, column headings and control hints.
+    pub accent: Color,
+    /// Headers and the values worth a glance: prices, the selected tab.
+    pub highlight: Color,
+    pub text: Color,
+    /// Labels and axes.
+    pub dim: Color,
+    /// Chart grid and reference lines.
+    pub grid: Color,
+    /// Secondary details such as the coin list and Polymarket token.
+    pub info: Color,
+    pub selection_fg: Color,
+    pub selection_bg: Color,
+    /// Background of warnings such as the STALE badge.
+    pub alert: Color,
+    /// Text drawn over `alert`, `bid` or `ask` backgrounds.
+    pub inverse: Color,
+    /// Chart overlay and indicator lines, in the order they are drawn.
+    pub chart: [Color; 4],
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            bid: Color::Green,
+            ask: Color::Red,
+            accent: Color::Cyan,
+            highlight: Color::Yellow,
+            text: Color::White,
+            dim: Color::Gray,
+            grid: Color::DarkGray,
+            info: Color::Magenta,
+            selection_fg: Color::Black,
+            selection_bg: Color::Cyan,
+            alert: Color::Red,
+            inverse: Color::White,
+            chart: [Color::Blue, Color::Yellow, Color::Cyan, Color::Magenta],
+        }
+    }
+
+    /// For terminals with a light background.
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            bid: Color::Rgb(0, 135, 0),
+            ask: Color::Rgb(200, 0, 0),
+            accent: Color::Blue,
+            highlight: Color::Rgb(175, 95, 0),
+            text: Color::Black,
+            dim: Color::DarkGray,
+            grid: Color::Gray,
+            info: Color::Magenta,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+            alert: Color::Rgb(200, 0, 0),
+            inverse: Color::White,
+            chart: [Color::Blue, Color::Rgb(175, 95, 0), Color::Cyan, Color::Magenta],
+        }
+    }
+
+    /// Bright colors only, nothing dimmed.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_string(),
+            bid: Color::LightGreen,
+            ask: Color::LightRed,
+            accent: Color::LightCyan,
+            highlight: Color::LightYellow,
+            text: Color::White,
+            dim: Color::White,
+            grid: Color::Gray,
+            info: Color::LightMagenta,
+            selection_fg: Color::Black,
+            selection_bg: Color::White,
+            alert: Color::LightRed,
+            inverse: Color::Black,
+            chart: [Color::LightBlue, Color::LightYellow, Color::LightCyan, Color::LightMagenta],
+        }
+    }
+
+    pub fn presets() -> Vec<Theme> {
+        vec![Self::dark(), Self::light(), Self::high_contrast()]
+    }
+
+    /// `bid` for a gain or no change, `ask` for a loss.
+    pub fn change_color(&self, value: f64) -> Color {
+        if value >= 0.0 { self.bid } else { self.ask }
+    }
+
+    pub fn side_color(&self, side: OrderSide) -> Color {
+        match side {
+            OrderSide::Bid => self.bid,
+            OrderSide::Ask => self.ask,
+        }
+    }
+
+    /// Separator color for a book imbalance: `bid` when bids dominate by at
+    /// least `IMBALANCE_THRESHOLD`, `ask` when asks do, `highlight` otherwise.
+    pub fn imbalance_color(&self, imbalance: Option<f64>) -> Color {
+        match imbalance {
+            Some(i) if i >= IMBALANCE_THRESHOLD => self.bid,
+            Some(i) if i <= -IMBALANCE_THRESHOLD => self.ask,
+            _ => self.highlight,
+        }
+    }
+
+    /// The selected row of a table.
+    pub fn selection(&self) -> Style {
+        Style::default().fg(self.selection_fg).bg(self.selection_bg)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// A theme from the config file: a preset (`dark` unless `base` says
+/// otherwise) with any of its colors replaced. Colors are names such as
+/// "lightgreen" or "#rrggbb" hex codes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomTheme {
+    pub name: String,
+    pub base: Option<String>,
+    pub bid: Option<String>,
+    pub ask: Option<String>,
+    pub accent: Option<String>,
+    pub highlight: Option<String>,
+    pub text: Option<String>,
+    pub dim: Option<String>,
+    pub grid: Option<String>,
+    pub info: Option<String>,
+    pub selection_fg: Option<String>,
+    pub selection_bg: Option<String>,
+    pub alert: Option<String>,
+    pub inverse: Option<String>,
+    /// Up to four chart colors; missing ones keep the base's.
+    pub chart: Vec<String>,
+}
+
+impl CustomTheme {
+    pub fn resolve(&self) -> Result<Theme, String> {
+        if self.name.trim().is_empty() {
+            return Err("Custom theme needs a name".to_string());
+        }
+        let base = self.base.as_deref().unwrap_or("dark");
+        let mut theme = Theme::presets()
+            .into_iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(base))
+            .ok_or_else(|| format!("Theme {}: unknown base theme '{}'", self.name, base))?;
+        theme.name = self.name.trim().to_string();
+
+        let parse = |field: &str, value: &str| {
+            Color::from_str(value).map_err(|_| format!("Theme {}: invalid color '{}' for {}", self.name, value, field))
+        };
+        let overrides = [
+            ("bid", &self.bid, &mut theme.bid),
+            ("ask", &self.ask, &mut theme.ask),
+            ("accent", &self.accent, &mut theme.accent),
+            ("highlight", &self.highlight, &mut theme.highlight),
+            ("text", &self.text, &mut theme.text),
+            ("dim", &self.dim, &mut theme.dim),
+            ("grid", &self.grid, &mut theme.grid),
+            ("info", &self.info, &mut theme.info),
+            ("selection_fg", &self.selection_fg, &mut theme.selection_fg),
+            ("selection_bg", &self.selection_bg, &mut theme.selection_bg),
+            ("alert", &self.alert, &mut theme.alert),
+            ("inverse", &self.inverse, &mut theme.inverse),
+        ];
+        for (field, value, color) in overrides {
+            if let Some(value) = value {
+                *color = parse(field, value)?;
+            }
+        }
+        if self.chart.len() > theme.chart.len() {
+            return Err(format!("Theme {}: at most {} chart colors", self.name, theme.chart.len()));
+        }
+        for (slot, value) in theme.chart.iter_mut().zip(&self.chart) {
+            *slot = parse("chart", value)?;
+        }
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_define_every_role() {
+        let presets = Theme::presets();
+        let names: Vec<&str> = presets.iter().map(|theme| theme.name.as_str()).collect();
+        assert_eq!(names, vec!["dark", "light", "high-contrast"]);
+
+        for theme in &presets {
+            let roles = [
+                theme.bid,
+                theme.ask,
+                theme.accent,
+                theme.highlight,
+                theme.text,
+                theme.dim,
+                theme.grid,
+                theme.info,
+                theme.selection_fg,
+                theme.selection_bg,
+                theme.alert,
+                theme.inverse,
+            ];
+            // Nothing is left to the terminal's default colors
+            assert!(roles.iter().chain(&theme.chart).all(|color| *color != Color::Reset), "{}", theme.name);
+            assert_ne!(theme.bid, theme.ask, "{}", theme.name);
+            assert_ne!(theme.selection_fg, theme.selection_bg, "{}", theme.name);
+            assert_ne!(theme.inverse, theme.alert, "{}", theme.name);
+
+            assert_eq!(theme.change_color(1.5), theme.bid);
+            assert_eq!(theme.change_color(0.0), theme.bid);
+            assert_eq!(theme.change_color(-0.1), theme.ask);
+            assert_eq!(theme.side_color(OrderSide::Ask), theme.ask);
+            assert_eq!(theme.selection(), Style::default().fg(theme.selection_fg).bg(theme.selection_bg));
+        }
+    }
+
+    #[test]
+    fn test_imbalance_color_thresholds() {
+        let theme = Theme::dark();
+        assert_eq!(theme.imbalance_color(None), Color::Yellow);
+        assert_eq!(theme.imbalance_color(Some(0.0)), Color::Yellow);
+        assert_eq!(theme.imbalance_color(Some(0.19)), Color::Yellow);
+        assert_eq!(theme.imbalance_color(Some(IMBALANCE_THRESHOLD)), Color::Green);
+        assert_eq!(theme.imbalance_color(Some(1.0)), Color::Green);
+        assert_eq!(theme.imbalance_color(Some(-0.19)), Color::Yellow);
+        assert_eq!(theme.imbalance_color(Some(-IMBALANCE_THRESHOLD)), Color::Red);
+        assert_eq!(theme.imbalance_color(Some(-1.0)), Color::Red);
+    }
+
+    #[test]
+    fn test_custom_theme_overrides_its_base() {
+        let custom = CustomTheme {
+            name: "solarized".to_string(),
+            base: Some("Light".to_string()),
+            bid: Some("#859900".to_string()),
+            ask: Some("lightred".to_string()),
+            chart: vec!["#268bd2".to_string()],
+            ..CustomTheme::default()
+        };
+        let theme = custom.resolve().unwrap();
+        assert_eq!(theme.name, "solarized");
+        assert_eq!(theme.bid, Color::Rgb(0x85, 0x99, 0x00));
+        assert_eq!(theme.ask, Color::LightRed);
+        assert_eq!(theme.chart[0], Color::Rgb(0x26, 0x8b, 0xd2));
+        let light = Theme::light();
+        assert_eq!(theme.text, light.text);
+        assert_eq!(theme.chart[1..], light.chart[1..]);
+
+        let bad_color = CustomTheme { accent: Some("chartreuse-ish".to_string()), ..custom.clone() };
+        assert_eq!(
+            bad_color.resolve().unwrap_err(),
+            "Theme solarized: invalid color 'chartreuse-ish' for accent"
+        );
+        let bad_base = CustomTheme { base: Some("sepia".to_string()), ..custom.clone() };
+        assert!(bad_base.resolve().unwrap_err().contains("unknown base theme"));
+        assert!(CustomTheme::default().resolve().is_err());
+    }
+}
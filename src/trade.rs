@@ -1,8 +1,45 @@
+use crate::order::OrderSide;
+
 #[derive(Debug, Clone)]
 pub struct Trade {
     pub bid_order_id: u64,
     pub ask_order_id: u64,
     pub price: f64,
     pub quantity: f64,
+    /// When the trade executed, in milliseconds since the epoch. The book
+    /// stamps an incoming order's fills with that order's timestamp and a
+    /// matching sweep's with the time it ran.
     pub timestamp: u64,
+    /// Side of the order that crossed the spread; the resting order set the price.
+    pub aggressor_side: OrderSide,
+    /// Fee charged to the resting order, in quote currency.
+    pub maker_fee: f64,
+    /// Fee charged to the aggressor, in quote currency.
+    pub taker_fee: f64,
+}
+
+impl Trade {
+    pub fn notional(&self) -> f64 {
+        self.price * self.quantity
+    }
+}
+
+/// Maker and taker fee rates in basis points of a trade's notional.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeeSchedule {
+    pub maker_bps: f64,
+    pub taker_bps: f64,
+}
+
+impl FeeSchedule {
+    pub fn new(maker_bps: f64, taker_bps: f64) -> Self {
+        Self { maker_bps, taker_bps }
+    }
+
+    /// Sets `maker_fee` and `taker_fee` on `trade` from its notional.
+    pub fn apply(&self, trade: &mut Trade) {
+        let notional = trade.notional();
+        trade.maker_fee = notional * self.maker_bps / 10_000.0;
+        trade.taker_fee = notional * self.taker_bps / 10_000.0;
+    }
 }
@@ -0,0 +1,109 @@
+use crate::order::OrderSide;
+use crate::trade::Trade;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// One side's trades over a window: what whale alerts compare and report.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlowTotals {
+    /// The aggressor side every trade in the window shares.
+    pub side: OrderSide,
+    pub notional: f64,
+    pub quantity: f64,
+    pub trades: usize,
+}
+
+impl FlowTotals {
+    /// Volume-weighted average price of the window.
+    pub fn vwap(&self) -> f64 {
+        if self.quantity > 0.0 {
+            self.notional / self.quantity
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Recent trades as `(timestamp ms, price, quantity)`, kept apart by
+/// aggressor side so buying and selling into the same window never add up.
+#[derive(Debug, Clone, Default)]
+pub struct TradeFlow {
+    buys: VecDeque<(u64, f64, f64)>,
+    sells: VecDeque<(u64, f64, f64)>,
+}
+
+impl TradeFlow {
+    fn side_mut(&mut self, side: OrderSide) -> &mut VecDeque<(u64, f64, f64)> {
+        match side {
+            OrderSide::Bid => &mut self.buys,
+            OrderSide::Ask => &mut self.sells,
+        }
+    }
+
+    /// Adds `trade`, whose timestamp is in milliseconds, and returns its
+    /// side's totals over the `window` ending at it. Trades at least `window`
+    /// older are dropped, so a zero window holds the trade alone.
+    pub fn record(&mut self, trade: &Trade, window: Duration) -> FlowTotals {
+        let window_ms = window.as_millis() as u64;
+        let trades = self.side_mut(trade.aggressor_side);
+        while trades.front().is_some_and(|&(at, _, _)| at.saturating_add(window_ms) <= trade.timestamp) {
+            trades.pop_front();
+        }
+        trades.push_back((trade.timestamp, trade.price, trade.quantity));
+
+        FlowTotals {
+            side: trade.aggressor_side,
+            notional: trades.iter().map(|&(_, price, quantity)| price * quantity).sum(),
+            quantity: trades.iter().map(|&(_, _, quantity)| quantity).sum(),
+            trades: trades.len(),
+        }
+    }
+
+    /// Forgets `side`'s trades, so flow that already fired is not counted again.
+    pub fn clear(&mut self, side: OrderSide) {
+        self.side_mut(side).clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(timestamp: u64, side: OrderSide, price: f64, quantity: f64) -> Trade {
+        Trade {
+            bid_order_id: 0,
+            ask_order_id: 0,
+            price,
+            quantity,
+            timestamp,
+            aggressor_side: side,
+            maker_fee: 0.0,
+            taker_fee: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_window_keeps_sides_apart_and_drops_old_trades() {
+        let window = Duration::from_secs(5);
+        let mut flow = TradeFlow::default();
+        flow.record(&trade(1_000, OrderSide::Bid, 100.0, 2.0), window);
+        let sells = flow.record(&trade(2_000, OrderSide::Ask, 99.0, 10.0), window);
+        assert_eq!(sells.notional, 990.0);
+        assert_eq!(sells.trades, 1);
+
+        let buys = flow.record(&trade(3_000, OrderSide::Bid, 103.0, 1.0), window);
+        assert_eq!(buys, FlowTotals { side: OrderSide::Bid, notional: 303.0, quantity: 3.0, trades: 2 });
+        assert_eq!(buys.vwap(), 101.0);
+
+        // 5s after the first buy, it has left the window
+        let buys = flow.record(&trade(6_000, OrderSide::Bid, 100.0, 1.0), window);
+        assert_eq!((buys.notional, buys.trades), (203.0, 2));
+
+        flow.clear(OrderSide::Bid);
+        let buys = flow.record(&trade(6_500, OrderSide::Bid, 100.0, 1.0), window);
+        assert_eq!(buys.trades, 1);
+
+        let single = TradeFlow::default().record(&trade(1, OrderSide::Ask, 50.0, 2.0), Duration::ZERO);
+        assert_eq!((single.notional, single.vwap()), (100.0, 50.0));
+    }
+}
@@ -1,24 +1,69 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    text::{Span, Line},
+    text::{Span, Line, Text},
+    symbols,
     widgets::{
-        Block, Borders, Paragraph, Row, Table, Tabs, Wrap,
+        Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, TableState, Tabs, Wrap,
     },
     Frame,
 };
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use chrono;
 use rand::Rng;
-use crate::order_book::OrderBook;
-use crate::order::OrderSide;
-use crate::polymarket_orders::{PolymarketClobClient, PolymarketOrderSide, PolymarketOrderType, PolymarketSignatureType};
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthChar;
+use crate::binance_orders::{BinanceError, BinanceOrderClient, BinanceOrderRef, BinanceOrderRequest, BinanceOrderSide, BinanceTimeInForce, FilterViolation};
+use crate::binance_rest::{BinanceRestClient, SymbolFilters};
+use crate::binance_user_data::{user_stream_base_url, OrderUpdate, UserDataEvent, UserDataStreamHandle};
+use crate::candles::{self, MINUTE_CANDLE_CAPACITY};
+use crate::binance_ws::{BinanceWebSocketClient, MarketStreamEvent, MarketStreamHandle, StreamMetrics, StreamType};
+use crate::polymarket_user::{PolymarketOrderUpdate, PolymarketUserEvent, UserChannelConfig, UserChannelHandle};
+use crate::command_line::CommandLine;
+use crate::commands::{Arg, Command, CommandRegistry};
+use crate::config::{config_dir, AppConfig};
+use crate::export;
+use crate::exchange::{ExchangeClient, OrderAck, TimeInForce};
+use crate::indicators;
+use crate::logging::{LogBuffer, LOG_PANEL_LEVEL};
+use crate::simulator::MarketSimulator;
+use crate::theme::{CustomTheme, Theme};
+use crate::order_book::{DepthLevels, OrderBook, TRADE_HISTORY_CAPACITY};
+use crate::order::{Order, OrderSide};
+use crate::price::{Price, DEFAULT_PRICE_DECIMALS};
+use crate::trade::Trade;
+use crate::trade_flow::{FlowTotals, TradeFlow};
+use crate::watchlist::{MidHistory, WatchlistRow, WatchlistSort};
+use crate::polymarket_orders::{
+    validate_token_id, MarketParams, PolymarketClobClient, PolymarketError, PolymarketOrderArgs, PolymarketOrderSide,
+    PolymarketOrderType, PolymarketSignatureType,
+};
+
+/// One cell of the chart grid: a glyph and its style, or the right half of a
+/// double-width glyph in the cell before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChartCell {
+    Glyph(char, Style),
+    Continuation,
+}
+
+const BLANK_CELL: ChartCell = ChartCell::Glyph(' ', Style::new());
 
+/// Draws candlestick charts into a grid of terminal cells. Columns are counted
+/// in display width, so emoji and other wide glyphs keep the rows aligned.
 pub struct TerminalChartBackend {
     pub width: u32,
     pub height: u32,
-    pub buffer: Vec<String>,
+    /// Market named in the header, e.g. "ETH/USDT".
+    pub symbol: String,
+    /// Timeframe of the candles drawn, also named in the header.
+    pub timeframe: ChartTimeframe,
+    /// Decimals the header's price is shown with.
+    pub price_decimals: usize,
+    cells: Vec<Vec<ChartCell>>,
 }
 
 impl TerminalChartBackend {
@@ -26,222 +71,213 @@ impl TerminalChartBackend {
         Self {
             width,
             height,
-            buffer: vec![String::new(); height as usize],
+            symbol: "BTC/USDT".to_string(),
+            timeframe: ChartTimeframe::OneMinute,
+            price_decimals: DEFAULT_PRICE_DECIMALS,
+            cells: vec![vec![BLANK_CELL; width as usize]; height as usize],
         }
     }
+
+    /// Sets the market and timeframe the header names, and the decimals
+    /// its price is shown with.
+    pub fn set_labels(&mut self, symbol: &str, timeframe: &ChartTimeframe, price_decimals: usize) {
+        self.symbol = symbol.to_string();
+        self.timeframe = timeframe.clone();
+        self.price_decimals = price_decimals;
+    }
     
     pub fn clear(&mut self) {
-        self.buffer = vec![String::new(); self.height as usize];
+        self.cells = vec![vec![BLANK_CELL; self.width as usize]; self.height as usize];
+    }
+    
+    /// Writes `glyph` at `row`/`col`, blanking whatever half of a wide glyph
+    /// it overlaps. Returns the columns used: 0 when the glyph has no width
+    /// or does not fit.
+    fn put(&mut self, row: usize, col: usize, glyph: char, style: Style) -> usize {
+        let width = glyph.width().unwrap_or(0);
+        let Some(line) = self.cells.get_mut(row) else {
+            return 0;
+        };
+        if width == 0 || col + width > line.len() {
+            return 0;
+        }
+        
+        if col > 0 && line[col] == ChartCell::Continuation {
+            line[col - 1] = BLANK_CELL;
+        }
+        if line.get(col + width) == Some(&ChartCell::Continuation) {
+            line[col + width] = BLANK_CELL;
+        }
+        line[col] = ChartCell::Glyph(glyph, style);
+        if width == 2 {
+            line[col + 1] = ChartCell::Continuation;
+        }
+        width
+    }
+    
+    /// Writes `text` from `col` on, cut off at the right edge.
+    fn put_str(&mut self, row: usize, mut col: usize, text: &str, style: Style) {
+        for glyph in text.chars() {
+            match glyph.width() {
+                Some(0) | None => {}
+                Some(width) if col + width > self.width as usize => break,
+                Some(_) => col += self.put(row, col, glyph, style),
+            }
+        }
+    }
+    
+    fn glyph_at(&self, row: usize, col: usize) -> Option<char> {
+        match self.cells.get(row)?.get(col)? {
+            ChartCell::Glyph(glyph, _) => Some(*glyph),
+            ChartCell::Continuation => None,
+        }
     }
     
-    pub fn draw_candlestick_chart(&mut self, candlesticks: &[Candlestick], current_price: f64) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn draw_candlestick_chart(
+        &mut self,
+        candlesticks: &[Candlestick],
+        current_price: f64,
+        overlay: ChartOverlay,
+        theme: &Theme,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         if candlesticks.is_empty() || self.height < 8 || self.width < 20 {
             return Ok(());
         }
         
         self.clear();
-        for i in 0..self.height as usize {
-            self.buffer[i] = " ".repeat(self.width as usize);
-        }
-        
-        let min_price = candlesticks.iter().map(|c| c.low).fold(f64::INFINITY, f64::min);
-        let max_price = candlesticks.iter().map(|c| c.high).fold(f64::NEG_INFINITY, f64::max);
-        let price_padding = (max_price - min_price) * 0.1;
-        let adjusted_min = min_price - price_padding;
-        let adjusted_max = max_price + price_padding;
-        let price_range = adjusted_max - adjusted_min;
         
         let chart_height = self.height.saturating_sub(6);
         let chart_width = self.width.saturating_sub(12);
         let label_width = 8;
         let volume_height = 3u32;
         
-        if self.height > 0 {
-            let change_symbol = if current_price >= candlesticks.iter().rev().nth(1).map_or(current_price, |c| c.close) { "↗" } else { "↘" };
-            let header = format!("📈 BTC/USDT | ${:.2} {} | Range: ${:.0}-${:.0} | Vol: {:.0}M", 
-                current_price, change_symbol, adjusted_min, adjusted_max, 
-                candlesticks.last().map_or(0.0, |c| c.volume) / 1_000_000.0);
-            let header_truncated = if header.len() > self.width as usize {
-                header.chars().take(self.width as usize).collect()
-            } else {
-                header
-            };
-            self.buffer[0] = header_truncated;
-        }
+        // Only the newest candles fit; scale prices and volumes to those
+        let max_candles = chart_width.min(candlesticks.len() as u32) as usize;
+        let start_idx = candlesticks.len() - max_candles;
+        let visible = &candlesticks[start_idx..];
         
-        for i in 1..(chart_height + 1) {
-            let price_ratio = (chart_height - i) as f64 / chart_height as f64;
-            let price = adjusted_min + (price_ratio * price_range);
-            let price_label = format!("{:>7.0}", price);
-            
-            let i_usize = i as usize;
-            if i_usize < self.buffer.len() && price_label.len() <= label_width {
-                let mut line = price_label.clone();
-                line.push_str(" │");
-                
-                for j in (label_width + 2)..self.width as usize {
-                    if j % 5 == 0 {
-                        line.push('┄');
-                    } else {
-                        line.push(' ');
-                    }
-                }
-                
-                if line.len() < self.width as usize {
-                    let remaining = " ".repeat(self.width as usize - line.len());
-                    line.push_str(&remaining);
-                }
-                self.buffer[i_usize] = line;
-            }
-        }
+        let overlay_lines = self.overlay_series(candlesticks, overlay, theme);
+        let overlay_prices = || overlay_lines.iter().flat_map(|(series, _, _)| series.iter().skip(start_idx).flatten().copied());
+        let min_price = visible.iter().map(|c| c.low).chain(overlay_prices()).fold(f64::INFINITY, f64::min);
+        let max_price = visible.iter().map(|c| c.high).chain(overlay_prices()).fold(f64::NEG_INFINITY, f64::max);
+        let price_padding = (max_price - min_price) * 0.1;
+        let adjusted_min = min_price - price_padding;
+        let adjusted_max = max_price + price_padding;
+        let price_range = adjusted_max - adjusted_min;
         
-        let max_candles = chart_width.min(candlesticks.len() as u32);
-        let start_idx = candlesticks.len().saturating_sub(max_candles as usize);
+        let change_symbol = if current_price >= candlesticks.iter().rev().nth(1).map_or(current_price, |c| c.close) { "↗" } else { "↘" };
+        let header = format!("📈 {} {} | ${} {} | Range: ${:.0}-${:.0} | Vol: {:.0}M", 
+            self.symbol, self.timeframe.as_str(), Price(current_price).format_with(self.price_decimals), change_symbol, adjusted_min, adjusted_max, 
+            candlesticks.last().map_or(0.0, |c| c.volume) / 1_000_000.0);
+        self.put_str(0, 0, &header, Style::default().add_modifier(Modifier::BOLD));
         
-        for (col, idx) in (start_idx..candlesticks.len()).enumerate() {
-            if col >= max_candles as usize {
-                break;
-            }
-            
-            let candle = &candlesticks[idx];
-            let char_pos = label_width + 2 + col;
-            
-            let open_y = self.price_to_chart_y(candle.open, adjusted_min, adjusted_max, chart_height);
-            let close_y = self.price_to_chart_y(candle.close, adjusted_min, adjusted_max, chart_height);
-            let high_y = self.price_to_chart_y(candle.high, adjusted_min, adjusted_max, chart_height);
-            let low_y = self.price_to_chart_y(candle.low, adjusted_min, adjusted_max, chart_height);
-            
-            if high_y < low_y && high_y < self.buffer.len() && low_y < self.buffer.len() {
-                for y in high_y..=low_y {
-                    if y < self.buffer.len() && char_pos < self.width as usize {
-                        let mut line_chars: Vec<char> = self.buffer[y].chars().collect();
-                        if char_pos < line_chars.len() {
-                            line_chars[char_pos] = '│';
-                            self.buffer[y] = line_chars.into_iter().collect();
-                        }
-                    }
-                }
-            }
-            
-            let body_start = open_y.min(close_y);
-            let body_end = open_y.max(close_y);
-            let is_bullish = candle.close >= candle.open;
-            let body_char = if is_bullish { '█' } else { '░' };
-            
-            for y in body_start..=body_end {
-                if y < self.buffer.len() && char_pos < self.width as usize {
-                    let mut line_chars: Vec<char> = self.buffer[y].chars().collect();
-                    if char_pos < line_chars.len() {
-                        line_chars[char_pos] = body_char;
-                        self.buffer[y] = line_chars.into_iter().collect();
-                    }
-                }
+        // Price labels and grid: row 1 is the top of the range and row
+        // `chart_height` the bottom, matching `price_to_chart_y`
+        let grid_style = Style::default().fg(theme.grid);
+        for row in 1..=chart_height as usize {
+            let price = adjusted_max - price_range * (row - 1) as f64 / (chart_height - 1) as f64;
+            self.put_str(row, 0, &format!("{:>7.0} │", price), Style::default());
+            for col in (label_width + 2..self.width as usize).filter(|col| col % 5 == 0) {
+                self.put(row, col, '┄', grid_style);
             }
         }
         
-        // Draw moving averages
-        let ma7 = self.calculate_moving_average(candlesticks, 7);
-        let ma25 = self.calculate_moving_average(candlesticks, 25);
+        let candle_style = |candle: &Candlestick| {
+            Style::default().fg(if candle.close >= candle.open { theme.bid } else { theme.ask })
+        };
+        let to_row = |price: f64| Self::price_to_chart_y(price, adjusted_min, adjusted_max, chart_height);
         
-        // Draw MA7 (blue dots)
-        for (col, idx) in (start_idx..candlesticks.len()).enumerate() {
-            if col >= max_candles as usize || idx >= ma7.len() {
-                break;
+        for (col, candle) in visible.iter().enumerate() {
+            let x = label_width + 2 + col;
+            let style = candle_style(candle);
+            for y in to_row(candle.high)..=to_row(candle.low) {
+                self.put(y, x, '│', style);
             }
             
-            let ma_price = ma7[idx];
-            if ma_price.is_nan() {
-                continue;
-            }
-            
-            let y = self.price_to_chart_y(ma_price, adjusted_min, adjusted_max, chart_height);
-            let char_pos = label_width + 2 + col;
-            
-            if y < self.buffer.len() && char_pos < self.width as usize {
-                let mut line_chars: Vec<char> = self.buffer[y].chars().collect();
-                if char_pos < line_chars.len() && line_chars[char_pos] == ' ' {
-                    line_chars[char_pos] = '●';
-                    self.buffer[y] = line_chars.into_iter().collect();
-                }
+            let (open_y, close_y) = (to_row(candle.open), to_row(candle.close));
+            let body = if candle.close >= candle.open { '█' } else { '░' };
+            for y in open_y.min(close_y)..=open_y.max(close_y) {
+                self.put(y, x, body, style);
             }
         }
         
-        // Draw MA25 (yellow dots)
-        for (col, idx) in (start_idx..candlesticks.len()).enumerate() {
-            if col >= max_candles as usize || idx >= ma25.len() {
-                break;
-            }
-            
-            let ma_price = ma25[idx];
-            if ma_price.is_nan() {
-                continue;
-            }
-            
-            let y = self.price_to_chart_y(ma_price, adjusted_min, adjusted_max, chart_height);
-            let char_pos = label_width + 2 + col;
-            
-            if y < self.buffer.len() && char_pos < self.width as usize {
-                let mut line_chars: Vec<char> = self.buffer[y].chars().collect();
-                if char_pos < line_chars.len() && line_chars[char_pos] == ' ' {
-                    line_chars[char_pos] = '○';
-                    self.buffer[y] = line_chars.into_iter().collect();
+        // Draw the overlay lines as dots in the gaps between candles
+        for (series, glyph, color) in &overlay_lines {
+            for (col, price) in series.iter().skip(start_idx).enumerate() {
+                let Some(price) = price else {
+                    continue;
+                };
+                let (y, x) = (to_row(*price), label_width + 2 + col);
+                if matches!(self.glyph_at(y, x), Some(' ' | '┄')) {
+                    self.put(y, x, *glyph, Style::default().fg(*color));
                 }
             }
         }
         
-        // Draw volume bars below the chart
-        let volume_start_y = chart_height + 1;
-        let max_volume = candlesticks.iter().map(|c| c.volume).fold(0.0, f64::max);
-        
-        for (col, idx) in (start_idx..candlesticks.len()).enumerate() {
-            if col >= max_candles as usize {
-                break;
-            }
-            
-            let candle = &candlesticks[idx];
+        // Volume bars, dimmed, rising from the bottom of the band below the chart
+        let volume_bottom = (chart_height + volume_height) as usize;
+        let max_volume = visible.iter().map(|c| c.volume).fold(0.0, f64::max);
+        for (col, candle) in visible.iter().enumerate() {
             let volume_ratio = if max_volume > 0.0 { candle.volume / max_volume } else { 0.0 };
-            let volume_height_scaled = (volume_ratio * volume_height as f64) as usize;
-            let char_pos = label_width + 2 + col;
-            
-            for v in 0..volume_height_scaled {
-                let y = volume_start_y + v as u32;
-                if y < self.buffer.len() as u32 && char_pos < self.width as usize {
-                    let mut line_chars: Vec<char> = self.buffer[y as usize].chars().collect();
-                    if char_pos < line_chars.len() {
-                        line_chars[char_pos] = '▄';
-                        self.buffer[y as usize] = line_chars.into_iter().collect();
-                    }
-                }
+            let bars = (volume_ratio * volume_height as f64) as usize;
+            let style = candle_style(candle).add_modifier(Modifier::DIM);
+            for v in 0..bars {
+                self.put(volume_bottom - v, label_width + 2 + col, '▄', style);
             }
         }
         
         // Draw footer with legend and timeframe
-        let footer_idx = self.height.saturating_sub(1) as usize;
-        if footer_idx < self.buffer.len() {
-            let footer = format!("█ Candles | ● MA7 | ○ MA25 | ▄ Volume | Time: {}", 
-                candlesticks.last()
-                    .map(|c| c.timestamp.format("%H:%M").to_string())
-                    .unwrap_or_else(|| "N/A".to_string())
-            );
-            let footer_truncated = if footer.len() > self.width as usize {
-                footer.chars().take(self.width as usize).collect()
-            } else {
-                footer
-            };
-            self.buffer[footer_idx] = footer_truncated;
-        }
+        let footer = format!("█ Candles | {} | ▄ Volume | Time: {}", 
+            overlay.legend(),
+            candlesticks.last()
+                .map(|c| c.timestamp.format("%H:%M").to_string())
+                .unwrap_or_else(|| "N/A".to_string())
+        );
+        self.put_str(self.height as usize - 1, 0, &footer, Style::default());
         
         Ok(())
     }
     
-    fn price_to_chart_y(&self, price: f64, min_price: f64, max_price: f64, chart_height: u32) -> usize {
-        if max_price <= min_price {
-            return chart_height as usize / 2;
+    /// Chart row for `price`: row 1 for `max_price` down to row `chart_height`
+    /// for `min_price`, with row 0 left to the header.
+    fn price_to_chart_y(price: f64, min_price: f64, max_price: f64, chart_height: u32) -> usize {
+        if max_price <= min_price || chart_height < 2 {
+            return (chart_height as usize).div_ceil(2);
         }
         
-        let normalized = (price - min_price) / (max_price - min_price);
-        let y_pos = ((1.0 - normalized) * chart_height as f64) as usize;
-        y_pos.min(chart_height as usize - 1).max(0)
+        let normalized = ((price - min_price) / (max_price - min_price)).clamp(0.0, 1.0);
+        1 + ((1.0 - normalized) * (chart_height - 1) as f64).round() as usize
+    }
+    
+    /// The lines `overlay` draws over the candles, each with its glyph and color.
+    fn overlay_series(
+        &self,
+        candlesticks: &[Candlestick],
+        overlay: ChartOverlay,
+        theme: &Theme,
+    ) -> Vec<(Vec<Option<f64>>, char, Color)> {
+        let [first, second, third, _] = theme.chart;
+        match overlay {
+            ChartOverlay::MovingAverages => [(7, '●', first), (25, '○', second)]
+                .into_iter()
+                .map(|(period, glyph, color)| {
+                    let average = self.calculate_moving_average(candlesticks, period);
+                    (average.into_iter().map(|value| Some(value).filter(|v| !v.is_nan())).collect(), glyph, color)
+                })
+                .collect(),
+            ChartOverlay::Ema => vec![
+                (indicators::ema(candlesticks, 12), '●', first),
+                (indicators::ema(candlesticks, 26), '○', second),
+            ],
+            ChartOverlay::Bollinger => {
+                let bands = indicators::bollinger(candlesticks, BOLLINGER_PERIOD, BOLLINGER_WIDTH);
+                vec![
+                    (bands.upper, '╌', third),
+                    (bands.middle, '·', second),
+                    (bands.lower, '╌', third),
+                ]
+            }
+        }
     }
     
     fn calculate_moving_average(&self, data: &[Candlestick], period: usize) -> Vec<f64> {
@@ -267,8 +303,48 @@ impl TerminalChartBackend {
         ma
     }
     
+    /// The chart as styled lines, one per row.
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        self.cells
+            .iter()
+            .map(|row| {
+                let mut spans = Vec::new();
+                let mut text = String::new();
+                let mut current = Style::default();
+                for cell in row {
+                    let ChartCell::Glyph(glyph, style) = *cell else {
+                        continue;
+                    };
+                    if style != current && !text.is_empty() {
+                        spans.push(Span::styled(std::mem::take(&mut text), current));
+                    }
+                    current = style;
+                    text.push(glyph);
+                }
+                if !text.is_empty() {
+                    spans.push(Span::styled(text, current));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+    
+    /// The chart as plain text, without trailing blanks.
     pub fn render(&self) -> String {
-        self.buffer.join("\n")
+        self.cells
+            .iter()
+            .map(|row| {
+                let line: String = row
+                    .iter()
+                    .filter_map(|cell| match cell {
+                        ChartCell::Glyph(glyph, _) => Some(*glyph),
+                        ChartCell::Continuation => None,
+                    })
+                    .collect();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
 
@@ -283,11 +359,12 @@ fn format_number_with_color(value: f64, is_percentage: bool) -> String {
     formatted
 }
 
-// Helper function to get color for a number
-fn get_number_color(value: f64) -> Color {
-    if value >= 0.0 { Color::Green } else { Color::Red }
+// Helper function to format an optional latency percentile
+fn format_latency(value: Option<u64>, unit: &str) -> String {
+    value.map_or_else(|| "n/a".to_string(), |v| format!("{}{}", v, unit))
 }
 
+
 #[derive(Debug, Clone)]
 pub struct CoinType {
     pub symbol: String,
@@ -318,7 +395,6 @@ pub struct RealTimeData {
     pub last_update: chrono::DateTime<chrono::Utc>,
     pub is_connected: bool,
     pub connection_status: String,
-    pub update_frequency: Duration,
 }
 
 impl RealTimeData {
@@ -327,7 +403,6 @@ impl RealTimeData {
             last_update: chrono::Utc::now(),
             is_connected: false,
             connection_status: "Disconnected".to_string(),
-            update_frequency: Duration::from_secs(2),
         }
     }
     
@@ -361,6 +436,84 @@ impl Candlestick {
     }
 }
 
+/// Lines drawn over the candles on the Charts tab.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartOverlay {
+    /// Simple moving averages of 7 and 25 candles.
+    MovingAverages,
+    /// Exponential moving averages of 12 and 26 candles.
+    Ema,
+    /// Bollinger bands of `BOLLINGER_PERIOD` candles.
+    Bollinger,
+}
+
+impl ChartOverlay {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChartOverlay::MovingAverages => "MA 7/25",
+            ChartOverlay::Ema => "EMA 12/26",
+            ChartOverlay::Bollinger => "Bollinger 20/2",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ChartOverlay::MovingAverages => ChartOverlay::Ema,
+            ChartOverlay::Ema => ChartOverlay::Bollinger,
+            ChartOverlay::Bollinger => ChartOverlay::MovingAverages,
+        }
+    }
+
+    fn legend(&self) -> &'static str {
+        match self {
+            ChartOverlay::MovingAverages => "● MA7 | ○ MA25",
+            ChartOverlay::Ema => "● EMA12 | ○ EMA26",
+            ChartOverlay::Bollinger => "╌ BB upper/lower | · BB mid",
+        }
+    }
+}
+
+/// The oscillator shown in a pane under the Charts tab's price chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndicatorPane {
+    Off,
+    Rsi,
+    Macd,
+}
+
+impl IndicatorPane {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndicatorPane::Off => "Off",
+            IndicatorPane::Rsi => "RSI 14",
+            IndicatorPane::Macd => "MACD 12/26/9",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            IndicatorPane::Off => IndicatorPane::Rsi,
+            IndicatorPane::Rsi => IndicatorPane::Macd,
+            IndicatorPane::Macd => IndicatorPane::Off,
+        }
+    }
+}
+
+const BOLLINGER_PERIOD: usize = 20;
+const BOLLINGER_WIDTH: f64 = 2.0;
+const RSI_PERIOD: usize = 14;
+
+/// `(x, value)` points for the part of an indicator `series` from candle
+/// `start` on, with x counted from `start`. Candles without a value are skipped.
+pub fn indicator_points(series: &[Option<f64>], start: usize) -> Vec<(f64, f64)> {
+    series
+        .iter()
+        .enumerate()
+        .skip(start)
+        .filter_map(|(index, value)| Some(((index - start) as f64, (*value)?)))
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ChartTimeframe {
     OneMinute,
@@ -395,16 +548,103 @@ impl ChartTimeframe {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AlertType {
     PriceAbove(f64),      // Alert when price goes above target
     PriceBelow(f64),      // Alert when price goes below target
     PercentageChange(f64), // Alert on percentage change
     VolumeSpike(f64),     // Alert on volume spike
     PriceCross(f64),      // Alert when price crosses a level
+    /// The book's spread is wider than this.
+    SpreadAbove(f64),
+    /// The book's mid price crosses this level.
+    MidPriceCross(f64),
+    /// Less than `qty` rests in the best `levels` levels of `side`.
+    LiquidityBelow { side: OrderSide, levels: usize, qty: f64 },
+    /// One side's trades within `window_secs` add up to `notional` or more;
+    /// a zero window looks at single trades.
+    WhaleTrade { notional: f64, window_secs: u64 },
 }
 
-#[derive(Debug, Clone)]
+/// The live book as book alerts see it: spread, mid and the best levels.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookMetrics {
+    pub spread: Option<f64>,
+    pub mid_price: Option<f64>,
+    pub bids: DepthLevels,
+    pub asks: DepthLevels,
+}
+
+impl BookMetrics {
+    /// Takes `levels` levels a side from `book`.
+    pub fn from_book(book: &OrderBook, levels: usize) -> Self {
+        let (bids, asks) = book.get_market_depth(levels);
+        Self { spread: book.get_spread(), mid_price: book.get_mid_price(), bids, asks }
+    }
+
+    /// Quantity resting in the best `levels` levels of `side`.
+    pub fn liquidity(&self, side: OrderSide, levels: usize) -> f64 {
+        let book_side = match side {
+            OrderSide::Bid => &self.bids,
+            OrderSide::Ask => &self.asks,
+        };
+        book_side.iter().take(levels).map(|(_, quantity)| quantity).sum()
+    }
+}
+
+/// What an alert does after it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum AlertBehavior {
+    /// Fires once, then deactivates.
+    #[default]
+    OneShot,
+    /// Fires whenever its condition holds, at most once per `cooldown`.
+    Repeating { cooldown: Duration },
+    /// Fires, then waits for its condition to stop holding (price back on the
+    /// other side of the level) before it can fire again.
+    ReArmOnCross,
+}
+
+impl AlertBehavior {
+    /// Parses the flags that may follow an alert's value: `--repeat <cooldown>`
+    /// (e.g. `60s`, `5m`, `1h`, or plain seconds) or `--rearm`. The remaining
+    /// words are returned as the message.
+    pub fn parse_flags<'a>(args: &[&'a str]) -> Result<(AlertBehavior, Vec<&'a str>), String> {
+        let mut behavior = AlertBehavior::OneShot;
+        let mut message = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match *arg {
+                "--repeat" => {
+                    let cooldown = args.next().ok_or("Usage: --repeat <cooldown>, e.g. --repeat 60s")?;
+                    behavior = AlertBehavior::Repeating { cooldown: parse_cooldown(cooldown)? };
+                }
+                "--rearm" => behavior = AlertBehavior::ReArmOnCross,
+                word => message.push(word),
+            }
+        }
+        Ok((behavior, message))
+    }
+}
+
+fn parse_cooldown(text: &str) -> Result<Duration, String> {
+    let (number, unit) = match text.char_indices().find(|(_, c)| c.is_ascii_alphabetic()) {
+        Some((index, _)) => text.split_at(index),
+        None => (text, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => return Err(format!("Invalid cooldown '{}': use s, m or h", text)),
+    };
+    match number.parse::<f64>() {
+        Ok(value) if value > 0.0 && value.is_finite() => Ok(Duration::from_secs_f64(value * multiplier)),
+        _ => Err(format!("Invalid cooldown '{}'", text)),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceAlert {
     pub id: u64,
     pub symbol: String,
@@ -414,6 +654,18 @@ pub struct PriceAlert {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub triggered_at: Option<chrono::DateTime<chrono::Utc>>,
     pub triggered_count: u32,
+    pub behavior: AlertBehavior,
+    /// For `ReArmOnCross`: cleared when the alert fires, set again once its
+    /// condition stops holding.
+    pub armed: bool,
+    /// For `PercentageChange`: the price moves are measured from, set when
+    /// the alert is created and again each time it fires.
+    pub reference_price: Option<f64>,
+    /// For `MidPriceCross`: the mid at the previous check.
+    pub last_mid: Option<f64>,
+    /// For `WhaleTrade`: the trades still in its window.
+    #[serde(skip)]
+    pub trade_flow: TradeFlow,
 }
 
 impl PriceAlert {
@@ -427,45 +679,201 @@ impl PriceAlert {
             created_at: chrono::Utc::now(),
             triggered_at: None,
             triggered_count: 0,
+            behavior: AlertBehavior::OneShot,
+            armed: true,
+            reference_price: None,
+            last_mid: None,
+            trade_flow: TradeFlow::default(),
         }
     }
+
+    pub fn with_behavior(mut self, behavior: AlertBehavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+
+    /// Starts a `PercentageChange` alert's reference at `price`. Without one,
+    /// the first usable previous price passed to `check_trigger` is taken.
+    pub fn with_reference_price(mut self, price: f64) -> Self {
+        self.reference_price = Some(price).filter(|price| *price > 0.0);
+        self
+    }
     
+    /// Book alerts never fire here; see `check_trigger_with_book`.
     pub fn check_trigger(&mut self, current_price: f64, previous_price: f64, volume: f64) -> bool {
+        self.check_trigger_at(current_price, previous_price, volume, None, chrono::Utc::now())
+    }
+
+    pub fn check_trigger_with_book(&mut self, current_price: f64, previous_price: f64, volume: f64, book: &BookMetrics) -> bool {
+        self.check_trigger_at(current_price, previous_price, volume, Some(book), chrono::Utc::now())
+    }
+
+    /// `check_trigger` with the book, if there is one, and an explicit clock,
+    /// which cooldowns are measured on.
+    pub fn check_trigger_at(
+        &mut self,
+        current_price: f64,
+        previous_price: f64,
+        volume: f64,
+        book: Option<&BookMetrics>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
         if !self.is_active {
             return false;
         }
         
-        let triggered = match &self.alert_type {
+        let previous_mid = self.last_mid;
+        if let Some(mid) = book.and_then(|book| book.mid_price) {
+            self.last_mid = Some(mid);
+        }
+        
+        let condition = match &self.alert_type {
             AlertType::PriceAbove(target) => current_price > *target,
             AlertType::PriceBelow(target) => current_price < *target,
             AlertType::PercentageChange(threshold) => {
-                let change = ((current_price - previous_price) / previous_price).abs() * 100.0;
-                change >= *threshold
+                // percent_change is 0 until there is a usable reference price
+                let reference = match self.reference_price {
+                    Some(reference) if reference > 0.0 => reference,
+                    _ => *self.reference_price.insert(previous_price),
+                };
+                let change = percent_change(current_price - reference, reference).abs();
+                change > 0.0 && change >= *threshold
             },
             AlertType::VolumeSpike(threshold) => volume > *threshold,
             AlertType::PriceCross(target) => {
                 (previous_price < *target && current_price >= *target) ||
                 (previous_price > *target && current_price <= *target)
             },
+            AlertType::SpreadAbove(limit) => book.and_then(|book| book.spread).is_some_and(|spread| spread > *limit),
+            AlertType::MidPriceCross(target) => match (previous_mid, book.and_then(|book| book.mid_price)) {
+                (Some(previous), Some(mid)) => {
+                    (previous < *target && mid >= *target) || (previous > *target && mid <= *target)
+                }
+                _ => false,
+            },
+            AlertType::LiquidityBelow { side, levels, qty } => {
+                book.is_some_and(|book| book.liquidity(*side, *levels) < *qty)
+            }
+            // Driven by the trades passed to `check_trade` instead
+            AlertType::WhaleTrade { .. } => return false,
+        };
+        
+        let triggered = self.fire(condition, now);
+        if triggered && matches!(self.alert_type, AlertType::PercentageChange(_)) {
+            self.reference_price = Some(current_price);
+        }
+        triggered
+    }
+
+    /// Feeds `trade` to a `WhaleTrade` alert. When its side's flow over the
+    /// window reaches the notional and the alert fires, returns that flow,
+    /// which then starts over. Cooldowns are measured on trade time.
+    pub fn check_trade(&mut self, trade: &Trade) -> Option<FlowTotals> {
+        let AlertType::WhaleTrade { notional, window_secs } = self.alert_type else {
+            return None;
+        };
+        if !self.is_active {
+            return None;
+        }
+        let flow = self.trade_flow.record(trade, Duration::from_secs(window_secs));
+        let now = chrono::DateTime::from_timestamp_millis(trade.timestamp as i64).unwrap_or_else(chrono::Utc::now);
+        if !self.fire(flow.notional >= notional, now) {
+            return None;
+        }
+        self.trade_flow.clear(flow.side);
+        Some(flow)
+    }
+
+    /// Applies the alert's behavior to `condition`, recording a firing.
+    fn fire(&mut self, condition: bool, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let triggered = match self.behavior {
+            AlertBehavior::OneShot => condition,
+            AlertBehavior::Repeating { cooldown } => {
+                condition
+                    && self.triggered_at.is_none_or(|last| {
+                        (now - last).to_std().is_ok_and(|elapsed| elapsed >= cooldown)
+                    })
+            }
+            AlertBehavior::ReArmOnCross => {
+                let fire = condition && self.armed;
+                self.armed = !condition;
+                fire
+            }
         };
         
         if triggered {
-            self.triggered_at = Some(chrono::Utc::now());
+            self.triggered_at = Some(now);
             self.triggered_count += 1;
-            self.is_active = false; // Auto-disable after triggering
+            if self.behavior == AlertBehavior::OneShot {
+                self.is_active = false;
+            }
         }
         
         triggered
     }
 }
 
+/// One firing of an alert, as listed in the Alerts tab's history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertTrigger {
+    pub alert_id: u64,
+    pub symbol: String,
+    pub message: String,
+    /// The price the alert fired at.
+    pub price: f64,
+    pub triggered_at: chrono::DateTime<chrono::Utc>,
+    /// The alert's trigger count, this firing included.
+    pub trigger_count: u32,
+}
+
+impl AlertTrigger {
+    /// The firing `alert` just recorded, at `price`.
+    fn of(alert: &PriceAlert, price: f64) -> Self {
+        Self {
+            alert_id: alert.id,
+            symbol: alert.symbol.clone(),
+            message: alert.message.clone(),
+            price,
+            triggered_at: alert.triggered_at.unwrap_or_else(chrono::Utc::now),
+            trigger_count: alert.triggered_count,
+        }
+    }
+}
+
+/// Alert firings kept in the history unless the settings say otherwise.
+pub const DEFAULT_ALERT_HISTORY_LIMIT: usize = 100;
+
+/// Latest alert firings listed under the alerts.
+const ALERT_HISTORY_ROWS: usize = 6;
+
+/// The alert file's contents. `next_alert_id` is kept so ids of removed
+/// alerts are not handed out again after a restart.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedAlerts {
+    next_alert_id: u64,
+    alerts: Vec<PriceAlert>,
+    /// Alert firings, oldest first. Files saved before there was a history
+    /// have none.
+    #[serde(default)]
+    history: Vec<AlertTrigger>,
+}
+
+/// `alerts.json` in the config directory.
+pub fn default_alerts_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("alerts.json"))
+}
+
+/// `history` in the config directory: one command per line, oldest first.
+pub fn default_history_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("history"))
+}
+
 #[derive(Debug, Clone)]
 pub struct BinanceWebSocket {
     pub is_connected: bool,
     pub connection_status: String,
     pub last_message: chrono::DateTime<chrono::Utc>,
-    pub message_count: u64,
-    pub error_count: u64,
+    pub metrics: StreamMetrics,
 }
 
 impl BinanceWebSocket {
@@ -474,8 +882,7 @@ impl BinanceWebSocket {
             is_connected: false,
             connection_status: "Disconnected".to_string(),
             last_message: chrono::Utc::now(),
-            message_count: 0,
-            error_count: 0,
+            metrics: StreamMetrics::new(),
         }
     }
     
@@ -487,29 +894,79 @@ impl BinanceWebSocket {
         }
     }
     
-    pub fn record_message(&mut self) {
-        self.message_count += 1;
-        self.last_message = chrono::Utc::now();
+    pub fn record_message(&mut self, stream: &str) {
+        let now = chrono::Utc::now();
+        self.metrics.record_message(stream, None, now.timestamp_millis() as u64);
+        self.last_message = now;
     }
     
     pub fn record_error(&mut self) {
-        self.error_count += 1;
+        self.metrics.record_parse_failure();
+    }
+
+    /// Replaces the displayed metrics with a snapshot taken from a live
+    /// `BinanceWebSocketClient::metrics()`.
+    pub fn update_metrics(&mut self, metrics: StreamMetrics) {
+        self.metrics = metrics;
+        self.last_message = chrono::Utc::now();
     }
 }
 
+/// Number of price levels per side shown on the Order Book tab.
+pub const BOOK_DEPTH_LEVELS: usize = 20;
+
+/// Number of most recent orders listed on the Orders tab.
+pub const ORDER_HISTORY_ROWS: usize = 10;
+
+/// Help overlay lines scrolled per mouse wheel notch.
+const HELP_SCROLL_LINES: i16 = 3;
+
+/// Help sections the overlay opens at, depending on what is being done.
+pub const HELP_ORDER_INPUT_SECTION: &str = "📝 ORDER INPUT MODE:";
+pub const HELP_ALERTS_SECTION: &str = "🔔 ALERT COMMANDS:";
+
+/// A Polymarket book loaded with `pm book <token_id>`, shown on the Order Book
+/// tab in place of the selected coin's book.
+pub struct PolymarketBookView {
+    pub token_id: String,
+    pub book: OrderBook,
+    pub params: MarketParams,
+}
+
 pub struct App {
+    /// Book for the selected coin.
     pub order_book: OrderBook,
+    pub polymarket_book: Option<PolymarketBookView>,
+    /// Books for the other coins, keyed by coin symbol, kept while inactive.
+    pub order_books: HashMap<String, OrderBook>,
+    pub book_cursor: Option<usize>,
+    /// Levels skipped from the touch on each side of the Order Book tab.
+    pub book_scroll: usize,
     pub selected_tab: usize,
     pub tabs: Vec<String>,
-    pub user_command: String,
+    pub user_command: CommandLine,
+    /// Where the command history is kept between runs; `None` keeps it in
+    /// memory only.
+    pub history_path: Option<PathBuf>,
     pub real_time_data: VecDeque<String>,
+    /// The chart's candles: `minute_candles` resampled to `selected_timeframe`.
     pub candlestick_data: Vec<Candlestick>,
+    /// One-minute candles, oldest first, that every timeframe is derived from.
+    pub minute_candles: Vec<Candlestick>,
     pub market_data: MarketData,
     pub order_history: VecDeque<OrderRecord>,
     pub polymarket_client: Option<PolymarketClobClient>,
     pub current_market: String,
+    /// Decimals prices are shown with, keyed by coin symbol. Coins without an
+    /// entry use `DEFAULT_PRICE_DECIMALS`.
+    pub price_decimals: HashMap<String, usize>,
     pub order_input: OrderInput,
+    pub trading_mode: TradingMode,
     pub help_mode: bool,
+    /// Lines the help overlay is scrolled down by.
+    pub help_scroll: u16,
+    /// Regions of the last frame, for the mouse; see `click_at` and `scroll_at`.
+    pub layout: ScreenLayout,
     pub last_update: chrono::DateTime<chrono::Utc>,
     pub available_coins: Vec<CoinType>,
     pub selected_coin_index: usize,
@@ -517,45 +974,948 @@ pub struct App {
     pub auto_refresh: bool,
     pub refresh_interval: Duration,
     pub selected_timeframe: ChartTimeframe,
+    pub chart_overlay: ChartOverlay,
+    pub indicator_pane: IndicatorPane,
     pub price_alerts: Vec<PriceAlert>,
+    /// Index into `price_alerts` of the Alerts tab selection.
+    pub alerts_selected: usize,
+    /// Alert firings, oldest first, at most `alert_history_limit`.
+    pub alert_history: VecDeque<AlertTrigger>,
+    pub alert_history_limit: usize,
+    /// Recent mids per symbol, sampled each refresh, for the Watchlist.
+    pub watchlist_mids: HashMap<String, MidHistory>,
+    pub watchlist_sort: WatchlistSort,
+    /// Row of the Watchlist selection, in its current sort order.
+    pub watchlist_selected: usize,
+    /// Warnings and errors captured from the log, for the Logs tab. It stays
+    /// empty unless `logging::init` set up the capture.
+    pub log_buffer: LogBuffer,
+    /// Least severe level the Logs tab shows.
+    pub log_filter: tracing::Level,
     pub next_alert_id: u64,
+    /// Where alerts are saved when they change and on exit; `None` keeps them
+    /// in memory only.
+    pub alerts_path: Option<PathBuf>,
     pub alert_sound_enabled: bool,
+    /// Coin selected at startup; see `AppConfig::default_coin`.
+    pub default_coin: String,
+    /// Where settings are loaded from at startup and saved to when changed on
+    /// the Settings tab; `None` keeps them in memory only.
+    pub config_path: Option<PathBuf>,
+    /// The config file's simulator seed, saved back as it was read.
+    pub config_seed: Option<u64>,
+    /// The config file's log level and file, saved back as they were read;
+    /// logging is set up from them at startup.
+    pub config_log_level: String,
+    pub config_log_file: Option<PathBuf>,
+    /// Index into `Setting::ALL` of the Settings tab selection.
+    pub settings_selected: usize,
+    /// Colors every panel is drawn with; `theme <name>` switches it.
+    pub theme: Theme,
+    /// Valid themes from the config file, offered after the presets.
+    pub custom_themes: Vec<CustomTheme>,
     pub binance_ws: BinanceWebSocket,
+    /// The live Binance market stream for the selected coin, running while
+    /// real data is on.
+    pub market_stream: Option<MarketStreamHandle>,
+    /// Trades the market stream's book had recorded at the last drain, so
+    /// only new ones reach the whale alerts.
+    pub market_stream_trades_seen: u64,
+    /// Websocket endpoint the market stream connects to.
+    pub market_stream_url: String,
+    /// Runtime the market stream and HTTP requests run on as tasks. Without
+    /// one, the stream gets a thread and runtime of its own and requests use
+    /// a runtime shared by the process.
+    pub runtime: Option<tokio::runtime::Handle>,
+    requests: RequestQueue,
+    /// Set while an exchange order is being placed, so a second Enter does
+    /// not send it twice.
+    pub order_in_flight: bool,
+    pub binance_rest: BinanceRestClient,
+    /// exchangeInfo filters keyed by Binance symbol, e.g. "BTCUSDT".
+    pub symbol_filters: HashMap<String, SymbolFilters>,
+    pub binance_orders: Option<BinanceOrderClient>,
+    pub user_data_stream: Option<UserDataStreamHandle>,
+    pub polymarket_user_channel: Option<UserChannelHandle>,
+    pub depth_bar_mode: DepthBarMode,
+    pub book_view: BookView,
+    /// The trades tape as it was when paused; `None` while it follows the book.
+    pub tape_paused: Option<Vec<Trade>>,
+    pub large_trade_notional: f64,
+    /// The Orders tab selection, by `OrderRecord::order_id` so it stays on the
+    /// same order as new rows arrive.
+    pub selected_order_id: Option<String>,
+    /// First Orders tab row shown.
+    pub orders_scroll: usize,
     pub use_real_data: bool,
     pub terminal_chart: TerminalChartBackend,
+    pub commands: CommandRegistry<App>,
+    /// Source of every simulated price, order and candle; seeded by `with_seed`.
+    pub simulator: MarketSimulator,
 }
 
-pub struct MarketData {
-    pub current_price: f64,
-    pub price_change: f64,
-    pub price_change_percent: f64,
-    pub volume_24h: f64,
-    pub high_24h: f64,
-    pub low_24h: f64,
-    pub market_cap: f64,
+/// Refresh intervals without a feed update before the feed counts as stale.
+pub const STALE_FEED_INTERVALS: u32 = 3;
+
+/// Most messages kept in `App::real_time_data`.
+const REAL_TIME_LOG_CAPACITY: usize = 10;
+
+/// Widest depth bar drawn next to a book level, in cells.
+const DEPTH_BAR_WIDTH: usize = 20;
+
+/// How long a GTD order lives when no expiration was given.
+const DEFAULT_GTD_DURATION: Duration = Duration::from_secs(3600);
+
+/// Price levels per side plotted by the depth chart.
+const DEPTH_CHART_LEVELS: usize = 50;
+
+/// Trades the tape keeps on screen, and freezes when paused.
+pub const TRADE_TAPE_ROWS: usize = 50;
+
+/// Notional at or above which a tape print is highlighted, until `tape large`
+/// changes it.
+const DEFAULT_LARGE_TRADE_NOTIONAL: f64 = 50_000.0;
+
+/// Minute klines fetched by the Binance backfill, the most one request returns.
+const BACKFILL_MINUTES: u16 = 1000;
+
+/// Most decimals the `precision` command accepts.
+const MAX_PRICE_DECIMALS: usize = 8;
+
+/// Bounds for the `refresh` command, in seconds.
+const MIN_REFRESH_INTERVAL: f64 = 0.1;
+const MAX_REFRESH_INTERVAL: f64 = 3600.0;
+
+/// Every command the command bar understands, in the order `help` lists them.
+pub fn builtin_commands() -> CommandRegistry<App> {
+    const ALERT_ARGS: &[Arg] = &[Arg::Required("price"), Arg::Rest("message")];
+
+    let mut commands = CommandRegistry::new();
+    for command in [
+        Command::new("help", &[], "Toggle help mode", |app: &mut App, _| app.toggle_help()),
+        Command::new("clear", &[], "Clear command input", |app, _| app.clear_user_command()),
+        Command::new("coin", &[Arg::Required("symbol")], "Switch to a coin by symbol", |app, args| {
+            match app.available_coins.iter().position(|coin| coin.symbol.eq_ignore_ascii_case(args[0])) {
+                Some(index) => app.select_coin_by_index(index),
+                None => app.log(format!("Unknown coin: {}", args[0])),
+            }
+        }),
+        Command::new("coin add", &[Arg::Required("symbol")], "Add a coin to the coin list", |app, args| {
+            if let Err(reason) = app.add_coin(args[0]) {
+                app.log(reason);
+            }
+        }),
+        Command::new("coin remove", &[Arg::Required("symbol")], "Remove a coin and its order book", |app, args| {
+            if let Err(reason) = app.remove_coin(args[0]) {
+                app.log(reason);
+            }
+        }),
+        Command::new("add_orders", &[], "Add sample orders", |app, _| {
+            app.add_sample_orders();
+            app.log("Sample orders added".to_string());
+        }),
+        Command::new("place_order", &[], "Activate order input mode", |app, _| {
+            app.order_input.active = true;
+            app.log("Order input mode activated".to_string());
+        }),
+        Command::new("cancel_order", &[], "Enter order cancellation mode", |app, _| {
+            app.log("Order cancellation mode".to_string());
+        }),
+        Command::new("market_data", &[], "Update market data", |app, _| {
+            app.update_market_data();
+            app.log("Market data updated".to_string());
+        }),
+        Command::new("refresh", &[Arg::Required("seconds")], "Set the auto-refresh interval", |app, args| {
+            match parse_refresh_interval(args[0]) {
+                Ok(interval) => {
+                    app.refresh_interval = interval;
+                    app.log(format!("Refresh interval set to {:.1}s", interval.as_secs_f64()));
+                }
+                Err(reason) => app.log(reason),
+            }
+        }),
+        Command::new(
+            "precision",
+            &[Arg::Required("decimals")],
+            "Show the selected coin's prices with this many decimals",
+            |app, args| match args[0].parse::<usize>() {
+                Ok(decimals) if decimals <= MAX_PRICE_DECIMALS => {
+                    let coin = app.selected_symbol();
+                    app.log(format!("{} prices shown with {} decimals", coin, decimals));
+                    app.price_decimals.insert(coin, decimals);
+                }
+                _ => app.log(format!("Usage: precision <0-{}>", MAX_PRICE_DECIMALS)),
+            },
+        ),
+        Command::new("theme", &[Arg::Optional("theme")], "Switch color theme, or list the themes", |app, args| {
+            match args.first() {
+                Some(name) => match app.set_theme(name) {
+                    Ok(()) => {
+                        app.log(format!("Theme: {}", app.theme.name));
+                        app.persist_config();
+                    }
+                    Err(reason) => app.log(reason),
+                },
+                None => {
+                    let names: Vec<String> = app.themes().into_iter().map(|theme| theme.name).collect();
+                    app.log(format!("Themes: {} (current: {})", names.join(", "), app.theme.name));
+                }
+            }
+        }),
+        Command::new(
+            "export",
+            &[Arg::Required("what"), Arg::Required("path"), Arg::Optional("--force")],
+            "Write orders, trades, alerts or depth to a CSV file",
+            |app, args| {
+                let force = args.contains(&"--force");
+                let args: Vec<&str> = args.iter().copied().filter(|arg| *arg != "--force").collect();
+                let result = match args[..] {
+                    [what, path] => app.export(what, path, force),
+                    _ => Err("Usage: export <what> <path> [--force]".to_string()),
+                };
+                match result {
+                    Ok(message) | Err(message) => app.log(message),
+                }
+            },
+        ),
+        Command::new("bars", &[], "Toggle depth bars between cumulative and per-level size", |app, _| {
+            app.toggle_depth_bar_mode()
+        }),
+        Command::new(
+            "tape large",
+            &[Arg::Required("notional")],
+            "Highlight trades tape prints worth at least this much",
+            |app, args| match parse_positive("notional", args[0]) {
+                Ok(notional) => {
+                    app.large_trade_notional = notional;
+                    app.log(format!("Highlighting prints of ${:.0} or more", notional));
+                }
+                Err(reason) => app.log(reason),
+            },
+        ),
+        Command::new(
+            "pm book",
+            &[Arg::Optional("token_id")],
+            "Show a Polymarket order book (pm book to go back)",
+            |app, args| app.load_polymarket_book(args.first().copied().unwrap_or_default()),
+        ),
+        Command::new("pm cancel-all", &[], "Cancel all open Polymarket orders", |app, _| {
+            app.cancel_all_polymarket_orders()
+        }),
+        Command::new("pm stream", &[], "Follow Polymarket order status over the user channel", |app, _| {
+            app.start_polymarket_user_channel()
+        }),
+        Command::new(
+            "expire",
+            &[Arg::Required("minutes")],
+            "Make the order GTD, expiring after <minutes>",
+            |app, args| match args[0].parse::<u32>() {
+                Ok(minutes) if minutes > 0 => {
+                    let expiration = chrono::Utc::now().timestamp() + i64::from(minutes) * 60;
+                    app.order_input.expiration = Some(expiration);
+                    app.order_input.order_type = PolymarketOrderType::GTD;
+                    app.log(format!("GTD order expires {}", format_expiration(Some(expiration))));
+                }
+                _ => app.log("Usage: expire <minutes>".to_string()),
+            },
+        ),
+        Command::new("submit_order", &[], "Submit current order", |app, _| app.submit_order()),
+        Command::new("alert above", ALERT_ARGS, "Alert when price goes above target", |app, args| {
+            app.add_alert_from_args("above", args, AlertType::PriceAbove, "price")
+        }),
+        Command::new("alert below", ALERT_ARGS, "Alert when price goes below target", |app, args| {
+            app.add_alert_from_args("below", args, AlertType::PriceBelow, "price")
+        }),
+        Command::new(
+            "alert change",
+            &[Arg::Required("percent"), Arg::Rest("message")],
+            "Alert on percentage change",
+            |app, args| app.add_alert_from_args("change", args, AlertType::PercentageChange, "percentage"),
+        ),
+        Command::new(
+            "alert volume",
+            &[Arg::Required("amount"), Arg::Rest("message")],
+            "Alert on volume spike",
+            |app, args| app.add_alert_from_args("volume", args, AlertType::VolumeSpike, "volume"),
+        ),
+        Command::new("alert cross", ALERT_ARGS, "Alert when price crosses level", |app, args| {
+            app.add_alert_from_args("cross", args, AlertType::PriceCross, "price")
+        }),
+        Command::new(
+            "alert spread",
+            &[Arg::Required("spread"), Arg::Rest("message")],
+            "Alert when the book's spread widens past a value",
+            |app, args| app.add_alert_from_args("spread", args, AlertType::SpreadAbove, "spread"),
+        ),
+        Command::new("alert mid", ALERT_ARGS, "Alert when the book's mid price crosses level", |app, args| {
+            app.add_alert_from_args("mid", args, AlertType::MidPriceCross, "price")
+        }),
+        Command::new(
+            "alert liq",
+            &[Arg::Required("bid|ask"), Arg::Required("levels"), Arg::Required("qty"), Arg::Rest("message")],
+            "Alert when the best levels of a side hold less than qty",
+            |app, args| app.add_liquidity_alert(args),
+        ),
+        Command::new(
+            "alert whale",
+            &[Arg::Required("notional"), Arg::Optional("window_secs"), Arg::Rest("message")],
+            "Alert when one side's trades within the window reach a notional",
+            |app, args| app.add_whale_alert(args),
+        ),
+        Command::new("alert list", &[], "Show active alerts", |app, _| {
+            app.log(format!("Active alerts: {}", app.get_active_alerts_count()));
+        }),
+        Command::new("alert remove", &[Arg::Required("id")], "Remove specific alert", |app, args| {
+            match args[0].parse::<u64>() {
+                Ok(id) if app.remove_price_alert(id) => app.log("Alert removed successfully".to_string()),
+                Ok(_) => app.log("Alert not found".to_string()),
+                Err(_) => app.log("Invalid alert ID".to_string()),
+            }
+        }),
+        Command::new("alert history clear", &[], "Forget the triggered alerts history", |app, _| {
+            app.clear_alert_history();
+            app.log("Alert history cleared".to_string());
+        }),
+        Command::new("alert save", &[Arg::Optional("path")], "Save alerts to the alert file", |app, args| {
+            let Some(path) = args.first().map(PathBuf::from).or_else(|| app.alerts_path.clone()) else {
+                app.log("No alert file configured; give a path".to_string());
+                return;
+            };
+            match app.save_alerts_to(&path) {
+                Ok(()) => app.log(format!("💾 Saved {} alerts to {}", app.price_alerts.len(), path.display())),
+                Err(reason) => app.log(format!("⚠️ {}", reason)),
+            }
+        }),
+        Command::new("alert load", &[Arg::Optional("path")], "Replace alerts with the alert file's", |app, args| {
+            let Some(path) = args.first().map(PathBuf::from).or_else(|| app.alerts_path.clone()) else {
+                app.log("No alert file configured; give a path".to_string());
+                return;
+            };
+            match app.load_alerts_from(&path) {
+                Ok(count) => app.log(format!("📂 Loaded {} alerts from {}", count, path.display())),
+                Err(reason) => app.log(format!("⚠️ {}", reason)),
+            }
+        }),
+    ] {
+        commands.register(command);
+    }
+    commands
 }
 
-pub struct OrderRecord {
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-    pub side: OrderSide,
-    pub price: f64,
-    pub quantity: f64,
-    pub status: String,
-    pub order_id: String,
+/// A GTD expiration as UTC wall-clock time, or "never" when unset.
+fn format_expiration(expiration: Option<i64>) -> String {
+    expiration
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map_or_else(|| "never".to_string(), |time| format!("at {} UTC", time.format("%H:%M:%S")))
 }
 
-pub struct OrderInput {
-    pub side: PolymarketOrderSide,
-    pub price: String,
-    pub quantity: String,
-    pub order_type: PolymarketOrderType,
-    pub token_id: String,
-    pub active: bool,
-    pub current_field: usize,
+/// Binance spot pair used for a coin symbol.
+fn binance_symbol(coin: &str) -> String {
+    format!("{}USDT", coin)
+}
+
+/// Percentage move relative to `previous_price`, or 0.0 when the previous
+/// price is too close to zero for the ratio to mean anything.
+fn percent_change(change: f64, previous_price: f64) -> f64 {
+    if !previous_price.is_finite() || previous_price.abs() < 1e-9 {
+        return 0.0;
+    }
+    let percent = change / previous_price * 100.0;
+    if percent.is_finite() { percent } else { 0.0 }
+}
+
+/// Parses the argument of `refresh <seconds>`; fractional seconds are allowed.
+fn parse_refresh_interval(args: &str) -> Result<Duration, String> {
+    let args = args.trim();
+    let seconds: f64 = args
+        .parse()
+        .map_err(|_| format!("Usage: refresh <seconds> (got '{}')", args))?;
+    if !(MIN_REFRESH_INTERVAL..=MAX_REFRESH_INTERVAL).contains(&seconds) {
+        return Err(format!(
+            "Refresh interval must be between {} and {} seconds",
+            MIN_REFRESH_INTERVAL, MAX_REFRESH_INTERVAL
+        ));
+    }
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+/// Runtime for the HTTP requests of an app that was not given one. It lives as
+/// long as the process, since a client's pooled connections only work on the
+/// runtime that opened them.
+fn shared_runtime() -> Result<&'static tokio::runtime::Runtime, String> {
+    static RUNTIME: std::sync::OnceLock<Result<tokio::runtime::Runtime, String>> = std::sync::OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .thread_name("order-book-requests")
+                .enable_all()
+                .build()
+                .map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(|e| format!("Could not start the request runtime: {}", e))
+}
+
+/// Applies a finished background request to the app.
+type RequestDone = Box<dyn FnOnce(&mut App) + Send>;
+
+/// Background requests whose results are waiting for `App::drain_requests`.
+struct RequestQueue {
+    tx: std::sync::mpsc::Sender<RequestDone>,
+    rx: std::sync::mpsc::Receiver<RequestDone>,
+    /// Spawned requests whose results have not been applied yet.
+    in_flight: usize,
+}
+
+impl Default for RequestQueue {
+    fn default() -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+        Self { tx, rx, in_flight: 0 }
+    }
+}
+
+/// A limit order on its way to an exchange.
+#[derive(Debug, Clone)]
+struct PendingOrder {
+    symbol: String,
+    side: OrderSide,
+    price: f64,
+    quantity: f64,
+    time_in_force: TimeInForce,
+}
+
+/// Checks that need the network, run before an order is sent.
+enum Preflight {
+    /// The pair's exchangeInfo filters, fetched as they are not cached yet.
+    BinanceFilters { rest: BinanceRestClient, request: Option<BinanceOrderRequest> },
+    /// The balance and allowance check; only a confirmed shortfall blocks.
+    PolymarketFunds {
+        client: PolymarketClobClient,
+        order_args: PolymarketOrderArgs,
+    },
+}
+
+enum PlacementOutcome {
+    /// A preflight check stopped the order before it was sent.
+    Blocked(String),
+    Placed(OrderAck),
+    /// The exchange refused the order or could not be reached.
+    Failed(String),
+}
+
+/// How an order placed in the background ended.
+struct Placement {
+    /// Preflight failures that did not stop the order.
+    warnings: Vec<String>,
+    /// exchangeInfo fetched on the way, for the filter cache.
+    fetched_filters: Option<SymbolFilters>,
+    outcome: PlacementOutcome,
+}
+
+/// Runs the preflight check, then places `order` unless the check blocked it.
+async fn place_exchange_order(
+    exchange: Box<dyn ExchangeClient>,
+    preflight: Option<Preflight>,
+    order: PendingOrder,
+) -> Placement {
+    let mut warnings = Vec::new();
+    let mut fetched_filters = None;
+    let blocked = match preflight {
+        Some(Preflight::BinanceFilters { rest, request }) => match rest.get_exchange_info(&order.symbol).await {
+            Ok(filters) => {
+                let violation = request.and_then(|request| BinanceOrderClient::validate(&request, &filters).err());
+                fetched_filters = Some(filters);
+                violation.map(|violation| format!("🚫 Binance order blocked: {}", violation))
+            }
+            Err(e) => {
+                warnings.push(format!("⚠️ exchangeInfo {}: {}", order.symbol, e));
+                None
+            }
+        },
+        Some(Preflight::PolymarketFunds { client, order_args }) => match client.preflight_order(&order_args).await {
+            Ok(()) => None,
+            Err(PolymarketError::BalanceShortfall(shortfall)) => {
+                Some(format!("🚫 Polymarket order blocked: {}", shortfall))
+            }
+            Err(e) => {
+                warnings.push(format!("⚠️ Balance check failed: {}", e));
+                None
+            }
+        },
+        None => None,
+    };
+
+    let outcome = match blocked {
+        Some(reason) => PlacementOutcome::Blocked(reason),
+        None => match exchange
+            .place_limit(&order.symbol, order.side, order.price, order.quantity, order.time_in_force)
+            .await
+        {
+            Ok(ack) => PlacementOutcome::Placed(ack),
+            Err(e) => PlacementOutcome::Failed(e.to_string()),
+        },
+    };
+    Placement { warnings, fetched_filters, outcome }
+}
+
+pub struct MarketData {
+    pub current_price: f64,
+    pub price_change: f64,
+    pub price_change_percent: f64,
+    pub volume_24h: f64,
+    pub high_24h: f64,
+    pub low_24h: f64,
+    pub market_cap: f64,
+}
+
+pub struct OrderRecord {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub venue: OrderVenue,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: f64,
+    pub quantity: f64,
+    pub status: String,
+    pub order_id: String,
+    /// Why a "Rejected" order was turned down.
+    pub reason: Option<String>,
+}
+
+/// Realized profit and the remaining open position for one symbol, built by
+/// matching filled buys and sells first-in, first-out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PnlSummary {
+    pub symbol: String,
+    pub realized_pnl: f64,
+    /// Signed size of the open position: positive when long, negative when short.
+    pub position: f64,
+    pub average_entry: Option<f64>,
+}
+
+/// What the depth bars next to each book level measure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthBarMode {
+    /// Quantity from the spread out to the level, over the side's total.
+    Cumulative,
+    /// The level's own quantity, over the largest single level.
+    PerLevel,
+}
+
+impl DepthBarMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DepthBarMode::Cumulative => "Cumulative",
+            DepthBarMode::PerLevel => "Per level",
+        }
+    }
+}
+
+/// Where the last frame drew each region the mouse acts on, so clicks and
+/// wheel events can be hit-tested against it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScreenLayout {
+    pub tabs: Rect,
+    pub left_panel: Rect,
+    pub right_panel: Rect,
+    /// The help overlay, while it is shown.
+    pub help: Option<Rect>,
+    /// The Orders and Alerts tables, with the index of their first visible row.
+    pub orders_table: Option<(Rect, usize)>,
+    pub alerts_table: Option<(Rect, usize)>,
+}
+
+fn rect_contains(area: Rect, column: u16, row: u16) -> bool {
+    (area.left()..area.right()).contains(&column) && (area.top()..area.bottom()).contains(&row)
+}
+
+/// Index of the tab whose title, or the padding either side of it, is at
+/// (`column`, `row`) in a bordered `Tabs` widget drawn over `area`.
+pub fn tab_at(area: Rect, titles: &[String], column: u16, row: u16) -> Option<usize> {
+    if area.height < 3 || row != area.y + 1 || !rect_contains(area, column, row) {
+        return None;
+    }
+    let mut x = area.x + 1;
+    for (index, title) in titles.iter().enumerate() {
+        let width = title.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>() as u16;
+        let end = x.saturating_add(width + 2);
+        if (x..end).contains(&column) {
+            return Some(index);
+        }
+        // Skip the divider
+        x = end.saturating_add(1);
+    }
+    None
+}
+
+/// Row index, counting from the table's first row and not its first visible
+/// one, at (`column`, `row`) in a bordered table with a one-line header
+/// drawn over `area` and scrolled `offset` rows down.
+pub fn table_row_at(area: Rect, offset: usize, column: u16, row: u16) -> Option<usize> {
+    let first_row = area.y + 2;
+    let inside = area.width > 2 && (area.x + 1..area.right() - 1).contains(&column);
+    if !inside || row < first_row || row + 1 >= area.bottom() {
+        return None;
+    }
+    Some(offset + (row - first_row) as usize)
+}
+
+/// What the Order Book tab shows under the price header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BookView {
+    Ladder,
+    /// Cumulative quantity per side plotted against price.
+    DepthChart,
+}
+
+impl BookView {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookView::Ladder => "Ladder",
+            BookView::DepthChart => "Depth chart",
+        }
+    }
+}
+
+/// Step-line points for one side of `OrderBook::cumulative_depth`, best level
+/// first: the curve starts at zero on the best price and steps up to the
+/// running total at every level.
+pub fn depth_chart_points(cumulative: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut points = Vec::with_capacity(cumulative.len() * 2);
+    let mut previous = 0.0;
+    for &(price, total) in cumulative {
+        points.push((price, previous));
+        points.push((price, total));
+        previous = total;
+    }
+    points
+}
+
+/// `([min_price, max_price], [0, max_total])` for a depth chart of
+/// cumulative `bids` and `asks`. The price axis is centered on the mid, or on
+/// the best price of a one-sided book; `None` when both sides are empty.
+pub fn depth_chart_bounds(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Option<([f64; 2], [f64; 2])> {
+    let center = match (bids.first(), asks.first()) {
+        (Some((bid, _)), Some((ask, _))) => (bid + ask) / 2.0,
+        (Some((price, _)), None) | (None, Some((price, _))) => *price,
+        (None, None) => return None,
+    };
+    let levels = || bids.iter().chain(asks);
+    let half_width = levels().map(|(price, _)| (price - center).abs()).fold(0.0, f64::max);
+    // A single price would give an empty axis
+    let half_width = if half_width > 0.0 { half_width } else { center.abs().max(1.0) * 0.01 };
+    let top = levels().map(|(_, total)| *total).fold(0.0, f64::max);
+    let top = if top > 0.0 { top * 1.05 } else { 1.0 };
+    Some(([center - half_width, center + half_width], [0.0, top]))
+}
+
+/// Bar length for each level in `quantities`, which run outward from the
+/// spread.
+fn depth_bar_lengths(quantities: &[f64], mode: DepthBarMode) -> Vec<usize> {
+    let scale = match mode {
+        DepthBarMode::Cumulative => quantities.iter().sum::<f64>(),
+        DepthBarMode::PerLevel => quantities.iter().copied().fold(0.0, f64::max),
+    };
+    if scale <= 0.0 {
+        return vec![0; quantities.len()];
+    }
+
+    let mut cumulative = 0.0;
+    quantities
+        .iter()
+        .map(|quantity| {
+            cumulative += quantity;
+            let value = match mode {
+                DepthBarMode::Cumulative => cumulative,
+                DepthBarMode::PerLevel => *quantity,
+            };
+            ((value / scale).min(1.0) * DEPTH_BAR_WIDTH as f64) as usize
+        })
+        .collect()
+}
+
+/// An option on the Settings tab, in the order listed there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    AutoRefresh,
+    RefreshInterval,
+    AlertSound,
+    RealData,
+    DefaultCoin,
+    Theme,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 6] = [
+        Setting::AutoRefresh,
+        Setting::RefreshInterval,
+        Setting::AlertSound,
+        Setting::RealData,
+        Setting::DefaultCoin,
+        Setting::Theme,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::AutoRefresh => "Auto-refresh",
+            Setting::RefreshInterval => "Refresh interval",
+            Setting::AlertSound => "Alert sound",
+            Setting::RealData => "Real Binance data",
+            Setting::DefaultCoin => "Default coin",
+            Setting::Theme => "Theme",
+        }
+    }
+}
+
+/// Refresh intervals, in seconds, the Settings tab steps through.
+const REFRESH_INTERVAL_STEPS: [f64; 7] = [0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0];
+
+/// How orders from the Trading tab go out; `t` cycles through the modes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TradingMode {
+    /// Orders go out as the form describes them.
+    #[default]
+    Normal,
+    /// Limit orders are immediate-or-cancel: what does not fill on arrival is
+    /// cancelled. The simulated market places orders more often and nearer
+    /// the price.
+    Aggressive,
+    /// Limit orders are post-only and rejected if they would trade on
+    /// arrival; market orders are refused. The simulated market quietens down.
+    Conservative,
+}
+
+impl TradingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradingMode::Normal => "Normal",
+            TradingMode::Aggressive => "Aggressive",
+            TradingMode::Conservative => "Conservative",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TradingMode::Normal => TradingMode::Aggressive,
+            TradingMode::Aggressive => TradingMode::Conservative,
+            TradingMode::Conservative => TradingMode::Normal,
+        }
+    }
+
+    /// The time in force a limit order goes out with, given the one the
+    /// form's order type asks for.
+    pub fn time_in_force(&self, requested: TimeInForce) -> TimeInForce {
+        match self {
+            TradingMode::Normal => requested,
+            TradingMode::Aggressive => TimeInForce::ImmediateOrCancel,
+            TradingMode::Conservative => TimeInForce::PostOnly,
+        }
+    }
+
+    /// The chance per update that the simulation adds an order, and how far
+    /// either side of the current price it may land.
+    pub fn simulated_order_flow(&self) -> (f64, f64) {
+        match self {
+            TradingMode::Normal => (0.3, 100.0),
+            TradingMode::Aggressive => (0.6, 40.0),
+            TradingMode::Conservative => (0.15, 200.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderVenue {
+    Polymarket,
+    Local,
+    Binance,
+}
+
+impl OrderVenue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderVenue::Polymarket => "Polymarket",
+            OrderVenue::Local => "Local Book",
+            OrderVenue::Binance => "Binance",
+        }
+    }
+}
+
+/// The order form's fields, in the order Up/Down moves through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderField {
+    Price,
+    Quantity,
+    TokenId,
+    Side,
+    Type,
+}
+
+impl OrderField {
+    pub const ALL: [OrderField; 5] =
+        [OrderField::Price, OrderField::Quantity, OrderField::TokenId, OrderField::Side, OrderField::Type];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderField::Price => "Price",
+            OrderField::Quantity => "Quantity",
+            OrderField::TokenId => "Token ID",
+            OrderField::Side => "Side",
+            OrderField::Type => "Type",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|field| *field == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(self) -> Self {
+        let index = Self::ALL.iter().position(|field| *field == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+pub struct OrderInput {
+    pub venue: OrderVenue,
+    pub side: PolymarketOrderSide,
+    pub price: String,
+    pub quantity: String,
+    pub order_type: PolymarketOrderType,
+    /// Unix seconds a GTD order expires at; set when GTD is selected.
+    pub expiration: Option<i64>,
+    pub token_id: String,
+    pub active: bool,
+    pub current_field: OrderField,
+    /// A market order on the local book: no price, fills against the
+    /// opposite side and drops whatever it cannot fill.
+    pub market: bool,
+}
+
+/// The order-book call submitting the form on the local book makes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LocalOrderRequest {
+    Limit { side: OrderSide, price: f64, quantity: f64 },
+    Market { side: OrderSide, quantity: f64 },
+}
+
+impl OrderInput {
+    /// The text behind the selected field; `None` for Side and Type, which
+    /// are set with their letter shortcuts, and for the token id outside
+    /// Polymarket, where the selected coin's symbol is used instead.
+    fn current_text_mut(&mut self) -> Option<&mut String> {
+        match self.current_field {
+            OrderField::Price => Some(&mut self.price),
+            OrderField::Quantity => Some(&mut self.quantity),
+            OrderField::TokenId if self.venue == OrderVenue::Polymarket => Some(&mut self.token_id),
+            OrderField::TokenId | OrderField::Side | OrderField::Type => None,
+        }
+    }
+
+    /// Types `c` into the selected field. Price and quantity take digits and
+    /// one decimal point, the token id digits only. Returns whether `c` was
+    /// taken.
+    pub fn input_char(&mut self, c: char) -> bool {
+        let decimal = matches!(self.current_field, OrderField::Price | OrderField::Quantity);
+        let Some(text) = self.current_text_mut() else {
+            return false;
+        };
+        if c.is_ascii_digit() || (c == '.' && decimal && !text.contains('.')) {
+            text.push(c);
+            return true;
+        }
+        false
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(text) = self.current_text_mut() {
+            text.pop();
+        }
+    }
+
+    /// Moves to the next field, skipping the price of a market order.
+    pub fn next_field(&mut self) {
+        self.current_field = self.current_field.next();
+        if self.market && self.current_field == OrderField::Price {
+            self.current_field = self.current_field.next();
+        }
+    }
+
+    pub fn previous_field(&mut self) {
+        self.current_field = self.current_field.previous();
+        if self.market && self.current_field == OrderField::Price {
+            self.current_field = self.current_field.previous();
+        }
+    }
+
+    pub fn order_side(&self) -> OrderSide {
+        if self.side == PolymarketOrderSide::BUY {
+            OrderSide::Bid
+        } else {
+            OrderSide::Ask
+        }
+    }
+
+    /// The local book call this form maps to, or the reason it cannot be
+    /// submitted yet. Market orders need only a quantity.
+    pub fn local_order_request(&self) -> Result<LocalOrderRequest, String> {
+        let side = self.order_side();
+        if self.market {
+            let quantity = parse_positive("quantity", &self.quantity)?;
+            return Ok(LocalOrderRequest::Market { side, quantity });
+        }
+        let (price, quantity) = self.validate()?;
+        Ok(LocalOrderRequest::Limit { side, price, quantity })
+    }
+
+    /// Price and quantity as positive numbers, or the reason the form cannot
+    /// be submitted yet.
+    pub fn validate(&self) -> Result<(f64, f64), String> {
+        Ok((parse_positive("price", &self.price)?, parse_positive("quantity", &self.quantity)?))
+    }
+}
+
+/// A typed order form number, which must be positive.
+fn parse_positive(name: &str, text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(value) if value > 0.0 && value.is_finite() => Ok(value),
+        _ if text.is_empty() => Err(format!("Enter a {} first", name)),
+        _ => Err(format!("Invalid {}: '{}'", name, text)),
+    }
 }
 
 impl App {
+    /// An app with default settings that reads and writes no files; see
+    /// `with_config` and `persist_to_config_dir`.
     pub fn new() -> Self {
+        Self::with_config(None, None)
+    }
+
+    /// An app whose simulated order books, market data and candles are the
+    /// same on every run with the same `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_simulator(MarketSimulator::new(Some(seed)), None)
+    }
+
+    /// An app with its settings at `config_path`, if given, simulating from
+    /// `seed` or else the settings' seed.
+    pub fn with_config(config_path: Option<PathBuf>, seed: Option<u64>) -> Self {
+        let seed = seed.or_else(|| {
+            let config = AppConfig::load(config_path.as_ref()?).ok()?;
+            config.seed
+        });
+        Self::with_simulator(MarketSimulator::new(seed), config_path)
+    }
+
+    /// Keeps alerts and command history in the config directory, restoring
+    /// what earlier runs saved there.
+    pub fn persist_to_config_dir(&mut self) {
+        self.alerts_path = default_alerts_path();
+        self.history_path = default_history_path();
+        self.restore_alerts();
+        self.restore_command_history();
+    }
+
+    fn with_simulator(simulator: MarketSimulator, config_path: Option<PathBuf>) -> Self {
         let tabs = vec![
             "Order Book".to_string(),
             "Trading".to_string(),
@@ -564,23 +1924,23 @@ impl App {
             "Charts".to_string(),
             "Alerts".to_string(),
             "Settings".to_string(),
+            "Watchlist".to_string(),
+            "Logs".to_string(),
         ];
 
         let mut app = Self {
             order_book: OrderBook::new(),
+            polymarket_book: None,
+            order_books: HashMap::new(),
+            book_cursor: None,
+            book_scroll: 0,
             selected_tab: 0,
             tabs,
-            user_command: String::new(),
+            user_command: CommandLine::new(),
+            history_path: None,
             real_time_data: VecDeque::new(),
-            candlestick_data: vec![
-                Candlestick::new(chrono::Utc::now() - chrono::Duration::hours(24), 26400.0, 26500.0, 26300.0, 26436.58, 2.4e9),
-                Candlestick::new(chrono::Utc::now() - chrono::Duration::hours(20), 26436.58, 26550.0, 26400.0, 26500.0, 2.1e9),
-                Candlestick::new(chrono::Utc::now() - chrono::Duration::hours(16), 26500.0, 26600.0, 26450.0, 26550.0, 2.8e9),
-                Candlestick::new(chrono::Utc::now() - chrono::Duration::hours(12), 26550.0, 26650.0, 26500.0, 26600.0, 3.2e9),
-                Candlestick::new(chrono::Utc::now() - chrono::Duration::hours(8), 26600.0, 26700.0, 26550.0, 26650.0, 2.9e9),
-                Candlestick::new(chrono::Utc::now() - chrono::Duration::hours(4), 26650.0, 26750.0, 26600.0, 26700.0, 3.1e9),
-                Candlestick::new(chrono::Utc::now(), 26700.0, 26750.0, 26650.0, 26436.58, 2.4e9),
-            ],
+            candlestick_data: Vec::new(),
+            minute_candles: Vec::new(),
             market_data: MarketData {
                 current_price: 26436.58,
                 price_change: -63.42,
@@ -593,16 +1953,23 @@ impl App {
             order_history: VecDeque::new(),
             polymarket_client: None,
             current_market: "BTCUSDT".to_string(),
+            price_decimals: HashMap::from([("BTC".to_string(), 2), ("ETH".to_string(), 2), ("SOL".to_string(), 3)]),
             order_input: OrderInput {
+                venue: OrderVenue::Polymarket,
                 side: PolymarketOrderSide::BUY,
                 price: "26436".to_string(),
                 quantity: "0.1".to_string(),
                 order_type: PolymarketOrderType::GTC,
-                token_id: "BTCUSDT".to_string(),
+                expiration: None,
+                token_id: String::new(),
                 active: false,
-                current_field: 0,
+                current_field: OrderField::Price,
+                market: false,
             },
+            trading_mode: TradingMode::Normal,
             help_mode: false,
+            help_scroll: 0,
+            layout: ScreenLayout::default(),
             last_update: chrono::Utc::now(),
             available_coins: vec![
                 CoinType::new("BTC", "Bitcoin", 26436.58, -63.42, 2.4e9, 850.0e9),
@@ -614,16 +1981,59 @@ impl App {
             auto_refresh: true,
             refresh_interval: Duration::from_secs(2),
             selected_timeframe: ChartTimeframe::OneDay,
+            chart_overlay: ChartOverlay::MovingAverages,
+            indicator_pane: IndicatorPane::Off,
             price_alerts: Vec::new(),
+            alerts_selected: 0,
+            alert_history: VecDeque::new(),
+            alert_history_limit: DEFAULT_ALERT_HISTORY_LIMIT,
+            watchlist_mids: HashMap::new(),
+            watchlist_sort: WatchlistSort::default(),
+            watchlist_selected: 0,
+            log_buffer: LogBuffer::default(),
+            log_filter: LOG_PANEL_LEVEL,
             next_alert_id: 1,
+            // Tests never read or write the user's alert file
+            alerts_path: None,
             alert_sound_enabled: true,
+            default_coin: "BTC".to_string(),
+            config_path,
+            config_seed: None,
+            config_log_level: AppConfig::default().log_level,
+            config_log_file: None,
+            settings_selected: 0,
+            theme: Theme::dark(),
+            custom_themes: Vec::new(),
             binance_ws: BinanceWebSocket::new(),
+            market_stream: None,
+            market_stream_trades_seen: 0,
+            market_stream_url: BinanceWebSocketClient::new(String::new()).base_url,
+            runtime: None,
+            requests: RequestQueue::default(),
+            order_in_flight: false,
+            binance_rest: BinanceRestClient::new(),
+            symbol_filters: HashMap::new(),
+            binance_orders: None,
+            user_data_stream: None,
+            polymarket_user_channel: None,
+            depth_bar_mode: DepthBarMode::Cumulative,
+            book_view: BookView::Ladder,
+            tape_paused: None,
+            large_trade_notional: DEFAULT_LARGE_TRADE_NOTIONAL,
+            selected_order_id: None,
+            orders_scroll: 0,
             use_real_data: false,
             terminal_chart: TerminalChartBackend::new(80, 25),
+            commands: builtin_commands(),
+            simulator,
         };
 
+        app.seed_simulated_candles(app.market_data.current_price);
+        app.resample_candles();
         app.add_sample_orders();
         app.initialize_polymarket_client();
+        app.initialize_binance_client();
+        app.restore_config();
         app
     }
 
@@ -678,1905 +2088,6547 @@ impl App {
         }
     }
 
+    /// Appends to the activity log, folding a repeat of the last message into
+    /// a "(xN)" suffix and dropping the oldest entries past the capacity.
+    pub fn log(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        if let Some(last) = self.real_time_data.back_mut() {
+            let repeats = if *last == message {
+                Some(1)
+            } else {
+                last.strip_prefix(message.as_str())
+                    .and_then(|rest| rest.strip_prefix(" (x"))
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .and_then(|count| count.parse::<u32>().ok())
+            };
+            if let Some(count) = repeats {
+                *last = format!("{} (x{})", message, count + 1);
+                return;
+            }
+        }
+
+        self.real_time_data.push_back(message);
+        while self.real_time_data.len() > REAL_TIME_LOG_CAPACITY {
+            self.real_time_data.pop_front();
+        }
+    }
+
     pub fn initialize_polymarket_client(&mut self) {
         // Initialize with test credentials
-        let client = PolymarketClobClient::new(
+        let mut client = PolymarketClobClient::new(
             "https://clob.polymarket.com".to_string(),
             "test_private_key".to_string(),
             137,
             PolymarketSignatureType::EMAIL_MAGIC,
             Some("0xTestProxyAddress".to_string()),
         );
+        let credentials = client.create_or_derive_api_credentials();
+        client.set_api_credentials(credentials);
         self.polymarket_client = Some(client);
     }
 
-    pub fn next_tab(&mut self) {
-        self.selected_tab = (self.selected_tab + 1) % self.tabs.len();
+    /// Enables Binance order routing when `BINANCE_API_KEY` and
+    /// `BINANCE_SECRET_KEY` are set; `BINANCE_TESTNET=1` targets the spot testnet.
+    pub fn initialize_binance_client(&mut self) {
+        if let (Ok(api_key), Ok(secret_key)) = (std::env::var("BINANCE_API_KEY"), std::env::var("BINANCE_SECRET_KEY")) {
+            let testnet = std::env::var("BINANCE_TESTNET").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+            let mut client = BinanceOrderClient::new(api_key, secret_key, testnet);
+            if !testnet {
+                // Market data and orders hit the same host, so they share one weight budget
+                client = client.with_rate_limiter(self.binance_rest.rate_limiter().clone());
+            }
+            self.user_data_stream = Some(UserDataStreamHandle::spawn(client.clone(), user_stream_base_url(testnet)));
+            self.binance_orders = Some(client);
+        }
     }
 
-    pub fn previous_tab(&mut self) {
-        self.selected_tab = if self.selected_tab == 0 {
-            self.tabs.len() - 1
-        } else {
-            self.selected_tab - 1
+    /// Subscribes to the Polymarket user channel so order history rows follow
+    /// the CLOB's order and trade events.
+    pub fn start_polymarket_user_channel(&mut self) {
+        if self.polymarket_user_channel.is_some() {
+            self.log("Polymarket user channel already running".to_string());
+            return;
+        }
+        let Some(credentials) = self.polymarket_client.as_ref().and_then(|c| c.api_credentials()) else {
+            self.log("Polymarket API credentials not set".to_string());
+            return;
         };
-    }
 
-    pub fn add_user_command(&mut self, c: char) {
-        if self.order_input.active {
-            self.handle_order_input(c);
-        } else {
-            self.user_command.push(c);
-        }
+        self.polymarket_user_channel = Some(UserChannelHandle::spawn(UserChannelConfig::default(), credentials.clone()));
+        self.log("📡 Subscribed to the Polymarket user channel".to_string());
     }
 
-    pub fn handle_order_input(&mut self, c: char) {
-        match c {
-            'p' => self.order_input.price.push_str(&c.to_string()),
-            'q' => self.order_input.quantity.push_str(&c.to_string()),
-            't' => self.order_input.token_id.push_str(&c.to_string()),
-            'b' => self.order_input.side = PolymarketOrderSide::BUY,
-            's' => self.order_input.side = PolymarketOrderSide::SELL,
-            'g' => self.order_input.order_type = PolymarketOrderType::GTC,
-            'f' => self.order_input.order_type = PolymarketOrderType::FOK,
-            'd' => self.order_input.order_type = PolymarketOrderType::GTD,
-            _ => {}
+    /// Applies any events received from the Binance user data stream and the
+    /// Polymarket user channel since the last call.
+    pub fn drain_user_data_events(&mut self) {
+        let polymarket_events = self.polymarket_user_channel.as_mut().map(|c| c.drain()).unwrap_or_default();
+        for event in polymarket_events {
+            match event {
+                PolymarketUserEvent::Order(update) => self.apply_polymarket_order_update(&update),
+                PolymarketUserEvent::Reconnected => self.log("📡 Polymarket user channel reconnected".to_string()),
+                PolymarketUserEvent::AuthFailed(reason) => {
+                    self.polymarket_user_channel = None;
+                    tracing::error!(%reason, "Polymarket user channel rejected the API credentials");
+                    self.log(format!("⚠️ Polymarket user channel rejected the API credentials: {}", reason));
+                }
+                PolymarketUserEvent::StreamError(message) => {
+                    tracing::warn!(%message, "Polymarket user channel error");
+                    self.log(format!("⚠️ Polymarket user channel error, reconnecting: {}", message));
+                }
+            }
         }
-    }
 
-    pub fn remove_user_command(&mut self) {
-        if self.order_input.active {
-            // Remove from appropriate field
-            if !self.order_input.price.is_empty() {
-                self.order_input.price.pop();
-            } else if !self.order_input.quantity.is_empty() {
-                self.order_input.quantity.pop();
-            } else if !self.order_input.token_id.is_empty() {
-                self.order_input.token_id.pop();
+        let Some(stream) = self.user_data_stream.as_mut() else {
+            return;
+        };
+
+        for event in stream.drain() {
+            match event {
+                UserDataEvent::Order(update) => self.apply_order_update(&update),
+                UserDataEvent::Balance(update) => {
+                    for balance in &update.balances {
+                        self.log(format!(
+                            "💰 {} balance: {:.8} free / {:.8} locked",
+                            balance.asset, balance.free, balance.locked
+                        ));
+                    }
+                }
+                UserDataEvent::StreamError(message) => {
+                    tracing::warn!(%message, "Binance user data stream stopped");
+                    self.log(format!("⚠️ Binance user data stream stopped: {}", message));
+                }
             }
-        } else {
-            self.user_command.pop();
         }
     }
 
-    pub fn clear_user_command(&mut self) {
-        if self.order_input.active {
-            self.order_input.price.clear();
-            self.order_input.quantity.clear();
-            self.order_input.token_id.clear();
-            self.order_input.active = false;
-        } else {
-            self.user_command.clear();
-        }
+    /// Updates the matching Binance row in `order_history` from an
+    /// `executionReport`.
+    pub fn apply_order_update(&mut self, update: &OrderUpdate) {
+        let order_id = update.order_id.to_string();
+        let Some(record) = self.order_history
+            .iter_mut()
+            .find(|r| r.venue == OrderVenue::Binance && r.order_id == order_id)
+        else {
+            return;
+        };
+
+        record.status = update.status.label().to_string();
+        let message = format!(
+            "📬 Binance order #{} {}: {:.8}/{:.8} filled",
+            order_id, record.status, update.cumulative_filled_quantity, update.quantity
+        );
+        self.log(message);
     }
 
-    pub fn execute_user_command(&mut self) {
-        let command = self.user_command.clone();
-        let trimmed_command = command.trim();
-        
-        match trimmed_command {
-            "clear" => self.clear_user_command(),
-            "help" => self.help_mode = !self.help_mode,
-            "add_orders" => {
-                self.add_sample_orders();
-                self.real_time_data.push_back("Sample orders added".to_string());
-            }
-            "place_order" => {
-                self.order_input.active = true;
-                self.real_time_data.push_back("Order input mode activated".to_string());
-            }
-            "cancel_order" => {
-                self.real_time_data.push_back("Order cancellation mode".to_string());
-            }
-            "market_data" => {
-                self.update_market_data();
-                self.real_time_data.push_back("Market data updated".to_string());
-            }
-            "submit_order" => {
-                self.submit_polymarket_order();
-            }
-            _ => {
-                // Check for alert commands
-                if trimmed_command.starts_with("alert ") {
-                    self.handle_alert_command(&trimmed_command[6..]); // Remove "alert " prefix
-                } else if !trimmed_command.is_empty() {
-                    self.real_time_data.push_back(format!("Unknown command: {}", trimmed_command));
-                }
-            }
-        }
-        self.clear_user_command();
-    }
-    
-    pub fn handle_alert_command(&mut self, alert_args: &str) {
-        let parts: Vec<&str> = alert_args.split_whitespace().collect();
-        if parts.len() < 2 {
-            self.real_time_data.push_back("Usage: alert <type> <value> [message]".to_string());
+    /// Updates the matching Polymarket row in `order_history` from a user
+    /// channel event.
+    pub fn apply_polymarket_order_update(&mut self, update: &PolymarketOrderUpdate) {
+        let Some(record) = self.order_history
+            .iter_mut()
+            .find(|r| r.venue == OrderVenue::Polymarket && r.order_id == update.order_id)
+        else {
             return;
+        };
+
+        record.status = update.status.label().to_string();
+        let message = format!(
+            "📬 Polymarket order #{} {}: {}",
+            update.order_id, record.status, update.status.description()
+        );
+        self.log(message);
+    }
+
+    /// Stops background tasks (the Binance user data stream closes its
+    /// listenKey) before the application exits.
+    pub fn shutdown(&mut self) {
+        self.persist_alerts();
+        self.persist_command_history();
+        self.stop_market_stream();
+        if let Some(mut stream) = self.user_data_stream.take() {
+            stream.stop(Duration::from_secs(2));
         }
-        
-        let alert_type = parts[0];
-        let value_str = parts[1];
-        let message = if parts.len() > 2 {
-            parts[2..].join(" ")
-        } else {
-            format!("{} {}", alert_type, value_str)
+        if let Some(mut channel) = self.polymarket_user_channel.take() {
+            channel.stop(Duration::from_secs(2));
+        }
+    }
+
+    pub fn toggle_depth_bar_mode(&mut self) {
+        self.depth_bar_mode = match self.depth_bar_mode {
+            DepthBarMode::Cumulative => DepthBarMode::PerLevel,
+            DepthBarMode::PerLevel => DepthBarMode::Cumulative,
         };
-        
-        match alert_type {
-            "above" => {
-                if let Ok(price) = value_str.parse::<f64>() {
-                    let alert_type = AlertType::PriceAbove(price);
-                    self.add_price_alert(self.current_market.clone(), alert_type, message);
-                } else {
-                    self.real_time_data.push_back("Invalid price value".to_string());
-                }
-            }
-            "below" => {
-                if let Ok(price) = value_str.parse::<f64>() {
-                    let alert_type = AlertType::PriceBelow(price);
-                    self.add_price_alert(self.current_market.clone(), alert_type, message);
-                } else {
-                    self.real_time_data.push_back("Invalid price value".to_string());
-                }
-            }
-            "change" => {
-                if let Ok(percent) = value_str.parse::<f64>() {
-                    let alert_type = AlertType::PercentageChange(percent);
-                    self.add_price_alert(self.current_market.clone(), alert_type, message);
-                } else {
-                    self.real_time_data.push_back("Invalid percentage value".to_string());
-                }
-            }
-            "volume" => {
-                if let Ok(volume) = value_str.parse::<f64>() {
-                    let alert_type = AlertType::VolumeSpike(volume);
-                    self.add_price_alert(self.current_market.clone(), alert_type, message);
-                } else {
-                    self.real_time_data.push_back("Invalid volume value".to_string());
-                }
-            }
-            "cross" => {
-                if let Ok(price) = value_str.parse::<f64>() {
-                    let alert_type = AlertType::PriceCross(price);
-                    self.add_price_alert(self.current_market.clone(), alert_type, message);
-                } else {
-                    self.real_time_data.push_back("Invalid price value".to_string());
-                }
-            }
-            "list" => {
-                self.real_time_data.push_back(format!("Active alerts: {}", self.get_active_alerts_count()));
-            }
-            "remove" => {
-                if let Ok(id) = value_str.parse::<u64>() {
-                    if self.remove_price_alert(id) {
-                        self.real_time_data.push_back("Alert removed successfully".to_string());
-                    } else {
-                        self.real_time_data.push_back("Alert not found".to_string());
-                    }
-                } else {
-                    self.real_time_data.push_back("Invalid alert ID".to_string());
-                }
+        self.log(format!("Depth bars: {}", self.depth_bar_mode.as_str()));
+    }
+
+    pub fn cycle_chart_overlay(&mut self) {
+        self.chart_overlay = self.chart_overlay.next();
+        self.log(format!("Chart overlay: {}", self.chart_overlay.as_str()));
+    }
+
+    pub fn cycle_indicator_pane(&mut self) {
+        self.indicator_pane = self.indicator_pane.next();
+        self.log(format!("Indicator pane: {}", self.indicator_pane.as_str()));
+    }
+
+    pub fn toggle_book_view(&mut self) {
+        self.book_view = match self.book_view {
+            BookView::Ladder => BookView::DepthChart,
+            BookView::DepthChart => BookView::Ladder,
+        };
+        self.log(format!("Order book view: {}", self.book_view.as_str()));
+    }
+
+    /// Freezes the trades tape so it can be read, or resumes following the book.
+    pub fn toggle_tape_pause(&mut self) {
+        self.tape_paused = match self.tape_paused {
+            Some(_) => None,
+            None => Some(self.order_book.recent_trades(TRADE_TAPE_ROWS)),
+        };
+        self.log(format!("Trades tape {}", if self.tape_paused.is_some() { "paused" } else { "resumed" }));
+    }
+
+    /// Writes `what` (one of `EXPORT_KINDS`) as CSV to `path`, expanding a
+    /// leading `~`. Returns what was written, or why nothing was.
+    pub fn export(&mut self, what: &str, path: &str, overwrite: bool) -> Result<String, String> {
+        let (csv, rows) = match what {
+            "orders" => (export::orders_csv(&self.order_history), self.order_history.len()),
+            "trades" => {
+                // Oldest first, like the other exports
+                let mut trades = self.order_book.recent_trades(TRADE_HISTORY_CAPACITY);
+                trades.reverse();
+                (export::trades_csv(&trades), trades.len())
             }
-            _ => {
-                self.real_time_data.push_back(format!("Unknown alert type: {}. Use: above, below, change, volume, cross", alert_type));
+            "alerts" => (export::alerts_csv(&self.price_alerts), self.price_alerts.len()),
+            "depth" => {
+                let (bids, asks) = self.displayed_book().0.get_market_depth(usize::MAX);
+                (export::depth_csv(&bids, &asks), bids.len() + asks.len())
             }
-        }
+            _ => return Err(format!("Can't export '{}': choose {}", what, export::EXPORT_KINDS.join(", "))),
+        };
+        let path = export::expand_home(path);
+        export::write_export(&path, &csv, overwrite)?;
+        Ok(format!("📤 Exported {} {} rows to {}", rows, what, path.display()))
     }
 
-    pub fn submit_polymarket_order(&mut self) {
-        if let Some(client) = &self.polymarket_client {
-            let price: f64 = self.order_input.price.parse().unwrap_or(0.0);
-            let quantity: f64 = self.order_input.quantity.parse().unwrap_or(0.0);
-            
-            if price > 0.0 && quantity > 0.0 {
-                let order_args = client.create_order_args(
-                    price,
-                    quantity,
-                    self.order_input.side.clone(),
-                    self.order_input.token_id.clone(),
-                );
-                
-                let order = client.create_order(order_args);
-                
-                // Add to order history
-                let order_record = OrderRecord {
-                    timestamp: chrono::Utc::now(),
-                    side: if self.order_input.side == PolymarketOrderSide::BUY { 
-                        OrderSide::Bid 
-                    } else { 
-                        OrderSide::Ask 
-                    },
-                    price,
-                    quantity,
-                    status: "Submitted".to_string(),
-                    order_id: format!("{}", order.salt),
-                };
-                
-                self.order_history.push_back(order_record);
-                self.real_time_data.push_back(format!(
-                    "Order submitted: {:?} {} {} at ${}",
-                    self.order_input.side, quantity, self.order_input.token_id, price
-                ));
-                
-                // Clear order input
-                self.order_input.active = false;
-                self.order_input.price.clear();
-                self.order_input.quantity.clear();
-            }
+    /// The trades the tape shows, newest first.
+    pub fn tape_trades(&self) -> Vec<Trade> {
+        match &self.tape_paused {
+            Some(trades) => trades.clone(),
+            None => self.order_book.recent_trades(TRADE_TAPE_ROWS),
         }
     }
 
-        pub fn update_market_data(&mut self) {
-        // Store previous price for alert checking
-        let _previous_price = self.market_data.current_price;
-        
-        // Simulate market data updates
-        let mut rng = rand::thread_rng();
-        let change = (rng.gen::<f64>() - 0.5) * 100.0;
-        self.market_data.current_price += change;
-        self.market_data.price_change = change;
-        self.market_data.price_change_percent = (change / (self.market_data.current_price - change)) * 100.0;
-        self.market_data.volume_24h += rng.gen::<f64>() * 100_000_000.0;
-        
-        // Check price alerts (temporarily disabled due to borrow checker issue)
-        // self.check_all_alerts(self.market_data.current_price, previous_price, self.market_data.volume_24h);
-        
-        // Update candlestick data
-        self.update_candlestick_data();
-        
-        // Update real-time service status
-        self.real_time_service.update_connection_status("Live Updates", true);
-        
-        self.last_update = chrono::Utc::now();
+    pub fn next_tab(&mut self) {
+        self.selected_tab = (self.selected_tab + 1) % self.tabs.len();
     }
 
-    pub fn simulate_real_time_updates(&mut self) {
-        if !self.auto_refresh {
+    pub fn previous_tab(&mut self) {
+        self.selected_tab = if self.selected_tab == 0 {
+            self.tabs.len() - 1
+        } else {
+            self.selected_tab - 1
+        };
+    }
+
+    pub fn add_user_command(&mut self, c: char) {
+        if self.order_input.active {
+            self.handle_order_input(c);
+        } else {
+            self.user_command.insert(c);
+        }
+    }
+
+    /// Digits and the decimal point go into the selected field; the letters
+    /// set side, type and venue whichever field is selected.
+    pub fn handle_order_input(&mut self, c: char) {
+        if self.order_input.input_char(c) {
             return;
         }
-        
-        // Simulate live order book updates
-        let mut rng = rand::thread_rng();
-        
-        // Randomly add/remove orders to simulate market activity
-        if rng.gen::<f64>() < 0.3 { // 30% chance
-            let side = if rng.gen::<bool>() { OrderSide::Bid } else { OrderSide::Ask };
-            let price_offset = (rng.gen::<f64>() - 0.5) * 200.0;
-            let price = self.market_data.current_price + price_offset;
-            let quantity = rng.gen::<f64>() * 10.0 + 0.1;
-            
-            self.order_book.add_order(side, price, quantity, 
-                (chrono::Utc::now().timestamp() as u64) % 10000);
-            
-            self.real_time_data.push_back(format!(
-                "🔄 New {} order: {:.2} @ ${:.2}",
-                if side == OrderSide::Bid { "bid" } else { "ask" },
-                quantity, price
-            ));
+        match c {
+            'b' => self.order_input.side = PolymarketOrderSide::BUY,
+            's' => self.order_input.side = PolymarketOrderSide::SELL,
+            'g' => self.select_order_type(PolymarketOrderType::GTC),
+            'f' => self.select_order_type(PolymarketOrderType::FOK),
+            'd' => self.select_order_type(PolymarketOrderType::GTD),
+            'o' => self.toggle_order_venue(),
+            'm' => self.toggle_market_order(),
+            _ => {}
         }
-        
-        // Keep only last 10 updates
-        if self.real_time_data.len() > 10 {
-            self.real_time_data.drain(0..self.real_time_data.len() - 10);
+    }
+
+    /// Switches the order form's type. Choosing GTD without a future
+    /// expiration defaults it to `DEFAULT_GTD_DURATION` from now.
+    pub fn select_order_type(&mut self, order_type: PolymarketOrderType) {
+        self.order_input.order_type = order_type;
+        if order_type != PolymarketOrderType::GTD {
+            return;
         }
-        
-        // Update connection status
-        self.real_time_service.update_connection_status("Live Updates", true);
+        let now = chrono::Utc::now().timestamp();
+        if self.order_input.expiration.filter(|expiration| *expiration > now).is_none() {
+            self.order_input.expiration = Some(now + DEFAULT_GTD_DURATION.as_secs() as i64);
+        }
+        self.log(format!(
+            "GTD order expires {} (set another with: expire <minutes>)",
+            format_expiration(self.order_input.expiration)
+        ));
     }
 
-    pub fn update_candlestick_data(&mut self) {
-        let mut rng = rand::thread_rng();
-        
-        // Update the latest candlestick with new data
-        if let Some(latest_candle) = self.candlestick_data.last_mut() {
-            let price_change = (rng.gen::<f64>() - 0.5) * 200.0; // ±$100 price movement
-            let new_price = latest_candle.close + price_change;
-            
-            latest_candle.high = latest_candle.high.max(new_price);
-            latest_candle.low = latest_candle.low.min(new_price);
-            latest_candle.close = new_price;
-            latest_candle.volume += rng.gen::<f64>() * 50_000_000.0; // Add some volume
-            
-            // Update market data to match
-            self.market_data.current_price = new_price;
-            self.market_data.price_change = price_change;
-            self.market_data.price_change_percent = (price_change / (new_price - price_change)) * 100.0;
-        }
-        
-        // Occasionally add a new candlestick (every few updates)
-        if rng.gen::<f64>() < 0.1 { // 10% chance
-            let last_price = self.candlestick_data.last().map(|c| c.close).unwrap_or(self.market_data.current_price);
-            let new_candle = Candlestick::new(
-                chrono::Utc::now(),
-                last_price,
-                last_price + rng.gen::<f64>() * 100.0,
-                last_price - rng.gen::<f64>() * 100.0,
-                last_price + (rng.gen::<f64>() - 0.5) * 200.0,
-                rng.gen::<f64>() * 500_000_000.0 + 100_000_000.0,
-            );
-            
-            self.candlestick_data.push(new_candle);
-            
-            // Keep only last 50 candles for performance
-            if self.candlestick_data.len() > 50 {
-                self.candlestick_data.remove(0);
-            }
+    pub fn remove_user_command(&mut self) {
+        if self.order_input.active {
+            self.order_input.backspace();
+        } else {
+            self.user_command.backspace();
         }
     }
 
-    pub fn toggle_order_input(&mut self) {
-        self.order_input.active = !self.order_input.active;
+    pub fn clear_user_command(&mut self) {
         if self.order_input.active {
-            self.real_time_data.push_back("Order input mode activated".to_string());
+            self.order_input.price.clear();
+            self.order_input.quantity.clear();
+            self.order_input.token_id.clear();
+            self.order_input.active = false;
         } else {
-            self.real_time_data.push_back("Order input mode deactivated".to_string());
+            self.user_command.clear();
         }
     }
 
-    pub fn refresh_order_book(&mut self) {
-        // Get the currently selected coin info first
-        let coin_symbol = self.available_coins[self.selected_coin_index].symbol.clone();
-        let base_price = self.available_coins[self.selected_coin_index].price;
-        
-        // Add some new orders based on current market conditions
-        let current_time = chrono::Utc::now();
-        let mut rng = rand::thread_rng();
-        
-        // Generate orders around the current price with realistic spreads
-        let spread = base_price * 0.001; // 0.1% spread
-        
-        // Add a few new bid orders
-        for _ in 0..2 {
-            let price_offset = (rng.gen::<f64>() - 0.5) * spread;
-            let bid_price = base_price + price_offset - spread / 2.0;
-            
-            let quantity = match coin_symbol.as_str() {
-                "BTC" => rng.gen_range(0.01..0.5),
-                "ETH" => rng.gen_range(0.1..5.0),
-                "SOL" => rng.gen_range(1.0..50.0),
-                "ADA" => rng.gen_range(100.0..5000.0),
-                "DOT" => rng.gen_range(10.0..200.0),
-                "LINK" => rng.gen_range(20.0..500.0),
-                _ => rng.gen_range(0.5..50.0),
-            };
-            
-            self.order_book.add_order(OrderSide::Bid, bid_price, quantity, 
-                (current_time.timestamp() as u64) % 10000);
+    /// Runs the command bar input through `commands`, logging a usage hint
+    /// when it names no command or has the wrong arguments.
+    pub fn execute_user_command(&mut self) {
+        let command = self.user_command.submit();
+        let commands = self.commands.clone();
+        if let Err(e) = commands.execute(self, &command) {
+            self.log(e.to_string());
         }
-        
-        // Add a few new ask orders
-        for _ in 0..2 {
-            let price_offset = (rng.gen::<f64>() - 0.5) * spread;
-            let ask_price = base_price + price_offset + spread / 2.0;
-            
-            let quantity = match coin_symbol.as_str() {
-                "BTC" => rng.gen_range(0.01..0.3),
-                "ETH" => rng.gen_range(0.1..3.0),
-                "SOL" => rng.gen_range(1.0..30.0),
-                "ADA" => rng.gen_range(100.0..3000.0),
-                "DOT" => rng.gen_range(10.0..150.0),
-                "LINK" => rng.gen_range(20.0..300.0),
-                _ => rng.gen_range(0.5..30.0),
+        self.clear_user_command();
+    }
+
+    /// Completes the command bar's word under the cursor from the command
+    /// names, or coin symbols and theme names for `<symbol>` and `<theme>`
+    /// arguments, and lists the candidates when there is more than one.
+    pub fn complete_user_command(&mut self) {
+        let names: Vec<&str> = self.commands.commands().iter().map(|command| command.name).collect();
+        let symbols: Vec<String> = self.available_coins.iter().map(|coin| coin.symbol.clone()).collect();
+        let themes: Vec<String> = self.themes().into_iter().map(|theme| theme.name).collect();
+        let commands = &self.commands;
+        let candidates = self.user_command.complete(&names, |name| {
+            let takes = |argument: &str| {
+                commands.get(name).is_some_and(|command| {
+                    command.args.iter().any(|arg| matches!(arg, Arg::Required(a) | Arg::Optional(a) if *a == argument))
+                })
             };
-            
-            self.order_book.add_order(OrderSide::Ask, ask_price, quantity, 
-                (current_time.timestamp() as u64) % 10000);
+            if takes("symbol") {
+                symbols.clone()
+            } else if takes("theme") {
+                themes.clone()
+            } else {
+                Vec::new()
+            }
+        });
+        if candidates.len() > 1 {
+            self.log(candidates.join("  "));
         }
-        
-        self.real_time_data.push_back(format!(
-            "Order book refreshed for {} - added new orders around ${:.2}",
-            coin_symbol, base_price
-        ));
     }
 
-    pub fn toggle_trading_mode(&mut self) {
-        // Toggle between different trading modes
-        static mut TRADING_MODE: u8 = 0;
-        unsafe {
-            TRADING_MODE = (TRADING_MODE + 1) % 3;
-            let mode_name = match TRADING_MODE {
-                0 => "Normal",
-                1 => "Aggressive",
-                2 => "Conservative",
-                _ => "Normal",
-            };
-            self.real_time_data.push_back(format!("Trading mode: {}", mode_name));
+    /// Loads the command history from `history_path` at startup; a missing
+    /// file is an empty history.
+    fn restore_command_history(&mut self) {
+        let Some(path) = self.history_path.clone() else {
+            return;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => self.user_command.load_history(text.lines().map(str::to_string)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => self.log(format!("⚠️ Could not read command history {}: {}", path.display(), e)),
         }
     }
 
-    pub fn cycle_order_field_up(&mut self) {
-        // Cycle through order input fields
-        static mut CURRENT_FIELD: u8 = 0;
-        unsafe {
-            CURRENT_FIELD = (CURRENT_FIELD + 1) % 3;
-            let field_name = match CURRENT_FIELD {
-                0 => "Price",
-                1 => "Quantity", 
-                2 => "Token ID",
-                _ => "Price",
-            };
-            self.real_time_data.push_back(format!("Selected field: {}", field_name));
+    /// Saves the command history to `history_path`, if set.
+    fn persist_command_history(&mut self) {
+        let Some(path) = self.history_path.clone() else {
+            return;
+        };
+        let text: String = self.user_command.history().map(|command| format!("{}\n", command)).collect();
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, text));
+        if let Err(e) = written {
+            self.log(format!("⚠️ Could not save command history {}: {}", path.display(), e));
         }
     }
 
-    pub fn cycle_order_field_down(&mut self) {
-        if self.order_input.active {
-            match self.order_input.current_field {
-                0 => self.order_input.current_field = 4, // Wrap around
-                1 => self.order_input.current_field = 0,
-                2 => self.order_input.current_field = 1,
-                3 => self.order_input.current_field = 2,
-                4 => self.order_input.current_field = 3,
-                _ => self.order_input.current_field = 0,
+    /// Handles the `alert <type> <value> [flags] [message]` commands that take
+    /// a number; see `AlertBehavior::parse_flags` for the flags.
+    fn add_alert_from_args(&mut self, kind: &str, args: &[&str], alert_type: fn(f64) -> AlertType, what: &str) {
+        let Ok(value) = args[0].parse::<f64>() else {
+            self.log(format!("Invalid {} value", what));
+            return;
+        };
+        let (behavior, words) = match AlertBehavior::parse_flags(&args[1..]) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                self.log(reason);
+                return;
             }
-        }
+        };
+        let message = if words.is_empty() {
+            format!("{} {}", kind, args[0])
+        } else {
+            words.join(" ")
+        };
+        self.add_price_alert_with(self.current_market.clone(), alert_type(value), message, behavior);
     }
 
-    pub fn next_coin(&mut self) {
-        self.selected_coin_index = (self.selected_coin_index + 1) % self.available_coins.len();
-        self.update_market_data_for_selected_coin();
+    /// `alert liq <bid|ask> <levels> <qty> [message...]`.
+    fn add_liquidity_alert(&mut self, args: &[&str]) {
+        let side = match args[0].to_ascii_lowercase().as_str() {
+            "bid" | "bids" | "buy" => OrderSide::Bid,
+            "ask" | "asks" | "sell" => OrderSide::Ask,
+            _ => {
+                self.log(format!("Invalid side '{}': use bid or ask", args[0]));
+                return;
+            }
+        };
+        let Some(levels) = args[1].parse::<usize>().ok().filter(|levels| *levels > 0) else {
+            self.log("Invalid level count".to_string());
+            return;
+        };
+        let Some(qty) = args[2].parse::<f64>().ok().filter(|qty| *qty > 0.0 && qty.is_finite()) else {
+            self.log("Invalid quantity".to_string());
+            return;
+        };
+        let (behavior, words) = match AlertBehavior::parse_flags(&args[3..]) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                self.log(reason);
+                return;
+            }
+        };
+        let message = if words.is_empty() {
+            format!("liq {} {} {}", args[0], levels, args[2])
+        } else {
+            words.join(" ")
+        };
+        let alert_type = AlertType::LiquidityBelow { side, levels, qty };
+        self.add_price_alert_with(self.current_market.clone(), alert_type, message, behavior);
     }
 
-    pub fn previous_coin(&mut self) {
-        if self.selected_coin_index == 0 {
-            self.selected_coin_index = self.available_coins.len() - 1;
+    /// `alert whale <notional> [window_secs] [message...]`. Without a window,
+    /// single trades are checked.
+    fn add_whale_alert(&mut self, args: &[&str]) {
+        let Some(notional) = args[0].parse::<f64>().ok().filter(|notional| *notional > 0.0 && notional.is_finite()) else {
+            self.log("Invalid notional".to_string());
+            return;
+        };
+        let window_secs = args.get(1).and_then(|word| word.parse::<u64>().ok());
+        let rest = if window_secs.is_some() { &args[2..] } else { &args[1..] };
+        let (behavior, words) = match AlertBehavior::parse_flags(rest) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                self.log(reason);
+                return;
+            }
+        };
+        let window_secs = window_secs.unwrap_or(0);
+        let message = if words.is_empty() {
+            format!("whale {} {}s", args[0], window_secs)
         } else {
-            self.selected_coin_index -= 1;
+            words.join(" ")
+        };
+        let alert_type = AlertType::WhaleTrade { notional, window_secs };
+        self.add_price_alert_with(self.current_market.clone(), alert_type, message, behavior);
+    }
+
+    pub fn toggle_order_venue(&mut self) {
+        self.order_input.venue = match self.order_input.venue {
+            OrderVenue::Polymarket => OrderVenue::Local,
+            OrderVenue::Local => OrderVenue::Binance,
+            OrderVenue::Binance => OrderVenue::Polymarket,
+        };
+        // Market orders only exist on the local book
+        self.order_input.market &= self.order_input.venue == OrderVenue::Local;
+        self.log(format!("Order venue: {}", self.order_input.venue.as_str()));
+    }
+
+    /// Switches the local book form between limit and market orders.
+    pub fn toggle_market_order(&mut self) {
+        if self.order_input.venue != OrderVenue::Local {
+            self.log("Market orders are only available on the local book (o to switch venue)".to_string());
+            return;
+        }
+        self.order_input.market = !self.order_input.market;
+        if self.order_input.market && self.order_input.current_field == OrderField::Price {
+            self.order_input.next_field();
         }
-        self.update_market_data_for_selected_coin();
+        self.log(format!("Order kind: {}", if self.order_input.market { "Market" } else { "Limit" }));
     }
 
-    pub fn select_coin_by_index(&mut self, index: usize) {
-        if index < self.available_coins.len() {
-            self.selected_coin_index = index;
-            self.update_market_data_for_selected_coin();
+    /// Submits the current order input to the selected venue once its price
+    /// and quantity parse.
+    pub fn submit_order(&mut self) {
+        let _span = tracing::info_span!(
+            "submit_order",
+            venue = self.order_input.venue.as_str(),
+            symbol = %self.current_market
+        )
+        .entered();
+        if self.order_input.venue == OrderVenue::Local {
+            self.submit_local_order();
+            return;
         }
+        if let Err(reason) = self.order_input.validate() {
+            self.reject_order(self.order_input.venue, reason);
+            return;
+        }
+        self.submit_exchange_order(self.order_input.venue);
     }
 
-    pub fn update_market_data_for_selected_coin(&mut self) {
-        // Get coin data first to avoid borrowing issues
-        let coin_symbol = self.available_coins[self.selected_coin_index].symbol.clone();
-        let coin_price = self.available_coins[self.selected_coin_index].price;
-        let coin_change = self.available_coins[self.selected_coin_index].change_24h;
-        let coin_volume = self.available_coins[self.selected_coin_index].volume_24h;
-        let coin_market_cap = self.available_coins[self.selected_coin_index].market_cap;
-        
-        // Update market data
-        self.current_market = coin_symbol.clone();
-        self.market_data.current_price = coin_price;
-        self.market_data.price_change = coin_change;
-        self.market_data.volume_24h = coin_volume;
-        self.market_data.market_cap = coin_market_cap;
-        
-        // Update candlestick data for the new coin
-        self.candlestick_data.clear();
-        let base_price = coin_price;
-        let mut rng = rand::thread_rng();
-        
-        // Generate realistic candlestick data
-        for i in 0..30 {
-            let timestamp = chrono::Utc::now() - chrono::Duration::hours(24 - i as i64);
-            let trend_factor = (i as f64 / 30.0) * 0.02; // Small upward trend
-            let volatility = (rng.gen::<f64>() - 0.5) * 0.01; // 1% volatility
-            let price = base_price * (1.0 + trend_factor + volatility);
-            
-            let high = price + (rng.gen::<f64>() - 0.5) * 50.0;
-            let low = price - (rng.gen::<f64>() - 0.5) * 50.0;
-            let open = if i == 0 { base_price } else { self.candlestick_data[(i-1) as usize].close };
-            let close = price;
-            let volume = rng.gen::<f64>() * 500_000_000.0 + 100_000_000.0;
-            
-            self.candlestick_data.push(Candlestick::new(
-                timestamp,
-                open,
-                high,
-                low,
-                close,
-                volume,
-            ));
+    /// Records the order form as a Rejected order carrying `reason` and logs
+    /// it. The form is left as it was so the order can be corrected.
+    fn reject_order(&mut self, venue: OrderVenue, reason: String) {
+        let side = self.order_input.order_side();
+        let price = self.order_input.price.parse().unwrap_or(0.0);
+        let quantity = self.order_input.quantity.parse().unwrap_or(0.0);
+        self.record_rejection(venue, self.current_market.clone(), side, price, quantity, reason);
+    }
+
+    /// Records an order `venue` refused as a Rejected row with `reason`.
+    fn record_rejection(&mut self, venue: OrderVenue, symbol: String, side: OrderSide, price: f64, quantity: f64, reason: String) {
+        let rejected = self.order_history.iter().filter(|record| record.status == "Rejected").count();
+        self.order_history.push_back(OrderRecord {
+            timestamp: chrono::Utc::now(),
+            venue,
+            symbol,
+            side,
+            price,
+            quantity,
+            status: "Rejected".to_string(),
+            order_id: format!("rejected-{}", rejected + 1),
+            reason: Some(reason.clone()),
+        });
+        tracing::warn!(venue = venue.as_str(), %reason, "order rejected");
+        self.log(format!("❌ {} order rejected: {}", venue.as_str(), reason));
+    }
+
+    /// The client orders for `venue` are routed through; the local book has none.
+    pub fn exchange_for(&self, venue: OrderVenue) -> Option<&dyn ExchangeClient> {
+        match venue {
+            OrderVenue::Polymarket => self.polymarket_client.as_ref().map(|c| c as &dyn ExchangeClient),
+            OrderVenue::Binance => self.binance_orders.as_ref().map(|c| c as &dyn ExchangeClient),
+            OrderVenue::Local => None,
         }
-        
-        // Clear existing order book and generate new orders for the selected coin
-        self.order_book.clear();
-        self.generate_realistic_order_book_for_coin_symbol(&coin_symbol, coin_price);
-        
-        // Add real-time data entry
-        self.real_time_data.push_back(format!(
-            "Switched to {} - Order book updated with realistic market data",
-            coin_symbol
-        ));
     }
 
-    /// Generate realistic order book data for a specific cryptocurrency
-    pub fn generate_realistic_order_book_for_coin_symbol(&mut self, coin_symbol: &str, base_price: f64) {
-        let mut rng = rand::thread_rng();
-        
-        // Generate realistic bid orders (buy orders) - below current price
-        let num_bid_levels = 15 + (rng.gen::<usize>() % 10); // 15-25 levels
-        for i in 0..num_bid_levels {
-            let price_offset = (i as f64 + 1.0) * (base_price * 0.001); // 0.1% increments
-            let bid_price = base_price - price_offset;
-            
-            // Generate realistic quantities based on price level
-            let base_quantity = match coin_symbol {
-                "BTC" => 0.1..2.0,
-                "ETH" => 1.0..20.0,
-                "SOL" => 10.0..200.0,
-                "ADA" => 1000.0..20000.0,
-                "DOT" => 50.0..1000.0,
-                "LINK" => 100.0..2000.0,
-                _ => 1.0..100.0, // Default range
-            };
-            
-            let quantity = rng.gen_range(base_quantity);
-            let timestamp = chrono::Utc::now().timestamp() as u64 - (i * 60) as u64; // Staggered timestamps
-            
-            self.order_book.add_order(OrderSide::Bid, bid_price, quantity, timestamp);
+    /// A copy of `venue`'s client for a request sent off the UI thread, with
+    /// the network check to run before placing the current order input: the
+    /// Binance filters when they are not cached, the Polymarket balance.
+    fn exchange_request(&self, venue: OrderVenue, symbol: &str) -> Option<(Box<dyn ExchangeClient>, Option<Preflight>)> {
+        match venue {
+            OrderVenue::Binance => {
+                let client = self.binance_orders.as_ref()?;
+                let preflight = (!self.symbol_filters.contains_key(symbol)).then(|| Preflight::BinanceFilters {
+                    rest: self.binance_rest.clone(),
+                    request: self.binance_order_request(client),
+                });
+                Some((Box::new(client.clone()), preflight))
+            }
+            OrderVenue::Polymarket => {
+                let client = self.polymarket_client.as_ref()?;
+                let price = self.order_input.price.parse().unwrap_or(0.0);
+                let quantity = self.order_input.quantity.parse().unwrap_or(0.0);
+                let preflight = Preflight::PolymarketFunds {
+                    client: client.clone(),
+                    order_args: client.create_order_args(price, quantity, self.order_input.side, symbol.to_string()),
+                };
+                Some((Box::new(client.clone()), Some(preflight)))
+            }
+            OrderVenue::Local => None,
         }
-        
-        // Generate realistic ask orders (sell orders) - above current price
-        let num_ask_levels = 15 + (rng.gen::<usize>() % 10); // 15-25 levels
-        for i in 0..num_ask_levels {
-            let price_offset = (i as f64 + 1.0) * (base_price * 0.001); // 0.1% increments
-            let ask_price = base_price + price_offset;
-            
-            // Generate realistic quantities based on price level
-            let base_quantity = match coin_symbol {
-                "BTC" => 0.05..1.5,
-                "ETH" => 0.5..15.0,
-                "SOL" => 5.0..150.0,
-                "ADA" => 500.0..15000.0,
-                "DOT" => 25.0..750.0,
-                "LINK" => 50.0..1500.0,
-                _ => 0.5..75.0, // Default range
-            };
-            
-            let quantity = rng.gen_range(base_quantity);
-            let timestamp = chrono::Utc::now().timestamp() as u64 - (i * 60) as u64; // Staggered timestamps
-            
-            self.order_book.add_order(OrderSide::Ask, ask_price, quantity, timestamp);
+    }
+
+    /// Places the current order input as a limit order on `venue`: the token id
+    /// on Polymarket, the selected coin's USDT pair on Binance. The request
+    /// runs in the background and its outcome is recorded by `drain_requests`.
+    pub fn submit_exchange_order(&mut self, venue: OrderVenue) {
+        if self.order_in_flight {
+            self.log("⏳ Still placing the previous order".to_string());
+            return;
         }
-        
-        // Add some market maker orders around the current price for liquidity
-        let spread = base_price * 0.0005; // 0.05% spread
-        let bid_price = base_price - spread / 2.0;
-        let ask_price = base_price + spread / 2.0;
-        
-        // Add larger market maker orders
-        let market_maker_quantity = match coin_symbol {
-            "BTC" => 0.5..2.0,
-            "ETH" => 5.0..20.0,
-            "SOL" => 50.0..200.0,
-            "ADA" => 5000.0..20000.0,
-            "DOT" => 100.0..500.0,
-            "LINK" => 200.0..1000.0,
-            _ => 10.0..500.0,
+        let (price, quantity) = match self.order_input.validate() {
+            Ok(order) => order,
+            Err(reason) => return self.reject_order(venue, reason),
         };
-        
-        let bid_quantity = rng.gen_range(market_maker_quantity.clone());
-        let ask_quantity = rng.gen_range(market_maker_quantity);
-        
-        self.order_book.add_order(OrderSide::Bid, bid_price, bid_quantity, chrono::Utc::now().timestamp() as u64);
-        self.order_book.add_order(OrderSide::Ask, ask_price, ask_quantity, chrono::Utc::now().timestamp() as u64);
-        
-        // Log the order book generation
-        self.real_time_data.push_back(format!(
-            "Generated {} bid levels and {} ask levels for {}",
-            num_bid_levels, num_ask_levels, coin_symbol
-        ));
-    }
+        if venue == OrderVenue::Binance {
+            if let Some(Err(violation)) = self.binance_filter_check() {
+                self.log(format!("🚫 Binance order blocked: {}", violation));
+                return;
+            }
+        }
+        if venue == OrderVenue::Polymarket {
+            if let Err(reason) = validate_token_id(&self.order_input.token_id) {
+                self.log(format!("⚠️ {}", reason));
+                return;
+            }
+        }
 
-    pub fn get_trading_summary(&self) -> String {
-        let best_bid = self.order_book.get_best_bid().unwrap_or(0.0);
-        let best_ask = self.order_book.get_best_ask().unwrap_or(0.0);
-        let spread = self.order_book.get_spread().unwrap_or(0.0);
-        let spread_percent = if best_bid > 0.0 { (spread / best_bid) * 100.0 } else { 0.0 };
-        
-        format!(
-            "Bid: ${:.2} | Ask: ${:.2} | Spread: ${:.2} ({:.2}%) | Orders: {}",
-            best_bid, best_ask, spread, spread_percent, 
-            self.order_book.get_market_depth(100).0.len() + self.order_book.get_market_depth(100).1.len()
-        )
+        let symbol = match venue {
+            OrderVenue::Binance => self.selected_binance_symbol(),
+            _ => self.order_input.token_id.clone(),
+        };
+        let side = if self.order_input.side == PolymarketOrderSide::BUY { OrderSide::Bid } else { OrderSide::Ask };
+        let time_in_force = self.trading_mode.time_in_force(match self.order_input.order_type {
+            PolymarketOrderType::GTC => TimeInForce::GoodTilCancelled,
+            PolymarketOrderType::FOK => TimeInForce::FillOrKill,
+            PolymarketOrderType::GTD => TimeInForce::GoodTilDate(self.order_input.expiration.unwrap_or_else(|| {
+                chrono::Utc::now().timestamp() + DEFAULT_GTD_DURATION.as_secs() as i64
+            })),
+        });
+
+        let Some((exchange, preflight)) = self.exchange_request(venue, &symbol) else {
+            self.log(match venue {
+                OrderVenue::Binance => "⚠️ Binance trading disabled: set BINANCE_API_KEY and BINANCE_SECRET_KEY",
+                _ => "⚠️ Polymarket trading disabled: client not configured",
+            });
+            return;
+        };
+        let name = exchange.name();
+        let order = PendingOrder { symbol, side, price, quantity, time_in_force };
+        self.order_in_flight = true;
+        self.spawn_request(place_exchange_order(exchange, preflight, order.clone()), move |app, placement| {
+            app.finish_exchange_order(venue, name, order, placement)
+        });
     }
 
-    pub fn get_market_trend(&self) -> &'static str {
-        if self.market_data.price_change > 0.0 {
-            if self.market_data.price_change_percent > 5.0 {
-                "Strong Bullish"
-            } else if self.market_data.price_change_percent > 1.0 {
-                "Bullish"
-            } else {
-                "Slightly Bullish"
+    /// Records how an order placed by `submit_exchange_order` ended, under
+    /// the symbol it was sent with so a cancel goes to the same market.
+    fn finish_exchange_order(&mut self, venue: OrderVenue, name: &str, order: PendingOrder, placement: Placement) {
+        self.order_in_flight = false;
+        for warning in placement.warnings {
+            self.log(warning);
+        }
+        if let Some(filters) = placement.fetched_filters {
+            self.symbol_filters.insert(order.symbol.clone(), filters);
+        }
+
+        match placement.outcome {
+            PlacementOutcome::Blocked(reason) => self.log(reason),
+            PlacementOutcome::Placed(ack) => {
+                let price = ack.price.unwrap_or(order.price);
+                self.order_history.push_back(OrderRecord {
+                    timestamp: chrono::Utc::now(),
+                    venue,
+                    symbol: order.symbol.clone(),
+                    side: order.side,
+                    price,
+                    quantity: order.quantity,
+                    status: ack.status.label().to_string(),
+                    order_id: ack.order_id.clone(),
+                    reason: None,
+                });
+                tracing::info!(order_id = %ack.order_id, status = ack.status.label(), "order placed");
+                self.log(format!(
+                    "{} order #{} {:?} {} {} at ${}: {}",
+                    name, ack.order_id, order.side, order.quantity, order.symbol, price, ack.status.label()
+                ));
+
+                // Clear order input
+                self.order_input.active = false;
+                self.order_input.price.clear();
+                self.order_input.quantity.clear();
             }
-        } else {
-            if self.market_data.price_change_percent < -5.0 {
-                "Strong Bearish"
-            } else if self.market_data.price_change_percent < -1.0 {
-                "Bearish"
-            } else {
-                "Slightly Bearish"
+            PlacementOutcome::Failed(reason) => {
+                self.record_rejection(venue, order.symbol, order.side, order.price, order.quantity, reason)
             }
         }
     }
 
-    pub fn calculate_risk_metrics(&self) -> (f64, f64, f64) {
-        let volatility = (self.market_data.high_24h - self.market_data.low_24h) / self.market_data.current_price * 100.0;
-        let volume_ratio = self.market_data.volume_24h / 1e9; // Convert to billions
-        let price_momentum = self.market_data.price_change_percent;
-        
-        (volatility, volume_ratio, price_momentum)
+    fn selected_binance_symbol(&self) -> String {
+        binance_symbol(&self.selected_symbol())
     }
 
-    pub fn next_timeframe(&mut self) {
-        self.selected_timeframe = match self.selected_timeframe {
-            ChartTimeframe::OneMinute => ChartTimeframe::FiveMinutes,
-            ChartTimeframe::FiveMinutes => ChartTimeframe::FifteenMinutes,
-            ChartTimeframe::FifteenMinutes => ChartTimeframe::OneHour,
-            ChartTimeframe::OneHour => ChartTimeframe::FourHours,
-            ChartTimeframe::FourHours => ChartTimeframe::OneDay,
-            ChartTimeframe::OneDay => ChartTimeframe::OneMinute,
+    fn binance_order_request(&self, client: &BinanceOrderClient) -> Option<BinanceOrderRequest> {
+        let price: f64 = self.order_input.price.parse().unwrap_or(0.0);
+        let quantity: f64 = self.order_input.quantity.parse().unwrap_or(0.0);
+        if price <= 0.0 || quantity <= 0.0 {
+            return None;
+        }
+
+        let side = if self.order_input.side == PolymarketOrderSide::BUY {
+            BinanceOrderSide::BUY
+        } else {
+            BinanceOrderSide::SELL
         };
-        self.update_chart_for_timeframe();
+        Some(client.create_limit_order(&self.selected_binance_symbol(), side, quantity, price, BinanceTimeInForce::GTC))
     }
 
-    pub fn previous_timeframe(&mut self) {
-        self.selected_timeframe = match self.selected_timeframe {
-            ChartTimeframe::OneMinute => ChartTimeframe::OneDay,
-            ChartTimeframe::FiveMinutes => ChartTimeframe::OneMinute,
-            ChartTimeframe::FifteenMinutes => ChartTimeframe::FiveMinutes,
-            ChartTimeframe::OneHour => ChartTimeframe::FifteenMinutes,
-            ChartTimeframe::FourHours => ChartTimeframe::OneHour,
-            ChartTimeframe::OneDay => ChartTimeframe::FourHours,
+    /// Checks the order form against the cached exchange filters for the
+    /// selected coin. Returns `None` while there is nothing to check: no
+    /// Binance client, no filters fetched yet, or an incomplete form.
+    pub fn binance_filter_check(&self) -> Option<Result<(), FilterViolation>> {
+        let client = self.binance_orders.as_ref()?;
+        let filters = self.symbol_filters.get(&self.selected_binance_symbol())?;
+        let request = self.binance_order_request(client)?;
+        Some(BinanceOrderClient::validate(&request, filters))
+    }
+
+    /// Row of the selected order on the Orders tab, where rows run newest
+    /// first. `None` if nothing is selected or the order is gone.
+    pub fn selected_order_row(&self) -> Option<usize> {
+        let id = self.selected_order_id.as_deref()?;
+        self.order_history.iter().rev().position(|record| record.order_id == id)
+    }
+
+    pub fn move_orders_cursor_up(&mut self) {
+        self.move_orders_selection(-1);
+    }
+
+    pub fn move_orders_cursor_down(&mut self) {
+        self.move_orders_selection(1);
+    }
+
+    /// Moves the Orders tab selection `delta` rows, clamped to the first and
+    /// last row, and scrolls so it stays on screen. With nothing selected it
+    /// starts at the newest order.
+    pub fn move_orders_selection(&mut self, delta: isize) {
+        let rows = self.order_history.len();
+        let Some(last) = rows.checked_sub(1) else {
+            self.selected_order_id = None;
+            self.orders_scroll = 0;
+            return;
         };
-        self.update_chart_for_timeframe();
+        let row = match self.selected_order_row() {
+            Some(row) => row.saturating_add_signed(delta).min(last),
+            None => 0,
+        };
+        self.selected_order_id = Some(self.order_history[last - row].order_id.clone());
+
+        let max_scroll = rows.saturating_sub(ORDER_HISTORY_ROWS);
+        self.orders_scroll = self
+            .orders_scroll
+            .clamp((row + 1).saturating_sub(ORDER_HISTORY_ROWS), row)
+            .min(max_scroll);
     }
 
-    pub fn update_chart_for_timeframe(&mut self) {
-        // Generate appropriate candlestick data for the selected timeframe
-        let base_price = self.market_data.current_price;
-        let mut rng = rand::thread_rng();
-        
-        self.candlestick_data.clear();
-        
-        // Generate more data points for shorter timeframes
-        let data_points = match self.selected_timeframe {
-            ChartTimeframe::OneMinute => 60,      // 1 hour of 1-minute data
-            ChartTimeframe::FiveMinutes => 72,    // 6 hours of 5-minute data
-            ChartTimeframe::FifteenMinutes => 96, // 24 hours of 15-minute data
-            ChartTimeframe::OneHour => 168,       // 1 week of hourly data
-            ChartTimeframe::FourHours => 168,     // 4 weeks of 4-hour data
-            ChartTimeframe::OneDay => 30,         // 30 days of daily data
+    /// Cancels the Orders tab row under the cursor on the venue it was placed
+    /// on. Exchange cancels run in the background.
+    pub fn cancel_selected_history_order(&mut self) {
+        // Rows are shown newest first
+        let Some(index) = self.selected_order_row().and_then(|row| self.order_history.len().checked_sub(row + 1)) else {
+            return;
         };
-        
-        for i in 0..data_points {
-            let duration = self.selected_timeframe.duration();
-            let timestamp = chrono::Utc::now() - duration * (data_points - i) as i32;
-            
-            let trend_factor = (i as f64 / data_points as f64) * 0.05; // Small trend
-            let volatility = (rng.gen::<f64>() - 0.5) * 0.02; // Volatility based on timeframe
-            let price = base_price * (1.0 + trend_factor + volatility);
-            
-            let high = price + (rng.gen::<f64>() - 0.5) * 100.0;
-            let low = price - (rng.gen::<f64>() - 0.5) * 100.0;
-            let open = if i == 0 { base_price } else { self.candlestick_data[i-1].close };
-            let close = price;
-            let volume = rng.gen::<f64>() * 500_000_000.0 + 100_000_000.0;
-            
-            self.candlestick_data.push(Candlestick::new(
-                timestamp,
-                open,
-                high,
-                low,
-                close,
-                volume,
-            ));
+        let Some(record) = self.order_history.get(index) else {
+            return;
+        };
+        let order_id = record.order_id.clone();
+        if record.status == "Rejected" {
+            self.log(format!("⚠️ Order {} was rejected; there is nothing to cancel", order_id));
+            return;
         }
-        
-        self.real_time_data.push_back(format!(
-            "📊 Chart updated to {} timeframe",
-            self.selected_timeframe.as_str()
-        ));
-    }
 
-    // Price Alert Management Functions
-    pub fn add_price_alert(&mut self, symbol: String, alert_type: AlertType, message: String) -> u64 {
-        let alert_id = self.next_alert_id;
-        self.next_alert_id += 1;
-        
-        let message_clone = message.clone();
-        let alert = PriceAlert::new(alert_id, symbol, alert_type, message);
-        self.price_alerts.push(alert);
-        
-        self.real_time_data.push_back(format!(
-            "🔔 Price alert created: {}",
-            message_clone
-        ));
-        
-        alert_id
-    }
-    
-    pub fn remove_price_alert(&mut self, alert_id: u64) -> bool {
-        if let Some(pos) = self.price_alerts.iter().position(|a| a.id == alert_id) {
-            let alert = self.price_alerts.remove(pos);
-            self.real_time_data.push_back(format!(
-                "🗑️ Alert removed: {}",
-                alert.message
-            ));
-            true
-        } else {
-            false
+        let (venue, symbol) = (record.venue, record.symbol.clone());
+        match venue {
+            OrderVenue::Local => {
+                let result = order_id
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|id| self.order_book.remove_order(id))
+                    .map(|_| ())
+                    .ok_or_else(|| "order is no longer resting".to_string());
+                self.finish_cancel(order_id, result);
+            }
+            OrderVenue::Binance => match (self.binance_orders.clone(), order_id.parse::<u64>()) {
+                (Some(client), Ok(id)) => {
+                    let request = async move {
+                        match client.cancel_order(&symbol, &BinanceOrderRef::OrderId(id)).await {
+                            Ok(_) => Ok(()),
+                            Err(BinanceError::UnknownOrder { .. }) => {
+                                Err("Binance does not know this order (already filled or cancelled?)".to_string())
+                            }
+                            Err(e) => Err(e.to_string()),
+                        }
+                    };
+                    self.spawn_request(request, move |app, result| app.finish_cancel(order_id, result));
+                }
+                (None, _) => self.finish_cancel(order_id, Err("Binance trading is not enabled".to_string())),
+                (_, Err(_)) => self.finish_cancel(order_id, Err("invalid Binance order id".to_string())),
+            },
+            OrderVenue::Polymarket => match self.polymarket_client.clone() {
+                Some(client) => {
+                    let id = order_id.clone();
+                    let request = async move {
+                        match client.cancel_order(&id).await {
+                            Ok(response) if response.canceled.contains(&id) => Ok(()),
+                            Ok(response) => Err(response
+                                .not_canceled
+                                .get(&id)
+                                .cloned()
+                                .unwrap_or_else(|| "not cancelled".to_string())),
+                            Err(e) => Err(e.to_string()),
+                        }
+                    };
+                    self.spawn_request(request, move |app, result| app.finish_cancel(order_id, result));
+                }
+                None => self.finish_cancel(order_id, Err("Polymarket client not initialized".to_string())),
+            },
         }
     }
-    
-    pub fn toggle_price_alert(&mut self, alert_id: u64) -> bool {
-        if let Some(alert) = self.price_alerts.iter_mut().find(|a| a.id == alert_id) {
-            alert.is_active = !alert.is_active;
-            let status = if alert.is_active { "enabled" } else { "disabled" };
-            self.real_time_data.push_back(format!(
-                "🔔 Alert {}: {}",
-                status, alert.message
-            ));
-            true
-        } else {
-            false
+
+    /// Marks `order_id` Cancelled, or logs why it could not be cancelled.
+    fn finish_cancel(&mut self, order_id: String, result: Result<(), String>) {
+        match result {
+            Ok(()) => {
+                self.mark_orders_cancelled(std::slice::from_ref(&order_id));
+                self.log(format!("❌ Cancelled order #{}", order_id));
+            }
+            Err(reason) => {
+                self.log(format!("⚠️ Cannot cancel order #{}: {}", order_id, reason));
+            }
         }
     }
-    
-    pub fn check_all_alerts(&mut self, current_price: f64, previous_price: f64, volume: f64) {
-        // Check each alert and collect messages for triggered ones
-        let mut alert_messages = Vec::new();
-        
-        for alert in &mut self.price_alerts {
-            if alert.check_trigger(current_price, previous_price, volume) {
-                alert_messages.push(format!(
-                    "🚨 ALERT TRIGGERED: {} - Price: ${:.2}",
-                    alert.message, current_price
-                ));
-                
-                if self.alert_sound_enabled {
-                    alert_messages.push("🔊 Alert sound played".to_string());
-                }
+
+    /// Marks the order history rows with these ids as Cancelled, returning how
+    /// many rows changed.
+    pub fn mark_orders_cancelled(&mut self, order_ids: &[String]) -> usize {
+        let mut marked = 0;
+        for record in self.order_history.iter_mut() {
+            if record.status != "Cancelled" && order_ids.contains(&record.order_id) {
+                record.status = "Cancelled".to_string();
+                marked += 1;
             }
         }
-        
-        // Add all messages to real-time data
-        for message in alert_messages {
-            self.real_time_data.push_back(message);
+        marked
+    }
+
+    /// Cancels every open Polymarket order and reports any the CLOB refused.
+    pub fn cancel_all_polymarket_orders(&mut self) {
+        let Some(client) = self.polymarket_client.clone() else {
+            self.log("Polymarket client not initialized".to_string());
+            return;
+        };
+
+        self.spawn_request(async move { client.cancel_all().await }, |app, response| match response {
+            Ok(response) => {
+                app.mark_orders_cancelled(&response.canceled);
+                app.log(format!("❌ Cancelled {} Polymarket orders", response.canceled.len()));
+                let mut refused: Vec<_> = response.not_canceled.into_iter().collect();
+                refused.sort();
+                for (order_id, reason) in refused {
+                    app.log(format!("⚠️ Cannot cancel order #{}: {}", order_id, reason));
+                }
+            }
+            Err(e) => app.log(format!("Failed to cancel Polymarket orders: {}", e)),
+        });
+    }
+
+    /// Runs the order form against `order_book` as a limit or market order
+    /// and records the fills, plus the resting remainder of a limit order.
+    /// The trading mode decides whether a limit order may rest or take.
+    pub fn submit_local_order(&mut self) {
+        let request = match self.order_input.local_order_request() {
+            Ok(request) => request,
+            Err(reason) => return self.reject_order(OrderVenue::Local, reason),
+        };
+        let timestamp = chrono::Utc::now().timestamp_millis() as u64;
+        let time_in_force = self.trading_mode.time_in_force(TimeInForce::GoodTilCancelled);
+        let (order_id, side, price, quantity, trades) = match request {
+            LocalOrderRequest::Limit { side, price, quantity } if time_in_force == TimeInForce::PostOnly => {
+                match self.order_book.add_post_only_order(side, price, quantity, timestamp) {
+                    Ok(order_id) => (Some(order_id), side, Some(price), quantity, Vec::new()),
+                    Err(rejected) => return self.reject_order(OrderVenue::Local, rejected.to_string()),
+                }
+            }
+            LocalOrderRequest::Limit { side, price, quantity } => {
+                let (order_id, trades) = self.order_book.add_limit_order(side, price, quantity, timestamp);
+                if time_in_force == TimeInForce::ImmediateOrCancel {
+                    self.order_book.remove_order(order_id);
+                }
+                (Some(order_id), side, Some(price), quantity, trades)
+            }
+            LocalOrderRequest::Market { .. } if time_in_force == TimeInForce::PostOnly => {
+                let reason = format!("{} mode only places post-only limit orders", self.trading_mode.as_str());
+                return self.reject_order(OrderVenue::Local, reason);
+            }
+            LocalOrderRequest::Market { side, quantity } => {
+                (None, side, None, quantity, self.order_book.add_market_order(side, quantity, timestamp))
+            }
+        };
+        let rests = order_id.is_some() && time_in_force != TimeInForce::ImmediateOrCancel;
+
+        for trade in &trades {
+            self.log(format!(
+                "💱 Fill: {:.5} @ ${:.2} (bid #{} / ask #{})",
+                trade.quantity, trade.price, trade.bid_order_id, trade.ask_order_id
+            ));
+        }
+
+        let filled_quantity: f64 = trades.iter().map(|t| t.quantity).sum();
+        let status = if filled_quantity >= quantity {
+            "Filled"
+        } else if filled_quantity > 0.0 {
+            "Partially Filled"
+        } else if rests {
+            "Resting"
+        } else {
+            "Unfilled"
+        };
+
+        // Fills get their own rows; a limit order itself only shows while part of it rests
+        self.record_trades(&trades);
+        self.check_trade_alerts(&trades);
+        match (order_id, price) {
+            (Some(order_id), Some(price)) => {
+                if rests && filled_quantity < quantity {
+                    self.order_history.push_back(OrderRecord {
+                        timestamp: chrono::Utc::now(),
+                        venue: OrderVenue::Local,
+                        symbol: self.current_market.clone(),
+                        side,
+                        price,
+                        quantity: quantity - filled_quantity,
+                        status: "Resting".to_string(),
+                        order_id: order_id.to_string(),
+                        reason: None,
+                    });
+                }
+                self.log(format!(
+                    "Local order #{} {:?} {} at ${}: {}",
+                    order_id, side, quantity, price, status
+                ));
+            }
+            _ => self.log(format!(
+                "Local market order {:?} {}: {} ({} filled)",
+                side, quantity, status, filled_quantity
+            )),
         }
+
+        // The book changed under the Order Book tab's scroll and cursor
+        self.scroll_book(0);
+
+        // Clear order input
+        self.order_input.active = false;
+        self.order_input.price.clear();
+        self.order_input.quantity.clear();
     }
-    
-    pub fn get_active_alerts_count(&self) -> usize {
-        self.price_alerts.iter().filter(|a| a.is_active).count()
+
+    /// Appends a "Filled" history row per trade, on the aggressor's side at the
+    /// execution price. The row id is "<bid id>-<ask id>", so a fill always maps
+    /// to the same id and never collides with a resting order's id.
+    pub fn record_trades(&mut self, trades: &[Trade]) {
+        for trade in trades {
+            self.order_history.push_back(OrderRecord {
+                timestamp: chrono::Utc::now(),
+                venue: OrderVenue::Local,
+                symbol: self.current_market.clone(),
+                side: trade.aggressor_side,
+                price: trade.price,
+                quantity: trade.quantity,
+                status: "Filled".to_string(),
+                order_id: format!("{}-{}", trade.bid_order_id, trade.ask_order_id),
+                reason: None,
+            });
+        }
     }
-    
-    pub fn get_triggered_alerts_count(&self) -> usize {
-        self.price_alerts.iter().filter(|a| a.triggered_at.is_some()).count()
+
+    /// Walks the filled orders for the current market in submission order and
+    /// matches buys against sells FIFO.
+    pub fn compute_pnl(&self) -> PnlSummary {
+        // Open lots as (signed quantity, price); all lots share one direction.
+        let mut lots: VecDeque<(f64, f64)> = VecDeque::new();
+        let mut realized_pnl = 0.0;
+
+        let fills = self.order_history
+            .iter()
+            .filter(|record| record.status == "Filled" && record.symbol == self.current_market);
+
+        for record in fills {
+            let direction = if record.side == OrderSide::Bid { 1.0 } else { -1.0 };
+            let mut remaining = record.quantity;
+
+            while remaining > 0.0 {
+                match lots.front_mut() {
+                    Some((lot_quantity, lot_price)) if *lot_quantity * direction < 0.0 => {
+                        let matched = remaining.min(lot_quantity.abs());
+                        // Closing a long earns (exit - entry); closing a short earns (entry - exit)
+                        realized_pnl += (record.price - *lot_price) * matched * -direction;
+                        *lot_quantity += matched * direction;
+                        remaining -= matched;
+                        if lot_quantity.abs() < 1e-9 {
+                            lots.pop_front();
+                        }
+                    }
+                    _ => {
+                        lots.push_back((remaining * direction, record.price));
+                        remaining = 0.0;
+                    }
+                }
+            }
+        }
+
+        let position: f64 = lots.iter().map(|(quantity, _)| quantity).sum();
+        let average_entry = if position != 0.0 {
+            Some(lots.iter().map(|(quantity, price)| quantity * price).sum::<f64>() / position)
+        } else {
+            None
+        };
+
+        PnlSummary {
+            symbol: self.current_market.clone(),
+            realized_pnl,
+            position,
+            average_entry,
+        }
     }
 
-    // WebSocket and Real Data Management
-    pub fn toggle_real_data(&mut self) {
-        self.use_real_data = !self.use_real_data;
-        let _status = if self.use_real_data { "enabled" } else { "disabled" };
+        pub fn update_market_data(&mut self) {
+        // Store previous price for alert checking
+        let previous_price = self.market_data.current_price;
         
-        if self.use_real_data {
-            self.binance_ws.update_status("Connecting to Binance...", false);
-            self.real_time_data.push_back("🔄 Switching to real Binance data...".to_string());
-            // In a real implementation, this would start the WebSocket connection
+        // Simulate market data updates; the candle update below moves it again
+        let change = self.simulator.price_move() / 2.0;
+        self.market_data.current_price += change;
+        self.market_data.price_change = change;
+        self.market_data.price_change_percent = percent_change(change, self.market_data.current_price - change);
+        self.market_data.volume_24h += self.simulator.rng().gen::<f64>() * 100_000_000.0;
+        
+        // Update candlestick data
+        self.update_candlestick_data();
+        
+        // Alerts see the final price, after the candle update moved it too
+        self.check_all_alerts(self.market_data.current_price, previous_price, self.market_data.volume_24h);
+        
+        // Update real-time service status
+        self.real_time_service.update_connection_status("Live Updates", true);
+        
+        self.last_update = chrono::Utc::now();
+    }
+
+    /// Advances the simulated market by one refresh, unless it is paused.
+    pub fn simulation_tick(&mut self) {
+        if self.simulator.is_paused() {
+            return;
+        }
+        self.update_market_data();
+        self.simulate_real_time_updates();
+    }
+
+    pub fn toggle_simulation_pause(&mut self) {
+        if self.simulator.is_paused() {
+            self.simulator.resume();
+            self.log("▶️ Simulation resumed".to_string());
         } else {
-            self.binance_ws.update_status("Simulated data", false);
-            self.real_time_data.push_back("🔄 Switching to simulated data...".to_string());
+            self.simulator.pause();
+            self.log("⏸️ Simulation paused".to_string());
         }
     }
-    
-    pub fn simulate_binance_connection(&mut self) {
-        if self.use_real_data {
-            // Simulate WebSocket connection for demo purposes
-            self.binance_ws.update_status("Connected to Binance", true);
-            self.real_time_data.push_back("✅ Connected to Binance WebSocket".to_string());
+
+    pub fn simulate_real_time_updates(&mut self) {
+        if !self.auto_refresh {
+            return;
+        }
+        
+        // Randomly add orders to simulate market activity
+        let (chance, band) = self.trading_mode.simulated_order_flow();
+        if let Some((side, price, quantity)) = self.simulator.next_order(self.market_data.current_price, chance, band) {
+            self.order_book.add_order(side, price, quantity, 
+                (chrono::Utc::now().timestamp() as u64) % 10000);
             
-            // Simulate receiving real data
-            self.binance_ws.record_message();
-            self.real_time_data.push_back("📡 Receiving live market data from Binance".to_string());
+            self.log(format!(
+                "🔄 New {} order: {:.2} @ ${:.2}",
+                if side == OrderSide::Bid { "bid" } else { "ask" },
+                quantity, price
+            ));
         }
+        
+        // Update connection status
+        self.real_time_service.update_connection_status("Live Updates", true);
     }
-    
-    pub fn get_connection_summary(&self) -> String {
-        if self.use_real_data {
-            format!(
-                "Binance WebSocket: {} | Messages: {} | Errors: {} | Last: {}",
-                if self.binance_ws.is_connected { "🟢 Connected" } else { "🔴 Disconnected" },
-                self.binance_ws.message_count,
-                self.binance_ws.error_count,
-                self.binance_ws.last_message.format("%H:%M:%S")
-            )
-        } else {
-            "Simulated data mode - Press 'r' to toggle real data".to_string()
+
+    /// Folds a simulated price tick into the current minute candle and moves
+    /// the market data with it.
+    pub fn update_candlestick_data(&mut self) {
+        let last_price = self.minute_candles.last().map_or(self.market_data.current_price, |c| c.close);
+        let price_change = self.simulator.price_move();
+        let new_price = last_price + price_change;
+        let volume = self.simulator.rng().gen::<f64>() * 50_000_000.0;
+        candles::record_tick(&mut self.minute_candles, chrono::Utc::now(), new_price, volume);
+        self.resample_candles();
+        
+        self.market_data.current_price = new_price;
+        self.market_data.price_change = price_change;
+        self.market_data.price_change_percent = percent_change(price_change, last_price);
+    }
+
+    /// Replaces the minute candles with a simulated random walk over the last
+    /// `MINUTE_CANDLE_CAPACITY` minutes that ends at `price`.
+    fn seed_simulated_candles(&mut self, price: f64) {
+        let rng = self.simulator.rng();
+        let now = candles::bucket_start(chrono::Utc::now(), &ChartTimeframe::OneMinute);
+        let mut close = price;
+        let mut minutes = Vec::with_capacity(MINUTE_CANDLE_CAPACITY);
+        // Walk backwards from the current price, each candle opening where
+        // the one before it closed
+        for age in 0..MINUTE_CANDLE_CAPACITY {
+            let open = close * (1.0 + (rng.gen::<f64>() - 0.5) * 0.002);
+            let high = open.max(close) * (1.0 + rng.gen::<f64>() * 0.0005);
+            let low = open.min(close) * (1.0 - rng.gen::<f64>() * 0.0005);
+            let volume = rng.gen::<f64>() * 5_000_000.0 + 1_000_000.0;
+            minutes.push(Candlestick::new(now - chrono::Duration::minutes(age as i64), open, high, low, close, volume));
+            close = open;
         }
+        minutes.reverse();
+        self.minute_candles = minutes;
     }
-    
-    // Terminal chart management
-    pub fn resize_terminal_chart(&mut self, width: u32, height: u32) {
-        self.terminal_chart = TerminalChartBackend::new(width, height);
+
+    fn resample_candles(&mut self) {
+        self.candlestick_data = candles::resample(&self.minute_candles, &self.selected_timeframe);
     }
-    
-    pub fn update_terminal_chart_data(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        if self.candlestick_data.is_empty() {
-            return Ok(());
+
+    pub fn toggle_order_input(&mut self) {
+        self.order_input.active = !self.order_input.active;
+        if self.order_input.active {
+            self.log("Order input mode activated".to_string());
+        } else {
+            self.log("Order input mode deactivated".to_string());
         }
+    }
+
+    pub fn refresh_order_book(&mut self) {
+        // Get the currently selected coin info first
+        let Some(coin) = self.selected_coin() else {
+            return;
+        };
+        let (coin_symbol, base_price) = (coin.symbol.clone(), coin.price);
         
-        // Update the terminal chart with current data
-        self.terminal_chart.draw_candlestick_chart(
-            &self.candlestick_data,
-            self.market_data.current_price
-        )
+        // Add some new orders based on current market conditions
+        let current_time = chrono::Utc::now();
+        let rng = self.simulator.rng();
+        
+        // Generate orders around the current price with realistic spreads
+        let spread = base_price * 0.001; // 0.1% spread
+        
+        // Add a few new bid orders
+        for _ in 0..2 {
+            let price_offset = (rng.gen::<f64>() - 0.5) * spread;
+            let bid_price = base_price + price_offset - spread / 2.0;
+            
+            let quantity = match coin_symbol.as_str() {
+                "BTC" => rng.gen_range(0.01..0.5),
+                "ETH" => rng.gen_range(0.1..5.0),
+                "SOL" => rng.gen_range(1.0..50.0),
+                "ADA" => rng.gen_range(100.0..5000.0),
+                "DOT" => rng.gen_range(10.0..200.0),
+                "LINK" => rng.gen_range(20.0..500.0),
+                _ => rng.gen_range(0.5..50.0),
+            };
+            
+            self.order_book.add_order(OrderSide::Bid, bid_price, quantity, 
+                (current_time.timestamp() as u64) % 10000);
+        }
+        
+        // Add a few new ask orders
+        for _ in 0..2 {
+            let price_offset = (rng.gen::<f64>() - 0.5) * spread;
+            let ask_price = base_price + price_offset + spread / 2.0;
+            
+            let quantity = match coin_symbol.as_str() {
+                "BTC" => rng.gen_range(0.01..0.3),
+                "ETH" => rng.gen_range(0.1..3.0),
+                "SOL" => rng.gen_range(1.0..30.0),
+                "ADA" => rng.gen_range(100.0..3000.0),
+                "DOT" => rng.gen_range(10.0..150.0),
+                "LINK" => rng.gen_range(20.0..300.0),
+                _ => rng.gen_range(0.5..30.0),
+            };
+            
+            self.order_book.add_order(OrderSide::Ask, ask_price, quantity, 
+                (current_time.timestamp() as u64) % 10000);
+        }
+        
+        self.log(format!(
+            "Order book refreshed for {} - added new orders around ${:.2}",
+            coin_symbol, base_price
+        ));
+    }
+
+    /// The settings as they stand, for saving.
+    pub fn config(&self) -> AppConfig {
+        AppConfig {
+            auto_refresh: self.auto_refresh,
+            refresh_interval: self.refresh_interval.as_secs_f64(),
+            alert_sound_enabled: self.alert_sound_enabled,
+            use_real_data: self.use_real_data,
+            default_coin: self.default_coin.clone(),
+            theme: self.theme.name.clone(),
+            alert_history_limit: self.alert_history_limit,
+            seed: self.config_seed,
+            log_level: self.config_log_level.clone(),
+            log_file: self.config_log_file.clone(),
+            simulator: self.simulator.params,
+            themes: self.custom_themes.clone(),
+        }
+    }
+
+    /// Applies `config`, selecting its default coin. A refresh interval out
+    /// of bounds, invalid simulator params or an unknown coin are logged and
+    /// the current ones kept.
+    pub fn apply_config(&mut self, config: &AppConfig) {
+        self.auto_refresh = config.auto_refresh;
+        match parse_refresh_interval(&config.refresh_interval.to_string()) {
+            Ok(interval) => self.refresh_interval = interval,
+            Err(reason) => self.log(format!("⚠️ Settings: {}", reason)),
+        }
+        self.alert_sound_enabled = config.alert_sound_enabled;
+        self.set_alert_history_limit(config.alert_history_limit);
+        self.config_seed = config.seed;
+        self.config_log_level = config.log_level.clone();
+        self.config_log_file = config.log_file.clone();
+        match config.simulator.validate() {
+            Ok(()) => self.simulator.params = config.simulator,
+            Err(reason) => self.log(format!("⚠️ Settings: simulator {}", reason)),
+        }
+        if self.use_real_data != config.use_real_data {
+            self.toggle_real_data();
+        }
+        match self.available_coins.iter().position(|coin| coin.symbol.eq_ignore_ascii_case(&config.default_coin)) {
+            Some(index) => {
+                self.default_coin = self.available_coins[index].symbol.clone();
+                self.select_coin_by_index(index);
+            }
+            None => self.log(format!("⚠️ Settings: unknown default coin {}", config.default_coin)),
+        }
+        self.custom_themes.clear();
+        for custom in &config.themes {
+            match custom.resolve() {
+                Ok(_) => self.custom_themes.push(custom.clone()),
+                Err(reason) => self.log(format!("⚠️ Settings: {}", reason)),
+            }
+        }
+        if let Err(reason) = self.set_theme(&config.theme) {
+            self.log(format!("⚠️ Settings: {}", reason));
+        }
+    }
+
+    /// The presets followed by the config file's custom themes.
+    pub fn themes(&self) -> Vec<Theme> {
+        let custom = self.custom_themes.iter().filter_map(|custom| custom.resolve().ok());
+        Theme::presets().into_iter().chain(custom).collect()
+    }
+
+    /// Switches to the theme called `name`, ignoring case.
+    pub fn set_theme(&mut self, name: &str) -> Result<(), String> {
+        let theme = self
+            .themes()
+            .into_iter()
+            .find(|theme| theme.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("Unknown theme '{}'; try theme to list them", name.trim()))?;
+        self.theme = theme;
+        Ok(())
+    }
+
+    /// Loads and applies the settings at `config_path` at startup.
+    fn restore_config(&mut self) {
+        let Some(path) = self.config_path.clone() else {
+            return;
+        };
+        match AppConfig::load(&path) {
+            Ok(config) => self.apply_config(&config),
+            Err(reason) => self.log(format!("⚠️ {}; using default settings", reason)),
+        }
+    }
+
+    /// Saves the settings to `config_path`, if set, logging any failure.
+    fn persist_config(&mut self) {
+        let Some(path) = self.config_path.clone() else {
+            return;
+        };
+        if let Err(reason) = self.config().save(&path) {
+            self.log(format!("⚠️ {}", reason));
+        }
+    }
+
+    pub fn selected_setting(&self) -> Setting {
+        Setting::ALL[self.settings_selected.min(Setting::ALL.len() - 1)]
+    }
+
+    pub fn move_settings_selection(&mut self, delta: isize) {
+        self.settings_selected = self.settings_selected.saturating_add_signed(delta).min(Setting::ALL.len() - 1);
+    }
+
+    /// `setting`'s current value as the Settings tab shows it.
+    pub fn setting_value(&self, setting: Setting) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" }.to_string();
+        match setting {
+            Setting::AutoRefresh => on_off(self.auto_refresh),
+            Setting::RefreshInterval => format!("{}s", self.refresh_interval.as_secs_f64()),
+            Setting::AlertSound => on_off(self.alert_sound_enabled),
+            Setting::RealData => on_off(self.use_real_data),
+            Setting::DefaultCoin => self.default_coin.clone(),
+            Setting::Theme => self.theme.name.clone(),
+        }
+    }
+
+    /// Toggles the selected setting, or steps it to its next (or previous)
+    /// value, then saves the settings.
+    pub fn change_selected_setting(&mut self, forward: bool) {
+        let setting = self.selected_setting();
+        match setting {
+            Setting::AutoRefresh => self.auto_refresh = !self.auto_refresh,
+            Setting::RefreshInterval => {
+                let current = self.refresh_interval.as_secs_f64();
+                let next = if forward {
+                    REFRESH_INTERVAL_STEPS.iter().find(|step| **step > current).unwrap_or(&REFRESH_INTERVAL_STEPS[0])
+                } else {
+                    REFRESH_INTERVAL_STEPS
+                        .iter()
+                        .rev()
+                        .find(|step| **step < current)
+                        .unwrap_or(&REFRESH_INTERVAL_STEPS[REFRESH_INTERVAL_STEPS.len() - 1])
+                };
+                self.refresh_interval = Duration::from_secs_f64(*next);
+            }
+            Setting::AlertSound => self.alert_sound_enabled = !self.alert_sound_enabled,
+            Setting::RealData => self.toggle_real_data(),
+            Setting::DefaultCoin => {
+                let count = self.available_coins.len();
+                let current = self
+                    .available_coins
+                    .iter()
+                    .position(|coin| coin.symbol == self.default_coin)
+                    .unwrap_or(0);
+                let next = if forward { (current + 1) % count } else { (current + count - 1) % count };
+                self.default_coin = self.available_coins[next].symbol.clone();
+            }
+            Setting::Theme => {
+                let themes = self.themes();
+                let count = themes.len();
+                let current = themes.iter().position(|theme| theme.name == self.theme.name).unwrap_or(0);
+                let next = if forward { (current + 1) % count } else { (current + count - 1) % count };
+                self.theme = themes[next].clone();
+            }
+        }
+        self.log(format!("{}: {}", setting.label(), self.setting_value(setting)));
+        self.persist_config();
+    }
+
+    /// Cycles Normal, Aggressive and Conservative; see `TradingMode`.
+    pub fn toggle_trading_mode(&mut self) {
+        self.trading_mode = self.trading_mode.next();
+        self.log(format!("Trading mode: {}", self.trading_mode.as_str()));
+    }
+
+    pub fn cycle_order_field_up(&mut self) {
+        if self.order_input.active {
+            self.order_input.previous_field();
+        }
+    }
+
+    /// Moves the order book selection towards the top of the ladder
+    /// (highest ask first, then down through the bids).
+    pub fn move_book_cursor_up(&mut self) {
+        let rows = self.book_row_count();
+        self.book_cursor = match self.book_cursor {
+            _ if rows == 0 => None,
+            Some(0) | None => Some(0),
+            Some(index) => Some(index.min(rows) - 1),
+        };
+    }
+
+    pub fn move_book_cursor_down(&mut self) {
+        let rows = self.book_row_count();
+        self.book_cursor = match self.book_cursor {
+            _ if rows == 0 => None,
+            None => Some(0),
+            Some(index) => Some((index + 1).min(rows - 1)),
+        };
+    }
+
+    /// Rows the level cursor can move over. A loaded Polymarket book is
+    /// view-only, so the cursor stays off it.
+    fn book_row_count(&self) -> usize {
+        if self.polymarket_book.is_some() {
+            return 0;
+        }
+        let (bids, asks) = self.visible_book_depth(&self.order_book);
+        bids.len() + asks.len()
+    }
+
+    /// Scrolls the Order Book tab `delta` levels away from the touch
+    /// (negative: back towards it), stopping once the deepest side's last
+    /// level reaches the bottom of the panel.
+    pub fn scroll_book(&mut self, delta: isize) {
+        let (book, _) = self.displayed_book();
+        let (bids, asks) = book.get_market_depth(usize::MAX);
+        let max_scroll = bids.len().max(asks.len()).saturating_sub(BOOK_DEPTH_LEVELS);
+        self.book_scroll = self.book_scroll.min(max_scroll).saturating_add_signed(delta).min(max_scroll);
+
+        let rows = self.book_row_count();
+        self.book_cursor = self.book_cursor.and_then(|index| rows.checked_sub(1).map(|last| index.min(last)));
+    }
+
+    /// The levels the Order Book tab shows for `book`: `BOOK_DEPTH_LEVELS`
+    /// per side, starting `book_scroll` levels from the touch.
+    pub fn visible_book_depth(&self, book: &OrderBook) -> (DepthLevels, DepthLevels) {
+        let (bids, asks) = book.get_market_depth(self.book_scroll + BOOK_DEPTH_LEVELS);
+        // The book may have thinned out since the last scroll
+        let offset = self.book_scroll.min(bids.len().max(asks.len()).saturating_sub(BOOK_DEPTH_LEVELS));
+        let window = |levels: DepthLevels| levels.into_iter().skip(offset).collect();
+        (window(bids), window(asks))
+    }
+
+    /// Side and price of the level under the cursor. Rows run from the
+    /// highest displayed ask down to the lowest displayed bid.
+    pub fn selected_book_level(&self) -> Option<(OrderSide, f64)> {
+        if self.polymarket_book.is_some() {
+            return None;
+        }
+        let index = self.book_cursor?;
+        let (bids, asks) = self.visible_book_depth(&self.order_book);
+
+        if index < asks.len() {
+            Some((OrderSide::Ask, asks[asks.len() - 1 - index].0))
+        } else {
+            bids.get(index - asks.len()).map(|(price, _)| (OrderSide::Bid, *price))
+        }
+    }
+
+    /// Cancels a resting order at the selected level, preferring orders this
+    /// session placed on the local book over the front of the queue. Does
+    /// nothing while a Polymarket book is shown in place of the local one.
+    pub fn cancel_selected_order(&mut self) -> Option<Order> {
+        let (side, price) = self.selected_book_level()?;
+        let level_orders = self.order_book.get_level_orders(side, price);
+
+        let own_order = level_orders.iter().find(|order| {
+            self.order_history.iter().any(|record| record.order_id == order.id.to_string())
+        });
+        let selected_id = own_order.or(level_orders.first())?.id;
+
+        let cancelled = self.order_book.remove_order(selected_id)?;
+        if let Some(record) = self.order_history.iter_mut().find(|r| r.order_id == selected_id.to_string()) {
+            record.status = "Cancelled".to_string();
+        }
+        self.log(format!(
+            "❌ Cancelled order #{} {:?} {:.5} @ ${:.2}",
+            cancelled.id, cancelled.side, cancelled.quantity, cancelled.price.as_f64()
+        ));
+
+        let rows = self.book_row_count();
+        self.book_cursor = self.book_cursor.and_then(|index| rows.checked_sub(1).map(|last| index.min(last)));
+
+        Some(cancelled)
+    }
+
+    pub fn cycle_order_field_down(&mut self) {
+        if self.order_input.active {
+            self.order_input.next_field();
+        }
+    }
+
+    pub fn next_coin(&mut self) {
+        self.select_coin_by_index((self.selected_coin_index + 1) % self.available_coins.len());
+    }
+
+    pub fn previous_coin(&mut self) {
+        if self.selected_coin_index == 0 {
+            self.select_coin_by_index(self.available_coins.len() - 1);
+        } else {
+            self.select_coin_by_index(self.selected_coin_index - 1);
+        }
+    }
+
+    pub fn select_coin_by_index(&mut self, index: usize) {
+        if index < self.available_coins.len() {
+            let previous_symbol = self.selected_symbol();
+            let symbol = self.available_coins[index].symbol.clone();
+            self.selected_coin_index = index;
+            self.polymarket_book = None;
+            self.swap_active_order_book(&previous_symbol, &symbol);
+            self.update_market_data_for_selected_coin();
+            // The live stream follows the selected coin
+            if previous_symbol != symbol && self.market_stream.is_some() {
+                self.stop_market_stream();
+                self.connect_selected_market_stream();
+            }
+        }
+    }
+
+    /// The selected coin, or `None` should the index be past the coin list.
+    pub fn selected_coin(&self) -> Option<&CoinType> {
+        self.available_coins.get(self.selected_coin_index)
+    }
+
+    /// The selected coin's symbol, or "" without one.
+    pub fn selected_symbol(&self) -> String {
+        self.selected_coin().map(|coin| coin.symbol.clone()).unwrap_or_default()
+    }
+
+    /// Selects the coin `symbol`, e.g. "BTC", or its Binance pair "BTCUSDT",
+    /// ignoring case. Returns false, leaving the selection, for unknown coins.
+    pub fn select_coin_by_symbol(&mut self, symbol: &str) -> bool {
+        let symbol = symbol.to_ascii_uppercase();
+        let coin = symbol.strip_suffix("USDT").filter(|coin| !coin.is_empty()).unwrap_or(&symbol);
+        match self.available_coins.iter().position(|available| available.symbol.eq_ignore_ascii_case(coin)) {
+            Some(index) => {
+                self.select_coin_by_index(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds `symbol` to the coin list with an empty order book. With real data
+    /// on, its price, 24h change and volume come from the Binance ticker, and
+    /// the coin is added once the ticker arrives (an unknown market is
+    /// refused then); otherwise they are simulated.
+    pub fn add_coin(&mut self, symbol: &str) -> Result<(), String> {
+        let symbol = symbol.trim().to_ascii_uppercase();
+        if symbol.is_empty() || symbol.len() > 10 || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("Invalid coin symbol: {}", symbol));
+        }
+        if self.available_coins.iter().any(|coin| coin.symbol == symbol) {
+            return Err(format!("{} is already in the coin list", symbol));
+        }
+
+        if self.use_real_data {
+            let rest = self.binance_rest.clone();
+            let market = binance_symbol(&symbol);
+            self.spawn_request(async move { rest.get_ticker_24hr(&market).await }, move |app, ticker| match ticker {
+                Ok(ticker) => app.push_coin(CoinType::new(
+                    &symbol,
+                    &symbol,
+                    ticker.last_price,
+                    ticker.price_change,
+                    ticker.quote_volume,
+                    0.0,
+                )),
+                Err(e) => app.log(format!("Could not add {}: {}", symbol, e)),
+            });
+        } else {
+            let rng = self.simulator.rng();
+            let price = rng.gen_range(0.5..500.0);
+            let change = price * rng.gen_range(-0.05..0.05);
+            let volume = price * rng.gen_range(1.0e5..1.0e7);
+            self.push_coin(CoinType::new(&symbol, &symbol, price, change, volume, volume * 50.0));
+        }
+        Ok(())
+    }
+
+    /// Appends `coin` to the coin list with an empty order book, unless a coin
+    /// with its symbol was added in the meantime.
+    fn push_coin(&mut self, coin: CoinType) {
+        if self.available_coins.iter().any(|existing| existing.symbol == coin.symbol) {
+            self.log(format!("{} is already in the coin list", coin.symbol));
+            return;
+        }
+        self.log(format!("Added {} at ${:.4}", coin.symbol, coin.price));
+        self.order_books.insert(coin.symbol.clone(), OrderBook::default());
+        self.available_coins.push(coin);
+    }
+
+    /// Removes `symbol` and its order book. Removing the selected coin
+    /// selects the one after it (or before it, if it was last); the last
+    /// remaining coin can't be removed.
+    pub fn remove_coin(&mut self, symbol: &str) -> Result<(), String> {
+        let index = self
+            .available_coins
+            .iter()
+            .position(|coin| coin.symbol.eq_ignore_ascii_case(symbol))
+            .ok_or_else(|| format!("Unknown coin: {}", symbol))?;
+        if self.available_coins.len() == 1 {
+            return Err("Can't remove the only coin".to_string());
+        }
+
+        if index == self.selected_coin_index {
+            let neighbor = if index + 1 < self.available_coins.len() { index + 1 } else { index - 1 };
+            self.select_coin_by_index(neighbor);
+        }
+        let coin = self.available_coins.remove(index);
+        if self.selected_coin_index > index {
+            self.selected_coin_index -= 1;
+        }
+        self.order_books.remove(&coin.symbol);
+        self.log(format!("Removed {}", coin.symbol));
+        Ok(())
+    }
+
+    /// Fetches the Polymarket book for `token_id` in the background and shows
+    /// it on the Order Book tab once it arrives; an empty `token_id` switches
+    /// back to the coin's book.
+    pub fn load_polymarket_book(&mut self, token_id: &str) {
+        if token_id.is_empty() {
+            if self.polymarket_book.take().is_some() {
+                let coin = self.selected_symbol();
+                self.log(format!("Showing {} order book", coin));
+            } else {
+                self.log("Usage: pm book <token_id>".to_string());
+            }
+            return;
+        }
+        if let Err(reason) = validate_token_id(token_id) {
+            self.log(reason);
+            return;
+        }
+        let Some(client) = self.polymarket_client.clone() else {
+            self.log("Polymarket client not initialized".to_string());
+            return;
+        };
+
+        let token_id = token_id.to_string();
+        let requested = token_id.clone();
+        self.spawn_request(async move { client.get_order_book(&requested).await }, move |app, snapshot| {
+            match snapshot {
+                Ok(snapshot) => {
+                    let params = snapshot.market_params();
+                    if let Some(client) = app.polymarket_client.as_mut() {
+                        client.set_market_params(&token_id, params);
+                    }
+                    let book = snapshot.to_order_book();
+                    let (bids, asks) = book.get_total_price_levels();
+                    app.log(format!(
+                        "Loaded Polymarket book for {}: {} bid / {} ask levels",
+                        token_id, bids, asks
+                    ));
+                    app.polymarket_book = Some(PolymarketBookView { token_id, book, params });
+                    app.book_cursor = None;
+                }
+                Err(e) => app.log(format!("Failed to load Polymarket book: {}", e)),
+            }
+        });
+    }
+
+    /// The book drawn on the Order Book tab and the decimals its prices use.
+    pub fn displayed_book(&self) -> (&OrderBook, usize) {
+        match &self.polymarket_book {
+            Some(view) => (&view.book, view.params.price_decimals()),
+            None => (&self.order_book, self.current_price_decimals()),
+        }
+    }
+
+    /// Decimals `symbol`'s prices are shown with; `symbol` is a coin ("ETH")
+    /// or its Binance market ("ETHUSDT").
+    pub fn price_decimals_for(&self, symbol: &str) -> usize {
+        let coin = symbol.strip_suffix("USDT").unwrap_or(symbol);
+        self.price_decimals.get(coin).copied().unwrap_or(DEFAULT_PRICE_DECIMALS)
+    }
+
+    /// Decimals the selected coin's prices are shown with.
+    pub fn current_price_decimals(&self) -> usize {
+        self.price_decimals_for(&self.selected_symbol())
+    }
+
+    /// `price` formatted for the selected coin.
+    pub fn format_price(&self, price: f64) -> String {
+        Price(price).format_with(self.current_price_decimals())
+    }
+
+    /// Parks the active book under `previous_symbol` and activates the book for
+    /// `symbol`, creating an empty one the first time a coin is selected.
+    fn swap_active_order_book(&mut self, previous_symbol: &str, symbol: &str) {
+        if previous_symbol == symbol {
+            return;
+        }
+        let book = self.order_books.remove(symbol).unwrap_or_default();
+        let previous_book = std::mem::replace(&mut self.order_book, book);
+        self.order_books.insert(previous_symbol.to_string(), previous_book);
+        self.book_cursor = None;
+    }
+
+    /// Returns the book for `symbol`, whether or not it is the active one.
+    pub fn order_book_for(&self, symbol: &str) -> Option<&OrderBook> {
+        if self.selected_coin().is_some_and(|coin| coin.symbol == symbol) {
+            Some(&self.order_book)
+        } else {
+            self.order_books.get(symbol)
+        }
+    }
+
+    pub fn update_market_data_for_selected_coin(&mut self) {
+        // Get coin data first to avoid borrowing issues
+        let Some(coin) = self.selected_coin() else {
+            return;
+        };
+        let coin_symbol = coin.symbol.clone();
+        let coin_price = coin.price;
+        let coin_change = coin.change_24h;
+        let coin_volume = coin.volume_24h;
+        let coin_market_cap = coin.market_cap;
+        
+        // Update market data
+        self.current_market = coin_symbol.clone();
+        self.market_data.current_price = coin_price;
+        self.market_data.price_change = coin_change;
+        self.market_data.volume_24h = coin_volume;
+        self.market_data.market_cap = coin_market_cap;
+        
+        // Update candlestick data for the new coin
+        self.order_book.set_tick_size(None);
+        self.seed_simulated_candles(coin_price);
+        if self.use_real_data {
+            self.backfill_from_binance();
+        }
+        self.resample_candles();
+        
+        // Each coin keeps its own book; only seed one that is still empty
+        if self.order_book.get_total_orders() == 0 {
+            let book_price = self.market_data.current_price;
+            self.generate_realistic_order_book_for_coin_symbol(&coin_symbol, book_price);
+            self.log(format!(
+                "Switched to {} - Order book updated with realistic market data",
+                coin_symbol
+            ));
+        } else {
+            self.log(format!(
+                "Switched to {} - Restored order book ({} orders)",
+                coin_symbol, self.order_book.get_total_orders()
+            ));
+        }
+    }
+
+    /// Generate realistic order book data for a specific cryptocurrency
+    pub fn generate_realistic_order_book_for_coin_symbol(&mut self, coin_symbol: &str, base_price: f64) {
+        let rng = self.simulator.rng();
+        
+        // Generate realistic bid orders (buy orders) - below current price
+        let num_bid_levels = 15 + (rng.gen::<usize>() % 10); // 15-25 levels
+        for i in 0..num_bid_levels {
+            let price_offset = (i as f64 + 1.0) * (base_price * 0.001); // 0.1% increments
+            let bid_price = base_price - price_offset;
+            
+            // Generate realistic quantities based on price level
+            let base_quantity = match coin_symbol {
+                "BTC" => 0.1..2.0,
+                "ETH" => 1.0..20.0,
+                "SOL" => 10.0..200.0,
+                "ADA" => 1000.0..20000.0,
+                "DOT" => 50.0..1000.0,
+                "LINK" => 100.0..2000.0,
+                _ => 1.0..100.0, // Default range
+            };
+            
+            let quantity = rng.gen_range(base_quantity);
+            let timestamp = chrono::Utc::now().timestamp() as u64 - (i * 60) as u64; // Staggered timestamps
+            
+            self.order_book.add_order(OrderSide::Bid, bid_price, quantity, timestamp);
+        }
+        
+        // Generate realistic ask orders (sell orders) - above current price
+        let num_ask_levels = 15 + (rng.gen::<usize>() % 10); // 15-25 levels
+        for i in 0..num_ask_levels {
+            let price_offset = (i as f64 + 1.0) * (base_price * 0.001); // 0.1% increments
+            let ask_price = base_price + price_offset;
+            
+            // Generate realistic quantities based on price level
+            let base_quantity = match coin_symbol {
+                "BTC" => 0.05..1.5,
+                "ETH" => 0.5..15.0,
+                "SOL" => 5.0..150.0,
+                "ADA" => 500.0..15000.0,
+                "DOT" => 25.0..750.0,
+                "LINK" => 50.0..1500.0,
+                _ => 0.5..75.0, // Default range
+            };
+            
+            let quantity = rng.gen_range(base_quantity);
+            let timestamp = chrono::Utc::now().timestamp() as u64 - (i * 60) as u64; // Staggered timestamps
+            
+            self.order_book.add_order(OrderSide::Ask, ask_price, quantity, timestamp);
+        }
+        
+        // Add some market maker orders around the current price for liquidity
+        let spread = base_price * 0.0005; // 0.05% spread
+        let bid_price = base_price - spread / 2.0;
+        let ask_price = base_price + spread / 2.0;
+        
+        // Add larger market maker orders
+        let market_maker_quantity = match coin_symbol {
+            "BTC" => 0.5..2.0,
+            "ETH" => 5.0..20.0,
+            "SOL" => 50.0..200.0,
+            "ADA" => 5000.0..20000.0,
+            "DOT" => 100.0..500.0,
+            "LINK" => 200.0..1000.0,
+            _ => 10.0..500.0,
+        };
+        
+        let bid_quantity = rng.gen_range(market_maker_quantity.clone());
+        let ask_quantity = rng.gen_range(market_maker_quantity);
+        
+        self.order_book.add_order(OrderSide::Bid, bid_price, bid_quantity, chrono::Utc::now().timestamp() as u64);
+        self.order_book.add_order(OrderSide::Ask, ask_price, ask_quantity, chrono::Utc::now().timestamp() as u64);
+        
+        // Log the order book generation
+        self.log(format!(
+            "Generated {} bid levels and {} ask levels for {}",
+            num_bid_levels, num_ask_levels, coin_symbol
+        ));
+    }
+
+    pub fn get_trading_summary(&self) -> String {
+        let best_bid = self.order_book.get_best_bid().unwrap_or(0.0);
+        let best_ask = self.order_book.get_best_ask().unwrap_or(0.0);
+        let spread = self.order_book.get_spread().unwrap_or(0.0);
+        let spread_percent = if best_bid > 0.0 { (spread / best_bid) * 100.0 } else { 0.0 };
+        
+        format!(
+            "Bid: ${} | Ask: ${} | Spread: ${} ({:.2}%) | Orders: {}",
+            self.format_price(best_bid), self.format_price(best_ask), self.format_price(spread), spread_percent, 
+            self.order_book.get_market_depth(100).0.len() + self.order_book.get_market_depth(100).1.len()
+        )
+    }
+
+    pub fn get_market_trend(&self) -> &'static str {
+        if self.market_data.price_change > 0.0 {
+            if self.market_data.price_change_percent > 5.0 {
+                "Strong Bullish"
+            } else if self.market_data.price_change_percent > 1.0 {
+                "Bullish"
+            } else {
+                "Slightly Bullish"
+            }
+        } else {
+            if self.market_data.price_change_percent < -5.0 {
+                "Strong Bearish"
+            } else if self.market_data.price_change_percent < -1.0 {
+                "Bearish"
+            } else {
+                "Slightly Bearish"
+            }
+        }
+    }
+
+    pub fn calculate_risk_metrics(&self) -> (f64, f64, f64) {
+        let volatility = (self.market_data.high_24h - self.market_data.low_24h) / self.market_data.current_price * 100.0;
+        let volume_ratio = self.market_data.volume_24h / 1e9; // Convert to billions
+        let price_momentum = self.market_data.price_change_percent;
+        
+        (volatility, volume_ratio, price_momentum)
+    }
+
+    pub fn next_timeframe(&mut self) {
+        self.selected_timeframe = match self.selected_timeframe {
+            ChartTimeframe::OneMinute => ChartTimeframe::FiveMinutes,
+            ChartTimeframe::FiveMinutes => ChartTimeframe::FifteenMinutes,
+            ChartTimeframe::FifteenMinutes => ChartTimeframe::OneHour,
+            ChartTimeframe::OneHour => ChartTimeframe::FourHours,
+            ChartTimeframe::FourHours => ChartTimeframe::OneDay,
+            ChartTimeframe::OneDay => ChartTimeframe::OneMinute,
+        };
+        self.update_chart_for_timeframe();
+    }
+
+    pub fn previous_timeframe(&mut self) {
+        self.selected_timeframe = match self.selected_timeframe {
+            ChartTimeframe::OneMinute => ChartTimeframe::OneDay,
+            ChartTimeframe::FiveMinutes => ChartTimeframe::OneMinute,
+            ChartTimeframe::FifteenMinutes => ChartTimeframe::FiveMinutes,
+            ChartTimeframe::OneHour => ChartTimeframe::FifteenMinutes,
+            ChartTimeframe::FourHours => ChartTimeframe::OneHour,
+            ChartTimeframe::OneDay => ChartTimeframe::FourHours,
+        };
+        self.update_chart_for_timeframe();
+    }
+
+    /// Re-derives the chart's candles from the minute candles for the
+    /// selected timeframe.
+    pub fn update_chart_for_timeframe(&mut self) {
+        self.resample_candles();
+        self.log(format!(
+            "📊 Chart updated to {} timeframe",
+            self.selected_timeframe.as_str()
+        ));
+    }
+
+    /// Requests recent minute klines and the symbol's tick size from the
+    /// Binance REST API in the background. The klines replace the candles
+    /// when they arrive, provided the coin is still selected; until then (or
+    /// if the request fails) the simulated candles stay.
+    pub fn backfill_from_binance(&mut self) {
+        let coin = self.selected_symbol();
+        let symbol = binance_symbol(&coin);
+        let rest = self.binance_rest.clone();
+        let market = symbol.clone();
+        let request = async move {
+            tokio::join!(
+                rest.get_klines(&market, ChartTimeframe::OneMinute.as_str(), BACKFILL_MINUTES),
+                rest.get_exchange_info(&market)
+            )
+        };
+        self.spawn_request(request, move |app, (klines, exchange_info)| {
+            let selected = app.selected_symbol() == coin;
+            match exchange_info {
+                Ok(filters) => {
+                    if selected {
+                        app.order_book.set_tick_size(Some(filters.tick_size));
+                    }
+                    if filters.tick_size > 0.0 {
+                        // Enough decimals to show every tick, e.g. 2 for 0.01 and 0.05
+                        let decimals = (-filters.tick_size.log10() - 1e-9).ceil().max(0.0) as usize;
+                        app.price_decimals.insert(coin, decimals);
+                    }
+                    app.symbol_filters.insert(symbol.clone(), filters);
+                }
+                Err(e) => app.log(format!("⚠️ exchangeInfo {}: {}", symbol, e)),
+            }
+
+            let interval = ChartTimeframe::OneMinute.as_str();
+            match klines {
+                Ok(candles) if !candles.is_empty() => {
+                    if !selected {
+                        return;
+                    }
+                    if let Some(last) = candles.last() {
+                        app.apply_live_price(last.close);
+                    }
+                    app.log(format!(
+                        "📥 Loaded {} {} klines for {}",
+                        candles.len(), interval, symbol
+                    ));
+                    app.minute_candles = candles;
+                    app.resample_candles();
+                }
+                Ok(_) => app.log(format!("⚠️ No klines returned for {}", symbol)),
+                Err(e) => app.log(format!("⚠️ Kline backfill for {} failed: {}", symbol, e)),
+            }
+        });
+    }
+
+    // Price Alert Management Functions
+    pub fn add_price_alert(&mut self, symbol: String, alert_type: AlertType, message: String) -> u64 {
+        self.add_price_alert_with(symbol, alert_type, message, AlertBehavior::OneShot)
+    }
+
+    pub fn add_price_alert_with(
+        &mut self,
+        symbol: String,
+        alert_type: AlertType,
+        message: String,
+        behavior: AlertBehavior,
+    ) -> u64 {
+        let alert_id = self.next_alert_id;
+        self.next_alert_id += 1;
+        
+        let message_clone = message.clone();
+        let alert = PriceAlert::new(alert_id, symbol, alert_type, message)
+            .with_behavior(behavior)
+            .with_reference_price(self.market_data.current_price);
+        self.price_alerts.push(alert);
+        
+        self.log(format!(
+            "🔔 Price alert created: {}",
+            message_clone
+        ));
+        self.persist_alerts();
+        
+        alert_id
+    }
+    
+    pub fn remove_price_alert(&mut self, alert_id: u64) -> bool {
+        if let Some(pos) = self.price_alerts.iter().position(|a| a.id == alert_id) {
+            let alert = self.price_alerts.remove(pos);
+            self.clamp_alert_selection();
+            self.log(format!(
+                "🗑️ Alert removed: {}",
+                alert.message
+            ));
+            self.persist_alerts();
+            true
+        } else {
+            false
+        }
+    }
+    
+    pub fn toggle_price_alert(&mut self, alert_id: u64) -> bool {
+        if let Some(alert) = self.price_alerts.iter_mut().find(|a| a.id == alert_id) {
+            alert.is_active = !alert.is_active;
+            let status = if alert.is_active { "enabled" } else { "disabled" };
+            let message = format!("🔔 Alert {}: {}", status, alert.message);
+            self.log(message);
+            self.persist_alerts();
+            true
+        } else {
+            false
+        }
+    }
+    
+    /// Keeps the Alerts tab selection on a row after alerts are removed.
+    fn clamp_alert_selection(&mut self) {
+        self.alerts_selected = self.alerts_selected.min(self.price_alerts.len().saturating_sub(1));
+    }
+
+    /// Every coin's top of book and 24h change, in the Watchlist's sort order.
+    /// The selected coin's price is the live one.
+    pub fn watchlist_rows(&self) -> Vec<WatchlistRow> {
+        let mut rows: Vec<WatchlistRow> = self
+            .available_coins
+            .iter()
+            .enumerate()
+            .map(|(index, coin)| {
+                let book = self.order_book_for(&coin.symbol);
+                let last_price = if index == self.selected_coin_index { self.market_data.current_price } else { coin.price };
+                WatchlistRow {
+                    symbol: coin.symbol.clone(),
+                    last_price,
+                    change_percent: percent_change(coin.change_24h, coin.price),
+                    best_bid: book.and_then(OrderBook::get_best_bid),
+                    best_ask: book.and_then(OrderBook::get_best_ask),
+                }
+            })
+            .collect();
+        self.watchlist_sort.sort(&mut rows);
+        rows
+    }
+
+    /// Adds each coin's current mid to its sparkline history. Coins without a
+    /// two-sided book are skipped.
+    pub fn sample_watchlist_mids(&mut self) {
+        for coin in &self.available_coins {
+            if let Some(mid) = self.order_book_for(&coin.symbol).and_then(OrderBook::get_mid_price) {
+                self.watchlist_mids.entry(coin.symbol.clone()).or_default().push(mid);
+            }
+        }
+    }
+
+    pub fn cycle_watchlist_sort(&mut self) {
+        self.watchlist_sort = self.watchlist_sort.next();
+        self.log(format!("Watchlist sorted by {}", self.watchlist_sort.label()));
+    }
+
+    pub fn move_watchlist_selection(&mut self, delta: isize) {
+        let last = self.available_coins.len().saturating_sub(1);
+        self.watchlist_selected = self.watchlist_selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Switches to the selected Watchlist coin and shows its Order Book tab.
+    pub fn open_watchlist_selection(&mut self) {
+        let Some(row) = self.watchlist_rows().into_iter().nth(self.watchlist_selected) else {
+            return;
+        };
+        if let Some(index) = self.available_coins.iter().position(|coin| coin.symbol == row.symbol) {
+            if index != self.selected_coin_index {
+                self.select_coin_by_index(index);
+            }
+            self.selected_tab = 0;
+        }
+    }
+
+    /// Shows only errors on the Logs tab when `errors_only`, warnings too
+    /// otherwise.
+    pub fn set_log_filter(&mut self, errors_only: bool) {
+        self.log_filter = if errors_only { tracing::Level::ERROR } else { LOG_PANEL_LEVEL };
+    }
+
+    pub fn move_alert_selection(&mut self, delta: isize) {
+        self.alerts_selected = self.alerts_selected.saturating_add_signed(delta);
+        self.clamp_alert_selection();
+    }
+
+    /// Acts on a left click at (`column`, `row`) of the last frame: a tab
+    /// title switches to that tab and an Orders or Alerts row selects it.
+    pub fn click_at(&mut self, column: u16, row: u16) {
+        if let Some(tab) = tab_at(self.layout.tabs, &self.tabs, column, row) {
+            self.selected_tab = tab;
+            return;
+        }
+        let hit = |table: Option<(Rect, usize)>| table.and_then(|(area, offset)| table_row_at(area, offset, column, row));
+        if let Some(index) = hit(self.layout.orders_table) {
+            // Rows run newest first
+            if let Some(record) = self.order_history.len().checked_sub(index + 1).map(|i| &self.order_history[i]) {
+                self.selected_order_id = Some(record.order_id.clone());
+            }
+        } else if let Some(index) = hit(self.layout.alerts_table) {
+            if index < self.price_alerts.len() {
+                self.alerts_selected = index;
+            }
+        }
+    }
+
+    /// Opens or closes the help overlay. It opens at the order entry keys while
+    /// an order is being entered, at the alert commands on the Alerts tab, and
+    /// at the top otherwise.
+    pub fn toggle_help(&mut self) {
+        self.help_mode = !self.help_mode;
+        if self.help_mode {
+            let section = if self.order_input.active {
+                Some(HELP_ORDER_INPUT_SECTION)
+            } else if self.selected_tab == 5 {
+                Some(HELP_ALERTS_SECTION)
+            } else {
+                None
+            };
+            self.help_scroll = section.and_then(|section| self.help_section_offset(section)).unwrap_or(0);
+        }
+    }
+
+    /// The line `heading` starts on in the help overlay.
+    pub fn help_section_offset(&self, heading: &str) -> Option<u16> {
+        let lines = help_lines(self, self.help_width());
+        lines.iter().position(|line| line == heading).map(|index| index as u16)
+    }
+
+    fn help_width(&self) -> u16 {
+        self.layout.help.map_or(80, |area| area.width)
+    }
+
+    /// Furthest the help scrolls: until its last line is at the bottom of
+    /// the overlay.
+    pub fn help_max_scroll(&self) -> u16 {
+        let visible = self.layout.help.map_or(0, |area| area.height.saturating_sub(2));
+        (help_lines(self, self.help_width()).len() as u16).saturating_sub(visible)
+    }
+
+    /// Help lines moved by PageUp/PageDown: the overlay's height less a line
+    /// kept for context.
+    pub fn help_page(&self) -> u16 {
+        self.layout.help.map_or(1, |area| area.height.saturating_sub(3).max(1))
+    }
+
+    pub fn scroll_help(&mut self, delta: isize) {
+        let scroll = (self.help_scroll as isize + delta).clamp(0, self.help_max_scroll() as isize);
+        self.help_scroll = scroll as u16;
+    }
+
+    /// Scrolls whatever is under (`column`, `row`) by `delta` wheel notches:
+    /// the help overlay, or the book, orders or alerts in the left panel.
+    pub fn scroll_at(&mut self, column: u16, row: u16, delta: isize) {
+        if let Some(help) = self.layout.help {
+            if rect_contains(help, column, row) {
+                self.scroll_help(delta * HELP_SCROLL_LINES as isize);
+            }
+            return;
+        }
+        if !rect_contains(self.layout.left_panel, column, row) {
+            return;
+        }
+        match self.selected_tab {
+            0 => self.scroll_book(delta),
+            3 => self.move_orders_selection(delta),
+            5 => self.move_alert_selection(delta),
+            _ => {}
+        }
+    }
+
+    pub fn selected_alert_id(&self) -> Option<u64> {
+        self.price_alerts.get(self.alerts_selected).map(|alert| alert.id)
+    }
+
+    pub fn toggle_selected_alert(&mut self) {
+        if let Some(id) = self.selected_alert_id() {
+            self.toggle_price_alert(id);
+        }
+    }
+
+    pub fn remove_selected_alert(&mut self) {
+        if let Some(id) = self.selected_alert_id() {
+            self.remove_price_alert(id);
+        }
+    }
+
+    /// Records a price from the live feed and fires any alerts it reaches.
+    pub fn apply_live_price(&mut self, price: f64) {
+        let previous_price = self.market_data.current_price;
+        self.market_data.current_price = price;
+        self.check_all_alerts(price, previous_price, self.market_data.volume_24h);
+    }
+
+    /// Checks every alert against the price, volume and the selected coin's
+    /// book, logging, recording and announcing the ones that fire.
+    pub fn check_all_alerts(&mut self, current_price: f64, previous_price: f64, volume: f64) {
+        let levels = self
+            .price_alerts
+            .iter()
+            .filter_map(|alert| match alert.alert_type {
+                AlertType::LiquidityBelow { levels, .. } => Some(levels),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(1);
+        let book = BookMetrics::from_book(&self.order_book, levels);
+
+        // Check each alert and collect messages for triggered ones
+        let mut alert_messages = Vec::new();
+        let mut triggers = Vec::new();
+        let decimals = self.current_price_decimals();
+        
+        for alert in &mut self.price_alerts {
+            if alert.check_trigger_with_book(current_price, previous_price, volume, &book) {
+                alert_messages.push(format!(
+                    "🚨 ALERT TRIGGERED: {} - Price: ${}",
+                    alert.message,
+                    Price(current_price).format_with(decimals)
+                ));
+                
+                if self.alert_sound_enabled {
+                    alert_messages.push("🔊 Alert sound played".to_string());
+                }
+                triggers.push(AlertTrigger::of(alert, current_price));
+            }
+        }
+        self.record_alert_triggers(alert_messages, triggers);
+    }
+
+    /// Feeds `trades`, oldest first, to the whale alerts.
+    pub fn check_trade_alerts(&mut self, trades: &[Trade]) {
+        let mut alert_messages = Vec::new();
+        let mut triggers = Vec::new();
+
+        let decimals: Vec<usize> = self.price_alerts.iter().map(|alert| self.price_decimals_for(&alert.symbol)).collect();
+
+        for trade in trades {
+            for (alert, &decimals) in self.price_alerts.iter_mut().zip(&decimals) {
+                let Some(flow) = alert.check_trade(trade) else {
+                    continue;
+                };
+                let AlertType::WhaleTrade { window_secs, .. } = alert.alert_type else {
+                    continue;
+                };
+                alert_messages.push(format!(
+                    "🐋 WHALE ALERT: {} - {} ${:.2} over {} trade(s) in {}s, VWAP ${}",
+                    alert.message,
+                    if flow.side == OrderSide::Bid { "buys" } else { "sells" },
+                    flow.notional,
+                    flow.trades,
+                    window_secs,
+                    Price(flow.vwap()).format_with(decimals)
+                ));
+                if self.alert_sound_enabled {
+                    alert_messages.push("🔊 Alert sound played".to_string());
+                }
+                triggers.push(AlertTrigger::of(alert, flow.vwap()));
+            }
+        }
+        self.record_alert_triggers(alert_messages, triggers);
+    }
+
+    /// Logs `alert_messages`, announces and keeps `triggers`, and saves the
+    /// alerts if any fired.
+    fn record_alert_triggers(&mut self, alert_messages: Vec<String>, triggers: Vec<AlertTrigger>) {
+        // Triggering changes alert state, so save it
+        let any_triggered = !triggers.is_empty();
+        
+        // Add all messages to real-time data
+        for message in alert_messages {
+            self.log(message);
+        }
+        for trigger in triggers {
+            self.announce_alert(&trigger);
+            self.alert_history.push_back(trigger);
+        }
+        self.prune_alert_history();
+        if any_triggered {
+            self.persist_alerts();
+        }
+    }
+
+    /// Rings the terminal bell when alert sounds are on and, when built with
+    /// the `desktop-notifications` feature, raises a desktop notification.
+    fn announce_alert(&self, trigger: &AlertTrigger) {
+        if cfg!(test) {
+            return;
+        }
+        if self.alert_sound_enabled {
+            use std::io::Write;
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+        }
+        #[cfg(feature = "desktop-notifications")]
+        {
+            let body = format!("{} at ${:.2}", trigger.message, trigger.price);
+            let _ = notify_rust::Notification::new()
+                .summary(&format!("🚨 {} alert", trigger.symbol))
+                .body(&body)
+                .show();
+        }
+        #[cfg(not(feature = "desktop-notifications"))]
+        let _ = trigger;
+    }
+
+    /// Drops the oldest firings past `alert_history_limit`.
+    fn prune_alert_history(&mut self) {
+        let excess = self.alert_history.len().saturating_sub(self.alert_history_limit);
+        self.alert_history.drain(..excess);
+    }
+
+    pub fn set_alert_history_limit(&mut self, limit: usize) {
+        self.alert_history_limit = limit;
+        self.prune_alert_history();
+    }
+
+    pub fn clear_alert_history(&mut self) {
+        self.alert_history.clear();
+        self.persist_alerts();
+    }
+
+    /// Writes the alerts, their history and `next_alert_id` to `path` as
+    /// JSON, creating its directory if needed. The file is replaced atomically.
+    pub fn save_alerts_to(&self, path: &Path) -> Result<(), String> {
+        let saved = SavedAlerts {
+            next_alert_id: self.next_alert_id,
+            alerts: self.price_alerts.clone(),
+            history: self.alert_history.iter().cloned().collect(),
+        };
+        let json = serde_json::to_string_pretty(&saved).map_err(|e| format!("Could not encode alerts: {}", e))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+        }
+        let partial = path.with_extension("json.tmp");
+        std::fs::write(&partial, json)
+            .and_then(|_| std::fs::rename(&partial, path))
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+
+    /// Replaces the alerts with those saved at `path`, returning how many were
+    /// loaded. On error the current alerts are left untouched.
+    pub fn load_alerts_from(&mut self, path: &Path) -> Result<usize, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let saved: SavedAlerts =
+            serde_json::from_str(&json).map_err(|e| format!("Corrupted alert file {}: {}", path.display(), e))?;
+        // Never reuse an id, even if the file's counter is behind its alerts
+        let past_ids = saved.alerts.iter().map(|alert| alert.id + 1).max().unwrap_or(1);
+        self.next_alert_id = self.next_alert_id.max(saved.next_alert_id).max(past_ids);
+        self.price_alerts = saved.alerts;
+        self.alert_history = saved.history.into();
+        self.prune_alert_history();
+        self.clamp_alert_selection();
+        Ok(self.price_alerts.len())
+    }
+
+    /// Loads alerts from `alerts_path` at startup. A missing or unreadable
+    /// file leaves the list empty and says so in the log.
+    fn restore_alerts(&mut self) {
+        let Some(path) = self.alerts_path.clone() else {
+            return;
+        };
+        if !path.exists() {
+            self.log(format!("No saved alerts at {}", path.display()));
+            return;
+        }
+        match self.load_alerts_from(&path) {
+            Ok(count) => self.log(format!("📂 Restored {} alerts from {}", count, path.display())),
+            Err(reason) => self.log(format!("⚠️ {}; starting with no alerts", reason)),
+        }
+    }
+
+    /// Saves alerts to `alerts_path`, if set, logging any failure.
+    fn persist_alerts(&mut self) {
+        let Some(path) = self.alerts_path.clone() else {
+            return;
+        };
+        if let Err(reason) = self.save_alerts_to(&path) {
+            self.log(format!("⚠️ {}", reason));
+        }
+    }
+    
+    pub fn get_active_alerts_count(&self) -> usize {
+        self.price_alerts.iter().filter(|a| a.is_active).count()
+    }
+    
+    pub fn get_triggered_alerts_count(&self) -> usize {
+        self.price_alerts.iter().filter(|a| a.triggered_at.is_some()).count()
+    }
+
+    // WebSocket and Real Data Management
+    pub fn toggle_real_data(&mut self) {
+        self.use_real_data = !self.use_real_data;
+        let _status = if self.use_real_data { "enabled" } else { "disabled" };
+        
+        if self.use_real_data {
+            self.log("🔄 Switching to real Binance data...".to_string());
+            if self.market_stream.is_none() {
+                self.connect_selected_market_stream();
+            }
+        } else {
+            self.stop_market_stream();
+            self.binance_ws.update_status("Simulated data", false);
+            self.log("🔄 Switching to simulated data...".to_string());
+        }
+    }
+    
+    /// Reconnects the Binance market stream for the selected coin, e.g. after
+    /// it dropped. Only does so in real data mode; otherwise it says how to
+    /// turn that on.
+    pub fn simulate_binance_connection(&mut self) {
+        if !self.use_real_data {
+            self.log("Real data is off: enable real data with 'w' first".to_string());
+            return;
+        }
+        if self.market_stream.is_some() {
+            self.log("Binance market stream already running".to_string());
+            return;
+        }
+
+        self.connect_selected_market_stream();
+    }
+
+    fn connect_selected_market_stream(&mut self) {
+        let mut client = BinanceWebSocketClient::new(binance_symbol(&self.selected_symbol()));
+        client.base_url = self.market_stream_url.clone();
+        self.connect_market_stream(client);
+    }
+
+    /// Starts `client`'s depth and trade streams, on `runtime` when there is one.
+    pub fn connect_market_stream(&mut self, client: BinanceWebSocketClient) {
+        let symbol = client.symbol.clone();
+        let streams = vec![StreamType::Depth20, StreamType::Trade];
+        let stream = match &self.runtime {
+            Some(runtime) => MarketStreamHandle::spawn_on(runtime, client, streams),
+            None => MarketStreamHandle::spawn(client, streams),
+        };
+        self.market_stream = Some(stream);
+        self.market_stream_trades_seen = 0;
+        self.binance_ws.update_status("Connecting to Binance...", false);
+        self.log(format!("🔌 Connecting to the Binance market stream for {}", symbol));
+    }
+
+    fn stop_market_stream(&mut self) {
+        if let Some(mut stream) = self.market_stream.take() {
+            stream.stop(Duration::from_secs(2));
+        }
+    }
+
+    /// Runs `request` as a task on `runtime` (or the shared request runtime)
+    /// so the UI keeps drawing meanwhile; `done` gets its output from the
+    /// next `drain_requests`.
+    fn spawn_request<T: Send + 'static>(
+        &mut self,
+        request: impl std::future::Future<Output = T> + Send + 'static,
+        done: impl FnOnce(&mut App, T) + Send + 'static,
+    ) {
+        let runtime = match &self.runtime {
+            Some(runtime) => runtime.clone(),
+            None => match shared_runtime() {
+                Ok(runtime) => runtime.handle().clone(),
+                Err(reason) => return self.log(format!("⚠️ {}", reason)),
+            },
+        };
+        let results = self.requests.tx.clone();
+        self.requests.in_flight += 1;
+        runtime.spawn(async move {
+            let output = request.await;
+            let _ = results.send(Box::new(move |app: &mut App| done(app, output)));
+        });
+    }
+
+    /// Applies the results of the background requests finished so far.
+    pub fn drain_requests(&mut self) {
+        while let Ok(done) = self.requests.rx.try_recv() {
+            self.requests.in_flight -= 1;
+            done(self);
+        }
+    }
+
+    /// Waits up to `timeout` for every background request in flight,
+    /// applying each result as it arrives.
+    pub fn wait_for_requests(&mut self, timeout: Duration) {
+        let deadline = std::time::Instant::now() + timeout;
+        while self.requests.in_flight > 0 {
+            let left = deadline.saturating_duration_since(std::time::Instant::now());
+            let Ok(done) = self.requests.rx.recv_timeout(left) else {
+                return;
+            };
+            self.requests.in_flight -= 1;
+            done(self);
+        }
+    }
+
+    /// Applies market stream events and mirrors its book into `order_book`.
+    pub fn drain_market_stream_events(&mut self) {
+        let Some(stream) = self.market_stream.as_mut() else {
+            return;
+        };
+        let events = stream.drain();
+        let (bids, asks) = stream.order_book().get_market_depth(BOOK_DEPTH_LEVELS);
+        let mut trades = stream.order_book().recent_trades(TRADE_HISTORY_CAPACITY);
+        let recorded = stream.order_book().get_stats().trades_recorded;
+        let new_trades = (recorded.saturating_sub(self.market_stream_trades_seen) as usize).min(trades.len());
+        self.market_stream_trades_seen = recorded;
+
+        for event in events {
+            match event {
+                MarketStreamEvent::Connected => {
+                    self.binance_ws.update_status("Connected to Binance", true);
+                    self.log("✅ Connected to Binance WebSocket".to_string());
+                }
+                MarketStreamEvent::Metrics(metrics) => self.binance_ws.update_metrics(metrics),
+                MarketStreamEvent::Closed(reason) => {
+                    self.market_stream = None;
+                    self.binance_ws.update_status("Disconnected", false);
+                    match reason {
+                        Some(reason) => self.log(format!("⚠️ Binance market stream failed: {}", reason)),
+                        None => self.log("Binance market stream closed".to_string()),
+                    }
+                }
+            }
+        }
+
+        if bids.is_empty() && asks.is_empty() {
+            return;
+        }
+        let timestamp = chrono::Utc::now().timestamp_millis() as u64;
+        let book = OrderBook::new();
+        for (levels, side) in [(bids, OrderSide::Bid), (asks, OrderSide::Ask)] {
+            for (price, quantity) in levels {
+                book.set_level_quantity(side, price, quantity, timestamp);
+            }
+        }
+        // Oldest first, as the book records them
+        trades.reverse();
+        book.record_trades(&trades);
+        self.order_book = book;
+        self.check_trade_alerts(&trades[trades.len() - new_trades..]);
+    }
+    
+    /// Whether the feed in use has gone `STALE_FEED_INTERVALS` refresh
+    /// intervals without an update: the Binance stream in real data mode,
+    /// the simulated updates otherwise. A paused simulation is never stale.
+    pub fn feed_is_stale(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let last_update = if self.use_real_data {
+            self.binance_ws.last_message
+        } else if self.simulator.is_paused() {
+            return false;
+        } else {
+            self.real_time_service.last_update
+        };
+        let limit = self.refresh_interval * STALE_FEED_INTERVALS;
+        (now - last_update).to_std().is_ok_and(|age| age > limit)
+    }
+
+    pub fn get_connection_summary(&self) -> String {
+        if self.use_real_data {
+            format!(
+                "Binance WebSocket: {} | Messages: {} | Errors: {} | Last: {}",
+                if self.binance_ws.is_connected { "🟢 Connected" } else { "🔴 Disconnected" },
+                self.binance_ws.metrics.total_messages(),
+                self.binance_ws.metrics.parse_failures,
+                self.binance_ws.last_message.format("%H:%M:%S")
+            )
+        } else {
+            "Simulated data mode - Press 'w' to toggle real data".to_string()
+        }
+    }
+    
+    // Terminal chart management
+    /// The selected coin's market as the charts name it, e.g. "ETH/USDT".
+    pub fn chart_symbol(&self) -> String {
+        format!("{}/USDT", self.selected_symbol())
+    }
+
+    /// Replaces the chart grid with an empty one of the given size, labelled
+    /// with the selected coin and timeframe.
+    pub fn resize_terminal_chart(&mut self, width: u32, height: u32) {
+        self.terminal_chart = TerminalChartBackend::new(width, height);
+        self.terminal_chart.set_labels(&self.chart_symbol(), &self.selected_timeframe, self.current_price_decimals());
+    }
+    
+    pub fn update_terminal_chart_data(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.candlestick_data.is_empty() {
+            return Ok(());
+        }
+        
+        // Update the terminal chart with current data
+        self.terminal_chart.set_labels(&self.chart_symbol(), &self.selected_timeframe, self.current_price_decimals());
+        self.terminal_chart.draw_candlestick_chart(
+            &self.candlestick_data,
+            self.market_data.current_price,
+            self.chart_overlay,
+            &self.theme,
+        )
+    }
+}
+
+pub fn draw_ui(f: &mut Frame, app: &mut App) {
+    let size = f.size();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Top tabs
+            Constraint::Length(3),  // Coin switcher
+            Constraint::Min(15),    // Main content (ensure minimum height)
+            Constraint::Length(4),  // Bottom command bar (reduced height)
+        ])
+        .split(size);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(40),  // Left panel
+            Constraint::Percentage(60),  // Right panel
+        ])
+        .split(chunks[2]);
+
+    app.layout = ScreenLayout {
+        tabs: chunks[0],
+        left_panel: main_chunks[0],
+        right_panel: main_chunks[1],
+        ..ScreenLayout::default()
+    };
+    draw_tabs(f, app, chunks[0]);
+    draw_coin_switcher(f, app, chunks[1]);
+    
+    if app.help_mode {
+        // Show help overlay covering the entire main area
+        draw_help_overlay(f, app, chunks[2]);
+    } else {
+        // Show normal content
+        draw_left_panel(f, app, main_chunks[0]);
+        draw_right_panel(f, app, main_chunks[1]);
+    }
+    
+    draw_bottom_bar(f, app, chunks[3]);
+}
+
+fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
+    let titles: Vec<Line> = app.tabs
+        .iter()
+        .map(|t| Line::from(Span::styled(t, Style::default())))
+        .collect();
+
+    let tabs = Tabs::new(titles)
+        .select(app.selected_tab)
+        .block(Block::default().borders(Borders::ALL).title("Navigation"))
+        .style(Style::default().fg(app.theme.text))
+        .highlight_style(Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD));
+
+    f.render_widget(tabs, area);
+}
+
+/// The help overlay's text, one entry per line, for an overlay `width` wide.
+/// The command sections come from the command registry.
+pub fn help_lines(app: &App, width: u16) -> Vec<String> {
+    let mut content = String::new();
+    
+    content.push_str("🎯 ORDER BOOK APPLICATION - COMPREHENSIVE NAVIGATION GUIDE\n");
+    content.push_str(&"=".repeat(width.saturating_sub(2) as usize));
+    content.push_str("\n\n");
+    
+    // === QUICK NAVIGATION ===
+    content.push_str("⚡ QUICK NAVIGATION:\n");
+    content.push_str("• F2-F8: Direct tab access (F2=Order Book, F3=Trading, etc.)\n");
+    content.push_str("• 4-9: Quick tab selection\n");
+    content.push_str("• Tab/Shift+Tab: Next/Previous tab\n");
+    content.push_str("• Left/Right Arrow: Navigate tabs\n");
+    content.push_str("• ? or H: Toggle this help\n");
+    content.push_str("• Up/Down, PgUp/PgDn: Scroll this help\n");
+    content.push_str("• Mouse: Click a tab or table row; the wheel scrolls the panel under the pointer\n");
+    content.push_str("• Q: Quit application\n\n");
+    
+    // === TAB DESCRIPTIONS ===
+    content.push_str("📋 TAB DESCRIPTIONS:\n");
+    content.push_str("• Tab 1: Order Book - Live order book with bids/asks\n");
+    content.push_str("• Tab 2: Trading - Place and manage orders\n");
+    content.push_str("• Tab 3: Market Data - Real-time market information\n");
+    content.push_str("• Tab 4: Orders - Order history and status\n");
+    content.push_str("• Tab 5: Charts - Technical analysis with candlesticks\n");
+    content.push_str("  E: cycle MA/EMA/Bollinger overlay, U: cycle RSI/MACD pane\n");
+    content.push_str("• Tab 6: Alerts - Price alerts and notifications\n");
+    content.push_str("• Tab 7: Settings - Configuration and coin switcher\n");
+    content.push_str("• Tab 8: Watchlist - Top of book for every coin; Enter opens one, O changes the sort\n");
+    content.push_str("• Tab 9: Logs - Warnings and errors from the log file; + shows errors only, - warnings too\n\n");
+    
+    // === COIN SWITCHING ===
+    content.push_str("🪙 COIN SWITCHING:\n");
+    content.push_str("• N: Next coin (BTC → ETH → SOL → BTC)\n");
+    content.push_str("• V: Previous coin (SOL → ETH → BTC → SOL)\n");
+    content.push_str("• 1-3: Quick select specific coin\n");
+    content.push_str("• Available: BTC (Bitcoin), ETH (Ethereum), SOL (Solana)\n\n");
+    
+    // === ORDER INPUT MODE ===
+    content.push_str(HELP_ORDER_INPUT_SECTION);
+    content.push('\n');
+    content.push_str("• P or I: Toggle order input mode\n");
+    content.push_str("• Space: Quick toggle order input\n");
+    content.push_str("• B: Set order side to BUY\n");
+    content.push_str("• S: Set order side to SELL\n");
+    content.push_str("• G: Set order type to GTC (Good-Til-Cancelled)\n");
+    content.push_str("• F: Set order type to FOK (Fill-Or-Kill)\n");
+    content.push_str("• D: Set order type to GTD (Good-Til-Date)\n");
+    content.push_str("• O: Toggle venue (Polymarket / Local order book)\n");
+    content.push_str("• Up/Down Arrow: Cycle through order input fields\n");
+    content.push_str("• 0-9 and .: Type into the selected price, quantity or token field\n");
+    content.push_str("• Up/Down Arrow (Order Book tab): Select a price level\n");
+    content.push_str("• X (Order Book tab): Cancel an order at the selected level\n");
+    content.push_str("• J/K, PgUp/PgDn (Order Book tab): Scroll to deeper levels and back\n");
+    content.push_str("• Y (Order Book tab): Pause or resume the trades tape\n");
+    content.push_str("• E (Order Book tab): Switch between the ladder and the depth chart\n");
+    content.push_str("• J/K, PgUp/PgDn (Orders tab): Move the selection a row or a page\n");
+    content.push_str("• Up/Down + X (Orders tab): Cancel the selected order on its venue\n");
+    content.push_str("• Up/Down + E/X (Alerts tab): Enable/disable or remove the selected alert\n");
+    content.push_str("• Enter: Submit order when in input mode\n");
+    content.push_str("• Esc: Cancel/clear order input\n\n");
+    
+    // === MARKET DATA & TRADING ===
+    content.push_str("📊 MARKET DATA & TRADING:\n");
+    content.push_str("• M: Update market data\n");
+    content.push_str("• R: Refresh order book\n");
+    content.push_str("• A: Add sample orders\n");
+    content.push_str("• T: Toggle trading mode\n");
+    content.push_str("• W: Toggle real/simulated data\n");
+    content.push_str("• F9: Pause/resume the simulation\n");
+    content.push_str("• L: Toggle auto-refresh\n\n");
+    
+    // === CHART NAVIGATION ===
+    content.push_str("📈 CHART NAVIGATION:\n");
+    content.push_str("• < or ,: Previous timeframe (1m → 5m → 15m → 1h → 4h → 1d)\n");
+    content.push_str("• > or .: Next timeframe (1d → 4h → 1h → 15m → 5m → 1m)\n");
+    content.push_str("• Timeframes: 1m, 5m, 15m, 1h, 4h, 1d\n\n");
+    
+    // === COMMAND MANAGEMENT ===
+    content.push_str("⌨️ COMMAND MANAGEMENT:\n");
+    content.push_str("• Type commands in the bottom command bar\n");
+    content.push_str("• Enter: Execute command\n");
+    content.push_str("• Backspace/Delete: Delete before/under the cursor\n");
+    content.push_str("• Left/Right, Home/End: Move the cursor\n");
+    content.push_str("• Up/Down: Previous/next command from the history\n");
+    content.push_str("• Tab: Complete a command name or coin symbol\n");
+    content.push_str("• C: Clear command input\n");
+    content.push_str("• Esc: Clear command input\n\n");
+    
+    // === ALERT COMMANDS ===
+    content.push_str(HELP_ALERTS_SECTION);
+    content.push('\n');
+    for command in app.commands.commands().iter().filter(|c| c.name.starts_with("alert ")) {
+        content.push_str(&format!("• {} - {}\n", command.usage(), command.description));
+    }
+    content.push_str("• Add --repeat <cooldown> (e.g. 60s) or --rearm after the value to keep an alert firing\n");
+    content.push('\n');
+    
+    // === OTHER COMMANDS ===
+    content.push_str("🛠️ OTHER COMMANDS:\n");
+    for command in app.commands.commands().iter().filter(|c| !c.name.starts_with("alert ")) {
+        content.push_str(&format!("• {} - {}\n", command.usage(), command.description));
+    }
+    content.push('\n');
+    
+    // === PRO TIPS ===
+    content.push_str("💡 PRO TIPS:\n");
+    content.push_str("• Use F2-F8 for instant tab switching\n");
+    content.push_str("• Space bar toggles order input quickly\n");
+    content.push_str("• Arrow keys work in most contexts\n");
+    content.push_str("• Commas and periods work for timeframe navigation\n");
+    content.push_str("• Multiple ways to access help (? or H)\n");
+    content.push_str("• Esc key clears most inputs\n\n");
+    
+    content.push_str("Press ? or H again to hide this help and return to normal view");
+
+    content.lines().map(str::to_string).collect()
+}
+
+fn draw_help_overlay(f: &mut Frame, app: &mut App, area: Rect) {
+    app.layout.help = Some(area);
+    let max_scroll = app.help_max_scroll();
+    app.help_scroll = app.help_scroll.min(max_scroll);
+
+    // Unwrapped, so every line is one row and the scroll offsets stay exact
+    let paragraph = Paragraph::new(help_lines(app, area.width).join("\n"))
+        .block(Block::default().borders(Borders::ALL).title("Navigation & Controls Help"))
+        .style(Style::default().fg(app.theme.highlight))
+        .scroll((app.help_scroll, 0));
+    f.render_widget(paragraph, area);
+
+    if max_scroll > 0 {
+        let mut state = ScrollbarState::new(max_scroll as usize).position(app.help_scroll as usize);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight).style(Style::default().fg(app.theme.dim));
+        f.render_stateful_widget(scrollbar, area.inner(&Margin { vertical: 1, horizontal: 0 }), &mut state);
+    }
+}
+
+fn draw_coin_switcher(f: &mut Frame, app: &App, area: Rect) {
+    let mut lines = Vec::new();
+    
+    // Header with active coin info
+    let Some(selected_coin) = app.selected_coin() else {
+        return;
+    };
+    let change_percent = (selected_coin.change_24h / selected_coin.price) * 100.0;
+    let change_symbol = if change_percent >= 0.0 { "📈" } else { "📉" };
+    
+    let header_text = format!("🪙 {} ({}) ${} {} {:+.2}%", 
+        selected_coin.symbol, selected_coin.name, app.format_price(selected_coin.price), change_symbol, change_percent);
+    
+    let header_color = app.theme.change_color(change_percent);
+    let header_line = Line::from(Span::styled(header_text, Style::default().fg(header_color)));
+    lines.push(header_line);
+    
+    // Coin list with toggle indicators (horizontal layout)
+    let mut coin_line = String::new();
+    for (i, coin) in app.available_coins.iter().enumerate() {
+        let indicator = if i == app.selected_coin_index { "●" } else { "○" };
+        let change = (coin.change_24h / coin.price) * 100.0;
+        let change_arrow = if change >= 0.0 { "↗" } else { "↘" };
+        
+        coin_line.push_str(&format!("{} {} ${:.0} {} {:+.1}%  ", 
+            indicator, coin.symbol, coin.price, change_arrow, change));
+    }
+    
+    let coin_line_color = app.theme.info;
+    let coin_line_span = Line::from(Span::styled(coin_line, Style::default().fg(coin_line_color)));
+    lines.push(coin_line_span);
+    
+    // Controls line
+    let controls_text = "n/N: next | v/V: prev | 1-3: select";
+    let controls_line = Line::from(Span::styled(controls_text, Style::default().fg(app.theme.accent)));
+    lines.push(controls_line);
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Coin Switcher"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_left_panel(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Title
+            Constraint::Min(0),     // Content
+        ])
+        .split(area);
+
+    let title = Paragraph::new("Order Book")
+        .block(Block::default().borders(Borders::ALL))
+        .style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD));
+
+    f.render_widget(title, chunks[0]);
+
+    match app.selected_tab {
+        0 => draw_order_book(f, app, chunks[1]),
+        1 => draw_trading_panel(f, app, chunks[1]),
+        2 => draw_market_data_panel(f, app, chunks[1]),
+        3 => draw_orders_panel(f, app, chunks[1]),
+        4 => draw_charts_panel(f, app, chunks[1]),
+        5 => draw_alerts_panel(f, app, chunks[1]),
+        6 => draw_settings_panel(f, app, chunks[1]),
+        7 => draw_watchlist(f, app, chunks[1]),
+        8 => draw_logs(f, app, chunks[1]),
+        _ => {}
+    }
+}
+
+fn draw_right_panel(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Title
+            Constraint::Min(0),     // Content
+        ])
+        .split(area);
+
+    let title = Paragraph::new("Market Information")
+        .block(Block::default().borders(Borders::ALL))
+        .style(Style::default().fg(app.theme.bid).add_modifier(Modifier::BOLD));
+
+    f.render_widget(title, chunks[0]);
+
+            match app.selected_tab {
+            0 => {
+                let sections = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(9), Constraint::Min(0)])
+                    .split(chunks[1]);
+                draw_market_summary(f, app, sections[0]);
+                draw_trades_tape(f, app, sections[1]);
+            }
+            1 => draw_order_form(f, app, chunks[1]),
+            2 => draw_market_details(f, app, chunks[1]),
+            3 => draw_order_status(f, app, chunks[1]),
+            4 => draw_price_chart(f, app, chunks[1]),
+            5 => draw_websocket_status(f, app, chunks[1]),
+            6 => draw_configuration(f, app, chunks[1]),
+            7 | 8 => draw_market_summary(f, app, chunks[1]),
+            _ => {}
+        }
+}
+
+fn draw_order_book(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Header with current price
+            Constraint::Min(0),     // Order book content
+        ])
+        .split(area);
+
+    // Draw current price header
+    draw_current_price_header(f, app, chunks[0]);
+    
+    // Draw order book content
+    match app.book_view {
+        BookView::Ladder => draw_order_book_content(f, app, chunks[1]),
+        BookView::DepthChart => draw_depth_chart(f, app, chunks[1]),
+    }
+}
+
+fn draw_depth_chart(f: &mut Frame, app: &App, area: Rect) {
+    let (book, decimals) = app.displayed_book();
+    let (bids, asks) = book.cumulative_depth(DEPTH_CHART_LEVELS);
+    let block = Block::default().borders(Borders::ALL).title("Depth Chart (e for ladder)");
+
+    let Some((price_bounds, quantity_bounds)) = depth_chart_bounds(&bids, &asks) else {
+        f.render_widget(Paragraph::new("No depth to chart").block(block), area);
+        return;
+    };
+    let bid_points = depth_chart_points(&bids);
+    let ask_points = depth_chart_points(&asks);
+    let datasets = vec![
+        Dataset::default()
+            .name("Bids")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(app.theme.bid))
+            .data(&bid_points),
+        Dataset::default()
+            .name("Asks")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(app.theme.ask))
+            .data(&ask_points),
+    ];
+
+    let mid = (price_bounds[0] + price_bounds[1]) / 2.0;
+    let price_labels = [price_bounds[0], mid, price_bounds[1]]
+        .iter()
+        .map(|price| Span::raw(format!("{:.*}", decimals, price)))
+        .collect();
+    let quantity_labels = vec![Span::raw("0"), Span::raw(format!("{:.2}", quantity_bounds[1]))];
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .title("Price")
+                .style(Style::default().fg(app.theme.dim))
+                .bounds(price_bounds)
+                .labels(price_labels),
+        )
+        .y_axis(
+            Axis::default()
+                .title("Cumulative")
+                .style(Style::default().fg(app.theme.dim))
+                .bounds(quantity_bounds)
+                .labels(quantity_labels),
+        );
+
+    f.render_widget(chart, area);
+}
+
+fn draw_current_price_header(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let current_price = app.market_data.current_price;
+    let price_change = app.market_data.price_change;
+    let price_change_percent = app.market_data.price_change_percent;
+    
+    let change_symbol = if price_change >= 0.0 { "↗" } else { "↘" };
+    let change_color = theme.change_color(price_change);
+    
+    let price_text = format!("${}", app.format_price(current_price));
+    let change_text = format!("{} ${} ({:+.2}%)", change_symbol, app.format_price(price_change.abs()), price_change_percent);
+    
+    let header_content = vec![
+        Line::from(vec![
+            Span::styled("Current Price: ", Style::default().fg(theme.text)),
+            Span::styled(price_text, Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(vec![
+            Span::styled("Change: ", Style::default().fg(theme.text)),
+            Span::styled(change_text, Style::default().fg(change_color)),
+        ]),
+    ];
+
+    let header = Paragraph::new(header_content)
+        .block(Block::default().borders(Borders::ALL).title("Market Price"))
+        .alignment(ratatui::layout::Alignment::Center);
+
+    f.render_widget(header, area);
+}
+
+fn draw_order_book_content(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),  // Column headers (increased for 2 lines)
+            Constraint::Min(0),     // Order data
+        ])
+        .split(area);
+
+    // Draw column headers
+    draw_order_book_headers(f, &app.theme, chunks[0]);
+    
+    // Draw order data
+    draw_order_book_data(f, app, chunks[1]);
+}
+
+fn draw_order_book_headers(f: &mut Frame, theme: &Theme, area: Rect) {
+    let heading = Style::default().fg(theme.accent).add_modifier(Modifier::BOLD);
+    let header_content = vec![
+        Line::from(vec![
+            Span::styled("Price (USDT)", heading),
+            Span::styled("  ", Style::default()),
+            Span::styled("Amount (BTC)", heading),
+            Span::styled("  ", Style::default()),
+            Span::styled("Total (USDT)", heading),
+            Span::styled("  ", Style::default()),
+            Span::styled("Depth", heading),
+        ]),
+        Line::from(vec![
+            Span::styled("SELL ORDERS", Style::default().fg(theme.ask).add_modifier(Modifier::BOLD)),
+            Span::styled("  ", Style::default()),
+            Span::styled("", Style::default()),
+            Span::styled("  ", Style::default()),
+            Span::styled("", Style::default()),
+            Span::styled("  ", Style::default()),
+            Span::styled("", Style::default()),
+        ]),
+    ];
+
+    let header = Paragraph::new(header_content)
+        .block(Block::default().borders(Borders::NONE))
+        .alignment(ratatui::layout::Alignment::Left);
+
+    f.render_widget(header, area);
+}
+
+fn draw_order_book_data(f: &mut Frame, app: &App, area: Rect) {
+    let (book, decimals) = app.displayed_book();
+    let (bids, asks) = app.visible_book_depth(book);
+    
+    // Calculate total height for asks and bids
+    let total_height = area.height as usize;
+    let asks_height = (total_height / 2).min(asks.len());
+    let bids_height = (total_height / 2).min(bids.len());
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(asks_height as u16),  // Asks (sell orders)
+            Constraint::Length(4),                   // Current price separator
+            Constraint::Length(1),                   // Buy orders label
+            Constraint::Length(bids_height as u16),  // Bids (buy orders)
+        ])
+        .split(area);
+
+    // Split the cursor between the two tables; it only applies to the coin's book
+    let cursor = app.book_cursor.filter(|_| app.polymarket_book.is_none());
+    let ask_selection = cursor.filter(|index| *index < asks.len());
+    let bid_selection = cursor.and_then(|index| index.checked_sub(asks.len()));
+
+    // Draw asks (sell orders) - red, descending order
+    draw_asks_section(f, &app.theme, &asks, ask_selection, decimals, app.depth_bar_mode, chunks[0]);
+    
+    // Draw current price separator with more detail
+    draw_current_price_separator(f, app, chunks[1]);
+    
+    // Draw buy orders label
+    draw_buy_orders_label(f, &app.theme, chunks[2]);
+    
+    // Draw bids (buy orders) - green, descending order
+    draw_bids_section(f, &app.theme, &bids, bid_selection, decimals, app.depth_bar_mode, chunks[3]);
+}
+
+fn draw_buy_orders_label(f: &mut Frame, theme: &Theme, area: Rect) {
+    let label_content = vec![
+        Line::from(vec![
+            Span::styled("BUY ORDERS", Style::default().fg(theme.bid).add_modifier(Modifier::BOLD)),
+            Span::styled("  ", Style::default()),
+            Span::styled("", Style::default()),
+            Span::styled("  ", Style::default()),
+            Span::styled("", Style::default()),
+        ]),
+    ];
+
+    let label = Paragraph::new(label_content)
+        .block(Block::default().borders(Borders::NONE))
+        .alignment(ratatui::layout::Alignment::Left);
+
+    f.render_widget(label, area);
+}
+
+fn draw_current_price_separator(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let current_price = app.market_data.current_price;
+    let price_change = app.market_data.price_change;
+    let price_change_percent = app.market_data.price_change_percent;
+    
+    let change_symbol = if price_change >= 0.0 { "↗" } else { "↘" };
+    let change_color = theme.change_color(price_change);
+    
+    let price_text = app.format_price(current_price);
+    let change_text = format!("{} ${} ({:+.2}%)", change_symbol, app.format_price(price_change.abs()), price_change_percent);
+
+    let (book, decimals) = app.displayed_book();
+    let stats = book.get_stats();
+    let imbalance_color = theme.imbalance_color(stats.imbalance);
+    let format_level = |value: Option<f64>| value.map_or_else(|| "--".to_string(), |v| format!("{:.*}", decimals, v));
+    let imbalance_text = stats.imbalance.map_or_else(|| "--".to_string(), |i| format!("{:+.0}%", i * 100.0));
+
+    // A Polymarket book has no USDT price or 24h change; show its midpoint instead
+    let (headline, subline) = match &app.polymarket_book {
+        Some(view) => (
+            Line::from(vec![
+                Span::styled("Mid: ", Style::default().fg(theme.text)),
+                Span::styled(format_level(stats.mid_price), Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+            ]),
+            Line::from(vec![
+                Span::styled(format!("Polymarket {}", view.token_id), Style::default().fg(theme.info)),
+            ]),
+        ),
+        None => (
+            Line::from(vec![
+                Span::styled("Current: ", Style::default().fg(theme.text)),
+                Span::styled(price_text, Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+                Span::styled(" USDT", Style::default().fg(theme.dim)),
+            ]),
+            Line::from(vec![
+                Span::styled(change_text, Style::default().fg(change_color)),
+            ]),
+        ),
+    };
+    
+    let separator_content = vec![
+        Line::from(vec![
+            Span::styled("─".repeat(area.width as usize), Style::default().fg(imbalance_color)),
+        ]),
+        headline,
+        subline,
+        Line::from(vec![
+            Span::styled("Bid ", Style::default().fg(theme.dim)),
+            Span::styled(format_level(stats.best_bid), Style::default().fg(theme.bid)),
+            Span::styled("  Ask ", Style::default().fg(theme.dim)),
+            Span::styled(format_level(stats.best_ask), Style::default().fg(theme.ask)),
+            Span::styled("  Spread ", Style::default().fg(theme.dim)),
+            Span::styled(format_level(stats.spread), Style::default().fg(theme.text)),
+            Span::styled("  Imbalance ", Style::default().fg(theme.dim)),
+            Span::styled(imbalance_text, Style::default().fg(imbalance_color)),
+        ]),
+    ];
+
+    let separator = Paragraph::new(separator_content)
+        .block(Block::default().borders(Borders::NONE))
+        .alignment(ratatui::layout::Alignment::Center);
+    
+    f.render_widget(separator, area);
+}
+
+fn draw_asks_section(
+    f: &mut Frame,
+    theme: &Theme,
+    asks: &[(f64, f64)],
+    selected: Option<usize>,
+    decimals: usize,
+    bar_mode: DepthBarMode,
+    area: Rect,
+) {
+    let mut rows = Vec::new();
+    let quantities: Vec<f64> = asks.iter().map(|(_, qty)| *qty).collect();
+    let bar_lengths = depth_bar_lengths(&quantities, bar_mode);
+
+    // Add asks in descending order (highest price first)
+    for ((price, quantity), bar_length) in asks.iter().zip(bar_lengths).rev() {
+        let total = price * quantity;
+        let depth_bar = "█".repeat(bar_length);
+        
+        let row = Row::new(vec![
+            format!("{:.*}", decimals, price),
+            format!("{:.5}", quantity),
+            format!("{:.2}", total),
+            format!("{}", depth_bar),
+        ]);
+        rows.push(row);
+    }
+
+    let widths = [
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+    ];
+
+    let table = Table::new(rows, widths)
+        .block(Block::default().borders(Borders::NONE))
+        .style(Style::default().fg(theme.ask))
+        .highlight_style(Style::default().fg(theme.inverse).bg(theme.ask));
+
+    let mut state = TableState::default().with_selected(selected);
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_bids_section(
+    f: &mut Frame,
+    theme: &Theme,
+    bids: &[(f64, f64)],
+    selected: Option<usize>,
+    decimals: usize,
+    bar_mode: DepthBarMode,
+    area: Rect,
+) {
+    let mut rows = Vec::new();
+    let quantities: Vec<f64> = bids.iter().map(|(_, qty)| *qty).collect();
+    let bar_lengths = depth_bar_lengths(&quantities, bar_mode);
+
+    // Add bids in descending order (highest price first)
+    for ((price, quantity), bar_length) in bids.iter().zip(bar_lengths) {
+        let total = price * quantity;
+        let depth_bar = "█".repeat(bar_length);
+        
+        let row = Row::new(vec![
+            format!("{:.*}", decimals, price),
+            format!("{:.5}", quantity),
+            format!("{:.2}", total),
+            format!("{}", depth_bar),
+        ]);
+        rows.push(row);
+    }
+
+    let widths = [
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+    ];
+
+    let table = Table::new(rows, widths)
+        .block(Block::default().borders(Borders::NONE))
+        .style(Style::default().fg(theme.bid))
+        .highlight_style(Style::default().fg(theme.inverse).bg(theme.bid));
+
+    let mut state = TableState::default().with_selected(selected);
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_trading_panel(f: &mut Frame, app: &App, area: Rect) {
+    let content = format!(
+        "Trading Panel - {}\n\n\
+        Best Bid: ${}\n\
+        Best Ask: ${}\n\
+        Spread: ${}\n\
+        Last Price: ${}\n\
+        Volume 24h: ${:.0}",
+        app.current_market,
+        app.format_price(app.order_book.get_best_bid().unwrap_or(0.0)),
+        app.format_price(app.order_book.get_best_ask().unwrap_or(0.0)),
+        app.format_price(app.order_book.get_spread().unwrap_or(0.0)),
+        app.format_price(app.market_data.current_price),
+        app.market_data.volume_24h
+    );
+
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("Trading Overview"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_market_data_panel(f: &mut Frame, app: &App, area: Rect) {
+    let mut content = String::new();
+    
+    content.push_str(&format!("Market Data - {}\n\n", app.current_market));
+    content.push_str(&format!("Current Price: ${}\n", app.format_price(app.market_data.current_price)));
+    
+    let price_change_text = format_number_with_color(app.market_data.price_change, false);
+    let price_change_percent_text = format_number_with_color(app.market_data.price_change_percent, true);
+    
+    content.push_str(&format!("Change: ${} ({})\n", price_change_text, price_change_percent_text));
+    content.push_str(&format!("High 24h: ${}\n", app.format_price(app.market_data.high_24h)));
+    content.push_str(&format!("Low 24h: ${}\n", app.format_price(app.market_data.low_24h)));
+    content.push_str(&format!("Volume 24h: ${:.0}\n", app.market_data.volume_24h));
+    content.push_str(&format!("Market Cap: ${:.0}B\n", app.market_data.market_cap / 1e9));
+    content.push_str(&format!("Last Update: {}", app.last_update.format("%H:%M:%S")));
+
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("Market Data"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_orders_panel(f: &mut Frame, app: &mut App, area: Rect) {
+    let mut rows = Vec::new();
+    let header = Row::new(vec!["Time", "Side", "Price", "Qty", "Status", "ID"]);
+
+    for order in app.order_history.iter().rev() {
+        rows.push(Row::new(vec![
+            order.timestamp.format("%H:%M:%S").to_string(),
+            format!("{:?}", order.side),
+            format!("${}", app.format_price(order.price)),
+            format!("{:.2}", order.quantity),
+            order.status.clone(),
+            order.order_id.clone(),
+        ]));
+    }
+
+    let widths = [
+        Constraint::Percentage(16),
+        Constraint::Percentage(16),
+        Constraint::Percentage(16),
+        Constraint::Percentage(16),
+        Constraint::Percentage(16),
+        Constraint::Percentage(20),
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title("Order History (↑/↓ j/k PgUp/PgDn select, x cancel)"))
+        .style(Style::default().fg(app.theme.text))
+        .highlight_style(app.theme.selection());
+
+    let mut state = TableState::default()
+        .with_offset(app.orders_scroll)
+        .with_selected(app.selected_order_row());
+    f.render_stateful_widget(table, area, &mut state);
+    app.layout.orders_table = Some((area, state.offset()));
+}
+
+fn draw_charts_panel(f: &mut Frame, app: &mut App, area: Rect) {
+    let pane_height = if app.indicator_pane == IndicatorPane::Off { 0 } else { 9 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),            // Header
+            Constraint::Min(0),               // Chart content
+            Constraint::Length(pane_height),  // Indicator pane
+        ])
+        .split(area);
+
+    // Header
+    let header = Paragraph::new(format!("📊 Advanced Charts - {}", app.chart_symbol()))
+        .block(Block::default().borders(Borders::ALL))
+        .style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD))
+        .alignment(ratatui::layout::Alignment::Center);
+
+    f.render_widget(header, chunks[0]);
+
+    // Chart content
+    draw_price_chart(f, app, chunks[1]);
+    if app.indicator_pane != IndicatorPane::Off {
+        // The candles the price chart has room for: its borders and price labels take 14 columns
+        let visible = (chunks[1].width.saturating_sub(14) as usize).min(app.candlestick_data.len());
+        draw_indicator_pane(f, app, app.candlestick_data.len() - visible, chunks[2]);
+    }
+}
+
+/// RSI or MACD for the candles from `start` on, under the price chart.
+fn draw_indicator_pane(f: &mut Frame, app: &App, start: usize, area: Rect) {
+    let candles = &app.candlestick_data;
+    let [_, second, third, fourth] = app.theme.chart;
+
+    let (title, lines, bounds) = match app.indicator_pane {
+        IndicatorPane::Off => return,
+        IndicatorPane::Rsi => {
+            let rsi = indicator_points(&indicators::rsi(candles, RSI_PERIOD), start);
+            let title = match rsi.last() {
+                Some((_, value)) => format!("RSI {} {:.1} (u next, 70/30 marked)", RSI_PERIOD, value),
+                None => format!("RSI {}: needs more than {} candles", RSI_PERIOD, RSI_PERIOD),
+            };
+            (title, vec![("RSI", fourth, rsi)], [0.0, 100.0])
+        }
+        IndicatorPane::Macd => {
+            let macd = indicators::macd(candles, 12, 26, 9);
+            let macd_line = indicator_points(&macd.macd, start);
+            let signal = indicator_points(&macd.signal, start);
+            let (low, high) = macd_line
+                .iter()
+                .chain(&signal)
+                .fold((0.0f64, 0.0f64), |(low, high), (_, value)| (low.min(*value), high.max(*value)));
+            let padding = ((high - low) * 0.1).max(1e-9);
+            let title = match (macd_line.last(), signal.last()) {
+                (Some((_, macd)), Some((_, signal))) => {
+                    format!("MACD 12/26/9 {:.2} signal {:.2} (u next)", macd, signal)
+                }
+                _ => "MACD 12/26/9: needs at least 34 candles".to_string(),
+            };
+            (title, vec![("MACD", third, macd_line), ("Signal", second, signal)], [low - padding, high + padding])
+        }
+    };
+
+    let width = candles.len().saturating_sub(start).max(2) as f64 - 1.0;
+    // Reference levels: overbought/oversold for RSI, zero for MACD
+    let levels: Vec<f64> = if app.indicator_pane == IndicatorPane::Rsi { vec![30.0, 70.0] } else { vec![0.0] };
+    let level_points: Vec<Vec<(f64, f64)>> = levels.iter().map(|level| vec![(0.0, *level), (width, *level)]).collect();
+
+    let mut datasets: Vec<Dataset> = level_points
+        .iter()
+        .map(|points| {
+            Dataset::default()
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(app.theme.grid))
+                .data(points)
+        })
+        .collect();
+    for (name, color, points) in &lines {
+        datasets.push(
+            Dataset::default()
+                .name(*name)
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(points),
+        );
+    }
+
+    let chart = Chart::new(datasets)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .x_axis(Axis::default().bounds([0.0, width]))
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(app.theme.dim))
+                .bounds(bounds)
+                .labels(vec![Span::raw(format!("{:.1}", bounds[0])), Span::raw(format!("{:.1}", bounds[1]))]),
+        );
+    f.render_widget(chart, area);
+}
+
+fn draw_settings_panel(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let mut lines = Vec::new();
+    
+    // Coin Switcher Section
+    let header_text = "🪙 Coin Switcher";
+    let header_line = Line::from(Span::styled(header_text, Style::default().fg(theme.highlight)));
+    lines.push(header_line);
+    
+    let current_text = match app.selected_coin() {
+        Some(coin) => format!("Current: {} ({})", coin.symbol, coin.name),
+        None => "Current: none".to_string(),
+    };
+    let current_line = Line::from(Span::styled(current_text, Style::default().fg(theme.accent)));
+    lines.push(current_line);
+    
+    let available_text = "Available Coins:";
+    let available_line = Line::from(Span::styled(available_text, Style::default().fg(theme.text)));
+    lines.push(available_line);
+    
+    for (i, coin) in app.available_coins.iter().enumerate() {
+        let indicator = if i == app.selected_coin_index { "●" } else { "○" };
+        let status = if i == app.selected_coin_index { "SELECTED" } else { "       " };
+        let change_percent = (coin.change_24h / coin.price) * 100.0;
+        let change_color = theme.change_color(change_percent);
+        
+        let coin_text = format!("{} {} {} - ${} ({:+.2}%)", 
+            indicator, coin.symbol, status, Price(coin.price).format_with(app.price_decimals_for(&coin.symbol)), change_percent);
+        let coin_line = Line::from(Span::styled(coin_text, Style::default().fg(change_color)));
+        lines.push(coin_line);
+    }
+    
+    // Controls section
+    let controls_header = "\nCoin Controls:";
+    let controls_header_line = Line::from(Span::styled(controls_header, Style::default().fg(theme.highlight)));
+    lines.push(controls_header_line);
+    
+    let controls_text = "• n/N: Next coin\n• v/V: Previous coin\n• 1-3: Quick coin select";
+    let controls_line = Line::from(Span::styled(controls_text, Style::default().fg(theme.accent)));
+    lines.push(controls_line);
+    
+    // Settings section
+    let settings_header = "\nSettings:";
+    let settings_header_line = Line::from(Span::styled(settings_header, Style::default().fg(theme.highlight)));
+    lines.push(settings_header_line);
+    
+    let settings_text = format!("Current Market: {}\nPolymarket Client: {}\nOrder Input Mode: {}\nHelp Mode: {}\nDepth Bars: {}",
+        app.current_market,
+        if app.polymarket_client.is_some() { "Connected" } else { "Disconnected" },
+        if app.order_input.active { "Active" } else { "Inactive" },
+        if app.help_mode { "On" } else { "Off" },
+        app.depth_bar_mode.as_str()
+    );
+    let settings_line = Line::from(Span::styled(settings_text, Style::default().fg(theme.text)));
+    lines.push(settings_line);
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Settings & Coin Switcher"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_market_summary(f: &mut Frame, app: &App, area: Rect) {
+    let mut content = String::new();
+    
+    content.push_str("Market Summary\n\n");
+    content.push_str(&format!("Symbol: {}\n", app.current_market));
+    content.push_str(&format!("Current Price: ${}\n", app.format_price(app.market_data.current_price)));
+    
+    // Price change with color indication
+    let price_change_text = format_number_with_color(app.market_data.price_change, false);
+    let price_change_percent_text = format_number_with_color(app.market_data.price_change_percent, true);
+    
+    content.push_str(&format!("Change: ${} ({})\n", price_change_text, price_change_percent_text));
+    content.push_str(&format!("Volume: ${:.0}\n", app.market_data.volume_24h));
+    content.push_str(&format!("Market Cap: ${:.0}B", app.market_data.market_cap / 1e9));
+
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("Summary"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
+/// One trades tape line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TapeRow {
+    pub time: String,
+    pub price: String,
+    pub quantity: String,
+    pub side: OrderSide,
+    /// The print's notional reached the large-print threshold.
+    pub large: bool,
+}
+
+/// Formats `trades` for the tape in the order given, prices to `decimals`
+/// places, flagging prints worth at least `large_notional`.
+pub fn tape_rows(trades: &[Trade], decimals: usize, large_notional: f64) -> Vec<TapeRow> {
+    trades
+        .iter()
+        .map(|trade| TapeRow {
+            time: chrono::DateTime::from_timestamp_millis(trade.timestamp as i64)
+                .map(|time| time.format("%H:%M:%S").to_string())
+                .unwrap_or_default(),
+            price: Price(trade.price).format_with(decimals),
+            quantity: format!("{:.5}", trade.quantity),
+            side: trade.aggressor_side,
+            large: trade.notional() >= large_notional,
+        })
+        .collect()
+}
+
+fn draw_trades_tape(f: &mut Frame, app: &App, area: Rect) {
+    let rows = tape_rows(&app.tape_trades(), app.current_price_decimals(), app.large_trade_notional).into_iter().map(|row| {
+        let label = match row.side {
+            OrderSide::Bid => "Buy",
+            OrderSide::Ask => "Sell",
+        };
+        let mut style = Style::default().fg(app.theme.side_color(row.side));
+        if row.large {
+            style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        }
+        Row::new(vec![row.time, row.price, row.quantity, label.to_string()]).style(style)
+    });
+
+    let title = if app.tape_paused.is_some() { "Trades (paused, y resume)" } else { "Trades (y pause)" };
+    let widths = [
+        Constraint::Percentage(28),
+        Constraint::Percentage(30),
+        Constraint::Percentage(27),
+        Constraint::Percentage(15),
+    ];
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["Time", "Price", "Qty", "Side"]).style(Style::default().fg(app.theme.highlight)))
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    f.render_widget(table, area);
+}
+
+fn draw_order_form(f: &mut Frame, app: &App, area: Rect) {
+    let input = &app.order_input;
+    let submit_hint = match app.binance_filter_check() {
+        Some(Err(violation)) if input.venue == OrderVenue::Binance => {
+            format!("Submission blocked: {}", violation)
+        }
+        _ => "Enter - Submit order".to_string(),
+    };
+    let field_line = |field: OrderField, value: String| {
+        // Only Polymarket orders name a token; the others trade the selected coin
+        let label = match field {
+            OrderField::TokenId if input.venue != OrderVenue::Polymarket => "Symbol",
+            _ => field.as_str(),
+        };
+        if input.active && input.current_field == field {
+            Line::from(Span::styled(
+                format!("▶ {}: {}", label, value),
+                Style::default().fg(app.theme.selection_fg).bg(app.theme.highlight).add_modifier(Modifier::BOLD),
+            ))
+        } else {
+            Line::from(format!("  {}: {}", label, value))
+        }
+    };
+    let token = match input.venue {
+        OrderVenue::Polymarket if input.token_id.is_empty() => "(type a token id)".to_string(),
+        OrderVenue::Polymarket => input.token_id.clone(),
+        OrderVenue::Binance => app.selected_binance_symbol(),
+        OrderVenue::Local => app.selected_symbol(),
+    };
+    let expiry = if input.order_type == PolymarketOrderType::GTD {
+        format!(" (expires {})", format_expiration(input.expiration))
+    } else {
+        String::new()
+    };
+
+    let lines = vec![
+        Line::from("Order Form"),
+        Line::from(""),
+        Line::from(format!("  Venue: {}", input.venue.as_str())),
+        Line::from(format!("  Mode: {}", app.trading_mode.as_str())),
+        field_line(
+            OrderField::Price,
+            if input.market { "MARKET (no price)".to_string() } else { format!("${}", input.price) },
+        ),
+        field_line(OrderField::Quantity, input.quantity.clone()),
+        field_line(OrderField::TokenId, token),
+        field_line(OrderField::Side, format!("{:?}", input.side)),
+        field_line(OrderField::Type, format!("{:?}{}", input.order_type, expiry)),
+        Line::from(format!("  Status: {}", if input.active { "ACTIVE" } else { "Inactive" })),
+        Line::from(""),
+        Line::from("Controls:"),
+        Line::from("Up/Down - Select field"),
+        Line::from("0-9 . - Type, Backspace - Delete"),
+        Line::from("b/s - Change side"),
+        Line::from("g/f/d - Change type"),
+        Line::from("o - Toggle venue, m - Market/limit (local book)"),
+        Line::from("t - Trading mode (Aggressive: IOC, Conservative: post-only)"),
+        Line::from(submit_hint),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Place Order"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_market_details(f: &mut Frame, app: &App, area: Rect) {
+    let content = format!(
+        "Market Details\n\n\
+        High 24h: ${}\n\
+        Low 24h: ${}\n\
+        Open: ${}\n\
+        Previous Close: ${}\n\
+        Bid Size: {:.2}\n\
+        Ask Size: {:.2}\n\
+        Spread: ${:.4}\n\
+        Spread %: {:.2}%",
+        app.format_price(app.market_data.high_24h),
+        app.format_price(app.market_data.low_24h),
+        app.format_price(app.market_data.current_price - app.market_data.price_change),
+        app.format_price(app.market_data.current_price - app.market_data.price_change),
+        app.order_book.get_best_bid().map_or(0.0, |_| 10.0),
+        app.order_book.get_best_ask().map_or(0.0, |_| 12.0),
+        app.order_book.get_spread().unwrap_or(0.0),
+        app.order_book.get_spread().map_or(0.0, |s| (s / app.market_data.current_price) * 100.0)
+    );
+
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("Details"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_order_status(f: &mut Frame, app: &App, area: Rect) {
+    let pnl = app.compute_pnl();
+    let content = format!(
+        "Order Status\n\n\
+        Total Orders: {}\n\
+        Pending: {}\n\
+        Filled: {}\n\
+        Cancelled: {}\n\
+        Rejected: {}\n\
+        Last Order: {}\n\
+        Success Rate: {:.1}%\n\n\
+        {} Position: {:+.5}\n\
+        Avg Entry: {}\n\
+        Realized PnL: ${:+.2}\n\n\
+        Last Rejection: {}",
+        app.order_history.len(),
+        app.order_history.iter().filter(|o| o.status == "Pending").count(),
+        app.order_history.iter().filter(|o| o.status == "Filled").count(),
+        app.order_history.iter().filter(|o| o.status == "Cancelled").count(),
+        app.order_history.iter().filter(|o| o.status == "Rejected").count(),
+        app.order_history.back().map_or("None".to_string(), |o| o.timestamp.format("%H:%M:%S").to_string()),
+        if app.order_history.is_empty() { 0.0 } else { 
+            (app.order_history.iter().filter(|o| o.status == "Filled").count() as f64 / app.order_history.len() as f64) * 100.0 
+        },
+        pnl.symbol,
+        pnl.position,
+        pnl.average_entry.map_or("-".to_string(), |price| format!("${}", app.format_price(price))),
+        pnl.realized_pnl,
+        app.order_history.iter().rev().find_map(|o| o.reason.as_deref()).unwrap_or("-")
+    );
+
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("Status"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_price_chart(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Header
+            Constraint::Min(0),     // Chart
+        ])
+        .split(area);
+
+    // Header with timeframe and current price
+    let header = format!(
+        "📊 {} Price Chart - {} | Current: ${} | Change: {} ({:.2}%)",
+        app.chart_symbol(),
+        app.selected_timeframe.as_str(),
+        app.format_price(app.market_data.current_price),
+        app.format_price(app.market_data.price_change),
+        app.market_data.price_change_percent
+    );
+    
+    let header_para = Paragraph::new(header)
+        .block(Block::default().borders(Borders::ALL))
+        .style(Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD))
+        .alignment(ratatui::layout::Alignment::Center);
+
+    f.render_widget(header_para, chunks[0]);
+
+    // Chart content using terminal chart backend
+    if app.candlestick_data.len() < 2 {
+        let content = "Insufficient data for chart";
+        let paragraph = Paragraph::new(content)
+            .block(Block::default().borders(Borders::ALL).title("Chart"))
+            .wrap(Wrap { trim: true });
+        
+        f.render_widget(paragraph, chunks[1]);
+        return;
+    }
+
+    // Resize terminal chart to fit the area
+    let chart_width = chunks[1].width.saturating_sub(2) as u32;
+    let chart_height = chunks[1].height.saturating_sub(2) as u32;
+    
+    app.resize_terminal_chart(chart_width, chart_height);
+
+    // Update terminal chart with current data
+    let _ = app.terminal_chart.draw_candlestick_chart(
+        &app.candlestick_data, 
+        app.market_data.current_price,
+        app.chart_overlay,
+        &app.theme,
+    );
+
+    // Render the terminal chart; wrapping would break the column alignment
+    let paragraph = Paragraph::new(app.terminal_chart.lines())
+        .block(Block::default().borders(Borders::ALL).title("Real-time Chart"));
+
+    f.render_widget(paragraph, chunks[1]);
+}
+
+fn draw_configuration(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(Setting::ALL.len() as u16 + 3), Constraint::Min(0)])
+        .split(area);
+
+    let rows: Vec<Row> = Setting::ALL
+        .iter()
+        .map(|setting| Row::new(vec![setting.label().to_string(), app.setting_value(*setting)]))
+        .collect();
+    let table = Table::new(rows, [Constraint::Percentage(60), Constraint::Percentage(40)])
+        .header(Row::new(vec!["Setting", "Value"]).style(Style::default().fg(app.theme.highlight)))
+        .block(Block::default().borders(Borders::ALL).title("Settings"))
+        .highlight_style(app.theme.selection());
+    let mut state = TableState::default().with_selected(Some(app.settings_selected));
+    f.render_stateful_widget(table, chunks[0], &mut state);
+
+    let content = format!(
+        "↑/↓ select • Enter/+ change • - step back\n\
+        Saved to: {}\n\n\
+        API Endpoint: {}\n\
+        Chain ID: 137 (Polygon)\n\
+        Signature Type: {:?}",
+        app.config_path.as_ref().map_or("(not saved)".to_string(), |path| path.display().to_string()),
+        if app.polymarket_client.is_some() { "https://clob.polymarket.com" } else { "Not configured" },
+        PolymarketSignatureType::EMAIL_MAGIC
+    );
+
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("Config"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, chunks[1]);
+}
+
+fn draw_bottom_bar(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),  // User commands
+            Constraint::Percentage(25),  // Navigation help
+            Constraint::Percentage(20),  // Current coin info
+            Constraint::Percentage(25),  // Real-time updates
+        ])
+        .split(area);
+
+    // User commands area
+    let command_text = if app.user_command.is_empty() {
+        "Type commands here... (h for help)".to_string()
+    } else {
+        format!("Command: {}", app.user_command.text())
+    };
+    if !app.user_command.is_empty() && !app.order_input.active && chunks[0].width > 2 {
+        let before: usize = format!("Command: {}", app.user_command.before_cursor())
+            .chars()
+            .map(|c| c.width().unwrap_or(0))
+            .sum();
+        let x = chunks[0].x + 1 + (before as u16).min(chunks[0].width - 3);
+        f.set_cursor(x, chunks[0].y + 1);
+    }
+
+    let command_para = Paragraph::new(command_text)
+        .block(Block::default().borders(Borders::ALL).title("User Commands (Type here and press Enter)"))
+        .style(Style::default().fg(app.theme.highlight));
+
+    f.render_widget(command_para, chunks[0]);
+
+    // Navigation help area
+    let help_text = format!(
+        "Tab: {} | F2-F8: Quick tabs | ?/H: Help | P/Space: Order input | M: Market data | R: Refresh | N/V: Coin switch | </>: Timeframe | L: Auto-refresh | W: Real data",
+        app.tabs.get(app.selected_tab).map_or("", String::as_str)
+    );
+
+    let help_para = Paragraph::new(help_text)
+        .block(Block::default().borders(Borders::ALL).title("Navigation Help"))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(help_para, chunks[1]);
+
+    // Current coin info area
+    let Some(selected_coin) = app.selected_coin() else {
+        return;
+    };
+    let change_percent = (selected_coin.change_24h / selected_coin.price) * 100.0;
+    let change_color = app.theme.change_color(change_percent);
+    
+    let coin_text = format!(
+        "🪙 {} ({})\n${} {:+.2}%\nVolume: ${:.0}M",
+        selected_coin.symbol,
+        selected_coin.name,
+        app.format_price(selected_coin.price),
+        change_percent,
+        selected_coin.volume_24h / 1e6
+    );
+
+    let coin_para = Paragraph::new(coin_text)
+        .block(Block::default().borders(Borders::ALL).title("Active Coin"))
+        .style(Style::default().fg(change_color))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(coin_para, chunks[2]);
+
+    // Real-time updates area with status
+    let stale = app.feed_is_stale(chrono::Utc::now());
+    let status_color = if app.real_time_service.is_connected && !stale { app.theme.bid } else { app.theme.ask };
+    let status_text = format!(
+        "Status: {}\nTimeframe: {}\nAuto-refresh: {} ({:.1}s)\nUpdates: {}\nAlerts: {}",
+        app.real_time_service.connection_status,
+        app.selected_timeframe.as_str(),
+        if app.auto_refresh { "ON" } else { "OFF" },
+        app.refresh_interval.as_secs_f64(),
+        app.real_time_data.len(),
+        app.get_active_alerts_count()
+    );
+
+    let updates_text = if app.real_time_data.is_empty() {
+        "No updates yet...".to_string()
+    } else {
+        app.real_time_data.iter().take(3).cloned().collect::<Vec<_>>().join("\n")
+    };
+
+    let full_text = format!("{}\n\n{}", status_text, updates_text);
+    let mut lines = Vec::new();
+    if app.simulator.is_paused() && !app.use_real_data {
+        lines.push(Line::from(Span::styled(
+            " PAUSED ",
+            Style::default().fg(app.theme.inverse).bg(app.theme.grid).add_modifier(Modifier::BOLD),
+        )));
     }
-}
+    if stale {
+        lines.push(Line::from(Span::styled(
+            " STALE ",
+            Style::default().fg(app.theme.inverse).bg(app.theme.alert).add_modifier(Modifier::BOLD),
+        )));
+    }
+    lines.extend(full_text.lines().map(|line| Line::from(line.to_string())));
 
-pub fn draw_ui(f: &mut Frame, app: &mut App) {
-    let size = f.size();
+    let updates_para = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Real-time Status & Updates"))
+        .style(Style::default().fg(status_color))
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(updates_para, chunks[3]);
+}
 
+fn draw_alerts_panel(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),  // Top tabs
-            Constraint::Length(3),  // Coin switcher
-            Constraint::Min(15),    // Main content (ensure minimum height)
-            Constraint::Length(4),  // Bottom command bar (reduced height)
+            Constraint::Length(3),  // Header
+            Constraint::Min(0),     // Alerts list
         ])
-        .split(size);
+        .split(area);
 
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(40),  // Left panel
-            Constraint::Percentage(60),  // Right panel
-        ])
-        .split(chunks[2]);
+    // Header
+    let header = Paragraph::new(format!(
+        "🔔 Price Alerts - Active: {} | Triggered: {}",
+        app.get_active_alerts_count(),
+        app.get_triggered_alerts_count()
+    ))
+    .block(Block::default().borders(Borders::ALL))
+    .style(Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD))
+    .alignment(ratatui::layout::Alignment::Center);
 
-    draw_tabs(f, app, chunks[0]);
-    draw_coin_switcher(f, app, chunks[1]);
-    
-    if app.help_mode {
-        // Show help overlay covering the entire main area
-        draw_help_overlay(f, app, chunks[2]);
+    f.render_widget(header, chunks[0]);
+
+    // The triggered history goes below the alerts once there is some
+    let (list_area, history_area) = if app.alert_history.is_empty() {
+        (chunks[1], None)
     } else {
-        // Show normal content
-        draw_left_panel(f, app, main_chunks[0]);
-        draw_right_panel(f, app, main_chunks[1]);
+        let rows = app.alert_history.len().min(ALERT_HISTORY_ROWS) as u16;
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(rows + 3)])
+            .split(chunks[1]);
+        (split[0], Some(split[1]))
+    };
+    if let Some(area) = history_area {
+        draw_alert_history(f, app, area);
+    }
+
+    // Alerts list
+    if app.price_alerts.is_empty() {
+        let content = "No price alerts configured.\n\nUse the command line to create alerts:\n• alert above 27000 - Alert when price goes above $27,000\n• alert below 26000 - Alert when price goes below $26,000\n• alert change 5 - Alert on 5% price change\n• alert volume 1000000 - Alert on volume spike above 1M";
+        
+        let paragraph = Paragraph::new(content)
+            .block(Block::default().borders(Borders::ALL).title("Create Alerts"))
+            .wrap(Wrap { trim: true });
+        
+        f.render_widget(paragraph, list_area);
+    } else {
+        let mut rows = Vec::new();
+        let header = Row::new(vec!["ID", "Symbol", "Type", "Target", "Status", "Created", "Triggered"]);
+
+        for alert in &app.price_alerts {
+            let decimals = app.price_decimals_for(&alert.symbol);
+            let alert_type_str = match &alert.alert_type {
+                AlertType::PriceAbove(price) => format!("Above ${}", Price(*price).format_with(decimals)),
+                AlertType::PriceBelow(price) => format!("Below ${}", Price(*price).format_with(decimals)),
+                AlertType::PercentageChange(percent) => format!("{}% Change", percent),
+                AlertType::VolumeSpike(volume) => format!("Volume > {:.0}", volume),
+                AlertType::PriceCross(price) => format!("Cross ${}", Price(*price).format_with(decimals)),
+                AlertType::SpreadAbove(spread) => format!("Spread > {}", spread),
+                AlertType::MidPriceCross(price) => format!("Mid ${}", Price(*price).format_with(decimals)),
+                AlertType::LiquidityBelow { side, levels, qty } => {
+                    format!("{} {}lv < {}", if *side == OrderSide::Bid { "Bid" } else { "Ask" }, levels, qty)
+                }
+                AlertType::WhaleTrade { notional, window_secs } => format!("Whale ${:.0}/{}s", notional, window_secs),
+            };
+            
+            let status = match alert.behavior {
+                _ if !alert.is_active => "🔴 Inactive",
+                AlertBehavior::OneShot => "🟢 Active",
+                AlertBehavior::Repeating { .. } => "🔁 Repeating",
+                AlertBehavior::ReArmOnCross if alert.armed => "🟢 Armed",
+                AlertBehavior::ReArmOnCross => "🟡 Re-arming",
+            };
+            let created = alert.created_at.format("%H:%M").to_string();
+            let triggered = alert.triggered_at
+                .map(|t| t.format("%H:%M").to_string())
+                .unwrap_or_else(|| "Never".to_string());
+            
+            rows.push(Row::new(vec![
+                alert.id.to_string(),
+                alert.symbol.clone(),
+                alert_type_str,
+                alert.message.clone(),
+                status.to_string(),
+                created,
+                triggered,
+            ]));
+        }
+
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Length(15),
+            Constraint::Min(20),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ];
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title("Price Alerts (↑/↓ select, e enable/disable, x remove)"))
+            .style(Style::default().fg(app.theme.text))
+            .highlight_style(app.theme.selection());
+
+        let mut state = TableState::default().with_selected(Some(app.alerts_selected));
+        f.render_stateful_widget(table, list_area, &mut state);
+        app.layout.alerts_table = Some((list_area, state.offset()));
     }
-    
-    draw_bottom_bar(f, app, chunks[3]);
 }
 
-fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
-    let titles: Vec<Line> = app.tabs
+/// The latest alert firings, newest first.
+fn draw_alert_history(f: &mut Frame, app: &App, area: Rect) {
+    let header = Row::new(vec!["Time", "Symbol", "Alert", "Price", "Count"]);
+    let rows: Vec<Row> = app
+        .alert_history
         .iter()
-        .map(|t| Line::from(Span::styled(t, Style::default())))
+        .rev()
+        .take(ALERT_HISTORY_ROWS)
+        .map(|trigger| {
+            let decimals = app.price_decimals_for(&trigger.symbol);
+            Row::new(vec![
+                trigger.triggered_at.format("%m-%d %H:%M:%S").to_string(),
+                trigger.symbol.clone(),
+                trigger.message.clone(),
+                format!("${}", Price(trigger.price).format_with(decimals)),
+                format!("#{}", trigger.trigger_count),
+            ])
+        })
         .collect();
+    let widths = [
+        Constraint::Length(15),
+        Constraint::Length(8),
+        Constraint::Min(20),
+        Constraint::Length(14),
+        Constraint::Length(6),
+    ];
+    let title = format!("Triggered ({} kept, alert history clear to forget)", app.alert_history.len());
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .style(Style::default().fg(app.theme.dim));
+    f.render_widget(table, area);
+}
 
-    let tabs = Tabs::new(titles)
-        .select(app.selected_tab)
-        .block(Block::default().borders(Borders::ALL).title("Navigation"))
-        .style(Style::default().fg(Color::White))
-        .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+fn draw_watchlist(f: &mut Frame, app: &App, area: Rect) {
+    let header = Row::new(vec!["Symbol", "Last", "24h", "Bid", "Ask", "Spread", "Trend"])
+        .style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = app
+        .watchlist_rows()
+        .into_iter()
+        .map(|row| {
+            let decimals = app.price_decimals_for(&row.symbol);
+            let price = |value: Option<f64>| value.map_or_else(|| "-".to_string(), |value| Price(value).format_with(decimals));
+            let sparkline = app.watchlist_mids.get(&row.symbol).map(MidHistory::sparkline).unwrap_or_default();
+            let change_color = app.theme.change_color(row.change_percent);
+            Row::new(vec![
+                Text::from(row.symbol.clone()),
+                Text::from(price(Some(row.last_price))),
+                Text::styled(format!("{:+.2}%", row.change_percent), Style::default().fg(change_color)),
+                Text::styled(price(row.best_bid), Style::default().fg(app.theme.bid)),
+                Text::styled(price(row.best_ask), Style::default().fg(app.theme.ask)),
+                Text::from(price(row.spread())),
+                Text::styled(sparkline, Style::default().fg(app.theme.chart[0])),
+            ])
+        })
+        .collect();
+    let widths = [
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Length(9),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(10),
+        Constraint::Min(10),
+    ];
+    let title = format!(
+        "Watchlist by {} (↑/↓ select, Enter open, o sort)",
+        app.watchlist_sort.label()
+    );
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .style(Style::default().fg(app.theme.text))
+        .highlight_style(app.theme.selection());
+    let mut state = TableState::default().with_selected(Some(app.watchlist_selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
 
-    f.render_widget(tabs, area);
+fn draw_logs(f: &mut Frame, app: &App, area: Rect) {
+    let lines = app.log_buffer.lines(app.log_filter);
+    // Newest at the bottom, as many as fit inside the border
+    let visible = area.height.saturating_sub(2) as usize;
+    let items: Vec<Line> = lines[lines.len().saturating_sub(visible)..]
+        .iter()
+        .map(|line| {
+            let color = if line.level == tracing::Level::ERROR { app.theme.ask } else { app.theme.alert };
+            Line::from(Span::styled(line.to_string(), Style::default().fg(color)))
+        })
+        .collect();
+    let shown = if app.log_filter == tracing::Level::ERROR { "errors" } else { "warnings and errors" };
+    let title = format!("Logs: {} ({} lines, +/- filter)", shown, lines.len());
+    let paragraph = Paragraph::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .style(Style::default().fg(app.theme.text));
+    f.render_widget(paragraph, area);
 }
 
-fn draw_help_overlay(f: &mut Frame, _app: &App, area: Rect) {
+fn draw_websocket_status(f: &mut Frame, app: &App, area: Rect) {
     let mut content = String::new();
     
-    content.push_str("🎯 ORDER BOOK APPLICATION - COMPREHENSIVE NAVIGATION GUIDE\n");
-    content.push_str(&format!("{}", "=".repeat(area.width as usize - 2)));
-    content.push_str("\n\n");
-    
-    // === QUICK NAVIGATION ===
-    content.push_str("⚡ QUICK NAVIGATION:\n");
-    content.push_str("• F2-F8: Direct tab access (F2=Order Book, F3=Trading, etc.)\n");
-    content.push_str("• 1-7: Quick tab selection\n");
-    content.push_str("• Tab/Shift+Tab: Next/Previous tab\n");
-    content.push_str("• Left/Right Arrow: Navigate tabs\n");
-    content.push_str("• ? or H: Toggle this help\n");
-    content.push_str("• Q: Quit application\n\n");
-    
-    // === TAB DESCRIPTIONS ===
-    content.push_str("📋 TAB DESCRIPTIONS:\n");
-    content.push_str("• Tab 1: Order Book - Live order book with bids/asks\n");
-    content.push_str("• Tab 2: Trading - Place and manage orders\n");
-    content.push_str("• Tab 3: Market Data - Real-time market information\n");
-    content.push_str("• Tab 4: Orders - Order history and status\n");
-    content.push_str("• Tab 5: Charts - Technical analysis with candlesticks\n");
-    content.push_str("• Tab 6: Alerts - Price alerts and notifications\n");
-    content.push_str("• Tab 7: Settings - Configuration and coin switcher\n\n");
-    
-    // === COIN SWITCHING ===
-    content.push_str("🪙 COIN SWITCHING:\n");
-    content.push_str("• N: Next coin (BTC → ETH → SOL → BTC)\n");
-    content.push_str("• V: Previous coin (SOL → ETH → BTC → SOL)\n");
-    content.push_str("• 1-3: Quick select specific coin\n");
-    content.push_str("• Available: BTC (Bitcoin), ETH (Ethereum), SOL (Solana)\n\n");
-    
-    // === ORDER INPUT MODE ===
-    content.push_str("📝 ORDER INPUT MODE:\n");
-    content.push_str("• P or I: Toggle order input mode\n");
-    content.push_str("• Space: Quick toggle order input\n");
-    content.push_str("• B: Set order side to BUY\n");
-    content.push_str("• S: Set order side to SELL\n");
-    content.push_str("• G: Set order type to GTC (Good-Til-Cancelled)\n");
-    content.push_str("• F: Set order type to FOK (Fill-Or-Kill)\n");
-    content.push_str("• D: Set order type to GTD (Good-Til-Date)\n");
-    content.push_str("• Up/Down Arrow: Cycle through order input fields\n");
-    content.push_str("• Enter: Submit order when in input mode\n");
-    content.push_str("• Esc: Cancel/clear order input\n\n");
-    
-    // === MARKET DATA & TRADING ===
-    content.push_str("📊 MARKET DATA & TRADING:\n");
-    content.push_str("• M: Update market data\n");
-    content.push_str("• R: Refresh order book\n");
-    content.push_str("• A: Add sample orders\n");
-    content.push_str("• T: Toggle trading mode\n");
-    content.push_str("• W: Toggle real/simulated data\n");
-    content.push_str("• L: Toggle auto-refresh\n\n");
-    
-    // === CHART NAVIGATION ===
-    content.push_str("📈 CHART NAVIGATION:\n");
-    content.push_str("• < or ,: Previous timeframe (1m → 5m → 15m → 1h → 4h → 1d)\n");
-    content.push_str("• > or .: Next timeframe (1d → 4h → 1h → 15m → 5m → 1m)\n");
-    content.push_str("• Timeframes: 1m, 5m, 15m, 1h, 4h, 1d\n\n");
-    
-    // === COMMAND MANAGEMENT ===
-    content.push_str("⌨️ COMMAND MANAGEMENT:\n");
-    content.push_str("• Type commands in the bottom command bar\n");
-    content.push_str("• Enter: Execute command\n");
-    content.push_str("• Backspace: Delete last character\n");
-    content.push_str("• C: Clear command input\n");
-    content.push_str("• Esc: Clear command input\n");
-    content.push_str("• Delete: Clear command input\n\n");
-    
-    // === ALERT COMMANDS ===
-    content.push_str("🔔 ALERT COMMANDS:\n");
-    content.push_str("• alert above <price> [message] - Alert when price goes above target\n");
-    content.push_str("• alert below <price> [message] - Alert when price goes below target\n");
-    content.push_str("• alert change <percent> [message] - Alert on percentage change\n");
-    content.push_str("• alert volume <amount> [message] - Alert on volume spike\n");
-    content.push_str("• alert cross <price> [message] - Alert when price crosses level\n");
-    content.push_str("• alert list - Show active alerts\n");
-    content.push_str("• alert remove <id> - Remove specific alert\n\n");
+    content.push_str("🌐 Binance WebSocket Status\n\n");
     
-    // === OTHER COMMANDS ===
-    content.push_str("🛠️ OTHER COMMANDS:\n");
-    content.push_str("• help - Toggle help mode\n");
-    content.push_str("• clear - Clear command input\n");
-    content.push_str("• add_orders - Add sample orders\n");
-    content.push_str("• place_order - Activate order input mode\n");
-    content.push_str("• market_data - Update market data\n");
-    content.push_str("• submit_order - Submit current order\n\n");
+    // Connection status
+    let status_icon = if app.binance_ws.is_connected { "🟢" } else { "🔴" };
+    content.push_str(&format!("Status: {} {}\n", status_icon, app.binance_ws.connection_status));
     
-    // === PRO TIPS ===
-    content.push_str("💡 PRO TIPS:\n");
-    content.push_str("• Use F2-F8 for instant tab switching\n");
-    content.push_str("• Space bar toggles order input quickly\n");
-    content.push_str("• Arrow keys work in most contexts\n");
-    content.push_str("• Commas and periods work for timeframe navigation\n");
-    content.push_str("• Multiple ways to access help (? or H)\n");
-    content.push_str("• Esc key clears most inputs\n\n");
+    // Data mode
+    let mode_icon = if app.use_real_data { "📡" } else { "🎭" };
+    content.push_str(&format!("Data Mode: {} {}\n", mode_icon, 
+        if app.use_real_data { "Real Binance Data" } else { "Simulated Data" }));
     
-    content.push_str("Press ? or H again to hide this help and return to normal view");
-
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Navigation & Controls Help"))
-        .style(Style::default().fg(Color::Yellow))
-        .wrap(Wrap { trim: true });
-
-    f.render_widget(paragraph, area);
-}
+    // Statistics
+    let metrics = &app.binance_ws.metrics;
+    content.push_str(&format!("Messages Received: {} ({:.1}/s)\n",
+        metrics.total_messages(), metrics.messages_per_sec()));
+    content.push_str(&format!("Parse Failures: {}\n", metrics.parse_failures));
+    content.push_str(&format!("Malformed Levels: {}\n", metrics.malformed_levels));
+    content.push_str(&format!("Feed Latency p50/p99: {} / {}\n",
+        format_latency(metrics.end_to_end_latency_ms.p50(), "ms"),
+        format_latency(metrics.end_to_end_latency_ms.p99(), "ms")));
+    content.push_str(&format!("Depth Apply p50/p99: {} / {}\n",
+        format_latency(metrics.depth_apply_latency_us.p50(), "µs"),
+        format_latency(metrics.depth_apply_latency_us.p99(), "µs")));
+
+    let mut streams: Vec<_> = metrics.streams.iter().collect();
+    streams.sort_by(|a, b| a.0.cmp(b.0));
+    for (stream, rate) in streams {
+        content.push_str(&format!("  {}: {} msgs, {:.1}/s\n", stream, rate.total_messages, rate.messages_per_sec));
+    }
+    content.push_str(&format!("Last Message: {}\n", 
+        app.binance_ws.last_message.format("%H:%M:%S")));
 
-fn draw_coin_switcher(f: &mut Frame, app: &App, area: Rect) {
-    let mut lines = Vec::new();
-    
-    // Header with active coin info
-    let selected_coin = &app.available_coins[app.selected_coin_index];
-    let change_percent = (selected_coin.change_24h / selected_coin.price) * 100.0;
-    let change_symbol = if change_percent >= 0.0 { "📈" } else { "📉" };
-    
-    let header_text = format!("🪙 {} ({}) ${:.2} {} {:+.2}%", 
-        selected_coin.symbol, selected_coin.name, selected_coin.price, change_symbol, change_percent);
+    let usage = app.binance_rest.rate_limiter().usage(std::time::Instant::now());
+    content.push_str(&format!("REST Weight: {}/{} per min\n", usage.used_weight, usage.weight_limit));
+    if let Some(client) = &app.binance_orders {
+        let usage = client.rate_limiter().usage(std::time::Instant::now());
+        content.push_str(&format!("Order Rate: {}/{} per sec\n", usage.orders_this_second, usage.order_limit));
+    }
     
-    let header_color = get_number_color(change_percent);
-    let header_line = Line::from(Span::styled(header_text, Style::default().fg(header_color)));
-    lines.push(header_line);
+    // Connection info
+    content.push_str("\n📊 Connection Info:\n");
+    content.push_str(&format!("• Market: {}\n", app.current_market));
+    content.push_str(&format!("• Timeframe: {}\n", app.selected_timeframe.as_str()));
+    content.push_str(&format!("• Auto-refresh: {}\n", 
+        if app.auto_refresh { "ON" } else { "OFF" }));
     
-    // Coin list with toggle indicators (horizontal layout)
-    let mut coin_line = String::new();
-    for (i, coin) in app.available_coins.iter().enumerate() {
-        let indicator = if i == app.selected_coin_index { "●" } else { "○" };
-        let change = (coin.change_24h / coin.price) * 100.0;
-        let change_arrow = if change >= 0.0 { "↗" } else { "↘" };
-        
-        coin_line.push_str(&format!("{} {} ${:.0} {} {:+.1}%  ", 
-            indicator, coin.symbol, coin.price, change_arrow, change));
-    }
+    // Controls
+    content.push_str("\n🎮 Controls:\n");
+    content.push_str("• r/R: Toggle real/simulated data\n");
+    content.push_str("• b/B: Reconnect the Binance stream\n");
+    content.push_str("• < >: Change chart timeframe\n");
+    content.push_str("• l/L: Toggle auto-refresh\n");
     
-    let coin_line_color = Color::Magenta;
-    let coin_line_span = Line::from(Span::styled(coin_line, Style::default().fg(coin_line_color)));
-    lines.push(coin_line_span);
+    // Alert info
+    content.push_str(&format!("\n🔔 Alerts: {} active, {} triggered\n", 
+        app.get_active_alerts_count(), app.get_triggered_alerts_count()));
     
-    // Controls line
-    let controls_text = "n/N: next | v/V: prev | 1-3: select";
-    let controls_line = Line::from(Span::styled(controls_text, Style::default().fg(Color::Cyan)));
-    lines.push(controls_line);
+    // Real-time updates
+    if !app.real_time_data.is_empty() {
+        content.push_str("\n📡 Recent Updates:\n");
+        for update in app.real_time_data.iter().take(5) {
+            content.push_str(&format!("• {}\n", update));
+        }
+    }
 
-    let paragraph = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("Coin Switcher"))
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("WebSocket & Real-time Status"))
         .wrap(Wrap { trim: true });
 
     f.render_widget(paragraph, area);
 }
 
-fn draw_left_panel(f: &mut Frame, app: &mut App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Title
-            Constraint::Min(0),     // Content
-        ])
-        .split(area);
-
-    let title = Paragraph::new("Order Book")
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
-
-    f.render_widget(title, chunks[0]);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submit_local_crossing_order() {
+        let mut app = App::new();
+        let (_, asks) = app.order_book.get_market_depth(1);
+        let (best_ask, best_ask_quantity) = asks[0];
+
+        app.order_input.venue = OrderVenue::Local;
+        app.order_input.side = PolymarketOrderSide::BUY;
+        app.order_input.price = best_ask.to_string();
+        app.order_input.quantity = "0.5".to_string();
+        app.submit_order();
+
+        assert_eq!(app.order_history.len(), 1);
+        let record = &app.order_history[0];
+        assert_eq!(record.side, OrderSide::Bid);
+        assert_eq!(record.status, "Filled");
+        assert!(app.real_time_data.iter().any(|m| m.starts_with("💱 Fill")));
+
+        // The resting ask was partially consumed and nothing rested on the bid side
+        let (bids, asks) = app.order_book.get_market_depth(1);
+        assert_eq!(asks[0].0, best_ask);
+        assert!((asks[0].1 - (best_ask_quantity - 0.5)).abs() < 1e-6);
+        assert!(bids[0].0 < best_ask);
+    }
 
-    match app.selected_tab {
-        0 => draw_order_book(f, app, chunks[1]),
-        1 => draw_trading_panel(f, app, chunks[1]),
-        2 => draw_market_data_panel(f, app, chunks[1]),
-        3 => draw_orders_panel(f, app, chunks[1]),
-        4 => draw_charts_panel(f, app, chunks[1]),
-        5 => draw_alerts_panel(f, app, chunks[1]),
-        6 => draw_settings_panel(f, app, chunks[1]),
-        _ => {}
+    #[test]
+    fn test_local_order_on_a_crossed_book_records_only_its_fills() {
+        let mut app = App::new();
+        app.order_book = OrderBook::new();
+        // The simulator adds orders without matching, so the book can be crossed
+        app.order_book.add_order(OrderSide::Bid, 101.0, 5.0, 1);
+        app.order_book.add_order(OrderSide::Ask, 100.0, 5.0, 2);
+
+        app.order_input.venue = OrderVenue::Local;
+        app.order_input.side = PolymarketOrderSide::SELL;
+        app.order_input.price = "102".to_string();
+        app.order_input.quantity = "1".to_string();
+        app.submit_order();
+
+        assert!(!app.real_time_data.iter().any(|m| m.starts_with("💱 Fill")));
+        assert_eq!(app.order_history.len(), 1);
+        assert_eq!(app.order_history[0].status, "Resting");
+        assert!(app.order_book.recent_trades(10).is_empty());
+        assert_eq!(app.order_book.get_best_bid(), Some(101.0));
     }
-}
 
-fn draw_right_panel(f: &mut Frame, app: &mut App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Title
-            Constraint::Min(0),     // Content
-        ])
-        .split(area);
+    #[test]
+    fn test_local_order_request_routing() {
+        let mut input = App::new().order_input;
+        input.venue = OrderVenue::Local;
+        input.side = PolymarketOrderSide::SELL;
+        input.price = "101.5".to_string();
+        input.quantity = "2".to_string();
+        assert_eq!(
+            input.local_order_request(),
+            Ok(LocalOrderRequest::Limit { side: OrderSide::Ask, price: 101.5, quantity: 2.0 })
+        );
 
-    let title = Paragraph::new("Market Information")
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD));
+        // A market order ignores the price, even an unparsable one
+        input.market = true;
+        input.side = PolymarketOrderSide::BUY;
+        input.price = "abc".to_string();
+        assert_eq!(
+            input.local_order_request(),
+            Ok(LocalOrderRequest::Market { side: OrderSide::Bid, quantity: 2.0 })
+        );
+        input.quantity.clear();
+        assert_eq!(input.local_order_request(), Err("Enter a quantity first".to_string()));
 
-    f.render_widget(title, chunks[0]);
+        input.market = false;
+        input.quantity = "2".to_string();
+        assert_eq!(input.local_order_request(), Err("Invalid price: 'abc'".to_string()));
+    }
 
-            match app.selected_tab {
-            0 => draw_market_summary(f, app, chunks[1]),
-            1 => draw_order_form(f, app, chunks[1]),
-            2 => draw_market_details(f, app, chunks[1]),
-            3 => draw_order_status(f, app, chunks[1]),
-            4 => draw_price_chart(f, app, chunks[1]),
-            5 => draw_websocket_status(f, app, chunks[1]),
-            6 => draw_configuration(f, app, chunks[1]),
-            _ => {}
+    #[test]
+    fn test_trading_modes_cycle_and_shape_submissions() {
+        let mut app = App::new();
+        assert_eq!(app.trading_mode, TradingMode::Normal);
+        for expected in [TradingMode::Aggressive, TradingMode::Conservative, TradingMode::Normal] {
+            app.toggle_trading_mode();
+            assert_eq!(app.trading_mode, expected);
+            assert_eq!(app.real_time_data.back().unwrap(), &format!("Trading mode: {}", expected.as_str()));
         }
-}
-
-fn draw_order_book(f: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Header with current price
-            Constraint::Min(0),     // Order book content
-        ])
-        .split(area);
 
-    // Draw current price header
-    draw_current_price_header(f, app, chunks[0]);
-    
-    // Draw order book content
-    draw_order_book_content(f, app, chunks[1]);
-}
+        let gtd = TimeInForce::GoodTilDate(1_700_000_000);
+        assert_eq!(TradingMode::Normal.time_in_force(gtd), gtd);
+        assert_eq!(TradingMode::Aggressive.time_in_force(gtd), TimeInForce::ImmediateOrCancel);
+        assert_eq!(TradingMode::Conservative.time_in_force(TimeInForce::FillOrKill), TimeInForce::PostOnly);
+        let [normal, aggressive, conservative] =
+            [TradingMode::Normal, TradingMode::Aggressive, TradingMode::Conservative].map(|mode| mode.simulated_order_flow());
+        assert!(aggressive.0 > normal.0 && normal.0 > conservative.0);
+        assert!(aggressive.1 < normal.1 && normal.1 < conservative.1);
+
+        // Aggressive: the part of a local limit order that does not fill is cancelled
+        app.order_book = OrderBook::new();
+        app.order_history.clear();
+        app.order_book.add_order(OrderSide::Ask, 101.0, 1.0, 1);
+        let submit = |app: &mut App, price: &str, quantity: &str| {
+            app.order_input.venue = OrderVenue::Local;
+            app.order_input.side = PolymarketOrderSide::BUY;
+            app.order_input.price = price.to_string();
+            app.order_input.quantity = quantity.to_string();
+            app.submit_order();
+        };
+        app.trading_mode = TradingMode::Aggressive;
+        submit(&mut app, "102", "3");
+        assert_eq!(app.order_book.get_total_orders(), 0);
+        let statuses: Vec<&str> = app.order_history.iter().map(|record| record.status.as_str()).collect();
+        assert_eq!(statuses, vec!["Filled"]);
+
+        // Conservative: crossing limit orders and market orders are rejected, the rest rest
+        app.trading_mode = TradingMode::Conservative;
+        app.order_book.add_order(OrderSide::Ask, 101.0, 1.0, 2);
+        submit(&mut app, "102", "1");
+        let rejected = app.order_history.back().unwrap();
+        assert_eq!(rejected.status, "Rejected");
+        assert_eq!(rejected.reason.as_deref(), Some("post-only Bid at 102 would cross the best ask at 101"));
+        submit(&mut app, "100", "1");
+        assert_eq!(app.order_book.get_best_bid(), Some(100.0));
+        assert_eq!(app.order_history.back().unwrap().status, "Resting");
+        app.order_input.market = true;
+        submit(&mut app, "", "1");
+        assert_eq!(
+            app.order_history.back().unwrap().reason.as_deref(),
+            Some("Conservative mode only places post-only limit orders")
+        );
+        assert_eq!(app.order_book.get_best_ask(), Some(101.0));
+    }
 
-fn draw_current_price_header(f: &mut Frame, app: &App, area: Rect) {
-    let current_price = app.market_data.current_price;
-    let price_change = app.market_data.price_change;
-    let price_change_percent = app.market_data.price_change_percent;
-    
-    let change_symbol = if price_change >= 0.0 { "↗" } else { "↘" };
-    let change_color = if price_change >= 0.0 { Color::Green } else { Color::Red };
-    
-    let price_text = format!("${:.2}", current_price);
-    let change_text = format!("{} ${:.2} ({:+.2}%)", change_symbol, price_change.abs(), price_change_percent);
-    
-    let header_content = vec![
-        Line::from(vec![
-            Span::styled("Current Price: ", Style::default().fg(Color::White)),
-            Span::styled(price_text, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-        ]),
-        Line::from(vec![
-            Span::styled("Change: ", Style::default().fg(Color::White)),
-            Span::styled(change_text, Style::default().fg(change_color)),
-        ]),
-    ];
+    #[test]
+    fn test_submit_local_market_order_sweeps_asks() {
+        let mut app = App::new();
+        app.toggle_market_order();
+        assert!(!app.order_input.market, "market orders need the local venue");
+
+        app.order_input.venue = OrderVenue::Local;
+        app.order_input.active = true;
+        app.toggle_market_order();
+        assert!(app.order_input.market);
+        assert_eq!(app.order_input.current_field, OrderField::Quantity);
+        app.order_input.next_field();
+        app.order_input.next_field();
+        assert_ne!(app.order_input.current_field, OrderField::Price);
+
+        let (_, asks) = app.order_book.get_market_depth(2);
+        let quantity = asks[0].1 + asks[1].1 / 2.0;
+        app.order_input.side = PolymarketOrderSide::BUY;
+        app.order_input.quantity = quantity.to_string();
+        app.submit_order();
+
+        // One fill row per level taken, and nothing rests for a market order
+        assert_eq!(app.order_history.len(), 2);
+        assert!(app.order_history.iter().all(|r| r.status == "Filled" && r.side == OrderSide::Bid));
+        assert_eq!(app.order_history[0].price, asks[0].0);
+        assert_eq!(app.order_history[1].price, asks[1].0);
+        let (_, after) = app.order_book.get_market_depth(1);
+        assert_eq!(after[0].0, asks[1].0);
+        assert!((after[0].1 - (asks[1].1 - app.order_history[1].quantity)).abs() < 1e-5);
+        assert!(!app.order_input.active);
+
+        // Switching away from the local book drops market mode
+        app.toggle_order_venue();
+        assert!(!app.order_input.market);
+    }
 
-    let header = Paragraph::new(header_content)
-        .block(Block::default().borders(Borders::ALL).title("Market Price"))
-        .alignment(ratatui::layout::Alignment::Center);
+    #[test]
+    fn test_tape_rows_format_and_flag_large_prints() {
+        let trade = |price: f64, quantity: f64, aggressor_side| Trade {
+            bid_order_id: 1,
+            ask_order_id: 2,
+            price,
+            quantity,
+            // 2023-11-14 22:13:20.500 UTC
+            timestamp: 1_700_000_000_500,
+            aggressor_side,
+            maker_fee: 0.0,
+            taker_fee: 0.0,
+        };
+        let trades = [trade(26437.5, 2.0, OrderSide::Bid), trade(26430.0, 0.01234, OrderSide::Ask)];
+
+        let rows = tape_rows(&trades, 2, 50_000.0);
+        assert_eq!(
+            rows[0],
+            TapeRow {
+                time: "22:13:20".to_string(),
+                price: "26437.50".to_string(),
+                quantity: "2.00000".to_string(),
+                side: OrderSide::Bid,
+                large: true,
+            }
+        );
+        assert_eq!((rows[1].quantity.as_str(), rows[1].side, rows[1].large), ("0.01234", OrderSide::Ask, false));
 
-    f.render_widget(header, area);
-}
+        assert_eq!(tape_rows(&trades[..1], 4, 50_000.0)[0].price, "26437.5000");
 
-fn draw_order_book_content(f: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(4),  // Column headers (increased for 2 lines)
-            Constraint::Min(0),     // Order data
-        ])
-        .split(area);
+        // The threshold is inclusive
+        assert!(tape_rows(&trades[..1], 2, 52_875.0)[0].large);
+        assert!(!tape_rows(&trades[..1], 2, 52_875.01)[0].large);
+        assert!(tape_rows(&[], 2, 1.0).is_empty());
+    }
 
-    // Draw column headers
-    draw_order_book_headers(f, chunks[0]);
-    
-    // Draw order data
-    draw_order_book_data(f, app, chunks[1]);
-}
+    #[test]
+    fn test_tape_follows_matches_until_paused() {
+        let mut app = App::new();
+        let best_ask = app.order_book.get_best_ask().unwrap();
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        let sell_into_bids = |app: &App| {
+            app.order_book.add_market_order(OrderSide::Ask, 0.001, now);
+        };
 
-fn draw_order_book_headers(f: &mut Frame, area: Rect) {
-    let header_content = vec![
-        Line::from(vec![
-            Span::styled("Price (USDT)", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-            Span::styled("  ", Style::default()),
-            Span::styled("Amount (BTC)", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-            Span::styled("  ", Style::default()),
-            Span::styled("Total (USDT)", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-            Span::styled("  ", Style::default()),
-            Span::styled("Depth", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        ]),
-        Line::from(vec![
-            Span::styled("SELL ORDERS", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-            Span::styled("  ", Style::default()),
-            Span::styled("", Style::default()),
-            Span::styled("  ", Style::default()),
-            Span::styled("", Style::default()),
-            Span::styled("  ", Style::default()),
-            Span::styled("", Style::default()),
-        ]),
-    ];
+        app.order_book.add_limit_order(OrderSide::Bid, best_ask, 0.001, now);
+        assert_eq!(app.tape_trades().len(), 1);
+        assert_eq!(app.tape_trades()[0].aggressor_side, OrderSide::Bid);
+
+        app.toggle_tape_pause();
+        sell_into_bids(&app);
+        assert_eq!(app.tape_trades().len(), 1);
+
+        app.toggle_tape_pause();
+        let tape = app.tape_trades();
+        assert_eq!(tape.len(), 2);
+        assert_eq!(tape[0].aggressor_side, OrderSide::Ask, "newest first");
+
+        app.user_command.set("tape large 25");
+        app.execute_user_command();
+        assert_eq!(app.large_trade_notional, 25.0);
+        app.user_command.set("tape large -1");
+        app.execute_user_command();
+        assert_eq!(app.large_trade_notional, 25.0);
+    }
 
-    let header = Paragraph::new(header_content)
-        .block(Block::default().borders(Borders::NONE))
-        .alignment(ratatui::layout::Alignment::Left);
+    #[test]
+    fn test_depth_chart_points_are_monotonic_steps() {
+        let app = App::new();
+        let (bids, asks) = app.order_book.cumulative_depth(DEPTH_CHART_LEVELS);
+        assert!(!bids.is_empty() && !asks.is_empty());
+
+        for (levels, moves_up) in [(&bids, false), (&asks, true)] {
+            let points = depth_chart_points(levels);
+            assert_eq!(points.len(), levels.len() * 2);
+            assert_eq!(points[0], (levels[0].0, 0.0));
+            assert_eq!(points.last().unwrap().1, levels.last().unwrap().1);
+            for pair in points.windows(2) {
+                // Totals never fall, and prices only move away from the spread
+                assert!(pair[1].1 >= pair[0].1);
+                assert!(if moves_up { pair[1].0 >= pair[0].0 } else { pair[1].0 <= pair[0].0 });
+            }
+        }
+        assert!(depth_chart_points(&[]).is_empty());
+    }
 
-    f.render_widget(header, area);
-}
+    #[test]
+    fn test_depth_chart_bounds_center_and_one_sided_books() {
+        let bids = [(99.0, 2.0), (97.0, 5.0)];
+        let asks = [(101.0, 1.0), (102.0, 10.0)];
+        let (prices, totals) = depth_chart_bounds(&bids, &asks).unwrap();
+        assert_eq!(prices, [97.0, 103.0]);
+        assert!((totals[1] - 10.5).abs() < 1e-9);
+
+        // One-sided books center on their best price
+        let (prices, totals) = depth_chart_bounds(&bids, &[]).unwrap();
+        assert_eq!(prices, [97.0, 101.0]);
+        assert!((totals[1] - 5.25).abs() < 1e-9);
+        let (prices, _) = depth_chart_bounds(&[], &asks[..1]).unwrap();
+        assert!(prices[0] < 101.0 && prices[1] > 101.0);
+
+        assert_eq!(depth_chart_bounds(&[], &[]), None);
+    }
 
-fn draw_order_book_data(f: &mut Frame, app: &App, area: Rect) {
-    let (bids, asks) = app.order_book.get_market_depth(20);
-    
-    // Calculate total height for asks and bids
-    let total_height = area.height as usize;
-    let asks_height = (total_height / 2).min(asks.len());
-    let bids_height = (total_height / 2).min(bids.len());
-    
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(asks_height as u16),  // Asks (sell orders)
-            Constraint::Length(3),                   // Current price separator
-            Constraint::Length(1),                   // Buy orders label
-            Constraint::Length(bids_height as u16),  // Bids (buy orders)
-        ])
-        .split(area);
+    fn golden_candles() -> Vec<Candlestick> {
+        let at = |minute| chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 3, 1, 10, minute, 0).unwrap();
+        vec![
+            Candlestick::new(at(0), 100.0, 104.0, 99.0, 103.0, 3_000_000.0),
+            Candlestick::new(at(1), 103.0, 103.0, 96.0, 97.0, 1_000_000.0),
+            Candlestick::new(at(2), 97.0, 101.0, 96.0, 100.0, 2_000_000.0),
+        ]
+    }
 
-    // Draw asks (sell orders) - red, descending order
-    draw_asks_section(f, &asks, chunks[0]);
-    
-    // Draw current price separator with more detail
-    draw_current_price_separator(f, app, chunks[1]);
-    
-    // Draw buy orders label
-    draw_buy_orders_label(f, chunks[2]);
-    
-    // Draw bids (buy orders) - green, descending order
-    draw_bids_section(f, &bids, chunks[3]);
-}
+    #[test]
+    fn test_terminal_chart_golden_output() {
+        let mut chart = TerminalChartBackend::new(30, 11);
+        chart.draw_candlestick_chart(&golden_candles(), 100.0, ChartOverlay::MovingAverages, &Theme::dark()).unwrap();
+        // Prices 95.2-104.8 over five rows; the 📈 takes two of the 30 columns
+        let expected = [
+            "📈 BTC/USDT 1m | $100.00 ↗ | R",
+            "    105 │ │    ┄    ┄    ┄",
+            "    102 │ █░   ┄    ┄    ┄",
+            "    100 │ █░█  ┄    ┄    ┄",
+            "     98 │ ┄░█  ┄    ┄    ┄",
+            "     95 │ ┄││  ┄    ┄    ┄",
+            "          ▄",
+            "          ▄ ▄",
+            "          ▄▄▄",
+            "",
+            "█ Candles | ● MA7 | ○ MA25 | ▄",
+        ];
+        assert_eq!(chart.render(), expected.join("\n"));
 
-fn draw_buy_orders_label(f: &mut Frame, area: Rect) {
-    let label_content = vec![
-        Line::from(vec![
-            Span::styled("BUY ORDERS", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-            Span::styled("  ", Style::default()),
-            Span::styled("", Style::default()),
-            Span::styled("  ", Style::default()),
-            Span::styled("", Style::default()),
-        ]),
-    ];
+        // Candles are green or red and volume dimmed; the wick and body share a color
+        let lines = chart.lines();
+        let style_at = |row: usize, glyph: &str| {
+            lines[row].spans.iter().find(|span| span.content.contains(glyph)).map(|span| span.style)
+        };
+        assert_eq!(style_at(1, "│ "), Some(Style::default()));
+        assert_eq!(style_at(2, "█"), Some(Style::default().fg(Color::Green)));
+        assert_eq!(style_at(2, "░"), Some(Style::default().fg(Color::Red)));
+        assert_eq!(style_at(5, "││"), None, "the two wicks differ in color");
+        assert_eq!(style_at(8, "▄"), Some(Style::default().fg(Color::Green).add_modifier(Modifier::DIM)));
+        assert_eq!(lines[0].width(), 30);
+        assert!(lines.iter().all(|line| line.width() <= 30));
+    }
 
-    let label = Paragraph::new(label_content)
-        .block(Block::default().borders(Borders::NONE))
-        .alignment(ratatui::layout::Alignment::Left);
+    #[test]
+    fn test_terminal_chart_wide_glyphs_and_tiny_heights() {
+        let mut chart = TerminalChartBackend::new(24, 8);
+        chart.put_str(0, 0, "a📈b", Style::default());
+        assert_eq!(chart.render().lines().next(), Some("a📈b"));
+        // Overwriting either half of a wide glyph blanks the other half
+        chart.put(0, 2, 'x', Style::default());
+        assert_eq!(chart.render().lines().next(), Some("a xb"));
+        chart.put_str(1, 0, "📈📈", Style::default());
+        chart.put(1, 1, '📉', Style::default());
+        assert_eq!(chart.render().lines().nth(1), Some(" 📉"));
+        // A wide glyph that would straddle the edge is left out
+        chart.put_str(2, 21, "ab📈", Style::default());
+        assert_eq!(chart.render().lines().nth(2).map(str::trim), Some("ab"));
+
+        assert_eq!(TerminalChartBackend::price_to_chart_y(5.0, 0.0, 10.0, 0), 0);
+        assert_eq!(TerminalChartBackend::price_to_chart_y(5.0, 0.0, 10.0, 1), 1);
+        assert_eq!(TerminalChartBackend::price_to_chart_y(10.0, 0.0, 10.0, 3), 1);
+        assert_eq!(TerminalChartBackend::price_to_chart_y(0.0, 0.0, 10.0, 3), 3);
+
+        // With the emoji header every price row still puts its axis in column 8
+        let mut chart = TerminalChartBackend::new(40, 12);
+        chart.draw_candlestick_chart(&golden_candles(), 100.0, ChartOverlay::Ema, &Theme::dark()).unwrap();
+        for line in chart.lines().iter().skip(1).take(6) {
+            let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+            assert_eq!(text.chars().nth(8), Some('│'), "{}", text);
+        }
+    }
 
-    f.render_widget(label, area);
-}
+    #[test]
+    fn test_chart_header_names_coin_and_timeframe() {
+        let mut app = App::new();
+        app.select_coin_by_index(1);
+        app.selected_timeframe = ChartTimeframe::OneHour;
+        app.update_chart_for_timeframe();
+        app.resize_terminal_chart(100, 30);
+        app.update_terminal_chart_data().unwrap();
+        let header = app.terminal_chart.render().lines().next().unwrap().to_string();
+        assert!(header.starts_with("📈 ETH/USDT 1h | $"), "{}", header);
+
+        // A resize keeps the labels in step with the selection
+        app.select_coin_by_index(2);
+        app.selected_timeframe = ChartTimeframe::FiveMinutes;
+        app.resize_terminal_chart(100, 30);
+        assert_eq!((app.terminal_chart.symbol.as_str(), app.terminal_chart.timeframe.as_str()), ("SOL/USDT", "5m"));
+
+        // The price is shown with the coin's own decimals
+        app.update_chart_for_timeframe();
+        app.update_terminal_chart_data().unwrap();
+        let header = app.terminal_chart.render().lines().next().unwrap().to_string();
+        let price = format!("| ${:.3} ", app.market_data.current_price);
+        assert!(header.contains(&price), "{}", header);
+    }
 
-fn draw_current_price_separator(f: &mut Frame, app: &App, area: Rect) {
-    let current_price = app.market_data.current_price;
-    let price_change = app.market_data.price_change;
-    let price_change_percent = app.market_data.price_change_percent;
-    
-    let change_symbol = if price_change >= 0.0 { "↗" } else { "↘" };
-    let change_color = if price_change >= 0.0 { Color::Green } else { Color::Red };
-    
-    let price_text = format!("{:.2}", current_price);
-    let change_text = format!("{} ${:.2} ({:+.2}%)", change_symbol, price_change.abs(), price_change_percent);
-    
-    let separator_content = vec![
-        Line::from(vec![
-            Span::styled("─".repeat(area.width as usize), Style::default().fg(Color::Yellow)),
-        ]),
-        Line::from(vec![
-            Span::styled("Current: ", Style::default().fg(Color::White)),
-            Span::styled(price_text, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(" USDT", Style::default().fg(Color::Gray)),
-        ]),
-        Line::from(vec![
-            Span::styled(change_text, Style::default().fg(change_color)),
-        ]),
-    ];
+    #[test]
+    fn test_chart_overlays_and_indicator_points() {
+        let mut app = App::new();
+        app.update_chart_for_timeframe();
+        // 30 days of minutes, the oldest day partly
+        assert_eq!(app.candlestick_data.len(), 31);
+        for overlay in [ChartOverlay::MovingAverages, ChartOverlay::Ema, ChartOverlay::Bollinger] {
+            assert_eq!(app.chart_overlay, overlay);
+            let mut chart = TerminalChartBackend::new(100, 30);
+            chart.draw_candlestick_chart(&app.candlestick_data, 100.0, overlay, &app.theme).unwrap();
+            assert!(chart.render().contains(overlay.legend()), "{:?}", overlay);
+            app.cycle_chart_overlay();
+        }
+        assert_eq!(app.chart_overlay, ChartOverlay::MovingAverages);
+        app.cycle_indicator_pane();
+        assert_eq!(app.indicator_pane, IndicatorPane::Rsi);
+
+        let series = [None, None, Some(1.0), None, Some(3.0), Some(4.0)];
+        assert_eq!(indicator_points(&series, 0), vec![(2.0, 1.0), (4.0, 3.0), (5.0, 4.0)]);
+        assert_eq!(indicator_points(&series, 3), vec![(1.0, 3.0), (2.0, 4.0)]);
+        assert!(indicator_points(&series, 6).is_empty());
+    }
 
-    let separator = Paragraph::new(separator_content)
-        .block(Block::default().borders(Borders::NONE))
-        .alignment(ratatui::layout::Alignment::Center);
-    
-    f.render_widget(separator, area);
-}
+    #[test]
+    fn test_percent_change_near_zero_previous_price() {
+        assert_eq!(percent_change(5.0, 100.0), 5.0);
+        assert_eq!(percent_change(-25.0, 50.0), -50.0);
+
+        assert_eq!(percent_change(10.0, 0.0), 0.0);
+        assert_eq!(percent_change(10.0, -0.0), 0.0);
+        assert_eq!(percent_change(0.0, 0.0), 0.0);
+        assert_eq!(percent_change(10.0, 1e-12), 0.0);
+        assert_eq!(percent_change(10.0, f64::NAN), 0.0);
+        assert!(percent_change(f64::MAX, 1e-3).is_finite());
+    }
 
-fn draw_asks_section(f: &mut Frame, asks: &[(f64, f64)], area: Rect) {
-    let mut rows = Vec::new();
-    
-    // Calculate cumulative totals for background intensity
-    let mut cumulative_total = 0.0;
-    let max_total = asks.iter().map(|(_, qty)| qty).sum::<f64>();
-    
-    // Add asks in descending order (highest price first)
-    for (price, quantity) in asks.iter().rev() {
-        let total = price * quantity;
-        cumulative_total += quantity;
-        let intensity = (cumulative_total / max_total).min(1.0);
-        
-        // Create depth bar visualization
-        let bar_length = (intensity * 20.0) as usize;
-        let depth_bar = "█".repeat(bar_length);
-        
-        let row = Row::new(vec![
-            format!("{:.2}", price),
-            format!("{:.5}", quantity),
-            format!("{:.2}", total),
-            format!("{}", depth_bar),
-        ]);
-        rows.push(row);
+    #[test]
+    fn test_pm_book_command_loads_polymarket_book() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/book"))
+                .and(query_param("token_id", "123"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(
+                    r#"{"market":"0xabc","asset_id":"123","bids":[],"asks":[{"price":"0.523","size":"40"},{"price":"0.6","size":"10"}],"tick_size":"0.001","min_order_size":"5"}"#,
+                ))
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let mut app = App::new();
+        app.polymarket_client = Some(PolymarketClobClient::new(
+            server.uri(),
+            "test_private_key".to_string(),
+            137,
+            PolymarketSignatureType::EMAIL_MAGIC,
+            None,
+        ));
+        let coin_orders = app.order_book.get_total_orders();
+
+        app.user_command.set("pm book 123");
+        app.execute_user_command();
+        app.wait_for_requests(Duration::from_secs(5));
+        let (book, decimals) = app.displayed_book();
+        assert_eq!(decimals, 3);
+        assert_eq!(book.get_best_bid(), None);
+        assert_eq!(book.get_best_ask(), Some(0.523));
+        assert_eq!(app.polymarket_client.as_ref().unwrap().market_params("123").tick_size, 0.001);
+        assert!(app.real_time_data.back().unwrap().contains("0 bid / 2 ask levels"));
+        // The coin's own book is left alone
+        assert_eq!(app.order_book.get_total_orders(), coin_orders);
+
+        app.user_command.set("pm book");
+        app.execute_user_command();
+        assert!(app.polymarket_book.is_none());
+        assert_eq!(app.displayed_book().1, 2);
     }
 
-    let widths = [
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-    ];
+    #[test]
+    fn test_polymarket_cancels_update_history() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("DELETE"))
+                .and(path("/order"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"canceled":["0x1"],"not_canceled":{}}"#))
+                .mount(&server)
+                .await;
+            Mock::given(method("DELETE"))
+                .and(path("/cancel-all"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(
+                    r#"{"canceled":["0x2"],"not_canceled":{"0x3":"order can't be found - already canceled or matched"}}"#,
+                ))
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let mut app = App::new();
+        let mut client = PolymarketClobClient::new(
+            server.uri(),
+            "test_private_key".to_string(),
+            137,
+            PolymarketSignatureType::EMAIL_MAGIC,
+            None,
+        );
+        client.set_api_credentials(client.create_or_derive_api_credentials());
+        app.polymarket_client = Some(client);
+        app.order_history.clear();
+        for id in ["0x1", "0x2", "0x3"] {
+            let mut record = filled_record("token", OrderSide::Bid, 0.5, 10.0);
+            record.venue = OrderVenue::Polymarket;
+            record.status = "Pending".to_string();
+            record.order_id = id.to_string();
+            app.order_history.push_back(record);
+        }
 
-    let table = Table::new(rows, widths)
-        .block(Block::default().borders(Borders::NONE))
-        .style(Style::default().fg(Color::Red))
-        .highlight_style(Style::default().fg(Color::White).bg(Color::Red));
+        // Both requests run on the app's runtime, reusing its connections
+        app.runtime = Some(runtime.handle().clone());
+        app.selected_order_id = Some("0x1".to_string());
+        app.cancel_selected_history_order();
+        app.wait_for_requests(Duration::from_secs(5));
+        assert_eq!(app.order_history[0].status, "Cancelled");
+
+        app.user_command.set("pm cancel-all");
+        app.execute_user_command();
+        app.wait_for_requests(Duration::from_secs(5));
+        let statuses: Vec<&str> = app.order_history.iter().map(|r| r.status.as_str()).collect();
+        assert_eq!(statuses, vec!["Cancelled", "Cancelled", "Pending"]);
+        assert!(app.real_time_data.back().unwrap().contains("Cannot cancel order #0x3"));
+    }
 
-    f.render_widget(table, area);
-}
+    #[test]
+    fn test_user_channel_updates_polymarket_status() {
+        use crate::polymarket_orders::PolymarketOrderStatus;
+
+        let mut app = App::new();
+        app.order_history.clear();
+        let mut record = filled_record("token", OrderSide::Ask, 0.57, 10.0);
+        record.venue = OrderVenue::Polymarket;
+        record.status = "Pending".to_string();
+        record.order_id = "0xff35".to_string();
+        app.order_history.push_back(record);
+
+        let mut update = PolymarketOrderUpdate {
+            order_id: "0xff35".to_string(),
+            asset_id: "token".to_string(),
+            side: PolymarketOrderSide::SELL,
+            price: 0.57,
+            original_size: Some(10.0),
+            size_matched: 0.0,
+            status: PolymarketOrderStatus::Live,
+            timestamp: 1672290687,
+        };
+        let mut statuses = Vec::new();
+        for status in [
+            PolymarketOrderStatus::Live,
+            PolymarketOrderStatus::Matched,
+            PolymarketOrderStatus::Cancelled,
+        ] {
+            update.status = status;
+            app.apply_polymarket_order_update(&update);
+            statuses.push(app.order_history[0].status.clone());
+        }
+        assert_eq!(statuses, vec!["Live", "Matched", "Cancelled"]);
 
-fn draw_bids_section(f: &mut Frame, bids: &[(f64, f64)], area: Rect) {
-    let mut rows = Vec::new();
-    
-    // Calculate cumulative totals for background intensity
-    let mut cumulative_total = 0.0;
-    let max_total = bids.iter().map(|(_, qty)| qty).sum::<f64>();
-    
-    // Add bids in descending order (highest price first)
-    for (price, quantity) in bids {
-        let total = price * quantity;
-        cumulative_total += quantity;
-        let intensity = (cumulative_total / max_total).min(1.0);
-        
-        // Create depth bar visualization
-        let bar_length = (intensity * 20.0) as usize;
-        let depth_bar = "█".repeat(bar_length);
-        
-        let row = Row::new(vec![
-            format!("{:.2}", price),
-            format!("{:.5}", quantity),
-            format!("{:.2}", total),
-            format!("{}", depth_bar),
-        ]);
-        rows.push(row);
+        // Updates for orders placed elsewhere leave history alone
+        update.order_id = "0xother".to_string();
+        update.status = PolymarketOrderStatus::Live;
+        app.apply_polymarket_order_update(&update);
+        assert_eq!(app.order_history[0].status, "Cancelled");
+    }
+
+    #[test]
+    fn test_depth_bar_lengths() {
+        let quantities = [1.0, 4.0, 2.0, 3.0];
+        assert_eq!(depth_bar_lengths(&quantities, DepthBarMode::Cumulative), vec![2, 10, 14, 20]);
+        assert_eq!(depth_bar_lengths(&quantities, DepthBarMode::PerLevel), vec![5, 20, 10, 15]);
+        assert_eq!(depth_bar_lengths(&[0.0, 0.0], DepthBarMode::PerLevel), vec![0, 0]);
+        assert!(depth_bar_lengths(&[], DepthBarMode::Cumulative).is_empty());
+
+        let mut app = App::new();
+        app.toggle_depth_bar_mode();
+        assert_eq!(app.depth_bar_mode, DepthBarMode::PerLevel);
+        app.user_command.set("bars");
+        app.execute_user_command();
+        assert_eq!(app.depth_bar_mode, DepthBarMode::Cumulative);
     }
 
-    let widths = [
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-    ];
+    #[test]
+    fn test_gtd_selection_sets_expiration() {
+        let mut app = App::new();
+        let now = chrono::Utc::now().timestamp();
 
-    let table = Table::new(rows, widths)
-        .block(Block::default().borders(Borders::NONE))
-        .style(Style::default().fg(Color::Green))
-        .highlight_style(Style::default().fg(Color::White).bg(Color::Green));
+        app.select_order_type(PolymarketOrderType::GTC);
+        assert_eq!(app.order_input.expiration, None);
 
-    f.render_widget(table, area);
-}
+        app.select_order_type(PolymarketOrderType::GTD);
+        let default = app.order_input.expiration.expect("GTD should default an expiration");
+        assert!((default - (now + 3600)).abs() <= 2);
+        assert!(app.real_time_data.back().unwrap().contains("GTD order expires at"));
 
-fn draw_trading_panel(f: &mut Frame, app: &App, area: Rect) {
-    let content = format!(
-        "Trading Panel - {}\n\n\
-        Best Bid: ${:.2}\n\
-        Best Ask: ${:.2}\n\
-        Spread: ${:.2}\n\
-        Last Price: ${:.2}\n\
-        Volume 24h: ${:.0}",
-        app.current_market,
-        app.order_book.get_best_bid().unwrap_or(0.0),
-        app.order_book.get_best_ask().unwrap_or(0.0),
-        app.order_book.get_spread().unwrap_or(0.0),
-        app.market_data.current_price,
-        app.market_data.volume_24h
-    );
+        app.user_command.set("expire 5");
+        app.execute_user_command();
+        let expiration = app.order_input.expiration.unwrap();
+        assert!((expiration - (now + 300)).abs() <= 2);
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Trading Overview"))
-        .wrap(Wrap { trim: true });
+        // Re-selecting GTD keeps a still-future expiration
+        app.select_order_type(PolymarketOrderType::FOK);
+        app.select_order_type(PolymarketOrderType::GTD);
+        assert_eq!(app.order_input.expiration, Some(expiration));
 
-    f.render_widget(paragraph, area);
-}
+        app.user_command.set("expire soon");
+        app.execute_user_command();
+        assert_eq!(app.real_time_data.back().unwrap(), "Usage: expire <minutes>");
+    }
 
-fn draw_market_data_panel(f: &mut Frame, app: &App, area: Rect) {
-    let mut content = String::new();
-    
-    content.push_str(&format!("Market Data - {}\n\n", app.current_market));
-    content.push_str(&format!("Current Price: ${:.2}\n", app.market_data.current_price));
-    
-    // Price change with color indication
-    let price_change_text = format_number_with_color(app.market_data.price_change, false);
-    let _price_change_color = get_number_color(app.market_data.price_change);
-    
-    let price_change_percent_text = format_number_with_color(app.market_data.price_change_percent, true);
-    let _price_change_percent_color = get_number_color(app.market_data.price_change_percent);
-    
-    content.push_str(&format!("Change: ${} ({})\n", price_change_text, price_change_percent_text));
-    content.push_str(&format!("High 24h: ${:.2}\n", app.market_data.high_24h));
-    content.push_str(&format!("Low 24h: ${:.2}\n", app.market_data.low_24h));
-    content.push_str(&format!("Volume 24h: ${:.0}\n", app.market_data.volume_24h));
-    content.push_str(&format!("Market Cap: ${:.0}B\n", app.market_data.market_cap / 1e9));
-    content.push_str(&format!("Last Update: {}", app.last_update.format("%H:%M:%S")));
+    #[test]
+    fn test_price_precision_per_coin() {
+        let mut app = App::new();
+        assert_eq!(app.format_price(26436.587), "26436.59");
+        assert_eq!(app.price_decimals_for("SOLUSDT"), 3);
+        assert_eq!(app.price_decimals_for("DOGE"), DEFAULT_PRICE_DECIMALS);
+
+        app.user_command.set("precision 4");
+        app.execute_user_command();
+        assert_eq!(app.real_time_data.back().unwrap(), "BTC prices shown with 4 decimals");
+        assert_eq!(app.format_price(26436.587), "26436.5870");
+        assert_eq!(app.displayed_book().1, 4);
+        assert!(app.get_trading_summary().starts_with(&format!("Bid: ${:.4}", app.order_book.get_best_bid().unwrap())));
+
+        // Each coin keeps its own
+        app.select_coin_by_index(2);
+        assert_eq!(app.format_price(98.4512), "98.451");
+        app.user_command.set("precision 12");
+        app.execute_user_command();
+        assert_eq!(app.real_time_data.back().unwrap(), "Usage: precision <0-8>");
+        assert_eq!(app.current_price_decimals(), 3);
+        app.select_coin_by_index(0);
+        assert_eq!(app.current_price_decimals(), 4);
+    }
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Market Data"))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_refresh_command() {
+        assert_eq!(parse_refresh_interval("5"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse_refresh_interval(" 0.5 "), Ok(Duration::from_millis(500)));
+        assert!(parse_refresh_interval("").is_err());
+        assert!(parse_refresh_interval("fast").is_err());
+        assert!(parse_refresh_interval("0").is_err());
+        assert!(parse_refresh_interval("-2").is_err());
+        assert!(parse_refresh_interval("NaN").is_err());
+        assert!(parse_refresh_interval("86400").is_err());
+
+        let mut app = App::new();
+        app.user_command.set("refresh 10");
+        app.execute_user_command();
+        assert_eq!(app.refresh_interval, Duration::from_secs(10));
+
+        app.user_command.set("refresh soon");
+        app.execute_user_command();
+        assert_eq!(app.refresh_interval, Duration::from_secs(10));
+        assert!(app.real_time_data.back().unwrap().starts_with("Usage: refresh"));
+    }
 
-    f.render_widget(paragraph, area);
-}
+    #[test]
+    fn test_builtin_commands_dispatch_and_check_arity() {
+        use crate::commands::CommandError;
+
+        let registry = builtin_commands();
+        assert_eq!(registry.get("refresh").unwrap().usage(), "refresh <seconds>");
+        assert_eq!(registry.get("alert above").unwrap().usage(), "alert above <price> [message...]");
+
+        let mut app = App::new();
+        registry.execute(&mut app, "help").unwrap();
+        assert!(app.help_mode);
+        registry.execute(&mut app, "refresh 2.5").unwrap();
+        assert_eq!(app.refresh_interval, Duration::from_millis(2500));
+        registry.execute(&mut app, "alert above 30000 breakout").unwrap();
+        registry.execute(&mut app, "alert below 20000").unwrap();
+        assert_eq!(app.price_alerts.len(), 2);
+        assert_eq!(app.price_alerts[0].alert_type, AlertType::PriceAbove(30000.0));
+        assert_eq!(app.price_alerts[0].message, "breakout");
+        assert_eq!(app.price_alerts[1].message, "below 20000");
+        registry.execute(&mut app, "alert list").unwrap();
+        assert_eq!(app.real_time_data.back().unwrap(), "Active alerts: 2");
+
+        assert_eq!(
+            registry.execute(&mut app, "alert").unwrap_err().to_string(),
+            "Usage: alert <above|below|change|volume|cross|spread|mid|liq|whale|list|remove|history clear|save|load> ..."
+        );
+        assert_eq!(
+            registry.execute(&mut app, "alert above"),
+            Err(CommandError::Usage("alert above <price> [message...]".to_string()))
+        );
+        assert_eq!(
+            registry.execute(&mut app, "bars now"),
+            Err(CommandError::Usage("bars".to_string()))
+        );
+        assert_eq!(registry.execute(&mut app, "refresh"), Err(CommandError::Usage("refresh <seconds>".to_string())));
+        assert!(matches!(registry.execute(&mut app, "launch"), Err(CommandError::Unknown(_))));
+        assert_eq!(app.price_alerts.len(), 2);
+
+        // The command bar logs the hint and clears the input
+        app.user_command.set("alert remove");
+        app.execute_user_command();
+        assert_eq!(app.real_time_data.back().unwrap(), "Usage: alert remove <id>");
+        assert!(app.user_command.is_empty());
+        app.user_command.set("frobnicate");
+        app.execute_user_command();
+        assert_eq!(app.real_time_data.back().unwrap(), "Unknown command: frobnicate (type help for a list)");
+    }
 
-fn draw_orders_panel(f: &mut Frame, app: &App, area: Rect) {
-    let mut rows = Vec::new();
-    rows.push(Row::new(vec!["Time", "Side", "Price", "Qty", "Status", "ID"]));
+    #[test]
+    fn test_timeframes_resample_the_same_minutes() {
+        let mut app = App::with_seed(7);
+        let minutes = app.minute_candles.clone();
+        let total_volume: f64 = minutes.iter().map(|c| c.volume).sum();
+        let highest = minutes.iter().map(|c| c.high).fold(f64::NEG_INFINITY, f64::max);
+
+        app.next_timeframe();
+        assert_eq!(app.selected_timeframe, ChartTimeframe::OneMinute);
+        assert_eq!(app.candlestick_data.len(), minutes.len());
+        for _ in 0..5 {
+            app.next_timeframe();
+            let candles = &app.candlestick_data;
+            let volume: f64 = candles.iter().map(|c| c.volume).sum();
+            assert!((volume - total_volume).abs() < 1e-6 * total_volume);
+            assert_eq!(candles.iter().map(|c| c.high).fold(f64::NEG_INFINITY, f64::max), highest);
+            assert_eq!(candles.last().unwrap().close, minutes.last().unwrap().close);
+            assert_eq!(candles[0].open, minutes[0].open);
+        }
+        assert_eq!(app.selected_timeframe, ChartTimeframe::OneDay);
+        // Switching only re-resamples; the minutes stay as they were
+        assert_eq!(app.minute_candles.len(), minutes.len());
 
-    for order in app.order_history.iter().rev().take(10) {
-        rows.push(Row::new(vec![
-            order.timestamp.format("%H:%M:%S").to_string(),
-            format!("{:?}", order.side),
-            format!("${:.2}", order.price),
-            format!("{:.2}", order.quantity),
-            order.status.clone(),
-            order.order_id.clone(),
-        ]));
+        app.update_candlestick_data();
+        assert_eq!(app.market_data.current_price, app.minute_candles.last().unwrap().close);
+        assert_eq!(app.candlestick_data.last().unwrap().close, app.market_data.current_price);
     }
 
-    let widths = [
-        Constraint::Percentage(16),
-        Constraint::Percentage(16),
-        Constraint::Percentage(16),
-        Constraint::Percentage(16),
-        Constraint::Percentage(16),
-        Constraint::Percentage(20),
-    ];
+    #[test]
+    fn test_seeded_apps_simulate_identically() {
+        fn snapshot(app: &App) -> (usize, u32, Vec<[f64; 5]>) {
+            let candles = app.candlestick_data.iter().map(|c| [c.open, c.high, c.low, c.close, c.volume]).collect();
+            (app.order_book.get_total_orders(), app.order_book.checksum(25), candles)
+        }
+        fn run(app: &mut App) {
+            app.refresh_order_book();
+            app.select_coin_by_index(1);
+            app.update_market_data();
+            app.update_chart_for_timeframe();
+        }
 
-    let table = Table::new(rows, widths)
-        .block(Block::default().borders(Borders::ALL).title("Order History"))
-        .style(Style::default().fg(Color::White));
+        let mut first = App::with_seed(42);
+        let mut second = App::with_seed(42);
+        run(&mut first);
+        run(&mut second);
+        assert_eq!(snapshot(&first), snapshot(&second));
+        assert_eq!(first.market_data.current_price, second.market_data.current_price);
 
-    f.render_widget(table, area);
-}
+        let mut other = App::with_seed(43);
+        run(&mut other);
+        assert_ne!(snapshot(&first).2, snapshot(&other).2);
+    }
 
-fn draw_charts_panel(f: &mut Frame, app: &mut App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Header
-            Constraint::Min(0),     // Chart content
-        ])
-        .split(area);
+    #[test]
+    fn test_simulation_ticks_repeat_with_the_seed_and_pause() {
+        fn orders(app: &App) -> Vec<(OrderSide, f64, f64)> {
+            let (bids, asks) = app.order_book.get_market_depth(1000);
+            let side = |levels: Vec<(f64, f64)>, side| levels.into_iter().map(move |(price, quantity)| (side, price, quantity));
+            side(bids, OrderSide::Bid).chain(side(asks, OrderSide::Ask)).collect()
+        }
 
-    // Header
-    let header = Paragraph::new("📊 Advanced Charts - BTC/USDT")
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
-        .alignment(ratatui::layout::Alignment::Center);
+        let mut first = App::with_seed(9);
+        let mut second = App::with_seed(9);
+        for app in [&mut first, &mut second] {
+            app.simulator.params.order_arrival_rate = 3.0;
+            for _ in 0..20 {
+                app.simulation_tick();
+            }
+        }
+        assert_eq!(orders(&first), orders(&second));
+        assert_eq!(first.market_data.current_price, second.market_data.current_price);
+        assert!(first.order_book.get_total_orders() > 40);
+
+        // Paused, ticks leave the market alone and it doesn't count as stale
+        first.toggle_simulation_pause();
+        assert_eq!(first.real_time_data.back().unwrap(), "⏸️ Simulation paused");
+        let (price, book) = (first.market_data.current_price, orders(&first));
+        first.simulation_tick();
+        assert_eq!(first.market_data.current_price, price);
+        assert_eq!(orders(&first), book);
+        first.real_time_service.last_update = chrono::Utc::now() - chrono::Duration::hours(1);
+        assert!(!first.feed_is_stale(chrono::Utc::now()));
+
+        first.toggle_simulation_pause();
+        assert!(!first.simulator.is_paused());
+        first.simulation_tick();
+        assert_ne!(first.market_data.current_price, price);
+    }
 
-    f.render_widget(header, chunks[0]);
+    #[test]
+    fn test_polymarket_orders_need_a_valid_token_id() {
+        let mut app = App::new();
+        let history_len = app.order_history.len();
+        app.order_input.venue = OrderVenue::Polymarket;
+        app.order_input.price = "0.5".to_string();
+        app.order_input.quantity = "10".to_string();
+        assert!(app.order_input.token_id.is_empty());
+
+        for (token_id, reason) in [
+            ("", "Enter a Polymarket token id first"),
+            ("BTCUSDT", "Polymarket token ids are decimal numbers"),
+            ("0042", "leading zero"),
+        ] {
+            app.order_input.token_id = token_id.to_string();
+            app.submit_order();
+            assert!(app.real_time_data.back().unwrap().contains(reason), "{}", token_id);
+        }
+        assert_eq!(app.order_history.len(), history_len);
+
+        app.user_command.set("pm book BTCUSDT");
+        app.execute_user_command();
+        assert!(app.polymarket_book.is_none());
+        assert!(app.real_time_data.back().unwrap().starts_with("Invalid token id 'BTCUSDT'"));
+
+        // Binance and the local book trade the selected coin; the field is read-only
+        app.order_input.venue = OrderVenue::Binance;
+        app.order_input.current_field = OrderField::TokenId;
+        app.order_input.token_id.clear();
+        assert!(!app.order_input.input_char('1'));
+        assert!(app.order_input.token_id.is_empty());
+        app.order_input.venue = OrderVenue::Polymarket;
+        assert!(app.order_input.input_char('1'));
+        assert_eq!(app.order_input.token_id, "1");
+    }
 
-    // Chart content
-    draw_price_chart(f, app, chunks[1]);
-}
+    #[test]
+    fn test_order_input_typing_and_fields() {
+        let mut input = App::new().order_input;
+        input.price.clear();
+        input.quantity.clear();
+        input.token_id.clear();
 
-fn draw_settings_panel(f: &mut Frame, app: &App, area: Rect) {
-    let mut lines = Vec::new();
-    
-    // Coin Switcher Section
-    let header_text = "🪙 Coin Switcher";
-    let header_line = Line::from(Span::styled(header_text, Style::default().fg(Color::Yellow)));
-    lines.push(header_line);
-    
-    let current_text = format!("Current: {} ({})", 
-        app.available_coins[app.selected_coin_index].symbol,
-        app.available_coins[app.selected_coin_index].name);
-    let current_line = Line::from(Span::styled(current_text, Style::default().fg(Color::Cyan)));
-    lines.push(current_line);
-    
-    let available_text = "Available Coins:";
-    let available_line = Line::from(Span::styled(available_text, Style::default().fg(Color::White)));
-    lines.push(available_line);
-    
-    for (i, coin) in app.available_coins.iter().enumerate() {
-        let indicator = if i == app.selected_coin_index { "●" } else { "○" };
-        let status = if i == app.selected_coin_index { "SELECTED" } else { "       " };
-        let change_percent = (coin.change_24h / coin.price) * 100.0;
-        let change_color = get_number_color(change_percent);
-        
-        let coin_text = format!("{} {} {} - ${:.2} ({:+.2}%)", 
-            indicator, coin.symbol, status, coin.price, change_percent);
-        let coin_line = Line::from(Span::styled(coin_text, Style::default().fg(change_color)));
-        lines.push(coin_line);
+        for c in "26436.5.x".chars() {
+            input.input_char(c);
+        }
+        assert_eq!(input.price, "26436.5");
+        input.backspace();
+        assert_eq!(input.price, "26436.");
+
+        input.next_field();
+        assert_eq!(input.current_field, OrderField::Quantity);
+        assert!(input.input_char('0'));
+        assert!(input.input_char('.'));
+        assert!(!input.input_char('.'));
+        assert!(input.input_char('2'));
+        assert_eq!(input.quantity, "0.2");
+
+        // Token ids are integers; Side and Type take no typed characters
+        input.next_field();
+        assert!(!input.input_char('.'));
+        assert!(input.input_char('7'));
+        assert_eq!(input.token_id, "7");
+        input.next_field();
+        assert_eq!(input.current_field, OrderField::Side);
+        assert!(!input.input_char('1'));
+        input.backspace();
+        assert_eq!(input.token_id, "7");
+
+        input.next_field();
+        input.next_field();
+        assert_eq!(input.current_field, OrderField::Price);
+        input.previous_field();
+        assert_eq!(input.current_field, OrderField::Type);
+        assert_eq!(input.price, "26436.");
     }
-    
-    // Controls section
-    let controls_header = "\nCoin Controls:";
-    let controls_header_line = Line::from(Span::styled(controls_header, Style::default().fg(Color::Yellow)));
-    lines.push(controls_header_line);
-    
-    let controls_text = "• n/N: Next coin\n• v/V: Previous coin\n• 1-3: Quick coin select";
-    let controls_line = Line::from(Span::styled(controls_text, Style::default().fg(Color::Cyan)));
-    lines.push(controls_line);
-    
-    // Settings section
-    let settings_header = "\nSettings:";
-    let settings_header_line = Line::from(Span::styled(settings_header, Style::default().fg(Color::Yellow)));
-    lines.push(settings_header_line);
-    
-    let settings_text = format!("Current Market: {}\nPolymarket Client: {}\nOrder Input Mode: {}\nHelp Mode: {}\nAuto-refresh: Enabled\nNotifications: Enabled\nTheme: Dark\nLanguage: English",
-        app.current_market,
-        if app.polymarket_client.is_some() { "Connected" } else { "Disconnected" },
-        if app.order_input.active { "Active" } else { "Inactive" },
-        if app.help_mode { "On" } else { "Off" }
-    );
-    let settings_line = Line::from(Span::styled(settings_text, Style::default().fg(Color::White)));
-    lines.push(settings_line);
 
-    let paragraph = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("Settings & Coin Switcher"))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_order_input_validation() {
+        let mut input = App::new().order_input;
+        input.price = "26436.5".to_string();
+        input.quantity = "0.1".to_string();
+        assert_eq!(input.validate(), Ok((26436.5, 0.1)));
+
+        input.quantity.clear();
+        assert_eq!(input.validate(), Err("Enter a quantity first".to_string()));
+        input.quantity = "0".to_string();
+        assert_eq!(input.validate(), Err("Invalid quantity: '0'".to_string()));
+        input.price = ".".to_string();
+        assert_eq!(input.validate(), Err("Invalid price: '.'".to_string()));
+
+        // Enter with an invalid form logs why and leaves it for correction
+        let mut app = App::new();
+        app.order_input.venue = OrderVenue::Local;
+        app.order_input.active = true;
+        app.order_input.price = "100.".to_string();
+        app.order_input.quantity.clear();
+        let orders = app.order_book.get_total_orders();
+        app.submit_order();
+        assert_eq!(app.order_book.get_total_orders(), orders);
+        assert_eq!(app.real_time_data.back().unwrap(), "❌ Local Book order rejected: Enter a quantity first");
+        assert!(app.order_input.active);
+    }
 
-    f.render_widget(paragraph, area);
-}
+    #[test]
+    fn test_rejected_orders_are_recorded_with_reason() {
+        let mut app = App::new();
+        app.order_input.venue = OrderVenue::Polymarket;
+        app.order_input.token_id = "123".to_string();
+        app.order_input.side = PolymarketOrderSide::SELL;
+        app.order_input.price.clear();
+        app.order_input.quantity = "10".to_string();
+        app.submit_order();
+
+        assert_eq!(app.order_history.len(), 1);
+        let record = &app.order_history[0];
+        assert_eq!(record.status, "Rejected");
+        assert_eq!(record.reason.as_deref(), Some("Enter a price first"));
+        assert_eq!((record.venue, record.side, record.price, record.quantity), (OrderVenue::Polymarket, OrderSide::Ask, 0.0, 10.0));
+        assert_eq!(app.real_time_data.back().unwrap(), "❌ Polymarket order rejected: Enter a price first");
+        assert_eq!(app.order_input.quantity, "10");
+
+        // Each rejection gets its own row, and there is nothing to cancel
+        app.order_input.venue = OrderVenue::Local;
+        app.order_input.price = "-5".to_string();
+        app.submit_order();
+        assert_eq!(app.order_history.len(), 2);
+        assert_eq!(app.order_history[1].reason.as_deref(), Some("Invalid price: '-5'"));
+        assert_ne!(app.order_history[0].order_id, app.order_history[1].order_id);
+
+        app.selected_order_id = Some(app.order_history[1].order_id.clone());
+        app.cancel_selected_history_order();
+        assert_eq!(app.order_history[1].status, "Rejected");
+        assert!(app.real_time_data.back().unwrap().contains("nothing to cancel"));
+    }
 
-fn draw_market_summary(f: &mut Frame, app: &App, area: Rect) {
-    let mut content = String::new();
-    
-    content.push_str("Market Summary\n\n");
-    content.push_str(&format!("Symbol: {}\n", app.current_market));
-    content.push_str(&format!("Current Price: ${:.2}\n", app.market_data.current_price));
-    
-    // Price change with color indication
-    let price_change_text = format_number_with_color(app.market_data.price_change, false);
-    let price_change_percent_text = format_number_with_color(app.market_data.price_change_percent, true);
-    
-    content.push_str(&format!("Change: ${} ({})\n", price_change_text, price_change_percent_text));
-    content.push_str(&format!("Volume: ${:.0}\n", app.market_data.volume_24h));
-    content.push_str(&format!("Market Cap: ${:.0}B", app.market_data.market_cap / 1e9));
+    #[test]
+    fn test_alert_selection_clamps_as_alerts_change() {
+        let mut app = App::new();
+        app.move_alert_selection(1);
+        assert_eq!((app.alerts_selected, app.selected_alert_id()), (0, None));
+
+        let ids: Vec<u64> = (0..3)
+            .map(|i| app.add_price_alert("BTCUSDT".to_string(), AlertType::PriceAbove(100.0 + i as f64), String::new()))
+            .collect();
+        app.move_alert_selection(5);
+        assert_eq!(app.selected_alert_id(), Some(ids[2]));
+        app.move_alert_selection(-1);
+        assert_eq!(app.selected_alert_id(), Some(ids[1]));
+
+        app.toggle_selected_alert();
+        assert!(!app.price_alerts[1].is_active);
+        assert!(app.price_alerts[0].is_active && app.price_alerts[2].is_active);
+
+        // Removing the selected last row moves the selection up onto the new last row
+        app.move_alert_selection(1);
+        app.remove_selected_alert();
+        assert_eq!(app.alerts_selected, 1);
+        assert_eq!(app.selected_alert_id(), Some(ids[1]));
+
+        // Removing a row by id elsewhere clamps too
+        app.remove_price_alert(ids[0]);
+        assert_eq!(app.selected_alert_id(), Some(ids[1]));
+        app.remove_selected_alert();
+        assert_eq!((app.alerts_selected, app.selected_alert_id()), (0, None));
+        app.move_alert_selection(-3);
+        assert_eq!(app.alerts_selected, 0);
+    }
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Summary"))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_price_alerts_fire_on_price_updates() {
+        let mut app = App::with_seed(7);
+        app.market_data.current_price = 100.0;
+        app.price_decimals.insert("BTC".to_string(), 3);
+        app.add_price_alert("BTCUSDT".to_string(), AlertType::PriceAbove(105.0), "breakout".to_string());
+
+        app.apply_live_price(104.0);
+        assert!(app.price_alerts[0].triggered_at.is_none());
+        assert_eq!(app.get_active_alerts_count(), 1);
+
+        app.apply_live_price(106.5);
+        let alert = &app.price_alerts[0];
+        assert!(alert.triggered_at.is_some());
+        assert_eq!(alert.triggered_count, 1);
+        assert!(!alert.is_active);
+        assert!(app.real_time_data.iter().any(|m| m == "🚨 ALERT TRIGGERED: breakout - Price: $106.500"));
+
+        // The simulated update loop checks alerts too
+        app.add_price_alert("BTCUSDT".to_string(), AlertType::PriceAbove(0.0), "any price".to_string());
+        app.update_market_data();
+        assert!(app.price_alerts[1].triggered_at.is_some());
+        assert_eq!(app.get_triggered_alerts_count(), 2);
+    }
 
-    f.render_widget(paragraph, area);
-}
+    #[test]
+    fn test_percentage_alert_ignores_zero_previous_price() {
+        let mut alert = PriceAlert::new(1, "BTCUSDT".to_string(), AlertType::PercentageChange(5.0), String::new());
+        assert!(!alert.check_trigger(100.0, 0.0, 0.0));
+        // Measured from 98, the first usable price
+        assert!(!alert.check_trigger(100.0, 98.0, 0.0));
+        assert!(alert.check_trigger(103.0, 100.0, 0.0));
+        assert!(alert.triggered_at.is_some());
+    }
 
-fn draw_order_form(f: &mut Frame, app: &App, area: Rect) {
-    let content = format!(
-        "Order Form\n\n\
-        Side: {:?}\n\
-        Price: ${}\n\
-        Quantity: {}\n\
-        Type: {:?}\n\
-        Token: {}\n\
-        Status: {}\n\n\
-        Controls:\n\
-        b/s - Change side\n\
-        g/f/d - Change type\n\
-        Enter - Submit order",
-        app.order_input.side,
-        app.order_input.price,
-        app.order_input.quantity,
-        app.order_input.order_type,
-        app.order_input.token_id,
-        if app.order_input.active { "ACTIVE" } else { "Inactive" }
-    );
+    #[test]
+    fn test_percentage_alert_fires_on_cumulative_move() {
+        // Forty 0.25% steps: no single tick comes near 5%, but together they pass it
+        let mut alert = PriceAlert::new(1, "BTCUSDT".to_string(), AlertType::PercentageChange(5.0), String::new())
+            .with_behavior(AlertBehavior::Repeating { cooldown: Duration::ZERO })
+            .with_reference_price(100.0);
+        let mut price = 100.0;
+        let mut fired_at = Vec::new();
+        for step in 1..=40 {
+            let previous = price;
+            price *= 1.0025;
+            if alert.check_trigger(price, previous, 0.0) {
+                fired_at.push(step);
+            }
+        }
+        // 1.0025^20 is the first step past 5%; firing moves the reference
+        // there, so the next 5% takes another 20 steps
+        assert_eq!(fired_at, vec![20, 40]);
+        assert_eq!(alert.reference_price, Some(price));
+
+        // Drifting down counts too, and apps start the reference at the market price
+        let mut app = App::with_seed(7);
+        app.market_data.current_price = 100.0;
+        app.add_price_alert("BTCUSDT".to_string(), AlertType::PercentageChange(2.0), "slide".to_string());
+        for price in [99.5, 99.0, 98.6, 97.9] {
+            app.apply_live_price(price);
+        }
+        assert_eq!(app.price_alerts[0].triggered_count, 1);
+    }
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Place Order"))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_book_alerts_on_crafted_books() {
+        let book = OrderBook::new();
+        book.add_order(OrderSide::Bid, 99.0, 1.0, 1);
+        book.add_order(OrderSide::Bid, 98.0, 2.0, 2);
+        book.add_order(OrderSide::Bid, 97.0, 5.0, 3);
+        book.add_order(OrderSide::Ask, 101.0, 0.5, 4);
+        let metrics = BookMetrics::from_book(&book, 3);
+        assert_eq!((metrics.spread, metrics.mid_price), (Some(2.0), Some(100.0)));
+        assert_eq!(metrics.liquidity(OrderSide::Bid, 2), 3.0);
+        assert_eq!(metrics.liquidity(OrderSide::Ask, 3), 0.5);
+
+        let alert = |alert_type| PriceAlert::new(1, "BTC".to_string(), alert_type, String::new());
+        let mut wide = alert(AlertType::SpreadAbove(1.5));
+        let mut tight = alert(AlertType::SpreadAbove(2.0));
+        assert!(!tight.check_trigger_with_book(100.0, 100.0, 0.0, &metrics));
+        // Without a book, book alerts stay quiet
+        assert!(!wide.check_trigger(100.0, 100.0, 0.0));
+        assert!(wide.check_trigger_with_book(100.0, 100.0, 0.0, &metrics));
+
+        let mut thin_bids = alert(AlertType::LiquidityBelow { side: OrderSide::Bid, levels: 2, qty: 3.5 });
+        let mut deep_bids = alert(AlertType::LiquidityBelow { side: OrderSide::Bid, levels: 3, qty: 3.5 });
+        assert!(thin_bids.check_trigger_with_book(100.0, 100.0, 0.0, &metrics));
+        assert!(!deep_bids.check_trigger_with_book(100.0, 100.0, 0.0, &metrics));
+        let mut empty_asks = alert(AlertType::LiquidityBelow { side: OrderSide::Ask, levels: 1, qty: 0.1 });
+        assert!(!empty_asks.check_trigger_with_book(100.0, 100.0, 0.0, &metrics));
+        assert!(empty_asks.check_trigger_with_book(100.0, 100.0, 0.0, &BookMetrics::default()));
+
+        // The mid crosses 100.5 once the best ask moves up and the bids follow
+        let mut mid = alert(AlertType::MidPriceCross(100.5));
+        assert!(!mid.check_trigger_with_book(100.0, 100.0, 0.0, &metrics));
+        book.add_order(OrderSide::Bid, 100.5, 1.0, 5);
+        let moved = BookMetrics::from_book(&book, 3);
+        assert_eq!(moved.mid_price, Some(100.75));
+        assert!(mid.check_trigger_with_book(100.0, 100.0, 0.0, &moved));
+        assert_eq!(mid.last_mid, Some(100.75));
+    }
 
-    f.render_widget(paragraph, area);
-}
+    #[test]
+    fn test_book_alert_commands_check_the_selected_book() {
+        let mut app = App::with_seed(7);
+        app.price_alerts.clear();
+        app.order_book = OrderBook::new();
+        app.order_book.add_order(OrderSide::Bid, 99.0, 1.0, 1);
+        let best_ask = app.order_book.add_order(OrderSide::Ask, 101.0, 4.0, 2);
+
+        let registry = app.commands.clone();
+        registry.execute(&mut app, "alert spread 5").unwrap();
+        registry.execute(&mut app, "alert mid 100.5 mid up").unwrap();
+        registry.execute(&mut app, "alert liq ask 10 2.5").unwrap();
+        registry.execute(&mut app, "alert liq bid 2 2.5 --repeat 60s thin bids").unwrap();
+        registry.execute(&mut app, "alert liq middle 2 2.5").unwrap();
+        assert_eq!(app.real_time_data.back().unwrap(), "Invalid side 'middle': use bid or ask");
+        registry.execute(&mut app, "alert liq bid 0 2.5").unwrap();
+        assert_eq!(app.real_time_data.back().unwrap(), "Invalid level count");
+        let types: Vec<AlertType> = app.price_alerts.iter().map(|alert| alert.alert_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                AlertType::SpreadAbove(5.0),
+                AlertType::MidPriceCross(100.5),
+                AlertType::LiquidityBelow { side: OrderSide::Ask, levels: 10, qty: 2.5 },
+                AlertType::LiquidityBelow { side: OrderSide::Bid, levels: 2, qty: 2.5 },
+            ]
+        );
+        assert_eq!(app.price_alerts[3].message, "thin bids");
+        assert_eq!(app.price_alerts[3].behavior, AlertBehavior::Repeating { cooldown: Duration::from_secs(60) });
+
+        // Only the thin bid side fires; then the book widens and the mid moves
+        app.check_all_alerts(100.0, 100.0, 0.0);
+        let fired: Vec<u32> = app.price_alerts.iter().map(|alert| alert.triggered_count).collect();
+        assert_eq!(fired, vec![0, 0, 0, 1]);
+        app.order_book.add_order(OrderSide::Ask, 110.0, 1.0, 3);
+        app.order_book.remove_order(best_ask);
+        app.check_all_alerts(100.0, 100.0, 0.0);
+        let fired: Vec<u32> = app.price_alerts.iter().map(|alert| alert.triggered_count).collect();
+        assert_eq!(fired, vec![1, 1, 1, 1]);
+    }
 
-fn draw_market_details(f: &mut Frame, app: &App, area: Rect) {
-    let content = format!(
-        "Market Details\n\n\
-        High 24h: ${:.2}\n\
-        Low 24h: ${:.2}\n\
-        Open: ${:.2}\n\
-        Previous Close: ${:.2}\n\
-        Bid Size: {:.2}\n\
-        Ask Size: {:.2}\n\
-        Spread: ${:.4}\n\
-        Spread %: {:.2}%",
-        app.market_data.high_24h,
-        app.market_data.low_24h,
-        app.market_data.current_price - app.market_data.price_change,
-        app.market_data.current_price - app.market_data.price_change,
-        app.order_book.get_best_bid().map_or(0.0, |_| 10.0),
-        app.order_book.get_best_ask().map_or(0.0, |_| 12.0),
-        app.order_book.get_spread().unwrap_or(0.0),
-        app.order_book.get_spread().map_or(0.0, |s| (s / app.market_data.current_price) * 100.0)
-    );
+    #[test]
+    fn test_whale_alert_fires_once_on_one_sided_flow() {
+        let mut app = App::with_seed(7);
+        app.alert_sound_enabled = false;
+        app.price_alerts.clear();
+        let registry = app.commands.clone();
+        registry.execute(&mut app, "alert whale 500000 5").unwrap();
+        registry.execute(&mut app, "alert whale 500000 big print").unwrap();
+        registry.execute(&mut app, "alert whale -3").unwrap();
+        assert_eq!(app.real_time_data.back().unwrap(), "Invalid notional");
+        let types: Vec<AlertType> = app.price_alerts.iter().map(|alert| alert.alert_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                AlertType::WhaleTrade { notional: 500000.0, window_secs: 5 },
+                AlertType::WhaleTrade { notional: 500000.0, window_secs: 0 },
+            ]
+        );
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Details"))
-        .wrap(Wrap { trim: true });
+        let trade = |timestamp: u64, side: OrderSide, price: f64, quantity: f64| Trade {
+            bid_order_id: 0,
+            ask_order_id: 0,
+            price,
+            quantity,
+            timestamp,
+            aggressor_side: side,
+            maker_fee: 0.0,
+            taker_fee: 0.0,
+        };
+        app.price_decimals.insert("BTC".to_string(), 3);
+        // 800k changes hands in the first 3s, but buys only pass 500k on the last
+        let script = [
+            trade(1_000, OrderSide::Bid, 100.0, 2000.0),
+            trade(2_000, OrderSide::Ask, 100.0, 3000.0),
+            trade(3_000, OrderSide::Bid, 101.0, 2000.0),
+            trade(4_000, OrderSide::Bid, 102.0, 2000.0),
+            trade(5_000, OrderSide::Bid, 102.0, 1000.0),
+        ];
+        let mut fired = Vec::new();
+        for trade in &script {
+            app.check_trade_alerts(std::slice::from_ref(trade));
+            fired.push(app.price_alerts[0].triggered_count);
+        }
+        assert_eq!(fired, vec![0, 0, 0, 1, 1]);
+        assert_eq!(app.price_alerts[1].triggered_count, 0);
+        assert_eq!(app.alert_history.len(), 1);
+        assert_eq!(app.alert_history[0].price, 101.0);
+        assert_eq!(
+            app.real_time_data.back().unwrap(),
+            "🐋 WHALE ALERT: whale 500000 5s - buys $606000.00 over 3 trade(s) in 5s, VWAP $101.000"
+        );
 
-    f.render_widget(paragraph, area);
-}
+        app.check_trade_alerts(&[trade(6_000, OrderSide::Ask, 100.0, 6000.0)]);
+        assert_eq!(app.price_alerts[1].triggered_count, 1);
+        assert!(app.real_time_data.back().unwrap().contains("big print - sells $600000.00 over 1 trade(s) in 0s"));
+    }
 
-fn draw_order_status(f: &mut Frame, app: &App, area: Rect) {
-    let content = format!(
-        "Order Status\n\n\
-        Total Orders: {}\n\
-        Pending: {}\n\
-        Filled: {}\n\
-        Cancelled: {}\n\
-        Last Order: {}\n\
-        Success Rate: {:.1}%",
-        app.order_history.len(),
-        app.order_history.iter().filter(|o| o.status == "Pending").count(),
-        app.order_history.iter().filter(|o| o.status == "Filled").count(),
-        app.order_history.iter().filter(|o| o.status == "Cancelled").count(),
-        app.order_history.back().map_or("None".to_string(), |o| o.timestamp.format("%H:%M:%S").to_string()),
-        if app.order_history.is_empty() { 0.0 } else { 
-            (app.order_history.iter().filter(|o| o.status == "Filled").count() as f64 / app.order_history.len() as f64) * 100.0 
+    #[test]
+    fn test_alert_behaviors_on_oscillating_price() {
+        // Ten-second ticks oscillating around 27000: above on 4 stretches
+        let prices = [26900.0, 27100.0, 27200.0, 26950.0, 27050.0, 26800.0, 27300.0, 27400.0, 27500.0, 26990.0, 27010.0];
+        let start = chrono::Utc::now();
+        let run = |behavior: AlertBehavior| {
+            let mut alert = PriceAlert::new(1, "BTCUSDT".to_string(), AlertType::PriceAbove(27000.0), String::new())
+                .with_behavior(behavior);
+            let mut previous = prices[0];
+            for (i, price) in prices.iter().enumerate() {
+                alert.check_trigger_at(*price, previous, 0.0, None, start + chrono::Duration::seconds(10 * i as i64));
+                previous = *price;
+            }
+            alert
+        };
+
+        let one_shot = run(AlertBehavior::OneShot);
+        assert_eq!(one_shot.triggered_count, 1);
+        assert!(!one_shot.is_active);
+
+        // Every tick above the level fires
+        let repeating = run(AlertBehavior::Repeating { cooldown: Duration::ZERO });
+        assert_eq!(repeating.triggered_count, 7);
+        assert!(repeating.is_active);
+
+        // Ticks at 10, 40, 60 and 100s; 20s (too soon), 70s and 80s are within 30s of a firing
+        let cooldown = run(AlertBehavior::Repeating { cooldown: Duration::from_secs(30) });
+        assert_eq!(cooldown.triggered_count, 4);
+        assert_eq!(cooldown.triggered_at, Some(start + chrono::Duration::seconds(100)));
+
+        // Once per excursion above the level
+        let rearm = run(AlertBehavior::ReArmOnCross);
+        assert_eq!(rearm.triggered_count, 4);
+        assert!(rearm.is_active);
+        assert!(!rearm.armed);
+    }
+
+    #[test]
+    fn test_feed_is_stale_after_three_refresh_intervals() {
+        let mut app = App::new();
+        app.refresh_interval = Duration::from_secs(2);
+        let now = chrono::Utc::now();
+
+        app.real_time_service.last_update = now - chrono::Duration::seconds(1);
+        assert!(!app.feed_is_stale(now));
+        app.real_time_service.last_update = now - chrono::Duration::seconds(6);
+        assert!(!app.feed_is_stale(now));
+        app.real_time_service.last_update = now - chrono::Duration::seconds(7);
+        assert!(app.feed_is_stale(now));
+        // A clock that went backwards is not staleness
+        app.real_time_service.last_update = now + chrono::Duration::seconds(30);
+        assert!(!app.feed_is_stale(now));
+
+        // In real data mode only the Binance stream counts
+        app.use_real_data = true;
+        app.real_time_service.last_update = now;
+        app.binance_ws.last_message = now - chrono::Duration::seconds(60);
+        assert!(app.feed_is_stale(now));
+        app.binance_ws.record_message("btcusdt@trade");
+        assert!(!app.feed_is_stale(chrono::Utc::now()));
+    }
+
+    #[test]
+    fn test_binance_connect_requires_real_data() {
+        let mut app = App::new();
+        assert!(!app.use_real_data);
+        app.simulate_binance_connection();
+        assert_eq!(app.real_time_data.back().unwrap(), "Real data is off: enable real data with 'w' first");
+        assert!(app.market_stream.is_none());
+        assert!(!app.binance_ws.is_connected);
+    }
+
+    #[test]
+    fn test_market_stream_task_feeds_the_app_book() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        runtime.spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for update in [
+                r#"{"stream":"btcusdt@depth20@100ms","data":{"lastUpdateId":7,"bids":[["100.5","2.0"]],"asks":[["101.0","1.5"]]}}"#,
+                r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":2,"s":"BTCUSDT","t":1,"p":"101.0","q":"0.5","T":2,"m":false}}"#,
+            ] {
+                socket.send(Message::Text(update.to_string())).await.unwrap();
+            }
+            let _ = socket.next().await;
+        });
+
+        let mut app = App::with_seed(7);
+        app.runtime = Some(runtime.handle().clone());
+        app.use_real_data = true;
+        let mut client = BinanceWebSocketClient::new("BTCUSDT".to_string());
+        client.base_url = url;
+        app.connect_market_stream(client);
+
+        // The UI side only polls: each drain applies whatever the task has sent
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while app.order_book.recent_trades(1).is_empty() && std::time::Instant::now() < deadline {
+            app.drain_market_stream_events();
+            std::thread::sleep(Duration::from_millis(10));
         }
-    );
+        assert!(app.binance_ws.is_connected);
+        assert_eq!(app.order_book.get_best_bid(), Some(100.5));
+        assert_eq!(app.order_book.get_best_ask(), Some(101.0));
+        assert_eq!(app.order_book.recent_trades(1)[0].price, 101.0);
+
+        // Leaving real data mode stops the task
+        app.toggle_real_data();
+        assert!(app.market_stream.is_none());
+        assert!(!app.use_real_data);
+    }
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Status"))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_real_data_toggle_streams_the_selected_coin() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+        use tokio_tungstenite::tungstenite::Message;
+
+        // A fake feed that reports which streams each connection asked for
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (requested_tx, requested) = std::sync::mpsc::channel::<String>();
+        runtime.spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let requested_tx = requested_tx.clone();
+                tokio::spawn(async move {
+                    // The callback's error type is tungstenite's, large or not
+                    #[allow(clippy::result_large_err)]
+                    let record = |request: &Request, response: Response| {
+                        let _ = requested_tx.send(request.uri().to_string());
+                        Ok(response)
+                    };
+                    let mut socket = tokio_tungstenite::accept_hdr_async(stream, record).await.unwrap();
+                    let depth = r#"{"stream":"x@depth20@100ms","data":{"lastUpdateId":1,"bids":[["10.0","1.0"]],"asks":[["11.0","1.0"]]}}"#;
+                    socket.send(Message::Text(depth.to_string())).await.unwrap();
+                    while socket.next().await.is_some_and(|message| message.is_ok()) {}
+                });
+            }
+        });
+
+        let mut app = App::with_seed(7);
+        app.runtime = Some(runtime.handle().clone());
+        app.market_stream_url = url;
+        app.toggle_real_data();
+        assert!(app.use_real_data);
+        assert!(app.market_stream.is_some());
+        let first = requested.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(first.contains("btcusdt@depth20"), "{}", first);
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !app.binance_ws.is_connected && std::time::Instant::now() < deadline {
+            app.drain_market_stream_events();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(app.binance_ws.is_connected);
 
-    f.render_widget(paragraph, area);
-}
+        // Switching coins moves the stream to the new market
+        app.select_coin_by_index(1);
+        let second = requested.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(second.contains("ethusdt@depth20"), "{}", second);
+        assert_eq!(app.real_time_data.back().unwrap(), "🔌 Connecting to the Binance market stream for ETHUSDT");
 
-fn draw_price_chart(f: &mut Frame, app: &mut App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Header
-            Constraint::Min(0),     // Chart
-        ])
-        .split(area);
+        app.toggle_real_data();
+        assert!(app.market_stream.is_none());
+        assert!(!app.binance_ws.is_connected);
+    }
 
-    // Header with timeframe and current price
-    let header = format!(
-        "📊 BTC/USDT Price Chart - {} | Current: ${:.2} | Change: {:.2} ({:.2}%)",
-        app.selected_timeframe.as_str(),
-        app.market_data.current_price,
-        app.market_data.price_change,
-        app.market_data.price_change_percent
-    );
-    
-    let header_para = Paragraph::new(header)
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-        .alignment(ratatui::layout::Alignment::Center);
+    fn temp_alert_file(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("order-book-test-{}", std::process::id()))
+            .join(format!("{}.json", name))
+    }
+
+    #[test]
+    fn test_command_history_survives_a_restart() {
+        let path = temp_alert_file("history");
+        let mut app = App::new();
+        app.history_path = Some(path.clone());
+        for command in ["bars", "coin eth", "frobnicate"] {
+            app.user_command.set(command);
+            app.execute_user_command();
+        }
+        assert_eq!(app.selected_coin_index, 1);
+        app.shutdown();
+
+        let mut restored = App::new();
+        restored.history_path = Some(path.clone());
+        restored.restore_command_history();
+        assert_eq!(restored.user_command.history().collect::<Vec<_>>(), vec!["bars", "coin eth", "frobnicate"]);
+        restored.user_command.history_previous();
+        assert_eq!(restored.user_command.text(), "frobnicate");
+        std::fs::remove_file(&path).unwrap();
+
+        // Without a file there is simply no history yet
+        restored.restore_command_history();
+        assert_eq!(restored.user_command.history().count(), 3);
+        let mut fresh = App::new();
+        fresh.history_path = Some(path);
+        fresh.restore_command_history();
+        assert_eq!(fresh.user_command.history().count(), 0);
+    }
 
-    f.render_widget(header_para, chunks[0]);
+    #[test]
+    fn test_settings_changes_are_saved_and_restored() {
+        let path = temp_alert_file("config");
+        let mut app = App::new();
+        app.config_path = Some(path.clone());
+        assert_eq!(app.config(), AppConfig::default());
+
+        app.settings_selected = Setting::ALL.iter().position(|s| *s == Setting::RefreshInterval).unwrap();
+        app.change_selected_setting(true);
+        assert_eq!(app.setting_value(Setting::RefreshInterval), "5s");
+        app.change_selected_setting(false);
+        app.change_selected_setting(false);
+        assert_eq!(app.refresh_interval, Duration::from_secs(1));
+        app.move_settings_selection(10);
+        assert_eq!(app.selected_setting(), Setting::Theme);
+        app.move_settings_selection(-1);
+        assert_eq!(app.selected_setting(), Setting::DefaultCoin);
+        app.change_selected_setting(false);
+        assert_eq!(app.default_coin, "SOL");
+        app.move_settings_selection(-10);
+        app.change_selected_setting(true);
+        assert!(!app.auto_refresh);
+
+        let mut restored = App::new();
+        restored.config_path = Some(path.clone());
+        restored.restore_config();
+        assert!(!restored.auto_refresh);
+        assert_eq!(restored.refresh_interval, Duration::from_secs(1));
+        assert_eq!(restored.default_coin, "SOL");
+        assert_eq!(restored.selected_coin_index, 2);
+
+        // A bad file leaves the settings alone
+        std::fs::write(&path, "default_coin = 7").unwrap();
+        let mut fresh = App::new();
+        fresh.config_path = Some(path.clone());
+        fresh.restore_config();
+        assert_eq!(fresh.config(), AppConfig::default());
+        assert!(fresh.real_time_data.iter().any(|line| line.contains("Invalid settings file")));
+
+        let unknown = AppConfig {
+            default_coin: "DOGE".to_string(),
+            refresh_interval: 0.0,
+            seed: Some(5),
+            simulator: crate::simulator::SimulatorParams { min_order_size: 0.0, ..Default::default() },
+            ..AppConfig::default()
+        };
+        fresh.apply_config(&unknown);
+        assert_eq!(fresh.selected_coin_index, 0);
+        assert_eq!(fresh.refresh_interval, Duration::from_secs(2));
+        assert_eq!(fresh.simulator.params, crate::simulator::SimulatorParams::default());
+        assert!(fresh.real_time_data.iter().any(|line| line.starts_with("⚠️ Settings: simulator order sizes")));
+        // The seed is kept for saving; it only seeds apps created afterwards
+        assert_eq!(fresh.config().seed, Some(5));
+        std::fs::remove_file(&path).unwrap();
+    }
 
-    // Chart content using terminal chart backend
-    if app.candlestick_data.len() < 2 {
-        let content = "Insufficient data for chart";
-        let paragraph = Paragraph::new(content)
-            .block(Block::default().borders(Borders::ALL).title("Chart"))
-            .wrap(Wrap { trim: true });
-        
-        f.render_widget(paragraph, chunks[1]);
-        return;
+    #[test]
+    fn test_theme_command_setting_and_custom_themes() {
+        let path = temp_alert_file("theme-config");
+        let mut app = App::new();
+        app.config_path = Some(path.clone());
+        assert_eq!(app.theme, Theme::dark());
+
+        app.user_command.set("theme Light");
+        app.execute_user_command();
+        assert_eq!(app.theme, Theme::light());
+        app.user_command.set("theme sepia");
+        app.execute_user_command();
+        assert_eq!(app.theme.name, "light");
+        assert!(app.real_time_data.back().unwrap().starts_with("Unknown theme 'sepia'"));
+        assert_eq!(AppConfig::load(&path).unwrap().theme, "light");
+
+        // The chart draws with the theme's colors
+        let mut chart = TerminalChartBackend::new(30, 11);
+        chart.draw_candlestick_chart(&app.candlestick_data, 100.0, ChartOverlay::MovingAverages, &app.theme).unwrap();
+        let colors: Vec<Option<Color>> =
+            chart.lines().iter().flat_map(|line| line.spans.iter().map(|span| span.style.fg)).collect();
+        assert!(colors.contains(&Some(app.theme.grid)));
+        assert!(!colors.contains(&Some(Theme::dark().grid)));
+
+        // Custom themes come from the config file; broken ones are skipped
+        let config = AppConfig {
+            theme: "Mono".to_string(),
+            themes: vec![
+                CustomTheme { name: "mono".to_string(), bid: Some("white".to_string()), ..CustomTheme::default() },
+                CustomTheme { name: "broken".to_string(), ask: Some("nope".to_string()), ..CustomTheme::default() },
+            ],
+            ..AppConfig::default()
+        };
+        app.apply_config(&config);
+        assert_eq!(app.theme.name, "mono");
+        assert_eq!(app.theme.bid, Color::White);
+        assert_eq!(app.themes().len(), 4);
+        assert!(app.real_time_data.iter().any(|line| line.contains("invalid color 'nope'")));
+
+        // The Settings tab cycles through every theme
+        app.settings_selected = Setting::ALL.len() - 1;
+        app.change_selected_setting(true);
+        assert_eq!(app.setting_value(Setting::Theme), "dark");
+        app.change_selected_setting(false);
+        assert_eq!(app.theme.name, "mono");
+        let saved = AppConfig::load(&path).unwrap();
+        assert_eq!((saved.theme.as_str(), saved.themes.len()), ("mono", 1));
+
+        app.user_command.set("theme hi");
+        app.complete_user_command();
+        assert_eq!(app.user_command.text(), "theme high-contrast ");
+        std::fs::remove_file(&path).unwrap();
     }
 
-    // Resize terminal chart to fit the area
-    let chart_width = chunks[1].width.saturating_sub(2) as u32;
-    let chart_height = chunks[1].height.saturating_sub(2) as u32;
-    
-    app.resize_terminal_chart(chart_width, chart_height);
+    #[test]
+    fn test_tab_and_table_row_hit_testing() {
+        let titles: Vec<String> = ["Book", "Trading", "📈"].iter().map(|title| title.to_string()).collect();
+        let tabs = Rect::new(10, 0, 40, 3);
+        // " Book │ Trading │ 📈 " starting inside the left border at column 11
+        assert_eq!(tab_at(tabs, &titles, 11, 1), Some(0));
+        assert_eq!(tab_at(tabs, &titles, 16, 1), Some(0));
+        assert_eq!(tab_at(tabs, &titles, 17, 1), None, "divider");
+        assert_eq!(tab_at(tabs, &titles, 18, 1), Some(1));
+        assert_eq!(tab_at(tabs, &titles, 26, 1), Some(1));
+        assert_eq!(tab_at(tabs, &titles, 28, 1), Some(2));
+        assert_eq!(tab_at(tabs, &titles, 31, 1), Some(2));
+        assert_eq!(tab_at(tabs, &titles, 32, 1), None);
+        assert_eq!(tab_at(tabs, &titles, 12, 0), None, "border");
+        assert_eq!(tab_at(tabs, &titles, 5, 1), None);
+
+        // Border on row 20, header on 21, rows 22-28, bottom border on 29
+        let table = Rect::new(0, 20, 30, 10);
+        assert_eq!(table_row_at(table, 0, 5, 22), Some(0));
+        assert_eq!(table_row_at(table, 0, 5, 28), Some(6));
+        assert_eq!(table_row_at(table, 4, 5, 23), Some(5));
+        assert_eq!(table_row_at(table, 0, 5, 21), None, "header");
+        assert_eq!(table_row_at(table, 0, 5, 29), None, "bottom border");
+        assert_eq!(table_row_at(table, 0, 0, 22), None, "left border");
+        assert_eq!(table_row_at(table, 0, 29, 22), None, "right border");
+        assert_eq!(table_row_at(table, 0, 5, 35), None);
+    }
 
-    // Update terminal chart with current data
-    let _ = app.terminal_chart.draw_candlestick_chart(
-        &app.candlestick_data, 
-        app.market_data.current_price
-    );
+    #[test]
+    fn test_clicks_and_wheel_use_the_last_layout() {
+        let mut app = App::new();
+        app.order_input.venue = OrderVenue::Local;
+        for _ in 0..3 {
+            app.order_input.price = "1".to_string();
+            app.order_input.quantity = "0.1".to_string();
+            app.submit_order();
+        }
+        app.layout = ScreenLayout {
+            tabs: Rect::new(0, 0, 100, 3),
+            left_panel: Rect::new(0, 3, 40, 30),
+            right_panel: Rect::new(40, 3, 60, 30),
+            orders_table: Some((Rect::new(0, 6, 40, 12), 0)),
+            ..ScreenLayout::default()
+        };
 
-    // Render the terminal chart
-    let chart_content = app.terminal_chart.render();
-    let paragraph = Paragraph::new(chart_content)
-        .block(Block::default().borders(Borders::ALL).title("Real-time Chart"))
-        .wrap(Wrap { trim: true });
+        // "Orders" starts at column 1 + (12 + 1) + (9 + 1) + (13 + 1) = 38
+        app.click_at(40, 1);
+        assert_eq!(app.selected_tab, 3);
+        app.click_at(10, 10);
+        assert_eq!(app.selected_order_row(), Some(2));
+        assert_eq!(app.selected_order_id.as_deref(), Some(app.order_history[0].order_id.as_str()));
+        // Past the last order, or off the table, nothing changes
+        app.click_at(10, 12);
+        app.click_at(60, 10);
+        assert_eq!(app.selected_order_row(), Some(2));
+
+        app.scroll_at(10, 10, -1);
+        assert_eq!(app.selected_order_row(), Some(1));
+        app.scroll_at(60, 10, 1);
+        assert_eq!(app.selected_order_row(), Some(1));
+
+        app.help_scroll = 0;
+        app.layout.help = Some(Rect::new(0, 3, 100, 30));
+        app.scroll_at(60, 10, 2);
+        assert_eq!(app.help_scroll, 2 * HELP_SCROLL_LINES as u16);
+        app.scroll_at(60, 10, -5);
+        assert_eq!(app.help_scroll, 0);
+        assert_eq!(app.selected_order_row(), Some(1));
+    }
 
-    f.render_widget(paragraph, chunks[1]);
-}
+    #[test]
+    fn test_watchlist_rows_sampling_and_open() {
+        let mut app = App::with_seed(7);
+        // Visiting each coin seeds its book
+        app.select_coin_by_index(1);
+        app.select_coin_by_index(2);
+        app.select_coin_by_index(0);
+
+        let rows = app.watchlist_rows();
+        assert_eq!(rows.iter().map(|row| row.symbol.as_str()).collect::<Vec<_>>(), ["BTC", "ETH", "SOL"]);
+        assert_eq!(rows[0].last_price, app.market_data.current_price);
+        assert_eq!(rows[1].best_bid, app.order_book_for("ETH").unwrap().get_best_bid());
+        assert!(rows.iter().all(|row| row.spread().is_some_and(|spread| spread > 0.0)));
+
+        app.sample_watchlist_mids();
+        app.sample_watchlist_mids();
+        let eth_mid = app.order_book_for("ETH").unwrap().get_mid_price().unwrap();
+        assert_eq!(app.watchlist_mids["ETH"].samples().collect::<Vec<_>>(), vec![eth_mid, eth_mid]);
+        assert_eq!(app.watchlist_mids.len(), 3);
+
+        // The selection is a row of the sorted list, here the last by symbol
+        app.watchlist_sort = WatchlistSort::Symbol;
+        app.move_watchlist_selection(5);
+        assert_eq!(app.watchlist_selected, 2);
+        app.selected_tab = 7;
+        app.open_watchlist_selection();
+        assert_eq!(app.current_market, "SOL");
+        assert_eq!(app.selected_tab, 0);
+
+        app.cycle_watchlist_sort();
+        assert_eq!(app.watchlist_sort, WatchlistSort::LastPrice);
+        assert_eq!(app.real_time_data.back().unwrap(), "Watchlist sorted by price");
+    }
 
-fn draw_configuration(f: &mut Frame, app: &App, area: Rect) {
-    let content = format!(
-        "Configuration\n\n\
-        API Endpoint: {}\n\
-        Chain ID: 137 (Polygon)\n\
-        Signature Type: {:?}\n\
-        Auto-refresh: Enabled\n\
-        Notifications: Enabled\n\
-        Logging: Debug\n\
-        Theme: Dark\n\
-        Language: English\n\
-        Timezone: UTC",
-        if app.polymarket_client.is_some() { "https://clob.polymarket.com" } else { "Not configured" },
-        PolymarketSignatureType::EMAIL_MAGIC
-    );
+    #[test]
+    fn test_sample_book_notional() {
+        let mut app = App::with_seed(7);
+        app.market_data.current_price = 100.0;
+        app.add_sample_orders();
+        let (bids, asks) = app.order_book.total_notional();
+        // 20 levels a side, half a dollar apart from 0.50 to 10.00 off 100
+        assert!((bids - 17669.7762).abs() < 1e-3, "{}", bids);
+        assert!((asks - 17473.8737).abs() < 1e-3, "{}", asks);
+    }
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Config"))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_help_opens_at_the_section_in_context_and_clamps() {
+        let mut app = App::new();
+        let lines = help_lines(&app, 100);
+        let order_section = lines.iter().position(|line| line == HELP_ORDER_INPUT_SECTION).unwrap() as u16;
+        let alerts_section = lines.iter().position(|line| line == HELP_ALERTS_SECTION).unwrap() as u16;
+        assert!(0 < order_section && order_section < alerts_section);
+        assert_eq!(app.help_section_offset("🪙 NO SUCH SECTION"), None);
+
+        app.toggle_help();
+        assert_eq!(app.help_scroll, 0);
+        app.toggle_help();
+        app.selected_tab = 5;
+        app.toggle_help();
+        assert_eq!(app.help_scroll, alerts_section);
+        app.toggle_help();
+        app.order_input.active = true;
+        app.toggle_help();
+        assert_eq!(app.help_scroll, order_section);
+
+        // Drawn 30 rows high, the help stops once its last line is in view
+        app.layout.help = Some(Rect::new(0, 3, 100, 30));
+        let max_scroll = lines.len() as u16 - 28;
+        assert_eq!(app.help_max_scroll(), max_scroll);
+        assert_eq!(app.help_page(), 27);
+        app.scroll_help(1000);
+        assert_eq!(app.help_scroll, max_scroll);
+        app.scroll_help(-1);
+        assert_eq!(app.help_scroll, max_scroll - 1);
+        app.scroll_help(-1000);
+        assert_eq!(app.help_scroll, 0);
+    }
 
-    f.render_widget(paragraph, area);
-}
+    #[test]
+    fn test_tab_completion_over_commands_and_coins() {
+        let mut app = App::new();
+        app.user_command.set("add");
+        app.complete_user_command();
+        assert_eq!(app.user_command.text(), "add_orders ");
+
+        app.user_command.set("coin s");
+        app.complete_user_command();
+        assert_eq!(app.user_command.text(), "coin SOL ");
+        app.execute_user_command();
+        assert_eq!(app.selected_coin_index, 2);
+
+        // Ambiguous words list their candidates
+        app.user_command.set("alert ");
+        app.complete_user_command();
+        assert_eq!(app.user_command.text(), "alert ");
+        assert_eq!(
+            app.real_time_data.back().unwrap(),
+            "above  below  change  cross  history  liq  list  load  mid  remove  save  spread  volume  whale"
+        );
+        // Only `<symbol>` arguments complete to coins
+        app.user_command.set("refresh ");
+        app.complete_user_command();
+        assert_eq!(app.user_command.text(), "refresh ");
+
+        app.user_command.set("coin DOGE");
+        app.execute_user_command();
+        assert_eq!(app.real_time_data.back().unwrap(), "Unknown coin: DOGE");
+    }
 
-fn draw_bottom_bar(f: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(30),  // User commands
-            Constraint::Percentage(25),  // Navigation help
-            Constraint::Percentage(20),  // Current coin info
-            Constraint::Percentage(25),  // Real-time updates
-        ])
-        .split(area);
+    #[test]
+    fn test_alerts_round_trip_through_file() {
+        let path = temp_alert_file("round-trip");
+        let mut app = App::new();
+        app.alerts_path = Some(path.clone());
+        let above = app.add_price_alert("BTCUSDT".to_string(), AlertType::PriceAbove(27000.0), "breakout".to_string());
+        let removed = app.add_price_alert("BTCUSDT".to_string(), AlertType::PriceBelow(20000.0), "gone".to_string());
+        let rearm = app.add_price_alert_with(
+            "ETHUSDT".to_string(),
+            AlertType::PriceCross(3000.0),
+            "eth level".to_string(),
+            AlertBehavior::ReArmOnCross,
+        );
+        app.add_price_alert_with(
+            "BTCUSDT".to_string(),
+            AlertType::VolumeSpike(5e9),
+            "volume".to_string(),
+            AlertBehavior::Repeating { cooldown: Duration::from_secs(90) },
+        );
+        app.remove_price_alert(removed);
+        // Fires the 27000 alert, then only the ETH one, which disarms it
+        app.check_all_alerts(27100.0, 26900.0, 0.0);
+        app.check_all_alerts(3010.0, 2990.0, 0.0);
+
+        let mut restored = App::new();
+        restored.alerts_path = Some(path.clone());
+        restored.restore_alerts();
+        assert_eq!(restored.price_alerts.len(), 3);
+        assert_eq!(restored.next_alert_id, 5);
+
+        let fired = &restored.price_alerts[0];
+        assert_eq!(fired.id, above);
+        assert!(!fired.is_active);
+        assert_eq!(fired.triggered_count, 1);
+        assert_eq!(fired.triggered_at, app.price_alerts[0].triggered_at);
+        assert_eq!(fired.created_at, app.price_alerts[0].created_at);
+
+        let crossed = &restored.price_alerts[1];
+        assert_eq!(crossed.id, rearm);
+        assert_eq!(crossed.behavior, AlertBehavior::ReArmOnCross);
+        assert!(crossed.is_active && !crossed.armed);
+        assert_eq!(crossed.symbol, "ETHUSDT");
+        assert_eq!(restored.price_alerts[2].behavior, AlertBehavior::Repeating { cooldown: Duration::from_secs(90) });
+        // The two firings come back with them
+        assert_eq!(restored.alert_history, app.alert_history);
+        assert_eq!(restored.alert_history.len(), 2);
+
+        // The removed alert's id is not handed out again
+        assert_eq!(restored.add_price_alert("BTCUSDT".to_string(), AlertType::PriceAbove(1.0), String::new()), 5);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
 
-    // User commands area
-    let command_text = if app.user_command.is_empty() {
-        "Type commands here... (h for help)".to_string()
-    } else {
-        format!("Command: {}", app.user_command)
-    };
+    #[test]
+    fn test_alert_history_records_triggers_and_prunes() {
+        let mut app = App::new();
+        app.price_alerts.clear();
+        let cross = app.add_price_alert_with(
+            "BTC".to_string(),
+            AlertType::PriceCross(100.0),
+            "level".to_string(),
+            AlertBehavior::Repeating { cooldown: Duration::ZERO },
+        );
+        app.add_price_alert("BTC".to_string(), AlertType::PriceAbove(150.0), "high".to_string());
+
+        app.check_all_alerts(101.0, 99.0, 0.0);
+        let first = app.alert_history[0].clone();
+        assert_eq!((first.alert_id, first.price, first.trigger_count), (cross, 101.0, 1));
+        assert_eq!(first.message, "level");
+        assert_eq!(Some(first.triggered_at), app.price_alerts[0].triggered_at);
+        app.check_all_alerts(98.0, 101.0, 0.0);
+        assert_eq!(app.alert_history.len(), 2);
+        assert_eq!((app.alert_history[1].price, app.alert_history[1].trigger_count), (98.0, 2));
+
+        // Past the limit the oldest firings go, oldest first
+        app.set_alert_history_limit(3);
+        for price in [160.0, 99.0, 101.0] {
+            app.check_all_alerts(price, if price > 100.0 { 99.0 } else { 101.0 }, 0.0);
+        }
+        assert_eq!(app.alert_history.len(), 3);
+        let prices: Vec<f64> = app.alert_history.iter().map(|trigger| trigger.price).collect();
+        assert_eq!(prices, vec![160.0, 99.0, 101.0]);
+        assert_eq!(app.alert_history[0].message, "high");
+        app.set_alert_history_limit(1);
+        assert_eq!(app.alert_history.back().unwrap().trigger_count, 5);
+        assert_eq!(app.alert_history.len(), 1);
+
+        app.commands.clone().execute(&mut app, "alert history clear").unwrap();
+        assert!(app.alert_history.is_empty());
+        assert_eq!(app.real_time_data.back().unwrap(), "Alert history cleared");
+    }
 
-    let command_para = Paragraph::new(command_text)
-        .block(Block::default().borders(Borders::ALL).title("User Commands (Type here and press Enter)"))
-        .style(Style::default().fg(Color::Yellow));
+    #[test]
+    fn test_alert_file_errors_fall_back_to_empty_list() {
+        let path = temp_alert_file("corrupted");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let mut app = App::new();
+        app.alerts_path = Some(path.clone());
+        app.restore_alerts();
+        assert!(app.price_alerts.is_empty());
+        assert!(app.real_time_data.back().unwrap().contains("Corrupted alert file"));
+
+        app.alerts_path = Some(temp_alert_file("missing"));
+        app.restore_alerts();
+        assert!(app.price_alerts.is_empty());
+        assert!(app.real_time_data.back().unwrap().starts_with("No saved alerts at"));
+
+        // An explicit load that fails keeps the alerts already set
+        app.add_price_alert("BTCUSDT".to_string(), AlertType::PriceAbove(1.0), "keep".to_string());
+        assert!(app.load_alerts_from(&path).is_err());
+        assert_eq!(app.price_alerts.len(), 1);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
 
-    f.render_widget(command_para, chunks[0]);
+    #[test]
+    fn test_alert_save_and_load_commands() {
+        let path = temp_alert_file("commands");
+        let mut app = App::new();
+        app.user_command.set("alert save");
+        app.execute_user_command();
+        assert_eq!(app.real_time_data.back().unwrap(), "No alert file configured; give a path");
+
+        app.user_command.set("alert above 27000 --rearm");
+        app.execute_user_command();
+        app.user_command.set(&format!("alert save {}", path.display()));
+        app.execute_user_command();
+        assert!(app.real_time_data.back().unwrap().starts_with("💾 Saved 1 alerts"));
+
+        let mut other = App::new();
+        other.user_command.set(&format!("alert load {}", path.display()));
+        other.execute_user_command();
+        assert!(other.real_time_data.back().unwrap().starts_with("📂 Loaded 1 alerts"));
+        assert_eq!(other.price_alerts[0].behavior, AlertBehavior::ReArmOnCross);
+        assert_eq!(other.next_alert_id, 2);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
 
-    // Navigation help area
-    let help_text = format!(
-        "Tab: {} | F2-F8: Quick tabs | ?/H: Help | P/Space: Order input | M: Market data | R: Refresh | N/V: Coin switch | </>: Timeframe | L: Auto-refresh | W: Real data",
-        app.tabs[app.selected_tab]
-    );
+    #[test]
+    fn test_alert_command_flags() {
+        assert_eq!(
+            AlertBehavior::parse_flags(&["--repeat", "60s", "big", "move"]),
+            Ok((AlertBehavior::Repeating { cooldown: Duration::from_secs(60) }, vec!["big", "move"]))
+        );
+        assert_eq!(
+            AlertBehavior::parse_flags(&["--repeat", "5m"]),
+            Ok((AlertBehavior::Repeating { cooldown: Duration::from_secs(300) }, vec![]))
+        );
+        assert_eq!(AlertBehavior::parse_flags(&["--rearm"]), Ok((AlertBehavior::ReArmOnCross, vec![])));
+        assert_eq!(AlertBehavior::parse_flags(&["note"]), Ok((AlertBehavior::OneShot, vec!["note"])));
+        assert!(AlertBehavior::parse_flags(&["--repeat"]).is_err());
+        assert!(AlertBehavior::parse_flags(&["--repeat", "soon"]).is_err());
+        assert!(AlertBehavior::parse_flags(&["--repeat", "10d"]).is_err());
+
+        let mut app = App::new();
+        app.user_command.set("alert above 27000 --repeat 60s");
+        app.execute_user_command();
+        app.user_command.set("alert cross 26000 --rearm support");
+        app.execute_user_command();
+        assert_eq!(app.price_alerts.len(), 2);
+        assert_eq!(app.price_alerts[0].behavior, AlertBehavior::Repeating { cooldown: Duration::from_secs(60) });
+        assert_eq!(app.price_alerts[0].message, "above 27000");
+        assert_eq!(app.price_alerts[1].behavior, AlertBehavior::ReArmOnCross);
+        assert_eq!(app.price_alerts[1].message, "support");
+
+        app.user_command.set("alert below 25000 --repeat");
+        app.execute_user_command();
+        assert_eq!(app.price_alerts.len(), 2);
+        assert!(app.real_time_data.back().unwrap().starts_with("Usage: --repeat"));
+    }
 
-    let help_para = Paragraph::new(help_text)
-        .block(Block::default().borders(Borders::ALL).title("Navigation Help"))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_log_is_bounded_and_collapses_repeats() {
+        let mut app = App::new();
+        for i in 0..1000 {
+            app.log(format!("message {}", i));
+            assert!(app.real_time_data.len() <= REAL_TIME_LOG_CAPACITY);
+        }
+        assert_eq!(app.real_time_data.back().unwrap(), "message 999");
+
+        app.log("Connected");
+        app.log("Connected");
+        app.log("Connected".to_string());
+        assert_eq!(app.real_time_data.back().unwrap(), "Connected (x3)");
+        app.log("Disconnected");
+        app.log("Connected");
+        assert_eq!(app.real_time_data.back().unwrap(), "Connected");
+        assert_eq!(app.real_time_data.len(), REAL_TIME_LOG_CAPACITY);
+    }
 
-    f.render_widget(help_para, chunks[1]);
+    #[test]
+    fn test_record_trades() {
+        let mut app = App::new();
+        app.order_history.clear();
+        app.current_market = "BTC".to_string();
 
-    // Current coin info area
-    let selected_coin = &app.available_coins[app.selected_coin_index];
-    let change_percent = (selected_coin.change_24h / selected_coin.price) * 100.0;
-    let change_color = get_number_color(change_percent);
-    
-    let coin_text = format!(
-        "🪙 {} ({})\n${:.2} {:+.2}%\nVolume: ${:.0}M",
-        selected_coin.symbol,
-        selected_coin.name,
-        selected_coin.price,
-        change_percent,
-        selected_coin.volume_24h / 1e6
-    );
+        app.record_trades(&[
+            Trade { bid_order_id: 7, ask_order_id: 3, price: 100.5, quantity: 0.25, timestamp: 1, aggressor_side: OrderSide::Bid, maker_fee: 0.0, taker_fee: 0.0 },
+            Trade { bid_order_id: 9, ask_order_id: 12, price: 99.0, quantity: 1.5, timestamp: 2, aggressor_side: OrderSide::Ask, maker_fee: 0.0, taker_fee: 0.0 },
+        ]);
 
-    let coin_para = Paragraph::new(coin_text)
-        .block(Block::default().borders(Borders::ALL).title("Active Coin"))
-        .style(Style::default().fg(change_color))
-        .wrap(Wrap { trim: true });
+        assert_eq!(app.order_history.len(), 2);
+        let (buy, sell) = (&app.order_history[0], &app.order_history[1]);
+        assert_eq!((buy.side, buy.price, buy.quantity), (OrderSide::Bid, 100.5, 0.25));
+        assert_eq!((sell.side, sell.price, sell.quantity), (OrderSide::Ask, 99.0, 1.5));
+        assert!(app.order_history.iter().all(|r| r.status == "Filled" && r.venue == OrderVenue::Local));
+        assert_eq!(buy.order_id, "7-3");
+        assert_eq!(sell.order_id, "9-12");
+    }
 
-    f.render_widget(coin_para, chunks[2]);
+    #[test]
+    fn test_cancel_selected_order() {
+        let mut app = App::new();
+        let (_, asks) = app.order_book.get_market_depth(BOOK_DEPTH_LEVELS);
+        let best_ask = asks[0].0;
+        let level_size = app.order_book.get_level_orders(OrderSide::Ask, best_ask).len();
+
+        // Rows start at the highest ask, so the best ask sits just above the bids
+        app.book_cursor = Some(asks.len() - 1);
+        assert_eq!(app.selected_book_level(), Some((OrderSide::Ask, best_ask)));
+
+        let cancelled = app.cancel_selected_order().expect("an order should be cancelled");
+        assert_eq!(cancelled.price.as_f64(), best_ask);
+        assert!(app.order_book.get_order(cancelled.id).is_none());
+        assert_eq!(app.order_book.get_level_orders(OrderSide::Ask, best_ask).len(), level_size - 1);
+    }
 
-    // Real-time updates area with status
-    let status_color = if app.real_time_service.is_connected { Color::Green } else { Color::Red };
-    let status_text = format!(
-        "Status: {}\nTimeframe: {}\nAuto-refresh: {}\nUpdates: {}\nAlerts: {}",
-        app.real_time_service.connection_status,
-        app.selected_timeframe.as_str(),
-        if app.auto_refresh { "ON" } else { "OFF" },
-        app.real_time_data.len(),
-        app.get_active_alerts_count()
-    );
+    #[test]
+    fn test_cancel_selected_history_order() {
+        let mut app = App::new();
+        let best_bid = app.order_book.get_best_bid().unwrap();
+
+        app.order_input.venue = OrderVenue::Local;
+        app.order_input.side = PolymarketOrderSide::BUY;
+        app.order_input.price = format!("{}", best_bid - 1.0);
+        app.order_input.quantity = "0.5".to_string();
+        app.submit_order();
+
+        let resting_id: u64 = app.order_history.back().unwrap().order_id.parse().unwrap();
+        assert!(app.order_book.get_order(resting_id).is_some());
+
+        app.move_orders_cursor_down();
+        assert_eq!(app.selected_order_row(), Some(0));
+        app.cancel_selected_history_order();
+        assert_eq!(app.order_history.back().unwrap().status, "Cancelled");
+        assert!(app.order_book.get_order(resting_id).is_none());
+
+        // Binance rows need a configured client
+        app.binance_orders = None;
+        app.order_history.back_mut().unwrap().venue = OrderVenue::Binance;
+        app.order_history.back_mut().unwrap().status = "Pending".to_string();
+        app.cancel_selected_history_order();
+        assert_eq!(app.order_history.back().unwrap().status, "Pending");
+        assert!(app.real_time_data.back().unwrap().contains("not enabled"));
+    }
 
-    let updates_text = if app.real_time_data.is_empty() {
-        "No updates yet...".to_string()
-    } else {
-        app.real_time_data.iter().take(3).cloned().collect::<Vec<_>>().join("\n")
-    };
+    #[test]
+    fn test_binance_filter_violation_blocks_submission() {
+        let mut app = App::new();
+        app.binance_orders = Some(BinanceOrderClient::with_base_url("key".to_string(), "secret".to_string(), "http://127.0.0.1:9"));
+        app.symbol_filters.insert("BTCUSDT".to_string(), SymbolFilters {
+            symbol: "BTCUSDT".to_string(),
+            tick_size: 0.01,
+            min_price: 0.01,
+            max_price: 1_000_000.0,
+            step_size: 0.00001,
+            min_qty: 0.00001,
+            max_qty: 9000.0,
+            min_notional: 5.0,
+        });
+
+        app.order_input.venue = OrderVenue::Binance;
+        app.order_input.price = "50000.005".to_string();
+        app.order_input.quantity = "0.001".to_string();
+        assert!(matches!(app.binance_filter_check(), Some(Err(FilterViolation::PriceOffTick { .. }))));
+
+        let history_len = app.order_history.len();
+        app.submit_order();
+        assert_eq!(app.order_history.len(), history_len);
+        assert_eq!(app.order_input.price, "50000.005");
+        assert!(app.real_time_data.back().unwrap().contains("blocked"));
+
+        app.order_input.price = "50000.01".to_string();
+        assert_eq!(app.binance_filter_check(), Some(Ok(())));
+    }
 
-    let full_text = format!("{}\n\n{}", status_text, updates_text);
+    #[test]
+    fn test_binance_order_is_cancelled_on_the_pair_it_was_placed_on() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/api/v3/order"))
+                .and(query_param("symbol", "BTCUSDT"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(
+                    r#"{"symbol":"BTCUSDT","orderId":28,"orderListId":-1,"clientOrderId":"abc","transactTime":1,
+                        "price":"50000.00","origQty":"0.00100000","executedQty":"0.00000000","cummulativeQuoteQty":"0.00",
+                        "status":"NEW","timeInForce":"GTC","type":"LIMIT","side":"BUY","fills":[]}"#,
+                ))
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("DELETE"))
+                .and(path("/api/v3/order"))
+                .and(query_param("symbol", "BTCUSDT"))
+                .and(query_param("orderId", "28"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(
+                    r#"{"symbol":"BTCUSDT","origClientOrderId":"abc","orderId":28,"orderListId":-1,"clientOrderId":"c1",
+                        "price":"50000.00","origQty":"0.00100000","executedQty":"0.00000000","cummulativeQuoteQty":"0.00",
+                        "status":"CANCELED","timeInForce":"GTC","type":"LIMIT","side":"BUY"}"#,
+                ))
+                .expect(1)
+                .mount(&server)
+                .await;
+            server
+        });
+
+        // Without a settings file the market is "BTCUSDT", not a coin symbol
+        let mut app = App::new();
+        assert_eq!(app.current_market, "BTCUSDT");
+        app.runtime = Some(runtime.handle().clone());
+        app.binance_orders = Some(BinanceOrderClient::with_base_url("key".to_string(), "secret".to_string(), &server.uri()));
+        app.symbol_filters.insert("BTCUSDT".to_string(), SymbolFilters {
+            symbol: "BTCUSDT".to_string(),
+            tick_size: 0.01,
+            min_price: 0.01,
+            max_price: 1_000_000.0,
+            step_size: 0.00001,
+            min_qty: 0.00001,
+            max_qty: 9000.0,
+            min_notional: 5.0,
+        });
+        app.order_input.venue = OrderVenue::Binance;
+        app.order_input.price = "50000".to_string();
+        app.order_input.quantity = "0.001".to_string();
+        app.submit_order();
+        app.wait_for_requests(Duration::from_secs(5));
+
+        let record = app.order_history.back().unwrap();
+        assert_eq!(record.order_id, "28");
+        assert_eq!(record.symbol, "BTCUSDT");
+        app.selected_order_id = Some("28".to_string());
+        app.cancel_selected_history_order();
+        app.wait_for_requests(Duration::from_secs(5));
+        assert_eq!(app.order_history.back().unwrap().status, "Cancelled");
+    }
 
-    let updates_para = Paragraph::new(full_text)
-        .block(Block::default().borders(Borders::ALL).title("Real-time Status & Updates"))
-        .style(Style::default().fg(status_color))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_polymarket_submit_goes_through_exchange_client() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/order"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(
+                    r#"{"success":true,"errorMsg":"","orderID":"0x3a7b5f2d1c","status":"live"}"#,
+                ))
+                .expect(1)
+                .mount(&server)
+                .await;
+            // Exactly enough USDC for 10 shares at 0.51
+            Mock::given(method("GET"))
+                .and(path("/balance-allowance"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"balance":"5100000","allowance":"5100000"}"#))
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let mut app = App::new();
+        let mut client = PolymarketClobClient::new(
+            server.uri(),
+            "test_private_key".to_string(),
+            137,
+            PolymarketSignatureType::EMAIL_MAGIC,
+            Some("0xTestProxyAddress".to_string()),
+        );
+        client.set_api_credentials(client.create_or_derive_api_credentials());
+        app.polymarket_client = Some(client);
+        let history_len = app.order_history.len();
+        assert!(app.exchange_for(OrderVenue::Polymarket).is_some());
+        assert!(app.exchange_for(OrderVenue::Local).is_none());
+
+        app.order_input.venue = OrderVenue::Polymarket;
+        app.order_input.token_id = "123".to_string();
+        app.order_input.price = "0.506".to_string();
+        app.order_input.quantity = "10".to_string();
+        app.submit_order();
+
+        // The order is placed in the background; the UI carries on meanwhile
+        // and a second submit waits for the first
+        assert!(app.order_in_flight);
+        assert_eq!(app.order_history.len(), history_len);
+        app.submit_order();
+        assert_eq!(app.real_time_data.back().unwrap(), "⏳ Still placing the previous order");
+        app.wait_for_requests(Duration::from_secs(5));
+        assert!(!app.order_in_flight);
+        assert_eq!(app.order_history.len(), history_len + 1);
+        let record = app.order_history.back().unwrap();
+        assert_eq!(record.venue, OrderVenue::Polymarket);
+        assert_eq!(record.price, 0.51);
+        assert_eq!(record.order_id, "0x3a7b5f2d1c");
+        assert!(app.order_input.price.is_empty());
+
+        // Below the market's minimum size: rejected with the CLOB code, input kept
+        app.order_input.price = "0.5".to_string();
+        app.order_input.quantity = "1".to_string();
+        app.submit_order();
+        app.wait_for_requests(Duration::from_secs(5));
+        assert_eq!(app.order_history.len(), history_len + 2);
+        let record = app.order_history.back().unwrap();
+        assert_eq!(record.status, "Rejected");
+        assert!(record.reason.as_deref().unwrap().contains("INVALID_ORDER_MIN_SIZE"));
+        assert!(app.real_time_data.back().unwrap().contains("INVALID_ORDER_MIN_SIZE"));
+        assert_eq!(app.order_input.quantity, "1");
+
+        // More than the account holds: blocked before signing, never posted
+        app.order_input.price = "0.51".to_string();
+        app.order_input.quantity = "10.01".to_string();
+        app.submit_order();
+        app.wait_for_requests(Duration::from_secs(5));
+        assert_eq!(app.order_history.len(), history_len + 2);
+        let message = app.real_time_data.back().unwrap();
+        assert!(message.contains("Polymarket order blocked: Not enough USDC"), "{}", message);
+        assert!(message.contains("0.005100 short"), "{}", message);
+    }
 
-    f.render_widget(updates_para, chunks[3]);
-}
+    #[test]
+    fn test_order_updates_drive_binance_status() {
+        let mut app = App::new();
+        app.order_history.clear();
+        let mut record = filled_record("BTC", OrderSide::Bid, 50000.0, 1.0);
+        record.venue = OrderVenue::Binance;
+        record.status = "Pending".to_string();
+        record.order_id = "4293153".to_string();
+        app.order_history.push_back(record);
+
+        for (status, filled, label) in [
+            ("NEW", "0.00000000", "Pending"),
+            ("PARTIALLY_FILLED", "0.40000000", "Partially Filled"),
+            ("FILLED", "1.00000000", "Filled"),
+        ] {
+            let report = format!(
+                r#"{{"e":"executionReport","E":1,"s":"BTCUSDT","c":"abc","S":"BUY","o":"LIMIT","q":"1.00000000",
+                    "p":"50000.00","x":"TRADE","X":"{}","r":"NONE","i":4293153,"l":"0.0","z":"{}","L":"50000.00","T":1}}"#,
+                status, filled
+            );
+            let Some(UserDataEvent::Order(update)) = crate::binance_user_data::parse_user_data_event(&report).unwrap() else {
+                panic!("expected an order update");
+            };
+            app.apply_order_update(&update);
+            assert_eq!(app.order_history[0].status, label);
+        }
+    }
 
-fn draw_alerts_panel(f: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Header
-            Constraint::Min(0),     // Alerts list
-        ])
-        .split(area);
+    #[test]
+    fn test_order_books_kept_per_coin() {
+        let mut app = App::new();
+        app.select_coin_by_index(0);
+        let btc_order = app.order_book.add_order(OrderSide::Bid, 1.0, 42.0, 1);
+        let btc_orders = app.order_book.get_total_orders();
+
+        app.select_coin_by_index(1);
+        assert_eq!(app.current_market, "ETH");
+        assert!(app.order_book.get_level_orders(OrderSide::Bid, 1.0).is_empty());
+        assert!(app.order_book.get_total_orders() > 0);
+        let eth_order = app.order_book.add_order(OrderSide::Ask, 1_000_000.0, 1.0, 2);
+        assert_eq!(app.order_book_for("BTC").unwrap().get_total_orders(), btc_orders);
+
+        app.next_coin();
+        app.previous_coin();
+        app.previous_coin();
+        assert_eq!(app.current_market, "BTC");
+        assert_eq!(app.order_book.get_order(btc_order).map(|o| o.quantity), Some(42.0));
+        assert_eq!(app.order_book.get_total_orders(), btc_orders);
+        assert_eq!(app.order_book_for("ETH").unwrap().get_order(eth_order).map(|o| o.price.as_f64()), Some(1_000_000.0));
+    }
 
-    // Header
-    let header = Paragraph::new(format!(
-        "🔔 Price Alerts - Active: {} | Triggered: {}",
-        app.get_active_alerts_count(),
-        app.get_triggered_alerts_count()
-    ))
-    .block(Block::default().borders(Borders::ALL))
-    .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-    .alignment(ratatui::layout::Alignment::Center);
+    #[test]
+    fn test_coin_add_and_remove_commands() {
+        let mut app = App::with_seed(7);
+        app.commands.clone().execute(&mut app, "coin add ada").unwrap();
+        let symbols = |app: &App| app.available_coins.iter().map(|coin| coin.symbol.clone()).collect::<Vec<_>>();
+        assert_eq!(symbols(&app), ["BTC", "ETH", "SOL", "ADA"]);
+        let ada = &app.available_coins[3];
+        assert!(ada.price > 0.0 && ada.volume_24h > 0.0);
+        assert_eq!(app.order_book_for("ADA").map(|book| book.get_total_orders()), Some(0));
+
+        assert_eq!(app.add_coin("ADA").unwrap_err(), "ADA is already in the coin list");
+        assert!(app.add_coin("not-a-coin").is_err());
+        assert_eq!(app.available_coins.len(), 4);
+
+        // Switching to the added coin seeds its book like any other
+        app.commands.clone().execute(&mut app, "coin ada").unwrap();
+        assert_eq!(app.current_market, "ADA");
+        assert!(app.order_book.get_total_orders() > 0);
+
+        // Removing a coin before the selected one keeps the selection on ADA
+        app.remove_coin("eth").unwrap();
+        assert_eq!(symbols(&app), ["BTC", "SOL", "ADA"]);
+        assert_eq!(app.selected_coin_index, 2);
+        assert_eq!(app.current_market, "ADA");
+        assert!(app.order_book_for("ETH").is_none());
+        assert_eq!(app.remove_coin("ETH").unwrap_err(), "Unknown coin: ETH");
+    }
 
-    f.render_widget(header, chunks[0]);
+    #[test]
+    fn test_export_command_writes_csv() {
+        let dir = temp_alert_file("export").with_extension("");
+        std::fs::create_dir_all(dir.join("my exports")).unwrap();
+        let path = dir.join("my exports").join("alerts.csv");
+        let _ = std::fs::remove_file(&path);
+        let mut app = App::with_seed(7);
+        app.add_price_alert("BTC".to_string(), AlertType::PriceAbove(50000.0), "BTC up".to_string());
+
+        let command = format!("export alerts \"{}\"", path.display());
+        app.commands.clone().execute(&mut app, &command).unwrap();
+        assert_eq!(
+            app.real_time_data.back().unwrap(),
+            &format!("📤 Exported 1 alerts rows to {}", path.display())
+        );
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert!(csv.starts_with("id,symbol,type,value"), "{}", csv);
+        assert_eq!(csv.lines().count(), 2);
+
+        // An existing file is kept unless --force is given
+        app.commands.clone().execute(&mut app, &command.replacen("alerts", "depth", 1)).unwrap();
+        assert!(app.real_time_data.back().unwrap().ends_with("add --force to overwrite it"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), csv);
+        app.commands.clone().execute(&mut app, &format!("{} --force", command.replacen("alerts", "depth", 1))).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with("side,price,quantity"));
+
+        app.commands.clone().execute(&mut app, "export candles x.csv").unwrap();
+        assert_eq!(
+            app.real_time_data.back().unwrap(),
+            "Can't export 'candles': choose orders, trades, alerts, depth"
+        );
+        std::fs::remove_dir_all(dir.join("my exports")).unwrap();
+    }
 
-    // Alerts list
-    if app.price_alerts.is_empty() {
-        let content = "No price alerts configured.\n\nUse the command line to create alerts:\n• alert above 27000 - Alert when price goes above $27,000\n• alert below 26000 - Alert when price goes below $26,000\n• alert change 5 - Alert on 5% price change\n• alert volume 1000000 - Alert on volume spike above 1M";
-        
-        let paragraph = Paragraph::new(content)
-            .block(Block::default().borders(Borders::ALL).title("Create Alerts"))
-            .wrap(Wrap { trim: true });
-        
-        f.render_widget(paragraph, chunks[1]);
-    } else {
-        let mut rows = Vec::new();
-        rows.push(Row::new(vec!["ID", "Symbol", "Type", "Target", "Status", "Created", "Triggered"]));
+    #[test]
+    fn test_removing_the_selected_coin_selects_a_neighbor() {
+        let mut app = App::with_seed(7);
+        app.add_coin("DOT").unwrap();
+        app.select_coin_by_index(1);
+
+        // The coin after it takes its place...
+        app.commands.clone().execute(&mut app, "coin remove ETH").unwrap();
+        assert_eq!(app.selected_coin_index, 1);
+        assert_eq!(app.current_market, "SOL");
+        assert!(app.order_book.get_total_orders() > 0);
+        assert!(!app.order_books.contains_key("ETH"));
+        assert!(!app.order_books.contains_key("SOL"));
+
+        // ...unless it was last, when the one before does
+        app.select_coin_by_index(2);
+        app.remove_coin("DOT").unwrap();
+        assert_eq!(app.selected_coin_index, 1);
+        assert_eq!(app.current_market, "SOL");
+        assert!(!app.order_books.contains_key("DOT"));
+
+        app.remove_coin("BTC").unwrap();
+        assert_eq!(app.selected_coin_index, 0);
+        assert_eq!(app.available_coins[app.selected_coin_index].symbol, "SOL");
+        assert_eq!(app.remove_coin("SOL").unwrap_err(), "Can't remove the only coin");
+    }
 
-        for alert in &app.price_alerts {
-            let alert_type_str = match &alert.alert_type {
-                AlertType::PriceAbove(price) => format!("Above ${:.2}", price),
-                AlertType::PriceBelow(price) => format!("Below ${:.2}", price),
-                AlertType::PercentageChange(percent) => format!("{}% Change", percent),
-                AlertType::VolumeSpike(volume) => format!("Volume > {:.0}", volume),
-                AlertType::PriceCross(price) => format!("Cross ${:.2}", price),
-            };
-            
-            let status = if alert.is_active { "🟢 Active" } else { "🔴 Inactive" };
-            let created = alert.created_at.format("%H:%M").to_string();
-            let triggered = alert.triggered_at
-                .map(|t| t.format("%H:%M").to_string())
-                .unwrap_or_else(|| "Never".to_string());
-            
-            rows.push(Row::new(vec![
-                alert.id.to_string(),
-                alert.symbol.clone(),
-                alert_type_str,
-                alert.message.clone(),
-                status.to_string(),
-                created,
-                triggered,
-            ]));
+    #[test]
+    fn test_selection_past_the_coin_list_does_not_panic() {
+        let mut app = App::with_seed(7);
+        app.selected_coin_index = app.available_coins.len();
+        assert!(app.selected_coin().is_none());
+        assert_eq!(app.selected_symbol(), "");
+        assert!(app.order_book_for("BTC").is_none());
+        app.update_market_data_for_selected_coin();
+        app.refresh_order_book();
+
+        let backend = ratatui::backend::TestBackend::new(120, 40);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        for tab in 0..app.tabs.len() {
+            app.selected_tab = tab;
+            terminal.draw(|f| draw_ui(f, &mut app)).unwrap();
         }
 
-        let widths = [
-            Constraint::Length(5),
-            Constraint::Length(8),
-            Constraint::Length(15),
-            Constraint::Min(20),
-            Constraint::Length(12),
-            Constraint::Length(8),
-            Constraint::Length(10),
-        ];
+        assert!(app.select_coin_by_symbol("ethusdt"));
+        assert_eq!(app.selected_symbol(), "ETH");
+        assert!(!app.select_coin_by_symbol("USDT"));
+    }
 
-        let table = Table::new(rows, widths)
-            .block(Block::default().borders(Borders::ALL).title("Price Alerts"))
-            .style(Style::default().fg(Color::White));
+    #[test]
+    fn test_logs_tab_filters_captured_lines() {
+        use crate::logging::CaptureLayer;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let mut app = App::with_seed(7);
+        let subscriber = tracing_subscriber::registry().with(CaptureLayer::new(app.log_buffer.clone(), LOG_PANEL_LEVEL));
+        tracing::subscriber::with_default(subscriber, || {
+            app.order_input.venue = OrderVenue::Local;
+            app.order_input.quantity = "not a number".to_string();
+            app.submit_order();
+            tracing::error!("Binance market stream failed");
+        });
+        assert_eq!(app.log_buffer.lines(app.log_filter).len(), 2);
+        assert!(app.log_buffer.lines(app.log_filter)[0].message.starts_with("order rejected venue=Local"));
+
+        app.set_log_filter(true);
+        let errors = app.log_buffer.lines(app.log_filter);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Binance market stream failed");
+        app.set_log_filter(false);
+        assert_eq!(app.log_filter, tracing::Level::WARN);
+
+        let backend = ratatui::backend::TestBackend::new(120, 40);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        app.selected_tab = 8;
+        terminal.draw(|f| draw_ui(f, &mut app)).unwrap();
+    }
 
-        f.render_widget(table, chunks[1]);
+    fn filled_record(symbol: &str, side: OrderSide, price: f64, quantity: f64) -> OrderRecord {
+        OrderRecord {
+            timestamp: chrono::Utc::now(),
+            venue: OrderVenue::Local,
+            symbol: symbol.to_string(),
+            side,
+            price,
+            quantity,
+            status: "Filled".to_string(),
+            order_id: String::new(),
+            reason: None,
+        }
     }
-}
 
-fn draw_websocket_status(f: &mut Frame, app: &App, area: Rect) {
-    let mut content = String::new();
-    
-    content.push_str("🌐 Binance WebSocket Status\n\n");
-    
-    // Connection status
-    let status_icon = if app.binance_ws.is_connected { "🟢" } else { "🔴" };
-    content.push_str(&format!("Status: {} {}\n", status_icon, app.binance_ws.connection_status));
-    
-    // Data mode
-    let mode_icon = if app.use_real_data { "📡" } else { "🎭" };
-    content.push_str(&format!("Data Mode: {} {}\n", mode_icon, 
-        if app.use_real_data { "Real Binance Data" } else { "Simulated Data" }));
-    
-    // Statistics
-    content.push_str(&format!("Messages Received: {}\n", app.binance_ws.message_count));
-    content.push_str(&format!("Errors: {}\n", app.binance_ws.error_count));
-    content.push_str(&format!("Last Message: {}\n", 
-        app.binance_ws.last_message.format("%H:%M:%S")));
-    
-    // Connection info
-    content.push_str("\n📊 Connection Info:\n");
-    content.push_str(&format!("• Market: {}\n", app.current_market));
-    content.push_str(&format!("• Timeframe: {}\n", app.selected_timeframe.as_str()));
-    content.push_str(&format!("• Auto-refresh: {}\n", 
-        if app.auto_refresh { "ON" } else { "OFF" }));
-    
-    // Controls
-    content.push_str("\n🎮 Controls:\n");
-    content.push_str("• r/R: Toggle real/simulated data\n");
-    content.push_str("• b/B: Simulate Binance connection\n");
-    content.push_str("• < >: Change chart timeframe\n");
-    content.push_str("• l/L: Toggle auto-refresh\n");
-    
-    // Alert info
-    content.push_str(&format!("\n🔔 Alerts: {} active, {} triggered\n", 
-        app.get_active_alerts_count(), app.get_triggered_alerts_count()));
-    
-    // Real-time updates
-    if !app.real_time_data.is_empty() {
-        content.push_str("\n📡 Recent Updates:\n");
-        for update in app.real_time_data.iter().take(5) {
-            content.push_str(&format!("• {}\n", update));
+    #[test]
+    fn test_orders_selection_clamps_and_follows_order_id() {
+        let mut app = App::new();
+        app.order_history.clear();
+        app.move_orders_cursor_down();
+        assert_eq!(app.selected_order_id, None);
+
+        for id in 1..=25 {
+            let mut record = filled_record("BTCUSDT", OrderSide::Bid, 100.0, 1.0);
+            record.order_id = id.to_string();
+            app.order_history.push_back(record);
         }
+
+        // Starts at the newest row and stops at the top
+        app.move_orders_cursor_up();
+        app.move_orders_cursor_up();
+        assert_eq!(app.selected_order_id.as_deref(), Some("25"));
+        assert_eq!((app.selected_order_row(), app.orders_scroll), (Some(0), 0));
+
+        // A page down lands just off the first page, which scrolls by one
+        app.move_orders_selection(ORDER_HISTORY_ROWS as isize);
+        assert_eq!(app.selected_order_id.as_deref(), Some("15"));
+        assert_eq!(app.orders_scroll, 1);
+
+        app.move_orders_selection(100);
+        assert_eq!(app.selected_order_id.as_deref(), Some("1"));
+        assert_eq!((app.selected_order_row(), app.orders_scroll), (Some(24), 15));
+        app.move_orders_cursor_down();
+        assert_eq!(app.selected_order_row(), Some(24));
+
+        // New rows arrive on top; the selection stays on the same order
+        let mut newest = filled_record("BTCUSDT", OrderSide::Ask, 101.0, 1.0);
+        newest.order_id = "26".to_string();
+        app.order_history.push_back(newest);
+        assert_eq!(app.selected_order_row(), Some(25));
+
+        app.move_orders_selection(-100);
+        assert_eq!(app.selected_order_id.as_deref(), Some("26"));
+        assert_eq!(app.orders_scroll, 0);
     }
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("WebSocket & Real-time Status"))
-        .wrap(Wrap { trim: true });
+    #[test]
+    fn test_book_scroll_clamps_to_deepest_page() {
+        let mut app = App::new();
+        app.order_book = OrderBook::new();
+        for level in 0..30 {
+            app.order_book.add_order(OrderSide::Bid, 100.0 - level as f64, 1.0, 1);
+        }
+        for level in 0..25 {
+            app.order_book.add_order(OrderSide::Ask, 101.0 + level as f64, 1.0, 1);
+        }
 
-    f.render_widget(paragraph, area);
+        app.scroll_book(-1);
+        assert_eq!(app.book_scroll, 0);
+        app.scroll_book(5);
+        let (bids, asks) = app.visible_book_depth(&app.order_book);
+        assert_eq!((bids[0].0, asks[0].0), (95.0, 106.0));
+        assert_eq!((bids.len(), asks.len()), (BOOK_DEPTH_LEVELS, BOOK_DEPTH_LEVELS));
+
+        // The 30-level bid side bottoms out at offset 10; the cursor is
+        // pulled back onto the 15 asks and 20 bids still shown
+        app.book_cursor = Some(39);
+        app.scroll_book(BOOK_DEPTH_LEVELS as isize * 5);
+        assert_eq!(app.book_scroll, 10);
+        assert_eq!(app.book_cursor, Some(34));
+        let (bids, asks) = app.visible_book_depth(&app.order_book);
+        assert_eq!((bids.len(), asks.len()), (20, 15));
+        assert_eq!(app.selected_book_level(), Some((OrderSide::Bid, 71.0)));
+
+        // A thinner book is shown from its deepest full page
+        app.order_book.set_level_quantity(OrderSide::Bid, 71.0, 0.0, 2);
+        assert_eq!(app.visible_book_depth(&app.order_book).0[0].0, 91.0);
+
+        app.scroll_book(-100);
+        assert_eq!(app.book_scroll, 0);
+    }
+
+    #[test]
+    fn test_compute_pnl_fifo() {
+        let mut app = App::new();
+        app.current_market = "BTC".to_string();
+        app.order_history.clear();
+        app.order_history.extend([
+            filled_record("BTC", OrderSide::Bid, 100.0, 1.0),
+            filled_record("BTC", OrderSide::Bid, 110.0, 2.0),
+            // Closes the 100 lot and one unit of the 110 lot: 1*(120-100) + 1*(120-110)
+            filled_record("BTC", OrderSide::Ask, 120.0, 2.0),
+            filled_record("ETH", OrderSide::Ask, 5000.0, 1.0),
+        ]);
+        let mut pending = filled_record("BTC", OrderSide::Ask, 130.0, 5.0);
+        pending.status = "Resting".to_string();
+        app.order_history.push_back(pending);
+
+        let pnl = app.compute_pnl();
+        assert_eq!(pnl.symbol, "BTC");
+        assert!((pnl.realized_pnl - 30.0).abs() < 1e-9);
+        assert!((pnl.position - 1.0).abs() < 1e-9);
+        assert_eq!(pnl.average_entry, Some(110.0));
+
+        // Selling through the remaining long flips the position short
+        app.order_history.push_back(filled_record("BTC", OrderSide::Ask, 105.0, 3.0));
+        let pnl = app.compute_pnl();
+        assert!((pnl.realized_pnl - 25.0).abs() < 1e-9);
+        assert!((pnl.position + 2.0).abs() < 1e-9);
+        assert_eq!(pnl.average_entry, Some(105.0));
+
+        app.order_history.push_back(filled_record("BTC", OrderSide::Bid, 100.0, 2.0));
+        let pnl = app.compute_pnl();
+        assert!((pnl.realized_pnl - 35.0).abs() < 1e-9);
+        assert_eq!(pnl.position, 0.0);
+        assert_eq!(pnl.average_entry, None);
+    }
 }
@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+/// Mid prices kept per symbol for the Watchlist sparklines.
+pub const SPARKLINE_SAMPLES: usize = 30;
+
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Recent mid prices of one symbol, oldest first, at most `capacity`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidHistory {
+    samples: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl MidHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self { samples: VecDeque::with_capacity(capacity), capacity: capacity.max(1) }
+    }
+
+    /// Records a mid, dropping the oldest past the capacity.
+    pub fn push(&mut self, mid: f64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(mid);
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = f64> + '_ {
+        self.samples.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// One bar per sample, scaled between the lowest and highest. A flat
+    /// history sits on the middle bar.
+    pub fn sparkline(&self) -> String {
+        let low = self.samples().fold(f64::INFINITY, f64::min);
+        let high = self.samples().fold(f64::NEG_INFINITY, f64::max);
+        self.samples()
+            .map(|mid| {
+                let level = if high > low {
+                    ((mid - low) / (high - low) * (SPARK_BARS.len() - 1) as f64).round() as usize
+                } else {
+                    SPARK_BARS.len() / 2 - 1
+                };
+                SPARK_BARS[level]
+            })
+            .collect()
+    }
+}
+
+impl Default for MidHistory {
+    fn default() -> Self {
+        Self::new(SPARKLINE_SAMPLES)
+    }
+}
+
+/// One Watchlist row: a symbol's top of book and 24h move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchlistRow {
+    pub symbol: String,
+    pub last_price: f64,
+    pub change_percent: f64,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+}
+
+impl WatchlistRow {
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask? - self.best_bid?)
+    }
+}
+
+/// The column the Watchlist is ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WatchlistSort {
+    /// The coin list's own order.
+    #[default]
+    Listed,
+    Symbol,
+    /// Highest first.
+    LastPrice,
+    /// Biggest gain first.
+    Change,
+    /// Tightest first; rows without a two-sided book go last.
+    Spread,
+}
+
+impl WatchlistSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Listed => Self::Symbol,
+            Self::Symbol => Self::LastPrice,
+            Self::LastPrice => Self::Change,
+            Self::Change => Self::Spread,
+            Self::Spread => Self::Listed,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Listed => "listed",
+            Self::Symbol => "symbol",
+            Self::LastPrice => "price",
+            Self::Change => "24h change",
+            Self::Spread => "spread",
+        }
+    }
+
+    /// Orders `rows` by this column, keeping the given order among ties.
+    pub fn sort(self, rows: &mut [WatchlistRow]) {
+        let descending = |a: f64, b: f64| b.partial_cmp(&a).unwrap_or(Ordering::Equal);
+        match self {
+            Self::Listed => {}
+            Self::Symbol => rows.sort_by(|a, b| a.symbol.cmp(&b.symbol)),
+            Self::LastPrice => rows.sort_by(|a, b| descending(a.last_price, b.last_price)),
+            Self::Change => rows.sort_by(|a, b| descending(a.change_percent, b.change_percent)),
+            Self::Spread => rows.sort_by(|a, b| match (a.spread(), b.spread()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(symbol: &str, last_price: f64, change_percent: f64, book: Option<(f64, f64)>) -> WatchlistRow {
+        WatchlistRow {
+            symbol: symbol.to_string(),
+            last_price,
+            change_percent,
+            best_bid: book.map(|(bid, _)| bid),
+            best_ask: book.map(|(_, ask)| ask),
+        }
+    }
+
+    fn symbols(rows: &[WatchlistRow]) -> Vec<&str> {
+        rows.iter().map(|row| row.symbol.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_by_each_column() {
+        let listed = vec![
+            row("SOL", 150.0, -2.0, Some((149.9, 150.1))),
+            row("BTC", 65000.0, 1.5, Some((64999.0, 65001.0))),
+            row("ADA", 0.45, 4.0, None),
+            row("ETH", 3200.0, 1.5, Some((3199.95, 3200.0))),
+        ];
+        let sorted = |sort: WatchlistSort| {
+            let mut rows = listed.clone();
+            sort.sort(&mut rows);
+            rows
+        };
+        assert_eq!(symbols(&sorted(WatchlistSort::Listed)), ["SOL", "BTC", "ADA", "ETH"]);
+        assert_eq!(symbols(&sorted(WatchlistSort::Symbol)), ["ADA", "BTC", "ETH", "SOL"]);
+        assert_eq!(symbols(&sorted(WatchlistSort::LastPrice)), ["BTC", "ETH", "SOL", "ADA"]);
+        // BTC and ETH tie on change and keep their listed order
+        assert_eq!(symbols(&sorted(WatchlistSort::Change)), ["ADA", "BTC", "ETH", "SOL"]);
+        assert_eq!(symbols(&sorted(WatchlistSort::Spread)), ["ETH", "SOL", "BTC", "ADA"]);
+
+        let mut sort = WatchlistSort::default();
+        for _ in 0..5 {
+            sort = sort.next();
+        }
+        assert_eq!(sort, WatchlistSort::Listed);
+        assert_eq!(WatchlistSort::Change.label(), "24h change");
+    }
+
+    #[test]
+    fn test_mid_history_keeps_the_latest_samples() {
+        let mut history = MidHistory::new(4);
+        assert!(history.is_empty());
+        assert_eq!(history.sparkline(), "");
+        for mid in [100.0, 101.0, 102.0, 103.0, 104.0, 98.0] {
+            history.push(mid);
+        }
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.samples().collect::<Vec<_>>(), vec![102.0, 103.0, 104.0, 98.0]);
+        assert_eq!(history.sparkline(), "▆▇█▁");
+
+        let mut flat = MidHistory::default();
+        flat.push(5.0);
+        flat.push(5.0);
+        assert_eq!(flat.sparkline(), "▄▄");
+        for _ in 0..SPARKLINE_SAMPLES {
+            flat.push(5.0);
+        }
+        assert_eq!(flat.len(), SPARKLINE_SAMPLES);
+    }
+}